@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::task::Poll;
 
 use aws_config::Region;
@@ -5,6 +6,7 @@ use aws_credential_types::Credentials;
 use aws_sdk_s3::error::SdkError;
 use aws_sdk_s3::operation::delete_object::DeleteObjectError;
 use aws_sdk_s3::operation::get_object::{GetObjectError, GetObjectOutput};
+use aws_sdk_s3::operation::head_object::{HeadObjectError, HeadObjectOutput};
 use aws_sdk_s3::operation::put_object::PutObjectError;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::ObjectCannedAcl;
@@ -110,6 +112,31 @@ impl Bucket {
 		Ok(resp)
 	}
 
+	/// Gets the bytes of an object in the range, which includes its end like
+	/// the range header of the request.
+	pub async fn get_object_range(
+		&self,
+		key: &str,
+		range: RangeInclusive<u64>,
+	) -> Result<GetObjectOutput, SdkError<GetObjectError>> {
+		let resp = self
+			.client
+			.get_object()
+			.bucket(self.name())
+			.key(key)
+			.range(format!("bytes={}-{}", range.start(), range.end()))
+			.send()
+			.await?;
+
+		Ok(resp)
+	}
+
+	pub async fn head_object(&self, key: &str) -> Result<HeadObjectOutput, SdkError<HeadObjectError>> {
+		let resp = self.client.head_object().bucket(self.name()).key(key).send().await?;
+
+		Ok(resp)
+	}
+
 	pub async fn put_object(
 		&self,
 		key: impl Into<String>,
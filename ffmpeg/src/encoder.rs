@@ -545,6 +545,17 @@ impl Encoder {
 		}
 	}
 
+	/// Changes the rate control of the open encoder. Encoders which support
+	/// reconfiguration, like libx264, apply it from the next frame, others
+	/// keep the rate they were opened with.
+	pub fn set_bitrate(&mut self, bitrate: i64, rc_max_rate: i64, rc_buffer_size: i32) {
+		let encoder = self.encoder.as_deref_mut_except();
+
+		encoder.bit_rate = bitrate;
+		encoder.rc_max_rate = rc_max_rate;
+		encoder.rc_buffer_size = rc_buffer_size;
+	}
+
 	pub fn stream_index(&self) -> i32 {
 		self.stream_index
 	}
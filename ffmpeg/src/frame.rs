@@ -1,5 +1,6 @@
 use ffmpeg_sys_next::*;
 
+use crate::dict::Dictionary;
 use crate::error::FfmpegError;
 use crate::smart_object::SmartPtr;
 use crate::utils::check_i64;
@@ -111,6 +112,27 @@ impl Frame {
 	pub fn linesize(&self, index: usize) -> Option<i32> {
 		self.0.as_deref_except().linesize.get(index).copied()
 	}
+
+	/// A copy of the metadata filters attached to the frame.
+	pub fn metadata(&self) -> Dictionary {
+		// Safety: the dictionary is owned by the frame, and is copied before the
+		// borrow of the frame ends.
+		unsafe { Dictionary::from_ptr(self.0.as_deref_except().metadata) }.clone()
+	}
+
+	/// The side data of the given type attached to the frame.
+	pub fn side_data(&self, ty: AVFrameSideDataType) -> Option<&[u8]> {
+		// Safety: the side data is owned by the frame, and lives as long as the
+		// borrow of the frame.
+		unsafe {
+			let side_data = av_frame_get_side_data(self.as_ptr(), ty);
+			if side_data.is_null() || (*side_data).data.is_null() {
+				return None;
+			}
+
+			Some(std::slice::from_raw_parts((*side_data).data, (*side_data).size))
+		}
+	}
 }
 
 impl std::fmt::Debug for Frame {
@@ -129,6 +151,30 @@ impl std::fmt::Debug for Frame {
 }
 
 impl VideoFrame {
+	/// Allocates a frame with its own buffers for the given size and pixel
+	/// format.
+	pub fn alloc(width: i32, height: i32, pixel_format: AVPixelFormat) -> Result<Self, FfmpegError> {
+		let mut frame = Frame::new()?;
+
+		unsafe {
+			// Safety: `frame` is a valid pointer
+			let frame_mut = frame.as_mut_ptr().as_mut().unwrap();
+
+			frame_mut.width = width;
+			frame_mut.height = height;
+			frame_mut.format = pixel_format as i32;
+
+			// Safety: `av_frame_get_buffer` is safe to call, and the pointer returned is
+			// valid.
+			match av_frame_get_buffer(frame_mut, 32) {
+				0 => {}
+				err => return Err(FfmpegError::Code(err.into())),
+			}
+		}
+
+		Ok(frame.video())
+	}
+
 	pub fn width(&self) -> usize {
 		self.0.0.as_deref_except().width as usize
 	}
@@ -175,6 +221,22 @@ impl VideoFrame {
 				.map(|ptr| std::slice::from_raw_parts(*ptr, self.linesize(index).unwrap() as usize * self.height()))
 		}
 	}
+
+	pub fn data_mut(&mut self, index: usize) -> Option<&mut [u8]> {
+		let len = self.linesize(index)? as usize * self.height();
+
+		// Safety: the plane is `linesize * height` bytes long, and the frame is
+		// borrowed mutably for the lifetime of the slice.
+		unsafe {
+			self.0
+				.0
+				.as_deref_mut_except()
+				.data
+				.get(index)
+				.filter(|ptr| !ptr.is_null())
+				.map(|ptr| std::slice::from_raw_parts_mut(*ptr, len))
+		}
+	}
 }
 
 impl std::fmt::Debug for VideoFrame {
@@ -211,6 +273,43 @@ impl std::ops::DerefMut for VideoFrame {
 }
 
 impl AudioFrame {
+	/// Allocates a frame with its own buffers for the given number of samples
+	/// and audio format.
+	pub fn alloc(
+		nb_samples: i32,
+		sample_format: AVSampleFormat,
+		channel_layout: u64,
+		sample_rate: i32,
+	) -> Result<Self, FfmpegError> {
+		let mut frame = Frame::new()?;
+
+		unsafe {
+			// Safety: `frame` is a valid pointer
+			let frame_mut = frame.as_mut_ptr().as_mut().unwrap();
+
+			frame_mut.nb_samples = nb_samples;
+			frame_mut.format = sample_format as i32;
+			frame_mut.sample_rate = sample_rate;
+			frame_mut.channel_layout = channel_layout;
+
+			// Safety: `av_channel_layout_from_mask` is safe to call, and the layout is
+			// owned by the frame.
+			match av_channel_layout_from_mask(&mut frame_mut.ch_layout, channel_layout) {
+				0 => {}
+				err => return Err(FfmpegError::Code(err.into())),
+			}
+
+			// Safety: `av_frame_get_buffer` is safe to call, and the pointer returned is
+			// valid.
+			match av_frame_get_buffer(frame_mut, 0) {
+				0 => {}
+				err => return Err(FfmpegError::Code(err.into())),
+			}
+		}
+
+		Ok(frame.audio())
+	}
+
 	pub fn nb_samples(&self) -> i32 {
 		self.0.0.as_deref_except().nb_samples
 	}
@@ -234,6 +333,22 @@ impl AudioFrame {
 	pub fn set_channel_layout(&mut self, channel_layout: u64) {
 		self.0.0.as_deref_mut_except().channel_layout = channel_layout;
 	}
+
+	pub fn data_mut(&mut self, index: usize) -> Option<&mut [u8]> {
+		let len = self.linesize(index)? as usize;
+
+		// Safety: each plane of an audio frame is `linesize` bytes long, and the
+		// frame is borrowed mutably for the lifetime of the slice.
+		unsafe {
+			self.0
+				.0
+				.as_deref_mut_except()
+				.data
+				.get(index)
+				.filter(|ptr| !ptr.is_null())
+				.map(|ptr| std::slice::from_raw_parts_mut(*ptr, len))
+		}
+	}
 }
 
 impl std::fmt::Debug for AudioFrame {
@@ -337,6 +337,9 @@ impl<G: ApiGlobal> AuthMutation<G> {
 				recording_config_id: None,
 				visibility: pb::scuffle::video::v1::types::Visibility::Public as i32,
 				tags: Some(pb::scuffle::video::v1::types::Tags { tags: HashMap::new() }),
+				source_url: None,
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
 			})
 			.await
 			.map_err_gql("failed to create room")?;
@@ -50,6 +50,7 @@ impl ApiRequest<Stream> for tonic::Request<EventsFetchRequest> {
 				Target::Room => Resource::Room,
 				Target::S3Bucket => Resource::S3Bucket,
 				Target::TranscodingConfig => Resource::TranscodingConfig,
+				Target::IngestPolicy => Resource::IngestPolicy,
 			};
 
 			vec![AccessTokenScope {
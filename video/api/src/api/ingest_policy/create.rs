@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use pb::scuffle::video::v1::events_fetch_request::Target;
+use pb::scuffle::video::v1::types::access_token_scope::Permission;
+use pb::scuffle::video::v1::types::{event, Resource};
+use pb::scuffle::video::v1::{IngestPolicyCreateRequest, IngestPolicyCreateResponse};
+use tonic::Status;
+use ulid::Ulid;
+use video_common::database::{AccessToken, DatabaseTable};
+
+use super::utils::{
+	emit_modified, unset_organization_default, validate_audio_codecs, validate_limit, validate_video_codecs,
+};
+use crate::api::utils::tags::validate_tags;
+use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
+use crate::global::ApiGlobal;
+use crate::ratelimit::RateLimitResource;
+
+impl_request_scopes!(
+	IngestPolicyCreateRequest,
+	video_common::database::IngestPolicy,
+	(Resource::IngestPolicy, Permission::Create),
+	RateLimitResource::IngestPolicyCreate
+);
+
+pub fn validate(req: &IngestPolicyCreateRequest) -> tonic::Result<()> {
+	validate_tags(req.tags.as_ref())
+}
+
+pub fn build_query(
+	req: &IngestPolicyCreateRequest,
+	access_token: &AccessToken,
+) -> tonic::Result<utils::database::QueryBuilder<'static>> {
+	let mut qb = utils::database::QueryBuilder::default();
+
+	qb.push("INSERT INTO ")
+		.push(<IngestPolicyCreateRequest as TonicRequest>::Table::NAME)
+		.push(" (");
+
+	let mut seperated = qb.separated(",");
+
+	seperated.push("id");
+	seperated.push("organization_id");
+	seperated.push("max_bitrate");
+	seperated.push("max_width");
+	seperated.push("max_height");
+	seperated.push("max_fps");
+	seperated.push("allowed_video_codecs");
+	seperated.push("allowed_audio_codecs");
+	seperated.push("max_bytes_between_keyframes");
+	seperated.push("max_time_between_keyframes");
+	seperated.push("organization_default");
+	seperated.push("tags");
+
+	qb.push(") VALUES (");
+
+	let mut seperated = qb.separated(",");
+
+	seperated.push_bind(Ulid::new());
+	seperated.push_bind(access_token.organization_id);
+	seperated.push_bind(validate_limit("max_bitrate", req.max_bitrate)?);
+	seperated.push_bind(validate_limit("max_width", req.max_width)?);
+	seperated.push_bind(validate_limit("max_height", req.max_height)?);
+	seperated.push_bind(validate_limit("max_fps", req.max_fps)?);
+	seperated.push_bind(validate_video_codecs(&req.allowed_video_codecs)?);
+	seperated.push_bind(validate_audio_codecs(&req.allowed_audio_codecs)?);
+	seperated.push_bind(validate_limit(
+		"max_bytes_between_keyframes",
+		req.max_bytes_between_keyframes,
+	)?);
+	seperated.push_bind(validate_limit("max_time_between_keyframes", req.max_time_between_keyframes)?);
+	seperated.push_bind(req.organization_default);
+	seperated.push_bind(utils::database::Json(req.tags.clone().unwrap_or_default().tags));
+
+	qb.push(") RETURNING *");
+
+	Ok(qb)
+}
+
+impl ApiRequest<IngestPolicyCreateResponse> for tonic::Request<IngestPolicyCreateRequest> {
+	async fn process<G: ApiGlobal>(
+		&self,
+		global: &Arc<G>,
+		access_token: &AccessToken,
+	) -> tonic::Result<tonic::Response<IngestPolicyCreateResponse>> {
+		let req = self.get_ref();
+
+		validate(req)?;
+
+		let query = build_query(req, access_token)?;
+
+		let mut client = global.db().get().await.map_err(|err| {
+			tracing::error!(err = %err, "failed to get db client");
+			Status::internal("internal server error")
+		})?;
+
+		let tx = client.transaction().await.map_err(|err| {
+			tracing::error!(err = %err, "beginning transaction");
+			Status::internal("internal server error")
+		})?;
+
+		let replaced_defaults = if req.organization_default {
+			unset_organization_default(&tx, access_token.organization_id, None).await?
+		} else {
+			Vec::new()
+		};
+
+		let result: video_common::database::IngestPolicy = query.build_query_as().fetch_one(&tx).await.map_err(|err| {
+			tracing::error!(err = %err, "failed to create {}", <IngestPolicyCreateRequest as TonicRequest>::Table::FRIENDLY_NAME);
+			Status::internal(format!(
+				"failed to create {}",
+				<IngestPolicyCreateRequest as TonicRequest>::Table::FRIENDLY_NAME
+			))
+		})?;
+
+		tx.commit().await.map_err(|err| {
+			tracing::error!(err = %err, "committing transaction");
+			Status::internal("internal server error")
+		})?;
+
+		emit_modified(global, access_token.organization_id, replaced_defaults).await;
+
+		video_common::events::emit(
+			global.nats(),
+			&global.config().events.stream_name,
+			access_token.organization_id,
+			Target::IngestPolicy,
+			event::Event::IngestPolicy(event::IngestPolicy {
+				ingest_policy_id: Some(result.id.into()),
+				event: Some(event::ingest_policy::Event::Created(event::ingest_policy::Created {})),
+			}),
+		)
+		.await;
+
+		Ok(tonic::Response::new(IngestPolicyCreateResponse {
+			ingest_policy: Some(result.into_proto()),
+		}))
+	}
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use pb::scuffle::video::v1::types::access_token_scope::Permission;
+use pb::scuffle::video::v1::types::Resource;
+use pb::scuffle::video::v1::{IngestPolicyGetRequest, IngestPolicyGetResponse};
+use video_common::database::{AccessToken, DatabaseTable};
+
+use crate::api::utils::{get, impl_request_scopes, ApiRequest, TonicRequest};
+use crate::global::ApiGlobal;
+use crate::ratelimit::RateLimitResource;
+
+impl_request_scopes!(
+	IngestPolicyGetRequest,
+	video_common::database::IngestPolicy,
+	(Resource::IngestPolicy, Permission::Read),
+	RateLimitResource::IngestPolicyGet
+);
+
+pub fn build_query(
+	req: &IngestPolicyGetRequest,
+	access_token: &AccessToken,
+) -> tonic::Result<utils::database::QueryBuilder<'static>> {
+	let mut qb = utils::database::QueryBuilder::default();
+	qb.push("SELECT * FROM ")
+		.push(<IngestPolicyGetRequest as TonicRequest>::Table::NAME)
+		.push(" WHERE ");
+	let mut seperated = qb.separated(" AND ");
+
+	get::organization_id(&mut seperated, access_token.organization_id);
+	get::ids(&mut seperated, &req.ids);
+	get::search_options(&mut seperated, req.search_options.as_ref())?;
+
+	Ok(qb)
+}
+
+impl ApiRequest<IngestPolicyGetResponse> for tonic::Request<IngestPolicyGetRequest> {
+	async fn process<G: ApiGlobal>(
+		&self,
+		global: &Arc<G>,
+		access_token: &AccessToken,
+	) -> tonic::Result<tonic::Response<IngestPolicyGetResponse>> {
+		let req = self.get_ref();
+
+		let query = build_query(req, access_token)?;
+
+		let results = query.build_query_as().fetch_all(global.db()).await.map_err(|err| {
+			tracing::error!(err = %err, "failed to fetch ingest policies");
+			tonic::Status::internal("failed to fetch ingest policies")
+		})?;
+
+		Ok(tonic::Response::new(IngestPolicyGetResponse {
+			ingest_policies: results
+				.into_iter()
+				.map(video_common::database::IngestPolicy::into_proto)
+				.collect(),
+		}))
+	}
+}
@@ -0,0 +1,85 @@
+use pb::scuffle::video::v1::ingest_policy_server::{
+	IngestPolicy as IngestPolicyTrait, IngestPolicyServer as IngestPolicyService,
+};
+use pb::scuffle::video::v1::{
+	IngestPolicyCreateRequest, IngestPolicyCreateResponse, IngestPolicyDeleteRequest, IngestPolicyDeleteResponse,
+	IngestPolicyGetRequest, IngestPolicyGetResponse, IngestPolicyModifyRequest, IngestPolicyModifyResponse,
+	IngestPolicyTagRequest, IngestPolicyTagResponse, IngestPolicyUntagRequest, IngestPolicyUntagResponse,
+};
+use tonic::{async_trait, Request, Response};
+
+use super::utils::ratelimit::scope_ratelimit;
+use super::utils::ApiRequest;
+use crate::global::ApiGlobal;
+
+pub(crate) mod create;
+pub(crate) mod delete;
+pub(crate) mod get;
+pub(crate) mod modify;
+pub(crate) mod tag;
+pub(crate) mod untag;
+pub(crate) mod utils;
+
+pub struct IngestPolicyServer<G: ApiGlobal> {
+	_phantom: std::marker::PhantomData<G>,
+}
+
+impl<G: ApiGlobal> IngestPolicyServer<G> {
+	pub fn build() -> IngestPolicyService<Self> {
+		IngestPolicyService::new(Self::new())
+	}
+
+	pub(crate) const fn new() -> Self {
+		Self {
+			_phantom: std::marker::PhantomData,
+		}
+	}
+}
+
+#[async_trait]
+impl<G: ApiGlobal> IngestPolicyTrait for IngestPolicyServer<G> {
+	async fn get(&self, request: Request<IngestPolicyGetRequest>) -> tonic::Result<Response<IngestPolicyGetResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
+	async fn create(
+		&self,
+		request: Request<IngestPolicyCreateRequest>,
+	) -> tonic::Result<Response<IngestPolicyCreateResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
+	async fn modify(
+		&self,
+		request: Request<IngestPolicyModifyRequest>,
+	) -> tonic::Result<Response<IngestPolicyModifyResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
+	async fn delete(
+		&self,
+		request: Request<IngestPolicyDeleteRequest>,
+	) -> tonic::Result<Response<IngestPolicyDeleteResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
+	async fn tag(&self, request: Request<IngestPolicyTagRequest>) -> tonic::Result<Response<IngestPolicyTagResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
+	async fn untag(&self, request: Request<IngestPolicyUntagRequest>) -> tonic::Result<Response<IngestPolicyUntagResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+}
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use pb::ext::UlidExt;
+use pb::scuffle::video::v1::events_fetch_request::Target;
+use pb::scuffle::video::v1::types::access_token_scope::Permission;
+use pb::scuffle::video::v1::types::{event, Resource};
+use pb::scuffle::video::v1::{IngestPolicyModifyRequest, IngestPolicyModifyResponse};
+use tonic::Status;
+use video_common::database::{AccessToken, DatabaseTable};
+
+use super::utils::{
+	emit_modified, unset_organization_default, validate_audio_codecs, validate_modify_limit, validate_video_codecs,
+};
+use crate::api::errors::MODIFY_NO_FIELDS;
+use crate::api::utils::tags::validate_tags;
+use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
+use crate::global::ApiGlobal;
+use crate::ratelimit::RateLimitResource;
+
+impl_request_scopes!(
+	IngestPolicyModifyRequest,
+	video_common::database::IngestPolicy,
+	(Resource::IngestPolicy, Permission::Modify),
+	RateLimitResource::IngestPolicyModify
+);
+
+pub fn validate(req: &IngestPolicyModifyRequest) -> tonic::Result<()> {
+	validate_tags(req.tags.as_ref())
+}
+
+pub fn build_query<'a>(
+	req: &'a IngestPolicyModifyRequest,
+	access_token: &AccessToken,
+) -> tonic::Result<utils::database::QueryBuilder<'a>> {
+	let mut qb = utils::database::QueryBuilder::default();
+
+	qb.push("UPDATE ")
+		.push(<IngestPolicyModifyRequest as TonicRequest>::Table::NAME)
+		.push(" SET ");
+
+	let mut seperated = qb.separated(",");
+
+	if let Some(max_bitrate) = req.max_bitrate {
+		seperated
+			.push("max_bitrate = ")
+			.push_bind_unseparated(validate_modify_limit("max_bitrate", max_bitrate)?);
+	}
+
+	if let Some(max_width) = req.max_width {
+		seperated
+			.push("max_width = ")
+			.push_bind_unseparated(validate_modify_limit("max_width", max_width)?);
+	}
+
+	if let Some(max_height) = req.max_height {
+		seperated
+			.push("max_height = ")
+			.push_bind_unseparated(validate_modify_limit("max_height", max_height)?);
+	}
+
+	if let Some(max_fps) = req.max_fps {
+		seperated
+			.push("max_fps = ")
+			.push_bind_unseparated(validate_modify_limit("max_fps", max_fps)?);
+	}
+
+	if let Some(codecs) = &req.allowed_video_codecs {
+		seperated
+			.push("allowed_video_codecs = ")
+			.push_bind_unseparated(validate_video_codecs(&codecs.items)?);
+	}
+
+	if let Some(codecs) = &req.allowed_audio_codecs {
+		seperated
+			.push("allowed_audio_codecs = ")
+			.push_bind_unseparated(validate_audio_codecs(&codecs.items)?);
+	}
+
+	if let Some(max_bytes_between_keyframes) = req.max_bytes_between_keyframes {
+		seperated
+			.push("max_bytes_between_keyframes = ")
+			.push_bind_unseparated(validate_modify_limit(
+				"max_bytes_between_keyframes",
+				max_bytes_between_keyframes,
+			)?);
+	}
+
+	if let Some(max_time_between_keyframes) = req.max_time_between_keyframes {
+		seperated
+			.push("max_time_between_keyframes = ")
+			.push_bind_unseparated(validate_modify_limit(
+				"max_time_between_keyframes",
+				max_time_between_keyframes,
+			)?);
+	}
+
+	if let Some(organization_default) = req.organization_default {
+		seperated
+			.push("organization_default = ")
+			.push_bind_unseparated(organization_default);
+	}
+
+	if let Some(tags) = &req.tags {
+		seperated
+			.push("tags = ")
+			.push_bind_unseparated(utils::database::Json(&tags.tags));
+	}
+
+	if req.max_bitrate.is_none()
+		&& req.max_width.is_none()
+		&& req.max_height.is_none()
+		&& req.max_fps.is_none()
+		&& req.allowed_video_codecs.is_none()
+		&& req.allowed_audio_codecs.is_none()
+		&& req.max_bytes_between_keyframes.is_none()
+		&& req.max_time_between_keyframes.is_none()
+		&& req.organization_default.is_none()
+		&& req.tags.is_none()
+	{
+		return Err(tonic::Status::invalid_argument(MODIFY_NO_FIELDS));
+	}
+
+	seperated.push("updated_at = NOW()");
+
+	qb.push(" WHERE id = ").push_bind(req.id.into_ulid());
+	qb.push(" AND organization_id = ").push_bind(access_token.organization_id);
+	qb.push(" RETURNING *");
+
+	Ok(qb)
+}
+
+impl ApiRequest<IngestPolicyModifyResponse> for tonic::Request<IngestPolicyModifyRequest> {
+	async fn process<G: ApiGlobal>(
+		&self,
+		global: &Arc<G>,
+		access_token: &AccessToken,
+	) -> tonic::Result<tonic::Response<IngestPolicyModifyResponse>> {
+		let req = self.get_ref();
+
+		validate(req)?;
+
+		let query = build_query(req, access_token)?;
+
+		let mut client = global.db().get().await.map_err(|err| {
+			tracing::error!(err = %err, "failed to get db client");
+			Status::internal("internal server error")
+		})?;
+
+		let tx = client.transaction().await.map_err(|err| {
+			tracing::error!(err = %err, "beginning transaction");
+			Status::internal("internal server error")
+		})?;
+
+		let replaced_defaults = if req.organization_default == Some(true) {
+			unset_organization_default(&tx, access_token.organization_id, Some(req.id.into_ulid())).await?
+		} else {
+			Vec::new()
+		};
+
+		let result: Option<video_common::database::IngestPolicy> =
+			query.build_query_as().fetch_optional(&tx).await.map_err(|err| {
+				tracing::error!(err = %err, "failed to modify {}", <IngestPolicyModifyRequest as TonicRequest>::Table::FRIENDLY_NAME);
+				Status::internal(format!(
+					"failed to modify {}",
+					<IngestPolicyModifyRequest as TonicRequest>::Table::FRIENDLY_NAME
+				))
+			})?;
+
+		match result {
+			Some(result) => {
+				tx.commit().await.map_err(|err| {
+					tracing::error!(err = %err, "committing transaction");
+					Status::internal("internal server error")
+				})?;
+
+				emit_modified(global, access_token.organization_id, replaced_defaults).await;
+
+				video_common::events::emit(
+					global.nats(),
+					&global.config().events.stream_name,
+					access_token.organization_id,
+					Target::IngestPolicy,
+					event::Event::IngestPolicy(event::IngestPolicy {
+						ingest_policy_id: Some(result.id.into()),
+						event: Some(event::ingest_policy::Event::Modified(event::ingest_policy::Modified {})),
+					}),
+				)
+				.await;
+				Ok(tonic::Response::new(IngestPolicyModifyResponse {
+					ingest_policy: Some(result.into_proto()),
+				}))
+			}
+			None => Err(Status::not_found(format!(
+				"{} not found",
+				<IngestPolicyModifyRequest as TonicRequest>::Table::FRIENDLY_NAME
+			))),
+		}
+	}
+}
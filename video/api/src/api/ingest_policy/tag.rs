@@ -0,0 +1,22 @@
+use pb::scuffle::video::v1::events_fetch_request::Target;
+use pb::scuffle::video::v1::types::access_token_scope::Permission;
+use pb::scuffle::video::v1::types::{event, Resource};
+use pb::scuffle::video::v1::{IngestPolicyTagRequest, IngestPolicyTagResponse};
+
+use crate::api::utils::impl_request_scopes;
+use crate::api::utils::tags::impl_tag_req;
+use crate::ratelimit::RateLimitResource;
+
+impl_request_scopes!(
+	IngestPolicyTagRequest,
+	video_common::database::IngestPolicy,
+	(Resource::IngestPolicy, Permission::Modify),
+	RateLimitResource::IngestPolicyTag
+);
+
+impl_tag_req!(IngestPolicyTagRequest, IngestPolicyTagResponse, Target::IngestPolicy, [id] {
+	event::Event::IngestPolicy(event::IngestPolicy {
+		ingest_policy_id: Some(id.into()),
+		event: Some(event::ingest_policy::Event::Modified(event::ingest_policy::Modified {})),
+	})
+});
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use pb::scuffle::video::v1::events_fetch_request::Target;
+use pb::scuffle::video::v1::types::event;
+use ulid::Ulid;
+use utils::database::ClientLike;
+
+use crate::global::ApiGlobal;
+
+/// The video codecs ingest can accept, as they appear in a codec string.
+const VIDEO_CODECS: &[&str] = &["avc1", "hev1", "av01"];
+
+/// The audio codecs ingest can accept, as they appear in a codec string.
+const AUDIO_CODECS: &[&str] = &["mp4a", "opus"];
+
+pub fn validate_video_codecs(codecs: &[String]) -> tonic::Result<Vec<String>> {
+	validate_codecs("allowed_video_codecs", codecs, VIDEO_CODECS)
+}
+
+pub fn validate_audio_codecs(codecs: &[String]) -> tonic::Result<Vec<String>> {
+	validate_codecs("allowed_audio_codecs", codecs, AUDIO_CODECS)
+}
+
+fn validate_codecs(field: &str, codecs: &[String], supported: &[&str]) -> tonic::Result<Vec<String>> {
+	let mut result = Vec::with_capacity(codecs.len());
+
+	for codec in codecs {
+		let codec = codec.to_lowercase();
+		if !supported.contains(&codec.as_str()) {
+			return Err(tonic::Status::invalid_argument(format!(
+				"{field} must only contain {}",
+				supported.join(", ")
+			)));
+		}
+
+		if !result.contains(&codec) {
+			result.push(codec);
+		}
+	}
+
+	Ok(result)
+}
+
+/// Limits must be positive when creating an ingest policy.
+pub fn validate_limit<T: Default + PartialOrd>(field: &str, value: Option<T>) -> tonic::Result<Option<T>> {
+	match value {
+		Some(value) if value <= T::default() => {
+			Err(tonic::Status::invalid_argument(format!("{field} must be greater than 0")))
+		}
+		value => Ok(value),
+	}
+}
+
+/// When modifying an ingest policy a limit of 0 removes it.
+pub fn validate_modify_limit<T: Default + PartialOrd>(field: &str, value: T) -> tonic::Result<Option<T>> {
+	if value < T::default() {
+		Err(tonic::Status::invalid_argument(format!("{field} must not be negative")))
+	} else if value == T::default() {
+		Ok(None)
+	} else {
+		Ok(Some(value))
+	}
+}
+
+/// An organization can only have one default ingest policy, so the current one
+/// has to give up the spot before another policy takes it. Returns the ids of
+/// the policies that were changed.
+pub async fn unset_organization_default(
+	client: impl ClientLike,
+	organization_id: Ulid,
+	except: Option<Ulid>,
+) -> tonic::Result<Vec<Ulid>> {
+	utils::database::query(
+		"UPDATE ingest_policies SET organization_default = FALSE, updated_at = NOW() WHERE organization_id = $1 AND organization_default AND id IS DISTINCT FROM $2 RETURNING id",
+	)
+	.bind(organization_id)
+	.bind(except)
+	.build_query_single_scalar()
+	.fetch_all(client)
+	.await
+	.map_err(|err| {
+		tracing::error!(err = %err, "failed to unset organization default ingest policy");
+		tonic::Status::internal("failed to unset organization default ingest policy")
+	})
+}
+
+pub async fn emit_modified<G: ApiGlobal>(global: &Arc<G>, organization_id: Ulid, ids: impl IntoIterator<Item = Ulid>) {
+	for id in ids {
+		video_common::events::emit(
+			global.nats(),
+			&global.config().events.stream_name,
+			organization_id,
+			Target::IngestPolicy,
+			event::Event::IngestPolicy(event::IngestPolicy {
+				ingest_policy_id: Some(id.into()),
+				event: Some(event::ingest_policy::Event::Modified(event::ingest_policy::Modified {})),
+			}),
+		)
+		.await;
+	}
+}
@@ -12,6 +12,7 @@ use crate::global::ApiGlobal;
 pub(crate) mod access_token;
 pub(crate) mod errors;
 pub(crate) mod events;
+pub(crate) mod ingest_policy;
 pub(crate) mod playback_key_pair;
 pub(crate) mod playback_session;
 pub(crate) mod recording;
@@ -71,6 +72,7 @@ pub async fn run<G: ApiGlobal>(global: Arc<G>) -> Result<()> {
 	.add_service(recording_config::RecordingConfigServer::<G>::build())
 	.add_service(transcoding_config::TranscodingConfigServer::<G>::build())
 	.add_service(s3_bucket::S3BucketServer::<G>::build())
+	.add_service(ingest_policy::IngestPolicyServer::<G>::build())
 	.add_service(access_token::AccessTokenServer::<G>::build())
 	.add_service(events::EventsServer::<G>::build())
 	.serve_with_shutdown(config.bind_address, async {
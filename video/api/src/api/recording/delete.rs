@@ -32,6 +32,20 @@ struct ThumbnailResp {
 	idx: i32,
 }
 
+#[derive(postgres_from_row::FromRow)]
+struct SpriteResp {
+	recording_id: Ulid,
+	id: Ulid,
+	idx: i32,
+}
+
+#[derive(postgres_from_row::FromRow)]
+struct CaptionResp {
+	recording_id: Ulid,
+	id: Ulid,
+	idx: i32,
+}
+
 #[derive(postgres_from_row::FromRow)]
 struct SegmentResp {
 	recording_id: Ulid,
@@ -40,6 +54,14 @@ struct SegmentResp {
 	rendition: Rendition,
 }
 
+#[derive(postgres_from_row::FromRow)]
+struct SourceChunkResp {
+	recording_id: Ulid,
+	connection_id: Ulid,
+	id: Ulid,
+	idx: i32,
+}
+
 #[derive(postgres_from_row::FromRow)]
 struct RecordingResp {
 	id: Ulid,
@@ -82,6 +104,59 @@ impl UpdateBatch for ThumbnailResp {
 	}
 }
 
+impl UpdateBatch for SpriteResp {
+	const NAME: &'static str = "sprite";
+
+	fn is_same_batch(&self, batch: &RecordingDeleteBatchTask) -> bool {
+		batch.recording_id.into_ulid() == self.recording_id
+			&& matches!(batch.objects_type, Some(recording_delete_batch_task::ObjectsType::Sprites(_)))
+	}
+
+	fn update_batch(&self, deleted_recordings: &HashMap<Ulid, Ulid>, batch: &mut RecordingDeleteBatchTask) {
+		batch.recording_id = Some(self.recording_id.into());
+		batch.s3_bucket_id = Some(deleted_recordings[&self.recording_id].into());
+		batch.objects_type = Some(recording_delete_batch_task::ObjectsType::Sprites(
+			recording_delete_batch_task::SpriteType {},
+		));
+		batch.objects.clear();
+	}
+
+	fn to_object(&self) -> recording_delete_batch_task::Object {
+		recording_delete_batch_task::Object {
+			index: self.idx,
+			object_id: Some(self.id.into()),
+		}
+	}
+}
+
+impl UpdateBatch for CaptionResp {
+	const NAME: &'static str = "caption segment";
+
+	fn is_same_batch(&self, batch: &RecordingDeleteBatchTask) -> bool {
+		batch.recording_id.into_ulid() == self.recording_id
+			&& matches!(
+				batch.objects_type,
+				Some(recording_delete_batch_task::ObjectsType::Captions(_))
+			)
+	}
+
+	fn update_batch(&self, deleted_recordings: &HashMap<Ulid, Ulid>, batch: &mut RecordingDeleteBatchTask) {
+		batch.recording_id = Some(self.recording_id.into());
+		batch.s3_bucket_id = Some(deleted_recordings[&self.recording_id].into());
+		batch.objects_type = Some(recording_delete_batch_task::ObjectsType::Captions(
+			recording_delete_batch_task::CaptionType {},
+		));
+		batch.objects.clear();
+	}
+
+	fn to_object(&self) -> recording_delete_batch_task::Object {
+		recording_delete_batch_task::Object {
+			index: self.idx,
+			object_id: Some(self.id.into()),
+		}
+	}
+}
+
 impl UpdateBatch for SegmentResp {
 	const NAME: &'static str = "segment";
 
@@ -110,6 +185,34 @@ impl UpdateBatch for SegmentResp {
 	}
 }
 
+impl UpdateBatch for SourceChunkResp {
+	const NAME: &'static str = "source chunk";
+
+	fn is_same_batch(&self, batch: &RecordingDeleteBatchTask) -> bool {
+		batch.recording_id.into_ulid() == self.recording_id
+			&& batch.objects_type
+				== Some(recording_delete_batch_task::ObjectsType::SourceChunks(
+					self.connection_id.into(),
+				))
+	}
+
+	fn update_batch(&self, deleted_recordings: &HashMap<Ulid, Ulid>, batch: &mut RecordingDeleteBatchTask) {
+		batch.recording_id = Some(self.recording_id.into());
+		batch.s3_bucket_id = Some(deleted_recordings[&self.recording_id].into());
+		batch.objects_type = Some(recording_delete_batch_task::ObjectsType::SourceChunks(
+			self.connection_id.into(),
+		));
+		batch.objects.clear();
+	}
+
+	fn to_object(&self) -> recording_delete_batch_task::Object {
+		recording_delete_batch_task::Object {
+			index: self.idx,
+			object_id: Some(self.id.into()),
+		}
+	}
+}
+
 async fn handle_resp(
 	global: &Arc<impl ApiGlobal>,
 	deleted_recordings: &HashMap<Ulid, Ulid>,
@@ -314,6 +417,40 @@ impl ApiRequest<RecordingDeleteResponse> for tonic::Request<RecordingDeleteReque
 
 			handle_end_of_stream(global, &mut batch).await?;
 
+			handle_query::<SpriteResp>(
+				global,
+				&client,
+				&deleted_recordings,
+				&mut batch,
+				utils::database::query("SELECT id, recording_id, idx FROM ")
+					.push(<video_common::database::RecordingSprite as DatabaseTable>::NAME)
+					.push(" WHERE recording_id = ANY(")
+					.push_bind(&deleted_ids)
+					.push(") AND organization_id = ")
+					.push_bind(access_token.organization_id)
+					.push(" ORDER BY recording_id"),
+			)
+			.await?;
+
+			handle_end_of_stream(global, &mut batch).await?;
+
+			handle_query::<CaptionResp>(
+				global,
+				&client,
+				&deleted_recordings,
+				&mut batch,
+				utils::database::query("SELECT id, recording_id, idx FROM ")
+					.push(<video_common::database::RecordingCaptionSegment as DatabaseTable>::NAME)
+					.push(" WHERE recording_id = ANY(")
+					.push_bind(&deleted_ids)
+					.push(") AND organization_id = ")
+					.push_bind(access_token.organization_id)
+					.push(" ORDER BY recording_id"),
+			)
+			.await?;
+
+			handle_end_of_stream(global, &mut batch).await?;
+
 			handle_query::<SegmentResp>(
 				global,
 				&client,
@@ -330,6 +467,23 @@ impl ApiRequest<RecordingDeleteResponse> for tonic::Request<RecordingDeleteReque
 			)
 			.await?;
 
+			handle_end_of_stream(global, &mut batch).await?;
+
+			handle_query::<SourceChunkResp>(
+				global,
+				&client,
+				&deleted_recordings,
+				&mut batch,
+				utils::database::query("SELECT id, recording_id, connection_id, idx FROM ")
+					.push(<video_common::database::RecordingSourceChunk as DatabaseTable>::NAME)
+					.push(" WHERE recording_id = ANY(")
+					.push_bind(&deleted_ids)
+					.push(") AND organization_id = ")
+					.push_bind(access_token.organization_id)
+					.push(" ORDER BY recording_id, connection_id"),
+			)
+			.await?;
+
 			handle_end_of_stream(global, &mut batch).await
 		};
 
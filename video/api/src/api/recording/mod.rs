@@ -2,6 +2,7 @@ use pb::scuffle::video::v1::recording_server::{Recording as RecordingServiceTrai
 use pb::scuffle::video::v1::{
 	RecordingDeleteRequest, RecordingDeleteResponse, RecordingGetRequest, RecordingGetResponse, RecordingModifyRequest,
 	RecordingModifyResponse, RecordingTagRequest, RecordingTagResponse, RecordingUntagRequest, RecordingUntagResponse,
+	RecordingUploadRequest, RecordingUploadResponse,
 };
 use tonic::{async_trait, Request, Response};
 
@@ -14,6 +15,7 @@ pub(crate) mod get;
 pub(crate) mod modify;
 pub(crate) mod tag;
 pub(crate) mod untag;
+pub(crate) mod upload;
 
 pub struct RecordingServer<G: ApiGlobal> {
 	_phantom: std::marker::PhantomData<G>,
@@ -39,6 +41,12 @@ impl<G: ApiGlobal> RecordingServiceTrait for RecordingServer<G> {
 		});
 	}
 
+	async fn upload(&self, request: Request<RecordingUploadRequest>) -> tonic::Result<Response<RecordingUploadResponse>> {
+		scope_ratelimit!(self, request, global, access_token, || async {
+			request.process(global, access_token).await
+		});
+	}
+
 	async fn modify(&self, request: Request<RecordingModifyRequest>) -> tonic::Result<Response<RecordingModifyResponse>> {
 		scope_ratelimit!(self, request, global, access_token, || async {
 			request.process(global, access_token).await
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use aws_sdk_s3::config::{Credentials, Region};
+use pb::ext::UlidExt;
+use pb::scuffle::video::internal::events::RecordingUploadTask;
+use pb::scuffle::video::v1::recording_upload_request::Source;
+use pb::scuffle::video::v1::types::access_token_scope::Permission;
+use pb::scuffle::video::v1::types::Resource;
+use pb::scuffle::video::v1::{RecordingUploadRequest, RecordingUploadResponse};
+use prost::Message;
+use tonic::Status;
+use ulid::Ulid;
+use video_common::database::{AccessToken, DatabaseTable, S3Bucket, Visibility};
+
+use crate::api::utils::tags::validate_tags;
+use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
+use crate::config::ApiConfig;
+use crate::global::ApiGlobal;
+use crate::ratelimit::RateLimitResource;
+
+impl_request_scopes!(
+	RecordingUploadRequest,
+	video_common::database::Recording,
+	(Resource::Recording, Permission::Create),
+	RateLimitResource::RecordingUpload
+);
+
+pub fn validate(req: &RecordingUploadRequest) -> tonic::Result<()> {
+	validate_tags(req.tags.as_ref())?;
+
+	match &req.source {
+		Some(Source::Data(data)) if data.is_empty() => Err(Status::invalid_argument("uploaded file is empty")),
+		Some(Source::S3Object(object)) if object.key.is_empty() => Err(Status::invalid_argument("s3 object key is empty")),
+		Some(_) => Ok(()),
+		None => Err(Status::invalid_argument("source is required")),
+	}
+}
+
+impl ApiRequest<RecordingUploadResponse> for tonic::Request<RecordingUploadRequest> {
+	async fn process<G: ApiGlobal>(
+		&self,
+		global: &Arc<G>,
+		access_token: &AccessToken,
+	) -> tonic::Result<tonic::Response<RecordingUploadResponse>> {
+		let req = self.get_ref();
+
+		validate(req)?;
+
+		let visibility = pb::scuffle::video::v1::types::Visibility::try_from(req.visibility)
+			.map_err(|_| Status::invalid_argument("invalid visibility value"))?;
+
+		let client = global.db().get().await.map_err(|err| {
+			tracing::error!(err = %err, "failed to get db client");
+			Status::internal("internal server error")
+		})?;
+
+		let recording_config: video_common::database::RecordingConfig =
+			utils::database::query("SELECT * FROM recording_configs WHERE id = $1 AND organization_id = $2")
+				.bind(req.recording_config_id.into_ulid())
+				.bind(access_token.organization_id)
+				.build_query_as()
+				.fetch_optional(&client)
+				.await
+				.map_err(|err| {
+					tracing::error!(err = %err, "failed to query recording config");
+					Status::internal("failed to query recording configs")
+				})?
+				.ok_or_else(|| Status::not_found("recording config not found"))?;
+
+		let transcoding_config = if let Some(transcoding_config_id) = &req.transcoding_config_id {
+			let transcoding_config: video_common::database::TranscodingConfig =
+				utils::database::query("SELECT * FROM transcoding_configs WHERE id = $1 AND organization_id = $2")
+					.bind(transcoding_config_id.into_ulid())
+					.bind(access_token.organization_id)
+					.build_query_as()
+					.fetch_optional(&client)
+					.await
+					.map_err(|err| {
+						tracing::error!(err = %err, "failed to query transcoding config");
+						Status::internal("failed to query transcoding configs")
+					})?
+					.ok_or_else(|| Status::not_found("transcoding config not found"))?;
+
+			Some(transcoding_config.into_proto())
+		} else {
+			None
+		};
+
+		let recording_id = Ulid::new();
+
+		// The bucket the file was uploaded to, if it was uploaded with the request
+		let (s3_bucket_id, key, uploaded) = match req.source.as_ref().unwrap() {
+			Source::S3Object(object) => {
+				let s3_bucket_id = object.s3_bucket_id.into_ulid();
+
+				utils::database::query("SELECT id FROM s3_buckets WHERE id = $1 AND organization_id = $2")
+					.bind(s3_bucket_id)
+					.bind(access_token.organization_id)
+					.build()
+					.fetch_optional(&client)
+					.await
+					.map_err(|err| {
+						tracing::error!(err = %err, "failed to query s3 bucket");
+						Status::internal("failed to query s3 buckets")
+					})?
+					.ok_or_else(|| Status::not_found("s3 bucket not found"))?;
+
+				(s3_bucket_id, object.key.clone(), None)
+			}
+			Source::Data(data) => {
+				// The file is stored next to the recording until it is transcoded
+				let s3_bucket: S3Bucket =
+					utils::database::query("SELECT * FROM s3_buckets WHERE id = $1 AND organization_id = $2")
+						.bind(recording_config.s3_bucket_id)
+						.bind(access_token.organization_id)
+						.build_query_as()
+						.fetch_optional(&client)
+						.await
+						.map_err(|err| {
+							tracing::error!(err = %err, "failed to query s3 bucket");
+							Status::internal("failed to query s3 buckets")
+						})?
+						.ok_or_else(|| Status::not_found("s3 bucket not found"))?;
+
+				let bucket = binary_helper::s3::Bucket::new(
+					s3_bucket.name.clone(),
+					Credentials::from_keys(&s3_bucket.access_key_id, &s3_bucket.secret_access_key, None),
+					Region::new(s3_bucket.region.clone()),
+					s3_bucket.endpoint.clone(),
+				);
+
+				let key = video_common::keys::s3_upload(access_token.organization_id, recording_id);
+
+				bucket.put_object(key.clone(), data.clone(), None).await.map_err(|err| {
+					tracing::error!(err = %err, "failed to upload file");
+					Status::unavailable("failed to upload file to s3 bucket")
+				})?;
+
+				(s3_bucket.id, key, Some(bucket))
+			}
+		};
+
+		// The recording is committed before the upload is queued, so that the
+		// transcoder taking the upload finds it.
+		let result: video_common::database::Recording = utils::database::query("INSERT INTO ")
+			.push(<RecordingUploadRequest as TonicRequest>::Table::NAME)
+			.push(" (id, organization_id, recording_config_id, s3_bucket_id, visibility, allow_dvr, tags) VALUES (")
+			.push_bind(recording_id)
+			.push(", ")
+			.push_bind(access_token.organization_id)
+			.push(", ")
+			.push_bind(recording_config.id)
+			.push(", ")
+			.push_bind(recording_config.s3_bucket_id)
+			.push(", ")
+			.push_bind(Visibility::from(visibility))
+			.push(", FALSE, ")
+			.push_bind(utils::database::Json(req.tags.clone().unwrap_or_default().tags))
+			.push(") RETURNING *")
+			.build_query_as()
+			.fetch_one(&client)
+			.await
+			.map_err(|err| {
+				tracing::error!(err = %err, "failed to create {}", <RecordingUploadRequest as TonicRequest>::Table::FRIENDLY_NAME);
+				Status::internal(format!(
+					"failed to create {}",
+					<RecordingUploadRequest as TonicRequest>::Table::FRIENDLY_NAME
+				))
+			})?;
+
+		let task = RecordingUploadTask {
+			organization_id: Some(access_token.organization_id.into()),
+			recording_id: Some(recording_id.into()),
+			s3_bucket_id: Some(s3_bucket_id.into()),
+			key: key.clone(),
+			delete_source: uploaded.is_some(),
+			recording_config: Some(recording_config.into_proto()),
+			transcoding_config,
+		};
+
+		// The queue is created by the transcoders, if none of them is there to take
+		// the upload the recording would never finish, so it is removed again.
+		let queued = async {
+			global
+				.jetstream()
+				.publish(
+					global.config::<ApiConfig>().recording_upload_subject.clone(),
+					task.encode_to_vec().into(),
+				)
+				.await?
+				.await
+		}
+		.await;
+
+		if let Err(err) = queued {
+			tracing::error!(err = %err, "failed to queue upload");
+
+			if let Err(err) = utils::database::query("DELETE FROM ")
+				.push(<RecordingUploadRequest as TonicRequest>::Table::NAME)
+				.push(" WHERE id = ")
+				.push_bind(recording_id)
+				.push(" AND organization_id = ")
+				.push_bind(access_token.organization_id)
+				.build()
+				.execute(&client)
+				.await
+			{
+				tracing::error!(err = %err, "failed to delete {}", <RecordingUploadRequest as TonicRequest>::Table::FRIENDLY_NAME);
+			}
+
+			if let Some(bucket) = &uploaded {
+				if let Err(err) = bucket.delete_object(&key).await {
+					tracing::error!(err = %err, "failed to delete uploaded file");
+				}
+			}
+
+			return Err(Status::unavailable("failed to queue upload"));
+		}
+
+		Ok(tonic::Response::new(RecordingUploadResponse {
+			recording: Some(result.into_proto(Vec::new(), 0, 0.0)),
+		}))
+	}
+}
@@ -46,6 +46,7 @@ pub async fn build_query(
 	seperated.push("updated_at");
 	seperated.push("s3_bucket_id");
 	seperated.push("tags");
+	seperated.push("capture_source");
 
 	qb.push(") VALUES (");
 
@@ -94,6 +95,7 @@ pub async fn build_query(
 	seperated.push_bind(chrono::Utc::now());
 	seperated.push_bind(bucket.id);
 	seperated.push_bind(utils::database::Json(req.tags.clone().unwrap_or_default().tags));
+	seperated.push_bind(req.capture_source);
 
 	qb.push(") RETURNING *");
 
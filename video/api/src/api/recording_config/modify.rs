@@ -73,6 +73,10 @@ pub async fn build_query<'a>(
 			.push_bind_unseparated(utils::database::Json(&tags.tags));
 	}
 
+	if let Some(capture_source) = req.capture_source {
+		seperated.push("capture_source = ").push_bind_unseparated(capture_source);
+	}
+
 	if let Some(s3_bucket_id) = &req.s3_bucket_id {
 		utils::database::query("SELECT * FROM s3_buckets WHERE id = $1 AND organization_id = $2")
 			.bind(s3_bucket_id.into_ulid())
@@ -95,6 +99,7 @@ pub async fn build_query<'a>(
 		&& req.stored_renditions.is_none()
 		&& req.lifecycle_policies.is_none()
 		&& req.s3_bucket_id.is_none()
+		&& req.capture_source.is_none()
 	{
 		return Err(Status::invalid_argument(MODIFY_NO_FIELDS));
 	}
@@ -9,7 +9,7 @@ use ulid::Ulid;
 use utils::database::ClientLike;
 use video_common::database::{AccessToken, DatabaseTable, Visibility};
 
-use super::utils::create_stream_key;
+use super::utils::{create_stream_key, validate_ip_filter, validate_source_url};
 use crate::api::utils::tags::validate_tags;
 use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
 use crate::global::ApiGlobal;
@@ -23,7 +23,17 @@ impl_request_scopes!(
 );
 
 pub fn validate(req: &RoomCreateRequest) -> tonic::Result<()> {
-	validate_tags(req.tags.as_ref())
+	validate_tags(req.tags.as_ref())?;
+
+	if let Some(source_url) = &req.source_url {
+		validate_source_url(source_url)?;
+	}
+
+	if let Some(ip_filter) = &req.ingest_ip_filter {
+		validate_ip_filter(ip_filter)?;
+	}
+
+	Ok(())
 }
 
 pub async fn build_query(
@@ -46,6 +56,10 @@ pub async fn build_query(
 	seperated.push("visibility");
 	seperated.push("stream_key");
 	seperated.push("tags");
+	seperated.push("source_url");
+	seperated.push("ingest_policy_id");
+	seperated.push("ingest_ip_allow");
+	seperated.push("ingest_ip_deny");
 
 	qb.push(") VALUES (");
 
@@ -85,6 +99,24 @@ pub async fn build_query(
 		None
 	};
 
+	let ingest_policy_id = if let Some(ingest_policy_id) = &req.ingest_policy_id {
+		utils::database::query("SELECT * FROM ingest_policies WHERE id = $1 AND organization_id = $2")
+			.bind(ingest_policy_id.into_ulid())
+			.bind(access_token.organization_id)
+			.build()
+			.fetch_optional(&client)
+			.await
+			.map_err(|err| {
+				tracing::error!(err = %err, "failed to fetch ingest policy");
+				Status::internal("failed to fetch ingest policy")
+			})?
+			.ok_or_else(|| Status::not_found("ingest policy not found"))?;
+
+		Some(ingest_policy_id.into_ulid())
+	} else {
+		None
+	};
+
 	let visibility = pb::scuffle::video::v1::types::Visibility::try_from(req.visibility)
 		.map_err(|_| Status::invalid_argument("invalid visibility value"))?;
 
@@ -98,6 +130,10 @@ pub async fn build_query(
 	seperated.push_bind(Visibility::from(visibility));
 	seperated.push_bind(create_stream_key());
 	seperated.push_bind(utils::database::Json(req.tags.clone().unwrap_or_default().tags));
+	seperated.push_bind(req.source_url.clone());
+	seperated.push_bind(ingest_policy_id);
+	seperated.push_bind(req.ingest_ip_filter.as_ref().map(|f| f.allow.clone()).unwrap_or_default());
+	seperated.push_bind(req.ingest_ip_filter.as_ref().map(|f| f.deny.clone()).unwrap_or_default());
 
 	qb.push(") RETURNING *");
 
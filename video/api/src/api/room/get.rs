@@ -40,6 +40,11 @@ pub fn build_query(
 		seperated.push_bind_unseparated(recording_config_id.into_ulid());
 	}
 
+	if let Some(ingest_policy_id) = req.ingest_policy_id.as_ref() {
+		seperated.push("ingest_policy_id = ");
+		seperated.push_bind_unseparated(ingest_policy_id.into_ulid());
+	}
+
 	if let Some(status) = req.status {
 		let status = pb::scuffle::video::v1::types::RoomStatus::try_from(status)
 			.map_err(|_| Status::invalid_argument("invalid status value"))?;
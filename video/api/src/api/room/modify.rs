@@ -9,6 +9,7 @@ use tonic::Status;
 use utils::database::ClientLike;
 use video_common::database::{AccessToken, DatabaseTable, Visibility};
 
+use super::utils::{validate_ip_filter, validate_source_url};
 use crate::api::errors::MODIFY_NO_FIELDS;
 use crate::api::utils::tags::validate_tags;
 use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
@@ -23,7 +24,17 @@ impl_request_scopes!(
 );
 
 pub fn validate(req: &RoomModifyRequest) -> tonic::Result<()> {
-	validate_tags(req.tags.as_ref())
+	validate_tags(req.tags.as_ref())?;
+
+	if let Some(source_url) = req.source_url.as_deref().filter(|url| !url.is_empty()) {
+		validate_source_url(source_url)?;
+	}
+
+	if let Some(ip_filter) = &req.ingest_ip_filter {
+		validate_ip_filter(ip_filter)?;
+	}
+
+	Ok(())
 }
 
 pub async fn build_query<'a>(
@@ -85,6 +96,27 @@ pub async fn build_query<'a>(
 		}
 	}
 
+	if let Some(ingest_policy_id) = &req.ingest_policy_id {
+		let ingest_policy_id = ingest_policy_id.into_ulid();
+		if ingest_policy_id.is_nil() {
+			seperated.push("ingest_policy_id = NULL");
+		} else {
+			utils::database::query("SELECT 1 FROM ingest_policies WHERE id = $1 AND organization_id = $2")
+				.bind(ingest_policy_id)
+				.bind(access_token.organization_id)
+				.build()
+				.fetch_optional(&client)
+				.await
+				.map_err(|err| {
+					tracing::error!(err = %err, "failed to fetch ingest policy");
+					Status::internal("failed to fetch ingest policy")
+				})?
+				.ok_or_else(|| Status::not_found("ingest policy not found"))?;
+
+			seperated.push("ingest_policy_id = ").push_bind_unseparated(ingest_policy_id);
+		}
+	}
+
 	if let Some(visibility) = req.visibility {
 		let visibility = pb::scuffle::video::v1::types::Visibility::try_from(visibility)
 			.map_err(|_| Status::invalid_argument("invalid visibility value"))?;
@@ -100,10 +132,26 @@ pub async fn build_query<'a>(
 			.push_bind_unseparated(utils::database::Json(&tags.tags));
 	}
 
+	if let Some(source_url) = &req.source_url {
+		if source_url.is_empty() {
+			seperated.push("source_url = NULL");
+		} else {
+			seperated.push("source_url = ").push_bind_unseparated(source_url);
+		}
+	}
+
+	if let Some(ip_filter) = &req.ingest_ip_filter {
+		seperated.push("ingest_ip_allow = ").push_bind_unseparated(&ip_filter.allow);
+		seperated.push("ingest_ip_deny = ").push_bind_unseparated(&ip_filter.deny);
+	}
+
 	if req.tags.is_none()
 		&& req.transcoding_config_id.is_none()
 		&& req.recording_config_id.is_none()
 		&& req.visibility.is_none()
+		&& req.source_url.is_none()
+		&& req.ingest_policy_id.is_none()
+		&& req.ingest_ip_filter.is_none()
 	{
 		return Err(Status::invalid_argument(MODIFY_NO_FIELDS));
 	}
@@ -1,4 +1,6 @@
+use pb::scuffle::video::v1::types::IpFilter;
 use rand::Rng;
+use video_common::ip_range::IpRange;
 
 pub fn create_stream_key() -> String {
 	rand::thread_rng()
@@ -7,3 +9,57 @@ pub fn create_stream_key() -> String {
 		.map(char::from)
 		.collect::<String>()
 }
+
+/// The maximum length of a room's source url.
+const MAX_SOURCE_URL_LENGTH: usize = 2048;
+
+/// Checks that a room's source url is something ingest knows how to pull from.
+/// The addresses the host resolves to are checked by ingest when it connects,
+/// as they can change after the room is created.
+pub fn validate_source_url(source_url: &str) -> tonic::Result<()> {
+	if source_url.len() > MAX_SOURCE_URL_LENGTH {
+		return Err(tonic::Status::invalid_argument(format!(
+			"source_url must be at most {MAX_SOURCE_URL_LENGTH} characters"
+		)));
+	}
+
+	let url = url::Url::parse(source_url).map_err(|_| tonic::Status::invalid_argument("invalid source_url"))?;
+
+	match url.scheme() {
+		"rtmp" | "rtmps" | "http" | "https" => {}
+		_ => {
+			return Err(tonic::Status::invalid_argument(
+				"source_url scheme must be one of rtmp, rtmps, http or https",
+			));
+		}
+	}
+
+	if url.host_str().is_none() {
+		return Err(tonic::Status::invalid_argument("source_url must have a host"));
+	}
+
+	Ok(())
+}
+
+/// The maximum number of entries in each list of a room's ip filter.
+const MAX_IP_FILTER_ENTRIES: usize = 32;
+
+/// Checks that every entry of a room's ip filter is an ip address or a CIDR
+/// range.
+pub fn validate_ip_filter(ip_filter: &IpFilter) -> tonic::Result<()> {
+	for (name, list) in [("allow", &ip_filter.allow), ("deny", &ip_filter.deny)] {
+		if list.len() > MAX_IP_FILTER_ENTRIES {
+			return Err(tonic::Status::invalid_argument(format!(
+				"ingest_ip_filter.{name} must have at most {MAX_IP_FILTER_ENTRIES} entries"
+			)));
+		}
+
+		if let Some(entry) = list.iter().find(|entry| entry.parse::<IpRange>().is_err()) {
+			return Err(tonic::Status::invalid_argument(format!(
+				"ingest_ip_filter.{name} has an invalid entry: {entry}"
+			)));
+		}
+	}
+
+	Ok(())
+}
@@ -5,10 +5,13 @@ use pb::scuffle::video::v1::events_fetch_request::Target;
 use pb::scuffle::video::v1::types::access_token_scope::Permission;
 use pb::scuffle::video::v1::types::{event, Resource};
 use pb::scuffle::video::v1::{TranscodingConfigCreateRequest, TranscodingConfigCreateResponse};
-use tonic::Status;
 use ulid::Ulid;
 use video_common::database::{AccessToken, DatabaseTable, Rendition};
 
+use super::utils::{
+	validate_content_aware_encoding, validate_custom_renditions, validate_loudness_normalization, validate_overlays,
+	validate_renditions,
+};
 use crate::api::utils::tags::validate_tags;
 use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
 use crate::global::ApiGlobal;
@@ -41,6 +44,11 @@ pub fn build_query(
 	seperated.push("organization_id");
 	seperated.push("renditions");
 	seperated.push("tags");
+	seperated.push("custom_renditions");
+	seperated.push("overlays");
+	seperated.push("loudness_normalization");
+	seperated.push("content_aware_encoding");
+	seperated.push("captions");
 
 	qb.push(") VALUES (");
 
@@ -48,18 +56,39 @@ pub fn build_query(
 
 	let renditions = req.renditions().map(Rendition::from).collect::<HashSet<_>>();
 
-	if !renditions.iter().any(|r| r.is_audio()) {
-		return Err(Status::invalid_argument("must specify at least one audio rendition"));
+	validate_custom_renditions(&req.custom_renditions)?;
+	validate_renditions(&renditions, Some(&req.custom_renditions))?;
+	validate_overlays(&req.overlays)?;
+
+	if let Some(loudness_normalization) = &req.loudness_normalization {
+		validate_loudness_normalization(loudness_normalization)?;
 	}
 
-	if !renditions.iter().any(|r| r.is_video()) {
-		return Err(Status::invalid_argument("must specify at least one video rendition"));
+	if let Some(content_aware_encoding) = &req.content_aware_encoding {
+		validate_content_aware_encoding(content_aware_encoding)?;
 	}
 
 	seperated.push_bind(Ulid::new());
 	seperated.push_bind(access_token.organization_id);
 	seperated.push_bind(renditions.into_iter().collect::<Vec<_>>());
 	seperated.push_bind(utils::database::Json(req.tags.clone().unwrap_or_default().tags));
+	seperated.push_bind(
+		req.custom_renditions
+			.clone()
+			.into_iter()
+			.map(utils::database::Protobuf)
+			.collect::<Vec<_>>(),
+	);
+	seperated.push_bind(
+		req.overlays
+			.clone()
+			.into_iter()
+			.map(utils::database::Protobuf)
+			.collect::<Vec<_>>(),
+	);
+	seperated.push_bind(req.loudness_normalization.clone().map(utils::database::Protobuf));
+	seperated.push_bind(req.content_aware_encoding.clone().map(utils::database::Protobuf));
+	seperated.push_bind(req.captions);
 
 	qb.push(") RETURNING *");
 
@@ -19,6 +19,7 @@ pub(crate) mod get;
 pub(crate) mod modify;
 pub(crate) mod tag;
 pub(crate) mod untag;
+pub(crate) mod utils;
 
 pub struct TranscodingConfigServer<G: ApiGlobal> {
 	_phantom: std::marker::PhantomData<G>,
@@ -9,6 +9,10 @@ use pb::scuffle::video::v1::{TranscodingConfigModifyRequest, TranscodingConfigMo
 use tonic::Status;
 use video_common::database::{AccessToken, DatabaseTable, Rendition};
 
+use super::utils::{
+	validate_content_aware_encoding, validate_custom_renditions, validate_loudness_normalization, validate_overlays,
+	validate_renditions,
+};
 use crate::api::errors::MODIFY_NO_FIELDS;
 use crate::api::utils::tags::validate_tags;
 use crate::api::utils::{impl_request_scopes, ApiRequest, TonicRequest};
@@ -38,29 +42,87 @@ pub fn build_query<'a>(
 
 	let mut seperated = qb.separated(",");
 
+	let custom_renditions = req.custom_renditions.as_ref().map(|c| c.items.as_slice());
+
+	if let Some(custom_renditions) = custom_renditions {
+		validate_custom_renditions(custom_renditions)?;
+	}
+
 	if let Some(renditions) = &req.renditions {
 		let renditions = renditions.items().map(Rendition::from).collect::<HashSet<_>>();
 
-		if !renditions.iter().any(|r| r.is_audio()) {
-			return Err(Status::invalid_argument("must specify at least one audio rendition"));
-		}
-
-		if !renditions.iter().any(|r| r.is_video()) {
-			return Err(Status::invalid_argument("must specify at least one video rendition"));
-		}
+		validate_renditions(&renditions, custom_renditions)?;
 
 		seperated
 			.push("renditions = ")
 			.push_bind_unseparated(renditions.into_iter().collect::<Vec<_>>());
 	}
 
+	if let Some(custom_renditions) = custom_renditions {
+		seperated.push("custom_renditions = ").push_bind_unseparated(
+			custom_renditions
+				.iter()
+				.cloned()
+				.map(utils::database::Protobuf)
+				.collect::<Vec<_>>(),
+		);
+	}
+
+	if let Some(overlays) = &req.overlays {
+		validate_overlays(&overlays.items)?;
+
+		seperated.push("overlays = ").push_bind_unseparated(
+			overlays
+				.items
+				.iter()
+				.cloned()
+				.map(utils::database::Protobuf)
+				.collect::<Vec<_>>(),
+		);
+	}
+
+	if let Some(loudness_normalization) = &req.loudness_normalization {
+		if loudness_normalization.integrated_loudness == 0.0 {
+			seperated.push("loudness_normalization = NULL");
+		} else {
+			validate_loudness_normalization(loudness_normalization)?;
+
+			seperated
+				.push("loudness_normalization = ")
+				.push_bind_unseparated(utils::database::Protobuf(loudness_normalization.clone()));
+		}
+	}
+
+	if let Some(content_aware_encoding) = &req.content_aware_encoding {
+		if content_aware_encoding.min_bitrate_ratio == 0.0 {
+			seperated.push("content_aware_encoding = NULL");
+		} else {
+			validate_content_aware_encoding(content_aware_encoding)?;
+
+			seperated
+				.push("content_aware_encoding = ")
+				.push_bind_unseparated(utils::database::Protobuf(content_aware_encoding.clone()));
+		}
+	}
+
+	if let Some(captions) = req.captions {
+		seperated.push("captions = ").push_bind_unseparated(captions);
+	}
+
 	if let Some(tags) = &req.tags {
 		seperated
 			.push("tags = ")
 			.push_bind_unseparated(utils::database::Json(&tags.tags));
 	}
 
-	if req.renditions.is_none() && req.tags.is_none() {
+	if req.renditions.is_none()
+		&& req.tags.is_none()
+		&& req.custom_renditions.is_none()
+		&& req.overlays.is_none()
+		&& req.loudness_normalization.is_none()
+		&& req.content_aware_encoding.is_none()
+		&& req.captions.is_none()
+	{
 		return Err(tonic::Status::invalid_argument(MODIFY_NO_FIELDS));
 	}
 
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use pb::scuffle::video::v1::types::{
+	custom_rendition, overlay, ContentAwareEncoding, CustomRendition, LoudnessNormalization, Overlay,
+};
+use tonic::Status;
+use video_common::database::Rendition;
+
+/// One rung for each of the VIDEO_CUSTOM renditions.
+const MAX_CUSTOM_RENDITIONS: usize = 8;
+const MAX_NAME_LENGTH: usize = 32;
+const NAME_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-_";
+
+/// The sides a custom rendition can be scaled to, in pixels.
+const MIN_SIDE: i32 = 100;
+const MAX_SIDE: i32 = 1080;
+
+const MAX_FPS: i32 = 120;
+
+/// The highest bitrate of a custom rendition, in bits per second.
+const MAX_BITRATE: i64 = 50_000_000;
+
+const MAX_OVERLAYS: usize = 8;
+const MAX_IMAGE_KEY_LENGTH: usize = 1024;
+
+/// The largest margin of an overlay, as a fraction of the height of the video.
+const MAX_MARGIN: f32 = 0.5;
+
+/// The targets the loudnorm filter accepts, in LUFS, dBTP and LU.
+const INTEGRATED_LOUDNESS: RangeInclusive<f64> = -70.0..=-5.0;
+const TRUE_PEAK: RangeInclusive<f64> = -9.0..=0.0;
+const LOUDNESS_RANGE: RangeInclusive<f64> = 1.0..=50.0;
+
+/// The share of the bitrate of a rung content aware encoding may pick.
+const BITRATE_RATIO: RangeInclusive<f64> = 0.1..=1.0;
+/// The constant rate factors the h264, hevc and av1 encoders all accept.
+const CRF: RangeInclusive<u32> = 0..=51;
+/// The seconds of source measured before the bitrates are picked.
+const ANALYSIS_DURATION: RangeInclusive<u32> = 1..=60;
+
+pub fn validate_renditions(
+	renditions: &HashSet<Rendition>,
+	custom_renditions: Option<&[CustomRendition]>,
+) -> tonic::Result<()> {
+	if !renditions.iter().any(|r| r.is_audio()) {
+		return Err(Status::invalid_argument("must specify at least one audio rendition"));
+	}
+
+	if !renditions.iter().any(|r| r.is_video()) {
+		return Err(Status::invalid_argument("must specify at least one video rendition"));
+	}
+
+	// When only the renditions are modified the custom renditions are not known,
+	// the transcoder skips the ones which are not defined.
+	if let Some(custom_renditions) = custom_renditions {
+		if let Some(rendition) = renditions
+			.iter()
+			.filter(|r| r.is_custom())
+			.find(|r| !custom_renditions.iter().any(|c| Rendition::from(c.rendition()) == **r))
+		{
+			return Err(Status::invalid_argument(format!(
+				"{rendition} is not defined by a custom rendition"
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+pub fn validate_custom_renditions(custom_renditions: &[CustomRendition]) -> tonic::Result<()> {
+	if custom_renditions.len() > MAX_CUSTOM_RENDITIONS {
+		return Err(Status::invalid_argument(format!(
+			"at most {MAX_CUSTOM_RENDITIONS} custom renditions can be defined"
+		)));
+	}
+
+	let mut renditions = HashSet::new();
+	let mut names = HashSet::new();
+
+	for custom in custom_renditions {
+		let rendition = Rendition::from(custom.rendition());
+		if !rendition.is_custom() {
+			return Err(Status::invalid_argument(
+				"custom renditions must use one of the VIDEO_CUSTOM renditions",
+			));
+		}
+
+		if !renditions.insert(rendition) {
+			return Err(Status::invalid_argument(format!("{rendition} is defined more than once")));
+		}
+
+		if custom.name.is_empty() || custom.name.len() > MAX_NAME_LENGTH {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition name must be between 1 and {MAX_NAME_LENGTH} characters"
+			)));
+		}
+
+		if let Some(c) = custom.name.chars().find(|c| !NAME_ALPHABET.contains(*c)) {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition name contains invalid characters: {}: {c}",
+				custom.name
+			)));
+		}
+
+		if !names.insert(custom.name.as_str()) {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition name is used more than once: {}",
+				custom.name
+			)));
+		}
+
+		if !(MIN_SIDE..=MAX_SIDE).contains(&custom.side) {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition side must be between {MIN_SIDE} and {MAX_SIDE}"
+			)));
+		}
+
+		if !(0..=MAX_FPS).contains(&custom.max_fps) {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition max_fps must be between 0 and {MAX_FPS}"
+			)));
+		}
+
+		if !(1..=MAX_BITRATE).contains(&custom.max_bitrate) {
+			return Err(Status::invalid_argument(format!(
+				"custom rendition max_bitrate must be between 1 and {MAX_BITRATE}"
+			)));
+		}
+
+		if custom_rendition::Codec::try_from(custom.codec).is_err() {
+			return Err(Status::invalid_argument("custom rendition codec is not supported"));
+		}
+	}
+
+	Ok(())
+}
+
+pub fn validate_overlays(overlays: &[Overlay]) -> tonic::Result<()> {
+	if overlays.len() > MAX_OVERLAYS {
+		return Err(Status::invalid_argument(format!(
+			"at most {MAX_OVERLAYS} overlays can be defined"
+		)));
+	}
+
+	for overlay in overlays {
+		match &overlay.content {
+			None => return Err(Status::invalid_argument("overlay content is required")),
+			Some(overlay::Content::Image(image)) => {
+				if image.s3_bucket_id.is_none() {
+					return Err(Status::invalid_argument("overlay image s3_bucket_id is required"));
+				}
+
+				if image.key.is_empty() || image.key.len() > MAX_IMAGE_KEY_LENGTH {
+					return Err(Status::invalid_argument(format!(
+						"overlay image key must be between 1 and {MAX_IMAGE_KEY_LENGTH} characters"
+					)));
+				}
+			}
+			Some(overlay::Content::LiveBadge(_) | overlay::Content::Timestamp(_)) => {}
+		}
+
+		if overlay::Position::try_from(overlay.position).is_err() {
+			return Err(Status::invalid_argument("overlay position is not supported"));
+		}
+
+		if !(overlay.size > 0.0 && overlay.size <= 1.0) {
+			return Err(Status::invalid_argument("overlay size must be greater than 0 and at most 1"));
+		}
+
+		if !(0.0..=MAX_MARGIN).contains(&overlay.margin) {
+			return Err(Status::invalid_argument(format!(
+				"overlay margin must be between 0 and {MAX_MARGIN}"
+			)));
+		}
+
+		if !(overlay.opacity > 0.0 && overlay.opacity <= 1.0) {
+			return Err(Status::invalid_argument(
+				"overlay opacity must be greater than 0 and at most 1",
+			));
+		}
+
+		// The source renditions are copied, so there is nothing to draw onto.
+		if overlay
+			.renditions()
+			.map(Rendition::from)
+			.any(|r| !r.is_video() || r == Rendition::VideoSource)
+		{
+			return Err(Status::invalid_argument(
+				"overlays can only be drawn onto transcoded video renditions",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+pub fn validate_loudness_normalization(loudness_normalization: &LoudnessNormalization) -> tonic::Result<()> {
+	if !INTEGRATED_LOUDNESS.contains(&loudness_normalization.integrated_loudness) {
+		return Err(Status::invalid_argument(format!(
+			"integrated loudness must be between {} and {} LUFS",
+			INTEGRATED_LOUDNESS.start(),
+			INTEGRATED_LOUDNESS.end()
+		)));
+	}
+
+	if !TRUE_PEAK.contains(&loudness_normalization.true_peak) {
+		return Err(Status::invalid_argument(format!(
+			"true peak must be between {} and {} dBTP",
+			TRUE_PEAK.start(),
+			TRUE_PEAK.end()
+		)));
+	}
+
+	if let Some(loudness_range) = loudness_normalization.loudness_range {
+		if !LOUDNESS_RANGE.contains(&loudness_range) {
+			return Err(Status::invalid_argument(format!(
+				"loudness range must be between {} and {} LU",
+				LOUDNESS_RANGE.start(),
+				LOUDNESS_RANGE.end()
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+pub fn validate_content_aware_encoding(content_aware_encoding: &ContentAwareEncoding) -> tonic::Result<()> {
+	if !BITRATE_RATIO.contains(&content_aware_encoding.min_bitrate_ratio) {
+		return Err(Status::invalid_argument(format!(
+			"min bitrate ratio must be between {} and {}",
+			BITRATE_RATIO.start(),
+			BITRATE_RATIO.end()
+		)));
+	}
+
+	if let Some(max_bitrate_ratio) = content_aware_encoding.max_bitrate_ratio {
+		if max_bitrate_ratio < content_aware_encoding.min_bitrate_ratio || max_bitrate_ratio > *BITRATE_RATIO.end() {
+			return Err(Status::invalid_argument(format!(
+				"max bitrate ratio must be between the min bitrate ratio and {}",
+				BITRATE_RATIO.end()
+			)));
+		}
+	}
+
+	if let Some(crf) = content_aware_encoding.crf {
+		if !CRF.contains(&crf) {
+			return Err(Status::invalid_argument(format!(
+				"crf must be between {} and {}",
+				CRF.start(),
+				CRF.end()
+			)));
+		}
+	}
+
+	if let Some(analysis_duration) = content_aware_encoding.analysis_duration {
+		if !ANALYSIS_DURATION.contains(&analysis_duration) {
+			return Err(Status::invalid_argument(format!(
+				"analysis duration must be between {} and {} seconds",
+				ANALYSIS_DURATION.start(),
+				ANALYSIS_DURATION.end()
+			)));
+		}
+	}
+
+	Ok(())
+}
@@ -55,6 +55,7 @@ impl std::str::FromStr for ResourcePermission {
 			"all" => None,
 			"access_token" => Some(Resource::AccessToken),
 			"events" => Some(Resource::Event),
+			"ingest_policy" => Some(Resource::IngestPolicy),
 			"playback_key_pair" => Some(Resource::PlaybackKeyPair),
 			"playback_session" => Some(Resource::PlaybackSession),
 			"recording" => Some(Resource::Recording),
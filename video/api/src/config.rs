@@ -18,6 +18,9 @@ pub struct ApiConfig {
 	/// The batch size for deleting recordings
 	pub recording_delete_batch_size: usize,
 
+	/// The subject to queue uploaded recordings for transcoding on
+	pub recording_upload_subject: String,
+
 	/// The events config
 	pub events: EventsConfig,
 
@@ -134,6 +137,7 @@ impl Default for ApiConfig {
 			events: EventsConfig::default(),
 			recording_delete_stream: "scuffle-video-recording_delete".to_string(),
 			recording_delete_batch_size: 1000,
+			recording_upload_subject: "scuffle-video-recording_uploads".to_string(),
 			rate_limit_rules: RatelimitRules::default(),
 		}
 	}
@@ -11,6 +11,13 @@ pub enum RateLimitResource {
 	EventsFetch,
 	EventsAck,
 
+	IngestPolicyGet,
+	IngestPolicyCreate,
+	IngestPolicyModify,
+	IngestPolicyDelete,
+	IngestPolicyTag,
+	IngestPolicyUntag,
+
 	PlaybackKeyPairGet,
 	PlaybackKeyPairCreate,
 	PlaybackKeyPairModify,
@@ -30,6 +37,7 @@ pub enum RateLimitResource {
 	RecordingConfigUntag,
 
 	RecordingGet,
+	RecordingUpload,
 	RecordingModify,
 	RecordingDelete,
 	RecordingTag,
@@ -72,6 +80,13 @@ impl RateLimitResource {
 			Self::EventsFetch => "events:subscribe",
 			Self::EventsAck => "events:ack",
 
+			Self::IngestPolicyGet => "ingest_policy:get",
+			Self::IngestPolicyCreate => "ingest_policy:create",
+			Self::IngestPolicyModify => "ingest_policy:modify",
+			Self::IngestPolicyDelete => "ingest_policy:delete",
+			Self::IngestPolicyTag => "ingest_policy:tag",
+			Self::IngestPolicyUntag => "ingest_policy:untag",
+
 			Self::PlaybackKeyPairGet => "playback_key_pair:get",
 			Self::PlaybackKeyPairCreate => "playback_key_pair:create",
 			Self::PlaybackKeyPairModify => "playback_key_pair:modify",
@@ -91,6 +106,7 @@ impl RateLimitResource {
 			Self::RecordingConfigUntag => "recording_config:untag",
 
 			Self::RecordingGet => "recording:get",
+			Self::RecordingUpload => "recording:upload",
 			Self::RecordingModify => "recording:modify",
 			Self::RecordingDelete => "recording:delete",
 			Self::RecordingTag => "recording:tag",
@@ -135,6 +151,13 @@ impl FromStr for RateLimitResource {
 
 			"events:subscribe" => Ok(Self::EventsFetch),
 
+			"ingest_policy:get" => Ok(Self::IngestPolicyGet),
+			"ingest_policy:create" => Ok(Self::IngestPolicyCreate),
+			"ingest_policy:modify" => Ok(Self::IngestPolicyModify),
+			"ingest_policy:delete" => Ok(Self::IngestPolicyDelete),
+			"ingest_policy:tag" => Ok(Self::IngestPolicyTag),
+			"ingest_policy:untag" => Ok(Self::IngestPolicyUntag),
+
 			"playback_key_pair:get" => Ok(Self::PlaybackKeyPairGet),
 			"playback_key_pair:create" => Ok(Self::PlaybackKeyPairCreate),
 			"playback_key_pair:modify" => Ok(Self::PlaybackKeyPairModify),
@@ -152,6 +175,7 @@ impl FromStr for RateLimitResource {
 			"recording_config:tag" => Ok(Self::RecordingConfigTag),
 			"recording_config:untag" => Ok(Self::RecordingConfigUntag),
 			"recording:get" => Ok(Self::RecordingGet),
+			"recording:upload" => Ok(Self::RecordingUpload),
 			"recording:modify" => Ok(Self::RecordingModify),
 			"recording:delete" => Ok(Self::RecordingDelete),
 			"recording:tag" => Ok(Self::RecordingTag),
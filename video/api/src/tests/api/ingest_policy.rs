@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use pb::scuffle::video::v1::ingest_policy_modify_request::CodecList;
+use pb::scuffle::video::v1::types::{SearchOptions, Tags};
+use pb::scuffle::video::v1::{
+	IngestPolicyCreateRequest, IngestPolicyCreateResponse, IngestPolicyDeleteRequest, IngestPolicyDeleteResponse,
+	IngestPolicyGetRequest, IngestPolicyGetResponse, IngestPolicyModifyRequest, IngestPolicyModifyResponse,
+	RoomModifyRequest, RoomModifyResponse,
+};
+
+use crate::api::ingest_policy;
+use crate::tests::api::utils::{assert_query_matches, create_ingest_policy, create_room, process_request};
+use crate::tests::utils;
+
+#[tokio::test]
+async fn test_ingest_policy_get_qb() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let test_cases = vec![
+		(
+			IngestPolicyGetRequest {
+				ids: vec![access_token.organization_id.into()],
+				search_options: None,
+			},
+			Ok("SELECT * FROM ingest_policies WHERE organization_id = $1 AND id = ANY($2) ORDER BY id ASC LIMIT 100"),
+		),
+		(
+			IngestPolicyGetRequest {
+				ids: vec![],
+				search_options: Some(SearchOptions {
+					limit: 10,
+					reverse: true,
+					after_id: Some(access_token.organization_id.into()),
+					tags: None,
+				}),
+			},
+			Ok("SELECT * FROM ingest_policies WHERE organization_id = $1 AND id < $2 ORDER BY id DESC LIMIT $3"),
+		),
+	];
+
+	for (req, expected) in test_cases {
+		let result = ingest_policy::get::build_query(&req, &access_token);
+		assert_query_matches(result, expected);
+	}
+
+	utils::teardown(global, handler).await;
+}
+
+#[tokio::test]
+async fn test_ingest_policy_create_qb() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let test_cases = vec![
+		(
+			IngestPolicyCreateRequest {
+				max_bitrate: Some(6000 * 1024),
+				max_width: Some(1920),
+				max_height: Some(1080),
+				max_fps: Some(60),
+				allowed_video_codecs: vec!["avc1".to_string()],
+				..Default::default()
+			},
+			Ok(
+				"INSERT INTO ingest_policies (id,organization_id,max_bitrate,max_width,max_height,max_fps,allowed_video_codecs,allowed_audio_codecs,max_bytes_between_keyframes,max_time_between_keyframes,organization_default,tags) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12) RETURNING *",
+			),
+		),
+		(
+			IngestPolicyCreateRequest {
+				max_bitrate: Some(0),
+				..Default::default()
+			},
+			Err("max_bitrate must be greater than 0"),
+		),
+		(
+			IngestPolicyCreateRequest {
+				allowed_video_codecs: vec!["vp8".to_string()],
+				..Default::default()
+			},
+			Err("allowed_video_codecs must only contain avc1, hev1, av01"),
+		),
+		(
+			IngestPolicyCreateRequest {
+				allowed_audio_codecs: vec!["avc1".to_string()],
+				..Default::default()
+			},
+			Err("allowed_audio_codecs must only contain mp4a, opus"),
+		),
+	];
+
+	for (req, expected) in test_cases {
+		assert!(ingest_policy::create::validate(&req).is_ok());
+		let result = ingest_policy::create::build_query(&req, &access_token);
+		assert_query_matches(result, expected);
+	}
+
+	utils::teardown(global, handler).await;
+}
+
+#[tokio::test]
+async fn test_ingest_policy_modify_qb() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let test_cases = vec![
+		(
+			IngestPolicyModifyRequest {
+				id: Some(access_token.id.into()),
+				max_bitrate: Some(6000 * 1024),
+				max_fps: Some(0),
+				..Default::default()
+			},
+			Ok(
+				"UPDATE ingest_policies SET max_bitrate = $1,max_fps = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
+			),
+		),
+		(
+			IngestPolicyModifyRequest {
+				id: Some(access_token.id.into()),
+				allowed_audio_codecs: Some(CodecList { items: vec![] }),
+				organization_default: Some(true),
+				..Default::default()
+			},
+			Ok(
+				"UPDATE ingest_policies SET allowed_audio_codecs = $1,organization_default = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
+			),
+		),
+		(
+			IngestPolicyModifyRequest {
+				id: Some(access_token.id.into()),
+				max_width: Some(-1),
+				..Default::default()
+			},
+			Err("max_width must not be negative"),
+		),
+		(
+			IngestPolicyModifyRequest {
+				id: Some(access_token.id.into()),
+				..Default::default()
+			},
+			Err("at least one field must be set to modify"),
+		),
+	];
+
+	for (req, expected) in test_cases {
+		assert!(ingest_policy::modify::validate(&req).is_ok());
+		let result = ingest_policy::modify::build_query(&req, &access_token);
+		assert_query_matches(result, expected);
+	}
+
+	utils::teardown(global, handler).await;
+}
+
+#[tokio::test]
+async fn test_ingest_policy_create() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let response: IngestPolicyCreateResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyCreateRequest {
+			max_bitrate: Some(6000 * 1024),
+			allowed_video_codecs: vec!["AVC1".to_string(), "avc1".to_string(), "hev1".to_string()],
+			organization_default: true,
+			tags: Some(Tags {
+				tags: vec![("tag_key".to_string(), "tag_value".to_string())].into_iter().collect(),
+			}),
+			..Default::default()
+		},
+	)
+	.await
+	.unwrap();
+	let first = response.ingest_policy.unwrap();
+	assert_eq!(first.max_bitrate, Some(6000 * 1024));
+	assert_eq!(first.max_width, None);
+	assert_eq!(first.allowed_video_codecs, vec!["avc1".to_string(), "hev1".to_string()]);
+	assert!(first.allowed_audio_codecs.is_empty());
+	assert!(first.organization_default);
+	assert_eq!(first.tags.unwrap().tags.get("tag_key").unwrap(), "tag_value");
+
+	// A new default policy replaces the old one.
+	let response: IngestPolicyCreateResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyCreateRequest {
+			organization_default: true,
+			..Default::default()
+		},
+	)
+	.await
+	.unwrap();
+	let second = response.ingest_policy.unwrap();
+	assert!(second.organization_default);
+
+	let response: IngestPolicyGetResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyGetRequest {
+			ids: vec![first.id.unwrap()],
+			search_options: None,
+		},
+	)
+	.await
+	.unwrap();
+	assert!(!response.ingest_policies[0].organization_default);
+
+	utils::teardown(global, handler).await;
+}
+
+#[tokio::test]
+async fn test_ingest_policy_modify() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let ingest_policy = create_ingest_policy(&global, access_token.organization_id, HashMap::new()).await;
+
+	let response: IngestPolicyModifyResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyModifyRequest {
+			id: Some(ingest_policy.id.into()),
+			max_height: Some(720),
+			max_time_between_keyframes: Some(4000),
+			allowed_audio_codecs: Some(CodecList {
+				items: vec!["opus".to_string()],
+			}),
+			..Default::default()
+		},
+	)
+	.await
+	.unwrap();
+	let modified = response.ingest_policy.unwrap();
+	assert_eq!(modified.max_height, Some(720));
+	assert_eq!(modified.max_time_between_keyframes, Some(4000));
+	assert_eq!(modified.allowed_audio_codecs, vec!["opus".to_string()]);
+
+	let response: IngestPolicyModifyResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyModifyRequest {
+			id: Some(ingest_policy.id.into()),
+			max_height: Some(0),
+			..Default::default()
+		},
+	)
+	.await
+	.unwrap();
+	let modified = response.ingest_policy.unwrap();
+	assert_eq!(modified.max_height, None, "a limit of 0 removes the limit");
+	assert_eq!(modified.max_time_between_keyframes, Some(4000));
+
+	utils::teardown(global, handler).await;
+}
+
+#[tokio::test]
+async fn test_ingest_policy_delete() {
+	let (global, handler, access_token) = utils::setup(Default::default()).await;
+
+	let used = create_ingest_policy(&global, access_token.organization_id, HashMap::new()).await;
+	let unused = create_ingest_policy(&global, access_token.organization_id, HashMap::new()).await;
+	let room = create_room(&global, access_token.organization_id).await;
+
+	let _: RoomModifyResponse = process_request(
+		&global,
+		&access_token,
+		RoomModifyRequest {
+			id: Some(room.id.into()),
+			ingest_policy_id: Some(used.id.into()),
+			..Default::default()
+		},
+	)
+	.await
+	.unwrap();
+
+	let response: IngestPolicyDeleteResponse = process_request(
+		&global,
+		&access_token,
+		IngestPolicyDeleteRequest {
+			ids: vec![used.id.into(), unused.id.into()],
+		},
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(response.ids.len(), 1);
+	assert_eq!(response.ids[0].into_ulid(), unused.id);
+	assert_eq!(response.failed_deletes.len(), 1);
+	assert_eq!(response.failed_deletes[0].id.unwrap().into_ulid(), used.id);
+	assert_eq!(response.failed_deletes[0].reason, "ingest policy is in use by a room");
+
+	utils::teardown(global, handler).await;
+}
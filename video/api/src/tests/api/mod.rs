@@ -1,5 +1,6 @@
 mod access_token;
 mod events;
+mod ingest_policy;
 mod playback_key_pair;
 mod playback_session;
 mod recording;
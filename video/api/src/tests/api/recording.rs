@@ -10,6 +10,7 @@ use pb::scuffle::video::v1::types::{Tags, Visibility};
 use pb::scuffle::video::v1::{
 	RecordingDeleteRequest, RecordingDeleteResponse, RecordingGetRequest, RecordingGetResponse, RecordingModifyRequest,
 	RecordingModifyResponse, RecordingTagRequest, RecordingTagResponse, RecordingUntagRequest, RecordingUntagResponse,
+	RecordingUploadRequest, RecordingUploadResponse,
 };
 use ulid::Ulid;
 use video_common::database::{AccessToken, Rendition};
@@ -17,8 +18,9 @@ use video_common::database::{AccessToken, Rendition};
 use crate::api::recording::RecordingServer;
 use crate::config::ApiConfig;
 use crate::tests::api::utils::{
-	create_recording, create_recording_config, create_recording_segment, create_recording_thumbnail, create_room,
-	create_s3_bucket, process_request,
+	create_recording, create_recording_caption_segment, create_recording_config, create_recording_segment,
+	create_recording_sprite, create_recording_thumbnail, create_room, create_s3_bucket, create_transcoding_config,
+	process_request,
 };
 use crate::tests::global::GlobalState;
 use crate::tests::utils;
@@ -326,6 +328,128 @@ async fn test_recording_untag() {
 	utils::teardown(global, handler).await;
 }
 
+#[tokio::test]
+async fn test_recording_upload() {
+	let recording_upload_subject = Ulid::new().to_string();
+
+	let (global, handler, access_token) = utils::setup(ApiConfig {
+		recording_upload_subject: recording_upload_subject.clone(),
+		..Default::default()
+	})
+	.await;
+
+	let s3_bucket = create_s3_bucket(&global, access_token.organization_id, HashMap::new()).await;
+	let recording_config =
+		create_recording_config(&global, access_token.organization_id, s3_bucket.id, HashMap::new()).await;
+	let transcoding_config = create_transcoding_config(&global, access_token.organization_id, HashMap::new()).await;
+
+	let request = RecordingUploadRequest {
+		source: Some(pb::scuffle::video::v1::recording_upload_request::Source::S3Object(
+			pb::scuffle::video::v1::recording_upload_request::S3Object {
+				s3_bucket_id: Some(s3_bucket.id.into()),
+				key: "uploads/video.mp4".to_string(),
+			},
+		)),
+		recording_config_id: Some(recording_config.id.into()),
+		transcoding_config_id: Some(transcoding_config.id.into()),
+		visibility: Visibility::Private.into(),
+		tags: Some(Tags {
+			tags: vec![("key".to_string(), "value".to_string())].into_iter().collect(),
+		}),
+	};
+
+	// No transcoder has created the queue yet
+	let err = process_request::<_, RecordingUploadResponse>(&global, &access_token, request.clone())
+		.await
+		.unwrap_err();
+	assert_eq!(err.code(), tonic::Code::Unavailable, "expected unavailable");
+
+	let resp: RecordingGetResponse = process_request(&global, &access_token, RecordingGetRequest::default())
+		.await
+		.unwrap();
+	assert!(resp.recordings.is_empty(), "expected no recording to be created");
+
+	global
+		.jetstream()
+		.get_or_create_stream(async_nats::jetstream::stream::Config {
+			name: recording_upload_subject.clone(),
+			subjects: vec![recording_upload_subject.clone()],
+			retention: async_nats::jetstream::stream::RetentionPolicy::WorkQueue,
+			storage: async_nats::jetstream::stream::StorageType::Memory,
+			..Default::default()
+		})
+		.await
+		.unwrap();
+
+	let mut stream_listener = global.nats().subscribe(recording_upload_subject).await.unwrap();
+
+	let resp: RecordingUploadResponse = process_request(&global, &access_token, request.clone()).await.unwrap();
+
+	let recording = resp.recording.unwrap();
+	assert!(recording.room_id.is_none(), "expected no room id");
+	assert_eq!(
+		recording.recording_config_id.into_ulid(),
+		recording_config.id,
+		"expected recording config id to match"
+	);
+	assert_eq!(
+		recording.s3_bucket_id.into_ulid(),
+		s3_bucket.id,
+		"expected s3 bucket id to match"
+	);
+	assert_eq!(
+		recording.visibility,
+		Visibility::Private as i32,
+		"expected visibility to match"
+	);
+	assert!(recording.renditions.is_empty(), "expected no renditions before transcoding");
+	assert!(recording.ended_at.is_none(), "expected recording not to be ended");
+	assert_eq!(
+		recording.tags.unwrap().tags.get("key").unwrap(),
+		"value",
+		"expected tag to match"
+	);
+
+	let msg = stream_listener
+		.next()
+		.timeout(Duration::from_secs(1))
+		.await
+		.expect("expected upload task")
+		.unwrap();
+	let task: pb::scuffle::video::internal::events::RecordingUploadTask = prost::Message::decode(msg.payload).unwrap();
+
+	assert_eq!(task.organization_id.into_ulid(), access_token.organization_id);
+	assert_eq!(task.recording_id.into_ulid(), recording.id.into_ulid());
+	assert_eq!(task.s3_bucket_id.into_ulid(), s3_bucket.id);
+	assert_eq!(task.key, "uploads/video.mp4");
+	assert!(!task.delete_source, "expected the s3 object to be kept");
+	assert_eq!(task.recording_config.unwrap().id.into_ulid(), recording_config.id);
+	assert_eq!(task.transcoding_config.unwrap().id.into_ulid(), transcoding_config.id);
+
+	let err = process_request::<_, RecordingUploadResponse>(
+		&global,
+		&access_token,
+		RecordingUploadRequest {
+			recording_config_id: Some(Ulid::new().into()),
+			..request.clone()
+		},
+	)
+	.await
+	.unwrap_err();
+	assert_eq!(err.code(), tonic::Code::NotFound, "expected recording config not found");
+
+	let err = process_request::<_, RecordingUploadResponse>(
+		&global,
+		&access_token,
+		RecordingUploadRequest { source: None, ..request },
+	)
+	.await
+	.unwrap_err();
+	assert_eq!(err.code(), tonic::Code::InvalidArgument, "expected source to be required");
+
+	utils::teardown(global, handler).await;
+}
+
 #[tokio::test]
 async fn test_recording_delete() {
 	let recording_delete_stream = Ulid::new().to_string();
@@ -361,12 +485,39 @@ async fn test_recording_delete() {
 	.map(|t| (t.id, t.idx))
 	.collect::<HashSet<_>>();
 
+	let mut sprites = create_recording_sprite(
+		&global,
+		access_token.organization_id,
+		recording.id,
+		(0..173).map(|i| {
+			let tile_times = (0..25).map(|t| (i * 25 + t) as f32 * 5.0).collect::<Vec<_>>();
+			(i, tile_times, (i + 1) as f32 * 125.0)
+		}),
+	)
+	.await
+	.into_iter()
+	.map(|s| (s.id, s.idx))
+	.collect::<HashSet<_>>();
+
+	let mut captions = create_recording_caption_segment(
+		&global,
+		access_token.organization_id,
+		recording.id,
+		(0..900).map(|i| (i, i as f32 * 2.0, i as f32 * 2.0 + 2.0)),
+	)
+	.await
+	.into_iter()
+	.map(|c| (c.id, c.idx))
+	.collect::<HashSet<_>>();
+
 	let mut segments = create_recording_segment(
 		&global,
 		access_token.organization_id,
 		recording.id,
-		Rendition::variants()
-			.into_iter()
+		recording_config
+			.renditions
+			.iter()
+			.copied()
 			.flat_map(|rendition| (0..10_800).map(move |i| (rendition, i, i as f32 * 2.0, i as f32 * 2.0 + 2.0))),
 	)
 	.await
@@ -426,11 +577,35 @@ async fn test_recording_delete() {
 					)
 				}
 			}
+			pb::scuffle::video::internal::events::recording_delete_batch_task::ObjectsType::Sprites(_) => {
+				for obj in msg.objects {
+					assert!(
+						sprites.remove(&(obj.object_id.into_ulid(), obj.index)),
+						"expected sprite to be deleted"
+					)
+				}
+			}
+			pb::scuffle::video::internal::events::recording_delete_batch_task::ObjectsType::Captions(_) => {
+				for obj in msg.objects {
+					assert!(
+						captions.remove(&(obj.object_id.into_ulid(), obj.index)),
+						"expected caption segment to be deleted"
+					)
+				}
+			}
+			pb::scuffle::video::internal::events::recording_delete_batch_task::ObjectsType::SourceChunks(_) => {
+				panic!("expected no source chunks to be deleted")
+			}
 		}
 	}
 
-	assert_eq!(count, 60, "expected 60 messages");
+	// 4320 thumbnails, 173 sprites, 900 caption segments and 10800 segments per
+	// rendition of the recording config, in batches of 1000
+	let expected = 5 + 1 + 1 + recording_config.renditions.len() * 11;
+	assert_eq!(count, expected, "expected {expected} messages");
 	assert!(thumbnails.is_empty(), "expected all thumbnails to be deleted");
+	assert!(sprites.is_empty(), "expected all sprites to be deleted");
+	assert!(captions.is_empty(), "expected all caption segments to be deleted");
 	assert!(segments.is_empty(), "expected all segments to be deleted");
 
 	utils::teardown(global, handler).await;
@@ -494,6 +669,22 @@ async fn test_recording_boiler_plate() {
 	assert_eq!(response.code(), tonic::Code::PermissionDenied);
 	assert_eq!(response.message(), "missing required scope: recording:read");
 
+	let response = server
+		.upload(build_request(
+			&global,
+			&no_scopes_token,
+			RecordingUploadRequest {
+				source: Some(pb::scuffle::video::v1::recording_upload_request::Source::Data(
+					vec![0; 16].into(),
+				)),
+				..Default::default()
+			},
+		))
+		.await
+		.unwrap_err();
+	assert_eq!(response.code(), tonic::Code::PermissionDenied);
+	assert_eq!(response.message(), "missing required scope: recording:create");
+
 	let response = server
 		.modify(build_request(
 			&global,
@@ -73,9 +73,10 @@ async fn test_recording_config_create_qb() {
 				pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
+			capture_source: false,
 		},
 		Ok(
-			"INSERT INTO recording_configs (id,organization_id,renditions,lifecycle_policies,updated_at,s3_bucket_id,tags) VALUES ($1,$2,$3,$4,$5,$6,$7) RETURNING *",
+			"INSERT INTO recording_configs (id,organization_id,renditions,lifecycle_policies,updated_at,s3_bucket_id,tags,capture_source) VALUES ($1,$2,$3,$4,$5,$6,$7,$8) RETURNING *",
 		),
 	)];
 
@@ -102,6 +103,7 @@ async fn test_recording_config_modify_qb() {
 				lifecycle_policies: Some(LifecyclePolicyList { items: vec![] }),
 				stored_renditions: None,
 				s3_bucket_id: None,
+				capture_source: None,
 			},
 			Ok(
 				"UPDATE recording_configs SET lifecycle_policies = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
@@ -118,6 +120,7 @@ async fn test_recording_config_modify_qb() {
 				lifecycle_policies: None,
 				stored_renditions: None,
 				s3_bucket_id: None,
+				capture_source: None,
 			},
 			Ok(
 				"UPDATE recording_configs SET tags = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
@@ -139,6 +142,7 @@ async fn test_recording_config_modify_qb() {
 						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 					],
 				}),
+				capture_source: None,
 			},
 			Ok(
 				"UPDATE recording_configs SET renditions = $1,tags = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
@@ -151,11 +155,25 @@ async fn test_recording_config_modify_qb() {
 				lifecycle_policies: None,
 				s3_bucket_id: Some(s3_bucket.id.into()),
 				stored_renditions: None,
+				capture_source: None,
 			},
 			Ok(
 				"UPDATE recording_configs SET s3_bucket_id = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
 			),
 		),
+		(
+			RecordingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				lifecycle_policies: None,
+				s3_bucket_id: None,
+				stored_renditions: None,
+				capture_source: Some(true),
+			},
+			Ok(
+				"UPDATE recording_configs SET capture_source = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
 		(
 			RecordingConfigModifyRequest {
 				id: Some(access_token.id.into()),
@@ -163,6 +181,7 @@ async fn test_recording_config_modify_qb() {
 				lifecycle_policies: None,
 				stored_renditions: None,
 				s3_bucket_id: None,
+				capture_source: None,
 			},
 			Err("at least one field must be set to modify"),
 		),
@@ -312,6 +331,7 @@ async fn test_recording_config_create() {
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
 			tags: None,
+			capture_source: false,
 		},
 	)
 	.await
@@ -330,6 +350,7 @@ async fn test_recording_config_create() {
 					pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 					pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 				],
+				source: true,
 			}],
 			s3_bucket_id: Some(s3_bucket.id.into()),
 			stored_renditions: vec![
@@ -339,6 +360,7 @@ async fn test_recording_config_create() {
 			tags: Some(Tags {
 				tags: vec![("tag_key".to_string(), "tag_value".to_string())].into_iter().collect(),
 			}),
+			capture_source: true,
 		},
 	)
 	.await
@@ -346,6 +368,7 @@ async fn test_recording_config_create() {
 	let created = response.recording_config.as_ref().unwrap();
 	assert_eq!(created.tags.as_ref().unwrap().tags.get("tag_key").unwrap(), "tag_value");
 	assert_eq!(created.tags.as_ref().unwrap().tags.len(), 1, "1 tag");
+	assert!(created.capture_source);
 	assert_eq!(created.lifecycle_policies.len(), 1, "1 lifecycle policy");
 	assert_eq!(
 		created.lifecycle_policies[0],
@@ -356,6 +379,7 @@ async fn test_recording_config_create() {
 				pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
+			source: true,
 		}
 	);
 
@@ -391,6 +415,7 @@ async fn test_recording_config_modify() {
 				tags: vec![("key3".to_string(), "value3".to_string())].into_iter().collect(),
 			}),
 			s3_bucket_id: None,
+			capture_source: None,
 		},
 	)
 	.await
@@ -417,10 +442,12 @@ async fn test_recording_config_modify() {
 						pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 					],
+					source: false,
 				}],
 			}),
 			stored_renditions: None,
 			tags: None,
+			capture_source: None,
 		},
 	)
 	.await
@@ -437,6 +464,7 @@ async fn test_recording_config_modify() {
 				pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
+			source: false,
 		}
 	);
 
@@ -663,6 +691,7 @@ async fn test_recording_config_boiler_plate() {
 					pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 				],
 				tags: None,
+				capture_source: false,
 			},
 		))
 		.await
@@ -773,6 +802,7 @@ async fn test_recording_config_boiler_plate() {
 				lifecycle_policies: None,
 				s3_bucket_id: None,
 				stored_renditions: None,
+				capture_source: None,
 			},
 		))
 		.await
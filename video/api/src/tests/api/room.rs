@@ -4,7 +4,7 @@ use std::sync::Arc;
 use ::utils::prelude::FutureTimeout;
 use binary_helper::global::{GlobalDb, GlobalNats};
 use futures_util::StreamExt;
-use pb::scuffle::video::v1::types::{SearchOptions, Tags};
+use pb::scuffle::video::v1::types::{IpFilter, SearchOptions, Tags};
 use pb::scuffle::video::v1::{
 	RoomCreateRequest, RoomCreateResponse, RoomDeleteRequest, RoomDeleteResponse, RoomDisconnectRequest,
 	RoomDisconnectResponse, RoomGetRequest, RoomGetResponse, RoomModifyRequest, RoomModifyResponse, RoomResetKeyRequest,
@@ -15,7 +15,8 @@ use video_common::database::{AccessToken, RoomStatus, Visibility};
 
 use crate::api::room::{self, RoomServer};
 use crate::tests::api::utils::{
-	assert_query_matches, create_recording_config, create_room, create_s3_bucket, create_transcoding_config, process_request,
+	assert_query_matches, create_ingest_policy, create_recording_config, create_room, create_s3_bucket,
+	create_transcoding_config, process_request,
 };
 use crate::tests::global::GlobalState;
 use crate::tests::utils;
@@ -32,6 +33,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: None,
 				status: None,
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok("SELECT * FROM rooms WHERE organization_id = $1 AND id = ANY($2) ORDER BY id ASC LIMIT 100"),
@@ -43,6 +45,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: None,
 				status: None,
+				ingest_policy_id: None,
 				search_options: Some(SearchOptions {
 					limit: 1,
 					reverse: true,
@@ -59,6 +62,7 @@ async fn test_room_get_qb() {
 				recording_config_id: Some(access_token.id.into()),
 				visibility: None,
 				status: None,
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok(
@@ -72,6 +76,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: Some(pb::scuffle::video::v1::types::Visibility::Public as i32),
 				status: None,
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok("SELECT * FROM rooms WHERE organization_id = $1 AND visibility = $2 ORDER BY id ASC LIMIT 100"),
@@ -83,6 +88,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: Some(pb::scuffle::video::v1::types::Visibility::Private as i32),
 				status: None,
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok("SELECT * FROM rooms WHERE organization_id = $1 AND visibility = $2 ORDER BY id ASC LIMIT 100"),
@@ -94,6 +100,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: None,
 				status: Some(pb::scuffle::video::v1::types::RoomStatus::Ready as i32),
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok("SELECT * FROM rooms WHERE organization_id = $1 AND status = $2 ORDER BY id ASC LIMIT 100"),
@@ -105,6 +112,7 @@ async fn test_room_get_qb() {
 				recording_config_id: None,
 				visibility: None,
 				status: Some(pb::scuffle::video::v1::types::RoomStatus::Offline as i32),
+				ingest_policy_id: None,
 				search_options: None,
 			},
 			Ok("SELECT * FROM rooms WHERE organization_id = $1 AND status = $2 ORDER BY id ASC LIMIT 100"),
@@ -127,6 +135,7 @@ async fn test_room_create_qb() {
 	let recording_config =
 		create_recording_config(&global, access_token.organization_id, s3_bucket.id, HashMap::new()).await;
 	let transcoding_config = create_transcoding_config(&global, access_token.organization_id, HashMap::new()).await;
+	let ingest_policy = create_ingest_policy(&global, access_token.organization_id, HashMap::new()).await;
 
 	let test_cases = vec![
 		(
@@ -135,9 +144,12 @@ async fn test_room_create_qb() {
 				recording_config_id: None,
 				visibility: pb::scuffle::video::v1::types::Visibility::Public as i32,
 				tags: None,
+				source_url: None,
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
 			},
 			Ok(
-				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags) VALUES ($1,$2,$3,$4,$5,$6,$7) RETURNING *",
+				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags,source_url,ingest_policy_id,ingest_ip_allow,ingest_ip_deny) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11) RETURNING *",
 			),
 		),
 		(
@@ -146,9 +158,35 @@ async fn test_room_create_qb() {
 				recording_config_id: Some(recording_config.id.into()),
 				visibility: pb::scuffle::video::v1::types::Visibility::Public as i32,
 				tags: None,
+				source_url: None,
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
 			},
 			Ok(
-				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags) VALUES ($1,$2,$3,$4,$5,$6,$7) RETURNING *",
+				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags,source_url,ingest_policy_id,ingest_ip_allow,ingest_ip_deny) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11) RETURNING *",
+			),
+		),
+		(
+			RoomCreateRequest {
+				transcoding_config_id: None,
+				recording_config_id: None,
+				visibility: pb::scuffle::video::v1::types::Visibility::Public as i32,
+				tags: None,
+				source_url: Some("rtmp://example.com/live/stream".to_string()),
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
+			},
+			Ok(
+				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags,source_url,ingest_policy_id,ingest_ip_allow,ingest_ip_deny) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11) RETURNING *",
+			),
+		),
+		(
+			RoomCreateRequest {
+				ingest_policy_id: Some(ingest_policy.id.into()),
+				..Default::default()
+			},
+			Ok(
+				"INSERT INTO rooms (id,organization_id,transcoding_config_id,recording_config_id,visibility,stream_key,tags,source_url,ingest_policy_id,ingest_ip_allow,ingest_ip_deny) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11) RETURNING *",
 			),
 		),
 	];
@@ -162,6 +200,92 @@ async fn test_room_create_qb() {
 	utils::teardown(global, handler).await;
 }
 
+#[test]
+fn test_room_source_url_validate() {
+	let valid = [
+		"rtmp://example.com/live/stream",
+		"rtmps://example.com:443/live/stream",
+		"http://example.com/live/stream.flv",
+		"https://example.com/live/stream.m3u8",
+	];
+
+	for source_url in valid {
+		let req = RoomCreateRequest {
+			source_url: Some(source_url.to_string()),
+			..Default::default()
+		};
+		assert!(room::create::validate(&req).is_ok(), "{source_url} should be valid");
+	}
+
+	let invalid = [
+		"".to_string(),
+		"not a url".to_string(),
+		"srt://example.com:9000".to_string(),
+		"file:///etc/passwd".to_string(),
+		format!("rtmp://example.com/{}", "a".repeat(2048)),
+	];
+
+	for source_url in invalid {
+		let req = RoomCreateRequest {
+			source_url: Some(source_url.clone()),
+			..Default::default()
+		};
+		assert!(room::create::validate(&req).is_err(), "{source_url} should be invalid");
+	}
+
+	// An empty source url on modify switches the room back to push ingest.
+	let req = RoomModifyRequest {
+		source_url: Some(String::new()),
+		..Default::default()
+	};
+	assert!(room::modify::validate(&req).is_ok());
+}
+
+#[test]
+fn test_room_ip_filter_validate() {
+	let valid = [
+		IpFilter::default(),
+		IpFilter {
+			allow: vec!["10.0.0.0/8".to_string(), "2001:db8::/32".to_string()],
+			deny: vec!["10.0.0.1".to_string()],
+		},
+	];
+
+	for ingest_ip_filter in valid {
+		let req = RoomCreateRequest {
+			ingest_ip_filter: Some(ingest_ip_filter.clone()),
+			..Default::default()
+		};
+		assert!(room::create::validate(&req).is_ok(), "{ingest_ip_filter:?} should be valid");
+	}
+
+	let invalid = [
+		IpFilter {
+			allow: vec!["not an ip".to_string()],
+			deny: vec![],
+		},
+		IpFilter {
+			allow: vec![],
+			deny: vec!["10.0.0.0/33".to_string()],
+		},
+		IpFilter {
+			allow: vec!["10.0.0.1".to_string(); 33],
+			deny: vec![],
+		},
+	];
+
+	for ingest_ip_filter in invalid {
+		let req = RoomModifyRequest {
+			ingest_ip_filter: Some(ingest_ip_filter.clone()),
+			..Default::default()
+		};
+		assert!(
+			room::modify::validate(&req).is_err(),
+			"{ingest_ip_filter:?} should be invalid"
+		);
+	}
+}
+
 #[tokio::test]
 async fn test_room_modify_qb() {
 	let (global, handler, access_token) = utils::setup(Default::default()).await;
@@ -170,6 +294,7 @@ async fn test_room_modify_qb() {
 	let recording_config =
 		create_recording_config(&global, access_token.organization_id, s3_bucket.id, HashMap::new()).await;
 	let transcoding_config = create_transcoding_config(&global, access_token.organization_id, HashMap::new()).await;
+	let ingest_policy = create_ingest_policy(&global, access_token.organization_id, HashMap::new()).await;
 	let room = create_room(&global, access_token.organization_id).await;
 
 	let test_cases = vec![
@@ -184,6 +309,9 @@ async fn test_room_modify_qb() {
 						.collect(),
 				}),
 				visibility: Some(pb::scuffle::video::v1::types::Visibility::Public as i32),
+				source_url: None,
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
 			},
 			Ok(
 				"UPDATE rooms SET transcoding_config_id = $1,recording_config_id = $2,visibility = $3,tags = $4,updated_at = NOW() WHERE id = $5 AND organization_id = $6 RETURNING *",
@@ -196,11 +324,61 @@ async fn test_room_modify_qb() {
 				transcoding_config_id: Some(Ulid::nil().into()),
 				tags: None,
 				visibility: None,
+				source_url: None,
+				ingest_policy_id: None,
+				ingest_ip_filter: None,
 			},
 			Ok(
 				"UPDATE rooms SET transcoding_config_id = NULL,recording_config_id = NULL,updated_at = NOW() WHERE id = $1 AND organization_id = $2 RETURNING *",
 			),
 		),
+		(
+			RoomModifyRequest {
+				id: Some(room.id.into()),
+				source_url: Some("https://example.com/live/stream.m3u8".to_string()),
+				..Default::default()
+			},
+			Ok(
+				"UPDATE rooms SET source_url = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
+		(
+			RoomModifyRequest {
+				id: Some(room.id.into()),
+				source_url: Some(String::new()),
+				..Default::default()
+			},
+			Ok("UPDATE rooms SET source_url = NULL,updated_at = NOW() WHERE id = $1 AND organization_id = $2 RETURNING *"),
+		),
+		(
+			RoomModifyRequest {
+				id: Some(room.id.into()),
+				ingest_policy_id: Some(ingest_policy.id.into()),
+				..Default::default()
+			},
+			Ok("UPDATE rooms SET ingest_policy_id = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *"),
+		),
+		(
+			RoomModifyRequest {
+				id: Some(room.id.into()),
+				ingest_policy_id: Some(Ulid::nil().into()),
+				..Default::default()
+			},
+			Ok("UPDATE rooms SET ingest_policy_id = NULL,updated_at = NOW() WHERE id = $1 AND organization_id = $2 RETURNING *"),
+		),
+		(
+			RoomModifyRequest {
+				id: Some(room.id.into()),
+				ingest_ip_filter: Some(IpFilter {
+					allow: vec!["10.0.0.0/8".to_string()],
+					deny: vec!["10.0.0.1".to_string()],
+				}),
+				..Default::default()
+			},
+			Ok(
+				"UPDATE rooms SET ingest_ip_allow = $1,ingest_ip_deny = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
+			),
+		),
 	];
 
 	for (req, expected) in test_cases {
@@ -283,6 +461,9 @@ async fn test_room_create() {
 					.into_iter()
 					.collect(),
 			}),
+			source_url: None,
+			ingest_policy_id: None,
+			ingest_ip_filter: None,
 		},
 	)
 	.await
@@ -371,6 +552,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: None,
 			status: None,
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -388,6 +570,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: None,
 			status: None,
+			ingest_policy_id: None,
 			search_options: Some(SearchOptions {
 				limit: 1,
 				reverse: true,
@@ -419,6 +602,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: Some(pb::scuffle::video::v1::types::Visibility::Private as i32),
 			status: None,
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -437,6 +621,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: Some(pb::scuffle::video::v1::types::Visibility::Public as i32),
 			status: None,
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -454,6 +639,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: None,
 			status: Some(pb::scuffle::video::v1::types::RoomStatus::Ready as i32),
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -480,6 +666,7 @@ async fn test_room_get() {
 			recording_config_id: None,
 			visibility: None,
 			status: Some(pb::scuffle::video::v1::types::RoomStatus::Ready as i32),
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -510,6 +697,7 @@ async fn test_room_get() {
 			recording_config_id: Some(recording_config.id.into()),
 			visibility: None,
 			status: None,
+			ingest_policy_id: None,
 			search_options: None,
 		},
 	)
@@ -545,6 +733,9 @@ async fn test_room_modify() {
 					.collect(),
 			}),
 			visibility: Some(pb::scuffle::video::v1::types::Visibility::Public as i32),
+			source_url: None,
+			ingest_policy_id: None,
+			ingest_ip_filter: None,
 		},
 	)
 	.await
@@ -590,6 +781,9 @@ async fn test_room_modify() {
 			transcoding_config_id: Some(Ulid::nil().into()),
 			tags: None,
 			visibility: None,
+			source_url: None,
+			ingest_policy_id: None,
+			ingest_ip_filter: None,
 		},
 	)
 	.await
@@ -2,8 +2,11 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use pb::ext::UlidExt;
-use pb::scuffle::video::v1::transcoding_config_modify_request::RenditionList;
-use pb::scuffle::video::v1::types::{SearchOptions, Tags};
+use pb::scuffle::video::v1::transcoding_config_modify_request::{CustomRenditionList, OverlayList, RenditionList};
+use pb::scuffle::video::v1::types::{
+	content_aware_encoding, overlay, ContentAwareEncoding, CustomRendition, LoudnessNormalization, Overlay, Rendition,
+	SearchOptions, Tags,
+};
 use pb::scuffle::video::v1::{
 	TranscodingConfigCreateRequest, TranscodingConfigCreateResponse, TranscodingConfigDeleteRequest,
 	TranscodingConfigDeleteResponse, TranscodingConfigGetRequest, TranscodingConfigGetResponse,
@@ -17,6 +20,46 @@ use crate::tests::api::utils::{assert_query_matches, create_transcoding_config,
 use crate::tests::global::GlobalState;
 use crate::tests::utils;
 
+fn custom_rendition(rendition: Rendition, name: &str) -> CustomRendition {
+	CustomRendition {
+		rendition: rendition as i32,
+		name: name.to_string(),
+		side: 1080,
+		max_fps: 60,
+		max_bitrate: 6000 * 1024,
+		codec: pb::scuffle::video::v1::types::custom_rendition::Codec::H264 as i32,
+	}
+}
+
+fn live_badge_overlay(renditions: &[Rendition]) -> Overlay {
+	Overlay {
+		content: Some(overlay::Content::LiveBadge(overlay::LiveBadge {})),
+		position: overlay::Position::TopRight as i32,
+		size: 0.05,
+		margin: 0.02,
+		opacity: 1.0,
+		renditions: renditions.iter().map(|r| *r as i32).collect(),
+	}
+}
+
+fn loudness_normalization(integrated_loudness: f64) -> LoudnessNormalization {
+	LoudnessNormalization {
+		integrated_loudness,
+		true_peak: -1.0,
+		loudness_range: None,
+	}
+}
+
+fn content_aware_encoding(min_bitrate_ratio: f64) -> ContentAwareEncoding {
+	ContentAwareEncoding {
+		mode: content_aware_encoding::Mode::Bitrate.into(),
+		min_bitrate_ratio,
+		max_bitrate_ratio: None,
+		crf: None,
+		analysis_duration: None,
+	}
+}
+
 #[tokio::test]
 async fn test_transcoding_config_get_qb() {
 	let (global, handler, access_token) = utils::setup(Default::default()).await;
@@ -68,8 +111,15 @@ async fn test_transcoding_config_create_qb() {
 				pb::scuffle::video::v1::types::Rendition::VideoSource as i32,
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
+			custom_renditions: vec![],
+			overlays: vec![],
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: false,
 		},
-		Ok("INSERT INTO transcoding_configs (id,organization_id,renditions,tags) VALUES ($1,$2,$3,$4) RETURNING *"),
+		Ok(
+			"INSERT INTO transcoding_configs (id,organization_id,renditions,tags,custom_renditions,overlays,loudness_normalization,content_aware_encoding,captions) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9) RETURNING *",
+		),
 	)];
 
 	for (req, expected) in test_cases {
@@ -96,6 +146,11 @@ async fn test_transcoding_config_modify_qb() {
 						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 					],
 				}),
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
 			},
 			Ok(
 				"UPDATE transcoding_configs SET renditions = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
@@ -110,6 +165,11 @@ async fn test_transcoding_config_modify_qb() {
 						.collect(),
 				}),
 				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
 			},
 			Ok(
 				"UPDATE transcoding_configs SET tags = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
@@ -129,16 +189,306 @@ async fn test_transcoding_config_modify_qb() {
 						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 					],
 				}),
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
 			},
 			Ok(
 				"UPDATE transcoding_configs SET renditions = $1,tags = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
 			),
 		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: Some(RenditionList {
+					items: vec![
+						pb::scuffle::video::v1::types::Rendition::VideoCustom1 as i32,
+						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
+					],
+				}),
+				custom_renditions: Some(CustomRenditionList {
+					items: vec![custom_rendition(Rendition::VideoCustom1, "1080p60")],
+				}),
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET renditions = $1,custom_renditions = $2,updated_at = NOW() WHERE id = $3 AND organization_id = $4 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: Some(RenditionList {
+					items: vec![
+						pb::scuffle::video::v1::types::Rendition::VideoCustom2 as i32,
+						pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
+					],
+				}),
+				custom_renditions: Some(CustomRenditionList {
+					items: vec![custom_rendition(Rendition::VideoCustom1, "1080p60")],
+				}),
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("video_custom_2 is not defined by a custom rendition"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: Some(CustomRenditionList {
+					items: vec![custom_rendition(Rendition::VideoHd, "720p")],
+				}),
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("custom renditions must use one of the VIDEO_CUSTOM renditions"),
+		),
 		(
 			TranscodingConfigModifyRequest {
 				id: Some(access_token.id.into()),
 				tags: None,
 				renditions: None,
+				custom_renditions: Some(CustomRenditionList {
+					items: vec![custom_rendition(Rendition::VideoCustom1, "1080p60"); 9],
+				}),
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("at most 8 custom renditions can be defined"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: Some(OverlayList {
+					items: vec![live_badge_overlay(&[Rendition::VideoHd])],
+				}),
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET overlays = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: Some(OverlayList {
+					items: vec![live_badge_overlay(&[Rendition::VideoSource])],
+				}),
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("overlays can only be drawn onto transcoded video renditions"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: Some(OverlayList {
+					items: vec![Overlay {
+						opacity: 0.0,
+						..live_badge_overlay(&[])
+					}],
+				}),
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("overlay opacity must be greater than 0 and at most 1"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: Some(OverlayList {
+					items: vec![Overlay {
+						content: Some(overlay::Content::Image(overlay::Image {
+							s3_bucket_id: None,
+							key: "logo.png".to_string(),
+						})),
+						..live_badge_overlay(&[])
+					}],
+				}),
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("overlay image s3_bucket_id is required"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: Some(loudness_normalization(-16.0)),
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET loudness_normalization = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: Some(loudness_normalization(0.0)),
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET loudness_normalization = NULL,updated_at = NOW() WHERE id = $1 AND organization_id = $2 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: Some(loudness_normalization(-2.0)),
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("integrated loudness must be between -70 and -5 LUFS"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: Some(LoudnessNormalization {
+					loudness_range: Some(0.5),
+					..loudness_normalization(-23.0)
+				}),
+				content_aware_encoding: None,
+				captions: None,
+			},
+			Err("loudness range must be between 1 and 50 LU"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: Some(content_aware_encoding(0.4)),
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET content_aware_encoding = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: Some(content_aware_encoding(0.0)),
+				captions: None,
+			},
+			Ok(
+				"UPDATE transcoding_configs SET content_aware_encoding = NULL,updated_at = NOW() WHERE id = $1 AND organization_id = $2 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: Some(ContentAwareEncoding {
+					max_bitrate_ratio: Some(0.3),
+					..content_aware_encoding(0.4)
+				}),
+				captions: None,
+			},
+			Err("max bitrate ratio must be between the min bitrate ratio and 1"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: Some(ContentAwareEncoding {
+					mode: content_aware_encoding::Mode::CappedCrf.into(),
+					crf: Some(63),
+					..content_aware_encoding(0.4)
+				}),
+				captions: None,
+			},
+			Err("crf must be between 0 and 51"),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: Some(true),
+			},
+			Ok(
+				"UPDATE transcoding_configs SET captions = $1,updated_at = NOW() WHERE id = $2 AND organization_id = $3 RETURNING *",
+			),
+		),
+		(
+			TranscodingConfigModifyRequest {
+				id: Some(access_token.id.into()),
+				tags: None,
+				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
 			},
 			Err("at least one field must be set to modify"),
 		),
@@ -280,6 +630,11 @@ async fn test_transcoding_config_create() {
 				pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 			],
 			tags: None,
+			custom_renditions: vec![],
+			overlays: vec![],
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: false,
 		},
 	)
 	.await
@@ -298,11 +653,19 @@ async fn test_transcoding_config_create() {
 			tags: Some(Tags {
 				tags: vec![("tag_key".to_string(), "tag_value".to_string())].into_iter().collect(),
 			}),
+			custom_renditions: vec![],
+			overlays: vec![],
+			loudness_normalization: Some(loudness_normalization(-16.0)),
+			content_aware_encoding: Some(content_aware_encoding(0.4)),
+			captions: true,
 		},
 	)
 	.await
 	.unwrap();
 	let created = response.transcoding_config.as_ref().unwrap();
+	assert_eq!(created.loudness_normalization, Some(loudness_normalization(-16.0)));
+	assert_eq!(created.content_aware_encoding, Some(content_aware_encoding(0.4)));
+	assert!(created.captions);
 	assert_eq!(created.tags.as_ref().unwrap().tags.get("tag_key").unwrap(), "tag_value");
 	assert_eq!(created.tags.as_ref().unwrap().tags.len(), 1, "1 tag");
 	assert_eq!(created.renditions.len(), 2, "1 lifecycle policy");
@@ -314,6 +677,60 @@ async fn test_transcoding_config_create() {
 		]
 	);
 
+	let response: TranscodingConfigCreateResponse = process_request(
+		&global,
+		&access_token,
+		TranscodingConfigCreateRequest {
+			renditions: vec![Rendition::VideoCustom1 as i32, Rendition::AudioSource as i32],
+			tags: None,
+			custom_renditions: vec![custom_rendition(Rendition::VideoCustom1, "1080p60")],
+			overlays: vec![],
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: false,
+		},
+	)
+	.await
+	.unwrap();
+	let created = response.transcoding_config.as_ref().unwrap();
+	assert_eq!(
+		created.renditions,
+		vec![Rendition::AudioSource as i32, Rendition::VideoCustom1 as i32]
+	);
+	assert_eq!(
+		created.custom_renditions,
+		vec![custom_rendition(Rendition::VideoCustom1, "1080p60")]
+	);
+
+	let response: TranscodingConfigCreateResponse = process_request(
+		&global,
+		&access_token,
+		TranscodingConfigCreateRequest {
+			renditions: vec![
+				Rendition::AudioOpus as i32,
+				Rendition::VideoSource as i32,
+				Rendition::AudioAacHe as i32,
+			],
+			tags: None,
+			custom_renditions: vec![],
+			overlays: vec![],
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: false,
+		},
+	)
+	.await
+	.unwrap();
+	let created = response.transcoding_config.as_ref().unwrap();
+	assert_eq!(
+		created.renditions,
+		vec![
+			Rendition::VideoSource as i32,
+			Rendition::AudioAacHe as i32,
+			Rendition::AudioOpus as i32,
+		]
+	);
+
 	utils::teardown(global, handler).await;
 }
 
@@ -342,6 +759,11 @@ async fn test_transcoding_config_modify() {
 			tags: Some(Tags {
 				tags: vec![("key3".to_string(), "value3".to_string())].into_iter().collect(),
 			}),
+			custom_renditions: None,
+			overlays: None,
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: None,
 		},
 	)
 	.await
@@ -366,6 +788,11 @@ async fn test_transcoding_config_modify() {
 				],
 			}),
 			tags: None,
+			custom_renditions: None,
+			overlays: None,
+			loudness_normalization: None,
+			content_aware_encoding: None,
+			captions: None,
 		},
 	)
 	.await
@@ -595,6 +1022,11 @@ async fn test_transcoding_config_boiler_plate() {
 					pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
 				],
 				tags: None,
+				custom_renditions: vec![],
+				overlays: vec![],
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: false,
 			},
 		))
 		.await
@@ -707,6 +1139,11 @@ async fn test_transcoding_config_boiler_plate() {
 					tags: vec![("key".to_string(), "value".to_string())].into_iter().collect(),
 				}),
 				renditions: None,
+				custom_renditions: None,
+				overlays: None,
+				loudness_normalization: None,
+				content_aware_encoding: None,
+				captions: None,
 			},
 		))
 		.await
@@ -118,6 +118,77 @@ pub async fn create_recording_thumbnail(
 	results
 }
 
+pub async fn create_recording_sprite(
+	global: &Arc<GlobalState>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	inserts: impl Iterator<Item = (i32, Vec<f32>, f32)>,
+) -> Vec<video_common::database::RecordingSprite> {
+	let mut results = Vec::new();
+
+	let client = global.db().get().await.unwrap();
+
+	for inserts in &inserts.chunks(u16::MAX as usize / 11) {
+		let mut qb = utils::database::QueryBuilder::default();
+
+		qb.push(
+			"INSERT INTO recording_sprites (organization_id, recording_id, idx, id, start_time, end_time, tile_times, tile_width, tile_height, columns, rows) ",
+		);
+
+		qb.push_values(inserts, |mut qb, values| {
+			qb.push_bind(organization_id);
+			qb.push_bind(recording_id);
+			qb.push_bind(values.0);
+			qb.push_bind(Ulid::new());
+			qb.push_bind(values.1.first().copied().unwrap_or_default());
+			qb.push_bind(values.2);
+			qb.push_bind(values.1);
+			qb.push_bind(160);
+			qb.push_bind(90);
+			qb.push_bind(5);
+			qb.push_bind(5);
+		});
+
+		qb.push(" RETURNING *");
+
+		results.extend(qb.build_query_as().fetch_all(&client).await.unwrap());
+	}
+
+	results
+}
+
+pub async fn create_recording_caption_segment(
+	global: &Arc<GlobalState>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	inserts: impl Iterator<Item = (i32, f32, f32)>,
+) -> Vec<video_common::database::RecordingCaptionSegment> {
+	let mut results = Vec::new();
+
+	let client = global.db().get().await.unwrap();
+
+	for inserts in &inserts.chunks(u16::MAX as usize / 6) {
+		let mut qb = utils::database::QueryBuilder::default();
+
+		qb.push("INSERT INTO recording_caption_segments (organization_id, recording_id, idx, id, start_time, end_time) ");
+
+		qb.push_values(inserts, |mut qb, values| {
+			qb.push_bind(organization_id);
+			qb.push_bind(recording_id);
+			qb.push_bind(values.0);
+			qb.push_bind(Ulid::new());
+			qb.push_bind(values.1);
+			qb.push_bind(values.2);
+		});
+
+		qb.push(" RETURNING *");
+
+		results.extend(qb.build_query_as().fetch_all(&client).await.unwrap());
+	}
+
+	results
+}
+
 pub async fn create_recording_segment(
 	global: &Arc<GlobalState>,
 	organization_id: Ulid,
@@ -187,6 +258,21 @@ pub async fn create_transcoding_config(
 		.unwrap()
 }
 
+pub async fn create_ingest_policy(
+	global: &Arc<GlobalState>,
+	organization_id: Ulid,
+	tags: HashMap<String, String>,
+) -> video_common::database::IngestPolicy {
+	utils::database::query("INSERT INTO ingest_policies (id, organization_id, tags) VALUES ($1, $2, $3) RETURNING *")
+		.bind(Ulid::new())
+		.bind(organization_id)
+		.bind(utils::database::Json(tags))
+		.build_query_as()
+		.fetch_one(global.db())
+		.await
+		.unwrap()
+}
+
 pub async fn create_s3_bucket(
 	global: &Arc<GlobalState>,
 	organization_id: Ulid,
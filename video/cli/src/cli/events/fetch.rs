@@ -42,6 +42,7 @@ pub enum Target {
 	RecordingConfig,
 	TranscodingConfig,
 	S3Bucket,
+	IngestPolicy,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -68,6 +69,14 @@ struct EventPayload {
 	clean: Option<bool>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	cause: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	ip: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	healthy: Option<bool>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	issues: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	progress: Option<f32>,
 }
 
 impl Invokable for Fetch {
@@ -83,6 +92,7 @@ impl Invokable for Fetch {
 						Target::RecordingConfig => events_fetch_request::Target::RecordingConfig.into(),
 						Target::TranscodingConfig => events_fetch_request::Target::TranscodingConfig.into(),
 						Target::S3Bucket => events_fetch_request::Target::S3Bucket.into(),
+						Target::IngestPolicy => events_fetch_request::Target::IngestPolicy.into(),
 					},
 					max_events: self.limit as _,
 					max_delay_ms: self.max_delay,
@@ -114,7 +124,7 @@ impl Invokable for Fetch {
 								resource: "recording".to_owned(),
 								action: "started".to_owned(),
 								recording_config_id: Some(started.recording_config_id.into_ulid()),
-								room_id: Some(started.room_id.into_ulid()),
+								room_id: started.room_id.map(|id| id.into_ulid()),
 								..Default::default()
 							},
 							Some(event::recording::Event::Finished(_)) => EventPayload {
@@ -129,6 +139,13 @@ impl Invokable for Fetch {
 								action: "modified".to_owned(),
 								..Default::default()
 							},
+							Some(event::recording::Event::Progress(progress)) => EventPayload {
+								resource_id: recording.recording_id.into_ulid(),
+								resource: "recording".to_owned(),
+								action: "progress".to_owned(),
+								progress: Some(progress.progress),
+								..Default::default()
+							},
 							Some(event::recording::Event::Deleted(deleted)) => match deleted.event {
 								Some(event::recording::deleted::Event::Started(deleted_started)) => EventPayload {
 									resource_id: recording.recording_id.into_ulid(),
@@ -225,6 +242,27 @@ impl Invokable for Fetch {
 							},
 							None => return Err(anyhow::anyhow!("s3 bucket event missing")),
 						},
+						Some(event::Event::IngestPolicy(ingest_policy)) => match ingest_policy.event {
+							Some(event::ingest_policy::Event::Created(_)) => EventPayload {
+								resource_id: ingest_policy.ingest_policy_id.into_ulid(),
+								resource: "ingest_policy".to_owned(),
+								action: "created".to_owned(),
+								..Default::default()
+							},
+							Some(event::ingest_policy::Event::Modified(_)) => EventPayload {
+								resource_id: ingest_policy.ingest_policy_id.into_ulid(),
+								resource: "ingest_policy".to_owned(),
+								action: "modified".to_owned(),
+								..Default::default()
+							},
+							Some(event::ingest_policy::Event::Deleted(_)) => EventPayload {
+								resource_id: ingest_policy.ingest_policy_id.into_ulid(),
+								resource: "ingest_policy".to_owned(),
+								action: "deleted".to_owned(),
+								..Default::default()
+							},
+							None => return Err(anyhow::anyhow!("ingest policy event missing")),
+						},
 						Some(event::Event::AccessToken(access_token)) => match access_token.event {
 							Some(event::access_token::Event::Created(_)) => EventPayload {
 								resource_id: access_token.access_token_id.into_ulid(),
@@ -277,8 +315,9 @@ impl Invokable for Fetch {
 							Some(event::room::Event::Connected(connected)) => EventPayload {
 								resource_id: room.room_id.into_ulid(),
 								resource: "room".to_owned(),
-								action: "connected".to_owned(),
+								action: if connected.resumed { "resumed" } else { "connected" }.to_owned(),
 								connection_id: Some(connected.connection_id.into_ulid()),
+								ip: connected.ip,
 								..Default::default()
 							},
 							Some(event::room::Event::Disconnected(disconnected)) => EventPayload {
@@ -298,6 +337,44 @@ impl Invokable for Fetch {
 								clean: Some(disconnected.clean),
 								..Default::default()
 							},
+							Some(event::room::Event::BackupConnected(connected)) => EventPayload {
+								resource_id: room.room_id.into_ulid(),
+								resource: "room".to_owned(),
+								action: "backup_connected".to_owned(),
+								connection_id: Some(connected.connection_id.into_ulid()),
+								ip: connected.ip,
+								..Default::default()
+							},
+							Some(event::room::Event::BackupDisconnected(disconnected)) => EventPayload {
+								resource_id: room.room_id.into_ulid(),
+								resource: "room".to_owned(),
+								action: "backup_disconnected".to_owned(),
+								connection_id: Some(disconnected.connection_id.into_ulid()),
+								clean: Some(disconnected.clean),
+								cause: disconnected.cause,
+								..Default::default()
+							},
+							Some(event::room::Event::InputChanged(changed)) => EventPayload {
+								resource_id: room.room_id.into_ulid(),
+								resource: "room".to_owned(),
+								action: if changed.backup {
+									"switched_to_backup"
+								} else {
+									"switched_to_primary"
+								}
+								.to_owned(),
+								connection_id: Some(changed.connection_id.into_ulid()),
+								..Default::default()
+							},
+							Some(event::room::Event::HealthChanged(changed)) => EventPayload {
+								resource_id: room.room_id.into_ulid(),
+								resource: "room".to_owned(),
+								action: "health_changed".to_owned(),
+								connection_id: Some(changed.connection_id.into_ulid()),
+								healthy: Some(changed.healthy),
+								issues: changed.issues,
+								..Default::default()
+							},
 							Some(event::room::Event::Ready(ready)) => EventPayload {
 								resource_id: room.room_id.into_ulid(),
 								resource: "room".to_owned(),
@@ -0,0 +1,73 @@
+use anyhow::Context;
+
+use super::IngestPolicy;
+use crate::cli::{Cli, Invokable};
+use crate::invoker::Invoker;
+
+#[derive(Debug, clap::Args)]
+pub struct Create {
+	/// The maximum bitrate in bits per second
+	#[clap(long)]
+	max_bitrate: Option<i64>,
+
+	/// The maximum video width in pixels
+	#[clap(long)]
+	max_width: Option<i32>,
+
+	/// The maximum video height in pixels
+	#[clap(long)]
+	max_height: Option<i32>,
+
+	/// The maximum video frame rate
+	#[clap(long)]
+	max_fps: Option<i32>,
+
+	/// The allowed video codecs (avc1, hev1, av01)
+	#[clap(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+	allowed_video_codecs: Vec<String>,
+
+	/// The allowed audio codecs (mp4a, opus)
+	#[clap(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+	allowed_audio_codecs: Vec<String>,
+
+	/// The maximum number of bytes between two keyframes
+	#[clap(long)]
+	max_bytes_between_keyframes: Option<i64>,
+
+	/// The maximum time between two keyframes in milliseconds
+	#[clap(long)]
+	max_time_between_keyframes: Option<i64>,
+
+	/// Make this the default ingest policy of the organization
+	#[clap(long)]
+	organization_default: bool,
+
+	/// The tags for the ingest policy (JSON)
+	#[clap(long, default_value = "{}")]
+	tags: String,
+}
+
+impl Invokable for Create {
+	async fn invoke(&self, invoker: &mut Invoker, _: &Cli) -> anyhow::Result<()> {
+		let resp = invoker
+			.invoke(pb::scuffle::video::v1::IngestPolicyCreateRequest {
+				max_bitrate: self.max_bitrate,
+				max_width: self.max_width,
+				max_height: self.max_height,
+				max_fps: self.max_fps,
+				allowed_video_codecs: self.allowed_video_codecs.clone(),
+				allowed_audio_codecs: self.allowed_audio_codecs.clone(),
+				max_bytes_between_keyframes: self.max_bytes_between_keyframes,
+				max_time_between_keyframes: self.max_time_between_keyframes,
+				organization_default: self.organization_default,
+				tags: Some(pb::scuffle::video::v1::types::Tags {
+					tags: serde_json::from_str(&self.tags).context("failed to parse tags")?,
+				}),
+			})
+			.await?;
+
+		invoker.display(&IngestPolicy::from_proto(resp.ingest_policy.unwrap_or_default()))?;
+
+		Ok(())
+	}
+}
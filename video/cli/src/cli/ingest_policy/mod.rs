@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use pb::ext::UlidExt;
+
+use crate::cli::{Cli, Invokable};
+use crate::invoker::Invoker;
+mod create;
+mod delete;
+mod get;
+mod modify;
+mod tag;
+mod untag;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Commands {
+	/// Get ingest policies
+	Get(get::Get),
+
+	/// Create an ingest policy
+	Create(create::Create),
+
+	/// Modify an ingest policy
+	Modify(modify::Modify),
+
+	/// Delete ingest policies
+	Delete(delete::Delete),
+
+	/// Tag ingest policies
+	Tag(tag::Tag),
+
+	/// Untag ingest policies
+	Untag(untag::Untag),
+}
+
+impl Invokable for Commands {
+	async fn invoke(&self, invoker: &mut Invoker, args: &Cli) -> anyhow::Result<()> {
+		match self {
+			Self::Get(cmd) => cmd.invoke(invoker, args).await,
+			Self::Create(cmd) => cmd.invoke(invoker, args).await,
+			Self::Modify(cmd) => cmd.invoke(invoker, args).await,
+			Self::Delete(cmd) => cmd.invoke(invoker, args).await,
+			Self::Tag(cmd) => cmd.invoke(invoker, args).await,
+			Self::Untag(cmd) => cmd.invoke(invoker, args).await,
+		}
+	}
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct IngestPolicy {
+	id: ulid::Ulid,
+	max_bitrate: Option<i64>,
+	max_width: Option<i32>,
+	max_height: Option<i32>,
+	max_fps: Option<i32>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	allowed_video_codecs: Vec<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	allowed_audio_codecs: Vec<String>,
+	max_bytes_between_keyframes: Option<i64>,
+	max_time_between_keyframes: Option<i64>,
+	organization_default: bool,
+	created_at: chrono::DateTime<chrono::Utc>,
+	updated_at: chrono::DateTime<chrono::Utc>,
+	#[serde(skip_serializing_if = "HashMap::is_empty")]
+	tags: HashMap<String, String>,
+}
+
+impl IngestPolicy {
+	pub fn from_proto(proto: pb::scuffle::video::v1::types::IngestPolicy) -> Self {
+		Self {
+			id: proto.id.into_ulid(),
+			max_bitrate: proto.max_bitrate,
+			max_width: proto.max_width,
+			max_height: proto.max_height,
+			max_fps: proto.max_fps,
+			allowed_video_codecs: proto.allowed_video_codecs,
+			allowed_audio_codecs: proto.allowed_audio_codecs,
+			max_bytes_between_keyframes: proto.max_bytes_between_keyframes,
+			max_time_between_keyframes: proto.max_time_between_keyframes,
+			organization_default: proto.organization_default,
+			tags: proto.tags.map(|tags| tags.tags).unwrap_or_default(),
+			created_at: Utc.timestamp_millis_opt(proto.created_at).unwrap(),
+			updated_at: Utc.timestamp_millis_opt(proto.updated_at).unwrap(),
+		}
+	}
+}
@@ -0,0 +1,103 @@
+use anyhow::Context;
+use pb::scuffle::video::v1::ingest_policy_modify_request::CodecList;
+use ulid::Ulid;
+
+use super::IngestPolicy;
+use crate::cli::{Cli, Invokable};
+use crate::invoker::Invoker;
+
+#[derive(Debug, clap::Args)]
+pub struct Modify {
+	/// The id of the ingest policy to modify
+	#[clap(long, required = true)]
+	id: Ulid,
+
+	/// The maximum bitrate in bits per second (0 removes the limit)
+	#[clap(long)]
+	max_bitrate: Option<i64>,
+
+	/// The maximum video width in pixels (0 removes the limit)
+	#[clap(long)]
+	max_width: Option<i32>,
+
+	/// The maximum video height in pixels (0 removes the limit)
+	#[clap(long)]
+	max_height: Option<i32>,
+
+	/// The maximum video frame rate (0 removes the limit)
+	#[clap(long)]
+	max_fps: Option<i32>,
+
+	/// The allowed video codecs (avc1, hev1, av01)
+	#[clap(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+	allowed_video_codecs: Option<Vec<String>>,
+
+	/// Allow all video codecs
+	#[clap(long, conflicts_with = "allowed_video_codecs")]
+	unset_allowed_video_codecs: bool,
+
+	/// The allowed audio codecs (mp4a, opus)
+	#[clap(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+	allowed_audio_codecs: Option<Vec<String>>,
+
+	/// Allow all audio codecs
+	#[clap(long, conflicts_with = "allowed_audio_codecs")]
+	unset_allowed_audio_codecs: bool,
+
+	/// The maximum number of bytes between two keyframes (0 removes the limit)
+	#[clap(long)]
+	max_bytes_between_keyframes: Option<i64>,
+
+	/// The maximum time between two keyframes in milliseconds (0 removes the
+	/// limit)
+	#[clap(long)]
+	max_time_between_keyframes: Option<i64>,
+
+	/// Make this the default ingest policy of the organization
+	#[clap(long)]
+	organization_default: Option<bool>,
+
+	/// The tags for the ingest policy (JSON)
+	#[clap(long)]
+	tags: Option<String>,
+}
+
+impl Invokable for Modify {
+	async fn invoke(&self, invoker: &mut Invoker, _: &Cli) -> anyhow::Result<()> {
+		let codec_list = |codecs: &Option<Vec<String>>, unset: bool| {
+			if unset {
+				Some(CodecList { items: Vec::new() })
+			} else {
+				codecs.as_ref().map(|items| CodecList { items: items.clone() })
+			}
+		};
+
+		let resp = invoker
+			.invoke(pb::scuffle::video::v1::IngestPolicyModifyRequest {
+				id: Some(self.id.into()),
+				max_bitrate: self.max_bitrate,
+				max_width: self.max_width,
+				max_height: self.max_height,
+				max_fps: self.max_fps,
+				allowed_video_codecs: codec_list(&self.allowed_video_codecs, self.unset_allowed_video_codecs),
+				allowed_audio_codecs: codec_list(&self.allowed_audio_codecs, self.unset_allowed_audio_codecs),
+				max_bytes_between_keyframes: self.max_bytes_between_keyframes,
+				max_time_between_keyframes: self.max_time_between_keyframes,
+				organization_default: self.organization_default,
+				tags: self
+					.tags
+					.as_ref()
+					.map(|tags| {
+						anyhow::Ok(pb::scuffle::video::v1::types::Tags {
+							tags: serde_json::from_str(tags).context("failed to parse tags")?,
+						})
+					})
+					.transpose()?,
+			})
+			.await?;
+
+		invoker.display(&IngestPolicy::from_proto(resp.ingest_policy.unwrap_or_default()))?;
+
+		Ok(())
+	}
+}
@@ -5,6 +5,7 @@ use crate::invoker::Invoker;
 pub mod access_token;
 pub mod display;
 pub mod events;
+pub mod ingest_policy;
 pub mod organization;
 pub mod playback_key_pair;
 pub mod playback_session;
@@ -78,6 +79,9 @@ pub enum Commands {
 	/// Events commands
 	Events(SubCommand<events::Commands>),
 
+	/// Ingest policy commands
+	IngestPolicy(SubCommand<ingest_policy::Commands>),
+
 	/// Playback key pair commands
 	PlaybackKeyPair(SubCommand<playback_key_pair::Commands>),
 
@@ -106,6 +110,7 @@ impl Invokable for Commands {
 			Self::Organization(cmd) => cmd.command.invoke(invoker, args).await,
 			Self::AccessToken(cmd) => cmd.command.invoke(invoker, args).await,
 			Self::Events(cmd) => cmd.command.invoke(invoker, args).await,
+			Self::IngestPolicy(cmd) => cmd.command.invoke(invoker, args).await,
 			Self::PlaybackKeyPair(cmd) => cmd.command.invoke(invoker, args).await,
 			Self::PlaybackSession(cmd) => cmd.command.invoke(invoker, args).await,
 			Self::Recording(cmd) => cmd.command.invoke(invoker, args).await,
@@ -18,12 +18,39 @@ pub struct Modify {
 	/// The tags for the organization (JSON)
 	#[clap(long)]
 	tags: Option<String>,
+
+	/// The url of the hook that authorizes publish requests
+	#[clap(long)]
+	ingest_auth_url: Option<String>,
+
+	/// Remove the authorization hook of the organization
+	#[clap(long, conflicts_with = "ingest_auth_url")]
+	unset_ingest_auth_url: bool,
+
+	/// How long to wait for the authorization hook (milliseconds)
+	#[clap(long)]
+	ingest_auth_timeout: Option<i64>,
+
+	/// Allow publishing with a valid stream key when the authorization hook
+	/// fails
+	#[clap(long)]
+	ingest_auth_fail_open: Option<bool>,
 }
 
 impl Invokable for Modify {
 	async fn invoke(&self, invoker: &mut Invoker, _: &Cli) -> anyhow::Result<()> {
-		if self.name.is_none() && self.tags.is_none() {
-			anyhow::bail!("at least one of --name or --tags must be specified");
+		if self.name.is_none()
+			&& self.tags.is_none()
+			&& self.ingest_auth_url.is_none()
+			&& !self.unset_ingest_auth_url
+			&& self.ingest_auth_timeout.is_none()
+			&& self.ingest_auth_fail_open.is_none()
+		{
+			anyhow::bail!("at least one field to modify must be specified");
+		}
+
+		if self.ingest_auth_timeout.is_some_and(|timeout| timeout <= 0) {
+			anyhow::bail!("--ingest-auth-timeout must be positive");
 		}
 
 		let resp = invoker
@@ -36,6 +63,13 @@ impl Invokable for Modify {
 					.map(|tags| serde_json::from_str(tags))
 					.transpose()
 					.context("failed to parse tags")?,
+				ingest_auth_url: if self.unset_ingest_auth_url {
+					Some(None)
+				} else {
+					self.ingest_auth_url.clone().map(Some)
+				},
+				ingest_auth_timeout: self.ingest_auth_timeout,
+				ingest_auth_fail_open: self.ingest_auth_fail_open,
 			})
 			.await?;
 
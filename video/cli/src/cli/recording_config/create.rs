@@ -19,6 +19,10 @@ pub struct Create {
 	#[clap(long)]
 	lifecycle_policies: Vec<String>,
 
+	/// Archive the source stream as well
+	#[clap(long)]
+	capture_source: bool,
+
 	/// The tags for the recording config (JSON)
 	#[clap(long, default_value = "{}")]
 	tags: String,
@@ -42,6 +46,7 @@ impl Invokable for Create {
 				tags: Some(pb::scuffle::video::v1::types::Tags {
 					tags: serde_json::from_str(&self.tags).context("failed to parse tags")?,
 				}),
+				capture_source: self.capture_source,
 			})
 			.await?;
 
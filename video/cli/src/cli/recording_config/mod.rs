@@ -41,6 +41,17 @@ pub enum Rendition {
 	VideoSd,
 	VideoLd,
 	AudioSource,
+	VideoCustom1,
+	VideoCustom2,
+	VideoCustom3,
+	VideoCustom4,
+	VideoCustom5,
+	VideoCustom6,
+	VideoCustom7,
+	VideoCustom8,
+	AudioAacHe,
+	AudioOpus,
+	AudioStereo,
 }
 
 impl From<Rendition> for i32 {
@@ -51,6 +62,17 @@ impl From<Rendition> for i32 {
 			Rendition::VideoSd => pb::scuffle::video::v1::types::Rendition::VideoSd as i32,
 			Rendition::VideoLd => pb::scuffle::video::v1::types::Rendition::VideoLd as i32,
 			Rendition::AudioSource => pb::scuffle::video::v1::types::Rendition::AudioSource as i32,
+			Rendition::VideoCustom1 => pb::scuffle::video::v1::types::Rendition::VideoCustom1 as i32,
+			Rendition::VideoCustom2 => pb::scuffle::video::v1::types::Rendition::VideoCustom2 as i32,
+			Rendition::VideoCustom3 => pb::scuffle::video::v1::types::Rendition::VideoCustom3 as i32,
+			Rendition::VideoCustom4 => pb::scuffle::video::v1::types::Rendition::VideoCustom4 as i32,
+			Rendition::VideoCustom5 => pb::scuffle::video::v1::types::Rendition::VideoCustom5 as i32,
+			Rendition::VideoCustom6 => pb::scuffle::video::v1::types::Rendition::VideoCustom6 as i32,
+			Rendition::VideoCustom7 => pb::scuffle::video::v1::types::Rendition::VideoCustom7 as i32,
+			Rendition::VideoCustom8 => pb::scuffle::video::v1::types::Rendition::VideoCustom8 as i32,
+			Rendition::AudioAacHe => pb::scuffle::video::v1::types::Rendition::AudioAacHe as i32,
+			Rendition::AudioOpus => pb::scuffle::video::v1::types::Rendition::AudioOpus as i32,
+			Rendition::AudioStereo => pb::scuffle::video::v1::types::Rendition::AudioStereo as i32,
 		}
 	}
 }
@@ -74,6 +96,7 @@ pub struct RecordingConfig {
 	pub renditions: Vec<String>,
 	pub lifecycle_policies: Vec<LifecyclePolicy>,
 	pub s3_bucket_id: Ulid,
+	pub capture_source: bool,
 	pub created_at: chrono::DateTime<chrono::Utc>,
 	pub updated_at: chrono::DateTime<chrono::Utc>,
 	#[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -92,9 +115,11 @@ impl RecordingConfig {
 					after_days: p.after_days,
 					action: p.action().as_str_name().to_string(),
 					renditions: p.renditions().map(|r| r.as_str_name().to_string()).collect(),
+					source: p.source,
 				})
 				.collect(),
 			s3_bucket_id: pb.s3_bucket_id.into_ulid(),
+			capture_source: pb.capture_source,
 			created_at: Utc.timestamp_millis_opt(pb.created_at).unwrap(),
 			updated_at: Utc.timestamp_millis_opt(pb.updated_at).unwrap(),
 			tags: pb.tags.map(|tags| tags.tags).unwrap_or_default(),
@@ -107,6 +132,8 @@ pub struct LifecyclePolicy {
 	pub after_days: i32,
 	pub action: String,
 	pub renditions: Vec<String>,
+	#[serde(default)]
+	pub source: bool,
 }
 
 impl TryFrom<LifecyclePolicy> for pb::scuffle::video::v1::types::RecordingLifecyclePolicy {
@@ -122,15 +149,13 @@ impl TryFrom<LifecyclePolicy> for pb::scuffle::video::v1::types::RecordingLifecy
 			renditions: value
 				.renditions
 				.iter()
-				.map(|r| match r.to_lowercase().as_str() {
-					"video_source" => Ok(pb::scuffle::video::v1::types::Rendition::VideoSource as i32),
-					"video_hd" => Ok(pb::scuffle::video::v1::types::Rendition::VideoHd as i32),
-					"video_sd" => Ok(pb::scuffle::video::v1::types::Rendition::VideoSd as i32),
-					"video_ld" => Ok(pb::scuffle::video::v1::types::Rendition::VideoLd as i32),
-					"audio_source" => Ok(pb::scuffle::video::v1::types::Rendition::AudioSource as i32),
-					_ => anyhow::bail!("invalid rendition: {}", r),
+				.map(|r| {
+					pb::scuffle::video::v1::types::Rendition::from_str_name(&r.to_uppercase())
+						.map(|r| r as i32)
+						.ok_or_else(|| anyhow::anyhow!("invalid rendition: {}", r))
 				})
 				.collect::<Result<_, _>>()?,
+			source: value.source,
 		})
 	}
 }
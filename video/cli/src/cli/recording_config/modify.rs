@@ -24,6 +24,10 @@ pub struct Modify {
 	/// The lifecycle policies to apply to the recording (JSON)
 	lifecycle_policies: Option<Vec<String>>,
 
+	#[clap(long)]
+	/// Archive the source stream as well
+	capture_source: Option<bool>,
+
 	/// The tags for the recording config (JSON)
 	#[clap(long)]
 	tags: Option<String>,
@@ -34,9 +38,12 @@ impl Invokable for Modify {
 		if self.renditions.is_none()
 			&& self.s3_bucket_id.is_none()
 			&& self.lifecycle_policies.is_none()
+			&& self.capture_source.is_none()
 			&& self.tags.is_none()
 		{
-			anyhow::bail!("at least one flag must be set, --renditions, --s3-bucket-id, --lifecycle-policies, or --tags");
+			anyhow::bail!(
+				"at least one flag must be set, --renditions, --s3-bucket-id, --lifecycle-policies, --capture-source, or --tags"
+			);
 		}
 
 		let resp = invoker
@@ -71,6 +78,7 @@ impl Invokable for Modify {
 						})
 					})
 					.transpose()?,
+				capture_source: self.capture_source,
 			})
 			.await?;
 
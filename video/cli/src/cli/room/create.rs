@@ -23,6 +23,23 @@ pub struct Create {
 	/// The tags for the room (JSON)
 	#[clap(long, default_value = "{}")]
 	tags: String,
+
+	/// The url to pull the stream from (rtmp, rtmps, http-flv or hls)
+	#[clap(long)]
+	source_url: Option<String>,
+
+	/// The ingest policy id of the room
+	#[clap(long)]
+	ingest_policy_id: Option<Ulid>,
+
+	/// An ip address or CIDR range allowed to publish to the room (repeatable)
+	#[clap(long)]
+	ip_allow: Vec<String>,
+
+	/// An ip address or CIDR range denied from publishing to the room
+	/// (repeatable)
+	#[clap(long)]
+	ip_deny: Vec<String>,
 }
 
 impl Invokable for Create {
@@ -38,6 +55,12 @@ impl Invokable for Create {
 				tags: Some(pb::scuffle::video::v1::types::Tags {
 					tags: serde_json::from_str(&self.tags).context("failed to parse tags")?,
 				}),
+				source_url: self.source_url.clone(),
+				ingest_policy_id: self.ingest_policy_id.map(Into::into),
+				ingest_ip_filter: Some(pb::scuffle::video::v1::types::IpFilter {
+					allow: self.ip_allow.clone(),
+					deny: self.ip_deny.clone(),
+				}),
 			})
 			.await?;
 
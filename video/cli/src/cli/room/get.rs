@@ -21,6 +21,10 @@ pub struct Get {
 	#[clap(long)]
 	recording_config_id: Option<Ulid>,
 
+	/// Filter by the ingest policy id of the room
+	#[clap(long)]
+	ingest_policy_id: Option<Ulid>,
+
 	/// Filter by the status of the room
 	#[clap(long)]
 	status: Option<Status>,
@@ -60,6 +64,7 @@ impl Invokable for Get {
 				ids: self.ids.iter().copied().map(Into::into).collect(),
 				transcoding_config_id: self.transcoding_config_id.map(Into::into),
 				recording_config_id: self.recording_config_id.map(Into::into),
+				ingest_policy_id: self.ingest_policy_id.map(Into::into),
 				status: self.status.map(|s| match s {
 					Status::Offline => pb::scuffle::video::v1::types::RoomStatus::Offline as i32,
 					Status::Waiting => pb::scuffle::video::v1::types::RoomStatus::WaitingForTranscoder as i32,
@@ -74,10 +74,21 @@ pub struct Room {
 	pub audio_input: Option<AudioConfig>,
 	pub video_output: Vec<VideoConfig>,
 	pub audio_output: Vec<AudioConfig>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ingest_health: Option<IngestHealth>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub audio_loudness: Option<AudioLoudness>,
 	pub active_connection_id: Option<Ulid>,
 	pub active_recording_id: Option<Ulid>,
 	pub transcoding_config_id: Option<Ulid>,
 	pub recording_config_id: Option<Ulid>,
+	pub ingest_policy_id: Option<Ulid>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source_url: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub ingest_ip_allow: Vec<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub ingest_ip_deny: Vec<String>,
 	pub created_at: chrono::DateTime<chrono::Utc>,
 	pub updated_at: chrono::DateTime<chrono::Utc>,
 	pub last_live_at: Option<chrono::DateTime<chrono::Utc>>,
@@ -94,6 +105,10 @@ impl Room {
 			visibility: room.visibility().as_str_name().to_string(),
 			transcoding_config_id: room.transcoding_config_id.map(|u| u.into_ulid()),
 			recording_config_id: room.recording_config_id.map(|u| u.into_ulid()),
+			ingest_policy_id: room.ingest_policy_id.map(|u| u.into_ulid()),
+			source_url: room.source_url.clone(),
+			ingest_ip_allow: room.ingest_ip_filter.as_ref().map(|f| f.allow.clone()).unwrap_or_default(),
+			ingest_ip_deny: room.ingest_ip_filter.as_ref().map(|f| f.deny.clone()).unwrap_or_default(),
 			created_at: Utc.timestamp_millis_opt(room.created_at).unwrap(),
 			updated_at: Utc.timestamp_millis_opt(room.updated_at).unwrap(),
 			last_live_at: room.last_live_at.map(|ts| Utc.timestamp_millis_opt(ts).unwrap()),
@@ -105,6 +120,8 @@ impl Room {
 			audio_input: room.audio_input.map(AudioConfig::from_proto),
 			video_output: room.video_output.into_iter().map(VideoConfig::from_proto).collect(),
 			audio_output: room.audio_output.into_iter().map(AudioConfig::from_proto).collect(),
+			ingest_health: room.ingest_health.map(IngestHealth::from_proto),
+			audio_loudness: room.audio_loudness.map(AudioLoudness::from_proto),
 			tags: room.tags.map(|tags| tags.tags).unwrap_or_default(),
 		}
 	}
@@ -113,6 +130,8 @@ impl Room {
 #[derive(Debug, serde::Serialize)]
 pub struct VideoConfig {
 	pub rendition: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub name: String,
 	pub bitrate: i64,
 	pub fps: i32,
 	pub width: i32,
@@ -124,6 +143,7 @@ impl VideoConfig {
 	pub fn from_proto(video_config: pb::scuffle::video::v1::types::VideoConfig) -> Self {
 		Self {
 			rendition: video_config.rendition().as_str_name().to_string(),
+			name: video_config.name,
 			bitrate: video_config.bitrate,
 			fps: video_config.fps,
 			width: video_config.width,
@@ -153,3 +173,51 @@ impl AudioConfig {
 		}
 	}
 }
+
+#[derive(Debug, serde::Serialize)]
+pub struct IngestHealth {
+	pub bitrate: i64,
+	pub fps: f64,
+	pub declared_fps: f64,
+	pub keyframe_interval_ms: Option<i64>,
+	pub av_drift_ms: i64,
+	pub dropped_frames: i64,
+	pub late_frames: i64,
+	pub timestamp_jumps: i64,
+	pub rtt_ms: Option<i64>,
+}
+
+impl IngestHealth {
+	pub fn from_proto(health: pb::scuffle::video::v1::types::IngestHealth) -> Self {
+		Self {
+			bitrate: health.bitrate,
+			fps: health.fps,
+			declared_fps: health.declared_fps,
+			keyframe_interval_ms: health.keyframe_interval_ms,
+			av_drift_ms: health.av_drift_ms,
+			dropped_frames: health.dropped_frames,
+			late_frames: health.late_frames,
+			timestamp_jumps: health.timestamp_jumps,
+			rtt_ms: health.rtt_ms,
+		}
+	}
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AudioLoudness {
+	pub integrated_loudness: f64,
+	pub short_term_loudness: f64,
+	pub loudness_range: f64,
+	pub true_peak: f64,
+}
+
+impl AudioLoudness {
+	pub fn from_proto(loudness: pb::scuffle::video::v1::types::AudioLoudness) -> Self {
+		Self {
+			integrated_loudness: loudness.integrated_loudness,
+			short_term_loudness: loudness.short_term_loudness,
+			loudness_range: loudness.loudness_range,
+			true_peak: loudness.true_peak,
+		}
+	}
+}
@@ -35,6 +35,36 @@ pub struct Modify {
 	/// The tags for the room (JSON)
 	#[clap(long)]
 	tags: Option<String>,
+
+	/// The url to pull the stream from (rtmp, rtmps, http-flv or hls)
+	#[clap(long)]
+	source_url: Option<String>,
+
+	/// Remove the source url of the room, switching it back to push ingest
+	#[clap(long, conflicts_with = "source_url")]
+	unset_source_url: bool,
+
+	/// The ingest policy id of the room
+	#[clap(long)]
+	ingest_policy_id: Option<Ulid>,
+
+	/// Remove the ingest policy id of the room
+	#[clap(long, conflicts_with = "ingest_policy_id")]
+	unset_ingest_policy_id: bool,
+
+	/// An ip address or CIDR range allowed to publish to the room, replaces
+	/// the existing ip filter (repeatable)
+	#[clap(long)]
+	ip_allow: Vec<String>,
+
+	/// An ip address or CIDR range denied from publishing to the room,
+	/// replaces the existing ip filter (repeatable)
+	#[clap(long)]
+	ip_deny: Vec<String>,
+
+	/// Remove the ip filter of the room
+	#[clap(long, conflicts_with_all = ["ip_allow", "ip_deny"])]
+	unset_ip_filter: bool,
 }
 
 impl Invokable for Modify {
@@ -65,6 +95,24 @@ impl Invokable for Modify {
 					Visibility::Public => pb::scuffle::video::v1::types::Visibility::Public as i32,
 					Visibility::Private => pb::scuffle::video::v1::types::Visibility::Private as i32,
 				}),
+				source_url: if self.unset_source_url {
+					Some(String::new())
+				} else {
+					self.source_url.clone()
+				},
+				ingest_policy_id: if self.unset_ingest_policy_id {
+					Some(Ulid::nil().into())
+				} else {
+					self.ingest_policy_id.map(Into::into)
+				},
+				ingest_ip_filter: if self.unset_ip_filter || !self.ip_allow.is_empty() || !self.ip_deny.is_empty() {
+					Some(pb::scuffle::video::v1::types::IpFilter {
+						allow: self.ip_allow.clone(),
+						deny: self.ip_deny.clone(),
+					})
+				} else {
+					None
+				},
 			})
 			.await?;
 
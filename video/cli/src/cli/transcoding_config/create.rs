@@ -1,6 +1,6 @@
 use anyhow::Context;
 
-use super::{Rendition, TranscodingConfig};
+use super::{ContentAwareEncoding, CustomRendition, LoudnessNormalization, Overlay, Rendition, TranscodingConfig};
 use crate::cli::{Cli, Invokable};
 use crate::invoker::Invoker;
 
@@ -10,6 +10,26 @@ pub struct Create {
 	#[clap(long, value_parser, num_args = 1.., value_delimiter = ' ', required = true)]
 	renditions: Vec<Rendition>,
 
+	/// The rungs of the custom ladder (JSON)
+	#[clap(long)]
+	custom_renditions: Vec<String>,
+
+	/// The overlays drawn onto the transcoded video (JSON)
+	#[clap(long)]
+	overlays: Vec<String>,
+
+	/// The loudness normalization of the transcoded audio (JSON)
+	#[clap(long)]
+	loudness_normalization: Option<String>,
+
+	/// The content aware encoding of the transcoded video (JSON)
+	#[clap(long)]
+	content_aware_encoding: Option<String>,
+
+	/// Extract the CEA-608 captions (CC1) embedded in the source video
+	#[clap(long)]
+	captions: bool,
+
 	/// The tags for the transcoding config (JSON)
 	#[clap(long, default_value = "{}")]
 	tags: String,
@@ -23,6 +43,43 @@ impl Invokable for Create {
 				tags: Some(pb::scuffle::video::v1::types::Tags {
 					tags: serde_json::from_str(&self.tags).context("failed to parse tags")?,
 				}),
+				custom_renditions: self
+					.custom_renditions
+					.iter()
+					.map(|c| {
+						serde_json::from_str::<CustomRendition>(c)
+							.context("failed to parse custom rendition")
+							.and_then(TryInto::try_into)
+					})
+					.collect::<anyhow::Result<Vec<_>>>()?,
+				overlays: self
+					.overlays
+					.iter()
+					.map(|o| {
+						serde_json::from_str::<Overlay>(o)
+							.context("failed to parse overlay")
+							.and_then(TryInto::try_into)
+					})
+					.collect::<anyhow::Result<Vec<_>>>()?,
+				loudness_normalization: self
+					.loudness_normalization
+					.as_ref()
+					.map(|l| {
+						serde_json::from_str::<LoudnessNormalization>(l)
+							.context("failed to parse loudness normalization")
+							.map(Into::into)
+					})
+					.transpose()?,
+				content_aware_encoding: self
+					.content_aware_encoding
+					.as_ref()
+					.map(|c| {
+						serde_json::from_str::<ContentAwareEncoding>(c)
+							.context("failed to parse content aware encoding")
+							.and_then(TryInto::try_into)
+					})
+					.transpose()?,
+				captions: self.captions,
 			})
 			.await?;
 
@@ -56,6 +56,15 @@ pub struct TranscodingConfig {
 	updated_at: chrono::DateTime<chrono::Utc>,
 	#[serde(skip_serializing_if = "HashMap::is_empty")]
 	tags: HashMap<String, String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	custom_renditions: Vec<CustomRendition>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	overlays: Vec<Overlay>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	loudness_normalization: Option<LoudnessNormalization>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	content_aware_encoding: Option<ContentAwareEncoding>,
+	captions: bool,
 }
 
 impl TranscodingConfig {
@@ -66,6 +75,220 @@ impl TranscodingConfig {
 			tags: proto.tags.map(|tags| tags.tags).unwrap_or_default(),
 			created_at: Utc.timestamp_millis_opt(proto.created_at).unwrap(),
 			updated_at: Utc.timestamp_millis_opt(proto.updated_at).unwrap(),
+			custom_renditions: proto
+				.custom_renditions
+				.iter()
+				.map(|c| CustomRendition {
+					rendition: c.rendition().as_str_name().to_string(),
+					name: c.name.clone(),
+					side: c.side,
+					max_fps: c.max_fps,
+					max_bitrate: c.max_bitrate,
+					codec: c.codec().as_str_name().to_string(),
+				})
+				.collect(),
+			overlays: proto.overlays.iter().map(Overlay::from_proto).collect(),
+			loudness_normalization: proto.loudness_normalization.map(Into::into),
+			content_aware_encoding: proto.content_aware_encoding.map(Into::into),
+			captions: proto.captions,
 		}
 	}
 }
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CustomRendition {
+	pub rendition: String,
+	pub name: String,
+	pub side: i32,
+	#[serde(default)]
+	pub max_fps: i32,
+	pub max_bitrate: i64,
+	#[serde(default)]
+	pub codec: String,
+}
+
+impl TryFrom<CustomRendition> for pb::scuffle::video::v1::types::CustomRendition {
+	type Error = anyhow::Error;
+
+	fn try_from(value: CustomRendition) -> Result<Self, Self::Error> {
+		Ok(Self {
+			rendition: pb::scuffle::video::v1::types::Rendition::from_str_name(&value.rendition.to_uppercase())
+				.ok_or_else(|| anyhow::anyhow!("invalid rendition: {}", value.rendition))? as i32,
+			name: value.name,
+			side: value.side,
+			max_fps: value.max_fps,
+			max_bitrate: value.max_bitrate,
+			codec: match value.codec.to_lowercase().as_str() {
+				"" | "h264" => pb::scuffle::video::v1::types::custom_rendition::Codec::H264 as i32,
+				"hevc" => pb::scuffle::video::v1::types::custom_rendition::Codec::Hevc as i32,
+				"av1" => pb::scuffle::video::v1::types::custom_rendition::Codec::Av1 as i32,
+				_ => anyhow::bail!("invalid codec: {}", value.codec),
+			},
+		})
+	}
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct LoudnessNormalization {
+	pub integrated_loudness: f64,
+	#[serde(default)]
+	pub true_peak: f64,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub loudness_range: Option<f64>,
+}
+
+impl From<pb::scuffle::video::v1::types::LoudnessNormalization> for LoudnessNormalization {
+	fn from(value: pb::scuffle::video::v1::types::LoudnessNormalization) -> Self {
+		Self {
+			integrated_loudness: value.integrated_loudness,
+			true_peak: value.true_peak,
+			loudness_range: value.loudness_range,
+		}
+	}
+}
+
+impl From<LoudnessNormalization> for pb::scuffle::video::v1::types::LoudnessNormalization {
+	fn from(value: LoudnessNormalization) -> Self {
+		Self {
+			integrated_loudness: value.integrated_loudness,
+			true_peak: value.true_peak,
+			loudness_range: value.loudness_range,
+		}
+	}
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ContentAwareEncoding {
+	/// One of `bitrate` or `capped_crf`
+	#[serde(default)]
+	pub mode: String,
+	pub min_bitrate_ratio: f64,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_bitrate_ratio: Option<f64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub crf: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub analysis_duration: Option<u32>,
+}
+
+impl From<pb::scuffle::video::v1::types::ContentAwareEncoding> for ContentAwareEncoding {
+	fn from(value: pb::scuffle::video::v1::types::ContentAwareEncoding) -> Self {
+		Self {
+			mode: value.mode().as_str_name().to_string(),
+			min_bitrate_ratio: value.min_bitrate_ratio,
+			max_bitrate_ratio: value.max_bitrate_ratio,
+			crf: value.crf,
+			analysis_duration: value.analysis_duration,
+		}
+	}
+}
+
+impl TryFrom<ContentAwareEncoding> for pb::scuffle::video::v1::types::ContentAwareEncoding {
+	type Error = anyhow::Error;
+
+	fn try_from(value: ContentAwareEncoding) -> Result<Self, Self::Error> {
+		use pb::scuffle::video::v1::types::content_aware_encoding::Mode;
+
+		Ok(Self {
+			mode: match value.mode.as_str() {
+				"" => Mode::Bitrate,
+				mode => Mode::from_str_name(&mode.to_uppercase())
+					.ok_or_else(|| anyhow::anyhow!("invalid mode: {}", value.mode))?,
+			} as i32,
+			min_bitrate_ratio: value.min_bitrate_ratio,
+			max_bitrate_ratio: value.max_bitrate_ratio,
+			crf: value.crf,
+			analysis_duration: value.analysis_duration,
+		})
+	}
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Overlay {
+	/// One of `image`, `live_badge` or `timestamp`
+	#[serde(rename = "type")]
+	pub kind: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub s3_bucket_id: Option<ulid::Ulid>,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub key: String,
+	#[serde(default)]
+	pub position: String,
+	pub size: f32,
+	#[serde(default)]
+	pub margin: f32,
+	#[serde(default = "default_opacity")]
+	pub opacity: f32,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub renditions: Vec<String>,
+}
+
+fn default_opacity() -> f32 {
+	1.0
+}
+
+impl Overlay {
+	fn from_proto(proto: &pb::scuffle::video::v1::types::Overlay) -> Self {
+		use pb::scuffle::video::v1::types::overlay::Content;
+
+		let (kind, s3_bucket_id, key) = match &proto.content {
+			Some(Content::Image(image)) => ("image", image.s3_bucket_id.map(|id| id.into_ulid()), image.key.clone()),
+			Some(Content::LiveBadge(_)) => ("live_badge", None, String::new()),
+			Some(Content::Timestamp(_)) => ("timestamp", None, String::new()),
+			None => ("", None, String::new()),
+		};
+
+		Self {
+			kind: kind.to_string(),
+			s3_bucket_id,
+			key,
+			position: proto.position().as_str_name().to_string(),
+			size: proto.size,
+			margin: proto.margin,
+			opacity: proto.opacity,
+			renditions: proto.renditions().map(|r| r.as_str_name().to_string()).collect(),
+		}
+	}
+}
+
+impl TryFrom<Overlay> for pb::scuffle::video::v1::types::Overlay {
+	type Error = anyhow::Error;
+
+	fn try_from(value: Overlay) -> Result<Self, Self::Error> {
+		use pb::scuffle::video::v1::types::overlay;
+
+		Ok(Self {
+			content: Some(match value.kind.to_lowercase().as_str() {
+				"image" => overlay::Content::Image(overlay::Image {
+					s3_bucket_id: Some(
+						value
+							.s3_bucket_id
+							.ok_or_else(|| anyhow::anyhow!("image overlays require a s3_bucket_id"))?
+							.into(),
+					),
+					key: value.key,
+				}),
+				"live_badge" => overlay::Content::LiveBadge(overlay::LiveBadge {}),
+				"timestamp" => overlay::Content::Timestamp(overlay::Timestamp {}),
+				_ => anyhow::bail!("invalid overlay type: {}", value.kind),
+			}),
+			position: match value.position.as_str() {
+				"" => overlay::Position::TopLeft,
+				position => overlay::Position::from_str_name(&position.to_uppercase())
+					.ok_or_else(|| anyhow::anyhow!("invalid position: {}", value.position))?,
+			} as i32,
+			size: value.size,
+			margin: value.margin,
+			opacity: value.opacity,
+			renditions: value
+				.renditions
+				.iter()
+				.map(|r| {
+					pb::scuffle::video::v1::types::Rendition::from_str_name(&r.to_uppercase())
+						.map(|r| r as i32)
+						.ok_or_else(|| anyhow::anyhow!("invalid rendition: {}", r))
+				})
+				.collect::<anyhow::Result<Vec<_>>>()?,
+		})
+	}
+}
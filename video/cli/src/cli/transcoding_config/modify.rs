@@ -1,7 +1,7 @@
 use anyhow::Context;
 use ulid::Ulid;
 
-use super::{Rendition, TranscodingConfig};
+use super::{ContentAwareEncoding, CustomRendition, LoudnessNormalization, Overlay, Rendition, TranscodingConfig};
 use crate::cli::{Cli, Invokable};
 use crate::invoker::Invoker;
 
@@ -15,6 +15,28 @@ pub struct Modify {
 	/// Renditions to transcode to
 	renditions: Option<Vec<Rendition>>,
 
+	#[clap(long)]
+	/// The rungs of the custom ladder (JSON)
+	custom_renditions: Option<Vec<String>>,
+
+	#[clap(long)]
+	/// The overlays drawn onto the transcoded video (JSON)
+	overlays: Option<Vec<String>>,
+
+	#[clap(long)]
+	/// The loudness normalization of the transcoded audio (JSON, an
+	/// integrated_loudness of 0 turns it off)
+	loudness_normalization: Option<String>,
+
+	#[clap(long)]
+	/// The content aware encoding of the transcoded video (JSON, a
+	/// min_bitrate_ratio of 0 turns it off)
+	content_aware_encoding: Option<String>,
+
+	#[clap(long)]
+	/// Extract the CEA-608 captions (CC1) embedded in the source video
+	captions: Option<bool>,
+
 	/// The tags for the transcoding config (JSON)
 	#[clap(long)]
 	tags: Option<String>,
@@ -22,8 +44,17 @@ pub struct Modify {
 
 impl Invokable for Modify {
 	async fn invoke(&self, invoker: &mut Invoker, _: &Cli) -> anyhow::Result<()> {
-		if self.renditions.is_none() && self.tags.is_none() {
-			anyhow::bail!("at least one flag must be set, --renditions or --tags");
+		if self.renditions.is_none()
+			&& self.custom_renditions.is_none()
+			&& self.overlays.is_none()
+			&& self.loudness_normalization.is_none()
+			&& self.content_aware_encoding.is_none()
+			&& self.captions.is_none()
+			&& self.tags.is_none()
+		{
+			anyhow::bail!(
+				"at least one flag must be set, --renditions, --custom-renditions, --overlays, --loudness-normalization, --content-aware-encoding, --captions or --tags"
+			);
 		}
 
 		let resp = invoker
@@ -43,6 +74,59 @@ impl Invokable for Modify {
 						})
 					})
 					.transpose()?,
+				custom_renditions: self
+					.custom_renditions
+					.as_ref()
+					.map(|c| {
+						anyhow::Ok(
+							pb::scuffle::video::v1::transcoding_config_modify_request::CustomRenditionList {
+								items: c
+									.iter()
+									.map(|c| {
+										serde_json::from_str::<CustomRendition>(c)
+											.context("failed to parse custom rendition")
+											.and_then(TryInto::try_into)
+									})
+									.collect::<anyhow::Result<Vec<_>>>()?,
+							},
+						)
+					})
+					.transpose()?,
+				overlays: self
+					.overlays
+					.as_ref()
+					.map(|o| {
+						anyhow::Ok(pb::scuffle::video::v1::transcoding_config_modify_request::OverlayList {
+							items: o
+								.iter()
+								.map(|o| {
+									serde_json::from_str::<Overlay>(o)
+										.context("failed to parse overlay")
+										.and_then(TryInto::try_into)
+								})
+								.collect::<anyhow::Result<Vec<_>>>()?,
+						})
+					})
+					.transpose()?,
+				loudness_normalization: self
+					.loudness_normalization
+					.as_ref()
+					.map(|l| {
+						serde_json::from_str::<LoudnessNormalization>(l)
+							.context("failed to parse loudness normalization")
+							.map(Into::into)
+					})
+					.transpose()?,
+				content_aware_encoding: self
+					.content_aware_encoding
+					.as_ref()
+					.map(|c| {
+						serde_json::from_str::<ContentAwareEncoding>(c)
+							.context("failed to parse content aware encoding")
+							.and_then(TryInto::try_into)
+					})
+					.transpose()?,
+				captions: self.captions,
 			})
 			.await?;
 
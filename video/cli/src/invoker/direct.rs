@@ -90,6 +90,9 @@ impl DirectBackend {
 			name: org.name,
 			tags: org.tags,
 			updated_at: org.updated_at,
+			ingest_auth_url: org.ingest_auth_url,
+			ingest_auth_timeout: org.ingest_auth_timeout,
+			ingest_auth_fail_open: org.ingest_auth_fail_open,
 		})
 	}
 
@@ -178,6 +181,9 @@ impl DirectBackend {
 				name: org.name,
 				tags: org.tags,
 				updated_at: org.updated_at,
+				ingest_auth_url: org.ingest_auth_url,
+				ingest_auth_timeout: org.ingest_auth_timeout,
+				ingest_auth_fail_open: org.ingest_auth_fail_open,
 			})
 			.collect())
 	}
@@ -187,25 +193,36 @@ impl DirectBackend {
 
 		qb.push("UPDATE organizations SET ");
 
-		let mut first = true;
+		let mut seperated = qb.separated(", ");
 
 		if let Some(name) = req.name {
-			first = false;
-			qb.push("name = ");
-			qb.push_bind(name);
+			seperated.push("name = ");
+			seperated.push_bind_unseparated(name);
 		}
 
 		if let Some(tags) = req.tags {
-			if !first {
-				qb.push(", ");
-			}
+			seperated.push("tags = ");
+			seperated.push_bind_unseparated(utils::database::Json(tags));
+		}
+
+		if let Some(ingest_auth_url) = req.ingest_auth_url {
+			seperated.push("ingest_auth_url = ");
+			seperated.push_bind_unseparated(ingest_auth_url);
+		}
 
-			qb.push("tags = ");
-			qb.push_bind(utils::database::Json(tags));
+		if let Some(ingest_auth_timeout) = req.ingest_auth_timeout {
+			seperated.push("ingest_auth_timeout = ");
+			seperated.push_bind_unseparated(ingest_auth_timeout);
+		}
+
+		if let Some(ingest_auth_fail_open) = req.ingest_auth_fail_open {
+			seperated.push("ingest_auth_fail_open = ");
+			seperated.push_bind_unseparated(ingest_auth_fail_open);
 		}
 
 		qb.push(" WHERE id = ");
 		qb.push_bind(req.id);
+		qb.push(" RETURNING *");
 
 		let org: video_common::database::Organization = qb
 			.build_query_as()
@@ -218,6 +235,9 @@ impl DirectBackend {
 			name: org.name,
 			tags: org.tags,
 			updated_at: org.updated_at,
+			ingest_auth_url: org.ingest_auth_url,
+			ingest_auth_timeout: org.ingest_auth_timeout,
+			ingest_auth_fail_open: org.ingest_auth_fail_open,
 		})
 	}
 
@@ -409,6 +429,25 @@ impl_request!(
 		self.generic_response(req).await
 	},
 
+	|self, req: IngestPolicyCreateRequest| -> IngestPolicyCreateResponse {
+		self.generic_response(req).await
+	},
+	|self, req: IngestPolicyDeleteRequest| -> IngestPolicyDeleteResponse {
+		self.generic_response(req).await
+	},
+	|self, req: IngestPolicyGetRequest| -> IngestPolicyGetResponse {
+		self.generic_response(req).await
+	},
+	|self, req: IngestPolicyModifyRequest| -> IngestPolicyModifyResponse {
+		self.generic_response(req).await
+	},
+	|self, req: IngestPolicyTagRequest| -> IngestPolicyTagResponse {
+		self.generic_response(req).await
+	},
+	|self, req: IngestPolicyUntagRequest| -> IngestPolicyUntagResponse {
+		self.generic_response(req).await
+	},
+
 	|self, req: OrganizationCreateRequest| -> Organization {
 		self.create_organization(req).await
 	},
@@ -16,6 +16,7 @@ pub struct GrpcBackend {
 	_channel: Channel,
 	access_token_client: pb::scuffle::video::v1::access_token_client::AccessTokenClient<AuthChannel>,
 	events_client: pb::scuffle::video::v1::events_client::EventsClient<AuthChannel>,
+	ingest_policy_client: pb::scuffle::video::v1::ingest_policy_client::IngestPolicyClient<AuthChannel>,
 	playback_key_pair_client: pb::scuffle::video::v1::playback_key_pair_client::PlaybackKeyPairClient<AuthChannel>,
 	playback_session_client: pb::scuffle::video::v1::playback_session_client::PlaybackSessionClient<AuthChannel>,
 	recording_client: pb::scuffle::video::v1::recording_client::RecordingClient<AuthChannel>,
@@ -72,6 +73,8 @@ impl GrpcBackend {
 			pb::scuffle::video::v1::access_token_client::AccessTokenClient::with_interceptor(channel.clone(), interceptor);
 		let events_client =
 			pb::scuffle::video::v1::events_client::EventsClient::with_interceptor(channel.clone(), interceptor);
+		let ingest_policy_client =
+			pb::scuffle::video::v1::ingest_policy_client::IngestPolicyClient::with_interceptor(channel.clone(), interceptor);
 		let playback_key_pair_client =
 			pb::scuffle::video::v1::playback_key_pair_client::PlaybackKeyPairClient::with_interceptor(
 				channel.clone(),
@@ -102,6 +105,7 @@ impl GrpcBackend {
 			_channel: channel,
 			access_token_client,
 			events_client,
+			ingest_policy_client,
 			playback_key_pair_client,
 			playback_session_client,
 			recording_client,
@@ -140,6 +144,25 @@ impl_request!(
 		Ok(self.events_client.ack(req).await.context("failed call grpc endpoint")?.into_inner())
 	},
 
+	|self, req: IngestPolicyCreateRequest| -> IngestPolicyCreateResponse {
+		Ok(self.ingest_policy_client.create(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+	|self, req: IngestPolicyDeleteRequest| -> IngestPolicyDeleteResponse {
+		Ok(self.ingest_policy_client.delete(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+	|self, req: IngestPolicyGetRequest| -> IngestPolicyGetResponse {
+		Ok(self.ingest_policy_client.get(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+	|self, req: IngestPolicyModifyRequest| -> IngestPolicyModifyResponse {
+		Ok(self.ingest_policy_client.modify(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+	|self, req: IngestPolicyTagRequest| -> IngestPolicyTagResponse {
+		Ok(self.ingest_policy_client.tag(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+	|self, req: IngestPolicyUntagRequest| -> IngestPolicyUntagResponse {
+		Ok(self.ingest_policy_client.untag(req).await.context("failed call grpc endpoint")?.into_inner())
+	},
+
 	|self, _req: OrganizationCreateRequest| -> Organization {
 		anyhow::bail!("gRPC backend does not support organization create")
 	},
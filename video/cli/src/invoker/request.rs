@@ -46,6 +46,9 @@ pub struct OrganizationModifyRequest {
 	pub id: Ulid,
 	pub name: Option<String>,
 	pub tags: Option<HashMap<String, String>>,
+	pub ingest_auth_url: Option<Option<String>>,
+	pub ingest_auth_timeout: Option<i64>,
+	pub ingest_auth_fail_open: Option<bool>,
 }
 
 #[derive(Debug)]
@@ -67,4 +70,8 @@ pub struct Organization {
 	pub updated_at: chrono::DateTime<chrono::Utc>,
 	#[serde(skip_serializing_if = "HashMap::is_empty")]
 	pub tags: HashMap<String, String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ingest_auth_url: Option<String>,
+	pub ingest_auth_timeout: i64,
+	pub ingest_auth_fail_open: bool,
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use postgres_from_row::FromRow;
+use ulid::Ulid;
+use utils::database::json;
+
+use super::DatabaseTable;
+
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct IngestPolicy {
+	/// The organization this ingest policy belongs to (primary key)
+	pub organization_id: Ulid,
+	/// A unique id for the ingest policy (primary key)
+	pub id: Ulid,
+
+	/// The maximum bitrate in bits per second
+	pub max_bitrate: Option<i64>,
+
+	/// The maximum video width in pixels
+	pub max_width: Option<i32>,
+
+	/// The maximum video height in pixels
+	pub max_height: Option<i32>,
+
+	/// The maximum video frame rate
+	pub max_fps: Option<i32>,
+
+	/// The allowed video codecs, empty allows all
+	pub allowed_video_codecs: Vec<String>,
+
+	/// The allowed audio codecs, empty allows all
+	pub allowed_audio_codecs: Vec<String>,
+
+	/// The maximum number of bytes between two keyframes
+	pub max_bytes_between_keyframes: Option<i64>,
+
+	/// The maximum time between two keyframes in milliseconds
+	pub max_time_between_keyframes: Option<i64>,
+
+	/// If this is the default ingest policy of the organization
+	pub organization_default: bool,
+
+	/// The date and time the ingest policy was last updated
+	pub updated_at: chrono::DateTime<chrono::Utc>,
+
+	/// Tags associated with the ingest policy
+	#[from_row(from_fn = "json")]
+	pub tags: HashMap<String, String>,
+}
+
+impl DatabaseTable for IngestPolicy {
+	const FRIENDLY_NAME: &'static str = "ingest policy";
+	const NAME: &'static str = "ingest_policies";
+}
+
+impl IngestPolicy {
+	pub fn into_proto(self) -> pb::scuffle::video::v1::types::IngestPolicy {
+		pb::scuffle::video::v1::types::IngestPolicy {
+			id: Some(self.id.into()),
+			max_bitrate: self.max_bitrate,
+			max_width: self.max_width,
+			max_height: self.max_height,
+			max_fps: self.max_fps,
+			allowed_video_codecs: self.allowed_video_codecs,
+			allowed_audio_codecs: self.allowed_audio_codecs,
+			max_bytes_between_keyframes: self.max_bytes_between_keyframes,
+			max_time_between_keyframes: self.max_time_between_keyframes,
+			organization_default: self.organization_default,
+			created_at: self.id.timestamp_ms() as i64,
+			updated_at: self.updated_at.timestamp_millis(),
+			tags: Some(self.tags.into()),
+		}
+	}
+}
@@ -1,4 +1,5 @@
 mod access_token;
+mod ingest_policy;
 mod organization;
 mod playback_key_pair;
 mod playback_session;
@@ -6,9 +7,12 @@ mod playback_session_browser;
 mod playback_session_device;
 mod playback_session_platform;
 mod recording;
+mod recording_caption_segment;
 mod recording_config;
 mod recording_rendition;
 mod recording_rendition_segment;
+mod recording_source_chunk;
+mod recording_sprite;
 mod recording_thumbnail;
 mod rendition;
 mod room;
@@ -19,6 +23,7 @@ mod transcoding_config;
 mod visibility;
 
 pub use access_token::*;
+pub use ingest_policy::*;
 pub use organization::*;
 pub use playback_key_pair::*;
 pub use playback_session::*;
@@ -26,9 +31,12 @@ pub use playback_session_browser::*;
 pub use playback_session_device::*;
 pub use playback_session_platform::*;
 pub use recording::*;
+pub use recording_caption_segment::*;
 pub use recording_config::*;
 pub use recording_rendition::*;
 pub use recording_rendition_segment::*;
+pub use recording_source_chunk::*;
+pub use recording_sprite::*;
 pub use recording_thumbnail::*;
 pub use rendition::*;
 pub use room::*;
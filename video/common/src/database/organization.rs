@@ -20,6 +20,16 @@ pub struct Organization {
 	/// Tags associated with the organization
 	#[from_row(from_fn = "json")]
 	pub tags: HashMap<String, String>,
+
+	/// The url of the hook which authorizes publish requests (optional)
+	pub ingest_auth_url: Option<String>,
+
+	/// How long to wait for the hook in milliseconds
+	pub ingest_auth_timeout: i64,
+
+	/// If publishing is allowed when the hook fails, the stream key is still
+	/// checked
+	pub ingest_auth_fail_open: bool,
 }
 
 impl DatabaseTable for Organization {
@@ -0,0 +1,35 @@
+use postgres_from_row::FromRow;
+use ulid::Ulid;
+
+use super::DatabaseTable;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RecordingCaptionSegment {
+	/// The organization this recording caption segment belongs to (primary
+	/// key)
+	pub organization_id: Ulid,
+	/// The recording this caption segment belongs to (primary key)
+	pub recording_id: Ulid,
+	/// The index of the caption segment, the same as the video segment it is
+	/// cut with (primary key)
+	pub idx: i32,
+
+	/// The unique id for the caption segment
+	pub id: Ulid,
+
+	/// The start time of the caption segment (relative to the start of the
+	/// recording)
+	pub start_time: f32,
+
+	/// The end time of the caption segment (relative to the start of the
+	/// recording)
+	pub end_time: f32,
+
+	/// The size of the caption segment in bytes
+	pub size_bytes: i64,
+}
+
+impl DatabaseTable for RecordingCaptionSegment {
+	const FRIENDLY_NAME: &'static str = "recording caption segment";
+	const NAME: &'static str = "recording_caption_segments";
+}
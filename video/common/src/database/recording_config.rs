@@ -30,6 +30,9 @@ pub struct RecordingConfig {
 	/// Tags associated with the recording config
 	#[from_row(from_fn = "json")]
 	pub tags: HashMap<String, String>,
+
+	/// Whether the source stream is archived as well
+	pub capture_source: bool,
 }
 
 impl DatabaseTable for RecordingConfig {
@@ -50,6 +53,7 @@ impl RecordingConfig {
 			created_at: self.id.timestamp_ms() as i64,
 			updated_at: self.updated_at.timestamp_millis(),
 			tags: Some(self.tags.into()),
+			capture_source: self.capture_source,
 		}
 	}
 }
@@ -26,6 +26,10 @@ pub struct RecordingRenditionSegment {
 
 	/// The size of the segment in bytes
 	pub size_bytes: i32,
+
+	/// The segment is the first one after a discontinuity, such as when the
+	/// broadcaster reconnected
+	pub discontinuity: bool,
 }
 
 impl DatabaseTable for RecordingRenditionSegment {
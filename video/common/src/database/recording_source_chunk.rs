@@ -0,0 +1,33 @@
+use postgres_from_row::FromRow;
+use ulid::Ulid;
+
+use super::DatabaseTable;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RecordingSourceChunk {
+	/// The organization this recording source chunk belongs to (primary key)
+	pub organization_id: Ulid,
+	/// The recording this source chunk belongs to (primary key)
+	pub recording_id: Ulid,
+	/// The ingest connection the chunk was received on (primary key)
+	pub connection_id: Ulid,
+	/// The index of the chunk within the connection (primary key)
+	pub idx: i32,
+
+	/// The unique id for the chunk
+	pub id: Ulid,
+
+	/// The start time of the chunk (on the timeline of the connection)
+	pub start_time: f32,
+
+	/// The end time of the chunk (on the timeline of the connection)
+	pub end_time: f32,
+
+	/// The size of the chunk in bytes
+	pub size_bytes: i64,
+}
+
+impl DatabaseTable for RecordingSourceChunk {
+	const FRIENDLY_NAME: &'static str = "recording source chunk";
+	const NAME: &'static str = "recording_source_chunks";
+}
@@ -0,0 +1,47 @@
+use postgres_from_row::FromRow;
+use ulid::Ulid;
+
+use super::DatabaseTable;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RecordingSprite {
+	/// The organization this recording sprite belongs to (primary key)
+	pub organization_id: Ulid,
+	/// The recording this sprite belongs to (primary key)
+	pub recording_id: Ulid,
+	/// The index of the sprite (primary key)
+	pub idx: i32,
+
+	/// The unique id for the sprite
+	pub id: Ulid,
+
+	/// The time of the first tile (relative to the start of the recording)
+	pub start_time: f32,
+
+	/// The time the last tile is shown until (relative to the start of the
+	/// recording)
+	pub end_time: f32,
+
+	/// The time of every tile on the sheet, in the order they are laid out
+	pub tile_times: Vec<f32>,
+
+	/// The width of a single tile in pixels
+	pub tile_width: i32,
+
+	/// The height of a single tile in pixels
+	pub tile_height: i32,
+
+	/// The number of tiles in a row
+	pub columns: i32,
+
+	/// The number of rows on the sheet
+	pub rows: i32,
+
+	/// The size of the sprite in bytes
+	pub size_bytes: i64,
+}
+
+impl DatabaseTable for RecordingSprite {
+	const FRIENDLY_NAME: &'static str = "recording sprite";
+	const NAME: &'static str = "recording_sprites";
+}
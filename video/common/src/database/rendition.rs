@@ -14,30 +14,100 @@ pub enum Rendition {
 	VideoLd,
 	#[postgres(name = "AUDIO_SOURCE")]
 	AudioSource,
+	#[postgres(name = "VIDEO_CUSTOM_1")]
+	VideoCustom1,
+	#[postgres(name = "VIDEO_CUSTOM_2")]
+	VideoCustom2,
+	#[postgres(name = "VIDEO_CUSTOM_3")]
+	VideoCustom3,
+	#[postgres(name = "VIDEO_CUSTOM_4")]
+	VideoCustom4,
+	#[postgres(name = "VIDEO_CUSTOM_5")]
+	VideoCustom5,
+	#[postgres(name = "VIDEO_CUSTOM_6")]
+	VideoCustom6,
+	#[postgres(name = "VIDEO_CUSTOM_7")]
+	VideoCustom7,
+	#[postgres(name = "VIDEO_CUSTOM_8")]
+	VideoCustom8,
+	#[postgres(name = "AUDIO_AAC_HE")]
+	AudioAacHe,
+	#[postgres(name = "AUDIO_OPUS")]
+	AudioOpus,
+	#[postgres(name = "AUDIO_STEREO")]
+	AudioStereo,
 }
 
 impl Rendition {
 	pub fn is_video(self) -> bool {
 		match self {
-			Self::VideoSource | Self::VideoHd | Self::VideoSd | Self::VideoLd => true,
-			Self::AudioSource => false,
+			Self::VideoSource
+			| Self::VideoHd
+			| Self::VideoSd
+			| Self::VideoLd
+			| Self::VideoCustom1
+			| Self::VideoCustom2
+			| Self::VideoCustom3
+			| Self::VideoCustom4
+			| Self::VideoCustom5
+			| Self::VideoCustom6
+			| Self::VideoCustom7
+			| Self::VideoCustom8 => true,
+			Self::AudioSource | Self::AudioAacHe | Self::AudioOpus | Self::AudioStereo => false,
 		}
 	}
 
 	pub fn is_audio(self) -> bool {
 		match self {
-			Self::VideoSource | Self::VideoHd | Self::VideoSd | Self::VideoLd => false,
-			Self::AudioSource => true,
+			Self::VideoSource
+			| Self::VideoHd
+			| Self::VideoSd
+			| Self::VideoLd
+			| Self::VideoCustom1
+			| Self::VideoCustom2
+			| Self::VideoCustom3
+			| Self::VideoCustom4
+			| Self::VideoCustom5
+			| Self::VideoCustom6
+			| Self::VideoCustom7
+			| Self::VideoCustom8 => false,
+			Self::AudioSource | Self::AudioAacHe | Self::AudioOpus | Self::AudioStereo => true,
 		}
 	}
 
-	pub const fn variants() -> [Rendition; 5] {
+	/// Whether the rendition is a rung of a custom ladder.
+	pub fn is_custom(self) -> bool {
+		matches!(
+			self,
+			Self::VideoCustom1
+				| Self::VideoCustom2
+				| Self::VideoCustom3
+				| Self::VideoCustom4
+				| Self::VideoCustom5
+				| Self::VideoCustom6
+				| Self::VideoCustom7
+				| Self::VideoCustom8
+		)
+	}
+
+	pub const fn variants() -> [Rendition; 16] {
 		[
 			Self::VideoSource,
 			Self::VideoHd,
 			Self::VideoSd,
 			Self::VideoLd,
 			Self::AudioSource,
+			Self::VideoCustom1,
+			Self::VideoCustom2,
+			Self::VideoCustom3,
+			Self::VideoCustom4,
+			Self::VideoCustom5,
+			Self::VideoCustom6,
+			Self::VideoCustom7,
+			Self::VideoCustom8,
+			Self::AudioAacHe,
+			Self::AudioOpus,
+			Self::AudioStereo,
 		]
 	}
 }
@@ -50,6 +120,17 @@ impl From<Rendition> for pb::scuffle::video::v1::types::Rendition {
 			Rendition::VideoSd => Self::VideoSd,
 			Rendition::VideoLd => Self::VideoLd,
 			Rendition::AudioSource => Self::AudioSource,
+			Rendition::VideoCustom1 => Self::VideoCustom1,
+			Rendition::VideoCustom2 => Self::VideoCustom2,
+			Rendition::VideoCustom3 => Self::VideoCustom3,
+			Rendition::VideoCustom4 => Self::VideoCustom4,
+			Rendition::VideoCustom5 => Self::VideoCustom5,
+			Rendition::VideoCustom6 => Self::VideoCustom6,
+			Rendition::VideoCustom7 => Self::VideoCustom7,
+			Rendition::VideoCustom8 => Self::VideoCustom8,
+			Rendition::AudioAacHe => Self::AudioAacHe,
+			Rendition::AudioOpus => Self::AudioOpus,
+			Rendition::AudioStereo => Self::AudioStereo,
 		}
 	}
 }
@@ -62,6 +143,17 @@ impl From<pb::scuffle::video::v1::types::Rendition> for Rendition {
 			pb::scuffle::video::v1::types::Rendition::VideoSd => Self::VideoSd,
 			pb::scuffle::video::v1::types::Rendition::VideoLd => Self::VideoLd,
 			pb::scuffle::video::v1::types::Rendition::AudioSource => Self::AudioSource,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom1 => Self::VideoCustom1,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom2 => Self::VideoCustom2,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom3 => Self::VideoCustom3,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom4 => Self::VideoCustom4,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom5 => Self::VideoCustom5,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom6 => Self::VideoCustom6,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom7 => Self::VideoCustom7,
+			pb::scuffle::video::v1::types::Rendition::VideoCustom8 => Self::VideoCustom8,
+			pb::scuffle::video::v1::types::Rendition::AudioAacHe => Self::AudioAacHe,
+			pb::scuffle::video::v1::types::Rendition::AudioOpus => Self::AudioOpus,
+			pb::scuffle::video::v1::types::Rendition::AudioStereo => Self::AudioStereo,
 		}
 	}
 }
@@ -74,6 +166,17 @@ impl std::fmt::Display for Rendition {
 			Self::VideoSd => write!(f, "video_sd"),
 			Self::VideoLd => write!(f, "video_ld"),
 			Self::AudioSource => write!(f, "audio_source"),
+			Self::VideoCustom1 => write!(f, "video_custom_1"),
+			Self::VideoCustom2 => write!(f, "video_custom_2"),
+			Self::VideoCustom3 => write!(f, "video_custom_3"),
+			Self::VideoCustom4 => write!(f, "video_custom_4"),
+			Self::VideoCustom5 => write!(f, "video_custom_5"),
+			Self::VideoCustom6 => write!(f, "video_custom_6"),
+			Self::VideoCustom7 => write!(f, "video_custom_7"),
+			Self::VideoCustom8 => write!(f, "video_custom_8"),
+			Self::AudioAacHe => write!(f, "audio_aac_he"),
+			Self::AudioOpus => write!(f, "audio_opus"),
+			Self::AudioStereo => write!(f, "audio_stereo"),
 		}
 	}
 }
@@ -88,6 +191,17 @@ impl std::str::FromStr for Rendition {
 			"video_sd" => Ok(Self::VideoSd),
 			"video_ld" => Ok(Self::VideoLd),
 			"audio_source" => Ok(Self::AudioSource),
+			"video_custom_1" => Ok(Self::VideoCustom1),
+			"video_custom_2" => Ok(Self::VideoCustom2),
+			"video_custom_3" => Ok(Self::VideoCustom3),
+			"video_custom_4" => Ok(Self::VideoCustom4),
+			"video_custom_5" => Ok(Self::VideoCustom5),
+			"video_custom_6" => Ok(Self::VideoCustom6),
+			"video_custom_7" => Ok(Self::VideoCustom7),
+			"video_custom_8" => Ok(Self::VideoCustom8),
+			"audio_aac_he" => Ok(Self::AudioAacHe),
+			"audio_opus" => Ok(Self::AudioOpus),
+			"audio_stereo" => Ok(Self::AudioStereo),
 			_ => Err(()),
 		}
 	}
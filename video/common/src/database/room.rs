@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use pb::scuffle::video::v1::types::{AudioConfig, RecordingConfig, TranscodingConfig, VideoConfig};
+use pb::scuffle::video::v1::types::{
+	AudioConfig, AudioLoudness, IngestHealth, IpFilter, RecordingConfig, TranscodingConfig, VideoConfig,
+};
 use postgres_from_row::FromRow;
 use ulid::Ulid;
 use utils::database::{json, protobuf_opt, protobuf_vec_opt};
@@ -63,6 +65,14 @@ pub struct Room {
 	/// The ingest bitrate
 	pub ingest_bitrate: Option<i64>,
 
+	/// The health of the input of the active ingest connection
+	#[from_row(from_fn = "protobuf_opt")]
+	pub ingest_health: Option<IngestHealth>,
+
+	/// The loudness of the audio of the active ingest connection
+	#[from_row(from_fn = "protobuf_opt")]
+	pub audio_loudness: Option<AudioLoudness>,
+
 	/// The video output configs after transcoding
 	#[from_row(from_fn = "protobuf_vec_opt")]
 	pub video_output: Option<Vec<VideoConfig>>,
@@ -74,6 +84,24 @@ pub struct Room {
 	/// Tags associated with the room
 	#[from_row(from_fn = "json")]
 	pub tags: HashMap<String, String>,
+
+	/// The url to pull the stream from, if the room is not push based
+	pub source_url: Option<String>,
+
+	/// The lease held by the ingest server pulling the stream
+	pub source_lease_id: Option<Ulid>,
+
+	/// The date and time the pull lease expires
+	pub source_lease_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+
+	/// The ingest policy this room uses
+	pub ingest_policy_id: Option<Ulid>,
+
+	/// The ip addresses or CIDR ranges allowed to publish to the room
+	pub ingest_ip_allow: Vec<String>,
+
+	/// The ip addresses or CIDR ranges denied from publishing to the room
+	pub ingest_ip_deny: Vec<String>,
 }
 
 impl DatabaseTable for Room {
@@ -100,6 +128,14 @@ impl Room {
 			active_recording_id: self.active_recording_id.map(|r| r.into()),
 			active_connection_id: self.active_ingest_connection_id.map(|c| c.into()),
 			tags: Some(self.tags.into()),
+			source_url: self.source_url,
+			ingest_policy_id: self.ingest_policy_id.map(|id| id.into()),
+			ingest_ip_filter: Some(IpFilter {
+				allow: self.ingest_ip_allow,
+				deny: self.ingest_ip_deny,
+			}),
+			ingest_health: self.ingest_health,
+			audio_loudness: self.audio_loudness,
 		}
 	}
 }
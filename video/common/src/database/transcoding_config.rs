@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
-use pb::scuffle::video::v1::types::Rendition as PbRendition;
+use pb::scuffle::video::v1::types::{
+	ContentAwareEncoding, CustomRendition, LoudnessNormalization, Overlay, Rendition as PbRendition,
+};
 use postgres_from_row::FromRow;
 use ulid::Ulid;
-use utils::database::json;
+use utils::database::{json, protobuf_opt, protobuf_vec};
 
 use super::{DatabaseTable, Rendition};
 
@@ -23,6 +25,25 @@ pub struct TranscodingConfig {
 	/// Tags associated with the transcoding config
 	#[from_row(from_fn = "json")]
 	pub tags: HashMap<String, String>,
+
+	/// The rungs of the custom ladder this transcoding config uses
+	#[from_row(from_fn = "protobuf_vec")]
+	pub custom_renditions: Vec<CustomRendition>,
+
+	/// The overlays drawn onto the transcoded renditions
+	#[from_row(from_fn = "protobuf_vec")]
+	pub overlays: Vec<Overlay>,
+
+	/// The loudness normalization of the transcoded audio renditions
+	#[from_row(from_fn = "protobuf_opt")]
+	pub loudness_normalization: Option<LoudnessNormalization>,
+
+	/// The content aware encoding of the transcoded video renditions
+	#[from_row(from_fn = "protobuf_opt")]
+	pub content_aware_encoding: Option<ContentAwareEncoding>,
+
+	/// If the captions embedded in the source video are extracted
+	pub captions: bool,
 }
 
 impl DatabaseTable for TranscodingConfig {
@@ -41,6 +62,11 @@ impl TranscodingConfig {
 			created_at: self.id.timestamp_ms() as i64,
 			updated_at: self.updated_at.timestamp_micros(),
 			tags: Some(self.tags.into()),
+			custom_renditions: self.custom_renditions,
+			overlays: self.overlays,
+			loudness_normalization: self.loudness_normalization,
+			content_aware_encoding: self.content_aware_encoding,
+			captions: self.captions,
 		}
 	}
 }
@@ -0,0 +1,115 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An ip address or a CIDR range, such as `10.0.0.0/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+	addr: IpAddr,
+	prefix: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidIpRange;
+
+impl fmt::Display for InvalidIpRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid ip address or CIDR range")
+	}
+}
+
+impl std::error::Error for InvalidIpRange {}
+
+impl FromStr for IpRange {
+	type Err = InvalidIpRange;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (addr, prefix) = match s.split_once('/') {
+			Some((addr, prefix)) => (addr, Some(prefix)),
+			None => (s, None),
+		};
+
+		let addr = IpAddr::from_str(addr).map_err(|_| InvalidIpRange)?.to_canonical();
+		let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+
+		let prefix = match prefix {
+			Some(prefix) => prefix.parse::<u8>().map_err(|_| InvalidIpRange)?,
+			None => max_prefix,
+		};
+
+		if prefix > max_prefix {
+			return Err(InvalidIpRange);
+		}
+
+		Ok(Self { addr, prefix })
+	}
+}
+
+impl fmt::Display for IpRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.addr, self.prefix)
+	}
+}
+
+impl IpRange {
+	pub fn contains(&self, ip: IpAddr) -> bool {
+		match (self.addr, ip.to_canonical()) {
+			(IpAddr::V4(addr), IpAddr::V4(ip)) => {
+				let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+				u32::from(addr) & mask == u32::from(ip) & mask
+			}
+			(IpAddr::V6(addr), IpAddr::V6(ip)) => {
+				let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+				u128::from(addr) & mask == u128::from(ip) & mask
+			}
+			_ => false,
+		}
+	}
+}
+
+/// Checks an ip against an allow and a deny list. The deny list takes
+/// precedence, and an empty allow list allows everyone. Entries which fail to
+/// parse match no ip, so an allow list with only invalid entries allows no one.
+/// The api rejects invalid entries before they are stored.
+pub fn ip_allowed(allow: &[String], deny: &[String], ip: IpAddr) -> bool {
+	let matches = |list: &[String]| {
+		list.iter()
+			.filter_map(|range| range.parse::<IpRange>().ok())
+			.any(|range| range.contains(ip))
+	};
+
+	!matches(deny) && (allow.is_empty() || matches(allow))
+}
+
+/// Checks whether an ip can be reached from the public internet. Loopback,
+/// private, shared, link-local, unspecified, broadcast and multicast
+/// addresses cannot.
+pub fn is_public(ip: IpAddr) -> bool {
+	match ip.to_canonical() {
+		IpAddr::V4(ip) => {
+			let [a, b, ..] = ip.octets();
+
+			!(ip.is_loopback()
+				|| ip.is_private()
+				|| ip.is_link_local()
+				|| ip.is_unspecified()
+				|| ip.is_broadcast()
+				|| ip.is_multicast()
+				// 0.0.0.0/8
+				|| a == 0
+				// 100.64.0.0/10
+				|| (a == 100 && b & 0xC0 == 64))
+		}
+		IpAddr::V6(ip) => {
+			let first = ip.segments()[0];
+
+			!(ip.is_loopback()
+				|| ip.is_unspecified()
+				|| ip.is_multicast()
+				// fc00::/7
+				|| first & 0xFE00 == 0xFC00
+				// fe80::/10
+				|| first & 0xFFC0 == 0xFE80)
+		}
+	}
+}
@@ -22,6 +22,14 @@ pub fn screenshot(organization_id: Ulid, room_id: Ulid, connection_id: Ulid, idx
 	format!("{organization_id}.{room_id}.{connection_id}.screenshot.{idx}",)
 }
 
+pub fn sprite(organization_id: Ulid, room_id: Ulid, connection_id: Ulid, idx: u32) -> String {
+	format!("{organization_id}.{room_id}.{connection_id}.sprite.{idx}",)
+}
+
+pub fn captions(organization_id: Ulid, room_id: Ulid, connection_id: Ulid, idx: u32) -> String {
+	format!("{organization_id}.{room_id}.{connection_id}.captions.{idx}",)
+}
+
 pub fn s3_segment(
 	organization_id: Ulid,
 	recording_id: Ulid,
@@ -36,14 +44,48 @@ pub fn s3_thumbnail(organization_id: Ulid, recording_id: Ulid, thumbnail_idx: u3
 	format!("{organization_id}/{recording_id}/thumbnails/{thumbnail_idx}.{thumbnail_id}.jpg",)
 }
 
+pub fn s3_sprite(organization_id: Ulid, recording_id: Ulid, sprite_idx: u32, sprite_id: Ulid) -> String {
+	format!("{organization_id}/{recording_id}/sprites/{sprite_idx}.{sprite_id}.jpg",)
+}
+
+pub fn s3_captions(organization_id: Ulid, recording_id: Ulid, segment_idx: u32, segment_id: Ulid) -> String {
+	format!("{organization_id}/{recording_id}/captions/{segment_idx}.{segment_id}.vtt",)
+}
+
 pub fn s3_init(organization_id: Ulid, recording_id: Ulid, rendition: Rendition) -> String {
 	format!("{organization_id}/{recording_id}/{rendition}/init.mp4",)
 }
 
+pub fn s3_source_chunk(
+	organization_id: Ulid,
+	recording_id: Ulid,
+	connection_id: Ulid,
+	chunk_idx: u32,
+	chunk_id: Ulid,
+) -> String {
+	format!("{organization_id}/{recording_id}/source/{connection_id}/{chunk_idx}.{chunk_id}.mp4")
+}
+
+pub fn s3_source_init(organization_id: Ulid, recording_id: Ulid, connection_id: Ulid) -> String {
+	format!("{organization_id}/{recording_id}/source/{connection_id}/init.mp4")
+}
+
+pub fn s3_upload(organization_id: Ulid, recording_id: Ulid) -> String {
+	format!("{organization_id}/{recording_id}/upload")
+}
+
 pub fn ingest_disconnect(session_id: Ulid) -> String {
 	format!("ingest.{session_id}.disconnect")
 }
 
+pub fn ingest_whip_unpublish(session_id: Ulid) -> String {
+	format!("ingest.{session_id}.whip_unpublish")
+}
+
+pub fn ingest_heartbeat(organization_id: Ulid, room_id: Ulid) -> String {
+	format!("ingest.{organization_id}.{room_id}.heartbeat")
+}
+
 pub fn event_subject(
 	stream_name: &str,
 	organization_id: Ulid,
@@ -51,3 +93,7 @@ pub fn event_subject(
 ) -> String {
 	format!("{stream_name}.{organization_id}.{}", target.as_str_name())
 }
+
+pub fn transcoder_load(subject: &str, transcoder_id: Ulid) -> String {
+	format!("{subject}.{transcoder_id}")
+}
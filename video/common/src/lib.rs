@@ -1,4 +1,5 @@
 pub mod database;
 pub mod events;
 pub mod ext;
+pub mod ip_range;
 pub mod keys;
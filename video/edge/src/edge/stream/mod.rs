@@ -21,9 +21,9 @@ use utils::http::router::Router;
 use utils::http::RouteError;
 use utils::make_response;
 use utils::prelude::FutureTimeout;
-use video_common::database::{Rendition, Room, RoomStatus, Visibility};
+use video_common::database::{Recording, RecordingSprite, Rendition, Room, RoomStatus, Visibility};
 use video_common::keys;
-use video_player_types::SessionRefresh;
+use video_player_types::{SessionRefresh, SpriteIndex};
 
 use self::tokens::{ScreenshotClaims, SessionClaims, SessionClaimsType};
 use super::error::Result;
@@ -203,6 +203,7 @@ async fn room_playlist<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Response
 		token.is_some(),
 		&audio_output,
 		&video_output,
+		room.active_transcoding_config.as_ref().is_some_and(|c| c.captions),
 	)?;
 
 	let body = if config.scuffle_json {
@@ -297,6 +298,16 @@ async fn recording_playlist<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Res
 		return Err((StatusCode::UNAUTHORIZED, "recording is private, token is required").into());
 	}
 
+	let captions: bool = utils::database::query(
+		"SELECT EXISTS(SELECT 1 FROM recording_caption_segments WHERE recording_id = $1 AND organization_id = $2)",
+	)
+	.bind(recording_id)
+	.bind(organization_id)
+	.build_query_single_scalar()
+	.fetch_one(&client)
+	.await
+	.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to query database"))?;
+
 	let audio_output = recording
 		.renditions
 		.iter()
@@ -403,6 +414,7 @@ async fn recording_playlist<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Res
 		token.is_some(),
 		&audio_output,
 		&video_output,
+		captions,
 	)?;
 
 	let body = if config.scuffle_json {
@@ -438,8 +450,6 @@ async fn session_playlist<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Respo
 
 	let session = req.param("session").unwrap();
 
-	let rendition = rendition(&req)?;
-
 	let session = SessionClaims::verify(&global, organization_id, session)?;
 
 	let client = global
@@ -471,6 +481,36 @@ async fn session_playlist<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Respo
 		return Err((StatusCode::BAD_REQUEST, "invalid session, expired or not found").into());
 	}
 
+	if req.param("rendition") == Some("captions") {
+		let playlist = playlist::subtitle_playlist(&global, &client, &session).await?;
+
+		let body = if config.scuffle_json {
+			Body::from(
+				serde_json::to_string(&playlist)
+					.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to encode playlist"))?,
+			)
+		} else {
+			Body::from(playlist.to_m3u8())
+		};
+
+		let mut resp = Response::new(body);
+		resp.headers_mut().insert(
+			"Content-Type",
+			if config.scuffle_json {
+				"application/json"
+			} else {
+				"application/vnd.apple.mpegurl"
+			}
+			.parse()
+			.unwrap(),
+		);
+		resp.headers_mut().insert("Cache-Control", "no-cache".parse().unwrap());
+
+		return Ok(resp);
+	}
+
+	let rendition = rendition(&req)?;
+
 	let manifest = if let SessionClaimsType::Room { room_id, connection_id } = session.ty {
 		let mut subscription = global
 			.subscriber()
@@ -775,6 +815,8 @@ async fn room_screenshot<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Respon
 		idx: manifest.screenshot_idx,
 		organization_id,
 		room_id,
+		sprite: false,
+		captions: false,
 	}
 	.sign(&global)?;
 
@@ -812,7 +854,15 @@ async fn room_screenshot_media<G: EdgeGlobal>(req: Request<Incoming>) -> Result<
 		return Err((StatusCode::BAD_REQUEST, "invalid media, room_name mismatch").into());
 	}
 
-	let key = keys::screenshot(organization_id, room_id, claims.connection_id, claims.idx);
+	if claims.captions {
+		return Err((StatusCode::BAD_REQUEST, "invalid media, not a screenshot").into());
+	}
+
+	let key = if claims.sprite {
+		keys::sprite(organization_id, room_id, claims.connection_id, claims.idx)
+	} else {
+		keys::screenshot(organization_id, room_id, claims.connection_id, claims.idx)
+	};
 
 	tracing::debug!(key = %key, "getting screenshot");
 
@@ -830,12 +880,257 @@ async fn room_screenshot_media<G: EdgeGlobal>(req: Request<Incoming>) -> Result<
 
 	let mut resp = Response::new(Body::from(buf));
 	resp.headers_mut().insert("Content-Type", "image/jpeg".parse().unwrap());
-	resp.headers_mut()
-		.insert("Cache-Control", "max-age=31536000".parse().unwrap());
+	// The sprite sheet which is being filled is replaced every time a tile is
+	// added.
+	resp.headers_mut().insert(
+		"Cache-Control",
+		if claims.sprite { "no-cache" } else { "max-age=31536000" }.parse().unwrap(),
+	);
+
+	Ok(resp)
+}
+
+async fn room_captions_media<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Response<Body>> {
+	let global = req.get_global::<G, _>()?;
+
+	let organization_id = organization_id(&req)?;
+	let room_id = room_id(&req)?;
+
+	let captions = req.param("captions").unwrap();
+
+	let claims = ScreenshotClaims::verify(&global, organization_id, room_id, captions)?;
+
+	if !claims.captions {
+		return Err((StatusCode::BAD_REQUEST, "invalid media, not a caption segment").into());
+	}
+
+	let key = keys::captions(organization_id, room_id, claims.connection_id, claims.idx);
+
+	tracing::debug!(key = %key, "getting captions");
+
+	let mut item = global
+		.media_store()
+		.get(&key)
+		.await
+		.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to get captions"))?;
+
+	let mut buf = Vec::new();
+
+	item.read_to_end(&mut buf)
+		.await
+		.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to read captions"))?;
+
+	let mut resp = Response::new(Body::from(buf));
+	resp.headers_mut().insert("Content-Type", "text/vtt".parse().unwrap());
+	resp.headers_mut().insert("Cache-Control", "max-age=31536000".parse().unwrap());
+
+	Ok(resp)
+}
+
+fn sprite_index_response(config: &HlsConfig, index: &SpriteIndex) -> Result<Response<Body>> {
+	let body = if config.scuffle_json {
+		Body::from(
+			serde_json::to_string(index).map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to encode sprites"))?,
+		)
+	} else {
+		Body::from(index.to_vtt())
+	};
+
+	let mut resp = Response::new(body);
+	resp.headers_mut().insert(
+		"Content-Type",
+		if config.scuffle_json { "application/json" } else { "text/vtt" }
+			.parse()
+			.unwrap(),
+	);
+	resp.headers_mut().insert("Cache-Control", "no-cache".parse().unwrap());
 
 	Ok(resp)
 }
 
+async fn room_sprites<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Response<Body>> {
+	let config = HlsConfig::new(&req)?;
+
+	let global = req.get_global::<G, _>()?;
+
+	let organization_id = organization_id(&req)?;
+	let room_id = room_id(&req)?;
+
+	let client = global
+		.db()
+		.get()
+		.await
+		.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to get database"))?;
+
+	let token = if let Some(token) = token(&req) {
+		Some(tokens::TokenClaims::verify(&client, organization_id, tokens::TargetId::Room(room_id), &token).await?)
+	} else {
+		None
+	};
+
+	let room: Option<Room> = utils::database::query(
+		r#"
+		SELECT
+			*
+		FROM
+			rooms
+		WHERE
+			organization_id = $1
+			AND id = $2
+			AND status != $3
+		"#,
+	)
+	.bind(organization_id)
+	.bind(room_id)
+	.bind(RoomStatus::Offline)
+	.build_query_as()
+	.fetch_optional(client)
+	.await
+	.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to query database"))?;
+
+	let room = room.ok_or((StatusCode::NOT_FOUND, "room not found"))?;
+
+	let connection_id = room
+		.active_ingest_connection_id
+		.ok_or((StatusCode::NOT_FOUND, "room not found"))?;
+
+	if room.visibility != Visibility::Public && token.is_none() {
+		return Err((StatusCode::UNAUTHORIZED, "room is private, token is required").into());
+	}
+
+	let manifest = global
+		.metadata_store()
+		.get(keys::manifest(organization_id, room_id, connection_id))
+		.await
+		.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to get manifest"))?
+		.ok_or((StatusCode::NOT_FOUND, "manifest not found"))?;
+
+	let manifest =
+		LiveManifest::decode(manifest).map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to decode manifest"))?;
+
+	let layout = manifest.sprite_layout.unwrap_or_default();
+
+	let mut index = SpriteIndex::default();
+
+	for sprite in &manifest.sprites {
+		let token = ScreenshotClaims {
+			connection_id,
+			idx: sprite.idx,
+			organization_id,
+			room_id,
+			sprite: true,
+			captions: false,
+		}
+		.sign(&global)?;
+
+		index.push_sheet(
+			&format!("/{organization_id}/{room_id}/{token}.jpg"),
+			&sprite.tile_times.iter().map(|t| *t as f64).collect::<Vec<_>>(),
+			sprite.end_time as f64,
+			layout.tile_width,
+			layout.tile_height,
+			layout.columns,
+		);
+	}
+
+	sprite_index_response(&config, &index)
+}
+
+#[derive(Debug, Clone, postgres_from_row::FromRow)]
+struct RecordingSpriteExt {
+	public_url: String,
+	#[from_row(flatten)]
+	sprite: RecordingSprite,
+}
+
+async fn recording_sprites<G: EdgeGlobal>(req: Request<Incoming>) -> Result<Response<Body>> {
+	let config = HlsConfig::new(&req)?;
+
+	let global = req.get_global::<G, _>()?;
+
+	let organization_id = organization_id(&req)?;
+	let recording_id = recording_id(&req)?;
+
+	let client = global
+		.db()
+		.get()
+		.await
+		.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to get database"))?;
+
+	let token = if let Some(token) = token(&req) {
+		Some(tokens::TokenClaims::verify(&client, organization_id, tokens::TargetId::Recording(recording_id), &token).await?)
+	} else {
+		None
+	};
+
+	let recording: Option<Recording> = utils::database::query(
+		r#"
+		SELECT
+			*
+		FROM
+			recordings
+		WHERE
+			id = $1
+			AND organization_id = $2
+			AND deleted = FALSE
+		"#,
+	)
+	.bind(recording_id)
+	.bind(organization_id)
+	.build_query_as()
+	.fetch_optional(&client)
+	.await
+	.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to query database"))?;
+
+	let recording = recording.ok_or((StatusCode::NOT_FOUND, "recording not found"))?;
+
+	if recording.visibility != Visibility::Public && token.is_none() {
+		return Err((StatusCode::UNAUTHORIZED, "recording is private, token is required").into());
+	}
+
+	let sprites: Vec<RecordingSpriteExt> = utils::database::query(
+		r#"
+		SELECT
+			s.public_url,
+			rs.*
+		FROM recording_sprites rs
+		INNER JOIN recordings r
+			ON r.id = rs.recording_id
+			AND r.organization_id = rs.organization_id
+		INNER JOIN s3_buckets s
+			ON s.id = r.s3_bucket_id
+		WHERE
+			rs.recording_id = $1
+			AND rs.organization_id = $2
+		ORDER BY rs.idx
+		"#,
+	)
+	.bind(recording_id)
+	.bind(organization_id)
+	.build_query_as()
+	.fetch_all(&client)
+	.await
+	.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to query database"))?;
+
+	let mut index = SpriteIndex::default();
+
+	for RecordingSpriteExt { public_url, sprite } in &sprites {
+		index.push_sheet(
+			&format!(
+				"{public_url}/{}",
+				keys::s3_sprite(organization_id, recording_id, sprite.idx as u32, sprite.id)
+			),
+			&sprite.tile_times.iter().map(|t| *t as f64).collect::<Vec<_>>(),
+			sprite.end_time as f64,
+			sprite.tile_width as u32,
+			sprite.tile_height as u32,
+			sprite.columns as u32,
+		);
+	}
+
+	sprite_index_response(&config, &index)
+}
+
 pub fn routes<G: EdgeGlobal>(_: &Arc<G>) -> RouterBuilder<Incoming, Body, RouteError<EdgeError>> {
 	Router::builder()
 		.get("/:organization_id/:room_id.m3u8", room_playlist::<G>)
@@ -843,6 +1138,9 @@ pub fn routes<G: EdgeGlobal>(_: &Arc<G>) -> RouterBuilder<Incoming, Body, RouteE
 		.get("/:organization_id/:session/:rendition.m3u8", session_playlist::<G>)
 		.get("/:organization_id/:session/refresh", session_refresh::<G>)
 		.get("/:organization_id/:room_id.jpg", room_screenshot::<G>)
+		.get("/:organization_id/:room_id.vtt", room_sprites::<G>)
+		.get("/:organization_id/r/:recording_id.vtt", recording_sprites::<G>)
 		.get("/:organization_id/:room_id/:media.mp4", room_media::<G>)
 		.get("/:organization_id/:room_id/:screenshot.jpg", room_screenshot_media::<G>)
+		.get("/:organization_id/:room_id/:captions.vtt", room_captions_media::<G>)
 }
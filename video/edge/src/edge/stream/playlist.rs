@@ -2,19 +2,22 @@ use std::sync::Arc;
 
 use hyper::StatusCode;
 use pb::ext::UlidExt;
-use pb::scuffle::video::internal::LiveRenditionManifest;
+use pb::scuffle::video::internal::{LiveManifest, LiveRenditionManifest};
 use pb::scuffle::video::v1::types::{AudioConfig, VideoConfig};
+use prost::Message;
 use ulid::Ulid;
 use utils::database::non_null_vec;
 use utils::http::ext::*;
-use video_common::database::{Recording, RecordingThumbnail, Rendition, Visibility};
+use video_common::database::{Recording, RecordingCaptionSegment, RecordingThumbnail, Rendition, Visibility};
+use video_common::keys;
 use video_player_types::{
 	RenditionPlaylist, RenditionPlaylistRendition, RenditionPlaylistSegment, RenditionPlaylistSegmentPart,
-	RoomPlaylistTrack, RoomPlaylistTrackAudio, RoomPlaylistTrackVideo, SessionPlaylist, ThumbnailRange,
+	RoomPlaylistTrack, RoomPlaylistTrackAudio, RoomPlaylistTrackVideo, SessionPlaylist, SubtitlePlaylist,
+	SubtitlePlaylistSegment, ThumbnailRange,
 };
 
 use super::hls_config::HlsConfig;
-use super::tokens::{MediaClaimsType, SessionClaims, SessionClaimsType};
+use super::tokens::{MediaClaimsType, ScreenshotClaims, SessionClaims, SessionClaimsType};
 use crate::edge::error::Result;
 use crate::edge::stream::tokens::MediaClaims;
 use crate::global::EdgeGlobal;
@@ -36,6 +39,15 @@ struct RecordingRenditionExt {
 	pub segment_start_times: Vec<f32>,
 	#[from_row(from_fn = "non_null_vec")]
 	pub segment_end_times: Vec<f32>,
+	#[from_row(from_fn = "non_null_vec")]
+	pub segment_discontinuities: Vec<bool>,
+}
+
+#[derive(Debug, Clone, postgres_from_row::FromRow)]
+struct RecordingCaptionSegmentExt {
+	pub public_url: String,
+	#[from_row(flatten)]
+	pub segment: RecordingCaptionSegment,
 }
 
 #[inline(always)]
@@ -53,6 +65,7 @@ pub fn room_playlist<G: EdgeGlobal>(
 	was_authenticated: bool,
 	audio_output: &[AudioConfig],
 	video_output: &[VideoConfig],
+	captions: bool,
 ) -> Result<SessionPlaylist> {
 	let session = SessionClaims {
 		id,
@@ -68,6 +81,7 @@ pub fn room_playlist<G: EdgeGlobal>(
 			.iter()
 			.map(|a| RoomPlaylistTrack {
 				name: Rendition::from(a.rendition()).to_string(),
+				label: None,
 				bitrate: a.bitrate as u32,
 				codec: a.codec.clone(),
 				other: RoomPlaylistTrackAudio {
@@ -80,6 +94,7 @@ pub fn room_playlist<G: EdgeGlobal>(
 			.iter()
 			.map(|v| RoomPlaylistTrack {
 				name: Rendition::from(v.rendition()).to_string(),
+				label: Some(v.name.clone()).filter(|name| !name.is_empty()),
 				bitrate: v.bitrate as u32,
 				codec: v.codec.clone(),
 				other: RoomPlaylistTrackVideo {
@@ -90,6 +105,7 @@ pub fn room_playlist<G: EdgeGlobal>(
 			})
 			.collect(),
 		session,
+		captions,
 	})
 }
 
@@ -102,6 +118,7 @@ pub fn recording_playlist<G: EdgeGlobal>(
 	was_authenticated: bool,
 	audio_output: &[AudioConfig],
 	video_output: &[VideoConfig],
+	captions: bool,
 ) -> Result<SessionPlaylist> {
 	let session = SessionClaims {
 		id,
@@ -117,6 +134,7 @@ pub fn recording_playlist<G: EdgeGlobal>(
 			.iter()
 			.map(|a| RoomPlaylistTrack {
 				name: Rendition::from(a.rendition()).to_string(),
+				label: None,
 				bitrate: a.bitrate as u32,
 				codec: a.codec.clone(),
 				other: RoomPlaylistTrackAudio {
@@ -129,6 +147,7 @@ pub fn recording_playlist<G: EdgeGlobal>(
 			.iter()
 			.map(|v| RoomPlaylistTrack {
 				name: Rendition::from(v.rendition()).to_string(),
+				label: Some(v.name.clone()).filter(|name| !name.is_empty()),
 				bitrate: v.bitrate as u32,
 				codec: v.codec.clone(),
 				other: RoomPlaylistTrackVideo {
@@ -139,6 +158,7 @@ pub fn recording_playlist<G: EdgeGlobal>(
 			})
 			.collect(),
 		session,
+		captions,
 	})
 }
 
@@ -168,6 +188,7 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 		}
 		(Some(manifest), SessionClaimsType::Room { connection_id, room_id }) => {
 			playlist.msn = manifest.segments.first().map(|s| s.idx).unwrap_or_default();
+			playlist.discontinuity_sequence = manifest.discontinuity_sequence;
 			playlist.init_segment_id = MediaClaims {
 				connection_id,
 				organization_id: session.organization_id,
@@ -226,6 +247,7 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 
 	if let Some((recording_id, skip, active_idx, public_url)) = &recording_data {
 		playlist.msn = 0;
+		playlist.discontinuity_sequence = 0;
 
 		playlist.dvr_prefix = Some(
 			format!("{public_url}/{organization_id}/{recording_id}/{rendition}")
@@ -253,7 +275,8 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
                     ARRAY_AGG(rs.id) as segment_ids,
                     ARRAY_AGG(rs.idx) as segment_indexes,
                     ARRAY_AGG(rs.start_time) as segment_start_times,
-                    ARRAY_AGG(rs.end_time) as segment_end_times
+                    ARRAY_AGG(rs.end_time) as segment_end_times,
+                    ARRAY_AGG(rs.discontinuity) as segment_discontinuities
                 FROM filtered_renditions AS r
                 LEFT JOIN recording_rendition_segments as rs
                     ON rs.rendition = r.rendition
@@ -297,15 +320,18 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 
 			let mut discontinuity_count = 0;
 
-			for (true_idx, (segment_idx, start_time, end_time, segment_id)) in recording_rendition
+			for (true_idx, (segment_idx, start_time, end_time, segment_id, discontinuity)) in recording_rendition
 				.segment_indexes
 				.iter()
 				.copied()
 				.zip(recording_rendition.segment_start_times.iter().copied())
 				.zip(recording_rendition.segment_end_times.iter().copied())
 				.zip(recording_rendition.segment_ids.iter().copied())
-				.map(|(((idx, start_time), end_time), id)| (idx as u32, start_time, end_time, id))
-				.take_while(|(idx, _, _, _)| active_idx.map(|aidx| *idx < aidx).unwrap_or(true))
+				.zip(recording_rendition.segment_discontinuities.iter().copied())
+				.map(|((((idx, start_time), end_time), id), discontinuity)| {
+					(idx as u32, start_time, end_time, id, discontinuity)
+				})
+				.take_while(|(idx, _, _, _, _)| active_idx.map(|aidx| *idx < aidx).unwrap_or(true))
 				.enumerate()
 			{
 				if true_idx + discontinuity_count != segment_idx as usize {
@@ -316,6 +342,7 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 						id: None,
 						idx: segment_idx,
 						parts: vec![],
+						discontinuity: false,
 					});
 					discontinuity_count += 1;
 					continue;
@@ -333,6 +360,7 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 					id: None,
 					idx: segment_idx,
 					parts: vec![],
+					discontinuity,
 				});
 			}
 		} else if let Some(manifest) = manifest {
@@ -430,6 +458,7 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 				dvr_tag,
 				parts,
 				idx: segment.idx,
+				discontinuity: segment.discontinuity,
 			});
 		}
 
@@ -471,3 +500,89 @@ pub async fn rendition_playlist<G: EdgeGlobal>(
 
 	Ok(playlist)
 }
+
+pub async fn subtitle_playlist<G: EdgeGlobal>(
+	global: &Arc<G>,
+	client: &utils::database::tokio_postgres::Client,
+	session: &SessionClaims,
+) -> Result<SubtitlePlaylist> {
+	let organization_id = session.organization_id;
+
+	let mut playlist = SubtitlePlaylist::default();
+
+	match session.ty {
+		SessionClaimsType::Room { room_id, connection_id } => {
+			let manifest = global
+				.metadata_store()
+				.get(keys::manifest(organization_id, room_id, connection_id))
+				.await
+				.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to get manifest"))?
+				.ok_or((StatusCode::NOT_FOUND, "manifest not found"))?;
+
+			let manifest = LiveManifest::decode(manifest)
+				.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to decode manifest"))?;
+
+			playlist.msn = manifest.caption_segments.first().map(|s| s.idx).unwrap_or_default();
+
+			for segment in &manifest.caption_segments {
+				let token = ScreenshotClaims {
+					connection_id,
+					idx: segment.idx,
+					organization_id,
+					room_id,
+					sprite: false,
+					captions: true,
+				}
+				.sign(global)?;
+
+				playlist.segments.push(SubtitlePlaylistSegment {
+					url: format!("/{organization_id}/{room_id}/{token}.vtt"),
+					start_time: normalize_float(segment.start_time as f64),
+					end_time: normalize_float(segment.end_time as f64),
+				});
+			}
+		}
+		SessionClaimsType::Recording { recording_id } => {
+			let segments: Vec<RecordingCaptionSegmentExt> = utils::database::query(
+				r#"
+                SELECT
+                    s.public_url,
+                    cs.*
+                FROM recording_caption_segments cs
+                INNER JOIN recordings r
+                    ON r.id = cs.recording_id
+                    AND r.organization_id = cs.organization_id
+                INNER JOIN s3_buckets s
+                    ON s.id = r.s3_bucket_id
+                WHERE
+                    cs.recording_id = $1
+                    AND cs.organization_id = $2
+                    AND r.deleted = FALSE
+                ORDER BY cs.idx
+                "#,
+			)
+			.bind(recording_id)
+			.bind(organization_id)
+			.build_query_as()
+			.fetch_all(client)
+			.await
+			.map_err_route((StatusCode::INTERNAL_SERVER_ERROR, "failed to query database"))?;
+
+			playlist.msn = segments.first().map(|s| s.segment.idx as u32).unwrap_or_default();
+			playlist.finished = true;
+			playlist.segments = segments
+				.into_iter()
+				.map(|RecordingCaptionSegmentExt { public_url, segment }| SubtitlePlaylistSegment {
+					url: format!(
+						"{public_url}/{}",
+						keys::s3_captions(organization_id, recording_id, segment.idx as u32, segment.id)
+					),
+					start_time: normalize_float(segment.start_time as f64),
+					end_time: normalize_float(segment.end_time as f64),
+				})
+				.collect();
+		}
+	}
+
+	Ok(playlist)
+}
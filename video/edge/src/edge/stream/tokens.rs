@@ -301,6 +301,14 @@ pub struct ScreenshotClaims {
 	/// The index of the screenshot that is allowed to be accessed
 	#[serde(rename = "i")]
 	pub idx: u32,
+
+	/// If the index is of a sprite sheet instead of a screenshot
+	#[serde(rename = "s", default, skip_serializing_if = "std::ops::Not::not")]
+	pub sprite: bool,
+
+	/// If the index is of a caption segment instead of a screenshot
+	#[serde(rename = "cc", default, skip_serializing_if = "std::ops::Not::not")]
+	pub captions: bool,
 }
 
 impl ScreenshotClaims {
@@ -0,0 +1,8 @@
+#![no_main]
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = aac::AudioSpecificConfig::parse(Bytes::copy_from_slice(data));
+});
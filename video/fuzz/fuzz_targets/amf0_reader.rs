@@ -0,0 +1,24 @@
+#![no_main]
+
+use amf0::{Amf0Reader, Amf0Writer};
+use bytesio::bytes_writer::BytesWriter;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let Ok(values) = Amf0Reader::new(data.to_vec().into()).read_all() else {
+		return;
+	};
+
+	// Anything we can write back out must read back the same.
+	for value in values {
+		let mut writer = BytesWriter::default();
+		if Amf0Writer::write_any(&mut writer, &value).is_err() {
+			continue;
+		}
+
+		let read = Amf0Reader::new(writer.dispose()).read_any().expect("failed to read written value");
+		if !matches!(read, amf0::Amf0Value::Number(n) if n.is_nan()) {
+			assert_eq!(read, value);
+		}
+	}
+});
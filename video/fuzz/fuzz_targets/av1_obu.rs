@@ -0,0 +1,15 @@
+#![no_main]
+
+use av1::seq::SequenceHeaderObu;
+use av1::{ObuHeader, ObuType};
+use bytesio::bit_reader::BitReader;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let mut reader = BitReader::from(data.to_vec());
+	while let Ok((header, data)) = ObuHeader::parse(&mut reader) {
+		if header.obu_type == ObuType::SequenceHeader {
+			let _ = SequenceHeaderObu::parse(header, data);
+		}
+	}
+});
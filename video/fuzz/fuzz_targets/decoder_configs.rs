@@ -0,0 +1,35 @@
+#![no_main]
+
+use std::io;
+
+use av1::AV1CodecConfigurationRecord;
+use bytes::Bytes;
+use h264::AVCDecoderConfigurationRecord;
+use h265::HEVCDecoderConfigurationRecord;
+use libfuzzer_sys::fuzz_target;
+
+macro_rules! check_remux {
+	($ty:ty, $data:expr) => {
+		if let Ok(config) = <$ty>::demux(&mut io::Cursor::new($data)) {
+			let mut muxed = Vec::new();
+			config.mux(&mut muxed).expect("failed to mux");
+			assert_eq!(muxed.len() as u64, config.size());
+
+			let remuxed = <$ty>::demux(&mut io::Cursor::new(Bytes::from(muxed))).expect("failed to demux muxed config");
+			assert_eq!(remuxed, config);
+		}
+	};
+}
+
+fuzz_target!(|data: &[u8]| {
+	let Some((kind, data)) = data.split_first() else {
+		return;
+	};
+
+	let data = Bytes::copy_from_slice(data);
+	match kind % 3 {
+		0 => check_remux!(AVCDecoderConfigurationRecord, data),
+		1 => check_remux!(HEVCDecoderConfigurationRecord, data),
+		_ => check_remux!(AV1CodecConfigurationRecord, data),
+	}
+});
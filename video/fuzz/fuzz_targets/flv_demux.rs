@@ -0,0 +1,10 @@
+#![no_main]
+
+use std::io;
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = flv::Flv::demux(&mut io::Cursor::new(Bytes::copy_from_slice(data)));
+});
@@ -0,0 +1,32 @@
+#![no_main]
+
+use std::io;
+
+use bytes::{Buf, Bytes};
+use libfuzzer_sys::fuzz_target;
+use mp4::DynBox;
+
+fuzz_target!(|data: &[u8]| {
+	let mut reader = io::Cursor::new(Bytes::copy_from_slice(data));
+
+	let mut boxes = Vec::new();
+	while reader.has_remaining() {
+		match DynBox::demux(&mut reader) {
+			Ok(box_) => boxes.push(box_),
+			Err(_) => return,
+		}
+	}
+
+	// Anything we can demux and mux again must demux to the same boxes.
+	let mut muxed = Vec::new();
+	for box_ in &boxes {
+		if box_.mux(&mut muxed).is_err() {
+			return;
+		}
+	}
+
+	let mut reader = io::Cursor::new(Bytes::from(muxed));
+	for box_ in boxes {
+		assert_eq!(DynBox::demux(&mut reader).expect("failed to demux muxed box"), box_);
+	}
+});
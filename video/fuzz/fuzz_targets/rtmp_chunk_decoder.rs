@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rtmp::ChunkDecoder;
+
+fuzz_target!(|data: &[u8]| {
+	let mut decoder = ChunkDecoder::default();
+
+	// Feed the data in small pieces to exercise the partial chunk handling.
+	for data in data.chunks(97) {
+		decoder.extend_data(data);
+		loop {
+			match decoder.read_chunk() {
+				Ok(Some(_)) => {}
+				Ok(None) | Err(_) => break,
+			}
+		}
+	}
+});
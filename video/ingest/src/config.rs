@@ -22,6 +22,200 @@ impl Default for RtmpConfig {
 	}
 }
 
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct SrtConfig {
+	/// The bind address for the SRT listener
+	pub bind_address: SocketAddr,
+
+	/// The latency used to recover lost packets
+	pub latency: Duration,
+}
+
+impl Default for SrtConfig {
+	fn default() -> Self {
+		Self {
+			bind_address: "[::]:9710".to_string().parse().unwrap(),
+			latency: Duration::from_millis(120),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct WhipConfig {
+	/// The bind address for the WHIP HTTP server
+	pub bind_address: SocketAddr,
+
+	/// If we should use TLS for the WHIP HTTP server
+	pub tls: Option<TlsConfig>,
+
+	/// STUN or TURN servers used to gather ICE candidates
+	pub ice_servers: Vec<String>,
+
+	/// How often to ask the publisher for a keyframe
+	pub keyframe_request_interval: Duration,
+}
+
+impl Default for WhipConfig {
+	fn default() -> Self {
+		Self {
+			bind_address: "[::]:8080".to_string().parse().unwrap(),
+			tls: None,
+			ice_servers: Vec::new(),
+			keyframe_request_interval: Duration::from_secs(2),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct PullConfig {
+	/// How often to look for rooms with a source url that nobody is pulling
+	pub poll_interval: Duration,
+
+	/// How long a lease on a room lasts before another ingest server can take
+	/// it over, it is renewed at a third of this
+	pub lease_duration: Duration,
+
+	/// The maximum number of rooms this ingest server pulls at once
+	pub max_pullers: usize,
+
+	/// The maximum time to wait for a source to connect and send data
+	pub connect_timeout: Duration,
+
+	/// The delay before the first reconnect attempt, doubled after every
+	/// failed attempt
+	pub min_backoff: Duration,
+
+	/// The maximum delay between reconnect attempts
+	pub max_backoff: Duration,
+
+	/// Networks which may be pulled from even though they are not public, such
+	/// as `10.0.0.0/8`. Sources which resolve to any other loopback, private,
+	/// link-local or multicast address are refused
+	pub allowed_networks: Vec<String>,
+}
+
+impl Default for PullConfig {
+	fn default() -> Self {
+		Self {
+			poll_interval: Duration::from_secs(5),
+			lease_duration: Duration::from_secs(30),
+			max_pullers: 100,
+			connect_timeout: Duration::from_secs(10),
+			min_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(60),
+			allowed_networks: Vec::new(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+	/// How long the active input can stop delivering media before the backup
+	/// input takes over
+	pub failover_timeout: Duration,
+
+	/// How often the active input tells the backup input that it is still
+	/// delivering media
+	pub heartbeat_interval: Duration,
+}
+
+impl Default for BackupConfig {
+	fn default() -> Self {
+		Self {
+			failover_timeout: Duration::from_secs(3),
+			heartbeat_interval: Duration::from_millis(500),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct IpLimitConfig {
+	/// The maximum number of open connections from a single ip address on
+	/// this ingest server, unlimited if zero. Ipv6 addresses are limited per
+	/// /64
+	pub max_connections: usize,
+
+	/// The number of connections a single ip address can open within
+	/// `quota_reset_seconds`, unlimited if zero
+	pub handshake_quota: u32,
+
+	/// The amount of time before the quota is reset in seconds
+	pub quota_reset_seconds: u32,
+
+	/// How many connections can exceed the quota before the ip address is
+	/// banned
+	pub exceeded_limit: u32,
+
+	/// The amount of time before exceeded is reset in seconds
+	pub exceeded_reset_seconds: u32,
+
+	/// The amount of time before the ip address is unbanned in seconds
+	pub banned_reset_seconds: u32,
+}
+
+impl Default for IpLimitConfig {
+	fn default() -> Self {
+		Self {
+			max_connections: 10,
+			handshake_quota: 30,
+			quota_reset_seconds: 60,
+			exceeded_limit: 5,
+			exceeded_reset_seconds: 300,
+			banned_reset_seconds: 600,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+	/// The lowest ratio of the actual to the declared frame rate before the
+	/// input is unhealthy, disabled if zero
+	pub min_fps_ratio: f64,
+
+	/// The longest time between keyframes before the input is unhealthy,
+	/// disabled if zero
+	pub max_keyframe_interval: Duration,
+
+	/// The largest drift between the audio and video timestamps before the
+	/// input is unhealthy, disabled if zero
+	pub max_av_drift: Duration,
+
+	/// The most dropped frames per update interval before the input is
+	/// unhealthy
+	pub max_dropped_frames: u64,
+
+	/// The most late frames per update interval before the input is unhealthy
+	pub max_late_frames: u64,
+
+	/// The most timestamp jumps per update interval before the input is
+	/// unhealthy
+	pub max_timestamp_jumps: u64,
+
+	/// The longest round trip time before the input is unhealthy, disabled if
+	/// zero
+	pub max_rtt: Duration,
+}
+
+impl Default for HealthConfig {
+	fn default() -> Self {
+		Self {
+			min_fps_ratio: 0.9,
+			max_keyframe_interval: Duration::from_secs(5),
+			max_av_drift: Duration::from_millis(500),
+			max_dropped_frames: 10,
+			max_late_frames: 10,
+			max_timestamp_jumps: 0,
+			max_rtt: Duration::from_secs(1),
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
 #[serde(default)]
 pub struct IngestConfig {
@@ -34,9 +228,17 @@ pub struct IngestConfig {
 	/// The interval in to update the bitrate for a room
 	pub bitrate_update_interval: Duration,
 
+	/// The thresholds for the health of the input, checked every bitrate
+	/// update interval
+	pub health: HealthConfig,
+
 	/// The maximum time to wait for a transcoder
 	pub transcoder_timeout: Duration,
 
+	/// How long a connection which dropped is kept around so that the
+	/// broadcaster can reconnect and resume it, disabled if zero
+	pub reconnect_grace_period: Duration,
+
 	/// Max Bitrate for ingest
 	pub max_bitrate: u64,
 
@@ -49,6 +251,23 @@ pub struct IngestConfig {
 	/// The config for the RTMP server
 	pub rtmp: RtmpConfig,
 
+	/// The config for the SRT listener, disabled if not set
+	pub srt: Option<SrtConfig>,
+
+	/// The config for the WHIP server, disabled if not set
+	pub whip: Option<WhipConfig>,
+
+	/// The config for pulling rooms from their source url, disabled if not set
+	pub pull: Option<PullConfig>,
+
+	/// The config for publishing a backup input with the `_backup` suffix on
+	/// the stream key, disabled if not set
+	pub backup: Option<BackupConfig>,
+
+	/// The config for limiting the connections of a single ip address,
+	/// disabled if not set
+	pub ip_limit: Option<IpLimitConfig>,
+
 	/// The address to advertise for the gRPC server which is used by
 	/// transcoders to connect to
 	pub grpc_advertise_address: String,
@@ -60,11 +279,18 @@ impl Default for IngestConfig {
 			events_stream_name: "scuffle-video-events".to_string(),
 			transcoder_request_subject: "scuffle-video-transcoder_requests".to_string(),
 			bitrate_update_interval: Duration::from_secs(5),
+			health: Default::default(),
 			max_bitrate: 12000 * 1024,
 			max_bytes_between_keyframes: 5 * 12000 * 1024 / 8,
 			max_time_between_keyframes: Duration::from_secs(10),
 			transcoder_timeout: Duration::from_secs(60),
+			reconnect_grace_period: Duration::ZERO,
 			rtmp: Default::default(),
+			srt: None,
+			whip: None,
+			pull: None,
+			backup: None,
+			ip_limit: None,
 			grpc_advertise_address: "".to_string(),
 		}
 	}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::net::IpAddr;
 
 use pb::scuffle::video::internal::{IngestWatchRequest, IngestWatchResponse};
 use tokio::sync::{mpsc, Mutex};
@@ -16,6 +17,9 @@ pub struct IncomingTranscoder {
 
 pub trait IngestState {
 	fn requests(&self) -> &Mutex<HashMap<Ulid, mpsc::Sender<IncomingTranscoder>>>;
+
+	/// The number of open connections of every ip address.
+	fn ip_connections(&self) -> &std::sync::Mutex<HashMap<IpAddr, usize>>;
 }
 
 pub trait IngestGlobal:
@@ -23,6 +27,7 @@ pub trait IngestGlobal:
 	+ binary_helper::global::GlobalConfigProvider<IngestConfig>
 	+ binary_helper::global::GlobalNats
 	+ binary_helper::global::GlobalDb
+	+ binary_helper::global::GlobalRedis
 	+ binary_helper::global::GlobalConfig
 	+ IngestState
 	+ Send
@@ -36,6 +41,7 @@ impl<T> IngestGlobal for T where
 		+ binary_helper::global::GlobalConfigProvider<IngestConfig>
 		+ binary_helper::global::GlobalNats
 		+ binary_helper::global::GlobalDb
+		+ binary_helper::global::GlobalRedis
 		+ binary_helper::global::GlobalConfig
 		+ IngestState
 		+ Send
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::header;
+use ulid::Ulid;
+use video_common::database::Organization;
+
+use crate::global::IngestGlobal;
+
+/// A publish request, sent as JSON to the authorization hook of the
+/// organization.
+#[derive(Debug, serde::Serialize)]
+pub struct AuthRequest<'a> {
+	pub protocol: &'static str,
+	pub app_name: Option<&'a str>,
+	pub stream_name: &'a str,
+	pub ip: IpAddr,
+	pub params: HashMap<String, String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AuthResponse {
+	allow: bool,
+	/// The room to publish to, instead of the one in the stream key.
+	#[serde(default)]
+	room_id: Option<String>,
+}
+
+/// What the authorization hook decided about a publish request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Authorization {
+	/// The stream key decides, because there is no hook or it allowed the
+	/// publish.
+	StreamKey,
+	/// The hook allowed the publish to this room, so no stream key is needed.
+	Room(Ulid),
+	/// The hook denied the publish.
+	Denied,
+}
+
+/// Ask the authorization hook of the organization about a publish request.
+/// Hook failures are decided by the fail open setting of the organization.
+pub async fn authorize<G: IngestGlobal>(
+	global: &Arc<G>,
+	organization_id: Ulid,
+	request: &AuthRequest<'_>,
+) -> Result<Authorization> {
+	let organization: Option<Organization> = utils::database::query("SELECT * FROM organizations WHERE id = $1")
+		.bind(organization_id)
+		.build_query_as()
+		.fetch_optional(global.db())
+		.await?;
+
+	let Some(organization) = organization else {
+		return Ok(Authorization::StreamKey);
+	};
+
+	let Some(url) = &organization.ingest_auth_url else {
+		return Ok(Authorization::StreamKey);
+	};
+
+	match call_hook(url, Duration::from_millis(organization.ingest_auth_timeout as u64), request).await {
+		Ok(authorization) => Ok(authorization),
+		Err(err) => {
+			tracing::warn!(error = %err, fail_open = organization.ingest_auth_fail_open, "authorization hook failed");
+
+			if organization.ingest_auth_fail_open {
+				Ok(Authorization::StreamKey)
+			} else {
+				Ok(Authorization::Denied)
+			}
+		}
+	}
+}
+
+async fn call_hook(url: &str, timeout: Duration, request: &AuthRequest<'_>) -> Result<Authorization> {
+	let client = reqwest::Client::builder().timeout(timeout).build()?;
+
+	let body = client
+		.post(url)
+		.header(header::CONTENT_TYPE, "application/json")
+		.body(serde_json::to_vec(request)?)
+		.send()
+		.await?
+		.error_for_status()?
+		.bytes()
+		.await?;
+
+	let response: AuthResponse = serde_json::from_slice(&body)?;
+
+	if !response.allow {
+		return Ok(Authorization::Denied);
+	}
+
+	match response.room_id {
+		Some(room_id) => Ok(Authorization::Room(Ulid::from_string(&room_id)?)),
+		None => Ok(Authorization::StreamKey),
+	}
+}
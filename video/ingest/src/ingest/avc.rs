@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::io;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use h264::{AVCDecoderConfigurationRecord, AvccExtendedConfig, Sps};
+use rtmp::ChannelData;
+
+/// Converts H.264 access units in Annex B format into FLV video tag bodies.
+/// A new sequence header is sent whenever the parameter sets change.
+#[derive(Default)]
+pub struct AvcConverter {
+	sps: Option<Bytes>,
+	pps: Option<Bytes>,
+	sent_config: bool,
+}
+
+impl AvcConverter {
+	pub fn convert(
+		&mut self,
+		data: Bytes,
+		timestamp: u32,
+		composition_time: u32,
+		out: &mut VecDeque<ChannelData>,
+	) -> io::Result<()> {
+		let mut keyframe = false;
+		let mut config_changed = false;
+		let mut nalus = BytesMut::new();
+
+		for nalu in annexb_nalus(data) {
+			match nalu[0] & 0x1F {
+				// SPS
+				7 => {
+					config_changed |= self.sps.as_ref() != Some(&nalu);
+					self.sps = Some(nalu);
+				}
+				// PPS
+				8 => {
+					config_changed |= self.pps.as_ref() != Some(&nalu);
+					self.pps = Some(nalu);
+				}
+				// Access unit delimiter
+				9 => {}
+				nalu_type => {
+					// IDR
+					keyframe |= nalu_type == 5;
+					nalus.put_u32(nalu.len() as u32);
+					nalus.extend_from_slice(&nalu);
+				}
+			}
+		}
+
+		if config_changed || !self.sent_config {
+			let (Some(sps), Some(pps)) = (&self.sps, &self.pps) else {
+				// We cannot decode anything until we have seen the parameter sets.
+				return Ok(());
+			};
+
+			let mut body = BytesMut::new();
+			body.extend_from_slice(&[0x17, 0x00, 0x00, 0x00, 0x00]);
+			avc_config(sps.clone(), pps.clone())?.mux(&mut (&mut body).writer())?;
+
+			out.push_back(ChannelData::Video {
+				timestamp,
+				data: body.freeze(),
+			});
+
+			self.sent_config = true;
+		}
+
+		if nalus.is_empty() {
+			return Ok(());
+		}
+
+		let mut body = BytesMut::with_capacity(5 + nalus.len());
+		body.put_u8(if keyframe { 0x17 } else { 0x27 });
+		body.put_u8(0x01);
+		body.put_uint(composition_time as u64, 3);
+		body.extend_from_slice(&nalus);
+
+		out.push_back(ChannelData::Video {
+			timestamp,
+			data: body.freeze(),
+		});
+
+		Ok(())
+	}
+}
+
+/// Split Annex B data into its NAL units.
+/// ISO/IEC-14496-10-2022 - B.2
+fn annexb_nalus(data: Bytes) -> impl Iterator<Item = Bytes> {
+	let mut starts = Vec::new();
+	let mut i = 0;
+	while i + 3 <= data.len() {
+		if data[i] == 0x00 && data[i + 1] == 0x00 && data[i + 2] == 0x01 {
+			starts.push(i + 3);
+			i += 3;
+		} else {
+			i += 1;
+		}
+	}
+
+	let ends = starts
+		.iter()
+		.skip(1)
+		.map(|start| start - 3)
+		.chain(std::iter::once(data.len()))
+		.collect::<Vec<_>>();
+
+	starts.into_iter().zip(ends).filter_map(move |(start, mut end)| {
+		// Four byte start codes and trailing_zero_8bits leave zeros at the end of the
+		// previous NAL unit.
+		while end > start && data[end - 1] == 0x00 {
+			end -= 1;
+		}
+
+		(end > start).then(|| data.slice(start..end))
+	})
+}
+
+fn avc_config(sps: Bytes, pps: Bytes) -> io::Result<AVCDecoderConfigurationRecord> {
+	let parsed = Sps::parse(sps.clone())?;
+
+	Ok(AVCDecoderConfigurationRecord {
+		configuration_version: 1,
+		profile_indication: sps[1],
+		profile_compatibility: sps[2],
+		level_indication: sps[3],
+		length_size_minus_one: 3,
+		extended_config: parsed.ext.map(|ext| AvccExtendedConfig {
+			chroma_format: ext.chroma_format_idc as u8,
+			bit_depth_luma_minus8: ext.bit_depth_luma_minus8 as u8,
+			bit_depth_chroma_minus8: ext.bit_depth_chroma_minus8 as u8,
+			sequence_parameter_set_ext: Vec::new(),
+		}),
+		sps: vec![sps],
+		pps: vec![pps],
+	})
+}
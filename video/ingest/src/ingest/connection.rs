@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::net::IpAddr;
 use std::pin::pin;
 use std::sync::Arc;
@@ -6,31 +7,40 @@ use std::time::Duration;
 use anyhow::Result;
 use base64::Engine;
 use bytes::Bytes;
-use bytesio::bytesio::AsyncReadWrite;
+use bytesio::bytesio::{AsyncReadWrite, BytesIOStats, ThroughputMeter};
 use flv::{FlvTag, FlvTagData, FlvTagType};
-use futures::Future;
 use futures_util::StreamExt;
 use pb::scuffle::video::internal::events::TranscoderRequestTask;
 use pb::scuffle::video::internal::{ingest_watch_request, ingest_watch_response, IngestWatchRequest, IngestWatchResponse};
 use pb::scuffle::video::v1::events_fetch_request::Target;
-use pb::scuffle::video::v1::types::{event, Rendition};
+use pb::scuffle::video::v1::types::{event, IngestHealth, Rendition};
 use prost::Message as _;
-use rtmp::{ChannelData, PublishRequest, Session, SessionError};
+use rtmp::{ChannelData, Session};
+use srt_tokio::access::{RejectReason, ServerRejectReason};
+use srt_tokio::ConnectionRequest;
 use tokio::select;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::time::Instant;
 use tonic::{Status, Streaming};
 use transmuxer::{AudioSettings, MediaSegment, TransmuxResult, Transmuxer, VideoSettings};
 use ulid::Ulid;
 use utils::context::ContextExt;
 use utils::prelude::FutureTimeout;
-use video_common::database::RoomStatus;
+use video_common::database::{IngestPolicy, RoomStatus};
 use video_common::{events, keys};
 
-use super::bytes_tracker::BytesTracker;
+use super::auth::{self, AuthRequest, Authorization};
 use super::errors::IngestError;
-use super::rtmp_session::{Data, RtmpSession};
+use super::health::HealthTracker;
+use super::ip_limit;
+use super::policy::Limits;
+use super::pull_session::PullSession;
+use super::rtmp_session::RtmpSession;
+use super::session::{Data, IngestSession};
+use super::srt_session::SrtSession;
 use super::update::{update_db, Update};
+use super::whip::{self, WhipReject, WhipResponse};
+use super::whip_session::WhipSession;
 use crate::config::IngestConfig;
 use crate::global::{IncomingTranscoder, IngestGlobal};
 
@@ -42,7 +52,11 @@ struct Transcoder {
 struct Connection {
 	id: Ulid,
 
-	bytes_tracker: BytesTracker,
+	/// The media received from the session, counted as bytes read.
+	bytes: BytesIOStats,
+	bytes_since_keyframe: u64,
+	throughput: ThroughputMeter,
+	health: HealthTracker,
 	initial_segment: Option<Bytes>,
 	fragment_list: Vec<MediaSegment>,
 
@@ -69,9 +83,72 @@ struct Connection {
 
 	error: Option<IngestError>,
 
+	// The limits from the ingest policy of the room
+	limits: Limits,
+
+	// If this connection resumed one which dropped
+	resumed: bool,
+	// If this connection can be resumed by a reconnect after it dropped
+	resumable: bool,
+	// If the next transcoder has to start after a discontinuity
+	discontinuity: bool,
+
+	// If this connection is published to the backup input of the room
+	backup: bool,
+	// If this connection is the backup input held warm until it takes over
+	standby: bool,
+	// If this connection took over the room from its other input
+	input_changed: bool,
+	last_heartbeat: Instant,
+
+	// The configs of the input, written to the room when it goes live
+	video_input: Vec<u8>,
+	audio_input: Vec<u8>,
+
 	// The room that is being published to
 	organization_id: Ulid,
 	room_id: Ulid,
+
+	// The ip address of the broadcaster, not set when pulling from a source
+	ip: Option<IpAddr>,
+}
+
+/// How a connection proves it is allowed to publish to a room.
+#[derive(Debug)]
+enum RoomClaim {
+	/// The stream key given by a broadcaster pushing to the room.
+	StreamKey(String),
+	/// The authorization hook of the organization allowed the broadcaster to
+	/// push to the room.
+	Authorized,
+	/// The lease held by the puller of a room with a source url.
+	SourceLease(Ulid),
+}
+
+impl RoomClaim {
+	/// The condition the room has to meet, `param` is the position of the
+	/// parameter bound by [`RoomClaim::bind`].
+	fn condition(&self, param: usize) -> String {
+		// Push ingest is not allowed for rooms that pull from a source, and the
+		// puller can only take over the room while it holds the lease.
+		match self {
+			Self::StreamKey(_) => format!("new.stream_key = ${param} AND new.source_url IS NULL"),
+			Self::Authorized => "new.source_url IS NULL".to_string(),
+			Self::SourceLease(_) => format!("new.source_lease_id = ${param} AND new.source_url IS NOT NULL"),
+		}
+	}
+
+	fn bind(self, query: &mut utils::database::QueryBuilder<'_>) {
+		match self {
+			Self::StreamKey(room_secret) => {
+				query.bind(room_secret);
+			}
+			Self::Authorized => {}
+			Self::SourceLease(lease_id) => {
+				query.bind(lease_id);
+			}
+		}
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -89,6 +166,7 @@ pub async fn handle<G: IngestGlobal, S: AsyncReadWrite>(global: Arc<G>, socket:
 	let (data_producer, data) = mpsc::channel(1);
 
 	let mut session = Session::new(socket, data_producer, event_producer);
+	let rtt = session.rtt();
 
 	// When a future is pinned it becomes pausable and can be resumed later
 	// The entire design here is to run on a single task, and share execution on the
@@ -99,14 +177,26 @@ pub async fn handle<G: IngestGlobal, S: AsyncReadWrite>(global: Arc<G>, socket:
 	// goes out of scope. If we used a tokio::spawn here, we would have to manually
 	// clean up the task.
 	let fut = pin!(session.run());
-	let mut session = RtmpSession::new(fut, publish, data);
+	let mut session = RtmpSession::new(fut, publish, data, rtt);
 
 	let Ok(Ok(Ok(Some(event)))) = session.publish().context(global.ctx()).timeout(Duration::from_secs(5)).await else {
 		tracing::debug!("connection disconnected before publish");
 		return;
 	};
 
-	let mut connection = match Connection::new(&global, event, ip).await {
+	if event.app_name != "live" {
+		return;
+	}
+
+	let request = AuthRequest {
+		protocol: "rtmp",
+		app_name: Some(&event.app_name),
+		stream_name: &event.stream_name,
+		ip,
+		params: event.connect_params.clone(),
+	};
+
+	let mut connection = match Connection::new(&global, request).await {
 		Ok(Some(c)) => c,
 		Ok(None) => return,
 		Err(e) => {
@@ -115,6 +205,8 @@ pub async fn handle<G: IngestGlobal, S: AsyncReadWrite>(global: Arc<G>, socket:
 		}
 	};
 
+	event.response.send(connection.id.into()).ok();
+
 	let clean_disconnect = connection.run(&global, session).await;
 
 	if let Err(err) = connection.cleanup(&global, clean_disconnect).await {
@@ -122,18 +214,196 @@ pub async fn handle<G: IngestGlobal, S: AsyncReadWrite>(global: Arc<G>, socket:
 	}
 }
 
-impl Connection {
-	#[tracing::instrument(
-        level = "debug",
-        skip(global, event, _ip),
-        fields(app = %event.app_name, stream = %event.stream_name)
-    )]
-	async fn new<G: IngestGlobal>(global: &Arc<G>, event: PublishRequest, _ip: IpAddr) -> Result<Option<Self>> {
-		if event.app_name != "live" {
-			return Ok(None);
+#[tracing::instrument(skip(global, request), fields(ip = %request.remote().ip()))]
+pub async fn handle_srt<G: IngestGlobal>(global: Arc<G>, request: ConnectionRequest, stream_name: String) {
+	let Some(_guard) = ip_limit::acquire(&global, request.remote().ip()).await else {
+		request.reject(RejectReason::Server(ServerRejectReason::Overload)).await.ok();
+		return;
+	};
+
+	let auth_request = AuthRequest {
+		protocol: "srt",
+		app_name: None,
+		stream_name: &stream_name,
+		ip: request.remote().ip(),
+		params: request
+			.stream_id()
+			.map(|id| HashMap::from([("streamid".to_string(), id.to_string())]))
+			.unwrap_or_default(),
+	};
+
+	let mut connection = match Connection::new(&global, auth_request).await {
+		Ok(Some(c)) => c,
+		Ok(None) => {
+			request
+				.reject(RejectReason::Server(ServerRejectReason::Unauthorized))
+				.await
+				.ok();
+			return;
+		}
+		Err(e) => {
+			tracing::error!(error = %e, "failed to create connection");
+			request
+				.reject(RejectReason::Server(ServerRejectReason::InternalServerError))
+				.await
+				.ok();
+			return;
+		}
+	};
+
+	let clean_disconnect = match request.accept(None).await {
+		Ok(socket) => connection.run(&global, SrtSession::new(socket)).await,
+		Err(e) => {
+			tracing::debug!(error = %e, "failed to accept srt connection");
+			connection.error = Some(IngestError::SrtConnectionError);
+			false
+		}
+	};
+
+	if let Err(err) = connection.cleanup(&global, clean_disconnect).await {
+		tracing::error!(error = %err, "failed to cleanup connection")
+	}
+}
+
+#[tracing::instrument(skip(global, params, offer, response))]
+pub async fn handle_whip<G: IngestGlobal>(
+	global: Arc<G>,
+	stream_name: String,
+	ip: IpAddr,
+	params: HashMap<String, String>,
+	offer: String,
+	response: oneshot::Sender<WhipResponse>,
+) {
+	let Some(config) = &global.config::<IngestConfig>().whip else {
+		response.send(Err(WhipReject::InternalServerError)).ok();
+		return;
+	};
+
+	let Some(_guard) = ip_limit::acquire(&global, ip).await else {
+		response.send(Err(WhipReject::TooManyRequests)).ok();
+		return;
+	};
+
+	let request = AuthRequest {
+		protocol: "whip",
+		app_name: None,
+		stream_name: &stream_name,
+		ip,
+		params,
+	};
+
+	let mut connection = match Connection::new(&global, request).await {
+		Ok(Some(c)) => c,
+		Ok(None) => {
+			response.send(Err(WhipReject::Unauthorized)).ok();
+			return;
+		}
+		Err(e) => {
+			tracing::error!(error = %e, "failed to create connection");
+			response.send(Err(WhipReject::InternalServerError)).ok();
+			return;
+		}
+	};
+
+	let clean_disconnect = match WhipSession::new(config, offer).await {
+		Ok((session, answer)) => {
+			// Subscribe before handing out the session url, so that an unpublish
+			// request cannot arrive before anyone answers it.
+			match global.nats().subscribe(keys::ingest_whip_unpublish(connection.id)).await {
+				Ok(sub) => {
+					let unpublish = tokio::spawn(whip::serve_unpublish(global.clone(), connection.id, stream_name, sub));
+
+					response.send(Ok((connection.id, answer))).ok();
+					let clean_disconnect = connection.run(&global, session).await;
+
+					unpublish.abort();
+					clean_disconnect
+				}
+				Err(e) => {
+					tracing::error!(error = %e, "failed to subscribe to unpublish subject");
+					response.send(Err(WhipReject::InternalServerError)).ok();
+					connection.error = Some(IngestError::FailedToSubscribe);
+					false
+				}
+			}
+		}
+		Err(e) => {
+			tracing::debug!(error = %e, "failed to negotiate whip session");
+			response.send(Err(WhipReject::Negotiation(e))).ok();
+			connection.error = Some(IngestError::WhipConnectionError);
+			false
 		}
+	};
+
+	if let Err(err) = connection.cleanup(&global, clean_disconnect).await {
+		tracing::error!(error = %err, "failed to cleanup connection")
+	}
+}
+
+/// Run a session pulled from a room's source url, `active` is set to the
+/// connection id while the room is live. Returns false if the room could not
+/// be taken over.
+#[tracing::instrument(skip(global, session, active))]
+pub async fn handle_pull<G: IngestGlobal>(
+	global: Arc<G>,
+	organization_id: Ulid,
+	room_id: Ulid,
+	lease_id: Ulid,
+	session: PullSession,
+	active: &watch::Sender<Option<Ulid>>,
+) -> bool {
+	let mut connection = match Connection::new_pull(&global, organization_id, room_id, lease_id).await {
+		Ok(Some(c)) => c,
+		Ok(None) => return false,
+		Err(e) => {
+			tracing::error!(error = %e, "failed to create connection");
+			return false;
+		}
+	};
 
-		let mut parts = event.stream_name.split('_');
+	active.send_replace(Some(connection.id));
+
+	let clean_disconnect = connection.run(&global, session).await;
+
+	if let Err(err) = connection.cleanup(&global, clean_disconnect).await {
+		tracing::error!(error = %err, "failed to cleanup connection")
+	}
+
+	active.send_replace(None);
+
+	true
+}
+
+/// Checks the ip address against the ip filter of the room.
+async fn room_allows_ip<G: IngestGlobal>(global: &Arc<G>, organization_id: Ulid, room_id: Ulid, ip: IpAddr) -> Result<bool> {
+	#[derive(postgres_from_row::FromRow)]
+	struct IpFilter {
+		ingest_ip_allow: Vec<String>,
+		ingest_ip_deny: Vec<String>,
+	}
+
+	let filter: Option<IpFilter> =
+		utils::database::query("SELECT ingest_ip_allow, ingest_ip_deny FROM rooms WHERE organization_id = $1 AND id = $2")
+			.bind(organization_id)
+			.bind(room_id)
+			.build_query_as()
+			.fetch_optional(global.db())
+			.await?;
+
+	// A missing room is turned away when it is claimed.
+	Ok(match filter {
+		Some(filter) => video_common::ip_range::ip_allowed(&filter.ingest_ip_allow, &filter.ingest_ip_deny, ip),
+		None => true,
+	})
+}
+
+impl Connection {
+	#[tracing::instrument(level = "debug", skip(global, request), fields(ip = %request.ip))]
+	async fn new<G: IngestGlobal>(global: &Arc<G>, request: AuthRequest<'_>) -> Result<Option<Self>> {
+		// Anything after a `?` is only meant for the authorization hook.
+		let stream_key = request.stream_name.split('?').next().unwrap_or_default();
+
+		let mut parts = stream_key.splitn(3, '_');
 		if parts.next() != Some("live") {
 			return Ok(None);
 		}
@@ -164,76 +434,245 @@ impl Connection {
 			Some((room_id, room_secret.to_string()))
 		};
 
-		let (room_id, room_secret) = match parts.next().and_then(parse_room_secret) {
-			Some(name) => name,
-			None => return Ok(None),
+		let key = parts.next().unwrap_or_default();
+
+		// The backup input is published with a `_backup` suffix on the stream key.
+		let (key, backup) = match key.strip_suffix("_backup") {
+			Some(key) if global.config::<IngestConfig>().backup.is_some() => (key, true),
+			_ => (key, false),
 		};
 
+		let (room_id, claim) = match auth::authorize(global, organization_id, &request).await? {
+			Authorization::Denied => {
+				tracing::debug!("publish denied by the authorization hook");
+				return Ok(None);
+			}
+			Authorization::Room(room_id) => (room_id, RoomClaim::Authorized),
+			Authorization::StreamKey => match parse_room_secret(key) {
+				Some((room_id, room_secret)) => (room_id, RoomClaim::StreamKey(room_secret)),
+				None => return Ok(None),
+			},
+		};
+
+		if !room_allows_ip(global, organization_id, room_id, request.ip).await? {
+			tracing::debug!("publish denied by the ip filter of the room");
+			return Ok(None);
+		}
+
+		let connection = Self::claim(global, organization_id, room_id, claim, backup).await?;
+
+		Ok(connection.map(|mut connection| {
+			connection.ip = Some(request.ip);
+			connection
+		}))
+	}
+
+	/// Take over a room that is pulled from a source url, using the lease held
+	/// by the puller instead of the stream key.
+	#[tracing::instrument(level = "debug", skip(global))]
+	async fn new_pull<G: IngestGlobal>(
+		global: &Arc<G>,
+		organization_id: Ulid,
+		room_id: Ulid,
+		lease_id: Ulid,
+	) -> Result<Option<Self>> {
+		Self::claim(global, organization_id, room_id, RoomClaim::SourceLease(lease_id), false).await
+	}
+
+	async fn claim<G: IngestGlobal>(
+		global: &Arc<G>,
+		organization_id: Ulid,
+		room_id: Ulid,
+		claim: RoomClaim,
+		backup: bool,
+	) -> Result<Option<Self>> {
 		#[derive(postgres_from_row::FromRow)]
 		struct Response {
 			id: Option<Ulid>,
+			resumed: bool,
+			from_backup: bool,
 		}
 
 		let id = Ulid::new();
 
-		let result: Option<Response> = utils::database::query(
+		// The room uses its own ingest policy, or the default one of the organization.
+		let policy: Option<IngestPolicy> = utils::database::query(
+			r#"
+			SELECT p.*
+			FROM rooms r
+			JOIN ingest_policies p ON
+				p.organization_id = r.organization_id AND
+				(p.id = r.ingest_policy_id OR (r.ingest_policy_id IS NULL AND p.organization_default))
+			WHERE
+				r.organization_id = $1 AND
+				r.id = $2
+			LIMIT 1
+			"#,
+		)
+		.bind(organization_id)
+		.bind(room_id)
+		.build_query_as()
+		.fetch_optional(global.db())
+		.await?;
+
+		let limits = Limits::new(global.config::<IngestConfig>(), policy);
+
+		if backup {
+			return Self::claim_backup(global, id, organization_id, room_id, claim, limits).await;
+		}
+
+		let claim_condition = claim.condition(5);
+
+		// A connection which dropped within the reconnect grace period is resumed,
+		// keeping its id, recording and transcoding config. The same goes for a
+		// connection which failed over to the backup input.
+		let resume = "(old.reconnect_deadline > NOW() OR old.active_ingest_backup)";
+
+		let mut query = utils::database::query(format!(
 			r#"
             UPDATE rooms as new
             SET 
                 updated_at = NOW(),
                 last_live_at = NOW(),
                 last_disconnected_at = NULL,
-                active_ingest_connection_id = $1,
-                status = $2,
-                video_input = NULL,
-                audio_input = NULL,
+                reconnect_deadline = NULL,
+                active_ingest_backup = false,
+                active_ingest_connection_id = CASE WHEN {resume} THEN old.active_ingest_connection_id ELSE $1 END,
+                status = CASE WHEN {resume} THEN old.status ELSE $2 END,
+                video_input = CASE WHEN {resume} THEN old.video_input END,
+                audio_input = CASE WHEN {resume} THEN old.audio_input END,
                 ingest_bitrate = NULL,
-                video_output = NULL,
-                audio_output = NULL,
-                active_recording_id = NULL,
-                active_recording_config = NULL,
-                active_transcoding_config = NULL
+                ingest_health = NULL,
+                audio_loudness = NULL,
+                video_output = CASE WHEN {resume} THEN old.video_output END,
+                audio_output = CASE WHEN {resume} THEN old.audio_output END,
+                active_recording_id = CASE WHEN {resume} THEN old.active_recording_id END,
+                active_recording_config = CASE WHEN {resume} THEN old.active_recording_config END,
+                active_transcoding_config = CASE WHEN {resume} THEN old.active_transcoding_config END
             FROM rooms as old
             WHERE 
                 new.organization_id = $3 AND
                 new.id = $4 AND
-                new.stream_key = $5 AND
-                (new.last_live_at < NOW() - INTERVAL '10 seconds' OR new.last_live_at IS NULL) AND
+                {claim_condition} AND
+                ({resume} OR new.last_live_at < NOW() - INTERVAL '10 seconds' OR new.last_live_at IS NULL) AND
                 old.organization_id = new.organization_id AND
                 old.id = new.id AND
                 old.stream_key = new.stream_key
-            RETURNING old.active_ingest_connection_id as id
+            RETURNING
+                old.active_ingest_connection_id as id,
+                COALESCE({resume}, false) as resumed,
+                old.active_ingest_backup as from_backup
             "#,
-		)
-		.bind(id)
-		.bind(RoomStatus::Offline)
-		.bind(organization_id)
-		.bind(room_id)
-		.bind(&room_secret)
-		.build_query_as()
-		.fetch_optional(global.db())
-		.await?;
+		));
+
+		query.bind(id).bind(RoomStatus::Offline).bind(organization_id).bind(room_id);
+
+		// The puller only reconnects once the connection is cleaned up, so there is
+		// nothing to wait for.
+		let resumable = !matches!(claim, RoomClaim::SourceLease(_));
+
+		claim.bind(&mut query);
+
+		let result: Option<Response> = query.build_query_as().fetch_optional(global.db()).await?;
 
 		let Some(result) = result else {
 			tracing::debug!("failed to find room");
 			return Ok(None);
 		};
 
+		// This also wakes up the dropped connection when it is being resumed, and
+		// makes the backup input hand the room back when the primary takes over.
 		if let Some(old_id) = result.id {
 			if let Err(err) = global.nats().publish(keys::ingest_disconnect(old_id), Bytes::new()).await {
 				tracing::error!(error = %err, "failed to publish disconnect event");
 			}
 		}
 
-		event.response.send(id.into()).ok();
+		let id = match result.id {
+			Some(old_id) if result.resumed => {
+				tracing::info!(connection_id = %old_id, "resuming connection");
+				old_id
+			}
+			_ => id,
+		};
+
+		let mut connection = Self::create(id, organization_id, room_id, limits);
+		connection.resumed = result.resumed;
+		connection.resumable = resumable;
+		connection.discontinuity = result.resumed;
+		connection.input_changed = result.from_backup;
 
+		Ok(Some(connection))
+	}
+
+	/// Take the backup slot of the room, the connection is held warm until the
+	/// active input stops delivering media.
+	async fn claim_backup<G: IngestGlobal>(
+		global: &Arc<G>,
+		id: Ulid,
+		organization_id: Ulid,
+		room_id: Ulid,
+		claim: RoomClaim,
+		limits: Limits,
+	) -> Result<Option<Self>> {
+		#[derive(postgres_from_row::FromRow)]
+		struct Response {
+			id: Option<Ulid>,
+		}
+
+		let mut query = utils::database::query(format!(
+			r#"
+			UPDATE rooms as new
+			SET
+				updated_at = NOW(),
+				backup_ingest_connection_id = $1
+			FROM rooms as old
+			WHERE
+				new.organization_id = $2 AND
+				new.id = $3 AND
+				{} AND
+				old.organization_id = new.organization_id AND
+				old.id = new.id
+			RETURNING old.backup_ingest_connection_id as id
+			"#,
+			claim.condition(4),
+		));
+
+		query.bind(id).bind(organization_id).bind(room_id);
+		claim.bind(&mut query);
+
+		let result: Option<Response> = query.build_query_as().fetch_optional(global.db()).await?;
+
+		let Some(result) = result else {
+			tracing::debug!("failed to find room");
+			return Ok(None);
+		};
+
+		// Only a single backup input is held warm.
+		if let Some(old_id) = result.id {
+			if let Err(err) = global.nats().publish(keys::ingest_disconnect(old_id), Bytes::new()).await {
+				tracing::error!(error = %err, "failed to publish disconnect event");
+			}
+		}
+
+		let mut connection = Self::create(id, organization_id, room_id, limits);
+		connection.backup = true;
+		connection.standby = true;
+
+		Ok(Some(connection))
+	}
+
+	fn create(id: Ulid, organization_id: Ulid, room_id: Ulid, limits: Limits) -> Self {
 		let (update_sender, update_reciever) = mpsc::channel(15);
 		let (incoming_sender, incoming_reciever) = mpsc::channel(15);
 
-		Ok(Some(Connection {
+		Connection {
 			id,
 			transmuxer: Transmuxer::new(),
-			bytes_tracker: BytesTracker::default(),
+			bytes: BytesIOStats::default(),
+			bytes_since_keyframe: 0,
+			throughput: ThroughputMeter::default(),
+			health: HealthTracker::default(),
 			current_transcoder_id: Ulid::nil(),
 			current_transcoder: None,
 			next_transcoder_id: None,
@@ -252,7 +691,18 @@ impl Connection {
 			video_timescale: 1,
 			audio_timescale: 1,
 			error: None,
-		}))
+			resumed: false,
+			resumable: false,
+			discontinuity: false,
+			backup: false,
+			standby: false,
+			input_changed: false,
+			last_heartbeat: Instant::now(),
+			video_input: Vec::new(),
+			audio_input: Vec::new(),
+			limits,
+			ip: None,
+		}
 	}
 
 	#[tracing::instrument(
@@ -260,11 +710,7 @@ impl Connection {
         skip(self, global, session),
         fields(organization_id = %self.organization_id, room_id = %self.room_id)
     )]
-	async fn run<'a, G: IngestGlobal>(
-		&mut self,
-		global: &Arc<G>,
-		mut session: RtmpSession<'a, impl Future<Output = Result<bool, SessionError>>>,
-	) -> bool {
+	async fn run<G: IngestGlobal, S: IngestSession>(&mut self, global: &Arc<G>, mut session: S) -> bool {
 		tracing::info!("new publish request");
 
 		// At this point we have a stream that is publishing to us
@@ -278,7 +724,6 @@ impl Connection {
 
 		let mut db_update_fut = pin!(update_db(
 			global.clone(),
-			self.id,
 			self.organization_id,
 			self.room_id,
 			self.update_recv.take().unwrap(),
@@ -299,6 +744,34 @@ impl Connection {
 			}
 		};
 
+		// The backup input waits for the active input to stop delivering media.
+		let failover_timeout = global
+			.config::<IngestConfig>()
+			.backup
+			.as_ref()
+			.map(|backup| backup.failover_timeout)
+			.unwrap_or_default();
+		let mut failover_deadline = Instant::now() + failover_timeout;
+
+		let mut heartbeat_sub = if self.standby {
+			match global
+				.nats()
+				.subscribe(keys::ingest_heartbeat(self.organization_id, self.room_id))
+				.await
+			{
+				Ok(sub) => Some(sub),
+				Err(e) => {
+					tracing::error!(error = %e, "failed to subscribe to heartbeat subject");
+
+					self.error = Some(IngestError::FailedToSubscribe);
+
+					return false;
+				}
+			}
+		} else {
+			None
+		};
+
 		while select! {
 			_ = global.ctx().done() => {
 				tracing::debug!("Global context closed, closing connection");
@@ -312,7 +785,7 @@ impl Connection {
 					Err(e) => {
 						tracing::error!(error = %e, "session error");
 
-						self.error = Some(IngestError::RtmpConnectionError);
+						self.error = Some(S::CONNECTION_ERROR);
 
 						false
 					},
@@ -345,11 +818,41 @@ impl Connection {
 					false
 				}
 			},
-			_ = bitrate_update_interval.tick() => self.on_bitrate_update(global),
+			_ = bitrate_update_interval.tick() => self.on_bitrate_update(global, session.rtt()).await,
+			Some(_) = async {
+				if let Some(sub) = heartbeat_sub.as_mut() {
+					sub.next().await
+				} else {
+					None
+				}
+			} => {
+				failover_deadline = Instant::now() + failover_timeout;
+				true
+			}
+			_ = tokio::time::sleep_until(failover_deadline), if heartbeat_sub.is_some() => {
+				failover_deadline = Instant::now() + failover_timeout;
+
+				match self.take_over(global).await {
+					Ok(Some(sub)) => {
+						conn_id_sub = sub;
+						heartbeat_sub = None;
+
+						self.go_live(global).await
+					}
+					Ok(None) => true,
+					Err(e) => {
+						tracing::error!(error = %e, "failed to take over from the active input");
+
+						self.error = Some(IngestError::FailedToUpdateRoom);
+
+						false
+					}
+				}
+			}
 			_ = tokio::time::sleep_until(next_timeout) => {
 				tracing::debug!("session timed out during data");
 
-				self.error = Some(IngestError::RtmpConnectionTimeout);
+				self.error = Some(S::CONNECTION_TIMEOUT);
 
 				false
 			},
@@ -612,6 +1115,7 @@ impl Connection {
 				send: event.transcoder,
 			});
 			self.current_transcoder_id = self.next_transcoder_id.take().unwrap();
+			self.discontinuity = false;
 		} else {
 			self.next_transcoder = Some(Transcoder {
 				recv: event.streaming,
@@ -658,6 +1162,7 @@ impl Connection {
 					request_id: Some(request_id.into()),
 					connection_id: Some(self.id.into()),
 					grpc_endpoint: config.grpc_advertise_address.clone(),
+					discontinuity: self.discontinuity,
 				}
 				.encode_to_vec()
 				.into(),
@@ -683,22 +1188,25 @@ impl Connection {
 		audio_settings: &AudioSettings,
 		init_data: Bytes,
 	) -> bool {
-		self.initial_segment = Some(init_data);
+		let warm = self.initial_segment.replace(init_data).is_some();
 
 		self.audio_timescale = audio_settings.timescale;
 		self.video_timescale = video_settings.timescale;
 
-		let video_settings = pb::scuffle::video::v1::types::VideoConfig {
+		self.health.set_declared_fps(video_settings.framerate);
+
+		self.video_input = pb::scuffle::video::v1::types::VideoConfig {
 			bitrate: video_settings.bitrate as i64,
 			codec: video_settings.codec.to_string(),
 			fps: video_settings.framerate as i32,
 			height: video_settings.height as i32,
 			width: video_settings.width as i32,
 			rendition: Rendition::VideoSource.into(),
+			name: String::new(),
 		}
 		.encode_to_vec();
 
-		let audio_settings = pb::scuffle::video::v1::types::AudioConfig {
+		self.audio_input = pb::scuffle::video::v1::types::AudioConfig {
 			bitrate: audio_settings.bitrate as i64,
 			channels: audio_settings.channels as i32,
 			codec: audio_settings.codec.to_string(),
@@ -707,6 +1215,32 @@ impl Connection {
 		}
 		.encode_to_vec();
 
+		// The backup input is not written to the room until it takes over.
+		if self.standby {
+			if !warm {
+				video_common::events::emit(
+					global.nats(),
+					&global.config().events_stream_name,
+					self.organization_id,
+					Target::Room,
+					event::Event::Room(event::Room {
+						room_id: Some(self.room_id.into()),
+						event: Some(event::room::Event::BackupConnected(event::room::BackupConnected {
+							connection_id: Some(self.id.into()),
+							ip: self.ip.map(|ip| ip.to_string()),
+						})),
+					}),
+				)
+				.await;
+			}
+
+			return true;
+		}
+
+		self.go_live(global).await
+	}
+
+	async fn go_live<G: IngestGlobal>(&mut self, global: &Arc<G>) -> bool {
 		match utils::database::query(
 			r#"
 			UPDATE rooms
@@ -722,8 +1256,8 @@ impl Connection {
 			"#,
 		)
 		.bind(RoomStatus::WaitingForTranscoder)
-		.bind(video_settings)
-		.bind(audio_settings)
+		.bind(&self.video_input)
+		.bind(&self.audio_input)
 		.bind(self.organization_id)
 		.bind(self.room_id)
 		.bind(self.id)
@@ -749,6 +1283,19 @@ impl Connection {
 			}
 		}
 
+		let event = if self.input_changed {
+			event::room::Event::InputChanged(event::room::InputChanged {
+				connection_id: Some(self.id.into()),
+				backup: self.backup,
+			})
+		} else {
+			event::room::Event::Connected(event::room::Connected {
+				connection_id: Some(self.id.into()),
+				resumed: self.resumed,
+				ip: self.ip.map(|ip| ip.to_string()),
+			})
+		};
+
 		video_common::events::emit(
 			global.nats(),
 			&global.config().events_stream_name,
@@ -756,9 +1303,7 @@ impl Connection {
 			Target::Room,
 			event::Event::Room(event::Room {
 				room_id: Some(self.room_id.into()),
-				event: Some(event::room::Event::Connected(event::room::Connected {
-					connection_id: Some(self.id.into()),
-				})),
+				event: Some(event),
 			}),
 		)
 		.await;
@@ -767,36 +1312,39 @@ impl Connection {
 	}
 
 	async fn on_data<G: IngestGlobal>(&mut self, global: &Arc<G>, data: ChannelData) -> bool {
-		self.bytes_tracker.add(&data);
+		self.bytes.bytes_read += data.data().len() as u64;
+		self.bytes_since_keyframe += data.data().len() as u64;
 
 		let config = global.config::<IngestConfig>();
+		let limits = &self.limits;
 
-		if self.bytes_tracker.since_keyframe() >= config.max_bytes_between_keyframes {
+		if self.bytes_since_keyframe >= limits.max_bytes_between_keyframes {
 			self.error = Some(IngestError::KeyframeBitrateDistance(
-				self.bytes_tracker.since_keyframe(),
-				config.max_bytes_between_keyframes,
+				self.bytes_since_keyframe,
+				limits.max_bytes_between_keyframes,
 			));
 
 			tracing::debug!(
 				"keyframe bitrate distance exceeded: {:?} - {} > {}",
 				Instant::now() - self.last_keyframe,
-				self.bytes_tracker.since_keyframe(),
-				config.max_bytes_between_keyframes
+				self.bytes_since_keyframe,
+				limits.max_bytes_between_keyframes
 			);
 
 			return false;
 		}
 
-		if self.bytes_tracker.total() * 8 >= config.max_bitrate * config.bitrate_update_interval.as_secs() {
+		let bytes_read = self.throughput.peek(self.bytes).bytes_read;
+		if bytes_read * 8 >= limits.max_bitrate * config.bitrate_update_interval.as_secs() {
 			self.error = Some(IngestError::BitrateLimit(
-				self.bytes_tracker.total() / config.bitrate_update_interval.as_secs() * 8,
-				config.max_bitrate,
+				bytes_read / config.bitrate_update_interval.as_secs() * 8,
+				limits.max_bitrate,
 			));
 
 			tracing::debug!(
 				"bitrate limit exceeded: {} > {}",
-				self.bytes_tracker.total() * 8 / config.bitrate_update_interval.as_secs(),
-				config.max_bitrate
+				bytes_read * 8 / config.bitrate_update_interval.as_secs(),
+				limits.max_bitrate
 			);
 
 			return false;
@@ -815,6 +1363,8 @@ impl Connection {
 					}
 				};
 
+				self.health.add(timestamp, &data);
+
 				self.transmuxer.add_tag(FlvTag {
 					timestamp,
 					data,
@@ -833,6 +1383,8 @@ impl Connection {
 					}
 				};
 
+				self.health.add(timestamp, &data);
+
 				self.transmuxer.add_tag(FlvTag {
 					timestamp,
 					data,
@@ -867,10 +1419,18 @@ impl Connection {
 				data,
 			})) => {
 				let bitrate = video_settings.bitrate as u64 + audio_settings.bitrate as u64;
-				if bitrate >= config.max_bitrate {
-					self.error = Some(IngestError::BitrateLimit(bitrate, config.max_bitrate));
+				if bitrate >= self.limits.max_bitrate {
+					self.error = Some(IngestError::BitrateLimit(bitrate, self.limits.max_bitrate));
+
+					tracing::debug!("bitrate limit exceeded: {} > {}", bitrate, self.limits.max_bitrate);
+
+					return false;
+				}
+
+				if let Err(err) = self.limits.check(&video_settings, &audio_settings) {
+					self.error = Some(err);
 
-					tracing::debug!("bitrate limit exceeded: {} > {}", bitrate, config.max_bitrate);
+					tracing::debug!("ingest policy violated: {}", err);
 
 					return false;
 				}
@@ -892,19 +1452,21 @@ impl Connection {
 	pub async fn on_media_segment<G: IngestGlobal>(&mut self, global: &Arc<G>, segment: MediaSegment) -> bool {
 		let config = global.config::<IngestConfig>();
 
-		if Instant::now() - self.last_keyframe >= config.max_time_between_keyframes {
-			self.error = Some(IngestError::KeyframeTimeLimit(config.max_time_between_keyframes.as_secs()));
+		if Instant::now() - self.last_keyframe >= self.limits.max_time_between_keyframes {
+			self.error = Some(IngestError::KeyframeTimeLimit(
+				self.limits.max_time_between_keyframes.as_secs(),
+			));
 
 			tracing::debug!(
 				"keyframe time limit exceeded: {:?} > {:?}",
 				Instant::now() - self.last_keyframe,
-				config.max_time_between_keyframes
+				self.limits.max_time_between_keyframes
 			);
 
 			return false;
 		}
 
-		if Instant::now() - self.last_transcoder_publish >= config.transcoder_timeout {
+		if !self.standby && Instant::now() - self.last_transcoder_publish >= config.transcoder_timeout {
 			tracing::error!("no transcoder available to publish to");
 
 			self.error = Some(IngestError::NoTranscoderAvailable);
@@ -912,10 +1474,25 @@ impl Connection {
 			return false;
 		}
 
+		// Let the backup input know that we are still delivering media.
+		if let Some(backup) = &config.backup {
+			if !self.standby && Instant::now() - self.last_heartbeat >= backup.heartbeat_interval {
+				self.last_heartbeat = Instant::now();
+
+				if let Err(err) = global
+					.nats()
+					.publish(keys::ingest_heartbeat(self.organization_id, self.room_id), Bytes::new())
+					.await
+				{
+					tracing::error!(error = %err, "failed to publish heartbeat");
+				}
+			}
+		}
+
 		if segment.keyframe {
 			self.last_keyframe = Instant::now();
 
-			self.bytes_tracker.keyframe();
+			self.bytes_since_keyframe = 0;
 
 			if let Some(transcoder) = self.next_transcoder.take() {
 				if let Some(transcoder) = self.current_transcoder.take() {
@@ -950,6 +1527,7 @@ impl Connection {
 
 				self.current_transcoder = Some(transcoder);
 				self.current_transcoder_id = self.next_transcoder_id.take().unwrap();
+				self.discontinuity = false;
 			};
 		}
 
@@ -963,13 +1541,14 @@ impl Connection {
 							r#type: match fragment.ty {
 								transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio as i32,
 								transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video as i32,
+								transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata as i32,
 							},
 							data: fragment.data.clone(),
 							keyframe: fragment.keyframe,
 							timestamp: fragment.timestamp,
 							timescale: match fragment.ty {
 								transmuxer::MediaType::Audio => self.audio_timescale,
-								transmuxer::MediaType::Video => self.video_timescale,
+								transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => self.video_timescale,
 							},
 						})),
 					})
@@ -991,13 +1570,14 @@ impl Connection {
 							r#type: match segment.ty {
 								transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio as i32,
 								transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video as i32,
+								transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata as i32,
 							},
 							keyframe: segment.keyframe,
 							data: segment.data.clone(),
 							timestamp: segment.timestamp,
 							timescale: match segment.ty {
 								transmuxer::MediaType::Audio => self.audio_timescale,
-								transmuxer::MediaType::Video => self.video_timescale,
+								transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => self.video_timescale,
 							},
 						})),
 					})
@@ -1026,12 +1606,26 @@ impl Connection {
 		true
 	}
 
-	fn on_bitrate_update<G: IngestGlobal>(&mut self, global: &Arc<G>) -> bool {
-		let bitrate = self.bytes_tracker.total() / global.config::<IngestConfig>().bitrate_update_interval.as_secs();
+	async fn on_bitrate_update<G: IngestGlobal>(&mut self, global: &Arc<G>, rtt: Option<Duration>) -> bool {
+		let config = global.config::<IngestConfig>();
+		let bitrate = self.throughput.sample(self.bytes).bytes_read / config.bitrate_update_interval.as_secs();
+
+		let health = self.health.report(bitrate as i64 * 8, rtt);
+
+		// The backup input is not written to the room until it takes over.
+		if self.standby {
+			return true;
+		}
 
-		self.bytes_tracker.clear();
+		if let Some(issues) = self.health.check(&health, &config.health) {
+			self.on_health_changed(global, issues, &health).await;
+		}
 
-		if !self.send_update(Update { bitrate: bitrate as i64 }) {
+		if !self.send_update(Update {
+			connection_id: self.id,
+			bitrate: bitrate as i64,
+			health,
+		}) {
 			self.error = Some(IngestError::FailedToUpdateBitrate);
 			tracing::error!("failed to send bitrate update");
 			false
@@ -1040,7 +1634,290 @@ impl Connection {
 		}
 	}
 
+	async fn on_health_changed<G: IngestGlobal>(&mut self, global: &Arc<G>, issues: Vec<String>, health: &IngestHealth) {
+		let healthy = issues.is_empty();
+
+		tracing::info!(healthy, ?issues, "ingest health changed");
+
+		video_common::events::emit(
+			global.nats(),
+			&global.config().events_stream_name,
+			self.organization_id,
+			Target::Room,
+			event::Event::Room(event::Room {
+				room_id: Some(self.room_id.into()),
+				event: Some(event::room::Event::HealthChanged(event::room::HealthChanged {
+					connection_id: Some(self.id.into()),
+					healthy,
+					issues,
+					health: Some(health.clone()),
+				})),
+			}),
+		)
+		.await;
+	}
+
+	/// Takes over the room from the active input once it stopped delivering
+	/// media, continuing its connection after a discontinuity. Returns the
+	/// subscription for disconnects of the connection that was taken over.
+	async fn take_over<G: IngestGlobal>(&mut self, global: &Arc<G>) -> Result<Option<async_nats::Subscriber>> {
+		// We have nothing to take over with until we have media ourselves.
+		if self.initial_segment.is_none() {
+			return Ok(None);
+		}
+
+		// Only a live room can be taken over, and a primary input which dropped
+		// only until its reconnect deadline has passed.
+		let active_id: Option<Ulid> = utils::database::query(
+			r#"
+			UPDATE rooms
+			SET
+				updated_at = NOW(),
+				backup_ingest_connection_id = NULL,
+				reconnect_deadline = NULL,
+				active_ingest_backup = true
+			WHERE
+				organization_id = $1 AND
+				id = $2 AND
+				backup_ingest_connection_id = $3 AND
+				active_ingest_connection_id IS NOT NULL AND
+				NOT active_ingest_backup AND
+				(reconnect_deadline IS NULL OR reconnect_deadline > NOW())
+			RETURNING active_ingest_connection_id
+			"#,
+		)
+		.bind(self.organization_id)
+		.bind(self.room_id)
+		.bind(self.id)
+		.build_query_single_scalar()
+		.fetch_optional(global.db())
+		.await?
+		.flatten();
+
+		let Some(active_id) = active_id else {
+			return Ok(None);
+		};
+
+		tracing::info!(connection_id = %active_id, "taking over from the active input");
+
+		// The primary input hands its transcoder over instead of ending the stream.
+		if let Err(err) = global.nats().publish(keys::ingest_disconnect(active_id), Bytes::new()).await {
+			tracing::error!(error = %err, "failed to publish disconnect event");
+		}
+
+		self.id = active_id;
+		self.standby = false;
+		self.input_changed = true;
+		self.discontinuity = true;
+		self.last_transcoder_publish = Instant::now();
+
+		Ok(Some(global.nats().subscribe(keys::ingest_disconnect(active_id)).await?))
+	}
+
+	/// Keeps a connection which dropped around for the reconnect grace period.
+	/// Returns true if the broadcaster reconnected and resumed the connection.
+	async fn wait_for_reconnect<G: IngestGlobal>(&mut self, global: &Arc<G>, clean_disconnect: bool) -> Result<bool> {
+		let config = global.config::<IngestConfig>();
+
+		// Only connections which went live and dropped unexpectedly can be resumed.
+		if clean_disconnect || !self.resumable || self.initial_segment.is_none() {
+			return Ok(false);
+		}
+
+		// A backup input held warm takes over once it notices that we stopped, so
+		// it is given the time to do so.
+		let failover_period = match &config.backup {
+			Some(backup) => {
+				let has_backup: Option<bool> = utils::database::query(
+					"SELECT backup_ingest_connection_id IS NOT NULL FROM rooms WHERE organization_id = $1 AND id = $2",
+				)
+				.bind(self.organization_id)
+				.bind(self.room_id)
+				.build_query_single_scalar()
+				.fetch_optional(global.db())
+				.await?;
+
+				if has_backup.unwrap_or_default() {
+					backup.failover_timeout * 2
+				} else {
+					Duration::ZERO
+				}
+			}
+			None => Duration::ZERO,
+		};
+
+		let grace_period = config.reconnect_grace_period.max(failover_period);
+		if grace_period.is_zero() {
+			return Ok(false);
+		}
+
+		// The resuming connection notifies us the same way it would disconnect us.
+		let mut sub = global.nats().subscribe(keys::ingest_disconnect(self.id)).await?;
+
+		let r = utils::database::query(
+			r#"
+			UPDATE rooms
+			SET
+				updated_at = NOW(),
+				reconnect_deadline = $1
+			WHERE
+				organization_id = $2 AND
+				id = $3 AND
+				active_ingest_connection_id = $4
+			"#,
+		)
+		.bind(chrono::Utc::now() + chrono::Duration::from_std(grace_period)?)
+		.bind(self.organization_id)
+		.bind(self.room_id)
+		.bind(self.id)
+		.build()
+		.execute(global.db())
+		.await?;
+
+		if r != 1 {
+			return Ok(false);
+		}
+
+		tracing::info!("waiting for the broadcaster to reconnect");
+
+		select! {
+			_ = sub.next() => {},
+			_ = tokio::time::sleep(grace_period) => {},
+			_ = global.ctx().done() => {},
+		}
+
+		// Whoever clears the deadline first owns the room, so if the resuming
+		// connection beat us to it we must not touch the room anymore.
+		let r = utils::database::query(
+			r#"
+			UPDATE rooms
+			SET
+				reconnect_deadline = NULL
+			WHERE
+				organization_id = $1 AND
+				id = $2 AND
+				active_ingest_connection_id = $3 AND
+				reconnect_deadline IS NOT NULL
+			"#,
+		)
+		.bind(self.organization_id)
+		.bind(self.room_id)
+		.bind(self.id)
+		.build()
+		.execute(global.db())
+		.await?;
+
+		if r == 1 {
+			return Ok(false);
+		}
+
+		// The room was either resumed, failed over to the backup input or taken
+		// over by a new connection.
+		let active_id: Option<Ulid> =
+			utils::database::query("SELECT active_ingest_connection_id FROM rooms WHERE organization_id = $1 AND id = $2")
+				.bind(self.organization_id)
+				.bind(self.room_id)
+				.build_query_single_scalar()
+				.fetch_optional(global.db())
+				.await?
+				.flatten();
+
+		Ok(active_id == Some(self.id))
+	}
+
+	/// Checks if the other input of the room continues this connection.
+	async fn replaced_by_other_input<G: IngestGlobal>(&mut self, global: &Arc<G>) -> Result<bool> {
+		let replaced: Option<bool> = utils::database::query(
+			r#"
+			SELECT active_ingest_backup != $1
+			FROM rooms
+			WHERE
+				organization_id = $2 AND
+				id = $3 AND
+				active_ingest_connection_id = $4
+			"#,
+		)
+		.bind(self.backup)
+		.bind(self.organization_id)
+		.bind(self.room_id)
+		.bind(self.id)
+		.build_query_single_scalar()
+		.fetch_optional(global.db())
+		.await?;
+
+		Ok(replaced.unwrap_or_default())
+	}
+
+	async fn cleanup_standby<G: IngestGlobal>(&mut self, global: &Arc<G>, clean_disconnect: bool) -> Result<()> {
+		video_common::events::emit(
+			global.nats(),
+			&global.config().events_stream_name,
+			self.organization_id,
+			Target::Room,
+			event::Event::Room(event::Room {
+				room_id: Some(self.room_id.into()),
+				event: Some(event::room::Event::BackupDisconnected(event::room::BackupDisconnected {
+					connection_id: Some(self.id.into()),
+					clean: clean_disconnect,
+					cause: self.error.as_ref().map(|e| e.to_string()),
+				})),
+			}),
+		)
+		.await;
+
+		utils::database::query(
+			r#"
+			UPDATE rooms
+			SET
+				updated_at = NOW(),
+				backup_ingest_connection_id = NULL
+			WHERE
+				organization_id = $1 AND
+				id = $2 AND
+				backup_ingest_connection_id = $3
+			"#,
+		)
+		.bind(self.organization_id)
+		.bind(self.room_id)
+		.bind(self.id)
+		.build()
+		.execute(global.db())
+		.await?;
+
+		Ok(())
+	}
+
 	async fn cleanup<G: IngestGlobal>(&mut self, global: &Arc<G>, clean_disconnect: bool) -> Result<()> {
+		if self.standby {
+			return self.cleanup_standby(global, clean_disconnect).await;
+		}
+
+		let mut handed_over = match self.wait_for_reconnect(global, clean_disconnect).await {
+			Ok(resumed) => resumed,
+			Err(err) => {
+				tracing::error!(error = %err, "failed to wait for reconnect");
+				false
+			}
+		};
+
+		if !handed_over {
+			handed_over = match self.replaced_by_other_input(global).await {
+				Ok(replaced) => replaced,
+				Err(err) => {
+					tracing::error!(error = %err, "failed to check for the other input");
+					false
+				}
+			};
+		}
+
+		// A connection which is continued elsewhere moves on to a new transcoder,
+		// so the current one must not complete the playlists.
+		let shutdown = if handed_over {
+			ingest_watch_response::Shutdown::Transcoder
+		} else {
+			ingest_watch_response::Shutdown::Stream
+		};
+
 		if let Some(next_id) = self.next_transcoder_id.take() {
 			global.requests().lock().await.remove(&next_id);
 		}
@@ -1053,9 +1930,7 @@ impl Connection {
 			transcoder
 				.send
 				.try_send(IngestWatchResponse {
-					message: Some(ingest_watch_response::Message::Shutdown(
-						ingest_watch_response::Shutdown::Stream as i32,
-					)),
+					message: Some(ingest_watch_response::Message::Shutdown(shutdown as i32)),
 				})
 				.ok();
 		}
@@ -1064,13 +1939,16 @@ impl Connection {
 			transcoder
 				.send
 				.try_send(IngestWatchResponse {
-					message: Some(ingest_watch_response::Message::Shutdown(
-						ingest_watch_response::Shutdown::Stream as i32,
-					)),
+					message: Some(ingest_watch_response::Message::Shutdown(shutdown as i32)),
 				})
 				.ok();
 		}
 
+		if handed_over {
+			tracing::info!("connection continued by a reconnect or the other input");
+			return Ok(());
+		}
+
 		video_common::events::emit(
 			global.nats(),
 			&global.config().events_stream_name,
@@ -1097,22 +1975,27 @@ impl Connection {
 				video_input = NULL,
 				audio_input = NULL,
 				ingest_bitrate = NULL,
+				ingest_health = NULL,
+				audio_loudness = NULL,
 				video_output = NULL,
 				audio_output = NULL,
 				active_recording_id = NULL,
 				active_recording_config = NULL,
 				active_transcoding_config = NULL,
+				active_ingest_backup = false,
 				status = $1
 				WHERE 
 					organization_id = $2 AND
 					id = $3 AND
-					active_ingest_connection_id = $4
+					active_ingest_connection_id = $4 AND
+					active_ingest_backup = $5
 			"#,
 		)
 		.bind(RoomStatus::Offline)
 		.bind(self.organization_id)
 		.bind(self.room_id)
 		.bind(self.id)
+		.bind(self.backup)
 		.build()
 		.execute(global.db())
 		.await?;
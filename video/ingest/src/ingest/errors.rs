@@ -17,6 +17,17 @@ pub enum IngestError {
 	SubscriptionClosedUnexpectedly,
 	FailedToRequestTranscoder,
 	FailedToUpdateRoom,
+	SrtConnectionError,
+	SrtConnectionTimeout,
+	WhipConnectionError,
+	WhipConnectionTimeout,
+	PullConnectionError,
+	PullConnectionTimeout,
+	VideoWidthLimit(u32, u32),
+	VideoHeightLimit(u32, u32),
+	FrameRateLimit(u32, u32),
+	VideoCodecNotAllowed,
+	AudioCodecNotAllowed,
 }
 
 impl std::fmt::Display for IngestError {
@@ -55,6 +66,25 @@ impl std::fmt::Display for IngestError {
 			}
 			Self::FailedToRequestTranscoder => write!(f, "I16: Failed to request transcoder"),
 			Self::FailedToUpdateRoom => write!(f, "I17: Failed to update room"),
+			Self::SrtConnectionError => write!(f, "I18: SRT connection error"),
+			Self::SrtConnectionTimeout => write!(f, "I19: SRT connection timeout"),
+			Self::WhipConnectionError => write!(f, "I20: WHIP connection error"),
+			Self::WhipConnectionTimeout => write!(f, "I21: WHIP connection timeout"),
+			Self::PullConnectionError => write!(f, "I22: Pull source connection error"),
+			Self::PullConnectionTimeout => write!(f, "I23: Pull source connection timeout"),
+			Self::VideoWidthLimit(a, b) => {
+				write!(f, "I24: Video width limit reached, the video is too wide: {}px > {}px", a, b)
+			}
+			Self::VideoHeightLimit(a, b) => {
+				write!(f, "I25: Video height limit reached, the video is too tall: {}px > {}px", a, b)
+			}
+			Self::FrameRateLimit(a, b) => write!(
+				f,
+				"I26: Frame rate limit reached, the frame rate is too high: {}fps > {}fps",
+				a, b
+			),
+			Self::VideoCodecNotAllowed => write!(f, "I27: Video codec not allowed by the ingest policy"),
+			Self::AudioCodecNotAllowed => write!(f, "I28: Audio codec not allowed by the ingest policy"),
 		}
 	}
 }
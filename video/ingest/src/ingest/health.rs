@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use flv::{
+	AacPacket, Av1Packet, AvcPacket, EnhancedAudioPacket, EnhancedPacket, FlvTagAudioData, FlvTagData, FlvTagVideoData,
+	FrameType, HevcPacket, OpusPacket,
+};
+use pb::scuffle::video::v1::types::IngestHealth;
+use tokio::time::Instant;
+
+use crate::config::HealthConfig;
+
+/// A gap between two timestamps of a track larger than this (in
+/// milliseconds) is a jump rather than missing frames.
+const TIMESTAMP_JUMP: i64 = 1000;
+
+/// How far (in milliseconds) a frame can fall behind the wall clock, compared
+/// to the frame which arrived the earliest, before it is late.
+const LATE_THRESHOLD: i64 = 500;
+
+/// Measures the health of the input from the timestamps of its frames,
+/// reported once every update interval.
+pub struct HealthTracker {
+	declared_fps: f64,
+
+	interval_start: Instant,
+	video_frames: u64,
+	dropped_frames: u64,
+	late_frames: u64,
+	timestamp_jumps: u64,
+
+	last_video: Option<u32>,
+	last_audio: Option<u32>,
+	last_keyframe: Option<u32>,
+	keyframe_interval: Option<u32>,
+
+	// The wall clock and the timestamp of the first video frame since the
+	// last jump, and how far the earliest frame since then lagged behind it
+	clock: Option<(Instant, u32)>,
+	min_lag: i64,
+
+	// The thresholds the input crossed at the last report
+	issues: Vec<String>,
+}
+
+impl Default for HealthTracker {
+	fn default() -> Self {
+		Self {
+			declared_fps: 0.0,
+			interval_start: Instant::now(),
+			video_frames: 0,
+			dropped_frames: 0,
+			late_frames: 0,
+			timestamp_jumps: 0,
+			last_video: None,
+			last_audio: None,
+			last_keyframe: None,
+			keyframe_interval: None,
+			clock: None,
+			min_lag: 0,
+			issues: Vec::new(),
+		}
+	}
+}
+
+impl HealthTracker {
+	/// The frame rate is measured from here on, so that the time it took to
+	/// receive the sequence headers does not count against it.
+	pub fn set_declared_fps(&mut self, fps: f64) {
+		self.declared_fps = fps;
+		self.interval_start = Instant::now();
+		self.video_frames = 0;
+	}
+
+	pub fn add(&mut self, timestamp: u32, data: &FlvTagData) {
+		match data {
+			FlvTagData::Video {
+				frame_type,
+				data:
+					FlvTagVideoData::Avc(AvcPacket::Nalu { .. })
+					| FlvTagVideoData::Enhanced(
+						EnhancedPacket::Hevc(HevcPacket::Nalu { .. }) | EnhancedPacket::Av1(Av1Packet::Raw(_)),
+					),
+			} => self.add_video(timestamp, *frame_type == FrameType::Keyframe),
+			FlvTagData::Audio {
+				data:
+					FlvTagAudioData::Aac(AacPacket::Raw(_))
+					| FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::Raw(_))),
+				..
+			} => self.add_audio(timestamp),
+			// Sequence headers and metadata are not frames.
+			_ => {}
+		}
+	}
+
+	fn add_video(&mut self, timestamp: u32, keyframe: bool) {
+		if let Some(last) = self.last_video {
+			let delta = timestamp_delta(last, timestamp);
+
+			if !(0..=TIMESTAMP_JUMP).contains(&delta) {
+				self.timestamp_jumps += 1;
+				self.clock = None;
+				self.last_keyframe = None;
+			} else if self.declared_fps > 0.0 {
+				let frames = (delta as f64 * self.declared_fps / 1000.0).round() as u64;
+				self.dropped_frames += frames.saturating_sub(1);
+			}
+		}
+
+		let now = Instant::now();
+		let lag = match self.clock {
+			Some((start, start_timestamp)) => (now - start).as_millis() as i64 - timestamp_delta(start_timestamp, timestamp),
+			None => {
+				self.clock = Some((now, timestamp));
+				self.min_lag = 0;
+				0
+			}
+		};
+
+		self.min_lag = self.min_lag.min(lag);
+		if lag - self.min_lag > LATE_THRESHOLD {
+			self.late_frames += 1;
+		}
+
+		if keyframe {
+			if let Some(last) = self.last_keyframe {
+				self.keyframe_interval = Some(timestamp.wrapping_sub(last));
+			}
+
+			self.last_keyframe = Some(timestamp);
+		}
+
+		self.video_frames += 1;
+		self.last_video = Some(timestamp);
+	}
+
+	fn add_audio(&mut self, timestamp: u32) {
+		if let Some(last) = self.last_audio {
+			if !(0..=TIMESTAMP_JUMP).contains(&timestamp_delta(last, timestamp)) {
+				self.timestamp_jumps += 1;
+			}
+		}
+
+		self.last_audio = Some(timestamp);
+	}
+
+	/// Reports the health of the input since the last report.
+	pub fn report(&mut self, bitrate: i64, rtt: Option<Duration>) -> IngestHealth {
+		let elapsed = self.interval_start.elapsed().as_secs_f64();
+
+		let health = IngestHealth {
+			bitrate,
+			fps: if elapsed > 0.0 {
+				self.video_frames as f64 / elapsed
+			} else {
+				0.0
+			},
+			declared_fps: self.declared_fps,
+			keyframe_interval_ms: self.keyframe_interval.map(|interval| interval as i64),
+			av_drift_ms: match (self.last_audio, self.last_video) {
+				(Some(audio), Some(video)) => timestamp_delta(video, audio),
+				_ => 0,
+			},
+			dropped_frames: self.dropped_frames as i64,
+			late_frames: self.late_frames as i64,
+			timestamp_jumps: self.timestamp_jumps as i64,
+			rtt_ms: rtt.map(|rtt| rtt.as_millis() as i64),
+		};
+
+		self.interval_start = Instant::now();
+		self.video_frames = 0;
+		self.dropped_frames = 0;
+		self.late_frames = 0;
+		self.timestamp_jumps = 0;
+
+		health
+	}
+
+	/// Checks the health against the thresholds, returns the thresholds the
+	/// input crosses if they changed since the last check.
+	pub fn check(&mut self, health: &IngestHealth, config: &HealthConfig) -> Option<Vec<String>> {
+		let issues = issues(health, config);
+		if issues == self.issues {
+			return None;
+		}
+
+		self.issues.clone_from(&issues);
+
+		Some(issues)
+	}
+}
+
+fn issues(health: &IngestHealth, config: &HealthConfig) -> Vec<String> {
+	let mut issues = Vec::new();
+
+	if health.declared_fps > 0.0 && health.fps < health.declared_fps * config.min_fps_ratio {
+		issues.push("low_fps");
+	}
+
+	if !config.max_keyframe_interval.is_zero()
+		&& health.keyframe_interval_ms.unwrap_or_default() > config.max_keyframe_interval.as_millis() as i64
+	{
+		issues.push("keyframe_interval");
+	}
+
+	if !config.max_av_drift.is_zero() && health.av_drift_ms.abs() > config.max_av_drift.as_millis() as i64 {
+		issues.push("av_drift");
+	}
+
+	if health.dropped_frames as u64 > config.max_dropped_frames {
+		issues.push("dropped_frames");
+	}
+
+	if health.late_frames as u64 > config.max_late_frames {
+		issues.push("late_frames");
+	}
+
+	if health.timestamp_jumps as u64 > config.max_timestamp_jumps {
+		issues.push("timestamp_jumps");
+	}
+
+	if !config.max_rtt.is_zero() && health.rtt_ms.unwrap_or_default() > config.max_rtt.as_millis() as i64 {
+		issues.push("rtt");
+	}
+
+	issues.into_iter().map(|issue| issue.to_string()).collect()
+}
+
+/// The difference between two timestamps in milliseconds, negative if the
+/// timestamp went backwards. RTMP timestamps wrap around.
+fn timestamp_delta(from: u32, to: u32) -> i64 {
+	to.wrapping_sub(from) as i32 as i64
+}
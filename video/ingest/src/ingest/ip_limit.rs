@@ -0,0 +1,102 @@
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use utils::prelude::FutureTimeout;
+use utils::ratelimiter::RateLimiterOptions;
+
+use crate::config::IngestConfig;
+use crate::global::IngestGlobal;
+
+/// Counts a connection against the limits of its ip address until it is
+/// dropped.
+pub struct IpGuard<G: IngestGlobal> {
+	global: Arc<G>,
+	ip: IpAddr,
+}
+
+impl<G: IngestGlobal> Drop for IpGuard<G> {
+	fn drop(&mut self) {
+		let mut connections = self.global.ip_connections().lock().unwrap();
+
+		if let Some(count) = connections.get_mut(&self.ip) {
+			*count -= 1;
+			if *count == 0 {
+				connections.remove(&self.ip);
+			}
+		}
+	}
+}
+
+/// The address connections are limited by. An ipv6 host is usually given a
+/// whole /64, so those are limited as one.
+fn limit_key(ip: IpAddr) -> IpAddr {
+	match ip.to_canonical() {
+		IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !(u64::MAX as u128))),
+		ip => ip,
+	}
+}
+
+/// Check if a new connection from the ip address is within the limits, the
+/// guard has to be held for as long as the connection is open.
+pub async fn acquire<G: IngestGlobal>(global: &Arc<G>, ip: IpAddr) -> Option<IpGuard<G>> {
+	let ip = limit_key(ip);
+	let config = global.config::<IngestConfig>().ip_limit.as_ref();
+
+	{
+		let mut connections = global.ip_connections().lock().unwrap();
+		let count = connections.entry(ip).or_default();
+
+		if let Some(config) = config {
+			if config.max_connections != 0 && *count >= config.max_connections {
+				tracing::debug!(%ip, "too many connections from ip");
+				return None;
+			}
+		}
+
+		*count += 1;
+	}
+
+	// The guard releases the connection again if the rate limit rejects it.
+	let guard = IpGuard {
+		global: global.clone(),
+		ip,
+	};
+
+	let Some(config) = config.filter(|config| config.handshake_quota != 0) else {
+		return Some(guard);
+	};
+
+	let options = RateLimiterOptions {
+		cost: 1,
+		quota: config.handshake_quota,
+		quota_reset_seconds: config.quota_reset_seconds,
+		exceeded_limit: config.exceeded_limit,
+		exceeded_reset_seconds: config.exceeded_reset_seconds,
+		banned_reset_seconds: config.banned_reset_seconds,
+		namespace: format!("{{ratelimit:ingest:{ip}}}"),
+		limit_key: "handshake".to_string(),
+		exceeded_key: "exceeded".to_string(),
+		banned_key: "banned".to_string(),
+	};
+
+	// An ip is not turned away because redis is unavailable.
+	match utils::ratelimiter::ratelimit(global.redis().as_ref(), &options)
+		.timeout(Duration::from_secs(1))
+		.await
+	{
+		Ok(Ok(resp)) if !resp.can_request() => {
+			tracing::debug!(%ip, banned = resp.banned, "ip is rate limited");
+			None
+		}
+		Ok(Ok(_)) => Some(guard),
+		Ok(Err(err)) => {
+			tracing::warn!(error = %err, "failed to rate limit");
+			Some(guard)
+		}
+		Err(_) => {
+			tracing::warn!("timed out rate limiting");
+			Some(guard)
+		}
+	}
+}
@@ -10,14 +10,55 @@ use utils::prelude::FutureTimeout;
 use crate::config::IngestConfig;
 use crate::global::IngestGlobal;
 
-mod bytes_tracker;
+mod auth;
+mod avc;
 mod connection;
 mod errors;
+mod health;
+mod ip_limit;
+mod policy;
+mod pull;
+mod pull_session;
 mod rtmp_session;
+mod session;
+mod srt;
+mod srt_session;
+pub(crate) mod ts;
 mod update;
+mod whip;
+mod whip_session;
 
 pub async fn run<G: IngestGlobal>(global: Arc<G>) -> Result<()> {
 	let config = global.config::<IngestConfig>();
+
+	let srt = async {
+		match &config.srt {
+			Some(srt) => srt::run(global.clone(), srt).await,
+			None => Ok(()),
+		}
+	};
+
+	let whip = async {
+		match &config.whip {
+			Some(whip) => whip::run(global.clone(), whip).await,
+			None => Ok(()),
+		}
+	};
+
+	let pull = async {
+		match &config.pull {
+			Some(pull) => pull::run(global.clone(), pull).await,
+			None => Ok(()),
+		}
+	};
+
+	tokio::try_join!(run_rtmp(global.clone()), srt, whip, pull)?;
+
+	Ok(())
+}
+
+async fn run_rtmp<G: IngestGlobal>(global: Arc<G>) -> Result<()> {
+	let config = global.config::<IngestConfig>();
 	tracing::info!("Ingest(RTMP) listening on {}", config.rtmp.bind_address);
 	let socket = if config.rtmp.bind_address.is_ipv6() {
 		TcpSocket::new_v6()?
@@ -58,6 +99,10 @@ pub async fn run<G: IngestGlobal>(global: Arc<G>) -> Result<()> {
 		let global = global.clone();
 
 		tokio::spawn(async move {
+			let Some(_guard) = ip_limit::acquire(&global, addr.ip()).await else {
+				return;
+			};
+
 			if let Some(tls_acceptor) = tls_acceptor {
 				let Ok(Ok(socket)) = tls_acceptor.accept(socket).timeout(Duration::from_secs(5)).await else {
 					return;
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use transmuxer::{AudioSettings, VideoSettings};
+use video_common::database::IngestPolicy;
+
+use super::errors::IngestError;
+use crate::config::IngestConfig;
+
+/// The limits a connection is held to, taken from the ingest policy of the
+/// room and falling back to the ingest config for anything it does not set.
+#[derive(Debug, Clone)]
+pub struct Limits {
+	pub max_bitrate: u64,
+	pub max_bytes_between_keyframes: u64,
+	pub max_time_between_keyframes: Duration,
+	max_width: Option<u32>,
+	max_height: Option<u32>,
+	max_fps: Option<u32>,
+	allowed_video_codecs: Vec<String>,
+	allowed_audio_codecs: Vec<String>,
+}
+
+impl Limits {
+	pub fn new(config: &IngestConfig, policy: Option<IngestPolicy>) -> Self {
+		let policy = policy.unwrap_or_default();
+
+		Self {
+			max_bitrate: policy.max_bitrate.map(|v| v as u64).unwrap_or(config.max_bitrate),
+			max_bytes_between_keyframes: policy
+				.max_bytes_between_keyframes
+				.map(|v| v as u64)
+				.unwrap_or(config.max_bytes_between_keyframes),
+			max_time_between_keyframes: policy
+				.max_time_between_keyframes
+				.map(|v| Duration::from_millis(v as u64))
+				.unwrap_or(config.max_time_between_keyframes),
+			max_width: policy.max_width.map(|v| v as u32),
+			max_height: policy.max_height.map(|v| v as u32),
+			max_fps: policy.max_fps.map(|v| v as u32),
+			allowed_video_codecs: policy.allowed_video_codecs,
+			allowed_audio_codecs: policy.allowed_audio_codecs,
+		}
+	}
+
+	/// Checks the settings of the init segment against the policy.
+	pub fn check(&self, video_settings: &VideoSettings, audio_settings: &AudioSettings) -> Result<(), IngestError> {
+		if let Some(max_width) = self.max_width {
+			if video_settings.width > max_width {
+				return Err(IngestError::VideoWidthLimit(video_settings.width, max_width));
+			}
+		}
+
+		if let Some(max_height) = self.max_height {
+			if video_settings.height > max_height {
+				return Err(IngestError::VideoHeightLimit(video_settings.height, max_height));
+			}
+		}
+
+		if let Some(max_fps) = self.max_fps {
+			// Allow for rounding, 59.94fps is fine with a limit of 60.
+			if video_settings.framerate.round() as u32 > max_fps {
+				return Err(IngestError::FrameRateLimit(video_settings.framerate.round() as u32, max_fps));
+			}
+		}
+
+		if !codec_allowed(&self.allowed_video_codecs, &video_settings.codec.to_string()) {
+			return Err(IngestError::VideoCodecNotAllowed);
+		}
+
+		if !codec_allowed(&self.allowed_audio_codecs, &audio_settings.codec.to_string()) {
+			return Err(IngestError::AudioCodecNotAllowed);
+		}
+
+		Ok(())
+	}
+}
+
+/// Codecs are matched on their family, so `avc1` allows `avc1.64001f`.
+fn codec_allowed(allowed: &[String], codec: &str) -> bool {
+	let family = codec.split('.').next().unwrap_or_default();
+	allowed.is_empty() || allowed.iter().any(|c| c == family)
+}
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use bytes::Bytes;
+use tokio::select;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use ulid::Ulid;
+use video_common::ip_range::IpRange;
+use video_common::keys;
+
+use super::connection;
+use super::pull_session::PullSession;
+use crate::config::PullConfig;
+use crate::global::IngestGlobal;
+
+#[derive(Debug, postgres_from_row::FromRow)]
+struct PullRoom {
+	organization_id: Ulid,
+	id: Ulid,
+	source_url: String,
+}
+
+#[derive(postgres_from_row::FromRow)]
+struct Lease {
+	source_url: Option<String>,
+}
+
+pub async fn run<G: IngestGlobal>(global: Arc<G>, config: &PullConfig) -> Result<()> {
+	tracing::info!("Ingest(Pull) polling for rooms every {:?}", config.poll_interval);
+
+	for network in &config.allowed_networks {
+		network
+			.parse::<IpRange>()
+			.with_context(|| format!("invalid allowed network: {network}"))?;
+	}
+
+	// All the rooms this ingest server pulls share a lease id, so that the
+	// lease can be renewed and released per room.
+	let lease_id = Ulid::new();
+
+	let mut pullers = JoinSet::new();
+	let mut poll_interval = tokio::time::interval(config.poll_interval);
+
+	loop {
+		select! {
+			_ = global.ctx().done() => break,
+			_ = poll_interval.tick() => {},
+			Some(_) = pullers.join_next() => continue,
+		}
+
+		let available = config.max_pullers.saturating_sub(pullers.len());
+		if available == 0 {
+			continue;
+		}
+
+		match claim_rooms(&global, config, lease_id, available).await {
+			Ok(rooms) => {
+				for room in rooms {
+					pullers.spawn(run_puller(global.clone(), config.clone(), lease_id, room));
+				}
+			}
+			Err(err) => tracing::error!(error = %err, "failed to claim rooms"),
+		}
+	}
+
+	// The pullers stop on their own once the context is done, they still need to
+	// clean up their connections and release their leases.
+	while pullers.join_next().await.is_some() {}
+
+	Ok(())
+}
+
+/// Take the lease on rooms with a source url which are not being pulled by
+/// anyone, either because they are new or because the ingest server pulling
+/// them went away.
+async fn claim_rooms<G: IngestGlobal>(
+	global: &Arc<G>,
+	config: &PullConfig,
+	lease_id: Ulid,
+	limit: usize,
+) -> Result<Vec<PullRoom>> {
+	Ok(utils::database::query(
+		r#"
+		UPDATE rooms
+		SET
+			source_lease_id = $1,
+			source_lease_expires_at = NOW() + $2::INT8 * INTERVAL '1 millisecond'
+		WHERE
+			source_url IS NOT NULL AND
+			(source_lease_expires_at IS NULL OR source_lease_expires_at < NOW())
+		LIMIT $3
+		RETURNING organization_id, id, source_url
+		"#,
+	)
+	.bind(lease_id)
+	.bind(config.lease_duration.as_millis() as i64)
+	.bind(limit as i64)
+	.build_query_as()
+	.fetch_all(global.db())
+	.await?)
+}
+
+#[tracing::instrument(skip(global, config, lease_id), fields(organization_id = %room.organization_id, room_id = %room.id))]
+async fn run_puller<G: IngestGlobal>(global: Arc<G>, config: PullConfig, lease_id: Ulid, room: PullRoom) {
+	tracing::info!(source_url = %room.source_url, "pulling room");
+
+	let (active_sender, active) = watch::channel(None);
+	let (stop_sender, stop) = watch::channel(false);
+
+	tokio::join!(
+		pull(&global, &config, lease_id, &room, active_sender, stop),
+		keep_lease(&global, &config, lease_id, &room, active, stop_sender),
+	);
+
+	if let Err(err) = release_lease(&global, lease_id, &room).await {
+		tracing::error!(error = %err, "failed to release lease");
+	}
+
+	tracing::info!("stopped pulling room");
+}
+
+/// Connect to the source and run it through a connection, reconnecting with
+/// backoff until we are told to stop.
+async fn pull<G: IngestGlobal>(
+	global: &Arc<G>,
+	config: &PullConfig,
+	lease_id: Ulid,
+	room: &PullRoom,
+	active: watch::Sender<Option<Ulid>>,
+	mut stop: watch::Receiver<bool>,
+) {
+	let mut backoff = config.min_backoff;
+
+	while !*stop.borrow() && !global.ctx().is_done() {
+		let result = select! {
+			r = PullSession::connect(config, &room.source_url) => r,
+			_ = stop.changed() => return,
+			_ = global.ctx().done() => return,
+		};
+
+		match result {
+			Ok(session) => {
+				if connection::handle_pull(global.clone(), room.organization_id, room.id, lease_id, session, &active).await {
+					backoff = config.min_backoff;
+				}
+			}
+			Err(err) => tracing::debug!(error = %err, "failed to connect to source"),
+		}
+
+		select! {
+			_ = tokio::time::sleep(backoff) => {},
+			_ = stop.changed() => return,
+			_ = global.ctx().done() => return,
+		}
+
+		backoff = (backoff * 2).min(config.max_backoff);
+	}
+}
+
+/// Renew the lease until the room's source url changes or we lose the lease,
+/// in which case the puller is stopped and the connection is disconnected.
+async fn keep_lease<G: IngestGlobal>(
+	global: &Arc<G>,
+	config: &PullConfig,
+	lease_id: Ulid,
+	room: &PullRoom,
+	mut active: watch::Receiver<Option<Ulid>>,
+	stop: watch::Sender<bool>,
+) {
+	let mut renew_interval = tokio::time::interval(config.lease_duration / 3);
+	renew_interval.tick().await; // The lease was just claimed
+
+	loop {
+		select! {
+			_ = renew_interval.tick() => {},
+			r = active.changed() => {
+				// The puller has stopped
+				if r.is_err() {
+					return;
+				}
+
+				continue;
+			},
+		}
+
+		let result = utils::database::query(
+			r#"
+			UPDATE rooms
+			SET source_lease_expires_at = NOW() + $1::INT8 * INTERVAL '1 millisecond'
+			WHERE organization_id = $2 AND id = $3 AND source_lease_id = $4
+			RETURNING source_url
+			"#,
+		)
+		.bind(config.lease_duration.as_millis() as i64)
+		.bind(room.organization_id)
+		.bind(room.id)
+		.bind(lease_id)
+		.build_query_as::<Lease>()
+		.fetch_optional(global.db())
+		.await;
+
+		match result {
+			Ok(Some(Lease { source_url: Some(url) })) if url == room.source_url => continue,
+			Ok(Some(_)) => tracing::info!("source url changed"),
+			Ok(None) => tracing::warn!("lost lease"),
+			Err(err) => {
+				tracing::error!(error = %err, "failed to renew lease");
+				continue;
+			}
+		}
+
+		stop.send_replace(true);
+
+		// If the source url was changed, the room will be picked up again with the new
+		// url once we release the lease.
+		let connection_id = *active.borrow();
+		if let Some(connection_id) = connection_id {
+			if let Err(err) = global
+				.nats()
+				.publish(keys::ingest_disconnect(connection_id), Bytes::new())
+				.await
+			{
+				tracing::error!(error = %err, "failed to publish disconnect event");
+			}
+		}
+
+		// Wait for the puller to stop
+		while active.changed().await.is_ok() {}
+
+		return;
+	}
+}
+
+async fn release_lease<G: IngestGlobal>(global: &Arc<G>, lease_id: Ulid, room: &PullRoom) -> Result<()> {
+	utils::database::query(
+		r#"
+		UPDATE rooms
+		SET source_lease_id = NULL, source_lease_expires_at = NULL
+		WHERE organization_id = $1 AND id = $2 AND source_lease_id = $3
+		"#,
+	)
+	.bind(room.organization_id)
+	.bind(room.id)
+	.bind(lease_id)
+	.build()
+	.execute(global.db())
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,620 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::Future;
+use mpegts::TsDemuxer;
+use rtmp::{ChannelData, ClientSession, SessionError};
+use tokio::net::TcpStream;
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use url::Url;
+use video_common::ip_range::{self, IpRange};
+
+use super::errors::IngestError;
+use super::session::{Data, IngestSession};
+use super::ts::FrameConverter;
+use crate::config::PullConfig;
+
+/// How many segments behind the live edge we start playing a HLS playlist.
+/// This gives us some buffer for reloading the playlist and downloading
+/// segments.
+const HLS_LIVE_EDGE_SEGMENTS: usize = 3;
+
+/// How far behind real time a HLS stream can fall before we stop trying to
+/// catch up.
+const HLS_MAX_LAG: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum PullError {
+	#[error("invalid source url: {0}")]
+	InvalidUrl(&'static str),
+	#[error("source address is not allowed")]
+	AddressNotAllowed,
+	#[error("timed out waiting for the source")]
+	Timeout,
+	#[error("source closed before sending any data")]
+	Closed,
+	#[error("io error: {0}")]
+	Io(#[from] io::Error),
+	#[error("rtmp error: {0}")]
+	Rtmp(SessionError),
+	#[error("http error: {0}")]
+	Http(#[from] reqwest::Error),
+	#[error("invalid flv stream: {0}")]
+	Flv(&'static str),
+	#[error("invalid playlist: {0}")]
+	Playlist(&'static str),
+}
+
+type RtmpFuture = Pin<Box<dyn Future<Output = Result<bool, SessionError>> + Send>>;
+
+/// A session which pulls media from a room's source url.
+pub struct PullSession {
+	source: Source,
+	first: Option<ChannelData>,
+}
+
+enum Source {
+	Rtmp {
+		future: Option<RtmpFuture>,
+		data: mpsc::Receiver<ChannelData>,
+	},
+	HttpFlv(FlvReader),
+	Hls(Box<HlsReader>),
+}
+
+impl PullSession {
+	/// Connect to the source and wait for it to send the first piece of media,
+	/// so that a room is only taken over once the source is actually live.
+	pub async fn connect(config: &PullConfig, source_url: &str) -> Result<Self, PullError> {
+		let url = Url::parse(source_url).map_err(|_| PullError::InvalidUrl("failed to parse"))?;
+		let filter = AddressFilter::new(config);
+
+		let source = match url.scheme() {
+			"rtmp" | "rtmps" => Source::rtmp(&url, &filter, config.connect_timeout).await?,
+			"http" | "https" => {
+				let client = filter.http_client(config.connect_timeout)?;
+
+				if url.path().ends_with(".m3u8") {
+					Source::Hls(Box::new(HlsReader::new(client, filter, url, config.connect_timeout)))
+				} else {
+					filter.check_url(&url)?;
+					Source::HttpFlv(FlvReader::new(client.get(url).send().await?.error_for_status()?))
+				}
+			}
+			_ => return Err(PullError::InvalidUrl("unsupported scheme")),
+		};
+
+		let mut session = Self { source, first: None };
+
+		match tokio::time::timeout(config.connect_timeout, session.source.next()).await {
+			Ok(Ok(Data::Data(Some(data)))) => session.first = Some(data),
+			Ok(Ok(_)) => return Err(PullError::Closed),
+			Ok(Err(err)) => return Err(err),
+			Err(_) => return Err(PullError::Timeout),
+		}
+
+		Ok(session)
+	}
+}
+
+impl IngestSession for PullSession {
+	type Error = PullError;
+
+	const CONNECTION_ERROR: IngestError = IngestError::PullConnectionError;
+	const CONNECTION_TIMEOUT: IngestError = IngestError::PullConnectionTimeout;
+
+	async fn data(&mut self) -> Result<Data, PullError> {
+		if let Some(data) = self.first.take() {
+			return Ok(Data::Data(Some(data)));
+		}
+
+		self.source.next().await
+	}
+}
+
+impl Source {
+	async fn rtmp(url: &Url, filter: &AddressFilter, connect_timeout: Duration) -> Result<Self, PullError> {
+		let host = url.host_str().ok_or(PullError::InvalidUrl("missing host"))?;
+		let tls = url.scheme() == "rtmps";
+		let port = url.port().unwrap_or(if tls { 443 } else { 1935 });
+
+		// The first path segment is the app, everything after it (including the
+		// query) is the stream name.
+		let path = url.path().trim_start_matches('/');
+		let (app, stream_name) = path.split_once('/').ok_or(PullError::InvalidUrl("missing stream name"))?;
+		if app.is_empty() || stream_name.is_empty() {
+			return Err(PullError::InvalidUrl("missing app or stream name"));
+		}
+
+		let stream_name = match url.query() {
+			Some(query) => format!("{stream_name}?{query}"),
+			None => stream_name.to_string(),
+		};
+
+		let tc_url = format!("{}://{}/{}", url.scheme(), url_authority(host, url.port()), app);
+		let app = app.to_string();
+
+		let connect = async {
+			let addrs = filter.resolve(host, port).await?;
+			Ok::<_, PullError>(TcpStream::connect(&addrs[..]).await?)
+		};
+
+		let stream = tokio::time::timeout(connect_timeout, connect)
+			.await
+			.map_err(|_| PullError::Timeout)??;
+		stream.set_nodelay(true)?;
+
+		let (data_producer, data) = mpsc::channel(1);
+
+		let future: RtmpFuture = if tls {
+			let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+				.map_err(|_| PullError::InvalidUrl("invalid host"))?;
+
+			let stream = tokio::time::timeout(connect_timeout, tls_connector().connect(server_name, stream))
+				.await
+				.map_err(|_| PullError::Timeout)??;
+
+			Box::pin(async move {
+				ClientSession::new(stream, data_producer)
+					.play(&tc_url, &app, &stream_name)
+					.await
+			})
+		} else {
+			Box::pin(async move {
+				ClientSession::new(stream, data_producer)
+					.play(&tc_url, &app, &stream_name)
+					.await
+			})
+		};
+
+		Ok(Self::Rtmp {
+			future: Some(future),
+			data,
+		})
+	}
+
+	async fn next(&mut self) -> Result<Data, PullError> {
+		match self {
+			Self::Rtmp { future, data } => {
+				// The session future cannot be polled again once it has finished.
+				let Some(fut) = future.as_mut() else {
+					return Ok(Data::Closed(false));
+				};
+
+				// The data producer is dropped along with the session, so once the channel
+				// closes we wait for the result of the session.
+				let result = select! {
+					Some(d) = data.recv() => return Ok(Data::Data(Some(d))),
+					r = fut.as_mut() => r,
+				};
+
+				*future = None;
+				Ok(Data::Closed(result.map_err(PullError::Rtmp)?))
+			}
+			Self::HttpFlv(reader) => Ok(match reader.next().await? {
+				Some(data) => Data::Data(Some(data)),
+				None => Data::Closed(true),
+			}),
+			Self::Hls(reader) => Ok(match reader.next().await? {
+				Some(data) => Data::Data(Some(data)),
+				None => Data::Closed(true),
+			}),
+		}
+	}
+}
+
+/// Refuses addresses which are not public, unless they are in one of the
+/// allowed networks of the config. Source urls are set by users, so without
+/// this a room could pull from services inside our own network.
+#[derive(Clone)]
+struct AddressFilter {
+	allowed: Arc<[IpRange]>,
+}
+
+impl AddressFilter {
+	/// The allowed networks are validated when the puller starts.
+	fn new(config: &PullConfig) -> Self {
+		Self {
+			allowed: config
+				.allowed_networks
+				.iter()
+				.filter_map(|network| network.parse().ok())
+				.collect(),
+		}
+	}
+
+	fn allows(&self, ip: IpAddr) -> bool {
+		ip_range::is_public(ip) || self.allowed.iter().any(|range| range.contains(ip))
+	}
+
+	/// Resolve a host, keeping only the addresses we may connect to.
+	async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, PullError> {
+		let addrs: Vec<_> = tokio::net::lookup_host((host, port))
+			.await?
+			.filter(|addr| self.allows(addr.ip()))
+			.collect();
+
+		if addrs.is_empty() {
+			return Err(PullError::AddressNotAllowed);
+		}
+
+		Ok(addrs)
+	}
+
+	/// Check a url with an ip address as its host. The http client does not
+	/// resolve those, hosts given by name are checked when they are resolved.
+	fn check_url(&self, url: &Url) -> Result<(), PullError> {
+		let ip = match url.host() {
+			Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+			Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+			_ => return Ok(()),
+		};
+
+		if self.allows(ip) {
+			Ok(())
+		} else {
+			Err(PullError::AddressNotAllowed)
+		}
+	}
+
+	/// A http client which resolves hosts and follows redirects through the
+	/// filter. Proxies are disabled, as they would resolve the host for us.
+	fn http_client(&self, connect_timeout: Duration) -> Result<reqwest::Client, PullError> {
+		let filter = self.clone();
+
+		Ok(reqwest::Client::builder()
+			.connect_timeout(connect_timeout)
+			.no_proxy()
+			.dns_resolver(Arc::new(self.clone()))
+			.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+				match filter.check_url(attempt.url()) {
+					Ok(()) => reqwest::redirect::Policy::default().redirect(attempt),
+					Err(err) => attempt.error(err),
+				}
+			}))
+			.build()?)
+	}
+}
+
+impl reqwest::dns::Resolve for AddressFilter {
+	fn resolve(&self, name: hyper_014::client::connect::dns::Name) -> reqwest::dns::Resolving {
+		let filter = self.clone();
+
+		Box::pin(async move {
+			let addrs = filter.resolve(name.as_str(), 0).await?;
+			Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+		})
+	}
+}
+
+fn url_authority(host: &str, port: Option<u16>) -> String {
+	match port {
+		Some(port) => format!("{host}:{port}"),
+		None => host.to_string(),
+	}
+}
+
+fn tls_connector() -> tokio_rustls::TlsConnector {
+	let mut root_store = rustls::RootCertStore::empty();
+	root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+	tokio_rustls::TlsConnector::from(Arc::new(
+		rustls::ClientConfig::builder()
+			.with_root_certificates(root_store)
+			.with_no_client_auth(),
+	))
+}
+
+/// Reads FLV tags from a HTTP response as they arrive.
+struct FlvReader {
+	response: reqwest::Response,
+	buf: BytesMut,
+	header_read: bool,
+}
+
+impl FlvReader {
+	fn new(response: reqwest::Response) -> Self {
+		Self {
+			response,
+			buf: BytesMut::new(),
+			header_read: false,
+		}
+	}
+
+	/// Read more data from the response, returns false once the response has
+	/// ended.
+	async fn fill(&mut self) -> Result<bool, PullError> {
+		match self.response.chunk().await? {
+			Some(chunk) => {
+				self.buf.extend_from_slice(&chunk);
+				Ok(true)
+			}
+			None => Ok(false),
+		}
+	}
+
+	async fn next(&mut self) -> Result<Option<ChannelData>, PullError> {
+		loop {
+			if !self.header_read {
+				// FLV header followed by the first previous tag size
+				if self.buf.len() >= 9 {
+					if &self.buf[..3] != b"FLV" {
+						return Err(PullError::Flv("missing signature"));
+					}
+
+					let header_size = u32::from_be_bytes([self.buf[5], self.buf[6], self.buf[7], self.buf[8]]) as usize;
+					if header_size < 9 {
+						return Err(PullError::Flv("invalid header size"));
+					}
+
+					if self.buf.len() >= header_size + 4 {
+						self.buf.advance(header_size + 4);
+						self.header_read = true;
+						continue;
+					}
+				}
+			} else if self.buf.len() >= 11 {
+				let tag_type = self.buf[0];
+				let data_size = u32::from_be_bytes([0, self.buf[1], self.buf[2], self.buf[3]]) as usize;
+				let timestamp = u32::from_be_bytes([self.buf[7], self.buf[4], self.buf[5], self.buf[6]]);
+
+				if tag_type & 0x20 != 0 {
+					return Err(PullError::Flv("encrypted tags are not supported"));
+				}
+
+				if self.buf.len() >= 11 + data_size + 4 {
+					self.buf.advance(11);
+					let data = self.buf.split_to(data_size).freeze();
+					self.buf.advance(4);
+
+					match tag_type & 0x1F {
+						8 => return Ok(Some(ChannelData::Audio { timestamp, data })),
+						9 => return Ok(Some(ChannelData::Video { timestamp, data })),
+						18 => return Ok(Some(ChannelData::Metadata { timestamp, data })),
+						_ => continue,
+					}
+				}
+			}
+
+			if !self.fill().await? {
+				return Ok(None);
+			}
+		}
+	}
+}
+
+/// Reads a live HLS playlist with MPEG-TS segments, and plays it back in real
+/// time.
+struct HlsReader {
+	client: reqwest::Client,
+	filter: AddressFilter,
+	playlist_url: Url,
+	request_timeout: Duration,
+	segments: VecDeque<Url>,
+	next_sequence: Option<u64>,
+	next_reload: Instant,
+	ended: bool,
+	flushed: bool,
+	demuxer: TsDemuxer,
+	converter: FrameConverter,
+	pending: VecDeque<ChannelData>,
+	// The time we started playing at and the timestamp of the first frame
+	clock: Option<(Instant, u32)>,
+}
+
+struct MediaPlaylist {
+	target_duration: Duration,
+	media_sequence: u64,
+	segments: Vec<Url>,
+	ended: bool,
+}
+
+impl HlsReader {
+	fn new(client: reqwest::Client, filter: AddressFilter, playlist_url: Url, request_timeout: Duration) -> Self {
+		Self {
+			client,
+			filter,
+			playlist_url,
+			request_timeout,
+			segments: VecDeque::new(),
+			next_sequence: None,
+			next_reload: Instant::now(),
+			ended: false,
+			flushed: false,
+			demuxer: TsDemuxer::new(),
+			converter: FrameConverter::default(),
+			pending: VecDeque::new(),
+			clock: None,
+		}
+	}
+
+	async fn next(&mut self) -> Result<Option<ChannelData>, PullError> {
+		loop {
+			if let Some(data) = self.pending.pop_front() {
+				self.wait_for(data.timestamp()).await;
+				return Ok(Some(data));
+			}
+
+			if let Some(segment) = self.segments.pop_front() {
+				let data = self.fetch(segment).await?;
+				for frame in self.demuxer.demux(&data) {
+					self.converter.convert(frame, &mut self.pending)?;
+				}
+
+				continue;
+			}
+
+			if self.ended {
+				if self.flushed {
+					return Ok(None);
+				}
+
+				self.flushed = true;
+				for frame in self.demuxer.flush() {
+					self.converter.convert(frame, &mut self.pending)?;
+				}
+
+				continue;
+			}
+
+			tokio::time::sleep_until(self.next_reload).await;
+			self.reload().await?;
+		}
+	}
+
+	/// Wait until it is time to play a frame, this spreads the segment out so
+	/// that it looks like a live stream.
+	async fn wait_for(&mut self, timestamp: u32) {
+		let (start, base) = *self.clock.get_or_insert((Instant::now(), timestamp));
+		let deadline = start + Duration::from_millis(timestamp.saturating_sub(base) as u64);
+
+		if Instant::now() > deadline + HLS_MAX_LAG {
+			tracing::debug!("hls source fell behind, resetting clock");
+			self.clock = Some((Instant::now(), timestamp));
+		} else {
+			tokio::time::sleep_until(deadline).await;
+		}
+	}
+
+	/// Variant and segment urls come from the playlist, so they are checked
+	/// just like the source url.
+	async fn fetch(&self, url: Url) -> Result<Bytes, PullError> {
+		self.filter.check_url(&url)?;
+
+		Ok(self
+			.client
+			.get(url)
+			.timeout(self.request_timeout)
+			.send()
+			.await?
+			.error_for_status()?
+			.bytes()
+			.await?)
+	}
+
+	async fn fetch_playlist(&self) -> Result<String, PullError> {
+		let body = self.fetch(self.playlist_url.clone()).await?;
+		String::from_utf8(body.into()).map_err(|_| PullError::Playlist("not utf-8"))
+	}
+
+	async fn reload(&mut self) -> Result<(), PullError> {
+		let mut body = self.fetch_playlist().await?;
+
+		// If we were given a multivariant playlist, we pull the highest quality
+		// variant and let the transcoder produce the other renditions.
+		if let Some(variant) = best_variant(&self.playlist_url, &body)? {
+			self.playlist_url = variant;
+			body = self.fetch_playlist().await?;
+		}
+
+		let playlist = parse_media_playlist(&self.playlist_url, &body)?;
+
+		let first = playlist.media_sequence;
+		let last = first + playlist.segments.len() as u64;
+
+		let start = match self.next_sequence {
+			// If the playlist moved past us we skip ahead.
+			Some(next) if next <= last => next.max(first),
+			// The playlist has already ended, so we play all of it.
+			_ if playlist.ended => first,
+			// We either just started or the source restarted its media sequence.
+			_ => last.saturating_sub(HLS_LIVE_EDGE_SEGMENTS as u64).max(first),
+		};
+
+		let new_segments = last.saturating_sub(start);
+		self.segments
+			.extend(playlist.segments.into_iter().skip((start - first) as usize));
+		self.next_sequence = Some(start + new_segments);
+		self.ended = playlist.ended;
+
+		// RFC 8216 - 6.3.4: if the playlist has not changed we should wait half the
+		// target duration before trying again.
+		self.next_reload = Instant::now()
+			+ if new_segments > 0 {
+				playlist.target_duration
+			} else {
+				playlist.target_duration / 2
+			};
+
+		Ok(())
+	}
+}
+
+/// Pick the variant with the highest bandwidth from a multivariant playlist,
+/// returns `None` if the playlist is a media playlist.
+fn best_variant(base: &Url, body: &str) -> Result<Option<Url>, PullError> {
+	let mut best: Option<(u64, Url)> = None;
+	let mut lines = body.lines().map(str::trim);
+
+	while let Some(line) = lines.next() {
+		let Some(attributes) = line.strip_prefix("#EXT-X-STREAM-INF:") else {
+			continue;
+		};
+
+		let bandwidth = attributes
+			.split(',')
+			.find_map(|attr| attr.strip_prefix("BANDWIDTH="))
+			.and_then(|b| b.parse().ok())
+			.unwrap_or(0);
+
+		let uri = lines
+			.find(|line| !line.is_empty() && !line.starts_with('#'))
+			.ok_or(PullError::Playlist("variant without uri"))?;
+		let uri = base.join(uri).map_err(|_| PullError::Playlist("invalid variant uri"))?;
+
+		if !matches!(&best, Some((b, _)) if *b >= bandwidth) {
+			best = Some((bandwidth, uri));
+		}
+	}
+
+	Ok(best.map(|(_, uri)| uri))
+}
+
+/// RFC 8216 - 4.3.3
+fn parse_media_playlist(base: &Url, body: &str) -> Result<MediaPlaylist, PullError> {
+	let mut lines = body.lines().map(str::trim).filter(|line| !line.is_empty());
+
+	if lines.next() != Some("#EXTM3U") {
+		return Err(PullError::Playlist("missing #EXTM3U"));
+	}
+
+	let mut playlist = MediaPlaylist {
+		target_duration: Duration::ZERO,
+		media_sequence: 0,
+		segments: Vec::new(),
+		ended: false,
+	};
+
+	for line in lines {
+		if let Some(duration) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+			let duration: u64 = duration.parse().map_err(|_| PullError::Playlist("invalid target duration"))?;
+			playlist.target_duration = Duration::from_secs(duration);
+		} else if let Some(sequence) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+			playlist.media_sequence = sequence.parse().map_err(|_| PullError::Playlist("invalid media sequence"))?;
+		} else if line == "#EXT-X-ENDLIST" {
+			playlist.ended = true;
+		} else if line.starts_with("#EXT-X-MAP:") {
+			return Err(PullError::Playlist("only MPEG-TS segments are supported"));
+		} else if let Some(key) = line.strip_prefix("#EXT-X-KEY:") {
+			if !key.split(',').any(|attr| attr == "METHOD=NONE") {
+				return Err(PullError::Playlist("encrypted segments are not supported"));
+			}
+		} else if !line.starts_with('#') {
+			let uri = base.join(line).map_err(|_| PullError::Playlist("invalid segment uri"))?;
+			playlist.segments.push(uri);
+		}
+	}
+
+	if playlist.target_duration.is_zero() {
+		return Err(PullError::Playlist("missing target duration"));
+	}
+
+	Ok(playlist)
+}
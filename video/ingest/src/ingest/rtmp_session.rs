@@ -1,24 +1,34 @@
 use std::pin::Pin;
+use std::time::Duration;
 
 use futures_util::Future;
 use rtmp::{ChannelData, PublishRequest, SessionError};
 use tokio::select;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+
+use super::errors::IngestError;
+use super::session::{Data, IngestSession};
 
 pub struct RtmpSession<'a, F> {
 	future: Pin<&'a mut F>,
 	publish: mpsc::Receiver<PublishRequest>,
 	data: mpsc::Receiver<ChannelData>,
-}
-
-pub enum Data {
-	Data(Option<ChannelData>),
-	Closed(bool),
+	rtt: watch::Receiver<Option<Duration>>,
 }
 
 impl<'a, F: Future<Output = Result<bool, SessionError>>> RtmpSession<'a, F> {
-	pub fn new(future: Pin<&'a mut F>, publish: mpsc::Receiver<PublishRequest>, data: mpsc::Receiver<ChannelData>) -> Self {
-		Self { future, publish, data }
+	pub fn new(
+		future: Pin<&'a mut F>,
+		publish: mpsc::Receiver<PublishRequest>,
+		data: mpsc::Receiver<ChannelData>,
+		rtt: watch::Receiver<Option<Duration>>,
+	) -> Self {
+		Self {
+			future,
+			publish,
+			data,
+			rtt,
+		}
 	}
 
 	pub async fn publish(&mut self) -> Result<Option<PublishRequest>, SessionError> {
@@ -27,11 +37,22 @@ impl<'a, F: Future<Output = Result<bool, SessionError>>> RtmpSession<'a, F> {
 			publish = self.publish.recv() => Ok(publish),
 		}
 	}
+}
 
-	pub async fn data(&mut self) -> Result<Data, SessionError> {
+impl<'a, F: Future<Output = Result<bool, SessionError>> + Send> IngestSession for RtmpSession<'a, F> {
+	type Error = SessionError;
+
+	const CONNECTION_ERROR: IngestError = IngestError::RtmpConnectionError;
+	const CONNECTION_TIMEOUT: IngestError = IngestError::RtmpConnectionTimeout;
+
+	async fn data(&mut self) -> Result<Data, SessionError> {
 		select! {
 			r = self.future.as_mut() => Ok(r.map(Data::Closed)?),
 			data = self.data.recv() => Ok(Data::Data(data)),
 		}
 	}
+
+	fn rtt(&self) -> Option<Duration> {
+		*self.rtt.borrow()
+	}
 }
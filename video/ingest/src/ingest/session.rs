@@ -0,0 +1,31 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use futures::Future;
+use rtmp::ChannelData;
+
+use super::errors::IngestError;
+
+pub enum Data {
+	Data(Option<ChannelData>),
+	Closed(bool),
+}
+
+/// A publishing session which a connection reads media from.
+/// Data is always given in the same format as RTMP, so other protocols
+/// convert to it.
+pub trait IngestSession {
+	type Error: Display;
+
+	/// The error to report when the session fails.
+	const CONNECTION_ERROR: IngestError;
+	/// The error to report when the session stops sending data.
+	const CONNECTION_TIMEOUT: IngestError;
+
+	fn data(&mut self) -> impl Future<Output = Result<Data, Self::Error>> + Send;
+
+	/// The round trip time to the publisher, if the protocol measures it.
+	fn rtt(&self) -> Option<Duration> {
+		None
+	}
+}
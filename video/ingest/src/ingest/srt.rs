@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::StreamExt;
+use srt_tokio::access::{AccessControlList, ConnectionMode, RejectReason, ServerRejectReason, StandardAccessControlEntry};
+use srt_tokio::SrtListener;
+use utils::context::ContextExt;
+
+use super::connection;
+use crate::config::SrtConfig;
+use crate::global::IngestGlobal;
+
+pub async fn run<G: IngestGlobal>(global: Arc<G>, config: &SrtConfig) -> Result<()> {
+	tracing::info!("Ingest(SRT) listening on {}", config.bind_address);
+
+	let (_listener, mut incoming) = SrtListener::builder()
+		.latency(config.latency)
+		.bind(config.bind_address)
+		.await?;
+
+	while let Ok(Some(request)) = incoming.incoming().next().context(global.ctx()).await {
+		tracing::debug!("Accepted connection from {}", request.remote());
+
+		let Some(stream_name) = request.stream_id().and_then(|id| stream_name(id)) else {
+			tracing::debug!("rejecting connection with invalid stream id");
+			request
+				.reject(RejectReason::Server(ServerRejectReason::BadRequest))
+				.await
+				.ok();
+			continue;
+		};
+
+		tokio::spawn(connection::handle_srt(global.clone(), request, stream_name));
+	}
+
+	Ok(())
+}
+
+/// Get the stream name (which is the same as the RTMP stream key) from an SRT
+/// stream id. Either the stream name itself or the access control syntax
+/// `#!::r=<stream name>,m=publish` can be used.
+fn stream_name(stream_id: &str) -> Option<String> {
+	if !stream_id.starts_with("#!::") {
+		return Some(stream_id.to_string());
+	}
+
+	let mut resource = None;
+
+	for entry in stream_id.parse::<AccessControlList>().ok()?.0 {
+		match entry.try_into() {
+			Ok(StandardAccessControlEntry::ResourceName(name)) => resource = Some(name),
+			Ok(StandardAccessControlEntry::Mode(mode)) if mode != ConnectionMode::Publish => return None,
+			_ => {}
+		}
+	}
+
+	resource
+}
@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+use std::io;
+
+use futures::StreamExt;
+use mpegts::{TsDemuxer, TsFrame};
+use rtmp::ChannelData;
+use srt_tokio::SrtSocket;
+
+use super::errors::IngestError;
+use super::session::{Data, IngestSession};
+use super::ts::FrameConverter;
+
+pub struct SrtSession {
+	socket: SrtSocket,
+	demuxer: TsDemuxer,
+	converter: FrameConverter,
+	pending: VecDeque<ChannelData>,
+	closed: bool,
+}
+
+impl SrtSession {
+	pub fn new(socket: SrtSocket) -> Self {
+		Self {
+			socket,
+			demuxer: TsDemuxer::new(),
+			converter: FrameConverter::default(),
+			pending: VecDeque::new(),
+			closed: false,
+		}
+	}
+
+	fn convert(&mut self, frames: Vec<TsFrame>) -> io::Result<()> {
+		for frame in frames {
+			self.converter.convert(frame, &mut self.pending)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl IngestSession for SrtSession {
+	type Error = io::Error;
+
+	const CONNECTION_ERROR: IngestError = IngestError::SrtConnectionError;
+	const CONNECTION_TIMEOUT: IngestError = IngestError::SrtConnectionTimeout;
+
+	async fn data(&mut self) -> io::Result<Data> {
+		loop {
+			if let Some(data) = self.pending.pop_front() {
+				return Ok(Data::Data(Some(data)));
+			}
+
+			if self.closed {
+				return Ok(Data::Closed(true));
+			}
+
+			match self.socket.next().await {
+				Some(Ok((_, data))) => {
+					let frames = self.demuxer.demux(&data);
+					self.convert(frames)?;
+				}
+				Some(Err(err)) => return Err(err),
+				None => {
+					// The caller closed the connection, so whatever is still buffered is
+					// complete.
+					self.closed = true;
+					let frames = self.demuxer.flush();
+					self.convert(frames)?;
+				}
+			}
+		}
+	}
+}
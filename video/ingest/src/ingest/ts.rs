@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+use std::io;
+
+use bytes::BytesMut;
+use mpegts::{StreamType, TsFrame, TS_TIMESCALE};
+use rtmp::ChannelData;
+
+use super::avc::AvcConverter;
+
+/// PTS and DTS values are 33 bits and wrap around.
+const TIMESTAMP_MASK: u64 = (1 << 33) - 1;
+
+/// Converts transport stream frames into FLV tag bodies, so that they can be
+/// handled the same way as RTMP data.
+#[derive(Default)]
+pub struct FrameConverter {
+	/// The last decode timestamp we saw, as it was sent.
+	last_decode_time: Option<u64>,
+	/// The decode time of the current frame relative to the first frame,
+	/// unwrapped so it keeps counting past the 33 bit wrap.
+	decode_time: i64,
+	avc: AvcConverter,
+	audio_config: Option<[u8; 2]>,
+}
+
+impl FrameConverter {
+	pub fn convert(&mut self, frame: TsFrame, out: &mut VecDeque<ChannelData>) -> io::Result<()> {
+		let Some(decode_time) = frame.decode_time() else {
+			return Ok(());
+		};
+
+		if let Some(last_decode_time) = self.last_decode_time.replace(decode_time) {
+			self.decode_time += timestamp_diff(decode_time, last_decode_time);
+		}
+
+		let timestamp = to_millis(self.decode_time);
+
+		match frame.stream_type {
+			StreamType::H264 => {
+				let composition_time = to_millis(frame.pts.map_or(0, |pts| timestamp_diff(pts, decode_time)));
+				self.avc.convert(frame.data, timestamp, composition_time, out)
+			}
+			StreamType::Aac => {
+				self.convert_aac(&frame.data, self.decode_time, out);
+				Ok(())
+			}
+			// We only support H.264 and AAC, other streams are ignored.
+			_ => Ok(()),
+		}
+	}
+
+	fn convert_aac(&mut self, mut data: &[u8], time: i64, out: &mut VecDeque<ChannelData>) {
+		// A single PES packet can contain multiple ADTS frames, each one is 1024
+		// samples long.
+		let mut frame_idx = 0;
+
+		while !data.is_empty() {
+			// Without a valid header we cannot find the next frame, so the rest of
+			// the packet is dropped.
+			let frame = match AdtsFrame::parse(data) {
+				Ok(frame) => frame,
+				Err(err) => {
+					tracing::debug!(error = %err, "skipping malformed ADTS frame");
+					break;
+				}
+			};
+			data = &data[frame.length..];
+
+			let config = frame.audio_specific_config();
+			if self.audio_config != Some(config) {
+				let mut body = BytesMut::with_capacity(4);
+				body.extend_from_slice(&[0xAF, 0x00]);
+				body.extend_from_slice(&config);
+
+				out.push_back(ChannelData::Audio {
+					timestamp: to_millis(time),
+					data: body.freeze(),
+				});
+
+				self.audio_config = Some(config);
+			}
+
+			let offset = frame_idx * 1024 * TS_TIMESCALE as i64 / frame.sample_rate as i64;
+
+			let mut body = BytesMut::with_capacity(2 + frame.payload.len());
+			body.extend_from_slice(&[0xAF, 0x01]);
+			body.extend_from_slice(frame.payload);
+
+			out.push_back(ChannelData::Audio {
+				timestamp: to_millis(time + offset),
+				data: body.freeze(),
+			});
+
+			frame_idx += 1;
+		}
+	}
+}
+
+/// The signed difference `a - b` between two 90kHz timestamps, assuming they
+/// are less than half the 33 bit range apart.
+fn timestamp_diff(a: u64, b: u64) -> i64 {
+	let diff = a.wrapping_sub(b) & TIMESTAMP_MASK;
+	if diff > TIMESTAMP_MASK / 2 {
+		diff as i64 - (TIMESTAMP_MASK as i64 + 1)
+	} else {
+		diff as i64
+	}
+}
+
+/// Convert a duration in 90kHz ticks to milliseconds.
+/// Frames slightly before the first one we saw (such as audio which starts
+/// before the first video frame) are clamped to 0.
+fn to_millis(ticks: i64) -> u32 {
+	(ticks.max(0) as u64 * 1000 / TS_TIMESCALE as u64) as u32
+}
+
+/// ISO/IEC 13818-7 - 6.2
+struct AdtsFrame<'a> {
+	audio_object_type: u8,
+	sampling_frequency_index: u8,
+	sample_rate: u32,
+	channel_configuration: u8,
+	length: usize,
+	payload: &'a [u8],
+}
+
+impl<'a> AdtsFrame<'a> {
+	fn parse(data: &'a [u8]) -> io::Result<Self> {
+		let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+		if data.len() < 7 || data[0] != 0xFF || data[1] & 0xF0 != 0xF0 {
+			return Err(invalid("missing ADTS syncword"));
+		}
+
+		let protection_absent = data[1] & 0x01 != 0;
+		let audio_object_type = (data[2] >> 6) + 1;
+		let sampling_frequency_index = (data[2] >> 2) & 0x0F;
+		let channel_configuration = ((data[2] & 0x01) << 2) | (data[3] >> 6);
+		let length = (((data[3] & 0x03) as usize) << 11) | ((data[4] as usize) << 3) | ((data[5] as usize) >> 5);
+		let header_length = if protection_absent { 7 } else { 9 };
+
+		let sample_rate = match sampling_frequency_index {
+			0 => 96000,
+			1 => 88200,
+			2 => 64000,
+			3 => 48000,
+			4 => 44100,
+			5 => 32000,
+			6 => 24000,
+			7 => 22050,
+			8 => 16000,
+			9 => 12000,
+			10 => 11025,
+			11 => 8000,
+			12 => 7350,
+			_ => return Err(invalid("invalid ADTS sampling frequency index")),
+		};
+
+		if length < header_length || length > data.len() {
+			return Err(invalid("invalid ADTS frame length"));
+		}
+
+		Ok(Self {
+			audio_object_type,
+			sampling_frequency_index,
+			sample_rate,
+			channel_configuration,
+			length,
+			payload: &data[header_length..length],
+		})
+	}
+
+	/// ISO/IEC 14496-3:2019(E) - 1.6.2.1
+	fn audio_specific_config(&self) -> [u8; 2] {
+		let config = (self.audio_object_type as u16) << 11
+			| (self.sampling_frequency_index as u16) << 7
+			| (self.channel_configuration as u16) << 3;
+
+		config.to_be_bytes()
+	}
+}
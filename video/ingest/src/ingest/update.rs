@@ -1,6 +1,8 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use pb::scuffle::video::v1::types::IngestHealth;
+use prost::Message as _;
 use tokio::sync::mpsc;
 use ulid::Ulid;
 use utils::prelude::FutureTimeout;
@@ -8,12 +10,13 @@ use utils::prelude::FutureTimeout;
 use crate::global::IngestGlobal;
 
 pub struct Update {
+	pub connection_id: Ulid,
 	pub bitrate: i64,
+	pub health: IngestHealth,
 }
 
 pub async fn update_db<G: IngestGlobal>(
 	global: Arc<G>,
-	id: Ulid,
 	organization_id: Ulid,
 	room_id: Ulid,
 	mut update_reciever: mpsc::Receiver<Update>,
@@ -27,17 +30,19 @@ pub async fn update_db<G: IngestGlobal>(
                 UPDATE rooms
                 SET
                     updated_at = NOW(),
-                    ingest_bitrate = $1
+                    ingest_bitrate = $1,
+                    ingest_health = $2
                 WHERE
-                    organization_id = $2 AND
-                    id = $3 AND
-                    active_ingest_connection_id = $4
+                    organization_id = $3 AND
+                    id = $4 AND
+                    active_ingest_connection_id = $5
                 "#,
 			)
 			.bind(update.bitrate)
+			.bind(update.health.encode_to_vec())
 			.bind(organization_id)
 			.bind(room_id)
-			.bind(id)
+			.bind(update.connection_id)
 			.build()
 			.execute(global.db())
 			.timeout(Duration::from_secs(3))
@@ -0,0 +1,288 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use async_nats::RequestErrorKind;
+use binary_helper::global::RequestGlobalExt;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::header;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpSocket;
+use tokio::sync::oneshot;
+use ulid::Ulid;
+use utils::context::ContextExt;
+use utils::http::ext::*;
+use utils::http::router::ext::RequestExt;
+use utils::http::router::middleware::{CorsMiddleware, CorsOptions};
+use utils::http::router::Router;
+use utils::http::RouteError;
+use utils::prelude::FutureTimeout;
+use video_common::keys;
+
+use super::connection;
+use crate::config::WhipConfig;
+use crate::global::IngestGlobal;
+
+type Body = Full<Bytes>;
+
+type RouteResult<T> = std::result::Result<T, RouteError<WhipError>>;
+
+/// SDP offers from browsers are a few kilobytes, anything much larger is not
+/// an offer.
+const MAX_OFFER_SIZE: usize = 64 * 1024;
+
+/// The replies to an unpublish request, sent by the node running the session.
+const UNPUBLISH_OK: &[u8] = b"ok";
+const UNPUBLISH_FORBIDDEN: &[u8] = b"forbidden";
+
+#[derive(thiserror::Error, Debug)]
+pub enum WhipError {
+	#[error("failed to negotiate session: {0}")]
+	Negotiation(#[from] webrtc::Error),
+	#[error("nats error: {0}")]
+	Nats(#[from] async_nats::PublishError),
+	#[error("nats request error: {0}")]
+	NatsRequest(#[from] async_nats::RequestError),
+}
+
+/// Why a WHIP session could not be started.
+#[derive(Debug)]
+pub enum WhipReject {
+	Unauthorized,
+	TooManyRequests,
+	Negotiation(webrtc::Error),
+	InternalServerError,
+}
+
+pub type WhipResponse = std::result::Result<(Ulid, String), WhipReject>;
+
+pub fn routes<G: IngestGlobal>(global: &Arc<G>) -> Router<Incoming, Body, RouteError<WhipError>> {
+	Router::builder()
+		.data(Arc::downgrade(global))
+		.middleware(CorsMiddleware::new(&CorsOptions::wildcard()))
+		.error_handler(utils::http::error_handler::<WhipError, _>)
+		.post("/whip", publish::<G>)
+		.delete("/whip/:connection_id", unpublish::<G>)
+		.not_found(|_| async move { Err((StatusCode::NOT_FOUND, "not found").into()) })
+		.build()
+}
+
+/// Start a session, the stream key is given as a bearer token and the body is
+/// the SDP offer.
+/// RFC 9725 - 4.2
+async fn publish<G: IngestGlobal>(req: Request<Incoming>) -> RouteResult<Response<Body>> {
+	let global = req.get_global::<G, _>()?;
+
+	let ip = req.extensions().get::<SocketAddr>().expect("missing remote address").ip();
+
+	let params = req
+		.uri()
+		.query()
+		.map(|query| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+		.unwrap_or_default();
+
+	if req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) != Some("application/sdp") {
+		return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, "content type must be application/sdp").into());
+	}
+
+	let stream_name = bearer_token(&req).map_err_route((StatusCode::UNAUTHORIZED, "missing bearer token"))?;
+
+	let offer = Limited::new(req.into_body(), MAX_OFFER_SIZE)
+		.collect()
+		.await
+		.map_ignore_err_route((StatusCode::BAD_REQUEST, "failed to read body"))?
+		.to_bytes();
+
+	let offer = String::from_utf8(offer.to_vec()).map_ignore_err_route((StatusCode::BAD_REQUEST, "invalid sdp offer"))?;
+
+	let (response, recv) = oneshot::channel();
+	tokio::spawn(connection::handle_whip(
+		global.clone(),
+		stream_name,
+		ip,
+		params,
+		offer,
+		response,
+	));
+
+	let (connection_id, answer) = match recv.await {
+		Ok(Ok(r)) => r,
+		Ok(Err(WhipReject::Unauthorized)) => return Err((StatusCode::UNAUTHORIZED, "invalid stream key").into()),
+		Ok(Err(WhipReject::TooManyRequests)) => return Err((StatusCode::TOO_MANY_REQUESTS, "too many connections").into()),
+		Ok(Err(WhipReject::Negotiation(err))) => {
+			return Err((StatusCode::BAD_REQUEST, "failed to negotiate session", err).into());
+		}
+		Ok(Err(WhipReject::InternalServerError)) | Err(_) => {
+			return Err((StatusCode::INTERNAL_SERVER_ERROR, "failed to create session").into());
+		}
+	};
+
+	Ok(Response::builder()
+		.status(StatusCode::CREATED)
+		.header(header::CONTENT_TYPE, "application/sdp")
+		.header(header::LOCATION, format!("/whip/{connection_id}"))
+		.body(Bytes::from(answer).into())
+		.expect("failed to build response"))
+}
+
+/// End a session, the connection id is the one given in the location header
+/// and the stream key the session was published with is given as a bearer
+/// token.
+/// RFC 9725 - 4.2
+async fn unpublish<G: IngestGlobal>(req: Request<Incoming>) -> RouteResult<Response<Body>> {
+	let global = req.get_global::<G, _>()?;
+
+	let connection_id = Ulid::from_string(req.param("connection_id").unwrap())
+		.map_ignore_err_route((StatusCode::BAD_REQUEST, "invalid connection_id"))?;
+
+	let stream_name = bearer_token(&req).map_err_route((StatusCode::UNAUTHORIZED, "missing bearer token"))?;
+
+	// The session can be running on any ingest node, the one running it checks
+	// the stream key and disconnects it.
+	let reply = match global
+		.nats()
+		.request(keys::ingest_whip_unpublish(connection_id), stream_name.into())
+		.await
+	{
+		Ok(reply) => reply,
+		Err(err) if err.kind() == RequestErrorKind::NoResponders => {
+			return Err((StatusCode::NOT_FOUND, "session not found").into());
+		}
+		Err(err) => {
+			return Err((StatusCode::INTERNAL_SERVER_ERROR, "failed to disconnect session", err).into());
+		}
+	};
+
+	match reply.payload.as_ref() {
+		UNPUBLISH_OK => Ok(Response::builder()
+			.status(StatusCode::OK)
+			.body(Body::default())
+			.expect("failed to build response")),
+		UNPUBLISH_FORBIDDEN => Err((StatusCode::FORBIDDEN, "stream key does not match the session").into()),
+		_ => Err((StatusCode::INTERNAL_SERVER_ERROR, "failed to disconnect session").into()),
+	}
+}
+
+fn bearer_token(req: &Request<Incoming>) -> Option<String> {
+	req.headers()
+		.get(header::AUTHORIZATION)
+		.and_then(|v| v.to_str().ok())
+		.and_then(|v| v.strip_prefix("Bearer "))
+		.map(|v| v.trim().to_string())
+}
+
+/// Answers the unpublish requests for a session while it runs. Only a request
+/// with the stream key the session was published with disconnects it.
+pub async fn serve_unpublish<G: IngestGlobal>(
+	global: Arc<G>,
+	connection_id: Ulid,
+	stream_name: String,
+	mut sub: async_nats::Subscriber,
+) {
+	while let Some(msg) = sub.next().await {
+		let Some(reply) = msg.reply else {
+			continue;
+		};
+
+		let payload = if constant_time_eq(&msg.payload, stream_name.as_bytes()) {
+			match global
+				.nats()
+				.publish(keys::ingest_disconnect(connection_id), Bytes::new())
+				.await
+			{
+				Ok(()) => UNPUBLISH_OK,
+				Err(err) => {
+					tracing::error!(error = %err, "failed to disconnect whip session");
+					b"".as_slice()
+				}
+			}
+		} else {
+			UNPUBLISH_FORBIDDEN
+		};
+
+		if let Err(err) = global.nats().publish(reply, Bytes::from_static(payload)).await {
+			tracing::warn!(error = %err, "failed to reply to unpublish request");
+		}
+	}
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+pub async fn run<G: IngestGlobal>(global: Arc<G>, config: &WhipConfig) -> Result<()> {
+	tracing::info!("Ingest(WHIP) listening on {}", config.bind_address);
+	let socket = if config.bind_address.is_ipv6() {
+		TcpSocket::new_v6()?
+	} else {
+		TcpSocket::new_v4()?
+	};
+
+	socket.set_reuseaddr(true)?;
+	socket.set_reuseport(true)?;
+	socket.bind(config.bind_address)?;
+	let listener = socket.listen(1024)?;
+
+	let tls_acceptor = if let Some(tls) = &config.tls {
+		tracing::info!("TLS enabled");
+		let cert = tokio::fs::read(&tls.cert).await.context("failed to read whip cert")?;
+		let key = tokio::fs::read(&tls.key).await.context("failed to read whip private key")?;
+
+		let key = rustls_pemfile::pkcs8_private_keys(&mut io::BufReader::new(io::Cursor::new(key)))
+			.next()
+			.ok_or_else(|| anyhow::anyhow!("failed to find private key in whip private key file"))??;
+
+		let certs = rustls_pemfile::certs(&mut io::BufReader::new(io::Cursor::new(cert))).collect::<Result<Vec<_>, _>>()?;
+
+		Some(Arc::new(tokio_rustls::TlsAcceptor::from(Arc::new(
+			rustls::ServerConfig::builder()
+				.with_no_client_auth()
+				.with_single_cert(certs, key.into())?,
+		))))
+	} else {
+		None
+	};
+
+	// The router only holds a weak reference to the global state, so that open
+	// keep-alive connections do not block shutdown.
+	let router = Arc::new(routes(&global));
+
+	while let Ok(r) = listener.accept().context(global.ctx()).await {
+		let (socket, addr) = r?;
+
+		let router = router.clone();
+		let service = service_fn(move |mut req| {
+			req.extensions_mut().insert(addr);
+			let this = router.clone();
+			async move { this.handle(req).await }
+		});
+
+		let tls_acceptor = tls_acceptor.clone();
+
+		tracing::debug!("Accepted connection from {}", addr);
+
+		tokio::spawn(async move {
+			let http = http1::Builder::new();
+
+			if let Some(tls_acceptor) = tls_acceptor {
+				let Ok(Ok(socket)) = tls_acceptor.accept(socket).timeout(Duration::from_secs(5)).await else {
+					return;
+				};
+				tracing::debug!("TLS handshake complete");
+				http.serve_connection(TokioIo::new(socket), service).await.ok();
+			} else {
+				http.serve_connection(TokioIo::new(socket), service).await.ok();
+			}
+		});
+	}
+
+	Ok(())
+}
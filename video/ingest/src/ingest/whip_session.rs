@@ -0,0 +1,400 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use rtmp::ChannelData;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::{MediaEngine, MIME_TYPE_H264, MIME_TYPE_OPUS};
+use webrtc::api::APIBuilder;
+use webrtc::ice_transport::ice_server::RTCIceServer;
+use webrtc::interceptor::registry::Registry;
+use webrtc::media::io::sample_builder::SampleBuilder;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
+use webrtc::rtp::codecs::h264::H264Packet;
+use webrtc::rtp::codecs::opus::OpusPacket;
+use webrtc::rtp_transceiver::rtp_codec::{RTCRtpCodecCapability, RTCRtpCodecParameters, RTPCodecType};
+use webrtc::rtp_transceiver::RTCPFeedback;
+use webrtc::track::track_remote::TrackRemote;
+
+use super::avc::AvcConverter;
+use super::errors::IngestError;
+use super::session::{Data, IngestSession};
+use crate::config::WhipConfig;
+
+/// How many packets the sample builders hold on to, so that packets which
+/// arrive out of order (or are retransmitted) can still be used.
+const MAX_LATE_PACKETS: u16 = 256;
+
+/// Opus is always decoded at 48kHz, regardless of the input sample rate.
+const OPUS_SAMPLE_RATE: u32 = 48000;
+
+type Message = Result<Data, webrtc::Error>;
+
+pub struct WhipSession {
+	peer_connection: Arc<RTCPeerConnection>,
+	data: mpsc::Receiver<Message>,
+}
+
+impl WhipSession {
+	/// Negotiate a session from an SDP offer, returning the session and the
+	/// SDP answer. Trickle ICE is not supported, so the answer contains all of
+	/// our candidates.
+	pub async fn new(config: &WhipConfig, offer: String) -> Result<(Self, String), webrtc::Error> {
+		let mut media_engine = MediaEngine::default();
+		register_codecs(&mut media_engine)?;
+
+		let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+
+		let api = APIBuilder::new()
+			.with_media_engine(media_engine)
+			.with_interceptor_registry(registry)
+			.build();
+
+		let peer_connection = Arc::new(
+			api.new_peer_connection(RTCConfiguration {
+				ice_servers: config
+					.ice_servers
+					.iter()
+					.map(|url| RTCIceServer {
+						urls: vec![url.clone()],
+						..Default::default()
+					})
+					.collect(),
+				..Default::default()
+			})
+			.await?,
+		);
+
+		let (sender, data) = mpsc::channel(64);
+
+		// The session is created before negotiating so that the peer connection is
+		// closed if anything fails.
+		let session = Self { peer_connection, data };
+
+		let start = Instant::now();
+		let keyframe_request_interval = config.keyframe_request_interval;
+		let weak = Arc::downgrade(&session.peer_connection);
+
+		let track_sender = sender.clone();
+		session.peer_connection.on_track(Box::new(move |track, _, _| {
+			tokio::spawn(read_track(
+				track,
+				track_sender.clone(),
+				weak.clone(),
+				start,
+				keyframe_request_interval,
+			));
+
+			Box::pin(async {})
+		}));
+
+		session
+			.peer_connection
+			.on_peer_connection_state_change(Box::new(move |state| {
+				let sender = sender.clone();
+				Box::pin(async move {
+					let message = match state {
+						RTCPeerConnectionState::Failed => Err(webrtc::Error::new("peer connection failed".to_string())),
+						RTCPeerConnectionState::Closed => Ok(Data::Closed(true)),
+						_ => return,
+					};
+
+					sender.send(message).await.ok();
+				})
+			}));
+
+		session
+			.peer_connection
+			.set_remote_description(RTCSessionDescription::offer(offer)?)
+			.await?;
+
+		let answer = session.peer_connection.create_answer(None).await?;
+		let mut gathering_complete = session.peer_connection.gathering_complete_promise().await;
+		session.peer_connection.set_local_description(answer).await?;
+		gathering_complete.recv().await;
+
+		let answer = session
+			.peer_connection
+			.local_description()
+			.await
+			.ok_or_else(|| webrtc::Error::new("missing local description".to_string()))?;
+
+		Ok((session, answer.sdp))
+	}
+}
+
+impl Drop for WhipSession {
+	fn drop(&mut self) {
+		let peer_connection = self.peer_connection.clone();
+		tokio::spawn(async move {
+			peer_connection.close().await.ok();
+		});
+	}
+}
+
+impl IngestSession for WhipSession {
+	type Error = webrtc::Error;
+
+	const CONNECTION_ERROR: IngestError = IngestError::WhipConnectionError;
+	const CONNECTION_TIMEOUT: IngestError = IngestError::WhipConnectionTimeout;
+
+	async fn data(&mut self) -> Result<Data, webrtc::Error> {
+		// The sender is held by the peer connection, so this only ends once the
+		// peer connection is gone.
+		self.data.recv().await.unwrap_or(Ok(Data::Closed(false)))
+	}
+}
+
+/// We only accept H.264 and Opus, since those are the codecs we can put into
+/// the same fMP4 segments as RTMP.
+fn register_codecs(media_engine: &mut MediaEngine) -> Result<(), webrtc::Error> {
+	let video_rtcp_feedback = vec![
+		RTCPFeedback {
+			typ: "goog-remb".to_string(),
+			parameter: "".to_string(),
+		},
+		RTCPFeedback {
+			typ: "ccm".to_string(),
+			parameter: "fir".to_string(),
+		},
+		RTCPFeedback {
+			typ: "nack".to_string(),
+			parameter: "".to_string(),
+		},
+		RTCPFeedback {
+			typ: "nack".to_string(),
+			parameter: "pli".to_string(),
+		},
+	];
+
+	// Constrained baseline, baseline, main and high profiles.
+	for (payload_type, profile_level_id) in [(102, "42e01f"), (104, "42001f"), (106, "4d001f"), (108, "64001f")] {
+		media_engine.register_codec(
+			RTCRtpCodecParameters {
+				capability: RTCRtpCodecCapability {
+					mime_type: MIME_TYPE_H264.to_string(),
+					clock_rate: 90000,
+					channels: 0,
+					sdp_fmtp_line: format!(
+						"level-asymmetry-allowed=1;packetization-mode=1;profile-level-id={profile_level_id}"
+					),
+					rtcp_feedback: video_rtcp_feedback.clone(),
+				},
+				payload_type,
+				..Default::default()
+			},
+			RTPCodecType::Video,
+		)?;
+	}
+
+	media_engine.register_codec(
+		RTCRtpCodecParameters {
+			capability: RTCRtpCodecCapability {
+				mime_type: MIME_TYPE_OPUS.to_string(),
+				clock_rate: OPUS_SAMPLE_RATE,
+				channels: 2,
+				sdp_fmtp_line: "minptime=10;useinbandfec=1".to_string(),
+				rtcp_feedback: vec![],
+			},
+			payload_type: 111,
+			..Default::default()
+		},
+		RTPCodecType::Audio,
+	)?;
+
+	Ok(())
+}
+
+async fn read_track(
+	track: Arc<TrackRemote>,
+	sender: mpsc::Sender<Message>,
+	peer_connection: Weak<RTCPeerConnection>,
+	start: Instant,
+	keyframe_request_interval: Duration,
+) {
+	let codec = track.codec().capability;
+	let clock = TrackClock::new(start, codec.clock_rate);
+
+	let result = if codec.mime_type.eq_ignore_ascii_case(MIME_TYPE_H264) {
+		tokio::spawn(request_keyframes(peer_connection, track.ssrc(), keyframe_request_interval));
+		read_video(&track, &sender, clock).await
+	} else if codec.mime_type.eq_ignore_ascii_case(MIME_TYPE_OPUS) {
+		read_audio(&track, &sender, clock, codec.channels as u8).await
+	} else {
+		return;
+	};
+
+	if let Err(err) = result {
+		sender.send(Err(err)).await.ok();
+	}
+}
+
+/// Browsers only send keyframes when they start or are asked for one, so we
+/// ask regularly to keep the keyframe interval within the ingest limits.
+async fn request_keyframes(peer_connection: Weak<RTCPeerConnection>, media_ssrc: u32, interval: Duration) {
+	let mut interval = tokio::time::interval(interval);
+
+	loop {
+		interval.tick().await;
+
+		let Some(peer_connection) = peer_connection.upgrade() else {
+			return;
+		};
+
+		let pli = PictureLossIndication {
+			sender_ssrc: 0,
+			media_ssrc,
+		};
+
+		if peer_connection.write_rtcp(&[Box::new(pli)]).await.is_err() {
+			return;
+		}
+	}
+}
+
+async fn read_video(track: &TrackRemote, sender: &mpsc::Sender<Message>, mut clock: TrackClock) -> Result<(), webrtc::Error> {
+	let mut builder = SampleBuilder::new(MAX_LATE_PACKETS, H264Packet::default(), clock.clock_rate);
+	let mut converter = AvcConverter::default();
+	let mut out = VecDeque::new();
+
+	// The track stops giving us packets once the peer connection closes, which is
+	// reported by the state change handler.
+	while let Ok((packet, _)) = track.read_rtp().await {
+		builder.push(packet);
+
+		while let Some(sample) = builder.pop() {
+			// WebRTC does not use B-frames so the composition time is always 0.
+			converter
+				.convert(sample.data, clock.timestamp(sample.packet_timestamp), 0, &mut out)
+				.map_err(|err| webrtc::Error::new(err.to_string()))?;
+		}
+
+		if !send(sender, &mut out).await {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+async fn read_audio(
+	track: &TrackRemote,
+	sender: &mpsc::Sender<Message>,
+	mut clock: TrackClock,
+	channels: u8,
+) -> Result<(), webrtc::Error> {
+	let mut builder = SampleBuilder::new(MAX_LATE_PACKETS, OpusPacket, OPUS_SAMPLE_RATE);
+	let mut sent_config = false;
+	let mut out = VecDeque::new();
+
+	while let Ok((packet, _)) = track.read_rtp().await {
+		builder.push(packet);
+
+		while let Some(sample) = builder.pop() {
+			let timestamp = clock.timestamp(sample.packet_timestamp);
+
+			// Opus is sent as Enhanced RTMP audio, with an ExHeader sound format and
+			// the audio packet type in the lower bits.
+			if !sent_config {
+				let mut body = BytesMut::new();
+				body.put_u8(0x90);
+				body.extend_from_slice(b"Opus");
+				body.extend_from_slice(&opus_head(channels));
+
+				out.push_back(ChannelData::Audio {
+					timestamp,
+					data: body.freeze(),
+				});
+
+				sent_config = true;
+			}
+
+			let mut body = BytesMut::with_capacity(5 + sample.data.len());
+			body.put_u8(0x91);
+			body.extend_from_slice(b"Opus");
+			body.extend_from_slice(&sample.data);
+
+			out.push_back(ChannelData::Audio {
+				timestamp,
+				data: body.freeze(),
+			});
+		}
+
+		if !send(sender, &mut out).await {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+async fn send(sender: &mpsc::Sender<Message>, out: &mut VecDeque<ChannelData>) -> bool {
+	for data in out.drain(..) {
+		if sender.send(Ok(Data::Data(Some(data)))).await.is_err() {
+			return false;
+		}
+	}
+
+	true
+}
+
+/// Build an OpusHead for a stream without a channel mapping table.
+/// RFC 7845 - 5.1
+fn opus_head(channels: u8) -> Bytes {
+	let mut head = BytesMut::with_capacity(19);
+	head.extend_from_slice(b"OpusHead");
+	head.put_u8(1); // version
+	head.put_u8(channels);
+	head.put_u16_le(0); // pre-skip
+	head.put_u32_le(OPUS_SAMPLE_RATE);
+	head.put_i16_le(0); // output gain
+	head.put_u8(0); // channel mapping family
+	head.freeze()
+}
+
+/// Converts RTP timestamps to milliseconds. Each track has its own random
+/// RTP timestamp offset, so tracks are lined up using the time their first
+/// sample arrived.
+struct TrackClock {
+	start: Instant,
+	clock_rate: u32,
+	base_millis: Option<u64>,
+	last_rtp_timestamp: u32,
+	ticks: u64,
+}
+
+impl TrackClock {
+	fn new(start: Instant, clock_rate: u32) -> Self {
+		Self {
+			start,
+			clock_rate,
+			base_millis: None,
+			last_rtp_timestamp: 0,
+			ticks: 0,
+		}
+	}
+
+	fn timestamp(&mut self, rtp_timestamp: u32) -> u32 {
+		let Some(base_millis) = self.base_millis else {
+			let base_millis = self.start.elapsed().as_millis() as u64;
+			self.base_millis = Some(base_millis);
+			self.last_rtp_timestamp = rtp_timestamp;
+			return base_millis as u32;
+		};
+
+		// RTP timestamps wrap around, so we keep track of the total number of ticks
+		// ourselves.
+		let delta = rtp_timestamp.wrapping_sub(self.last_rtp_timestamp) as i32 as i64;
+		self.ticks = self.ticks.saturating_add_signed(delta);
+		self.last_rtp_timestamp = rtp_timestamp;
+
+		(base_millis + self.ticks * 1000 / self.clock_rate as u64) as u32
+	}
+}
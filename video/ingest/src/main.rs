@@ -3,7 +3,8 @@ use std::net::IpAddr;
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use binary_helper::global::{setup_database, setup_nats, GlobalCtx, GlobalDb, GlobalNats};
+use binary_helper::config::RedisConfig;
+use binary_helper::global::{setup_database, setup_nats, setup_redis, GlobalCtx, GlobalDb, GlobalNats};
 use binary_helper::{bootstrap, grpc_health, grpc_server, impl_global_traits};
 use tokio::select;
 use tokio::sync::{mpsc, Mutex};
@@ -17,6 +18,9 @@ use video_ingest::global::IncomingTranscoder;
 struct ExtConfig {
 	/// The Ingest configuration.
 	ingest: IngestConfig,
+
+	/// The Redis configuration.
+	redis: RedisConfig,
 }
 
 impl binary_helper::config::ConfigExtention for ExtConfig {
@@ -31,12 +35,21 @@ struct GlobalState {
 	nats: async_nats::Client,
 	jetstream: async_nats::jetstream::Context,
 	db: Arc<utils::database::Pool>,
+	redis: Arc<fred::clients::RedisPool>,
 
 	requests: Mutex<HashMap<Ulid, mpsc::Sender<IncomingTranscoder>>>,
+	ip_connections: std::sync::Mutex<HashMap<IpAddr, usize>>,
 }
 
 impl_global_traits!(GlobalState);
 
+impl binary_helper::global::GlobalRedis for GlobalState {
+	#[inline(always)]
+	fn redis(&self) -> &Arc<fred::clients::RedisPool> {
+		&self.redis
+	}
+}
+
 impl binary_helper::global::GlobalConfigProvider<IngestConfig> for GlobalState {
 	#[inline(always)]
 	fn provide_config(&self) -> &IngestConfig {
@@ -48,12 +61,21 @@ impl video_ingest::global::IngestState for GlobalState {
 	fn requests(&self) -> &Mutex<HashMap<Ulid, mpsc::Sender<IncomingTranscoder>>> {
 		&self.requests
 	}
+
+	fn ip_connections(&self) -> &std::sync::Mutex<HashMap<IpAddr, usize>> {
+		&self.ip_connections
+	}
 }
 
 impl binary_helper::Global<AppConfig> for GlobalState {
 	async fn new(ctx: Context, mut config: AppConfig) -> anyhow::Result<Self> {
 		let (nats, jetstream) = setup_nats(&config.name, &config.nats).await?;
 		let db = setup_database(&config.database).await?;
+		let redis = setup_redis(&config.extra.redis).await?;
+
+		utils::ratelimiter::load_rate_limiter_script(&*redis)
+			.await
+			.context("failed to load rate limiter script")?;
 
 		if config.grpc.bind_address.port() == 0 {
 			// Get a random port
@@ -93,7 +115,9 @@ impl binary_helper::Global<AppConfig> for GlobalState {
 			nats,
 			jetstream,
 			db,
+			redis,
 			requests: Mutex::new(HashMap::new()),
+			ip_connections: std::sync::Mutex::new(HashMap::new()),
 		})
 	}
 }
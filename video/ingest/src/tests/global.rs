@@ -1,13 +1,16 @@
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use binary_helper::logging;
+use fred::interfaces::ClientLike;
 use postgres_from_row::tokio_postgres::NoTls;
 use tokio::sync::{mpsc, Mutex};
 use ulid::Ulid;
 use utils::context::{Context, Handler};
 use utils::database::deadpool_postgres::{ManagerConfig, PoolConfig, RecyclingMethod, Runtime};
 use utils::database::Pool;
+use utils::prelude::FutureTimeout;
 
 use crate::config::IngestConfig;
 use crate::global::IncomingTranscoder;
@@ -18,7 +21,9 @@ pub struct GlobalState {
 	nats: async_nats::Client,
 	jetstream: async_nats::jetstream::Context,
 	db: Arc<utils::database::Pool>,
+	redis: Arc<fred::clients::RedisPool>,
 	requests: Mutex<HashMap<Ulid, mpsc::Sender<IncomingTranscoder>>>,
+	ip_connections: std::sync::Mutex<HashMap<IpAddr, usize>>,
 }
 
 impl binary_helper::global::GlobalCtx for GlobalState {
@@ -49,12 +54,22 @@ impl binary_helper::global::GlobalDb for GlobalState {
 	}
 }
 
+impl binary_helper::global::GlobalRedis for GlobalState {
+	fn redis(&self) -> &Arc<fred::clients::RedisPool> {
+		&self.redis
+	}
+}
+
 impl binary_helper::global::GlobalConfig for GlobalState {}
 
 impl crate::global::IngestState for GlobalState {
 	fn requests(&self) -> &Mutex<HashMap<Ulid, mpsc::Sender<IncomingTranscoder>>> {
 		&self.requests
 	}
+
+	fn ip_connections(&self) -> &std::sync::Mutex<HashMap<IpAddr, usize>> {
+		&self.ip_connections
+	}
 }
 
 pub async fn mock_global_state(config: IngestConfig) -> (Arc<GlobalState>, Handler) {
@@ -68,6 +83,9 @@ pub async fn mock_global_state(config: IngestConfig) -> (Arc<GlobalState>, Handl
 
 	let database_uri = std::env::var("VIDEO_DATABASE_URL_TEST").expect("VIDEO_DATABASE_URL_TEST must be set");
 	let nats_addr = std::env::var("NATS_ADDR").expect("NATS_URL must be set");
+	let redis_url = std::env::var("REDIS_ADDR")
+		.map(|addr| format!("redis://{addr}"))
+		.unwrap_or_else(|_| std::env::var("REDIS_URL").expect("REDIS_URL and REDIS_ADDR are not set"));
 
 	let nats = async_nats::connect(&nats_addr).await.expect("failed to connect to nats");
 	let jetstream = async_nats::jetstream::new(nats.clone());
@@ -86,13 +104,32 @@ pub async fn mock_global_state(config: IngestConfig) -> (Arc<GlobalState>, Handl
 		.expect("failed to create pool"),
 	);
 
+	let redis = Arc::new(
+		fred::clients::RedisPool::new(fred::types::RedisConfig::from_url(&redis_url).unwrap(), None, None, None, 1).unwrap(),
+	);
+
+	redis.connect();
+
+	redis
+		.wait_for_connect()
+		.timeout(std::time::Duration::from_secs(5))
+		.await
+		.expect("failed to connect to redis")
+		.expect("failed to connect to redis");
+
+	utils::ratelimiter::load_rate_limiter_script(&*redis)
+		.await
+		.expect("failed to load rate limiter script");
+
 	let global = Arc::new(GlobalState {
 		config,
 		ctx,
 		requests: Mutex::new(HashMap::new()),
+		ip_connections: std::sync::Mutex::new(HashMap::new()),
 		nats,
 		jetstream,
 		db,
+		redis,
 	});
 
 	(global, handler)
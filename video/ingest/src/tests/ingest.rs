@@ -9,8 +9,13 @@ use async_stream::stream;
 use base64::Engine;
 use binary_helper::config::TlsConfig;
 use binary_helper::global::*;
-use bytes::Bytes;
-use futures::StreamExt;
+use bytes::{BufMut, Bytes, BytesMut};
+use flv::{AacPacket, AvcPacket, FlvTagAudioData, FlvTagData, FlvTagVideoData, FrameType};
+use fred::interfaces::KeysInterface;
+use futures::{SinkExt, StreamExt};
+use http_body_util::{BodyExt, Full};
+use hyper::StatusCode;
+use hyper_util::rt::TokioIo;
 use pb::ext::UlidExt;
 use pb::scuffle::video::internal::events::TranscoderRequestTask;
 use pb::scuffle::video::internal::ingest_client::IngestClient;
@@ -18,7 +23,9 @@ use pb::scuffle::video::internal::{ingest_watch_request, ingest_watch_response,
 use pb::scuffle::video::v1::events_fetch_request::Target;
 use pb::scuffle::video::v1::types::{event, Event, Rendition};
 use prost::Message;
-use tokio::io::AsyncWriteExt;
+use srt_tokio::SrtSocket;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::process::Command;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -28,9 +35,21 @@ use utils::prelude::FutureTimeout;
 use uuid::Uuid;
 use video_common::database::Room;
 use video_common::keys::{self, event_subject};
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::{MediaEngine, MIME_TYPE_H264, MIME_TYPE_OPUS};
+use webrtc::api::APIBuilder;
+use webrtc::interceptor::registry::Registry;
+use webrtc::media::Sample;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 
 use super::global::GlobalState;
-use crate::config::{IngestConfig, RtmpConfig};
+use crate::config::{
+	BackupConfig, HealthConfig, IngestConfig, IpLimitConfig, PullConfig, RtmpConfig, SrtConfig, WhipConfig,
+};
+use crate::global::IngestState;
 use crate::tests::global::mock_global_state;
 
 fn generate_key(org_id: Ulid, room_id: Ulid) -> String {
@@ -41,6 +60,20 @@ fn generate_key(org_id: Ulid, room_id: Ulid) -> String {
 	)
 }
 
+/// Thresholds which are never crossed, so that tests do not see health
+/// events unless they ask for them.
+fn quiet_health() -> HealthConfig {
+	HealthConfig {
+		min_fps_ratio: 0.0,
+		max_keyframe_interval: Duration::ZERO,
+		max_av_drift: Duration::ZERO,
+		max_dropped_frames: u64::MAX,
+		max_late_frames: u64::MAX,
+		max_timestamp_jumps: u64::MAX,
+		max_rtt: Duration::ZERO,
+	}
+}
+
 fn stream_with_ffmpeg(rtmp_port: u16, file: &str, key: &str) -> tokio::process::Child {
 	let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets");
 
@@ -89,6 +122,271 @@ fn stream_with_ffmpeg_tls(rtmp_port: u16, file: &str, tls_dir: &Path, key: &str)
 		.expect("failed to execute ffmpeg")
 }
 
+/// Stream a file as MPEG-TS over SRT, ffmpeg writes the transport stream to
+/// stdout and we send it with our own SRT caller. The connection is closed once
+/// ffmpeg exits.
+async fn stream_with_srt(
+	srt_port: u16,
+	file: &str,
+	stream_id: &str,
+) -> std::io::Result<(tokio::process::Child, JoinHandle<()>)> {
+	let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets");
+
+	let mut socket = SrtSocket::builder()
+		.call(format!("127.0.0.1:{srt_port}").as_str(), Some(stream_id))
+		.await?;
+
+	let mut ffmpeg = Command::new("ffmpeg")
+		.args([
+			"-re",
+			"-i",
+			dir.join(file).to_str().expect("failed to get path"),
+			"-c",
+			"copy",
+			"-f",
+			"mpegts",
+			"-",
+		])
+		.stdout(Stdio::piped())
+		.stderr(Stdio::inherit())
+		.spawn()
+		.expect("failed to execute ffmpeg");
+
+	let mut stdout = ffmpeg.stdout.take().unwrap();
+
+	let handle = tokio::spawn(async move {
+		// 7 transport stream packets is the most that fits in a single SRT packet.
+		let mut buf = vec![0; 7 * 188];
+		while stdout.read_exact(&mut buf).await.is_ok() {
+			if socket
+				.send((std::time::Instant::now(), Bytes::copy_from_slice(&buf)))
+				.await
+				.is_err()
+			{
+				break;
+			}
+		}
+
+		socket.close().await.ok();
+	});
+
+	Ok((ffmpeg, handle))
+}
+
+/// An in-process WebRTC publisher, which sends the H.264 video from an FLV
+/// file. Since there is no Opus encoder available, the AAC frames are replaced
+/// with silent Opus frames.
+struct WhipPublisher {
+	peer_connection: Arc<RTCPeerConnection>,
+	video: Arc<TrackLocalStaticSample>,
+	audio: Arc<TrackLocalStaticSample>,
+}
+
+impl WhipPublisher {
+	async fn new() -> (Self, String) {
+		let mut media_engine = MediaEngine::default();
+		media_engine.register_default_codecs().unwrap();
+		let registry = register_default_interceptors(Registry::new(), &mut media_engine).unwrap();
+		let api = APIBuilder::new()
+			.with_media_engine(media_engine)
+			.with_interceptor_registry(registry)
+			.build();
+
+		let peer_connection = Arc::new(api.new_peer_connection(Default::default()).await.unwrap());
+
+		let video = Arc::new(TrackLocalStaticSample::new(
+			RTCRtpCodecCapability {
+				mime_type: MIME_TYPE_H264.to_string(),
+				clock_rate: 90000,
+				sdp_fmtp_line: "level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f".to_string(),
+				..Default::default()
+			},
+			"video".to_string(),
+			"whip".to_string(),
+		));
+
+		let audio = Arc::new(TrackLocalStaticSample::new(
+			RTCRtpCodecCapability {
+				mime_type: MIME_TYPE_OPUS.to_string(),
+				clock_rate: 48000,
+				channels: 2,
+				..Default::default()
+			},
+			"audio".to_string(),
+			"whip".to_string(),
+		));
+
+		peer_connection.add_track(video.clone()).await.unwrap();
+		peer_connection.add_track(audio.clone()).await.unwrap();
+
+		let offer = peer_connection.create_offer(None).await.unwrap();
+		let mut gathering_complete = peer_connection.gathering_complete_promise().await;
+		peer_connection.set_local_description(offer).await.unwrap();
+		gathering_complete.recv().await;
+
+		let offer = peer_connection.local_description().await.unwrap().sdp;
+
+		(
+			Self {
+				peer_connection,
+				video,
+				audio,
+			},
+			offer,
+		)
+	}
+
+	/// Stream the file in real time, the peer connection is closed once the
+	/// file ends.
+	async fn stream(self, answer: String, file: &str) {
+		self.peer_connection
+			.set_remote_description(RTCSessionDescription::answer(answer).unwrap())
+			.await
+			.unwrap();
+
+		let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets");
+		let data = Bytes::from(std::fs::read(dir.join(file)).unwrap());
+		let flv = flv::Flv::demux(&mut std::io::Cursor::new(data)).unwrap();
+
+		let mut parameter_sets = Bytes::new();
+		let start = tokio::time::Instant::now();
+
+		for tag in flv.tags {
+			tokio::time::sleep_until(start + Duration::from_millis(tag.timestamp as u64)).await;
+
+			let sample = match tag.data {
+				FlvTagData::Video {
+					data: FlvTagVideoData::Avc(AvcPacket::SequenceHeader(config)),
+					..
+				} => {
+					let mut annexb = BytesMut::new();
+					for nalu in config.sps.iter().chain(config.pps.iter()) {
+						annexb.put_u32(1);
+						annexb.extend_from_slice(nalu);
+					}
+					parameter_sets = annexb.freeze();
+					continue;
+				}
+				FlvTagData::Video {
+					frame_type,
+					data: FlvTagVideoData::Avc(AvcPacket::Nalu { mut data, .. }),
+				} => {
+					let mut annexb = BytesMut::new();
+					if frame_type == FrameType::Keyframe {
+						annexb.extend_from_slice(&parameter_sets);
+					}
+
+					while data.len() >= 4 {
+						let len = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+						annexb.put_u32(1);
+						annexb.extend_from_slice(&data[4..4 + len]);
+						data = data.slice(4 + len..);
+					}
+
+					(
+						&self.video,
+						Sample {
+							data: annexb.freeze(),
+							duration: Duration::from_millis(33),
+							..Default::default()
+						},
+					)
+				}
+				FlvTagData::Audio {
+					data: FlvTagAudioData::Aac(AacPacket::Raw(_)),
+					..
+				} => (
+					&self.audio,
+					// A single 20ms silent CELT frame.
+					Sample {
+						data: Bytes::from_static(b"\xfc\xff\xfe"),
+						duration: Duration::from_millis(20),
+						..Default::default()
+					},
+				),
+				_ => continue,
+			};
+
+			if sample.0.write_sample(&sample.1).await.is_err() {
+				break;
+			}
+		}
+
+		self.peer_connection.close().await.ok();
+	}
+}
+
+/// Send a WHIP request, returning the status, location and body of the
+/// response.
+async fn whip_request(whip_port: u16, req: hyper::Request<Full<Bytes>>) -> (StatusCode, Option<String>, Bytes) {
+	let stream = TcpStream::connect(("127.0.0.1", whip_port)).await.unwrap();
+	let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await.unwrap();
+	tokio::spawn(conn);
+
+	let resp = sender.send_request(req).await.unwrap();
+	let status = resp.status();
+	let location = resp
+		.headers()
+		.get(hyper::header::LOCATION)
+		.map(|v| v.to_str().unwrap().to_string());
+	let body = resp.into_body().collect().await.unwrap().to_bytes();
+
+	(status, location, body)
+}
+
+async fn whip_publish(whip_port: u16, key: &str, offer: String) -> (StatusCode, Option<String>, Bytes) {
+	whip_request(
+		whip_port,
+		hyper::Request::post("/whip")
+			.header(hyper::header::HOST, "localhost")
+			.header(hyper::header::CONTENT_TYPE, "application/sdp")
+			.header(hyper::header::AUTHORIZATION, format!("Bearer {key}"))
+			.body(Full::new(Bytes::from(offer)))
+			.unwrap(),
+	)
+	.await
+}
+
+/// Serve a FLV file over HTTP to the first request at roughly real time,
+/// any later requests get a 404.
+async fn serve_http_flv(file: &str) -> u16 {
+	let data = std::fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets").join(file)).unwrap();
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+
+	tokio::spawn(async move {
+		let mut data = Some(data);
+
+		while let Ok((mut socket, _)) = listener.accept().await {
+			let mut buf = [0; 4096];
+			assert_ne!(socket.read(&mut buf).await.unwrap(), 0);
+
+			let Some(data) = data.take() else {
+				socket
+					.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+					.await
+					.ok();
+				continue;
+			};
+
+			socket
+				.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: video/x-flv\r\nConnection: close\r\n\r\n")
+				.await
+				.unwrap();
+
+			for chunk in data.chunks(1000) {
+				if socket.write_all(chunk).await.is_err() {
+					break;
+				}
+
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		}
+	});
+
+	port
+}
+
 fn spawn_ffprobe() -> tokio::process::Child {
 	Command::new("ffprobe")
 		.arg("-v")
@@ -150,6 +448,8 @@ impl Watcher {
 
 struct TestState {
 	pub rtmp_port: u16,
+	pub srt_port: u16,
+	pub whip_port: u16,
 	pub org_id: Ulid,
 	pub room_id: Ulid,
 	pub global: Arc<GlobalState>,
@@ -162,32 +462,96 @@ struct TestState {
 
 impl TestState {
 	async fn setup() -> Self {
-		Self::setup_new(None).await
+		Self::setup_new(None, None, None, None, quiet_health(), Duration::ZERO).await
+	}
+
+	async fn setup_with_reconnect_grace_period(reconnect_grace_period: Duration) -> Self {
+		Self::setup_new(None, None, None, None, quiet_health(), reconnect_grace_period).await
+	}
+
+	async fn setup_with_backup(backup: BackupConfig) -> Self {
+		Self::setup_new(None, None, Some(backup), None, quiet_health(), Duration::ZERO).await
+	}
+
+	async fn setup_with_ip_limit(ip_limit: IpLimitConfig) -> Self {
+		Self::setup_new(None, None, None, Some(ip_limit), quiet_health(), Duration::ZERO).await
+	}
+
+	async fn setup_with_health(health: HealthConfig) -> Self {
+		Self::setup_new(None, None, None, None, health, Duration::ZERO).await
+	}
+
+	async fn setup_with_pull(allowed_networks: Vec<String>) -> Self {
+		Self::setup_new(
+			None,
+			Some(PullConfig {
+				poll_interval: Duration::from_millis(100),
+				lease_duration: Duration::from_millis(600),
+				min_backoff: Duration::from_millis(100),
+				max_backoff: Duration::from_millis(500),
+				allowed_networks,
+				..Default::default()
+			}),
+			None,
+			None,
+			quiet_health(),
+			Duration::ZERO,
+		)
+		.await
 	}
 
 	async fn setup_with_tls(tls_dir: &Path) -> Self {
-		Self::setup_new(Some(TlsConfig {
-			cert: tls_dir.join("server.crt").to_str().unwrap().to_string(),
-			ca_cert: Some(tls_dir.join("ca.crt").to_str().unwrap().to_string()),
-			key: tls_dir.join("server.key").to_str().unwrap().to_string(),
-			domain: Some("localhost".to_string()),
-		}))
+		Self::setup_new(
+			Some(TlsConfig {
+				cert: tls_dir.join("server.crt").to_str().unwrap().to_string(),
+				ca_cert: Some(tls_dir.join("ca.crt").to_str().unwrap().to_string()),
+				key: tls_dir.join("server.key").to_str().unwrap().to_string(),
+				domain: Some("localhost".to_string()),
+			}),
+			None,
+			None,
+			None,
+			quiet_health(),
+			Duration::ZERO,
+		)
 		.await
 	}
 
-	async fn setup_new(tls: Option<TlsConfig>) -> Self {
+	async fn setup_new(
+		tls: Option<TlsConfig>,
+		pull: Option<PullConfig>,
+		backup: Option<BackupConfig>,
+		ip_limit: Option<IpLimitConfig>,
+		health: HealthConfig,
+		reconnect_grace_period: Duration,
+	) -> Self {
 		let grpc_port = portpicker::pick_unused_port().unwrap();
 		let rtmp_port = portpicker::pick_unused_port().unwrap();
+		let srt_port = portpicker::pick_unused_port().unwrap();
+		let whip_port = portpicker::pick_unused_port().unwrap();
 
 		let (global, handler) = mock_global_state(IngestConfig {
 			events_stream_name: Ulid::new().to_string(),
 			transcoder_request_subject: Uuid::new_v4().to_string(),
 			bitrate_update_interval: Duration::from_secs(1),
+			health,
+			reconnect_grace_period,
 			grpc_advertise_address: format!("127.0.0.1:{grpc_port}"),
 			rtmp: RtmpConfig {
 				bind_address: format!("127.0.0.1:{rtmp_port}").parse().unwrap(),
 				tls,
 			},
+			srt: Some(SrtConfig {
+				bind_address: format!("127.0.0.1:{srt_port}").parse().unwrap(),
+				..Default::default()
+			}),
+			whip: Some(WhipConfig {
+				bind_address: format!("127.0.0.1:{whip_port}").parse().unwrap(),
+				..Default::default()
+			}),
+			pull,
+			backup,
+			ip_limit,
 			..Default::default()
 		})
 		.await;
@@ -260,6 +624,8 @@ impl TestState {
 			org_id,
 			room_id,
 			rtmp_port,
+			srt_port,
+			whip_port,
 			global,
 			handler,
 			events: Box::pin(events),
@@ -525,6 +891,83 @@ async fn test_ingest_stream() {
 	state.finish().await;
 }
 
+#[tokio::test]
+async fn test_ingest_stream_health() {
+	let mut state = TestState::setup_with_health(HealthConfig {
+		// No input can deliver a hundred times its declared frame rate.
+		min_fps_ratio: 100.0,
+		..quiet_health()
+	})
+	.await;
+	let mut ffmpeg = stream_with_ffmpeg(
+		state.rtmp_port,
+		"avc_aac_keyframes.mp4",
+		&generate_key(state.org_id, state.room_id),
+	);
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::Connected(_)) => {}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::HealthChanged(changed)) => {
+				assert!(!changed.connection_id.into_ulid().is_nil());
+				assert!(!changed.healthy);
+				assert_eq!(changed.issues, vec!["low_fps".to_string()]);
+
+				let health = changed.health.unwrap();
+				assert_eq!(health.declared_fps as i32, 30);
+				assert!(health.fps > 0.0);
+				assert!(health.bitrate > 0);
+			}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	// The room is updated right after the event is emitted.
+	tokio::time::sleep(Duration::from_millis(200)).await;
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	let health = room.ingest_health.expect("missing ingest health");
+	assert_eq!(health.declared_fps as i32, 30);
+	assert_eq!(health.timestamp_jumps, 0);
+
+	ffmpeg.kill().await.unwrap();
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::Disconnected(_)) => {}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	assert!(room.ingest_health.is_none());
+
+	state.finish().await;
+}
+
 #[tokio::test]
 async fn test_ingest_stream_transcoder_disconnect() {
 	let mut state = TestState::setup().await;
@@ -654,6 +1097,159 @@ async fn test_ingest_stream_transcoder_disconnect() {
 	state.finish().await;
 }
 
+#[tokio::test]
+async fn test_ingest_stream_reconnect() {
+	let grace_period = Duration::from_secs(2);
+	let mut state = TestState::setup_with_reconnect_grace_period(grace_period).await;
+	let key = generate_key(state.org_id, state.room_id);
+
+	let mut ffmpeg = stream_with_ffmpeg(state.rtmp_port, "avc_aac_keyframes.mp4", &key);
+
+	let connection_id = match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::Connected(connected)),
+			..
+		})) => {
+			assert!(!connected.resumed);
+			connected.connection_id.into_ulid()
+		}
+		e => panic!("unexpected event: {:?}", e),
+	};
+
+	let msg = state.transcoder_request().await;
+	assert_eq!(msg.connection_id.into_ulid(), connection_id);
+	assert!(!msg.discontinuity);
+
+	// The encoder drops and reconnects within the grace period.
+	ffmpeg.kill().await.unwrap();
+	tokio::time::sleep(Duration::from_millis(500)).await;
+
+	let mut ffmpeg = stream_with_ffmpeg(state.rtmp_port, "avc_aac_keyframes.mp4", &key);
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::Connected(connected)),
+			..
+		})) => {
+			assert!(connected.resumed);
+			assert_eq!(connected.connection_id.into_ulid(), connection_id);
+		}
+		e => panic!("unexpected event: {:?}", e),
+	}
+
+	let msg = state.transcoder_request().await;
+	assert_eq!(msg.connection_id.into_ulid(), connection_id);
+	assert!(msg.discontinuity);
+
+	// Without a reconnect the connection is closed once the grace period is over.
+	ffmpeg.kill().await.unwrap();
+	tokio::time::sleep(grace_period).await;
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::Disconnected(disconnected)),
+			..
+		})) => {
+			assert_eq!(disconnected.connection_id.into_ulid(), connection_id);
+			assert!(!disconnected.clean);
+		}
+		e => panic!("unexpected event: {:?}", e),
+	}
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	assert_eq!(room.status, video_common::database::RoomStatus::Offline);
+	assert!(room.active_ingest_connection_id.is_none());
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_backup_failover() {
+	let mut state = TestState::setup_with_backup(BackupConfig {
+		failover_timeout: Duration::from_secs(1),
+		heartbeat_interval: Duration::from_millis(100),
+	})
+	.await;
+	let key = generate_key(state.org_id, state.room_id);
+
+	let mut primary = stream_with_ffmpeg(state.rtmp_port, "avc_aac_keyframes.mp4", &key);
+
+	let connection_id = match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::Connected(connected)),
+			..
+		})) => connected.connection_id.into_ulid(),
+		e => panic!("unexpected event: {:?}", e),
+	};
+
+	let msg = state.transcoder_request().await;
+	assert_eq!(msg.connection_id.into_ulid(), connection_id);
+	assert!(!msg.discontinuity);
+
+	// The backup is held warm while the primary delivers media.
+	let mut backup = stream_with_ffmpeg(state.rtmp_port, "avc_aac_keyframes.mp4", &format!("{key}_backup"));
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::BackupConnected(connected)),
+			..
+		})) => {
+			assert_ne!(connected.connection_id.into_ulid(), connection_id);
+		}
+		e => panic!("unexpected event: {:?}", e),
+	}
+
+	// The primary stops and the backup takes over its connection.
+	primary.kill().await.unwrap();
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::InputChanged(changed)),
+			..
+		})) => {
+			assert_eq!(changed.connection_id.into_ulid(), connection_id);
+			assert!(changed.backup);
+		}
+		e => panic!("unexpected event: {:?}", e),
+	}
+
+	let msg = state.transcoder_request().await;
+	assert_eq!(msg.connection_id.into_ulid(), connection_id);
+	assert!(msg.discontinuity);
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	assert_eq!(room.active_ingest_connection_id, Some(connection_id));
+
+	backup.kill().await.unwrap();
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(event::Room {
+			event: Some(event::room::Event::Disconnected(disconnected)),
+			..
+		})) => {
+			assert_eq!(disconnected.connection_id.into_ulid(), connection_id);
+			assert!(!disconnected.clean);
+		}
+		e => panic!("unexpected event: {:?}", e),
+	}
+
+	state.finish().await;
+}
+
 #[tokio::test]
 async fn test_ingest_stream_shutdown() {
 	let mut state = TestState::setup().await;
@@ -876,19 +1472,60 @@ async fn test_ingest_stream_reject() {
 	state.finish().await;
 }
 
-async fn test_ingest_stream_transcoder_full_tls(tls_dir: PathBuf) {
-	let mut state = TestState::setup_with_tls(&tls_dir).await;
-	let mut ffmpeg = stream_with_ffmpeg_tls(
+#[tokio::test]
+async fn test_ingest_stream_policy() {
+	let mut state = TestState::setup().await;
+
+	// The default policy of the organization applies to rooms without their own
+	// policy.
+	utils::database::query(
+		"INSERT INTO ingest_policies (id, organization_id, allowed_video_codecs, organization_default) VALUES ($1, $2, $3, $4)",
+	)
+	.bind(Ulid::new())
+	.bind(state.org_id)
+	.bind(vec!["hev1".to_string()])
+	.bind(true)
+	.build()
+	.execute(state.global.db())
+	.await
+	.unwrap();
+
+	let mut ffmpeg = stream_with_ffmpeg(
 		state.rtmp_port,
-		"avc_aac_large.mp4",
-		&tls_dir,
+		"avc_aac_keyframes.mp4",
 		&generate_key(state.org_id, state.room_id),
 	);
 
 	let update = state.organization_event().await;
-	assert!(update.timestamp.sub(chrono::Utc::now().timestamp_millis()) < 1000);
-	assert!(!update.event_id.into_ulid().is_nil(), "event_id is nil");
 	match update.event {
+		Some(event::Event::Room(room)) => {
+			assert_eq!(room.room_id.into_ulid(), state.room_id);
+			match room.event {
+				Some(event::room::Event::Disconnected(disconnected)) => {
+					assert!(!disconnected.connection_id.into_ulid().is_nil());
+					assert_eq!(disconnected.cause(), "I27: Video codec not allowed by the ingest policy");
+				}
+				_ => panic!("unexpected event"),
+			}
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	assert!(ffmpeg.wait().timeout(Duration::from_secs(1)).await.is_ok());
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_srt() {
+	let mut state = TestState::setup().await;
+
+	let stream_id = format!("#!::r={},m=publish", generate_key(state.org_id, state.room_id));
+	let (mut ffmpeg, srt) = stream_with_srt(state.srt_port, "avc_aac_keyframes.mp4", &stream_id)
+		.await
+		.expect("failed to connect");
+
+	match state.organization_event().await.event {
 		Some(event::Event::Room(room)) => {
 			assert_eq!(room.room_id.into_ulid(), state.room_id);
 			match room.event {
@@ -901,14 +1538,27 @@ async fn test_ingest_stream_transcoder_full_tls(tls_dir: PathBuf) {
 		_ => panic!("unexpected event"),
 	}
 
-	let msg = state.transcoder_request().await;
-	assert!(!msg.request_id.into_ulid().is_nil());
-	assert!(!msg.room_id.into_ulid().is_nil());
-	assert!(!msg.connection_id.into_ulid().is_nil());
-	assert!(!msg.organization_id.into_ulid().is_nil());
-	assert!(!msg.grpc_endpoint.is_empty());
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
 
-	// We should now be able to join the stream
+	let video_input = room.video_input.unwrap();
+	let audio_input = room.audio_input.unwrap();
+
+	assert_eq!(video_input.codec, "avc1.64001f");
+	assert_eq!(video_input.width, 480);
+	assert_eq!(video_input.height, 852);
+	assert_eq!(video_input.fps, 30);
+
+	assert_eq!(audio_input.codec, "mp4a.40.2");
+	assert_eq!(audio_input.sample_rate, 44100);
+	assert_eq!(audio_input.channels, 2);
+
+	let msg = state.transcoder_request().await;
 	let mut watcher = Watcher::new(msg.request_id.into_ulid(), msg.grpc_endpoint).await;
 
 	match watcher.recv().await.message {
@@ -926,24 +1576,565 @@ async fn test_ingest_stream_transcoder_full_tls(tls_dir: PathBuf) {
 
 	match watcher.recv().await.message {
 		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Video);
+			assert!(media.keyframe);
 			assert!(!media.data.is_empty());
 		}
 		_ => panic!("unexpected event"),
 	}
 
-	let mut got_shutting_down = false;
+	ffmpeg.kill().await.unwrap();
+	srt.await.unwrap();
 
-	while let Ok(Some(msg)) = watcher.recv.message().await {
-		match msg.message.unwrap() {
-			ingest_watch_response::Message::Media(media) => {
-				assert!(!media.data.is_empty());
-			}
-			ingest_watch_response::Message::Ready(_) => {
-				panic!("unexpected ready");
-			}
-			ingest_watch_response::Message::Shutdown(_) => {
-				got_shutting_down = true;
-				break;
+	let update = state.organization_event().await;
+	match update.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::Disconnected(disconnected)) => {
+				assert!(!disconnected.connection_id.into_ulid().is_nil());
+				// SRT signals when the caller closes the connection.
+				assert!(disconnected.clean);
+				assert!(disconnected.cause.is_none());
+			}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_srt_reject() {
+	let state = TestState::setup().await;
+
+	let bad_stream_ids = vec![
+		"bad_key".into(),
+		generate_key(state.org_id, state.org_id),
+		format!("#!::r={},m=request", generate_key(state.org_id, state.room_id)),
+	];
+
+	for stream_id in bad_stream_ids {
+		assert!(
+			stream_with_srt(state.srt_port, "avc_aac_large.mp4", &stream_id)
+				.await
+				.is_err()
+		);
+	}
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_whip() {
+	let mut state = TestState::setup().await;
+
+	let key = generate_key(state.org_id, state.room_id);
+
+	let (publisher, offer) = WhipPublisher::new().await;
+	let (status, location, answer) = whip_publish(state.whip_port, &key, offer).await;
+
+	assert_eq!(status, StatusCode::CREATED);
+	let location = location.expect("missing location");
+	assert!(location.starts_with("/whip/"));
+
+	let publisher = tokio::spawn(publisher.stream(String::from_utf8(answer.to_vec()).unwrap(), "avc_aac.flv"));
+
+	let connection_id = match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => {
+			assert_eq!(room.room_id.into_ulid(), state.room_id);
+			match room.event {
+				Some(event::room::Event::Connected(live)) => {
+					assert_eq!(live.ip.as_deref(), Some("127.0.0.1"));
+					live.connection_id.into_ulid()
+				}
+				_ => panic!("unexpected event"),
+			}
+		}
+		_ => panic!("unexpected event"),
+	};
+
+	assert_eq!(location, format!("/whip/{connection_id}"));
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	let video_input = room.video_input.unwrap();
+	let audio_input = room.audio_input.unwrap();
+
+	assert!(video_input.codec.starts_with("avc1."));
+	assert_eq!(audio_input.codec, "opus");
+	assert_eq!(audio_input.sample_rate, 48000);
+	assert_eq!(audio_input.channels, 2);
+
+	let msg = state.transcoder_request().await;
+	let mut watcher = Watcher::new(msg.request_id.into_ulid(), msg.grpc_endpoint).await;
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Init);
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Ready(_)) => {}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Video);
+			assert!(media.keyframe);
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	// Only the stream key the session was published with can end it.
+	assert_eq!(whip_delete(state.whip_port, &location, None).await, StatusCode::UNAUTHORIZED);
+	assert_eq!(
+		whip_delete(state.whip_port, &location, Some(&generate_key(state.org_id, Ulid::new()))).await,
+		StatusCode::FORBIDDEN
+	);
+	assert_eq!(
+		whip_delete(state.whip_port, &format!("/whip/{}", Ulid::new()), Some(&key)).await,
+		StatusCode::NOT_FOUND
+	);
+
+	whip_unpublish(state.whip_port, location, &key).await;
+
+	let update = state.organization_event().await;
+	match update.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::Disconnected(disconnected)) => {
+				assert_eq!(disconnected.connection_id.into_ulid(), connection_id);
+				assert!(disconnected.clean);
+				assert!(disconnected.cause.is_none());
+			}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	publisher.abort();
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_whip_reject() {
+	let state = TestState::setup().await;
+
+	let (_, offer) = WhipPublisher::new().await;
+
+	for key in ["bad_key".to_string(), generate_key(state.org_id, state.org_id)] {
+		let (status, location, _) = whip_publish(state.whip_port, &key, offer.clone()).await;
+		assert_eq!(status, StatusCode::UNAUTHORIZED);
+		assert!(location.is_none());
+	}
+
+	let (status, _, _) = whip_publish(
+		state.whip_port,
+		&generate_key(state.org_id, state.room_id),
+		"not an sdp offer".to_string(),
+	)
+	.await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	state.finish().await;
+}
+
+/// Answer every request with the given authorization hook response.
+async fn serve_auth_hook(response: String) -> u16 {
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+
+	tokio::spawn(async move {
+		while let Ok((mut socket, _)) = listener.accept().await {
+			let mut buf = [0; 4096];
+			assert_ne!(socket.read(&mut buf).await.unwrap(), 0);
+
+			socket
+				.write_all(
+					format!(
+						"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+						response.len(),
+						response
+					)
+					.as_bytes(),
+				)
+				.await
+				.ok();
+		}
+	});
+
+	port
+}
+
+async fn set_auth_hook(state: &TestState, url: &str, fail_open: bool) {
+	utils::database::query("UPDATE organizations SET ingest_auth_url = $1, ingest_auth_fail_open = $2 WHERE id = $3")
+		.bind(url)
+		.bind(fail_open)
+		.bind(state.org_id)
+		.build()
+		.execute(state.global.db())
+		.await
+		.unwrap();
+}
+
+async fn whip_delete(whip_port: u16, location: &str, key: Option<&str>) -> StatusCode {
+	let mut req = hyper::Request::delete(location).header(hyper::header::HOST, "localhost");
+	if let Some(key) = key {
+		req = req.header(hyper::header::AUTHORIZATION, format!("Bearer {key}"));
+	}
+
+	let (status, _, _) = whip_request(whip_port, req.body(Full::default()).unwrap()).await;
+	status
+}
+
+async fn whip_unpublish(whip_port: u16, location: String, key: &str) {
+	assert_eq!(whip_delete(whip_port, &location, Some(key)).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_ingest_stream_auth_hook() {
+	let state = TestState::setup().await;
+	let key = generate_key(state.org_id, state.room_id);
+
+	// The hook can deny a publish with a valid stream key.
+	let port = serve_auth_hook(r#"{"allow":false}"#.to_string()).await;
+	set_auth_hook(&state, &format!("http://127.0.0.1:{port}/auth"), false).await;
+
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, _, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+	// The hook can pick the room, in which case no stream key is needed.
+	let port = serve_auth_hook(format!(r#"{{"allow":true,"room_id":"{}"}}"#, state.room_id)).await;
+	set_auth_hook(&state, &format!("http://127.0.0.1:{port}/auth"), false).await;
+
+	let (_, offer) = WhipPublisher::new().await;
+	let hook_key = format!("live_{}_token", state.org_id);
+	let (status, location, _) = whip_publish(state.whip_port, &hook_key, offer).await;
+	assert_eq!(status, StatusCode::CREATED);
+	whip_unpublish(state.whip_port, location.expect("missing location"), &hook_key).await;
+
+	// An unreachable hook denies the publish unless the organization fails open.
+	let port = {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		listener.local_addr().unwrap().port()
+	};
+	set_auth_hook(&state, &format!("http://127.0.0.1:{port}/auth"), false).await;
+
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, _, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+	set_auth_hook(&state, &format!("http://127.0.0.1:{port}/auth"), true).await;
+
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, location, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::CREATED);
+	whip_unpublish(state.whip_port, location.expect("missing location"), &key).await;
+
+	state.finish().await;
+}
+
+async fn set_ip_filter(state: &TestState, allow: &[&str], deny: &[&str]) {
+	utils::database::query(
+		"UPDATE rooms SET ingest_ip_allow = $1, ingest_ip_deny = $2 WHERE organization_id = $3 AND id = $4",
+	)
+	.bind(allow.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+	.bind(deny.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+	.bind(state.org_id)
+	.bind(state.room_id)
+	.build()
+	.execute(state.global.db())
+	.await
+	.unwrap();
+}
+
+#[tokio::test]
+async fn test_ingest_stream_ip_filter() {
+	let state = TestState::setup().await;
+	let key = generate_key(state.org_id, state.room_id);
+
+	let rejected = [
+		(vec![], vec!["127.0.0.1"]),
+		(vec!["10.0.0.0/8"], vec![]),
+		(vec!["127.0.0.0/8"], vec!["127.0.0.0/24"]),
+	];
+
+	for (allow, deny) in rejected {
+		set_ip_filter(&state, &allow, &deny).await;
+
+		let (_, offer) = WhipPublisher::new().await;
+		let (status, _, _) = whip_publish(state.whip_port, &key, offer).await;
+		assert_eq!(status, StatusCode::UNAUTHORIZED, "allow: {allow:?}, deny: {deny:?}");
+	}
+
+	set_ip_filter(&state, &["127.0.0.0/8"], &["10.0.0.1"]).await;
+
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, location, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::CREATED);
+	whip_unpublish(state.whip_port, location.expect("missing location"), &key).await;
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_ip_limit() {
+	let state = TestState::setup_with_ip_limit(IpLimitConfig {
+		max_connections: 1,
+		handshake_quota: 0,
+		..Default::default()
+	})
+	.await;
+	let key = generate_key(state.org_id, state.room_id);
+
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, location, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::CREATED);
+
+	// The open connection uses up the limit of the ip.
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, _, _) = whip_publish(state.whip_port, &key, offer).await;
+	assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+	whip_unpublish(state.whip_port, location.expect("missing location"), &key).await;
+
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	assert!(state.global.ip_connections().lock().unwrap().is_empty());
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_handshake_rate_limit() {
+	let state = TestState::setup_with_ip_limit(IpLimitConfig {
+		max_connections: 0,
+		handshake_quota: 2,
+		..Default::default()
+	})
+	.await;
+
+	// Start with a fresh quota, in case the test ran recently.
+	let namespace = "{ratelimit:ingest:127.0.0.1}";
+	state
+		.global
+		.redis()
+		.del::<(), _>(vec![
+			format!("{namespace}handshake"),
+			format!("{namespace}exceeded"),
+			format!("{namespace}banned"),
+		])
+		.await
+		.unwrap();
+
+	let (_, offer) = WhipPublisher::new().await;
+
+	for _ in 0..2 {
+		let (status, _, _) = whip_publish(state.whip_port, "bad_key", offer.clone()).await;
+		assert_eq!(status, StatusCode::UNAUTHORIZED);
+	}
+
+	let (status, _, _) = whip_publish(state.whip_port, "bad_key", offer).await;
+	assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+	state.finish().await;
+}
+
+#[tokio::test]
+async fn test_ingest_stream_pull() {
+	// The sources of the tests are served on the loopback address
+	let mut state = TestState::setup_with_pull(vec!["127.0.0.0/8".to_string()]).await;
+
+	let port = serve_http_flv("avc_aac.flv").await;
+
+	utils::database::query("UPDATE rooms SET source_url = $1 WHERE organization_id = $2 AND id = $3")
+		.bind(format!("http://127.0.0.1:{port}/live.flv"))
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build()
+		.execute(state.global.db())
+		.await
+		.unwrap();
+
+	match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => {
+			assert_eq!(room.room_id.into_ulid(), state.room_id);
+			match room.event {
+				Some(event::room::Event::Connected(live)) => {
+					assert!(!live.connection_id.into_ulid().is_nil());
+				}
+				_ => panic!("unexpected event"),
+			}
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build_query_as()
+		.fetch_one(state.global.db())
+		.await
+		.unwrap();
+
+	assert!(room.source_lease_id.is_some());
+	assert!(room.video_input.unwrap().codec.starts_with("avc1."));
+	assert_eq!(room.audio_input.unwrap().codec, "mp4a.40.2");
+
+	let msg = state.transcoder_request().await;
+	let mut watcher = Watcher::new(msg.request_id.into_ulid(), msg.grpc_endpoint).await;
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Init);
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Ready(_)) => {}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Video);
+			assert!(media.keyframe);
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	// The source ends the response once the file has been sent.
+	match state.organization_event().await.event {
+		Some(event::Event::Room(room)) => match room.event {
+			Some(event::room::Event::Disconnected(disconnected)) => {
+				assert!(!disconnected.connection_id.into_ulid().is_nil());
+				assert!(disconnected.clean);
+				assert!(disconnected.cause.is_none());
+			}
+			_ => panic!("unexpected event: {:#?}", room),
+		},
+		_ => panic!("unexpected event"),
+	}
+
+	// Rooms with a source url cannot be pushed to.
+	let (_, offer) = WhipPublisher::new().await;
+	let (status, _, _) = whip_publish(state.whip_port, &generate_key(state.org_id, state.room_id), offer).await;
+	assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+	// Switching the room back to push ingest releases the lease.
+	utils::database::query("UPDATE rooms SET source_url = NULL WHERE organization_id = $1 AND id = $2")
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build()
+		.execute(state.global.db())
+		.await
+		.unwrap();
+
+	let mut released = false;
+	for _ in 0..30 {
+		let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+			.bind(state.org_id)
+			.bind(state.room_id)
+			.build_query_as()
+			.fetch_one(state.global.db())
+			.await
+			.unwrap();
+
+		if room.source_lease_id.is_none() {
+			released = true;
+			break;
+		}
+
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+
+	assert!(released, "lease was not released");
+
+	state.finish().await;
+}
+
+async fn test_ingest_stream_transcoder_full_tls(tls_dir: PathBuf) {
+	let mut state = TestState::setup_with_tls(&tls_dir).await;
+	let mut ffmpeg = stream_with_ffmpeg_tls(
+		state.rtmp_port,
+		"avc_aac_large.mp4",
+		&tls_dir,
+		&generate_key(state.org_id, state.room_id),
+	);
+
+	let update = state.organization_event().await;
+	assert!(update.timestamp.sub(chrono::Utc::now().timestamp_millis()) < 1000);
+	assert!(!update.event_id.into_ulid().is_nil(), "event_id is nil");
+	match update.event {
+		Some(event::Event::Room(room)) => {
+			assert_eq!(room.room_id.into_ulid(), state.room_id);
+			match room.event {
+				Some(event::room::Event::Connected(live)) => {
+					assert!(!live.connection_id.into_ulid().is_nil());
+				}
+				_ => panic!("unexpected event"),
+			}
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	let msg = state.transcoder_request().await;
+	assert!(!msg.request_id.into_ulid().is_nil());
+	assert!(!msg.room_id.into_ulid().is_nil());
+	assert!(!msg.connection_id.into_ulid().is_nil());
+	assert!(!msg.organization_id.into_ulid().is_nil());
+	assert!(!msg.grpc_endpoint.is_empty());
+
+	// We should now be able to join the stream
+	let mut watcher = Watcher::new(msg.request_id.into_ulid(), msg.grpc_endpoint).await;
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert_eq!(media.r#type(), ingest_watch_response::media::Type::Init);
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Ready(_)) => {}
+		_ => panic!("unexpected event"),
+	}
+
+	match watcher.recv().await.message {
+		Some(ingest_watch_response::Message::Media(media)) => {
+			assert!(!media.data.is_empty());
+		}
+		_ => panic!("unexpected event"),
+	}
+
+	let mut got_shutting_down = false;
+
+	while let Ok(Some(msg)) = watcher.recv.message().await {
+		match msg.message.unwrap() {
+			ingest_watch_response::Message::Media(media) => {
+				assert!(!media.data.is_empty());
+			}
+			ingest_watch_response::Message::Ready(_) => {
+				panic!("unexpected ready");
+			}
+			ingest_watch_response::Message::Shutdown(_) => {
+				got_shutting_down = true;
+				break;
 			}
 		}
 	}
@@ -1463,3 +2654,48 @@ async fn test_ingest_stream_transcoder_probe_reconnect_unexpected() {
 
 	state.finish().await;
 }
+
+#[tokio::test]
+async fn test_ingest_stream_pull_private_source() {
+	let state = TestState::setup_with_pull(Vec::new()).await;
+
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+
+	// Names are checked once they are resolved
+	utils::database::query("UPDATE rooms SET source_url = $1 WHERE organization_id = $2 AND id = $3")
+		.bind(format!("http://localhost:{port}/live.flv"))
+		.bind(state.org_id)
+		.bind(state.room_id)
+		.build()
+		.execute(state.global.db())
+		.await
+		.unwrap();
+
+	let mut leased = false;
+	for _ in 0..30 {
+		let room: Room = utils::database::query("SELECT * FROM rooms WHERE organization_id = $1 AND id = $2")
+			.bind(state.org_id)
+			.bind(state.room_id)
+			.build_query_as()
+			.fetch_one(state.global.db())
+			.await
+			.unwrap();
+
+		if room.source_lease_id.is_some() {
+			leased = true;
+			break;
+		}
+
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+
+	assert!(leased, "room was not leased");
+
+	assert!(
+		listener.accept().timeout(Duration::from_secs(1)).await.is_err(),
+		"the puller connected to a loopback address"
+	);
+
+	state.finish().await;
+}
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+use mpegts::{StreamType, TsFrame, TS_TIMESCALE};
+use rtmp::ChannelData;
+
+use crate::ingest::ts::FrameConverter;
+
+/// An AAC LC, 44.1kHz, stereo ADTS frame
+fn adts_frame(payload: &[u8]) -> Vec<u8> {
+	let length = 7 + payload.len();
+
+	let mut frame = vec![
+		0xFF,
+		0xF1,
+		0x50,
+		0x80 | (length >> 11) as u8,
+		(length >> 3) as u8,
+		((length & 0x07) << 5) as u8 | 0x1F,
+		0xFC,
+	];
+	frame.extend_from_slice(payload);
+	frame
+}
+
+fn aac_frame(pts: u64, data: Vec<u8>) -> TsFrame {
+	TsFrame {
+		pid: 257,
+		stream_type: StreamType::Aac,
+		pts: Some(pts),
+		dts: None,
+		data: Bytes::from(data),
+	}
+}
+
+fn audio_timestamps(out: &VecDeque<ChannelData>) -> Vec<u32> {
+	out.iter()
+		.filter_map(|data| match data {
+			ChannelData::Audio { timestamp, data } if data[1] == 0x01 => Some(*timestamp),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn test_frame_converter_timestamp_wrap() {
+	let mut converter = FrameConverter::default();
+	let mut out = VecDeque::new();
+
+	let six_hours = 6 * 60 * 60 * TS_TIMESCALE as u64;
+	let wrap = 1 << 33;
+
+	// Start a second before the 33 bit timestamps wrap, then keep going for
+	// longer than a full wrap.
+	let start = wrap - TS_TIMESCALE as u64;
+	for i in 0..6 {
+		let pts = (start + i * six_hours) % wrap;
+		converter
+			.convert(aac_frame(pts, adts_frame(&[0x21, 0x00])), &mut out)
+			.unwrap();
+	}
+
+	let six_hours_ms = 6 * 60 * 60 * 1000;
+	assert_eq!(audio_timestamps(&out), (0..6).map(|i| i * six_hours_ms).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_frame_converter_skips_malformed_adts() {
+	let mut converter = FrameConverter::default();
+	let mut out = VecDeque::new();
+
+	converter.convert(aac_frame(0, vec![0x00, 0x01, 0x02]), &mut out).unwrap();
+	assert!(out.is_empty());
+
+	// The valid frame before the garbage is kept
+	let mut data = adts_frame(&[0x21, 0x00]);
+	data.extend_from_slice(&[0xFF, 0x00]);
+	converter.convert(aac_frame(9000, data), &mut out).unwrap();
+	assert_eq!(audio_timestamps(&out), vec![100]);
+
+	// The session carries on with the next packet
+	converter
+		.convert(aac_frame(18000, adts_frame(&[0x21, 0x00])), &mut out)
+		.unwrap();
+	assert_eq!(audio_timestamps(&out), vec![100, 200]);
+}
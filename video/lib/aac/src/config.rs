@@ -2,6 +2,8 @@ use std::io;
 
 use bytes::Bytes;
 use bytesio::bit_reader::BitReader;
+use bytesio::bit_syntax;
+use bytesio::bit_syntax::BitSyntax;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
@@ -21,6 +23,8 @@ pub struct AudioSpecificConfig {
 pub enum AudioObjectType {
 	AacMain,
 	AacLowComplexity,
+	/// Spectral Band Replication, also known as HE-AAC
+	Sbr,
 	Unknown(u16),
 }
 
@@ -29,6 +33,7 @@ impl From<u16> for AudioObjectType {
 		match value {
 			1 => AudioObjectType::AacMain,
 			2 => AudioObjectType::AacLowComplexity,
+			5 => AudioObjectType::Sbr,
 			_ => AudioObjectType::Unknown(value),
 		}
 	}
@@ -39,6 +44,7 @@ impl From<AudioObjectType> for u16 {
 		match value {
 			AudioObjectType::AacMain => 1,
 			AudioObjectType::AacLowComplexity => 2,
+			AudioObjectType::Sbr => 5,
 			AudioObjectType::Unknown(value) => value,
 		}
 	}
@@ -90,27 +96,38 @@ impl SampleFrequencyIndex {
 	}
 }
 
+bit_syntax! {
+	/// The leading fields of the Audio Specific Config
+	/// ISO/IEC 14496-3:2019(E) - 1.6.2.1
+	struct AudioSpecificConfigHeader {
+		audio_object_type: u8 = bits(5),
+		audio_object_type_ext: Option<u8> = bits(6) if (audio_object_type == 31),
+		sampling_frequency_index: u8 = bits(4),
+		sampling_frequency: Option<u32> = bits(24) if (sampling_frequency_index == SampleFrequencyIndex::FreqEscape as u8),
+		channel_configuration: u8 = bits(4),
+	}
+}
+
 impl AudioSpecificConfig {
 	pub fn parse(data: Bytes) -> io::Result<Self> {
 		let mut bitreader = BitReader::from(data);
-		let mut audio_object_type = bitreader.read_bits(5)? as u16;
-		if audio_object_type == 31 {
-			audio_object_type = 32 + bitreader.read_bits(6)? as u16;
-		}
+		let header = AudioSpecificConfigHeader::parse(&mut bitreader)?;
 
-		let sampling_frequency_index = SampleFrequencyIndex::from_u8(bitreader.read_bits(4)? as u8)
-			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index"))?;
-		let sampling_frequency = match sampling_frequency_index {
-			SampleFrequencyIndex::FreqEscape => bitreader.read_bits(24)? as u32,
-			_ => sampling_frequency_index.to_freq(),
+		let audio_object_type = match header.audio_object_type_ext {
+			Some(ext) => 32 + ext as u16,
+			None => header.audio_object_type as u16,
 		};
 
-		let channel_configuration = bitreader.read_bits(4)? as u8;
+		let sampling_frequency_index = SampleFrequencyIndex::from_u8(header.sampling_frequency_index)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index"))?;
+		let sampling_frequency = header
+			.sampling_frequency
+			.unwrap_or_else(|| sampling_frequency_index.to_freq());
 
 		Ok(Self {
 			audio_object_type: audio_object_type.into(),
 			sampling_frequency,
-			channel_configuration,
+			channel_configuration: header.channel_configuration,
 			data: bitreader.into_inner().into_inner(),
 		})
 	}
@@ -1,4 +1,5 @@
 use bytes::Bytes;
+use proptest::prelude::*;
 
 use crate::config::SampleFrequencyIndex;
 use crate::{AudioObjectType, AudioSpecificConfig};
@@ -34,3 +35,10 @@ fn test_idx_to_freq() {
 	assert_eq!(88200, SampleFrequencyIndex::Freq88200.to_freq());
 	assert_eq!(96000, SampleFrequencyIndex::Freq96000.to_freq());
 }
+
+proptest! {
+	#[test]
+	fn test_parse_never_panics(data in prop::collection::vec(any::<u8>(), 0..64)) {
+		let _ = AudioSpecificConfig::parse(Bytes::from(data));
+	}
+}
@@ -10,6 +10,7 @@ pub enum Amf0ReadError {
 	StringParseError(str::Utf8Error),
 	IO(io::Error),
 	WrongType,
+	TooDeeplyNested,
 }
 
 macro_rules! from_error {
@@ -44,6 +45,7 @@ impl fmt::Display for Amf0ReadError {
 				write!(f, "unsupported type: {:?}", marker)
 			}
 			Self::WrongType => write!(f, "wrong type"),
+			Self::TooDeeplyNested => write!(f, "too deeply nested"),
 			Self::StringParseError(err) => write!(f, "string parse error: {}", err),
 			Self::IO(err) => write!(f, "io error: {}", err),
 		}
@@ -3,29 +3,49 @@ use std::io::{Cursor, Seek, SeekFrom};
 
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::Bytes;
+use bytesio::bytes_reader::BytesCursor;
 use num_traits::FromPrimitive;
 
 use super::{Amf0Marker, Amf0ReadError, Amf0Value};
 
+/// Objects can contain other objects, so without a limit a small hostile
+/// payload could make us recurse until the stack overflows.
+const MAX_NESTING_DEPTH: usize = 64;
+
 pub struct Amf0Reader {
 	cursor: Cursor<Bytes>,
+	depth: usize,
 }
 
 impl Amf0Reader {
 	pub fn new(buff: Bytes) -> Self {
 		Self {
 			cursor: Cursor::new(buff),
+			depth: 0,
 		}
 	}
 
+	fn read_nested(
+		&mut self,
+		read: impl FnOnce(&mut Self) -> Result<Amf0Value, Amf0ReadError>,
+	) -> Result<Amf0Value, Amf0ReadError> {
+		if self.depth >= MAX_NESTING_DEPTH {
+			return Err(Amf0ReadError::TooDeeplyNested);
+		}
+
+		self.depth += 1;
+		let result = read(self);
+		self.depth -= 1;
+
+		result
+	}
+
 	fn is_empty(&self) -> bool {
 		self.cursor.get_ref().len() == self.cursor.position() as usize
 	}
 
 	fn read_bytes(&mut self, len: usize) -> Result<Bytes, Amf0ReadError> {
-		let pos = self.cursor.position();
-		self.cursor.seek(SeekFrom::Current(len as i64))?;
-		Ok(self.cursor.get_ref().slice(pos as usize..pos as usize + len))
+		Ok(self.cursor.read_slice(len)?)
 	}
 
 	pub fn read_all(&mut self) -> Result<Vec<Amf0Value>, Amf0ReadError> {
@@ -59,9 +79,9 @@ impl Amf0Reader {
 			Amf0Marker::Number => self.read_number(),
 			Amf0Marker::Boolean => self.read_bool(),
 			Amf0Marker::String => self.read_string(),
-			Amf0Marker::Object => self.read_object(),
+			Amf0Marker::Object => self.read_nested(Self::read_object),
 			Amf0Marker::Null => self.read_null(),
-			Amf0Marker::EcmaArray => self.read_ecma_array(),
+			Amf0Marker::EcmaArray => self.read_nested(Self::read_ecma_array),
 			Amf0Marker::LongString => self.read_long_string(),
 			_ => Err(Amf0ReadError::UnsupportedType(marker)),
 		}
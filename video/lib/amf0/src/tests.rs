@@ -3,6 +3,7 @@ use std::io::Cursor;
 
 use byteorder::ReadBytesExt;
 use bytesio::bytes_writer::BytesWriter;
+use proptest::prelude::*;
 
 use crate::{Amf0Marker, Amf0ReadError, Amf0Reader, Amf0Value, Amf0WriteError, Amf0Writer};
 
@@ -112,6 +113,7 @@ fn test_read_error_display() {
 	);
 
 	assert_eq!(Amf0ReadError::WrongType.to_string(), "wrong type");
+	assert_eq!(Amf0ReadError::TooDeeplyNested.to_string(), "too deeply nested");
 
 	assert_eq!(
 		Amf0ReadError::StringParseError(
@@ -202,3 +204,45 @@ fn test_write_object() {
 
 	assert_eq!(writer.dispose(), amf0_object);
 }
+
+#[test]
+fn test_reader_nesting_limit() {
+	let mut amf0_object = Vec::new();
+	for _ in 0..1000 {
+		amf0_object.extend_from_slice(&[0x03, 0x00, 0x01, b'a']); // object with key "a"
+	}
+	amf0_object.push(0x05); // null
+
+	let mut amf_reader = Amf0Reader::new(amf0_object.into());
+	let result = amf_reader.read_any();
+	assert!(matches!(result, Err(Amf0ReadError::TooDeeplyNested)));
+}
+
+fn amf0_value_strategy() -> impl Strategy<Value = Amf0Value> {
+	let leaf = prop_oneof![
+		any::<f64>().prop_filter("NaN is not equal to itself", |n| !n.is_nan()).prop_map(Amf0Value::Number),
+		any::<bool>().prop_map(Amf0Value::Boolean),
+		".{0,32}".prop_map(Amf0Value::String),
+		Just(Amf0Value::Null),
+	];
+
+	leaf.prop_recursive(4, 64, 8, |inner| {
+		prop::collection::hash_map("[a-z]{1,16}", inner, 0..8).prop_map(Amf0Value::Object)
+	})
+}
+
+proptest! {
+	#[test]
+	fn test_round_trip(value in amf0_value_strategy()) {
+		let mut writer = BytesWriter::default();
+		Amf0Writer::write_any(&mut writer, &value).unwrap();
+
+		let mut reader = Amf0Reader::new(writer.dispose());
+		prop_assert_eq!(reader.read_any().unwrap(), value);
+	}
+
+	#[test]
+	fn test_reader_never_panics(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+		let _ = Amf0Reader::new(data.into()).read_all();
+	}
+}
@@ -4,6 +4,8 @@ use std::io::{
 
 use bytes::Bytes;
 use bytesio::bit_reader::BitReader;
+use bytesio::bit_syntax;
+use bytesio::bit_syntax::BitSyntax;
 
 pub mod seq;
 
@@ -17,12 +19,15 @@ pub struct ObuHeader {
 	pub extension_header: Option<ObuHeaderExtension>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-/// Obu Header Extension
-/// AV1-Spec-2 - 5.3.3
-pub struct ObuHeaderExtension {
-	pub temporal_id: u8,
-	pub spatial_id: u8,
+bit_syntax! {
+	#[derive(Debug, Clone, PartialEq)]
+	/// Obu Header Extension
+	/// AV1-Spec-2 - 5.3.3
+	pub struct ObuHeaderExtension {
+		pub temporal_id: u8 = bits(3),
+		pub spatial_id: u8 = bits(2),
+		reserved(3),
+	}
 }
 
 impl ObuHeader {
@@ -42,13 +47,7 @@ impl ObuHeader {
 		}
 
 		let extension_header = if extension_flag {
-			let temporal_id = bit_reader.read_bits(3)?;
-			let spatial_id = bit_reader.read_bits(2)?;
-			bit_reader.read_bits(3)?; // reserved_3bits
-			Some(ObuHeaderExtension {
-				temporal_id: temporal_id as u8,
-				spatial_id: spatial_id as u8,
-			})
+			Some(ObuHeaderExtension::parse(bit_reader)?)
 		} else {
 			None
 		};
@@ -60,8 +59,13 @@ impl ObuHeader {
 			return Err(io::Error::new(io::ErrorKind::InvalidData, "obu_size is not present"));
 		};
 
-		let mut data = vec![0; size as usize];
-		bit_reader.read_exact(&mut data)?;
+		// The size comes from the bitstream, so we cannot trust it enough to
+		// allocate the whole buffer up front.
+		let mut data = Vec::new();
+		bit_reader.take(size).read_to_end(&mut data)?;
+		if data.len() as u64 != size {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "obu is truncated"));
+		}
 
 		Ok((
 			ObuHeader {
@@ -1,6 +1,8 @@
 use std::io;
 
+use bytes::Bytes;
 use bytesio::bit_reader::BitReader;
+use proptest::prelude::*;
 
 use crate::config::AV1CodecConfigurationRecord;
 use crate::seq::{ColorConfig, OperatingPoint, SequenceHeaderObu};
@@ -101,3 +103,39 @@ fn test_config_mux() {
 
 	assert_eq!(buf, data);
 }
+
+proptest! {
+	#[test]
+	fn test_obu_parse_never_panics(data in prop::collection::vec(any::<u8>(), 0..512)) {
+		let mut reader = BitReader::from(data);
+		if let Ok((header, data)) = ObuHeader::parse(&mut reader) {
+			let _ = SequenceHeaderObu::parse(header, data);
+		}
+	}
+
+	#[test]
+	fn test_sequence_header_parse_never_panics(data in prop::collection::vec(any::<u8>(), 0..512)) {
+		let header = ObuHeader {
+			obu_type: ObuType::SequenceHeader,
+			extension_flag: false,
+			has_size_field: true,
+			extension_header: None,
+		};
+
+		let _ = SequenceHeaderObu::parse(header, Bytes::from(data));
+	}
+
+	#[test]
+	fn test_config_remux_is_stable(data in prop::collection::vec(any::<u8>(), 0..512)) {
+		let Ok(config) = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from(data))) else {
+			return Ok(());
+		};
+
+		let mut muxed = Vec::new();
+		config.mux(&mut muxed).unwrap();
+		prop_assert_eq!(muxed.len() as u64, config.size());
+
+		let remuxed = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from(muxed))).unwrap();
+		prop_assert_eq!(remuxed, config);
+	}
+}
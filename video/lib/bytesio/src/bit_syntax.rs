@@ -0,0 +1,257 @@
+use std::io;
+
+use crate::bit_reader::BitReader;
+use crate::bit_writer::BitWriter;
+
+/// A syntax structure which can be read from a `BitReader` and written back
+/// to a `BitWriter` bit for bit.
+///
+/// This is normally implemented with the [`bit_syntax!`](crate::bit_syntax!)
+/// macro rather than by hand.
+pub trait BitSyntax: Sized {
+	fn parse<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<Self>;
+
+	fn write(&self, writer: &mut BitWriter) -> io::Result<()>;
+}
+
+/// Reads an unsigned Exp-Golomb code, ue(v).
+pub fn read_ue<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<u64> {
+	let mut leading_zeros = 0;
+	while !reader.read_bit()? {
+		leading_zeros += 1;
+		if leading_zeros > 63 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "exp-golomb code is too long"));
+		}
+	}
+
+	let mut result = 1;
+	for _ in 0..leading_zeros {
+		result <<= 1;
+		result |= reader.read_bit()? as u64;
+	}
+
+	Ok(result - 1)
+}
+
+/// Reads a signed Exp-Golomb code, se(v).
+pub fn read_se<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<i64> {
+	let exp_glob = read_ue(reader)?;
+
+	if exp_glob % 2 == 0 {
+		Ok(-((exp_glob / 2) as i64))
+	} else {
+		Ok((exp_glob / 2) as i64 + 1)
+	}
+}
+
+/// Writes an unsigned Exp-Golomb code, ue(v).
+pub fn write_ue(writer: &mut BitWriter, input: u64) -> io::Result<()> {
+	let number = input
+		.checked_add(1)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "value is too large for exp-golomb"))?;
+	let leading_zeros = 63 - number.leading_zeros() as usize;
+
+	for _ in 0..leading_zeros {
+		writer.write_bit(false)?;
+	}
+
+	writer.write_bits(number, leading_zeros + 1)?;
+
+	Ok(())
+}
+
+/// Writes a signed Exp-Golomb code, se(v). `i64::MIN` has no code that fits
+/// in 64 bits and is rejected.
+pub fn write_se(writer: &mut BitWriter, number: i64) -> io::Result<()> {
+	let number = if number <= 0 {
+		number
+			.unsigned_abs()
+			.checked_mul(2)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "value is too small for exp-golomb"))?
+	} else {
+		number as u64 * 2 - 1
+	};
+
+	write_ue(writer, number)
+}
+
+#[doc(hidden)]
+pub fn convert<T: TryFrom<U>, U>(value: U) -> io::Result<T> {
+	T::try_from(value).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "value out of range for field"))
+}
+
+#[doc(hidden)]
+pub fn check_width(value: u64, count: usize) -> io::Result<u64> {
+	if count < 64 && value >> count != 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("value {value} does not fit in {count} bits"),
+		));
+	}
+
+	Ok(value)
+}
+
+/// Defines a struct together with its bitstream syntax and implements
+/// [`BitSyntax`](crate::bit_syntax::BitSyntax) for it.
+///
+/// Every field is written as `name: Type = descriptor`, where the descriptor
+/// is one of:
+/// - `bit` - a single bit, the field type must be `bool`.
+/// - `bits(n)` - a fixed width unsigned integer of `n` bits.
+/// - `ue` / `se` - an unsigned / signed Exp-Golomb code.
+/// - `nested` - another type implementing `BitSyntax`.
+///
+/// A descriptor can be followed by `if (condition)`, in which case the field
+/// type must be an `Option` and the field is only present when the condition
+/// holds. The condition can refer to any field declared before it.
+///
+/// Reserved bits are written as `reserved(n)` in place of a field. They are
+/// skipped when parsing and written as zeros, or as the given value with
+/// `reserved(n, value)`, and do not show up in the struct.
+///
+/// ```
+/// bytesio::bit_syntax! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Header {
+///         pub flag: bool = bit,
+///         pub id: u8 = bits(4),
+///         reserved(3),
+///         pub offset: Option<i32> = se if (flag),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! bit_syntax {
+	(
+		$(#[$meta:meta])*
+		$vis:vis struct $name:ident {
+			$($body:tt)*
+		}
+	) => {
+		$crate::bit_syntax!(@munch [$(#[$meta])*] [$vis] [$name] [] [] $($body)*);
+	};
+	(@munch $meta:tt $vis:tt $name:tt [$($fields:tt)*] [$($entries:tt)*]
+		reserved($count:expr) $(, $($rest:tt)*)?
+	) => {
+		$crate::bit_syntax!(@munch $meta $vis $name [$($fields)*] [$($entries)*] reserved($count, 0) $(, $($rest)*)?);
+	};
+	(@munch $meta:tt $vis:tt $name:tt [$($fields:tt)*] [$($entries:tt)*]
+		reserved($count:expr, $value:expr) $(, $($rest:tt)*)?
+	) => {
+		$crate::bit_syntax!(@munch $meta $vis $name
+			[$($fields)*]
+			[$($entries)* { reserved ($count, $value) }]
+			$($($rest)*)?
+		);
+	};
+	(@munch $meta:tt $vis:tt $name:tt [$($fields:tt)*] [$($entries:tt)*]
+		$(#[$field_meta:meta])*
+		$field_vis:vis $field:ident : $field_ty:ty = $kind:ident $(($($arg:tt)*))? $(if ($cond:expr))? $(, $($rest:tt)*)?
+	) => {
+		$crate::bit_syntax!(@munch $meta $vis $name
+			[$($fields)* { [$(#[$field_meta])*] [$field_vis] $field: $field_ty }]
+			[$($entries)* { field $field: $field_ty = $kind $(($($arg)*))? $(if ($cond))? }]
+			$($($rest)*)?
+		);
+	};
+	(@munch [$(#[$meta:meta])*] [$vis:vis] [$name:ident]
+		[$({ [$(#[$field_meta:meta])*] [$field_vis:vis] $field:ident: $field_ty:ty })*]
+		[$($entry:tt)*]
+	) => {
+		$(#[$meta])*
+		$vis struct $name {
+			$(
+				$(#[$field_meta])*
+				$field_vis $field: $field_ty,
+			)*
+		}
+
+		impl $crate::bit_syntax::BitSyntax for $name {
+			fn parse<T: ::std::io::Read>(reader: &mut $crate::bit_reader::BitReader<T>) -> ::std::io::Result<Self> {
+				$(
+					$crate::bit_syntax!(@parse_entry reader, $entry);
+				)*
+
+				Ok(Self { $($field),* })
+			}
+
+			fn write(&self, writer: &mut $crate::bit_writer::BitWriter) -> ::std::io::Result<()> {
+				$(
+					$crate::bit_syntax!(@write_entry self, writer, $entry);
+				)*
+
+				Ok(())
+			}
+		}
+	};
+	(@parse_entry $reader:ident, { reserved ($count:expr, $value:expr) }) => {
+		$reader.read_bits($count)?;
+	};
+	(@parse_entry $reader:ident, { field $field:ident: $field_ty:ty = $kind:ident $(($($arg:tt)*))? $(if ($cond:expr))? }) => {
+		let $field: $field_ty = $crate::bit_syntax!(@read $reader, $kind $(($($arg)*))? $(, $cond)?);
+	};
+	(@write_entry $self:ident, $writer:ident, { reserved ($count:expr, $value:expr) }) => {
+		$writer.write_bits($value, $count as usize)?;
+	};
+	(@write_entry $self:ident, $writer:ident, { field $field:ident: $field_ty:ty = $kind:ident $(($($arg:tt)*))? $(if ($cond:expr))? }) => {
+		#[allow(unused_variables)]
+		let $field = ::std::clone::Clone::clone(&$self.$field);
+		$crate::bit_syntax!(@write $writer, $field, $kind $(($($arg)*))? $(, $cond)?);
+	};
+	(@read $reader:ident, $kind:ident $(($($arg:tt)*))?, $cond:expr) => {
+		if $cond {
+			Some($crate::bit_syntax!(@read $reader, $kind $(($($arg)*))?))
+		} else {
+			None
+		}
+	};
+	(@read $reader:ident, bit) => {
+		$reader.read_bit()?
+	};
+	(@read $reader:ident, bits($count:expr)) => {
+		$crate::bit_syntax::convert($reader.read_bits($count)?)?
+	};
+	(@read $reader:ident, ue) => {
+		$crate::bit_syntax::convert($crate::bit_syntax::read_ue($reader)?)?
+	};
+	(@read $reader:ident, se) => {
+		$crate::bit_syntax::convert($crate::bit_syntax::read_se($reader)?)?
+	};
+	(@read $reader:ident, nested) => {
+		$crate::bit_syntax::BitSyntax::parse($reader)?
+	};
+	(@write $writer:ident, $field:ident, $kind:ident $(($($arg:tt)*))?, $cond:expr) => {
+		if $cond {
+			let Some(value) = &$field else {
+				return Err(::std::io::Error::new(
+					::std::io::ErrorKind::InvalidInput,
+					concat!(stringify!($field), " must be present"),
+				));
+			};
+
+			$crate::bit_syntax!(@write_value $writer, ::std::clone::Clone::clone(value), $kind $(($($arg)*))?);
+		}
+	};
+	(@write $writer:ident, $field:ident, $kind:ident $(($($arg:tt)*))?) => {
+		$crate::bit_syntax!(@write_value $writer, ::std::clone::Clone::clone(&$field), $kind $(($($arg)*))?);
+	};
+	(@write_value $writer:ident, $value:expr, bit) => {
+		$writer.write_bit($value)?
+	};
+	(@write_value $writer:ident, $value:expr, bits($count:expr)) => {
+		$writer.write_bits(
+			$crate::bit_syntax::check_width($crate::bit_syntax::convert($value)?, $count as usize)?,
+			$count as usize,
+		)?
+	};
+	(@write_value $writer:ident, $value:expr, ue) => {
+		$crate::bit_syntax::write_ue($writer, $crate::bit_syntax::convert($value)?)?
+	};
+	(@write_value $writer:ident, $value:expr, se) => {
+		$crate::bit_syntax::write_se($writer, $crate::bit_syntax::convert($value)?)?
+	};
+	(@write_value $writer:ident, $value:expr, nested) => {
+		$crate::bit_syntax::BitSyntax::write(&$value, $writer)?
+	};
+}
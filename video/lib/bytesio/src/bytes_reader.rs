@@ -76,7 +76,7 @@ pub trait BytesCursor {
 
 impl BytesCursor for io::Cursor<Bytes> {
 	fn extract_remaining(&mut self) -> Bytes {
-		let position = self.position() as usize;
+		let position = (self.position() as usize).min(self.get_ref().len());
 		let remaining = self.get_ref().slice(position..);
 		self.set_position(self.get_ref().len() as u64);
 		remaining
@@ -84,12 +84,13 @@ impl BytesCursor for io::Cursor<Bytes> {
 
 	fn read_slice(&mut self, size: usize) -> io::Result<Bytes> {
 		let position = self.position() as usize;
-		if position + size > self.get_ref().len() {
-			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "not enough bytes"));
-		}
+		let end = match position.checked_add(size) {
+			Some(end) if end <= self.get_ref().len() => end,
+			_ => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "not enough bytes")),
+		};
 
-		let slice = self.get_ref().slice(position..position + size);
-		self.set_position((position + size) as u64);
+		let slice = self.get_ref().slice(position..end);
+		self.set_position(end as u64);
 
 		Ok(slice)
 	}
@@ -1,10 +1,11 @@
 use std::time::Duration;
 
-use bytes::{Bytes, BytesMut};
+use bytes::BytesMut;
 use futures::SinkExt;
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::time::Instant;
 use tokio_stream::StreamExt;
-use tokio_util::codec::{BytesCodec, Framed};
+use tokio_util::codec::{BytesCodec, Decoder, Encoder, Framed};
 use utils::prelude::FutureTimeout;
 
 use super::bytesio_errors::BytesIOError;
@@ -13,47 +14,283 @@ pub trait AsyncReadWrite: AsyncRead + AsyncWrite + Unpin + Send + Sync {}
 
 impl<T> AsyncReadWrite for T where T: AsyncRead + AsyncWrite + Unpin + Send + Sync {}
 
-pub struct BytesIO<S: AsyncReadWrite> {
-	stream: Framed<S, BytesCodec>,
+/// Total number of bytes that have passed through a `BytesIO` since it was
+/// created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytesIOStats {
+	pub bytes_read: u64,
+	pub bytes_written: u64,
 }
 
-impl<S: AsyncReadWrite> BytesIO<S> {
-	pub fn new(stream: S) -> Self {
+/// The number of bytes that passed through a `BytesIO` between two calls to
+/// [`BytesIO::sample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThroughputSample {
+	pub bytes_read: u64,
+	pub bytes_written: u64,
+	pub elapsed: Duration,
+}
+
+impl ThroughputSample {
+	/// Bits per second read during the sample window.
+	pub fn read_bitrate(&self) -> u64 {
+		bitrate(self.bytes_read, self.elapsed)
+	}
+
+	/// Bits per second written during the sample window.
+	pub fn write_bitrate(&self) -> u64 {
+		bitrate(self.bytes_written, self.elapsed)
+	}
+}
+
+/// Turns running [`BytesIOStats`] totals into [`ThroughputSample`]s.
+///
+/// `BytesIO` keeps one for its own counters. Anything that counts bytes
+/// without going through a `BytesIO`, like the media handed to ingest by a
+/// session, can keep its totals in a `BytesIOStats` and sample them the same
+/// way.
+#[derive(Debug, Clone, Copy)]
+pub struct ThroughputMeter {
+	last_time: Instant,
+	last_stats: BytesIOStats,
+}
+
+impl Default for ThroughputMeter {
+	fn default() -> Self {
+		Self::new(BytesIOStats::default())
+	}
+}
+
+impl ThroughputMeter {
+	pub fn new(stats: BytesIOStats) -> Self {
+		Self {
+			last_time: Instant::now(),
+			last_stats: stats,
+		}
+	}
+
+	/// Returns the bytes counted since the last sample, without starting a new
+	/// sample window.
+	pub fn peek(&self, stats: BytesIOStats) -> ThroughputSample {
+		ThroughputSample {
+			bytes_read: stats.bytes_read - self.last_stats.bytes_read,
+			bytes_written: stats.bytes_written - self.last_stats.bytes_written,
+			elapsed: self.last_time.elapsed(),
+		}
+	}
+
+	/// Returns the bytes counted since the last sample and starts a new sample
+	/// window.
+	pub fn sample(&mut self, stats: BytesIOStats) -> ThroughputSample {
+		let sample = self.peek(stats);
+		*self = Self::new(stats);
+		sample
+	}
+}
+
+fn bitrate(bytes: u64, elapsed: Duration) -> u64 {
+	let millis = elapsed.as_millis() as u64;
+	if millis == 0 {
+		return 0;
+	}
+
+	bytes * 8 * 1000 / millis
+}
+
+/// A codec adapter which wraps any `tokio_util` codec and counts the bytes
+/// consumed by the decoder and produced by the encoder.
+#[derive(Debug, Clone, Default)]
+pub struct CountingCodec<C> {
+	inner: C,
+	stats: BytesIOStats,
+}
+
+impl<C> CountingCodec<C> {
+	pub fn new(inner: C) -> Self {
 		Self {
-			stream: Framed::new(stream, BytesCodec::new()),
+			inner,
+			stats: BytesIOStats::default(),
 		}
 	}
 
+	pub fn stats(&self) -> BytesIOStats {
+		self.stats
+	}
+
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	pub fn inner_mut(&mut self) -> &mut C {
+		&mut self.inner
+	}
+
+	pub fn into_inner(self) -> C {
+		self.inner
+	}
+}
+
+impl<C: Decoder> Decoder for CountingCodec<C> {
+	type Error = C::Error;
+	type Item = C::Item;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		let before = src.len();
+		let result = self.inner.decode(src);
+		self.stats.bytes_read += before.saturating_sub(src.len()) as u64;
+		result
+	}
+
+	fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		let before = src.len();
+		let result = self.inner.decode_eof(src);
+		self.stats.bytes_read += before.saturating_sub(src.len()) as u64;
+		result
+	}
+}
+
+impl<I, C: Encoder<I>> Encoder<I> for CountingCodec<C> {
+	type Error = C::Error;
+
+	fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		let before = dst.len();
+		let result = self.inner.encode(item, dst);
+		self.stats.bytes_written += dst.len().saturating_sub(before) as u64;
+		result
+	}
+}
+
+/// A framed transport over any `AsyncReadWrite` stream (plain TCP, TLS, an
+/// in-memory pipe, etc).
+///
+/// By default the stream is framed with a `BytesCodec`, which yields whatever
+/// bytes are available. Use [`BytesIO::with_codec`] to frame the stream with a
+/// protocol specific codec instead.
+pub struct BytesIO<S: AsyncReadWrite, C = BytesCodec> {
+	stream: Framed<S, CountingCodec<C>>,
+	read_timeout: Option<Duration>,
+	write_timeout: Option<Duration>,
+	throughput: ThroughputMeter,
+}
+
+impl<S: AsyncReadWrite> BytesIO<S> {
+	pub fn new(stream: S) -> Self {
+		Self::with_codec(stream, BytesCodec::new())
+	}
+
 	pub fn with_capacity(stream: S, capacity: usize) -> Self {
+		Self::from_framed(Framed::with_capacity(stream, CountingCodec::new(BytesCodec::new()), capacity))
+	}
+}
+
+impl<S: AsyncReadWrite, C> BytesIO<S, C> {
+	pub fn with_codec(stream: S, codec: C) -> Self {
+		Self::from_framed(Framed::new(stream, CountingCodec::new(codec)))
+	}
+
+	fn from_framed(stream: Framed<S, CountingCodec<C>>) -> Self {
 		Self {
-			stream: Framed::with_capacity(stream, BytesCodec::new(), capacity),
+			stream,
+			read_timeout: None,
+			write_timeout: None,
+			throughput: ThroughputMeter::default(),
 		}
 	}
 
-	pub async fn write(&mut self, bytes: Bytes) -> Result<(), BytesIOError> {
-		self.stream.send(bytes).await.map_err(|_| BytesIOError::ClientClosed)?;
+	/// Sets the deadline applied to every call to [`BytesIO::read`].
+	pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+		self.set_read_timeout(Some(timeout));
+		self
+	}
 
-		Ok(())
+	/// Sets the deadline applied to every call to [`BytesIO::write`].
+	pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+		self.set_write_timeout(Some(timeout));
+		self
 	}
 
-	pub async fn read(&mut self) -> Result<BytesMut, BytesIOError> {
-		let Some(Ok(message)) = self.stream.next().await else {
-			return Err(BytesIOError::ClientClosed);
-		};
+	pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+		self.read_timeout = timeout;
+	}
 
-		Ok(message)
+	pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+		self.write_timeout = timeout;
+	}
+
+	pub fn codec(&self) -> &C {
+		self.stream.codec().inner()
 	}
 
-	pub async fn read_timeout(&mut self, timeout: Duration) -> Result<BytesMut, BytesIOError> {
-		self.read().timeout(timeout).await?.map_err(|_| BytesIOError::ClientClosed)
+	pub fn codec_mut(&mut self) -> &mut C {
+		self.stream.codec_mut().inner_mut()
 	}
 
-	pub async fn write_timeout(&mut self, bytes: Bytes, timeout: Duration) -> Result<(), BytesIOError> {
-		self.write(bytes)
-			.timeout(timeout)
-			.await?
-			.map_err(|_| BytesIOError::ClientClosed)?;
+	pub fn stats(&self) -> BytesIOStats {
+		self.stream.codec().stats()
+	}
+
+	/// Returns the bytes read and written since the last call to `sample` (or
+	/// since the `BytesIO` was created).
+	pub fn sample(&mut self) -> ThroughputSample {
+		let stats = self.stats();
+		self.throughput.sample(stats)
+	}
+
+	pub fn into_inner(self) -> S {
+		self.stream.into_inner()
+	}
+
+	pub async fn write<I>(&mut self, item: I) -> Result<(), BytesIOError>
+	where
+		C: Encoder<I>,
+	{
+		match self.write_timeout {
+			Some(timeout) => self.write_timeout(item, timeout).await,
+			None => self.write_inner(item).await,
+		}
+	}
+
+	pub async fn read(&mut self) -> Result<C::Item, BytesIOError>
+	where
+		C: Decoder,
+	{
+		match self.read_timeout {
+			Some(timeout) => self.read_timeout(timeout).await,
+			None => self.read_inner().await,
+		}
+	}
+
+	pub async fn read_timeout(&mut self, timeout: Duration) -> Result<C::Item, BytesIOError>
+	where
+		C: Decoder,
+	{
+		self.read_inner().timeout(timeout).await?
+	}
+
+	pub async fn write_timeout<I>(&mut self, item: I, timeout: Duration) -> Result<(), BytesIOError>
+	where
+		C: Encoder<I>,
+	{
+		self.write_inner(item).timeout(timeout).await?
+	}
+
+	async fn write_inner<I>(&mut self, item: I) -> Result<(), BytesIOError>
+	where
+		C: Encoder<I>,
+	{
+		self.stream.send(item).await.map_err(|_| BytesIOError::ClientClosed)?;
 
 		Ok(())
 	}
+
+	async fn read_inner(&mut self) -> Result<C::Item, BytesIOError>
+	where
+		C: Decoder,
+	{
+		let Some(Ok(message)) = self.stream.next().await else {
+			return Err(BytesIOError::ClientClosed);
+		};
+
+		Ok(message)
+	}
 }
@@ -1,4 +1,5 @@
 pub mod bit_reader;
+pub mod bit_syntax;
 pub mod bit_writer;
 pub mod bytes_reader;
 pub mod bytes_writer;
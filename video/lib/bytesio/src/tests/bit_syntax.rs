@@ -0,0 +1,199 @@
+use proptest::prelude::*;
+
+use crate::bit_reader::BitReader;
+use crate::bit_syntax::{read_se, read_ue, write_se, write_ue, BitSyntax};
+use crate::bit_writer::BitWriter;
+
+crate::bit_syntax! {
+	#[derive(Debug, Clone, PartialEq)]
+	struct Inner {
+		a: u8 = bits(3),
+		b: bool = bit,
+	}
+}
+
+crate::bit_syntax! {
+	#[derive(Debug, Clone, PartialEq)]
+	struct Header {
+		/// A documented field
+		flag: bool = bit,
+		id: u8 = bits(4),
+		count: u32 = ue,
+		offset: i16 = se,
+		extra: Option<u64> = ue if (flag),
+		nested: Option<Inner> = nested if (id == 3),
+		tail: u16 = bits(11),
+	}
+}
+
+crate::bit_syntax! {
+	#[derive(Debug, Clone, PartialEq)]
+	struct WithReserved {
+		a: u8 = bits(2),
+		reserved(3),
+		reserved(3, 0b111),
+		b: bool = bit,
+	}
+}
+
+fn round_trip(header: &Header) -> Vec<u8> {
+	let mut writer = BitWriter::default();
+	header.write(&mut writer).unwrap();
+	let data = writer.into_inner();
+
+	let mut reader = BitReader::from(data.clone());
+	let parsed = Header::parse(&mut reader).unwrap();
+	assert_eq!(&parsed, header);
+
+	let mut writer = BitWriter::default();
+	parsed.write(&mut writer).unwrap();
+	assert_eq!(writer.into_inner(), data);
+
+	data
+}
+
+#[test]
+fn test_bit_syntax_round_trip() {
+	let data = round_trip(&Header {
+		flag: false,
+		id: 0b1010,
+		count: 0,
+		offset: 0,
+		extra: None,
+		nested: None,
+		tail: 0b11111111111,
+	});
+
+	// 0 1010 1 1 11111111111 (padding)
+	assert_eq!(data, vec![0b01010111, 0b11111111, 0b11000000]);
+
+	round_trip(&Header {
+		flag: true,
+		id: 3,
+		count: 300,
+		offset: -27,
+		extra: Some(u32::MAX as u64),
+		nested: Some(Inner { a: 0b101, b: true }),
+		tail: 1,
+	});
+}
+
+#[test]
+fn test_bit_syntax_reserved() {
+	let mut writer = BitWriter::default();
+	WithReserved { a: 0b10, b: true }.write(&mut writer).unwrap();
+
+	// 10 000 111 1 (padding)
+	assert_eq!(writer.into_inner(), vec![0b10000111, 0b10000000]);
+
+	// Reserved bits are ignored when parsing
+	let mut reader = BitReader::from(vec![0b01101010, 0b00000000]);
+	assert_eq!(WithReserved::parse(&mut reader).unwrap(), WithReserved { a: 0b01, b: false });
+}
+
+#[test]
+fn test_bit_syntax_write_errors() {
+	let mut writer = BitWriter::default();
+	let err = Header {
+		flag: false,
+		id: 0b10000,
+		count: 0,
+		offset: 0,
+		extra: None,
+		nested: None,
+		tail: 0,
+	}
+	.write(&mut writer)
+	.unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+	let mut writer = BitWriter::default();
+	let err = Header {
+		flag: true,
+		id: 0,
+		count: 0,
+		offset: 0,
+		extra: None,
+		nested: None,
+		tail: 0,
+	}
+	.write(&mut writer)
+	.unwrap_err();
+	assert_eq!(err.to_string(), "extra must be present");
+}
+
+#[test]
+fn test_bit_syntax_read_out_of_range() {
+	let mut writer = BitWriter::default();
+	writer.write_bit(false).unwrap();
+	writer.write_bits(0, 4).unwrap();
+	write_ue(&mut writer, u32::MAX as u64 + 1).unwrap();
+	writer.write_bits(0, 16).unwrap();
+
+	let mut reader = BitReader::from(writer.into_inner());
+	let err = Header::parse(&mut reader).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_exp_golomb_round_trip() {
+	let mut writer = BitWriter::default();
+	for i in 0..100 {
+		write_ue(&mut writer, i).unwrap();
+		write_se(&mut writer, -(i as i64)).unwrap();
+		write_se(&mut writer, i as i64).unwrap();
+	}
+
+	let mut reader = BitReader::from(writer.into_inner());
+	for i in 0..100 {
+		assert_eq!(read_ue(&mut reader).unwrap(), i);
+		assert_eq!(read_se(&mut reader).unwrap(), -(i as i64));
+		assert_eq!(read_se(&mut reader).unwrap(), i as i64);
+	}
+}
+
+#[test]
+fn test_exp_golomb_boundaries() {
+	let mut writer = BitWriter::default();
+	write_ue(&mut writer, u64::MAX - 1).unwrap();
+	write_se(&mut writer, i64::MIN + 1).unwrap();
+	write_se(&mut writer, i64::MAX).unwrap();
+
+	let mut reader = BitReader::from(writer.into_inner());
+	assert_eq!(read_ue(&mut reader).unwrap(), u64::MAX - 1);
+	assert_eq!(read_se(&mut reader).unwrap(), i64::MIN + 1);
+	assert_eq!(read_se(&mut reader).unwrap(), i64::MAX);
+
+	let mut writer = BitWriter::default();
+	let err = write_ue(&mut writer, u64::MAX).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+	let err = write_se(&mut writer, i64::MIN).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_exp_golomb_too_long() {
+	let mut reader = BitReader::from(vec![0; 16]);
+	let err = read_ue(&mut reader).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+proptest! {
+	#[test]
+	fn test_exp_golomb_round_trip_any(unsigned in 0..u64::MAX, signed in (i64::MIN + 1)..=i64::MAX) {
+		let mut writer = BitWriter::default();
+		write_ue(&mut writer, unsigned).unwrap();
+		write_se(&mut writer, signed).unwrap();
+
+		let mut reader = BitReader::from(writer.into_inner());
+		prop_assert_eq!(read_ue(&mut reader).unwrap(), unsigned);
+		prop_assert_eq!(read_se(&mut reader).unwrap(), signed);
+	}
+
+	#[test]
+	fn test_bit_syntax_parse_never_panics(data in prop::collection::vec(any::<u8>(), 0..64)) {
+		if let Ok(header) = Header::parse(&mut BitReader::from(data)) {
+			round_trip(&header);
+		}
+	}
+}
@@ -1,7 +1,11 @@
+use std::time::Duration;
+
 use bytes::Bytes;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::codec::LinesCodec;
 
-use crate::bytesio::BytesIO;
+use crate::bytesio::{BytesIO, BytesIOStats, ThroughputMeter};
+use crate::bytesio_errors::BytesIOError;
 
 #[tokio::test]
 async fn test_bytes_io() {
@@ -19,3 +23,92 @@ async fn test_bytes_io() {
 	let buf = bytesio.read().await.unwrap();
 	assert_eq!(buf.to_vec(), b"hello bytesio".to_vec());
 }
+
+#[tokio::test]
+async fn test_bytes_io_stats() {
+	let (pipe1, mut pipe2) = tokio::io::duplex(1024);
+	let mut bytesio = BytesIO::new(pipe1);
+
+	bytesio.write(Bytes::from_static(b"hello world")).await.unwrap();
+
+	let mut buf = vec![0; 11];
+	pipe2.read_exact(&mut buf).await.unwrap();
+
+	pipe2.write_all(b"hello").await.unwrap();
+	bytesio.read().await.unwrap();
+
+	assert_eq!(
+		bytesio.stats(),
+		BytesIOStats {
+			bytes_read: 5,
+			bytes_written: 11,
+		}
+	);
+
+	let sample = bytesio.sample();
+	assert_eq!(sample.bytes_read, 5);
+	assert_eq!(sample.bytes_written, 11);
+
+	let sample = bytesio.sample();
+	assert_eq!(sample.bytes_read, 0);
+	assert_eq!(sample.bytes_written, 0);
+}
+
+#[tokio::test]
+async fn test_throughput_meter() {
+	let mut meter = ThroughputMeter::default();
+	let mut stats = BytesIOStats::default();
+
+	stats.bytes_read += 100;
+	assert_eq!(meter.peek(stats).bytes_read, 100);
+
+	stats.bytes_read += 50;
+	let sample = meter.sample(stats);
+	assert_eq!(sample.bytes_read, 150);
+	assert_eq!(sample.bytes_written, 0);
+
+	assert_eq!(meter.peek(stats).bytes_read, 0);
+}
+
+#[tokio::test]
+async fn test_bytes_io_read_deadline() {
+	let (pipe1, _pipe2) = tokio::io::duplex(1024);
+	let mut bytesio = BytesIO::new(pipe1).with_read_timeout(Duration::from_millis(10));
+
+	assert!(matches!(bytesio.read().await, Err(BytesIOError::Timeout)));
+}
+
+#[tokio::test]
+async fn test_bytes_io_write_deadline() {
+	let (pipe1, _pipe2) = tokio::io::duplex(4);
+	let mut bytesio = BytesIO::new(pipe1).with_write_timeout(Duration::from_millis(10));
+
+	assert!(matches!(
+		bytesio.write(Bytes::from_static(b"hello world")).await,
+		Err(BytesIOError::Timeout)
+	));
+}
+
+#[tokio::test]
+async fn test_bytes_io_with_codec() {
+	let (pipe1, mut pipe2) = tokio::io::duplex(1024);
+	let mut bytesio = BytesIO::with_codec(pipe1, LinesCodec::new());
+
+	bytesio.write("hello").await.unwrap();
+
+	let mut buf = vec![0; 6];
+	pipe2.read_exact(&mut buf).await.unwrap();
+	assert_eq!(buf, b"hello\n".to_vec());
+
+	pipe2.write_all(b"first\nsecond\n").await.unwrap();
+
+	assert_eq!(bytesio.read().await.unwrap(), "first");
+	assert_eq!(bytesio.read().await.unwrap(), "second");
+	assert_eq!(
+		bytesio.stats(),
+		BytesIOStats {
+			bytes_read: 13,
+			bytes_written: 6,
+		}
+	);
+}
@@ -1,4 +1,5 @@
 mod bit_reader;
+mod bit_syntax;
 mod bit_writer;
 mod bytes_reader;
 mod bytes_writer;
@@ -76,10 +76,38 @@ pub enum FlvTagAudioData {
 	/// AAC Audio Packet defined in the FLV specification. Chapter 1 -
 	/// AACAUDIODATA
 	Aac(AacPacket),
+	/// Enhanced Audio Packet
+	Enhanced(EnhancedAudioPacket),
 	/// Data we don't know how to parse
 	Unknown { sound_format: u8, data: Bytes },
 }
 
+#[derive(Debug, Clone, PartialEq)]
+/// Enhanced Audio Packet
+/// Defined in the Enhanced RTMP v2 specification - ExAudioTagHeader
+pub enum EnhancedAudioPacket {
+	/// Sequence End
+	SequenceEnd,
+	/// Opus Audio Packet
+	Opus(OpusPacket),
+	/// We don't know how to parse it
+	Unknown {
+		packet_type: u8,
+		audio_codec: [u8; 4],
+		data: Bytes,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Opus Packet
+pub enum OpusPacket {
+	/// The Opus identification header (OpusHead)
+	/// RFC 7845 - 5.1
+	SequenceStart(Bytes),
+	/// A single Opus packet
+	Raw(Bytes),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// AAC Packet
 /// This is a container for aac data.
@@ -186,7 +214,8 @@ pub(crate) enum SoundCodecId {
 	Nellymoser = 0x6,
 	G711ALaw = 0x7,
 	G711MuLaw = 0x8,
-	Reserved = 0x9,
+	/// Enhanced RTMP v2, the codec is signaled with a FourCC
+	ExHeader = 0x9,
 	Aac = 0xA,
 	Speex = 0xB,
 	Mp38Khz = 0xE,
@@ -235,6 +264,41 @@ pub(crate) enum AacPacketType {
 	Raw = 0x1,
 }
 
+#[derive(Debug, Clone, Copy, FromPrimitive, PartialEq, Eq)]
+#[repr(u8)]
+/// Enhanced RTMP v2 Audio Packet Type
+pub(crate) enum EnhancedAudioPacketType {
+	SequenceStart = 0x00,
+	CodedFrames = 0x01,
+	SequenceEnd = 0x02,
+	MultichannelConfig = 0x04,
+	Multitrack = 0x05,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AudioFourCC {
+	Opus,
+	Unknown([u8; 4]),
+}
+
+impl From<[u8; 4]> for AudioFourCC {
+	fn from(fourcc: [u8; 4]) -> Self {
+		match &fourcc {
+			b"Opus" => AudioFourCC::Opus,
+			_ => AudioFourCC::Unknown(fourcc),
+		}
+	}
+}
+
+impl From<AudioFourCC> for [u8; 4] {
+	fn from(fourcc: AudioFourCC) -> Self {
+		match fourcc {
+			AudioFourCC::Opus => *b"Opus",
+			AudioFourCC::Unknown(fourcc) => fourcc,
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy, FromPrimitive, PartialEq, Eq)]
 #[repr(u8)]
 /// FLV Video Codec ID
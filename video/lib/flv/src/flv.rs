@@ -13,9 +13,10 @@ use num_traits::FromPrimitive;
 
 use crate::define::Flv;
 use crate::{
-	AacPacket, AacPacketType, Av1Packet, AvcPacket, AvcPacketType, EnhancedPacket, EnhancedPacketType, FlvDemuxerError,
-	FlvHeader, FlvTag, FlvTagAudioData, FlvTagData, FlvTagType, FlvTagVideoData, FrameType, HevcPacket, SoundCodecId,
-	SoundRate, SoundSize, SoundType, VideoCodecId, VideoFourCC,
+	AacPacket, AacPacketType, AudioFourCC, Av1Packet, AvcPacket, AvcPacketType, EnhancedAudioPacket,
+	EnhancedAudioPacketType, EnhancedPacket, EnhancedPacketType, FlvDemuxerError, FlvHeader, FlvTag, FlvTagAudioData,
+	FlvTagData, FlvTagType, FlvTagVideoData, FrameType, HevcPacket, OpusPacket, SoundCodecId, SoundRate, SoundSize,
+	SoundType, VideoCodecId, VideoFourCC,
 };
 
 impl Flv {
@@ -98,6 +99,17 @@ impl FlvTagData {
 
 				let sound_format = (flags & 0b1111_0000) >> 4;
 
+				if sound_format == SoundCodecId::ExHeader as u8 {
+					// Enhanced audio tags use the lower bits for the packet type, the legacy
+					// fields do not apply so we report the same values that AAC requires.
+					return Ok(FlvTagData::Audio {
+						sound_rate: SoundRate::Hz44000,
+						sound_size: SoundSize::Bit16,
+						sound_type: SoundType::Stereo,
+						data: FlvTagAudioData::demux_enhanced(flags & 0b0000_1111, &mut reader)?,
+					});
+				}
+
 				let sound_rate = (flags & 0b0000_1100) >> 2;
 				let sound_rate =
 					SoundRate::from_u8(sound_rate).ok_or_else(|| FlvDemuxerError::InvalidSoundRate(sound_rate))?;
@@ -182,6 +194,29 @@ impl FlvTagAudioData {
 			}),
 		}
 	}
+
+	pub fn demux_enhanced(packet_type: u8, reader: &mut io::Cursor<Bytes>) -> Result<Self, FlvDemuxerError> {
+		let packet_type = EnhancedAudioPacketType::from_u8(packet_type)
+			.ok_or_else(|| FlvDemuxerError::InvalidEnhancedPacketType(packet_type))?;
+		let mut audio_codec = [0; 4];
+		reader.read_exact(&mut audio_codec)?;
+		let audio_codec = AudioFourCC::from(audio_codec);
+
+		match (audio_codec, packet_type) {
+			(_, EnhancedAudioPacketType::SequenceEnd) => Ok(Self::Enhanced(EnhancedAudioPacket::SequenceEnd)),
+			(AudioFourCC::Opus, EnhancedAudioPacketType::SequenceStart) => Ok(Self::Enhanced(EnhancedAudioPacket::Opus(
+				OpusPacket::SequenceStart(reader.extract_remaining()),
+			))),
+			(AudioFourCC::Opus, EnhancedAudioPacketType::CodedFrames) => Ok(Self::Enhanced(EnhancedAudioPacket::Opus(
+				OpusPacket::Raw(reader.extract_remaining()),
+			))),
+			_ => Ok(Self::Enhanced(EnhancedAudioPacket::Unknown {
+				packet_type: packet_type as u8,
+				audio_codec: audio_codec.into(),
+				data: reader.extract_remaining(),
+			})),
+		}
+	}
 }
 
 impl AacPacket {
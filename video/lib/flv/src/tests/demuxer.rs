@@ -9,8 +9,8 @@ use bytesio::bit_reader::BitReader;
 use h264::{Sps, SpsExtended};
 
 use crate::{
-	AacPacket, Av1Packet, AvcPacket, EnhancedPacket, Flv, FlvTagAudioData, FlvTagData, FlvTagVideoData, FrameType,
-	HevcPacket, SoundRate, SoundSize, SoundType,
+	AacPacket, Av1Packet, AvcPacket, EnhancedAudioPacket, EnhancedPacket, Flv, FlvTagAudioData, FlvTagData, FlvTagType,
+	FlvTagVideoData, FrameType, HevcPacket, OpusPacket, SoundRate, SoundSize, SoundType,
 };
 
 #[test]
@@ -817,3 +817,59 @@ fn test_demux_flv_hevc_aac() {
 
 	assert!(read_seq_end);
 }
+
+#[test]
+fn test_demux_enhanced_audio_opus() {
+	let opus_head = Bytes::from_static(b"OpusHead\x01\x02\x38\x01\x80\xbb\x00\x00\x00\x00\x00");
+
+	let mut data = vec![0x90];
+	data.extend_from_slice(b"Opus");
+	data.extend_from_slice(&opus_head);
+
+	let tag = FlvTagData::demux(FlvTagType::Audio as u8, Bytes::from(data)).expect("failed to demux tag");
+	assert_eq!(
+		tag,
+		FlvTagData::Audio {
+			sound_rate: SoundRate::Hz44000,
+			sound_size: SoundSize::Bit16,
+			sound_type: SoundType::Stereo,
+			data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::SequenceStart(opus_head))),
+		}
+	);
+
+	let tag = FlvTagData::demux(FlvTagType::Audio as u8, Bytes::from_static(b"\x91Opus\xfc\xff\xfe"))
+		.expect("failed to demux tag");
+	match tag {
+		FlvTagData::Audio {
+			data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::Raw(data))),
+			..
+		} => assert_eq!(data, Bytes::from_static(b"\xfc\xff\xfe")),
+		_ => panic!("expected opus packet: {:?}", tag),
+	}
+
+	let tag = FlvTagData::demux(FlvTagType::Audio as u8, Bytes::from_static(b"\x92Opus")).expect("failed to demux tag");
+	assert!(matches!(
+		tag,
+		FlvTagData::Audio {
+			data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::SequenceEnd),
+			..
+		}
+	));
+
+	let tag = FlvTagData::demux(FlvTagType::Audio as u8, Bytes::from_static(b"\x91fLaC\x00")).expect("failed to demux tag");
+	assert_eq!(
+		tag,
+		FlvTagData::Audio {
+			sound_rate: SoundRate::Hz44000,
+			sound_size: SoundSize::Bit16,
+			sound_type: SoundType::Stereo,
+			data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::Unknown {
+				packet_type: 1,
+				audio_codec: *b"fLaC",
+				data: Bytes::from_static(b"\x00"),
+			}),
+		}
+	);
+
+	assert!(FlvTagData::demux(FlvTagType::Audio as u8, Bytes::from_static(b"\x9fOpus")).is_err());
+}
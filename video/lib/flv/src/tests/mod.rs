@@ -1,2 +1,3 @@
 mod demuxer;
 mod error;
+mod properties;
@@ -0,0 +1,22 @@
+use std::io;
+
+use bytes::Bytes;
+use proptest::prelude::*;
+
+use crate::{Flv, FlvTag};
+
+proptest! {
+	#[test]
+	fn test_demux_never_panics(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+		let _ = Flv::demux(&mut io::Cursor::new(Bytes::from(data)));
+	}
+
+	#[test]
+	fn test_tag_demux_never_panics(tag_type in 0..=32u8, data in prop::collection::vec(any::<u8>(), 0..2048)) {
+		let mut tag = vec![tag_type, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		tag[1..4].copy_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+		tag.extend(data);
+
+		let _ = FlvTag::demux(&mut io::Cursor::new(Bytes::from(tag)));
+	}
+}
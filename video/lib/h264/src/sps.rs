@@ -35,7 +35,7 @@ impl Sps {
 		// This is BARELY documented in the spec, but it's there.
 		// ISO/IEC-14496-10-2022 - 3.1.48
 		let mut i = 0;
-		while i < data.len() - 3 {
+		while i < data.len().saturating_sub(3) {
 			if data[i] == 0x00 && data[i + 1] == 0x00 && data[i + 2] == 0x03 {
 				vec.push(0x00);
 				vec.push(0x00);
@@ -117,10 +117,19 @@ impl Sps {
 			frame_crop_bottom_offset = read_exp_golomb(&mut bit_reader)?; // frame_crop_bottom_offset
 		}
 
-		let width = ((pic_width_in_mbs_minus1 + 1) * 16) - frame_crop_right_offset * 2 - frame_crop_left_offset * 2;
-		let height = ((2 - frame_mbs_only_flag as u64) * (pic_height_in_map_units_minus1 + 1) * 16)
-			- frame_crop_bottom_offset * 2
-			- frame_crop_top_offset * 2;
+		// These values come straight from the bitstream, so we have to guard against
+		// overflows and crops which are larger than the picture.
+		let invalid_dimensions = || io::Error::new(io::ErrorKind::InvalidData, "invalid picture dimensions");
+		let width = (pic_width_in_mbs_minus1 + 1)
+			.checked_mul(16)
+			.and_then(|width| width.checked_sub(frame_crop_right_offset.checked_add(frame_crop_left_offset)?.checked_mul(2)?))
+			.ok_or_else(invalid_dimensions)?;
+		let height = (pic_height_in_map_units_minus1 + 1)
+			.checked_mul((2 - frame_mbs_only_flag as u64) * 16)
+			.and_then(|height| {
+				height.checked_sub(frame_crop_bottom_offset.checked_add(frame_crop_top_offset)?.checked_mul(2)?)
+			})
+			.ok_or_else(invalid_dimensions)?;
 
 		let mut frame_rate = 0.0;
 
@@ -30,7 +30,7 @@ impl Sps {
 
 		// ISO/IEC-23008-2-2022 - 7.3.1.1
 		let mut i = 0;
-		while i < data.len() - 3 {
+		while i < data.len().saturating_sub(3) {
 			if data[i] == 0x00 && data[i + 1] == 0x00 && data[i + 2] == 0x03 {
 				vec.push(0x00);
 				vec.push(0x00);
@@ -143,8 +143,19 @@ impl Sps {
 			_ => return Err(io::Error::new(io::ErrorKind::InvalidData, "chroma_format_idc is not 0-3")),
 		};
 
-		let width = pic_width_in_luma_samples - sub_width_c * (conf_win_left_offset + conf_win_right_offset);
-		let height = pic_height_in_luma_samples - sub_height_c * (conf_win_top_offset + conf_win_bottom_offset);
+		// These values come straight from the bitstream, so we have to guard against
+		// overflows and conformance windows which are larger than the picture.
+		let invalid_dimensions = || io::Error::new(io::ErrorKind::InvalidData, "invalid picture dimensions");
+		let width = conf_win_left_offset
+			.checked_add(conf_win_right_offset)
+			.and_then(|offset| offset.checked_mul(sub_width_c))
+			.and_then(|offset| pic_width_in_luma_samples.checked_sub(offset))
+			.ok_or_else(invalid_dimensions)?;
+		let height = conf_win_top_offset
+			.checked_add(conf_win_bottom_offset)
+			.and_then(|offset| offset.checked_mul(sub_height_c))
+			.and_then(|offset| pic_height_in_luma_samples.checked_sub(offset))
+			.ok_or_else(invalid_dimensions)?;
 
 		read_exp_golomb(&mut bit_reader)?; // bit_depth_luma_minus8
 		read_exp_golomb(&mut bit_reader)?; // bit_depth_chroma_minus8
@@ -1,6 +1,7 @@
 use std::io;
 
 use bytes::Bytes;
+use proptest::prelude::*;
 
 use crate::sps::{ColorConfig, Sps};
 use crate::{HEVCDecoderConfigurationRecord, NaluType};
@@ -96,3 +97,24 @@ fn test_config_mux() {
 
 	assert_eq!(buf, data.to_vec());
 }
+
+proptest! {
+	#[test]
+	fn test_sps_parse_never_panics(data in prop::collection::vec(any::<u8>(), 0..512)) {
+		let _ = Sps::parse(Bytes::from(data));
+	}
+
+	#[test]
+	fn test_config_remux_is_stable(data in prop::collection::vec(any::<u8>(), 0..512)) {
+		let Ok(config) = HEVCDecoderConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from(data))) else {
+			return Ok(());
+		};
+
+		let mut muxed = Vec::new();
+		config.mux(&mut muxed).unwrap();
+		prop_assert_eq!(muxed.len() as u64, config.size());
+
+		let remuxed = HEVCDecoderConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from(muxed))).unwrap();
+		prop_assert_eq!(remuxed, config);
+	}
+}
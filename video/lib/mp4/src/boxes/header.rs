@@ -1,7 +1,5 @@
 use std::fmt::{Debug, Formatter};
-use std::io::{
-	Read, {self},
-};
+use std::io;
 
 use byteorder::{ReadBytesExt, WriteBytesExt};
 use bytes::Bytes;
@@ -28,28 +26,61 @@ impl Debug for BoxHeader {
 
 impl BoxHeader {
 	pub fn demux(reader: &mut io::Cursor<Bytes>) -> Result<(Self, Bytes), io::Error> {
+		let (header, _, data_size) = Self::read(reader)?;
+
+		let data = match data_size {
+			// As per spec this means the box extends to the end of the file.
+			None => reader.extract_remaining(),
+			Some(data_size) => reader.read_slice(data_size as usize)?,
+		};
+
+		Ok((header, data))
+	}
+
+	/// Read just the header of a box, leaving the reader positioned at the
+	/// start of the box data.
+	/// Returns the header, the size of the header in bytes and the size of the
+	/// box data. The data size is `None` if the box extends to the end of the
+	/// file.
+	pub fn read<R: io::Read>(reader: &mut R) -> io::Result<(Self, u64, Option<u64>)> {
 		let size = reader.read_u32::<byteorder::BigEndian>()? as u64;
 
 		let mut box_type: [u8; 4] = [0; 4];
 		reader.read_exact(&mut box_type)?;
 
-		let offset = if size == 1 { 16 } else { 8 };
-
-		let size = if size == 1 {
-			reader.read_u64::<byteorder::BigEndian>()?
+		let (header_size, size) = if size == 1 {
+			(16, reader.read_u64::<byteorder::BigEndian>()?)
 		} else {
-			size
+			(8, size)
 		};
 
-		let data = if size == 0 {
-			// As per spec this means the box extends to the end of the file.
-			reader.extract_remaining()
-		} else {
-			// We already read 8 bytes, so we need to subtract that from the size.
-			reader.read_slice((size - offset) as usize)?
-		};
+		Ok((Self { box_type }, header_size, Self::data_size(size, header_size)?))
+	}
+
+	/// Async version of [`BoxHeader::read`].
+	#[cfg(feature = "tokio")]
+	pub async fn read_async<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> io::Result<(Self, u64, Option<u64>)> {
+		use tokio::io::AsyncReadExt;
+
+		let size = reader.read_u32().await? as u64;
+
+		let mut box_type: [u8; 4] = [0; 4];
+		reader.read_exact(&mut box_type).await?;
+
+		let (header_size, size) = if size == 1 { (16, reader.read_u64().await?) } else { (8, size) };
+
+		Ok((Self { box_type }, header_size, Self::data_size(size, header_size)?))
+	}
+
+	fn data_size(size: u64, header_size: u64) -> io::Result<Option<u64>> {
+		if size == 0 {
+			return Ok(None);
+		}
 
-		Ok((Self { box_type }, data))
+		// The size includes the header we already read.
+		size.checked_sub(header_size)
+			.map(Some)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "box size is smaller than its header"))
 	}
 }
 
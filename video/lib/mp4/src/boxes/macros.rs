@@ -102,6 +102,12 @@ macro_rules! impl_box {
             pub fn demux(reader: &mut io::Cursor<Bytes>) -> io::Result<Self> {
                 let (header, data) = BoxHeader::demux(reader)?;
 
+                Self::demux_box(header, data)
+            }
+
+            /// Demux a box from a header which has already been read and the
+            /// box data.
+            pub fn demux_box(header: BoxHeader, data: Bytes) -> io::Result<Self> {
                 match_helper!(
                     [parse] & header.box_type,
                     header,
@@ -25,9 +25,11 @@ use crate::boxes::types::co64::Co64;
 use crate::boxes::types::colr::Colr;
 use crate::boxes::types::ctts::Ctts;
 use crate::boxes::types::dinf::Dinf;
+use crate::boxes::types::dops::DOps;
 use crate::boxes::types::dref::Dref;
 use crate::boxes::types::edts::Edts;
 use crate::boxes::types::elst::Elst;
+use crate::boxes::types::emsg::Emsg;
 use crate::boxes::types::esds::Esds;
 use crate::boxes::types::ftyp::Ftyp;
 use crate::boxes::types::hdlr::Hdlr;
@@ -84,5 +86,5 @@ impl_box!(
     Url, Avc1, Clap, Pasp, AvcC, Btrt,
     Mp4a, Esds, Moof, Mfhd, Traf, Tfhd,
     Tfdt, Trun, Mdat, Av01, Av1C, Colr,
-    Hev1, HvcC, Opus,
+    Hev1, HvcC, Opus, Emsg, DOps,
 );
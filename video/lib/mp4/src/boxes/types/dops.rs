@@ -0,0 +1,119 @@
+use std::io;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::Bytes;
+
+use crate::boxes::header::BoxHeader;
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Opus Specific Box
+/// Encapsulation of Opus in ISO Base Media File Format - Version 0.8.1 - 4.3.2
+pub struct DOps {
+	pub header: BoxHeader,
+	pub version: u8,
+	pub output_channel_count: u8,
+	pub pre_skip: u16,
+	pub input_sample_rate: u32,
+	pub output_gain: i16,
+	pub channel_mapping_family: u8,
+	pub channel_mapping_table: Option<ChannelMappingTable>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Only present when the channel mapping family is not 0.
+pub struct ChannelMappingTable {
+	pub stream_count: u8,
+	pub coupled_count: u8,
+	pub channel_mapping: Vec<u8>,
+}
+
+impl DOps {
+	pub fn new(
+		output_channel_count: u8,
+		pre_skip: u16,
+		input_sample_rate: u32,
+		output_gain: i16,
+		channel_mapping_family: u8,
+		channel_mapping_table: Option<ChannelMappingTable>,
+	) -> Self {
+		Self {
+			header: BoxHeader::new(Self::NAME),
+			version: 0,
+			output_channel_count,
+			pre_skip,
+			input_sample_rate,
+			output_gain,
+			channel_mapping_family,
+			channel_mapping_table,
+		}
+	}
+}
+
+impl BoxType for DOps {
+	const NAME: [u8; 4] = *b"dOps";
+
+	fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+		let mut reader = io::Cursor::new(data);
+
+		let version = reader.read_u8()?;
+		let output_channel_count = reader.read_u8()?;
+		let pre_skip = reader.read_u16::<BigEndian>()?;
+		let input_sample_rate = reader.read_u32::<BigEndian>()?;
+		let output_gain = reader.read_i16::<BigEndian>()?;
+		let channel_mapping_family = reader.read_u8()?;
+
+		let channel_mapping_table = if channel_mapping_family != 0 {
+			let stream_count = reader.read_u8()?;
+			let coupled_count = reader.read_u8()?;
+			let mut channel_mapping = vec![0; output_channel_count as usize];
+			io::Read::read_exact(&mut reader, &mut channel_mapping)?;
+
+			Some(ChannelMappingTable {
+				stream_count,
+				coupled_count,
+				channel_mapping,
+			})
+		} else {
+			None
+		};
+
+		Ok(Self {
+			header,
+			version,
+			output_channel_count,
+			pre_skip,
+			input_sample_rate,
+			output_gain,
+			channel_mapping_family,
+			channel_mapping_table,
+		})
+	}
+
+	fn primitive_size(&self) -> u64 {
+		1 // version
+        + 1 // output_channel_count
+        + 2 // pre_skip
+        + 4 // input_sample_rate
+        + 2 // output_gain
+        + 1 // channel_mapping_family
+        + self.channel_mapping_table.as_ref().map(|t| 2 + t.channel_mapping.len() as u64).unwrap_or(0)
+	}
+
+	fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+		writer.write_u8(self.version)?;
+		writer.write_u8(self.output_channel_count)?;
+		writer.write_u16::<BigEndian>(self.pre_skip)?;
+		writer.write_u32::<BigEndian>(self.input_sample_rate)?;
+		writer.write_i16::<BigEndian>(self.output_gain)?;
+		writer.write_u8(self.channel_mapping_family)?;
+
+		if let Some(table) = &self.channel_mapping_table {
+			writer.write_u8(table.stream_count)?;
+			writer.write_u8(table.coupled_count)?;
+			writer.write_all(&table.channel_mapping)?;
+		}
+
+		Ok(())
+	}
+}
@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -42,7 +42,7 @@ impl BoxType for Elst {
 
 		let entry_count = reader.read_u32::<BigEndian>()?;
 
-		let mut entries = Vec::with_capacity(entry_count as usize);
+		let mut entries = Vec::with_capacity((entry_count as usize).min(reader.remaining()));
 
 		for _ in 0..entry_count {
 			let (segment_duration, media_time, media_rate_integer, media_rate_fraction) = if header.version == 1 {
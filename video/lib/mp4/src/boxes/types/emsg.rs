@@ -0,0 +1,178 @@
+use std::io::{self, BufRead};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::Bytes;
+use bytesio::bytes_reader::BytesCursor;
+
+use crate::boxes::header::{BoxHeader, FullBoxHeader};
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Event Message Box
+/// ISO/IEC 23009-1:2022(E) - 5.10.3.3
+pub struct Emsg {
+	pub header: FullBoxHeader,
+	pub scheme_id_uri: String,
+	pub value: String,
+	pub timescale: u32,
+	/// For version 0 this is the presentation time delta from the earliest
+	/// presentation time of the segment, for version 1 it is the absolute
+	/// presentation time.
+	pub presentation_time: u64,
+	pub event_duration: u32,
+	pub id: u32,
+	pub message_data: Bytes,
+}
+
+impl Emsg {
+	pub fn new(
+		scheme_id_uri: impl Into<String>,
+		value: impl Into<String>,
+		timescale: u32,
+		presentation_time: u64,
+		event_duration: u32,
+		id: u32,
+		message_data: Bytes,
+	) -> Self {
+		Self {
+			header: FullBoxHeader::new(Self::NAME, 1, 0),
+			scheme_id_uri: scheme_id_uri.into(),
+			value: value.into(),
+			timescale,
+			presentation_time,
+			event_duration,
+			id,
+			message_data,
+		}
+	}
+
+	/// Converts the presentation time and duration of the event to another
+	/// timescale, rounding down, such as the timescale of the track the event
+	/// is written into.
+	pub fn rescale(&mut self, timescale: u32) {
+		if self.timescale == timescale || self.timescale == 0 || timescale == 0 {
+			return;
+		}
+
+		let rescale = |value: u64| (value as u128 * timescale as u128 / self.timescale as u128) as u64;
+
+		self.presentation_time = rescale(self.presentation_time);
+		self.event_duration = rescale(self.event_duration as u64).min(u32::MAX as u64) as u32;
+		self.timescale = timescale;
+	}
+}
+
+fn read_string(reader: &mut io::Cursor<Bytes>) -> io::Result<String> {
+	let mut buf = Vec::new();
+	reader.read_until(0, &mut buf)?;
+	if buf.pop() != Some(0) {
+		return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "emsg string is not null terminated"));
+	}
+
+	String::from_utf8(buf).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "emsg string is not valid utf8"))
+}
+
+impl BoxType for Emsg {
+	const NAME: [u8; 4] = *b"emsg";
+
+	fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+		let mut reader = io::Cursor::new(data);
+
+		let header = FullBoxHeader::demux(header, &mut reader)?;
+
+		let (scheme_id_uri, value, timescale, presentation_time, event_duration, id) = if header.version == 0 {
+			let scheme_id_uri = read_string(&mut reader)?;
+			let value = read_string(&mut reader)?;
+			let timescale = reader.read_u32::<BigEndian>()?;
+			let presentation_time_delta = reader.read_u32::<BigEndian>()? as u64;
+			let event_duration = reader.read_u32::<BigEndian>()?;
+			let id = reader.read_u32::<BigEndian>()?;
+
+			(scheme_id_uri, value, timescale, presentation_time_delta, event_duration, id)
+		} else {
+			let timescale = reader.read_u32::<BigEndian>()?;
+			let presentation_time = reader.read_u64::<BigEndian>()?;
+			let event_duration = reader.read_u32::<BigEndian>()?;
+			let id = reader.read_u32::<BigEndian>()?;
+			let scheme_id_uri = read_string(&mut reader)?;
+			let value = read_string(&mut reader)?;
+
+			(scheme_id_uri, value, timescale, presentation_time, event_duration, id)
+		};
+
+		let message_data = reader.extract_remaining();
+
+		Ok(Self {
+			header,
+			scheme_id_uri,
+			value,
+			timescale,
+			presentation_time,
+			event_duration,
+			id,
+			message_data,
+		})
+	}
+
+	fn primitive_size(&self) -> u64 {
+		self.header.size()
+			+ self.scheme_id_uri.len() as u64 + 1 // scheme_id_uri + null terminator
+			+ self.value.len() as u64 + 1 // value + null terminator
+			+ 4 // timescale
+			+ if self.header.version == 0 { 4 } else { 8 } // presentation_time
+			+ 4 // event_duration
+			+ 4 // id
+			+ self.message_data.len() as u64
+	}
+
+	fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+		self.header.mux(writer)?;
+
+		if self.header.version == 0 {
+			writer.write_all(self.scheme_id_uri.as_bytes())?;
+			writer.write_u8(0)?;
+			writer.write_all(self.value.as_bytes())?;
+			writer.write_u8(0)?;
+			writer.write_u32::<BigEndian>(self.timescale)?;
+			writer.write_u32::<BigEndian>(self.presentation_time as u32)?;
+			writer.write_u32::<BigEndian>(self.event_duration)?;
+			writer.write_u32::<BigEndian>(self.id)?;
+		} else {
+			writer.write_u32::<BigEndian>(self.timescale)?;
+			writer.write_u64::<BigEndian>(self.presentation_time)?;
+			writer.write_u32::<BigEndian>(self.event_duration)?;
+			writer.write_u32::<BigEndian>(self.id)?;
+			writer.write_all(self.scheme_id_uri.as_bytes())?;
+			writer.write_u8(0)?;
+			writer.write_all(self.value.as_bytes())?;
+			writer.write_u8(0)?;
+		}
+
+		writer.write_all(&self.message_data)?;
+
+		Ok(())
+	}
+
+	fn validate(&self) -> io::Result<()> {
+		if self.header.version > 1 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "emsg version must be 0 or 1"));
+		}
+
+		if self.header.flags != 0 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "emsg flags must be 0"));
+		}
+
+		if self.header.version == 0 && self.presentation_time > u32::MAX as u64 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"emsg version 0 presentation time delta must fit in 32 bits",
+			));
+		}
+
+		if self.scheme_id_uri.contains('\0') || self.value.contains('\0') {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "emsg strings must not contain null bytes"));
+		}
+
+		Ok(())
+	}
+}
@@ -31,6 +31,10 @@ impl BoxType for Ftyp {
 	const NAME: [u8; 4] = *b"ftyp";
 
 	fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+		if data.len() < 8 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "ftyp box is too small"));
+		}
+
 		let major_brand =
 			FourCC::from(TryInto::<[u8; 4]>::try_into(data.slice(0..4).as_ref()).expect("slice is 4 bytes long"));
 		let minor_version = data.slice(4..8).as_ref().read_u32::<byteorder::BigEndian>()?;
@@ -77,6 +81,7 @@ pub enum FourCC {
 	Avc1,
 	Av01,
 	Hev1,
+	Opus,
 	Unknown([u8; 4]),
 }
 
@@ -89,6 +94,7 @@ impl FourCC {
 			Self::Avc1 => *b"avc1",
 			Self::Av01 => *b"av01",
 			Self::Hev1 => *b"hev1",
+			Self::Opus => *b"Opus",
 			Self::Unknown(bytes) => *bytes,
 		}
 	}
@@ -103,6 +109,7 @@ impl From<[u8; 4]> for FourCC {
 			b"avc1" => Self::Avc1,
 			b"av01" => Self::Av01,
 			b"hev1" => Self::Hev1,
+			b"Opus" => Self::Opus,
 			_ => Self::Unknown(bytes),
 		}
 	}
@@ -8,9 +8,11 @@ pub mod co64;
 pub mod colr;
 pub mod ctts;
 pub mod dinf;
+pub mod dops;
 pub mod dref;
 pub mod edts;
 pub mod elst;
+pub mod emsg;
 pub mod esds;
 pub mod ftyp;
 pub mod hdlr;
@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -22,8 +22,8 @@ impl BoxType for Padb {
 
 		let header = FullBoxHeader::demux(header, &mut reader)?;
 
-		let sample_count = (reader.read_u32::<BigEndian>()? + 1) / 2;
-		let mut samples = Vec::with_capacity(sample_count as usize);
+		let sample_count = reader.read_u32::<BigEndian>()?.div_ceil(2);
+		let mut samples = Vec::with_capacity((sample_count as usize).min(reader.remaining()));
 
 		for _ in 0..sample_count {
 			let byte = reader.read_u8()?;
@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -36,7 +36,7 @@ impl BoxType for Sbgp {
 		};
 		let entry_count = data.read_u32::<BigEndian>()?;
 
-		let mut entries = Vec::with_capacity(entry_count as usize);
+		let mut entries = Vec::with_capacity((entry_count as usize).min(data.remaining()));
 
 		for _ in 0..entry_count {
 			let sample_count = data.read_u32::<BigEndian>()?;
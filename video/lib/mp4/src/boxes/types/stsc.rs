@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -40,7 +40,7 @@ impl BoxType for Stsc {
 		let header = FullBoxHeader::demux(header, &mut reader)?;
 
 		let entry_count = reader.read_u32::<BigEndian>()?;
-		let mut entries = Vec::with_capacity(entry_count as usize);
+		let mut entries = Vec::with_capacity((entry_count as usize).min(reader.remaining()));
 		for _ in 0..entry_count {
 			let first_chunk = reader.read_u32::<BigEndian>()?;
 			let samples_per_chunk = reader.read_u32::<BigEndian>()?;
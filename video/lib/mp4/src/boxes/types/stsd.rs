@@ -4,7 +4,7 @@ use std::io::{
 };
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use super::clap::Clap;
 use super::colr::Colr;
@@ -376,7 +376,7 @@ impl BoxType for Stsd {
 		let header = FullBoxHeader::demux(header, &mut reader)?;
 
 		let entry_count = reader.read_u32::<BigEndian>()?;
-		let mut entries = Vec::with_capacity(entry_count as usize);
+		let mut entries = Vec::with_capacity((entry_count as usize).min(reader.remaining()));
 
 		for _ in 0..entry_count {
 			let entry = DynBox::demux(&mut reader)?;
@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -36,7 +36,7 @@ impl BoxType for Stsz {
 		let sample_size = reader.read_u32::<BigEndian>()?;
 		let sample_count = reader.read_u32::<BigEndian>()?;
 
-		let mut samples = Vec::with_capacity(sample_count as usize);
+		let mut samples = Vec::with_capacity((sample_count as usize).min(reader.remaining()));
 		if sample_size == 0 {
 			for _ in 0..sample_count {
 				let size = reader.read_u32::<BigEndian>()?;
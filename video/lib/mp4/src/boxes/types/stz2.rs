@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -29,7 +29,7 @@ impl BoxType for Stz2 {
 		let field_size = reader.read_u8()?;
 		let sample_count = reader.read_u32::<BigEndian>()?;
 
-		let mut samples = Vec::with_capacity(sample_count as usize);
+		let mut samples = Vec::with_capacity((sample_count as usize).min(reader.remaining()));
 
 		let mut sample_idx = 0;
 		while sample_idx < sample_count {
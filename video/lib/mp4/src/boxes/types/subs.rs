@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -40,12 +40,12 @@ impl BoxType for Subs {
 		let header = FullBoxHeader::demux(header, &mut reader)?;
 
 		let entry_count = reader.read_u32::<BigEndian>()?;
-		let mut entries = Vec::with_capacity(entry_count as usize);
+		let mut entries = Vec::with_capacity((entry_count as usize).min(reader.remaining()));
 
 		for _ in 0..entry_count {
 			let sample_delta = reader.read_u32::<BigEndian>()?;
 			let subsample_count = reader.read_u16::<BigEndian>()?;
-			let mut subsamples = Vec::with_capacity(subsample_count as usize);
+			let mut subsamples = Vec::with_capacity((subsample_count as usize).min(reader.remaining()));
 
 			for _ in 0..subsample_count {
 				let subsample_size = if header.version == 1 {
@@ -1,7 +1,7 @@
 use std::io;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
@@ -165,6 +165,10 @@ impl Trun {
 	pub const FLAG_SAMPLE_DURATION: u32 = 0x000100;
 	pub const FLAG_SAMPLE_FLAGS: u32 = 0x000400;
 	pub const FLAG_SAMPLE_SIZE: u32 = 0x000200;
+	/// Samples without any per-sample fields take up no space in the box, so
+	/// the sample count has to be capped to stop a tiny box from making us
+	/// allocate an unbounded number of samples.
+	pub const MAX_EMPTY_SAMPLE_COUNT: u32 = 1 << 20;
 }
 
 impl BoxType for Trun {
@@ -189,7 +193,15 @@ impl BoxType for Trun {
 			None
 		};
 
-		let mut samples = Vec::with_capacity(sample_count as usize);
+		let per_sample_fields = Self::FLAG_SAMPLE_DURATION
+			| Self::FLAG_SAMPLE_SIZE
+			| Self::FLAG_SAMPLE_FLAGS
+			| Self::FLAG_SAMPLE_COMPOSITION_TIME_OFFSET;
+		if header.flags & per_sample_fields == 0 && sample_count > Self::MAX_EMPTY_SAMPLE_COUNT {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "trun sample count is too large"));
+		}
+
+		let mut samples = Vec::with_capacity((sample_count as usize).min(reader.remaining()));
 		for _ in 0..sample_count {
 			let duration = if header.flags & Self::FLAG_SAMPLE_DURATION != 0 {
 				Some(reader.read_u32::<BigEndian>()?)
@@ -48,21 +48,34 @@ impl fmt::Display for VideoCodec {
 				tier,
 				profile_compatibility,
 				constraint_indicator,
-			} => write!(
-				f,
-				"hev1.{}{:x}.{:x}.{}{:x}.{:x}",
-				match general_profile_space {
-					1 => "A",
-					2 => "B",
-					3 => "C",
-					_ => "",
-				},
-				profile, // 1 or 2 chars (hex)
-				profile_compatibility,
-				if *tier { 'H' } else { 'L' },
-				level, // 1 or 2 chars (hex)
-				constraint_indicator,
-			),
+			} => {
+				// The compatibility and constraint flags are stored in the order they are read
+				// from the hvcC box (little endian). The codec string wants the compatibility
+				// flags bit reversed and the constraint flags as bytes, with trailing zero
+				// bytes omitted. https://www.rfc-editor.org/rfc/rfc6381 (ISO/IEC 14496-15 Annex E)
+				write!(
+					f,
+					"hev1.{}{}.{:X}.{}{}",
+					match general_profile_space {
+						1 => "A",
+						2 => "B",
+						3 => "C",
+						_ => "",
+					},
+					profile,
+					u32::from_be_bytes(profile_compatibility.to_le_bytes()).reverse_bits(),
+					if *tier { 'H' } else { 'L' },
+					level,
+				)?;
+
+				let constraints = &constraint_indicator.to_le_bytes()[..6];
+				let len = constraints.iter().rposition(|b| *b != 0).map(|i| i + 1).unwrap_or(1);
+				for byte in &constraints[..len] {
+					write!(f, ".{:02X}", byte)?;
+				}
+
+				Ok(())
+			}
 			VideoCodec::Av1 {
 				profile,
 				level,
@@ -77,7 +90,7 @@ impl fmt::Display for VideoCodec {
 				full_range_flag,
 			} => write!(
 				f,
-				"av01.{}.{}{}.{:02}.{}.{}{}{}.{:02}.{:02}.{:02}.{}",
+				"av01.{}.{:02}{}.{:02}.{}.{}{}{}.{:02}.{:02}.{:02}.{}",
 				profile,
 				level,
 				if *tier { 'H' } else { 'M' },
@@ -124,36 +137,48 @@ impl FromStr for VideoCodec {
 				})
 			}
 			"hev1" => {
-				if splits.len() < 6 {
+				if splits.len() < 4 {
 					return Err("invalid codec, missing profile".into());
 				}
 
-				let general_profile_space = match splits[1] {
-					"A" => 1,
-					"B" => 2,
-					"C" => 3,
-					_ => {
-						return Err(format!("invalid codec, invalid general profile space: {}", splits[1]));
-					}
-				};
-
-				let profile = u8::from_str_radix(splits[2], 16)
-					.map_err(|e| format!("invalid codec, invalid profile: {}, {}", splits[2], e))?;
+				let (general_profile_space, profile) =
+					match splits[1].split_at(splits[1].find(|c: char| c.is_ascii_digit()).unwrap_or(0)) {
+						("", profile) => (0, profile),
+						("A", profile) => (1, profile),
+						("B", profile) => (2, profile),
+						("C", profile) => (3, profile),
+						(space, _) => {
+							return Err(format!("invalid codec, invalid general profile space: {}", space));
+						}
+					};
+
+				let profile = profile
+					.parse::<u8>()
+					.map_err(|e| format!("invalid codec, invalid profile: {}, {}", splits[1], e))?;
 
-				let profile_compatibility = u32::from_str_radix(splits[3], 16)
-					.map_err(|e| format!("invalid codec, invalid profile compatibility: {}, {}", splits[3], e))?;
+				let profile_compatibility = u32::from_str_radix(splits[2], 16)
+					.map_err(|e| format!("invalid codec, invalid profile compatibility: {}, {}", splits[2], e))?;
+				let profile_compatibility = u32::from_le_bytes(profile_compatibility.reverse_bits().to_be_bytes());
 
-				let tier = match splits[4] {
-					"H" => true,
-					"L" => false,
-					_ => return Err(format!("invalid codec, invalid tier: {}", splits[4])),
+				let tier = match splits[3].get(..1) {
+					Some("H") => true,
+					Some("L") => false,
+					_ => return Err(format!("invalid codec, invalid tier: {}", splits[3])),
 				};
 
-				let level = u8::from_str_radix(splits[5], 16)
-					.map_err(|e| format!("invalid codec, invalid level: {}, {}", splits[5], e))?;
+				let level = splits[3][1..]
+					.parse::<u8>()
+					.map_err(|e| format!("invalid codec, invalid level: {}, {}", splits[3], e))?;
 
-				let constraint_indicator = u64::from_str_radix(splits[6], 16)
-					.map_err(|e| format!("invalid codec, invalid constraint indicator: {}, {}", splits[6], e))?;
+				if splits.len() > 10 {
+					return Err("invalid codec, too many constraint bytes".into());
+				}
+
+				let mut constraints = [0; 8];
+				for (i, byte) in splits[4..].iter().enumerate() {
+					constraints[i] = u8::from_str_radix(byte, 16)
+						.map_err(|e| format!("invalid codec, invalid constraint indicator: {}, {}", byte, e))?;
+				}
 
 				Ok(VideoCodec::Hevc {
 					general_profile_space,
@@ -161,67 +186,65 @@ impl FromStr for VideoCodec {
 					level,
 					tier,
 					profile_compatibility,
-					constraint_indicator,
+					constraint_indicator: u64::from_le_bytes(constraints),
 				})
 			}
 			"av01" => {
-				if splits.len() < 12 {
+				if splits.len() < 10 {
 					return Err("invalid codec, missing profile".into());
 				}
 
-				let profile = u8::from_str_radix(splits[1], 16)
+				let profile = splits[1]
+					.parse::<u8>()
 					.map_err(|e| format!("invalid codec, invalid profile: {}, {}", splits[1], e))?;
 
-				let level = u8::from_str_radix(splits[2], 16)
+				let (level, tier) = splits[2].split_at(splits[2].len().saturating_sub(1));
+
+				let level = level
+					.parse::<u8>()
 					.map_err(|e| format!("invalid codec, invalid level: {}, {}", splits[2], e))?;
 
-				let tier = match splits[3] {
+				let tier = match tier {
 					"H" => true,
 					"M" => false,
-					_ => return Err(format!("invalid codec, invalid tier: {}", splits[3])),
+					_ => return Err(format!("invalid codec, invalid tier: {}", splits[2])),
 				};
 
-				let depth = splits[4]
+				let depth = splits[3]
 					.parse::<u8>()
-					.map_err(|e| format!("invalid codec, invalid depth: {}, {}", splits[4], e))?;
+					.map_err(|e| format!("invalid codec, invalid depth: {}, {}", splits[3], e))?;
 
-				let monochrome = match splits[5] {
+				let monochrome = match splits[4] {
 					"1" => true,
 					"0" => false,
-					_ => return Err(format!("invalid codec, invalid monochrome: {}", splits[5])),
+					_ => return Err(format!("invalid codec, invalid monochrome: {}", splits[4])),
 				};
 
-				let sub_sampling_x = match splits[6] {
-					"1" => true,
-					"0" => false,
-					_ => {
-						return Err(format!("invalid codec, invalid sub_sampling_x: {}", splits[6]));
-					}
-				};
-
-				let sub_sampling_y = match splits[7] {
-					"1" => true,
-					"0" => false,
+				let (sub_sampling_x, sub_sampling_y) = match splits[5].get(..2) {
+					Some("00") => (false, false),
+					Some("01") => (false, true),
+					Some("10") => (true, false),
+					Some("11") => (true, true),
 					_ => {
-						return Err(format!("invalid codec, invalid sub_sampling_y: {}", splits[7]));
+						return Err(format!("invalid codec, invalid chroma subsampling: {}", splits[5]));
 					}
 				};
 
-				let color_primaries = splits[8]
+				let color_primaries = splits[6]
 					.parse::<u8>()
-					.map_err(|e| format!("invalid codec, invalid color_primaries: {}, {}", splits[8], e))?;
+					.map_err(|e| format!("invalid codec, invalid color_primaries: {}, {}", splits[6], e))?;
 
-				let transfer_characteristics = splits[9]
+				let transfer_characteristics = splits[7]
 					.parse::<u8>()
-					.map_err(|e| format!("invalid codec, invalid transfer_characteristics: {}, {}", splits[9], e))?;
+					.map_err(|e| format!("invalid codec, invalid transfer_characteristics: {}, {}", splits[7], e))?;
 
-				let matrix_coefficients = splits[10]
+				let matrix_coefficients = splits[8]
 					.parse::<u8>()
-					.map_err(|e| format!("invalid codec, invalid matrix_coefficients: {}, {}", splits[10], e))?;
+					.map_err(|e| format!("invalid codec, invalid matrix_coefficients: {}, {}", splits[8], e))?;
 
-				let full_range_flag = splits[11]
+				let full_range_flag = splits[9]
 					.parse::<u8>()
-					.map_err(|e| format!("invalid codec, invalid full_range_flag: {}, {}", splits[11], e))?
+					.map_err(|e| format!("invalid codec, invalid full_range_flag: {}, {}", splits[9], e))?
 					== 1;
 
 				Ok(VideoCodec::Av1 {
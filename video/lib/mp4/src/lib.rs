@@ -1,6 +1,7 @@
 mod boxes;
 
 pub mod codec;
+pub mod reader;
 
 pub use boxes::{header, types, BoxType, DynBox};
 
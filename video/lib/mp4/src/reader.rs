@@ -0,0 +1,222 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use bytes::Bytes;
+
+use crate::header::BoxHeader;
+use crate::DynBox;
+
+/// Where a box lives in a stream, without its data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxLocation {
+	pub header: BoxHeader,
+	/// The offset of the start of the box (including the header) in the stream.
+	pub offset: u64,
+	pub header_size: u64,
+	pub data_size: u64,
+}
+
+impl BoxLocation {
+	/// The offset of the box data in the stream.
+	pub fn data_offset(&self) -> u64 {
+		self.offset + self.header_size
+	}
+
+	/// The total size of the box, including the header.
+	pub fn size(&self) -> u64 {
+		self.header_size + self.data_size
+	}
+
+	/// The offset of the first byte after the box.
+	pub fn end(&self) -> u64 {
+		self.offset + self.size()
+	}
+
+	/// Checks that the box fits in the stream, so none of the offsets above can
+	/// overflow.
+	fn new(header: BoxHeader, offset: u64, header_size: u64, data_size: Option<u64>, end: u64) -> io::Result<Self> {
+		if end < offset {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"box starts past the end of the stream",
+			));
+		}
+
+		let overflow = || io::Error::new(io::ErrorKind::InvalidData, "box size overflows");
+
+		let data_offset = offset.checked_add(header_size).ok_or_else(overflow)?;
+		// A box without a size extends to the end of the stream.
+		let data_size = data_size.unwrap_or(end.saturating_sub(data_offset));
+		let box_end = data_offset.checked_add(data_size).ok_or_else(overflow)?;
+
+		let location = Self {
+			header,
+			offset,
+			header_size,
+			data_size,
+		};
+
+		if data_offset > end || box_end > end {
+			return Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"box extends past the end of the stream",
+			));
+		}
+
+		Ok(location)
+	}
+
+	fn check_range(&self, offset: u64, len: usize) -> io::Result<u64> {
+		match offset.checked_add(len as u64) {
+			Some(end) if end <= self.data_size => Ok(self.data_offset() + offset),
+			_ => Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"range is outside of the box data",
+			)),
+		}
+	}
+}
+
+/// Walks the boxes in a seekable stream, reading only the box headers.
+/// Box data (such as a large `mdat`) is only read when asked for, so files
+/// do not need to fit in memory.
+pub struct BoxReader<R> {
+	reader: R,
+	position: u64,
+	end: u64,
+}
+
+impl<R: Read + Seek> BoxReader<R> {
+	/// Create a reader over the boxes from the current position to the end of
+	/// the stream.
+	pub fn new(mut reader: R) -> io::Result<Self> {
+		let position = reader.stream_position()?;
+		let end = reader.seek(SeekFrom::End(0))?;
+
+		Ok(Self { reader, position, end })
+	}
+
+	/// Create a reader over the child boxes of a container box, such as `moov`.
+	pub fn children(&mut self, location: &BoxLocation) -> BoxReader<&mut R> {
+		BoxReader {
+			reader: &mut self.reader,
+			position: location.data_offset(),
+			end: location.end(),
+		}
+	}
+
+	/// Read the header of the next box and skip over its data.
+	pub fn next_box(&mut self) -> io::Result<Option<BoxLocation>> {
+		if self.position >= self.end {
+			return Ok(None);
+		}
+
+		self.reader.seek(SeekFrom::Start(self.position))?;
+		let (header, header_size, data_size) = BoxHeader::read(&mut self.reader)?;
+
+		let location = BoxLocation::new(header, self.position, header_size, data_size, self.end)?;
+		self.position = location.end();
+
+		Ok(Some(location))
+	}
+
+	/// Read `len` bytes of the box data starting at `offset` into the data.
+	pub fn read_data(&mut self, location: &BoxLocation, offset: u64, len: usize) -> io::Result<Bytes> {
+		let start = location.check_range(offset, len)?;
+
+		self.reader.seek(SeekFrom::Start(start))?;
+		let mut data = vec![0; len];
+		self.reader.read_exact(&mut data)?;
+
+		Ok(data.into())
+	}
+
+	/// Read the whole box into memory and demux it.
+	/// For `mdat` prefer [`BoxReader::read_data`] to read only the samples
+	/// you need.
+	pub fn demux(&mut self, location: &BoxLocation) -> io::Result<DynBox> {
+		let len = usize::try_from(location.data_size)
+			.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "box is too large to demux"))?;
+		let data = self.read_data(location, 0, len)?;
+
+		DynBox::demux_box(location.header.clone(), data)
+	}
+
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+}
+
+#[cfg(feature = "tokio")]
+pub use self::tokio_reader::AsyncBoxReader;
+
+#[cfg(feature = "tokio")]
+mod tokio_reader {
+	use std::io::{self, SeekFrom};
+
+	use bytes::Bytes;
+	use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+	use super::BoxLocation;
+	use crate::header::BoxHeader;
+	use crate::DynBox;
+
+	/// Async version of [`super::BoxReader`].
+	pub struct AsyncBoxReader<R> {
+		reader: R,
+		position: u64,
+		end: u64,
+	}
+
+	impl<R: AsyncRead + AsyncSeek + Unpin> AsyncBoxReader<R> {
+		pub async fn new(mut reader: R) -> io::Result<Self> {
+			let position = reader.stream_position().await?;
+			let end = reader.seek(SeekFrom::End(0)).await?;
+
+			Ok(Self { reader, position, end })
+		}
+
+		pub fn children(&mut self, location: &BoxLocation) -> AsyncBoxReader<&mut R> {
+			AsyncBoxReader {
+				reader: &mut self.reader,
+				position: location.data_offset(),
+				end: location.end(),
+			}
+		}
+
+		pub async fn next_box(&mut self) -> io::Result<Option<BoxLocation>> {
+			if self.position >= self.end {
+				return Ok(None);
+			}
+
+			self.reader.seek(SeekFrom::Start(self.position)).await?;
+			let (header, header_size, data_size) = BoxHeader::read_async(&mut self.reader).await?;
+
+			let location = BoxLocation::new(header, self.position, header_size, data_size, self.end)?;
+			self.position = location.end();
+
+			Ok(Some(location))
+		}
+
+		pub async fn read_data(&mut self, location: &BoxLocation, offset: u64, len: usize) -> io::Result<Bytes> {
+			let start = location.check_range(offset, len)?;
+
+			self.reader.seek(SeekFrom::Start(start)).await?;
+			let mut data = vec![0; len];
+			self.reader.read_exact(&mut data).await?;
+
+			Ok(data.into())
+		}
+
+		pub async fn demux(&mut self, location: &BoxLocation) -> io::Result<DynBox> {
+			let len = usize::try_from(location.data_size)
+				.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "box is too large to demux"))?;
+			let data = self.read_data(location, 0, len).await?;
+
+			DynBox::demux_box(location.header.clone(), data)
+		}
+
+		pub fn into_inner(self) -> R {
+			self.reader
+		}
+	}
+}
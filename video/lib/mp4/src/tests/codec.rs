@@ -0,0 +1,56 @@
+use crate::codec::VideoCodec;
+
+#[test]
+fn test_hevc_codec_string() {
+	// Values as they are read from the hvcC box of a HEVC Main 5.1 stream.
+	let codec = VideoCodec::Hevc {
+		general_profile_space: 0,
+		profile_compatibility: 0x60,
+		profile: 1,
+		level: 153,
+		tier: false,
+		constraint_indicator: 0x90,
+	};
+
+	assert_eq!(codec.to_string(), "hev1.1.6.L153.90");
+	assert_eq!("hev1.1.6.L153.90".parse::<VideoCodec>().unwrap(), codec);
+
+	let codec = VideoCodec::Hevc {
+		general_profile_space: 2,
+		profile_compatibility: 0x20,
+		profile: 2,
+		level: 120,
+		tier: true,
+		constraint_indicator: 0,
+	};
+
+	assert_eq!(codec.to_string(), "hev1.B2.4.H120.00");
+	assert_eq!("hev1.B2.4.H120.00".parse::<VideoCodec>().unwrap(), codec);
+	assert_eq!("hev1.B2.4.H120".parse::<VideoCodec>().unwrap(), codec);
+
+	assert!("hev1.D1.6.L153.90".parse::<VideoCodec>().is_err());
+	assert!("hev1.1.6.X153.90".parse::<VideoCodec>().is_err());
+}
+
+#[test]
+fn test_av1_codec_string() {
+	let codec = VideoCodec::Av1 {
+		profile: 0,
+		level: 8,
+		tier: false,
+		depth: 8,
+		monochrome: false,
+		sub_sampling_x: true,
+		sub_sampling_y: true,
+		color_primaries: 1,
+		transfer_characteristics: 1,
+		matrix_coefficients: 1,
+		full_range_flag: false,
+	};
+
+	assert_eq!(codec.to_string(), "av01.0.08M.08.0.110.01.01.01.0");
+	assert_eq!("av01.0.08M.08.0.110.01.01.01.0".parse::<VideoCodec>().unwrap(), codec);
+
+	assert!("av01.0.08X.08.0.110.01.01.01.0".parse::<VideoCodec>().is_err());
+	assert!("av01.0.08M.08".parse::<VideoCodec>().is_err());
+}
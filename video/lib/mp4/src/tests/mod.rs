@@ -1 +1,4 @@
+mod codec;
 mod demux;
+mod properties;
+mod reader;
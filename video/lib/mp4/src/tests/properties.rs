@@ -0,0 +1,141 @@
+use std::io;
+
+use bytes::{Buf, Bytes};
+use proptest::prelude::*;
+
+use crate::types::dops::{ChannelMappingTable, DOps};
+use crate::types::emsg::Emsg;
+use crate::types::opus::Opus;
+use crate::types::stsd::{AudioSampleEntry, SampleEntry};
+use crate::DynBox;
+
+fn demux_all(data: Bytes) -> io::Result<Vec<DynBox>> {
+	let mut reader = io::Cursor::new(data);
+	let mut boxes = Vec::new();
+	while reader.has_remaining() {
+		boxes.push(DynBox::demux(&mut reader)?);
+	}
+
+	Ok(boxes)
+}
+
+fn box_strategy() -> impl Strategy<Value = Vec<u8>> {
+	// Generate well formed box headers with random payloads, so that the
+	// generated data makes it past the header into the individual box parsers.
+	let box_type = prop::sample::select(vec![
+		*b"ftyp", *b"moov", *b"mvhd", *b"trak", *b"tkhd", *b"mdia", *b"mdhd", *b"hdlr", *b"minf", *b"stbl", *b"stsd",
+		*b"stts", *b"stsc", *b"stsz", *b"stco", *b"co64", *b"ctts", *b"stss", *b"elst", *b"edts", *b"moof", *b"traf",
+		*b"tfhd", *b"tfdt", *b"trun", *b"avc1", *b"avcC", *b"hev1", *b"hvcC", *b"av01", *b"av1C", *b"mp4a", *b"esds",
+		*b"opus", *b"Opus", *b"dOps", *b"colr", *b"subs", *b"sbgp", *b"mdat", *b"emsg",
+	]);
+
+	(box_type, prop::collection::vec(any::<u8>(), 0..512)).prop_map(|(box_type, payload)| {
+		let mut data = Vec::with_capacity(payload.len() + 8);
+		data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+		data.extend_from_slice(&box_type);
+		data.extend(payload);
+		data
+	})
+}
+
+#[test]
+fn test_emsg_round_trip() {
+	let mut v0 = Emsg::new("urn:test", "event", 1000, 1234, 10, 7, Bytes::from_static(b"hello"));
+	v0.header.version = 0;
+	let v1 = Emsg::new("urn:test", "", 90000, u32::MAX as u64 + 1, 0, 8, Bytes::new());
+
+	for emsg in [v0, v1] {
+		let mut data = Vec::new();
+		DynBox::Emsg(emsg.clone()).mux(&mut data).unwrap();
+		assert_eq!(data.len() as u64, DynBox::Emsg(emsg.clone()).size());
+
+		match demux_all(Bytes::from(data)).unwrap().as_slice() {
+			[DynBox::Emsg(demuxed)] => assert_eq!(demuxed, &emsg),
+			other => panic!("unexpected boxes: {:?}", other),
+		}
+	}
+}
+
+#[test]
+fn test_emsg_rescale() {
+	let mut emsg = Emsg::new("urn:test", "event", 60000, 120_000, 30_000, 1, Bytes::new());
+
+	// From a 60fps video timescale to a 48khz audio timescale
+	emsg.rescale(48000);
+	assert_eq!(emsg.timescale, 48000);
+	assert_eq!(emsg.presentation_time, 96_000);
+	assert_eq!(emsg.event_duration, 24_000);
+
+	// Large times do not overflow
+	let mut emsg = Emsg::new("urn:test", "event", 1000, u64::MAX / 1000, 0, 1, Bytes::new());
+	emsg.rescale(1000 * 1000);
+	assert_eq!(emsg.presentation_time, u64::MAX / 1000 * 1000);
+
+	// An unknown timescale leaves the event as it is
+	let mut emsg = Emsg::new("urn:test", "event", 1000, 1234, 0, 1, Bytes::new());
+	emsg.rescale(0);
+	assert_eq!((emsg.timescale, emsg.presentation_time), (1000, 1234));
+}
+
+#[test]
+fn test_opus_round_trip() {
+	let stereo = DOps::new(2, 312, 48000, 0, 0, None);
+	let surround = DOps::new(
+		6,
+		312,
+		48000,
+		-256,
+		1,
+		Some(ChannelMappingTable {
+			stream_count: 4,
+			coupled_count: 2,
+			channel_mapping: vec![0, 4, 1, 2, 3, 5],
+		}),
+	);
+
+	for dops in [stereo, surround] {
+		let opus = Opus::new(
+			SampleEntry::new(AudioSampleEntry::new(dops.output_channel_count as u16, 16, 48000)),
+			dops,
+			None,
+		);
+
+		let mut data = Vec::new();
+		DynBox::Opus(opus.clone()).mux(&mut data).unwrap();
+		assert_eq!(data.len() as u64, DynBox::Opus(opus.clone()).size());
+
+		match demux_all(Bytes::from(data)).unwrap().as_slice() {
+			[DynBox::Opus(demuxed)] => assert_eq!(demuxed, &opus),
+			other => panic!("unexpected boxes: {:?}", other),
+		}
+	}
+}
+
+proptest! {
+	#[test]
+	fn test_demux_never_panics(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+		let _ = demux_all(Bytes::from(data));
+	}
+
+	#[test]
+	fn test_box_demux_never_panics(data in box_strategy()) {
+		let _ = demux_all(Bytes::from(data));
+	}
+
+	#[test]
+	fn test_remux_is_stable(data in box_strategy()) {
+		// Any box we can demux must survive a mux/demux round trip unchanged.
+		let Ok(boxes) = demux_all(Bytes::from(data)) else {
+			return Ok(());
+		};
+
+		let mut muxed = Vec::new();
+		for box_ in &boxes {
+			if box_.mux(&mut muxed).is_err() {
+				return Ok(());
+			}
+		}
+
+		prop_assert_eq!(demux_all(Bytes::from(muxed)).unwrap(), boxes);
+	}
+}
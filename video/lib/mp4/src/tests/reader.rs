@@ -0,0 +1,154 @@
+use std::io::{self, Cursor};
+
+use bytes::Bytes;
+
+use crate::boxes::header::BoxHeader;
+use crate::reader::BoxReader;
+use crate::DynBox;
+
+fn write_box(out: &mut Vec<u8>, box_type: &[u8; 4], data: &[u8]) {
+	out.extend_from_slice(&(data.len() as u32 + 8).to_be_bytes());
+	out.extend_from_slice(box_type);
+	out.extend_from_slice(data);
+}
+
+fn test_file() -> Vec<u8> {
+	let mut file = Vec::new();
+
+	// ftyp: major brand, minor version, one compatible brand
+	write_box(&mut file, b"ftyp", b"isom\0\0\x02\0isom");
+
+	let mut moov = Vec::new();
+	write_box(&mut moov, b"free", &[1, 2, 3]);
+	write_box(&mut moov, b"skip", &[]);
+	write_box(&mut file, b"moov", &moov);
+
+	// mdat with a 64-bit size
+	file.extend_from_slice(&1u32.to_be_bytes());
+	file.extend_from_slice(b"mdat");
+	file.extend_from_slice(&(16u64 + 256).to_be_bytes());
+	file.extend((0..=255).map(|i| i as u8));
+
+	file
+}
+
+#[test]
+fn test_box_reader_walk() {
+	let mut reader = BoxReader::new(Cursor::new(test_file())).unwrap();
+
+	let ftyp = reader.next_box().unwrap().unwrap();
+	assert_eq!(&ftyp.header.box_type, b"ftyp");
+	assert_eq!(ftyp.offset, 0);
+	assert_eq!(ftyp.header_size, 8);
+	assert_eq!(ftyp.data_size, 12);
+
+	let moov = reader.next_box().unwrap().unwrap();
+	assert_eq!(&moov.header.box_type, b"moov");
+	assert_eq!(moov.offset, 20);
+	assert_eq!(moov.size(), 8 + 11 + 8);
+
+	let mdat = reader.next_box().unwrap().unwrap();
+	assert_eq!(&mdat.header.box_type, b"mdat");
+	assert_eq!(mdat.offset, moov.end());
+	assert_eq!(mdat.header_size, 16);
+	assert_eq!(mdat.data_size, 256);
+
+	assert!(reader.next_box().unwrap().is_none());
+
+	let mut children = reader.children(&moov);
+	let free = children.next_box().unwrap().unwrap();
+	assert_eq!(&free.header.box_type, b"free");
+	assert_eq!(free.data_offset(), moov.data_offset() + 8);
+	let skip = children.next_box().unwrap().unwrap();
+	assert_eq!(&skip.header.box_type, b"skip");
+	assert_eq!(skip.data_size, 0);
+	assert!(children.next_box().unwrap().is_none());
+
+	let data = reader.read_data(&mdat, 10, 4).unwrap();
+	assert_eq!(data, Bytes::from_static(&[10, 11, 12, 13]));
+
+	let err = reader.read_data(&mdat, 250, 7).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+	match reader.demux(&ftyp).unwrap() {
+		DynBox::Ftyp(ftyp) => assert_eq!(ftyp.major_brand.to_bytes(), *b"isom"),
+		other => panic!("unexpected box: {:?}", other),
+	}
+}
+
+#[test]
+fn test_box_reader_size_zero_extends_to_end() {
+	let mut file = Vec::new();
+	write_box(&mut file, b"free", &[]);
+	file.extend_from_slice(&0u32.to_be_bytes());
+	file.extend_from_slice(b"mdat");
+	file.extend_from_slice(&[0; 100]);
+
+	let mut reader = BoxReader::new(Cursor::new(file)).unwrap();
+	reader.next_box().unwrap().unwrap();
+
+	let mdat = reader.next_box().unwrap().unwrap();
+	assert_eq!(mdat.data_size, 100);
+	assert!(reader.next_box().unwrap().is_none());
+}
+
+#[test]
+fn test_box_reader_truncated() {
+	let mut file = test_file();
+	file.truncate(file.len() - 1);
+
+	let mut reader = BoxReader::new(Cursor::new(file)).unwrap();
+	reader.next_box().unwrap();
+	reader.next_box().unwrap();
+
+	let err = reader.next_box().unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_box_header_size_smaller_than_header() {
+	let mut data = Vec::new();
+	data.extend_from_slice(&4u32.to_be_bytes());
+	data.extend_from_slice(b"free");
+
+	let err = BoxHeader::demux(&mut Cursor::new(Bytes::from(data.clone()))).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+	let err = BoxReader::new(Cursor::new(data)).unwrap().next_box().unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_box_reader_size_overflow() {
+	let mut file = Vec::new();
+	write_box(&mut file, b"free", &[]);
+	// mdat with a 64-bit size of u64::MAX
+	file.extend_from_slice(&1u32.to_be_bytes());
+	file.extend_from_slice(b"mdat");
+	file.extend_from_slice(&u64::MAX.to_be_bytes());
+
+	let mut reader = BoxReader::new(Cursor::new(file)).unwrap();
+	reader.next_box().unwrap().unwrap();
+
+	let err = reader.next_box().unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_box_reader_walk() {
+	use crate::reader::AsyncBoxReader;
+
+	let mut reader = AsyncBoxReader::new(Cursor::new(test_file())).await.unwrap();
+
+	let mut types = Vec::new();
+	let mut mdat = None;
+	while let Some(location) = reader.next_box().await.unwrap() {
+		types.push(location.header.box_type);
+		mdat = Some(location);
+	}
+	assert_eq!(types, vec![*b"ftyp", *b"moov", *b"mdat"]);
+
+	let data = reader.read_data(&mdat.unwrap(), 0, 3).await.unwrap();
+	assert_eq!(data, Bytes::from_static(&[0, 1, 2]));
+}
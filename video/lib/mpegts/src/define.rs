@@ -0,0 +1,66 @@
+use bytes::Bytes;
+
+/// The size of a single transport stream packet.
+pub const TS_PACKET_SIZE: usize = 188;
+
+/// The sync byte which starts every transport stream packet.
+pub const TS_SYNC_BYTE: u8 = 0x47;
+
+/// The clock rate of PTS and DTS values.
+pub const TS_TIMESCALE: u32 = 90000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The type of an elementary stream, as signaled in the PMT.
+/// ISO/IEC 13818-1 - Table 2-34
+pub enum StreamType {
+	/// ISO/IEC 13818-7 Audio with ADTS transport syntax
+	Aac,
+	/// ITU-T Rec. H.264 | ISO/IEC 14496-10 Video
+	H264,
+	/// ITU-T Rec. H.265 | ISO/IEC 23008-2 Video
+	H265,
+	/// Any stream type we do not handle
+	Other(u8),
+}
+
+impl From<u8> for StreamType {
+	fn from(value: u8) -> Self {
+		match value {
+			0x0F => Self::Aac,
+			0x1B => Self::H264,
+			0x24 => Self::H265,
+			other => Self::Other(other),
+		}
+	}
+}
+
+impl From<StreamType> for u8 {
+	fn from(value: StreamType) -> Self {
+		match value {
+			StreamType::Aac => 0x0F,
+			StreamType::H264 => 0x1B,
+			StreamType::H265 => 0x24,
+			StreamType::Other(other) => other,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A single access unit (PES packet payload) from an elementary stream.
+pub struct TsFrame {
+	pub pid: u16,
+	pub stream_type: StreamType,
+	/// The presentation timestamp in 90kHz units
+	pub pts: Option<u64>,
+	/// The decode timestamp in 90kHz units, if it differs from the PTS
+	pub dts: Option<u64>,
+	/// The elementary stream data, ie. Annex B NAL units or ADTS frames.
+	pub data: Bytes,
+}
+
+impl TsFrame {
+	/// The decode timestamp, falling back to the presentation timestamp.
+	pub fn decode_time(&self) -> Option<u64> {
+		self.dts.or(self.pts)
+	}
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::define::{StreamType, TsFrame, TS_PACKET_SIZE, TS_SYNC_BYTE};
+
+const PAT_PID: u16 = 0x0000;
+const PAT_TABLE_ID: u8 = 0x00;
+const PMT_TABLE_ID: u8 = 0x02;
+
+#[derive(Debug)]
+struct PesBuffer {
+	stream_type: StreamType,
+	data: BytesMut,
+	continuity_counter: Option<u8>,
+	/// If we have seen the start of the current PES packet, after packet loss
+	/// we drop data until the next packet starts.
+	started: bool,
+}
+
+impl PesBuffer {
+	fn new(stream_type: StreamType) -> Self {
+		Self {
+			stream_type,
+			data: BytesMut::new(),
+			continuity_counter: None,
+			started: false,
+		}
+	}
+
+	fn push(&mut self, pid: u16, unit_start: bool, continuity_counter: u8, payload: &[u8], frames: &mut Vec<TsFrame>) {
+		if let Some(last) = self.continuity_counter {
+			if continuity_counter == last {
+				// Duplicate packet
+				return;
+			}
+
+			if continuity_counter != (last + 1) & 0x0F {
+				// We lost some packets, so the current frame is incomplete.
+				self.data.clear();
+				self.started = false;
+			}
+		}
+
+		self.continuity_counter = Some(continuity_counter);
+
+		if unit_start {
+			if let Some(frame) = self.take_frame(pid) {
+				frames.push(frame);
+			}
+
+			self.started = true;
+		}
+
+		if !self.started {
+			return;
+		}
+
+		self.data.extend_from_slice(payload);
+
+		// PES packets which signal their length can be emitted as soon as they are
+		// complete, rather than waiting for the next one to start.
+		if let Some(length) = self.packet_length() {
+			if self.data.len() >= length {
+				if let Some(frame) = self.take_frame(pid) {
+					frames.push(frame);
+				}
+			}
+		}
+	}
+
+	fn packet_length(&self) -> Option<usize> {
+		if self.data.len() < 6 {
+			return None;
+		}
+
+		match u16::from_be_bytes([self.data[4], self.data[5]]) {
+			0 => None,
+			length => Some(6 + length as usize),
+		}
+	}
+
+	fn take_frame(&mut self, pid: u16) -> Option<TsFrame> {
+		let end = self.packet_length();
+		let data = self.data.split().freeze();
+		self.started = false;
+
+		// packet_start_code_prefix (3 bytes), stream_id, PES_packet_length,
+		// flags (2 bytes), PES_header_data_length
+		if data.len() < 9 || data[..3] != [0x00, 0x00, 0x01] {
+			return None;
+		}
+
+		let flags = data[7];
+		let header_length = data[8] as usize;
+		let payload_start = 9 + header_length;
+		let end = end.unwrap_or(data.len()).min(data.len());
+		if payload_start > end {
+			return None;
+		}
+
+		let header = &data[9..payload_start];
+		let pts = if flags & 0x80 != 0 {
+			Some(read_timestamp(header.get(0..5)?))
+		} else {
+			None
+		};
+		let dts = if flags & 0xC0 == 0xC0 {
+			Some(read_timestamp(header.get(5..10)?))
+		} else {
+			None
+		};
+
+		Some(TsFrame {
+			pid,
+			stream_type: self.stream_type,
+			pts,
+			dts,
+			data: data.slice(payload_start..end),
+		})
+	}
+}
+
+/// Read a 33 bit PTS or DTS value.
+/// ISO/IEC 13818-1 - 2.4.3.7
+fn read_timestamp(data: &[u8]) -> u64 {
+	(((data[0] as u64) >> 1) & 0x07) << 30
+		| (data[1] as u64) << 22
+		| ((data[2] as u64) >> 1) << 15
+		| (data[3] as u64) << 7
+		| (data[4] as u64) >> 1
+}
+
+/// CRC32/MPEG-2, running it over a section including its CRC gives 0.
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = 0xFFFFFFFFu32;
+	for byte in data {
+		crc ^= (*byte as u32) << 24;
+		for _ in 0..8 {
+			crc = if crc & 0x80000000 != 0 {
+				(crc << 1) ^ 0x04C11DB7
+			} else {
+				crc << 1
+			};
+		}
+	}
+
+	crc
+}
+
+/// A demuxer for MPEG transport streams.
+/// Only the first program in the PAT is demuxed.
+/// Since transport streams are usually sent over lossy links, corrupt packets
+/// and frames which are missing packets are dropped rather than treated as
+/// errors.
+#[derive(Debug, Default)]
+pub struct TsDemuxer {
+	buffer: BytesMut,
+	pmt_pid: Option<u16>,
+	sections: HashMap<u16, BytesMut>,
+	streams: HashMap<u16, PesBuffer>,
+}
+
+impl TsDemuxer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The elementary streams found in the PMT.
+	pub fn streams(&self) -> impl Iterator<Item = (u16, StreamType)> + '_ {
+		self.streams.iter().map(|(pid, stream)| (*pid, stream.stream_type))
+	}
+
+	/// Feed data to the demuxer and get back any frames which are complete.
+	/// The data does not need to be aligned to packet boundaries.
+	pub fn demux(&mut self, data: &[u8]) -> Vec<TsFrame> {
+		self.buffer.extend_from_slice(data);
+
+		let mut frames = Vec::new();
+
+		loop {
+			match self.buffer.iter().position(|b| *b == TS_SYNC_BYTE) {
+				Some(0) => {}
+				Some(position) => self.buffer.advance(position),
+				None => {
+					self.buffer.clear();
+					break;
+				}
+			}
+
+			if self.buffer.len() < TS_PACKET_SIZE {
+				break;
+			}
+
+			// If we can see the next packet, make sure this sync byte was not just part of
+			// some garbage data.
+			if self.buffer.len() > TS_PACKET_SIZE && self.buffer[TS_PACKET_SIZE] != TS_SYNC_BYTE {
+				self.buffer.advance(1);
+				continue;
+			}
+
+			let packet = self.buffer.split_to(TS_PACKET_SIZE).freeze();
+			self.handle_packet(packet, &mut frames);
+		}
+
+		frames
+	}
+
+	/// Get any frames which are still buffered, for use at the end of a
+	/// stream.
+	pub fn flush(&mut self) -> Vec<TsFrame> {
+		let mut frames = self
+			.streams
+			.iter_mut()
+			.filter(|(_, stream)| stream.started)
+			.filter_map(|(pid, stream)| stream.take_frame(*pid))
+			.collect::<Vec<_>>();
+
+		frames.sort_by_key(|frame| frame.decode_time());
+
+		frames
+	}
+
+	fn handle_packet(&mut self, packet: Bytes, frames: &mut Vec<TsFrame>) {
+		let transport_error = packet[1] & 0x80 != 0;
+		if transport_error {
+			return;
+		}
+
+		let unit_start = packet[1] & 0x40 != 0;
+		let pid = u16::from_be_bytes([packet[1] & 0x1F, packet[2]]);
+		let adaptation_field_control = (packet[3] >> 4) & 0x03;
+		let continuity_counter = packet[3] & 0x0F;
+
+		let mut offset = 4;
+		if adaptation_field_control & 0x02 != 0 {
+			offset += 1 + packet[4] as usize;
+		}
+
+		if adaptation_field_control & 0x01 == 0 || offset >= TS_PACKET_SIZE {
+			return;
+		}
+
+		let payload = &packet[offset..];
+
+		if pid == PAT_PID || Some(pid) == self.pmt_pid {
+			self.handle_section(pid, unit_start, payload);
+		} else if let Some(stream) = self.streams.get_mut(&pid) {
+			stream.push(pid, unit_start, continuity_counter, payload, frames);
+		}
+	}
+
+	fn handle_section(&mut self, pid: u16, unit_start: bool, payload: &[u8]) {
+		let buffer = self.sections.entry(pid).or_default();
+
+		if unit_start {
+			buffer.clear();
+
+			let pointer = payload[0] as usize;
+			let Some(section) = payload.get(1 + pointer..) else {
+				return;
+			};
+
+			buffer.extend_from_slice(section);
+		} else if buffer.is_empty() {
+			return;
+		} else {
+			buffer.extend_from_slice(payload);
+		}
+
+		if buffer.len() < 3 {
+			return;
+		}
+
+		let section_length = u16::from_be_bytes([buffer[1] & 0x0F, buffer[2]]) as usize;
+		if buffer.len() < 3 + section_length {
+			return;
+		}
+
+		let section = buffer.split_to(3 + section_length).freeze();
+		buffer.clear();
+
+		// table_id, section_length (2 bytes), table_id_extension (2 bytes),
+		// version (1 byte), section_number, last_section_number, ..., CRC32
+		if section.len() < 12 || crc32(&section) != 0 {
+			return;
+		}
+
+		let data = &section[8..section.len() - 4];
+
+		match section[0] {
+			PAT_TABLE_ID if pid == PAT_PID => self.handle_pat(data),
+			PMT_TABLE_ID => self.handle_pmt(data),
+			_ => {}
+		}
+	}
+
+	/// ISO/IEC 13818-1 - 2.4.4.3
+	fn handle_pat(&mut self, data: &[u8]) {
+		for program in data.chunks_exact(4) {
+			let program_number = u16::from_be_bytes([program[0], program[1]]);
+			let pid = u16::from_be_bytes([program[2] & 0x1F, program[3]]);
+
+			// Program 0 is the network PID
+			if program_number != 0 {
+				if self.pmt_pid != Some(pid) {
+					self.pmt_pid = Some(pid);
+					self.streams.clear();
+				}
+
+				return;
+			}
+		}
+	}
+
+	/// ISO/IEC 13818-1 - 2.4.4.8
+	fn handle_pmt(&mut self, data: &[u8]) {
+		if data.len() < 4 {
+			return;
+		}
+
+		let program_info_length = u16::from_be_bytes([data[2] & 0x0F, data[3]]) as usize;
+		let Some(mut entries) = data.get(4 + program_info_length..) else {
+			return;
+		};
+
+		let mut pids = Vec::new();
+		while entries.len() >= 5 {
+			let stream_type = StreamType::from(entries[0]);
+			let pid = u16::from_be_bytes([entries[1] & 0x1F, entries[2]]);
+			let es_info_length = u16::from_be_bytes([entries[3] & 0x0F, entries[4]]) as usize;
+			entries = entries.get(5 + es_info_length..).unwrap_or_default();
+
+			pids.push(pid);
+
+			match self.streams.get(&pid) {
+				Some(stream) if stream.stream_type == stream_type => {}
+				_ => {
+					self.streams.insert(pid, PesBuffer::new(stream_type));
+				}
+			}
+		}
+
+		self.streams.retain(|pid, _| pids.contains(pid));
+	}
+}
@@ -0,0 +1,8 @@
+mod define;
+mod demuxer;
+
+pub use define::*;
+pub use demuxer::TsDemuxer;
+
+#[cfg(test)]
+mod tests;
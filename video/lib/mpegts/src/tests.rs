@@ -0,0 +1,263 @@
+use proptest::prelude::*;
+
+use crate::demuxer::TsDemuxer;
+use crate::{StreamType, TsFrame, TS_PACKET_SIZE};
+
+const PMT_PID: u16 = 0x1000;
+const VIDEO_PID: u16 = 0x100;
+const AUDIO_PID: u16 = 0x101;
+
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = 0xFFFFFFFFu32;
+	for byte in data {
+		crc ^= (*byte as u32) << 24;
+		for _ in 0..8 {
+			crc = if crc & 0x80000000 != 0 {
+				(crc << 1) ^ 0x04C11DB7
+			} else {
+				crc << 1
+			};
+		}
+	}
+	crc
+}
+
+/// Split a payload into packets, padding the last one with an adaptation
+/// field.
+fn packets(pid: u16, payload: &[u8], counter: &mut u8) -> Vec<u8> {
+	let mut out = Vec::new();
+	let mut first = true;
+	let mut remaining = payload;
+
+	loop {
+		let mut packet = vec![0x47, (pid >> 8) as u8 & 0x1F, pid as u8, 0];
+		if first {
+			packet[1] |= 0x40;
+		}
+
+		let space = TS_PACKET_SIZE - 4;
+		if remaining.len() >= space {
+			packet[3] = 0x10 | *counter;
+			packet.extend_from_slice(&remaining[..space]);
+			remaining = &remaining[space..];
+		} else {
+			packet[3] = 0x30 | *counter;
+			let stuffing = space - remaining.len() - 1;
+			packet.push(stuffing as u8);
+			if stuffing > 0 {
+				packet.push(0x00);
+				packet.resize(packet.len() + stuffing - 1, 0xFF);
+			}
+			packet.extend_from_slice(remaining);
+			remaining = &[];
+		}
+
+		*counter = (*counter + 1) & 0x0F;
+		first = false;
+		out.extend(packet);
+
+		if remaining.is_empty() {
+			return out;
+		}
+	}
+}
+
+fn section(table_id: u8, data: &[u8]) -> Vec<u8> {
+	let length = 5 + data.len() + 4;
+	let mut section = vec![
+		table_id,
+		0xB0 | (length >> 8) as u8,
+		length as u8,
+		0x00,
+		0x01,
+		0xC1,
+		0x00,
+		0x00,
+	];
+	section.extend_from_slice(data);
+	section.extend(crc32(&section).to_be_bytes());
+
+	let mut payload = vec![0x00]; // pointer field
+	payload.extend(section);
+	payload
+}
+
+fn psi() -> Vec<u8> {
+	let mut out = packets(
+		0,
+		&section(0x00, &[0x00, 0x01, 0xE0 | (PMT_PID >> 8) as u8, PMT_PID as u8]),
+		&mut 0,
+	);
+
+	let mut pmt = vec![0xE0 | (VIDEO_PID >> 8) as u8, VIDEO_PID as u8, 0xF0, 0x00];
+	pmt.extend([0x1B, 0xE0 | (VIDEO_PID >> 8) as u8, VIDEO_PID as u8, 0xF0, 0x00]);
+	pmt.extend([0x0F, 0xE0 | (AUDIO_PID >> 8) as u8, AUDIO_PID as u8, 0xF0, 0x00]);
+	out.extend(packets(PMT_PID, &section(0x02, &pmt), &mut 0));
+
+	out
+}
+
+fn timestamp(prefix: u8, ts: u64) -> [u8; 5] {
+	[
+		prefix << 4 | ((ts >> 29) as u8 & 0x0E) | 1,
+		(ts >> 22) as u8,
+		((ts >> 14) as u8 & 0xFE) | 1,
+		(ts >> 7) as u8,
+		((ts << 1) as u8 & 0xFE) | 1,
+	]
+}
+
+fn pes(stream_id: u8, pts: u64, dts: Option<u64>, data: &[u8], with_length: bool) -> Vec<u8> {
+	let mut header = timestamp(if dts.is_some() { 0x3 } else { 0x2 }, pts).to_vec();
+	if let Some(dts) = dts {
+		header.extend(timestamp(0x1, dts));
+	}
+
+	let length = if with_length { 3 + header.len() + data.len() } else { 0 };
+	let mut pes = vec![0x00, 0x00, 0x01, stream_id, (length >> 8) as u8, length as u8, 0x80];
+	pes.push(if dts.is_some() { 0xC0 } else { 0x80 });
+	pes.push(header.len() as u8);
+	pes.extend(header);
+	pes.extend_from_slice(data);
+	pes
+}
+
+fn test_stream() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+	let video = (0..400).map(|i| i as u8).collect::<Vec<_>>();
+	let audio = vec![0xAA; 100];
+
+	let mut stream = psi();
+	let mut video_counter = 0;
+	let mut audio_counter = 0;
+	stream.extend(packets(
+		VIDEO_PID,
+		&pes(0xE0, 1_000_000, Some(990_000), &video, false),
+		&mut video_counter,
+	));
+	stream.extend(packets(
+		AUDIO_PID,
+		&pes(0xC0, 995_000, None, &audio, true),
+		&mut audio_counter,
+	));
+	stream.extend(packets(
+		VIDEO_PID,
+		&pes(0xE0, 1_003_000, None, &[1, 2, 3], false),
+		&mut video_counter,
+	));
+
+	(stream, video, audio)
+}
+
+fn check_frames(frames: &[TsFrame], video: &[u8], audio: &[u8]) {
+	assert_eq!(frames.len(), 2);
+
+	assert_eq!(frames[0].pid, AUDIO_PID);
+	assert_eq!(frames[0].stream_type, StreamType::Aac);
+	assert_eq!(frames[0].pts, Some(995_000));
+	assert_eq!(frames[0].dts, None);
+	assert_eq!(frames[0].data, audio);
+
+	// The video frame does not signal its length so it is only complete once
+	// the next one starts.
+	assert_eq!(frames[1].pid, VIDEO_PID);
+	assert_eq!(frames[1].stream_type, StreamType::H264);
+	assert_eq!(frames[1].pts, Some(1_000_000));
+	assert_eq!(frames[1].dts, Some(990_000));
+	assert_eq!(frames[1].decode_time(), Some(990_000));
+	assert_eq!(frames[1].data, video);
+}
+
+#[test]
+fn test_demux() {
+	let (stream, video, audio) = test_stream();
+
+	let mut demuxer = TsDemuxer::new();
+	let frames = demuxer.demux(&stream);
+	check_frames(&frames, &video, &audio);
+
+	let mut streams = demuxer.streams().collect::<Vec<_>>();
+	streams.sort_by_key(|(pid, _)| *pid);
+	assert_eq!(streams, vec![(VIDEO_PID, StreamType::H264), (AUDIO_PID, StreamType::Aac)]);
+
+	let frames = demuxer.flush();
+	assert_eq!(frames.len(), 1);
+	assert_eq!(frames[0].pts, Some(1_003_000));
+	assert_eq!(frames[0].data, [1, 2, 3].as_slice());
+}
+
+#[test]
+fn test_demux_unaligned() {
+	let (stream, video, audio) = test_stream();
+
+	let mut demuxer = TsDemuxer::new();
+	let mut frames = Vec::new();
+	for chunk in stream.chunks(7) {
+		frames.extend(demuxer.demux(chunk));
+	}
+
+	check_frames(&frames, &video, &audio);
+}
+
+#[test]
+fn test_demux_resync() {
+	let (stream, video, audio) = test_stream();
+
+	let mut data = vec![0x47, 0x00, 0x12, 0x47];
+	data.extend(stream);
+
+	let frames = TsDemuxer::new().demux(&data);
+	check_frames(&frames, &video, &audio);
+}
+
+#[test]
+fn test_demux_packet_loss() {
+	let (stream, _, audio) = test_stream();
+
+	// Drop the second packet of the video frame, the frame should be dropped
+	// rather than returned incomplete.
+	let mut data = stream[..TS_PACKET_SIZE * 3].to_vec();
+	data.extend_from_slice(&stream[TS_PACKET_SIZE * 4..]);
+
+	let mut demuxer = TsDemuxer::new();
+	let frames = demuxer.demux(&data);
+	assert_eq!(frames.len(), 1);
+	assert_eq!(frames[0].data, audio);
+
+	let frames = demuxer.flush();
+	assert_eq!(frames.len(), 1);
+	assert_eq!(frames[0].data, [1, 2, 3].as_slice());
+}
+
+#[test]
+fn test_demux_bad_crc() {
+	let (mut stream, _, _) = test_stream();
+
+	// Corrupt the PAT, we should not find any streams.
+	stream[TS_PACKET_SIZE - 1] ^= 0xFF;
+
+	let mut demuxer = TsDemuxer::new();
+	assert!(demuxer.demux(&stream).is_empty());
+	assert_eq!(demuxer.streams().count(), 0);
+}
+
+proptest! {
+	#[test]
+	fn test_demux_never_panics(data in prop::collection::vec(any::<u8>(), 0..4096)) {
+		let mut demuxer = TsDemuxer::new();
+		demuxer.demux(&data);
+		demuxer.flush();
+	}
+
+	#[test]
+	fn test_demux_never_panics_after_psi(packets in prop::collection::vec(prop::collection::vec(any::<u8>(), 187), 0..32)) {
+		let mut data = psi();
+		for packet in packets {
+			data.push(0x47);
+			data.extend(packet);
+		}
+
+		let mut demuxer = TsDemuxer::new();
+		demuxer.demux(&data);
+		demuxer.flush();
+	}
+}
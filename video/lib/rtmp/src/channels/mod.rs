@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bytes::Bytes;
 use tokio::sync::{mpsc, oneshot};
 
@@ -32,6 +34,8 @@ impl ChannelData {
 pub struct PublishRequest {
 	pub app_name: String,
 	pub stream_name: String,
+	/// The plain values of the connect command, such as the tcUrl
+	pub connect_params: HashMap<String, String>,
 	pub response: oneshot::Sender<UniqueID>,
 }
 
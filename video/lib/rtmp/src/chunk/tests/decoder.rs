@@ -1,9 +1,12 @@
 use std::io::Write;
 
 use byteorder::WriteBytesExt;
+use bytes::Bytes;
 use bytesio::bytes_writer::BytesWriter;
+use proptest::prelude::*;
 
-use crate::chunk::{ChunkDecodeError, ChunkDecoder};
+use crate::chunk::{Chunk, ChunkDecodeError, ChunkDecoder, ChunkEncoder};
+use crate::messages::MessageTypeID;
 
 #[test]
 fn test_decoder_error_display() {
@@ -596,3 +599,54 @@ fn test_decoder_larger_chunk_size() {
 		assert_eq!(chunk.payload[i], i as u8);
 	}
 }
+
+fn message_type_strategy() -> impl Strategy<Value = MessageTypeID> {
+	prop_oneof![
+		Just(MessageTypeID::SetChunkSize),
+		Just(MessageTypeID::Abort),
+		Just(MessageTypeID::Acknowledgement),
+		Just(MessageTypeID::UserControlEvent),
+		Just(MessageTypeID::WindowAcknowledgementSize),
+		Just(MessageTypeID::SetPeerBandwidth),
+		Just(MessageTypeID::Audio),
+		Just(MessageTypeID::Video),
+		Just(MessageTypeID::DataAMF0),
+		Just(MessageTypeID::CommandAMF0),
+		Just(MessageTypeID::Aggregate),
+	]
+}
+
+proptest! {
+	#[test]
+	fn test_decoder_round_trip(
+		chunk_stream_id in 2..=65599u32,
+		timestamp: u32,
+		msg_type_id in message_type_strategy(),
+		msg_stream_id: u32,
+		payload in prop::collection::vec(any::<u8>(), 0..2048),
+	) {
+		let chunk = Chunk::new(chunk_stream_id, timestamp, msg_type_id, msg_stream_id, Bytes::from(payload));
+
+		let mut writer = BytesWriter::default();
+		ChunkEncoder::default().write_chunk(&mut writer, chunk.clone()).unwrap();
+
+		let mut decoder = ChunkDecoder::default();
+		decoder.extend_data(&writer.dispose());
+
+		let decoded = decoder.read_chunk().unwrap().unwrap();
+		prop_assert_eq!(decoded.basic_header.chunk_stream_id, chunk.basic_header.chunk_stream_id);
+		prop_assert_eq!(decoded.message_header.timestamp, chunk.message_header.timestamp);
+		prop_assert_eq!(decoded.message_header.msg_type_id, chunk.message_header.msg_type_id);
+		prop_assert_eq!(decoded.message_header.msg_stream_id, chunk.message_header.msg_stream_id);
+		prop_assert_eq!(decoded.payload, chunk.payload);
+	}
+
+	#[test]
+	fn test_decoder_never_panics(data in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..512), 0..8)) {
+		let mut decoder = ChunkDecoder::default();
+		for data in data {
+			decoder.extend_data(&data);
+			while let Ok(Some(_)) = decoder.read_chunk() {}
+		}
+	}
+}
@@ -0,0 +1,140 @@
+use std::io::Write;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::{Bytes, BytesMut};
+use bytesio::bytes_reader::BytesReader;
+use bytesio::bytes_writer::BytesWriter;
+use rand::Rng;
+
+use super::define::{ClientHandshakeState, RtmpVersion};
+use super::errors::HandshakeError;
+use super::{define, utils};
+
+// Simple Handshake Client
+// RTMP Spec 1.0 - 5.2
+// Servers fall back to the simple handshake when the client does not send a
+// digest, so we never need to do the complex handshake as a client.
+pub struct HandshakeClient {
+	reader: BytesReader,
+
+	state: ClientHandshakeState,
+
+	s1_bytes: Bytes,
+	s1_timestamp: u32,
+}
+
+impl Default for HandshakeClient {
+	fn default() -> Self {
+		Self {
+			reader: BytesReader::new(BytesMut::default()),
+			state: ClientHandshakeState::WriteC0C1,
+			s1_bytes: Bytes::new(),
+			s1_timestamp: 0,
+		}
+	}
+}
+
+impl HandshakeClient {
+	pub fn extend_data(&mut self, data: &[u8]) {
+		self.reader.extend_from_slice(data);
+	}
+
+	pub fn state(&self) -> ClientHandshakeState {
+		self.state
+	}
+
+	pub fn extract_remaining_bytes(&mut self) -> BytesMut {
+		self.reader.extract_remaining_bytes()
+	}
+
+	pub fn handshake(&mut self, writer: &mut BytesWriter) -> Result<(), HandshakeError> {
+		loop {
+			match self.state {
+				ClientHandshakeState::WriteC0C1 => {
+					self.write_c0(writer)?;
+					self.write_c1(writer)?;
+					self.state = ClientHandshakeState::ReadS0S1S2;
+					break;
+				}
+				ClientHandshakeState::ReadS0S1S2 => {
+					// S0, S1 and S2 are always sent together, so we wait until we have all
+					// of them.
+					if self.reader.len() < 1 + define::RTMP_HANDSHAKE_SIZE * 2 {
+						break;
+					}
+
+					self.read_s0()?;
+					self.read_s1()?;
+					self.read_s2()?;
+					self.state = ClientHandshakeState::WriteC2;
+				}
+				ClientHandshakeState::WriteC2 => {
+					self.write_c2(writer)?;
+					self.state = ClientHandshakeState::Finish;
+				}
+				ClientHandshakeState::Finish => {
+					break;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Defined in RTMP Specification 1.0 - 5.2.2
+	fn write_c0(&self, writer: &mut BytesWriter) -> Result<(), HandshakeError> {
+		writer.write_u8(RtmpVersion::Version3 as u8)?;
+
+		Ok(())
+	}
+
+	/// Defined in RTMP Specification 1.0 - 5.2.3
+	fn write_c1(&self, writer: &mut BytesWriter) -> Result<(), HandshakeError> {
+		writer.write_u32::<BigEndian>(utils::current_time())?;
+
+		// Zero (4 bytes): This field MUST be all 0s. Servers use a non zero value
+		//  here to detect the complex handshake.
+		writer.write_u32::<BigEndian>(0)?;
+
+		let mut rng = rand::thread_rng();
+		for _ in 0..1528 {
+			writer.write_u8(rng.gen())?;
+		}
+
+		Ok(())
+	}
+
+	fn read_s0(&mut self) -> Result<(), HandshakeError> {
+		// A server that does not recognize the client's requested version
+		//  SHOULD respond with 3, so anything else is not a server we can talk to.
+		let version = self.reader.read_u8()?;
+		if version != RtmpVersion::Version3 as u8 {
+			return Err(HandshakeError::UnsupportedVersion(version));
+		}
+
+		Ok(())
+	}
+
+	fn read_s1(&mut self) -> Result<(), HandshakeError> {
+		self.s1_timestamp = self.reader.read_u32::<BigEndian>()?;
+		self.reader.read_u32::<BigEndian>()?;
+		self.s1_bytes = self.reader.read_bytes(1528)?.freeze();
+
+		Ok(())
+	}
+
+	fn read_s2(&mut self) -> Result<(), HandshakeError> {
+		// Same as the server, we do not check that S2 echoes our C1.
+		self.reader.read_bytes(define::RTMP_HANDSHAKE_SIZE)?;
+
+		Ok(())
+	}
+
+	fn write_c2(&self, writer: &mut BytesWriter) -> Result<(), HandshakeError> {
+		writer.write_u32::<BigEndian>(self.s1_timestamp)?;
+		writer.write_u32::<BigEndian>(utils::current_time())?;
+		writer.write_all(&self.s1_bytes[..])?;
+
+		Ok(())
+	}
+}
@@ -33,6 +33,15 @@ pub enum ServerHandshakeState {
 	Finish,
 }
 
+/// The state of the client side of the handshake.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientHandshakeState {
+	WriteC0C1,
+	ReadS0S1S2,
+	WriteC2,
+	Finish,
+}
+
 /// This is the total size of the C1/S1 C2/S2 packets.
 pub const RTMP_HANDSHAKE_SIZE: usize = 1536;
 
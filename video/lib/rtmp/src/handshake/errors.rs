@@ -6,6 +6,7 @@ use crate::macros::from_error;
 pub enum HandshakeError {
 	Digest(DigestError),
 	IO(std::io::Error),
+	UnsupportedVersion(u8),
 }
 
 from_error!(HandshakeError, Self::Digest, DigestError);
@@ -16,6 +17,7 @@ impl fmt::Display for HandshakeError {
 		match self {
 			Self::Digest(error) => write!(f, "digest error: {}", error),
 			Self::IO(error) => write!(f, "io error: {}", error),
+			Self::UnsupportedVersion(version) => write!(f, "unsupported version: {}", version),
 		}
 	}
 }
@@ -5,7 +5,7 @@ use bytes::Bytes;
 use bytesio::bytes_reader::BytesCursor;
 use bytesio::bytes_writer::BytesWriter;
 
-use super::{HandshakeError, HandshakeServer};
+use super::{ClientHandshakeState, HandshakeClient, HandshakeError, HandshakeServer};
 use crate::handshake::define::{
 	SchemaVersion, {self},
 };
@@ -153,4 +153,64 @@ fn test_error_display() {
 	// no idea why this io error is the error we get but this is mainly testing the
 	// display impl anyway
 	assert_eq!(err.to_string(), "io error: failed to fill whole buffer");
+
+	let err = HandshakeError::UnsupportedVersion(6);
+	assert_eq!(err.to_string(), "unsupported version: 6");
+}
+
+#[test]
+fn test_client_handshake() {
+	let mut handshake_client = HandshakeClient::default();
+	let mut handshake_server = HandshakeServer::default();
+
+	let mut writer = BytesWriter::default();
+	handshake_client.handshake(&mut writer).unwrap();
+	assert_eq!(handshake_client.state(), ClientHandshakeState::ReadS0S1S2);
+
+	let c0c1 = writer.dispose();
+	assert_eq!(c0c1.len(), 1 + define::RTMP_HANDSHAKE_SIZE);
+
+	handshake_server.extend_data(&c0c1);
+	let mut writer = BytesWriter::default();
+	handshake_server.handshake(&mut writer).unwrap();
+	let s0s1s2 = writer.dispose();
+
+	// Nothing happens until the client has all of S0, S1 and S2.
+	let mut writer = BytesWriter::default();
+	handshake_client.extend_data(&s0s1s2[..100]);
+	handshake_client.handshake(&mut writer).unwrap();
+	assert_eq!(handshake_client.state(), ClientHandshakeState::ReadS0S1S2);
+	assert!(writer.dispose().is_empty());
+
+	// Data sent after the handshake is left for the session.
+	handshake_client.extend_data(&s0s1s2[100..]);
+	handshake_client.extend_data(&[1, 2, 3]);
+	let mut writer = BytesWriter::default();
+	handshake_client.handshake(&mut writer).unwrap();
+	assert_eq!(handshake_client.state(), ClientHandshakeState::Finish);
+	assert_eq!(&handshake_client.extract_remaining_bytes()[..], &[1, 2, 3]);
+
+	let c2 = writer.dispose();
+	assert_eq!(c2.len(), define::RTMP_HANDSHAKE_SIZE);
+	// C2 echoes the random bytes from S1.
+	assert_eq!(&c2[8..], &s0s1s2[9..1 + define::RTMP_HANDSHAKE_SIZE]);
+
+	handshake_server.extend_data(&c2);
+	let mut writer = BytesWriter::default();
+	handshake_server.handshake(&mut writer).unwrap();
+	assert_eq!(handshake_server.state(), ServerHandshakeState::Finish);
+}
+
+#[test]
+fn test_client_handshake_bad_version() {
+	let mut handshake_client = HandshakeClient::default();
+
+	let mut writer = BytesWriter::default();
+	handshake_client.handshake(&mut writer).unwrap();
+
+	handshake_client.extend_data(&[6; 1 + define::RTMP_HANDSHAKE_SIZE * 2]);
+	assert!(matches!(
+		handshake_client.handshake(&mut writer),
+		Err(HandshakeError::UnsupportedVersion(6))
+	));
 }
@@ -10,7 +10,8 @@ mod session;
 mod user_control_messages;
 
 pub use channels::{ChannelData, DataConsumer, DataProducer, PublishConsumer, PublishProducer, PublishRequest, UniqueID};
-pub use session::{Session, SessionError};
+pub use chunk::{ChunkDecodeError, ChunkDecoder};
+pub use session::{ClientSession, Session, SessionError};
 
 #[cfg(test)]
 mod tests;
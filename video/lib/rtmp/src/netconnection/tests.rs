@@ -90,3 +90,59 @@ fn test_netconnection_create_stream_response() {
 	assert_eq!(values[2], Amf0Value::Null); // command object
 	assert_eq!(values[3], Amf0Value::Number(1.0)); // stream id
 }
+
+#[test]
+fn test_netconnection_connect() {
+	let encoder = ChunkEncoder::default();
+	let mut writer = BytesWriter::default();
+
+	NetConnection::write_connect(&encoder, &mut writer, 1.0, "live", "rtmp://localhost/live").unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x03);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x14);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+
+	let mut amf0_reader = Amf0Reader::new(chunk.payload);
+	let values = amf0_reader.read_all().unwrap();
+
+	assert_eq!(values.len(), 3);
+	assert_eq!(values[0], Amf0Value::String("connect".to_string())); // command name
+	assert_eq!(values[1], Amf0Value::Number(1.0)); // transaction id
+
+	let Amf0Value::Object(command_object) = &values[2] else {
+		panic!("expected command object");
+	};
+	assert_eq!(command_object.get("app"), Some(&Amf0Value::String("live".to_string())));
+	assert_eq!(
+		command_object.get("tcUrl"),
+		Some(&Amf0Value::String("rtmp://localhost/live".to_string()))
+	);
+}
+
+#[test]
+fn test_netconnection_create_stream() {
+	let encoder = ChunkEncoder::default();
+	let mut writer = BytesWriter::default();
+
+	NetConnection::write_create_stream(&encoder, &mut writer, 2.0).unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x03);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x14);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+
+	let mut amf0_reader = Amf0Reader::new(chunk.payload);
+	let values = amf0_reader.read_all().unwrap();
+
+	assert_eq!(values.len(), 3);
+	assert_eq!(values[0], Amf0Value::String("createStream".to_string())); // command name
+	assert_eq!(values[1], Amf0Value::Number(2.0)); // transaction id
+	assert_eq!(values[2], Amf0Value::Null); // command object
+}
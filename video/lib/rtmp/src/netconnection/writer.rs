@@ -72,4 +72,50 @@ impl NetConnection {
 
 		Self::write_chunk(encoder, amf0_writer, writer)
 	}
+
+	/// The connect command sent by a client.
+	/// RTMP Specification 1.0 - 7.2.1.1
+	pub fn write_connect(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		transaction_id: f64,
+		app: &str,
+		tc_url: &str,
+	) -> Result<(), NetConnectionError> {
+		let mut amf0_writer = BytesWriter::default();
+
+		Amf0Writer::write_string(&mut amf0_writer, "connect")?;
+		Amf0Writer::write_number(&mut amf0_writer, transaction_id)?;
+		Amf0Writer::write_object(
+			&mut amf0_writer,
+			&HashMap::from([
+				("app".to_string(), Amf0Value::String(app.to_string())),
+				("type".to_string(), Amf0Value::String("nonprivate".to_string())),
+				("flashVer".to_string(), Amf0Value::String("FMLE/3.0".to_string())),
+				("tcUrl".to_string(), Amf0Value::String(tc_url.to_string())),
+				("fpad".to_string(), Amf0Value::Boolean(false)),
+				// AAC and H.264, the same values ffmpeg sends.
+				("audioCodecs".to_string(), Amf0Value::Number(3191.0)),
+				("videoCodecs".to_string(), Amf0Value::Number(252.0)),
+				("videoFunction".to_string(), Amf0Value::Number(1.0)),
+			]),
+		)?;
+
+		Self::write_chunk(encoder, amf0_writer, writer)
+	}
+
+	/// RTMP Specification 1.0 - 7.2.1.3
+	pub fn write_create_stream(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		transaction_id: f64,
+	) -> Result<(), NetConnectionError> {
+		let mut amf0_writer = BytesWriter::default();
+
+		Amf0Writer::write_string(&mut amf0_writer, "createStream")?;
+		Amf0Writer::write_number(&mut amf0_writer, transaction_id)?;
+		Amf0Writer::write_null(&mut amf0_writer)?;
+
+		Self::write_chunk(encoder, amf0_writer, writer)
+	}
 }
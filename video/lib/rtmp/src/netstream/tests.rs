@@ -46,3 +46,29 @@ fn test_netstream_write_on_status() {
 		]))
 	); // info object
 }
+
+#[test]
+fn test_netstream_write_play() {
+	let encoder = ChunkEncoder::default();
+	let mut writer = BytesWriter::default();
+
+	NetStreamWriter::write_play(&encoder, &mut writer, 3.0, 1, "stream-key").unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x03);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x14);
+	assert_eq!(chunk.message_header.msg_stream_id, 1);
+
+	let mut amf0_reader = Amf0Reader::new(chunk.payload);
+	let values = amf0_reader.read_all().unwrap();
+
+	assert_eq!(values.len(), 5);
+	assert_eq!(values[0], Amf0Value::String("play".to_string())); // command name
+	assert_eq!(values[1], Amf0Value::Number(3.0)); // transaction id
+	assert_eq!(values[2], Amf0Value::Null); // command object
+	assert_eq!(values[3], Amf0Value::String("stream-key".to_string())); // stream name
+	assert_eq!(values[4], Amf0Value::Number(-2.0)); // start
+}
@@ -14,12 +14,19 @@ impl NetStreamWriter {
 		encoder: &ChunkEncoder,
 		amf0_writer: BytesWriter,
 		writer: &mut BytesWriter,
+		stream_id: u32,
 	) -> Result<(), NetStreamError> {
 		let data = amf0_writer.dispose();
 
 		encoder.write_chunk(
 			writer,
-			Chunk::new(DefinedChunkStreamID::Command as u32, 0, MessageTypeID::CommandAMF0, 0, data),
+			Chunk::new(
+				DefinedChunkStreamID::Command as u32,
+				0,
+				MessageTypeID::CommandAMF0,
+				stream_id,
+				data,
+			),
 		)?;
 
 		Ok(())
@@ -47,6 +54,27 @@ impl NetStreamWriter {
 			]),
 		)?;
 
-		Self::write_chunk(encoder, amf0_writer, writer)
+		Self::write_chunk(encoder, amf0_writer, writer, 0)
+	}
+
+	/// The play command is sent on the stream that is being played.
+	/// RTMP Specification 1.0 - 7.2.2.1
+	pub fn write_play(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		transaction_id: f64,
+		stream_id: u32,
+		stream_name: &str,
+	) -> Result<(), NetStreamError> {
+		let mut amf0_writer = BytesWriter::default();
+
+		Amf0Writer::write_string(&mut amf0_writer, "play")?;
+		Amf0Writer::write_number(&mut amf0_writer, transaction_id)?;
+		Amf0Writer::write_null(&mut amf0_writer)?;
+		Amf0Writer::write_string(&mut amf0_writer, stream_name)?;
+		// -2 plays a live stream if there is one, otherwise a recorded one.
+		Amf0Writer::write_number(&mut amf0_writer, -2.0)?;
+
+		Self::write_chunk(encoder, amf0_writer, writer, stream_id)
 	}
 }
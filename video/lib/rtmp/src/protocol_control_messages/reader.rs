@@ -14,4 +14,11 @@ impl ProtocolControlMessageReader {
 
 		Ok(chunk_size)
 	}
+
+	pub fn read_window_acknowledgement_size(data: Bytes) -> Result<u32, ProtocolControlMessageError> {
+		let mut cursor = Cursor::new(data);
+		let window_size = cursor.read_u32::<BigEndian>()?;
+
+		Ok(window_size)
+	}
 }
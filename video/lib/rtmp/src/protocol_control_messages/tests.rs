@@ -71,3 +71,27 @@ fn test_writer_set_peer_bandwidth() {
 	assert_eq!(chunk.message_header.msg_stream_id, 0);
 	assert_eq!(chunk.payload, vec![0x00, 0x00, 0x00, 0x01, 0x02]);
 }
+
+#[test]
+fn test_reader_read_window_acknowledgement_size() {
+	let data = vec![0x00, 0x26, 0x25, 0xa0];
+	let window_size = ProtocolControlMessageReader::read_window_acknowledgement_size(data.into()).unwrap();
+	assert_eq!(window_size, 2500000);
+}
+
+#[test]
+fn test_writer_acknowledgement() {
+	let encoder = ChunkEncoder::default();
+	let mut writer = BytesWriter::default();
+
+	ProtocolControlMessagesWriter::write_acknowledgement(&encoder, &mut writer, 1).unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x02);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x03);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+	assert_eq!(chunk.payload, vec![0x00, 0x00, 0x00, 0x01]);
+}
@@ -73,4 +73,23 @@ impl ProtocolControlMessagesWriter {
 
 		Ok(())
 	}
+
+	pub fn write_acknowledgement(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		sequence_number: u32,
+	) -> Result<(), ProtocolControlMessageError> {
+		encoder.write_chunk(
+			writer,
+			Chunk::new(
+				2, // chunk stream must be 2
+				0, // timestamps are ignored
+				MessageTypeID::Acknowledgement,
+				0, // message stream id is ignored
+				Bytes::from(sequence_number.to_be_bytes().to_vec()),
+			),
+		)?;
+
+		Ok(())
+	}
 }
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use amf0::{Amf0Reader, Amf0Value};
+use byteorder::{BigEndian, ReadBytesExt};
+use bytes::Bytes;
+use bytesio::bytes_writer::BytesWriter;
+use bytesio::bytesio::{AsyncReadWrite, BytesIO, BytesIOStats};
+use bytesio::bytesio_errors::BytesIOError;
+use utils::prelude::FutureTimeout;
+
+use super::errors::SessionError;
+use crate::channels::{ChannelData, DataProducer};
+use crate::chunk::{Chunk, ChunkDecoder, ChunkEncoder, CHUNK_SIZE};
+use crate::handshake::{ClientHandshakeState, HandshakeClient};
+use crate::messages::{MessageParser, MessageTypeID, RtmpMessageData};
+use crate::netconnection::NetConnection;
+use crate::netstream::NetStreamWriter;
+use crate::protocol_control_messages::{ProtocolControlMessageReader, ProtocolControlMessagesWriter};
+use crate::user_control_messages::{EventMessagesWriter, RTMP_EVENT_PING_REQUEST};
+
+const CONNECT_TRANSACTION_ID: f64 = 1.0;
+const CREATE_STREAM_TRANSACTION_ID: f64 = 2.0;
+const PLAY_TRANSACTION_ID: f64 = 3.0;
+
+/// How much the server should buffer for us, in milliseconds.
+const BUFFER_LENGTH: u32 = 3000;
+
+/// A client session which plays a stream from another RTMP server.
+/// This is the opposite of [`Session`](super::Session), the media the server
+/// sends is forwarded to the data producer in the same way as a published
+/// stream.
+pub struct ClientSession<S: AsyncReadWrite> {
+	/// Used to read and write data
+	io: BytesIO<S>,
+
+	/// Data read during the handshake which belongs to the session
+	skip_read: bool,
+
+	chunk_decoder: ChunkDecoder,
+	chunk_encoder: ChunkEncoder,
+
+	/// The stream id the server created for us
+	stream_id: u32,
+
+	/// If the server has started sending the stream
+	is_playing: bool,
+
+	/// The window size the server wants acknowledgements for
+	window_ack_size: u64,
+
+	/// Bytes read at the time of the last acknowledgement
+	last_ack: u64,
+
+	data_producer: DataProducer,
+}
+
+impl<S: AsyncReadWrite> ClientSession<S> {
+	pub fn new(stream: S, data_producer: DataProducer) -> Self {
+		// Servers only send data once the stream is live, so we have to wait longer
+		// than a server would for a publisher.
+		let io = BytesIO::new(stream)
+			.with_read_timeout(Duration::from_secs(10))
+			.with_write_timeout(Duration::from_secs(2));
+
+		Self {
+			io,
+			skip_read: false,
+			chunk_decoder: ChunkDecoder::default(),
+			chunk_encoder: ChunkEncoder::default(),
+			stream_id: 0,
+			is_playing: false,
+			window_ack_size: 0,
+			last_ack: 0,
+			data_producer,
+		}
+	}
+
+	/// Total bytes read from and written to the underlying connection.
+	pub fn io_stats(&self) -> BytesIOStats {
+		self.io.stats()
+	}
+
+	/// Play a stream to completion.
+	/// `tc_url` is the url of the application, ie.
+	/// `rtmp://localhost:1935/live` for `rtmp://localhost:1935/live/xyz`
+	/// The result will be true if the server ended the stream, and false if
+	/// the connection was closed without the stream ending.
+	pub async fn play(&mut self, tc_url: &str, app: &str, stream_name: &str) -> Result<bool, SessionError> {
+		self.do_handshake().await?;
+
+		tracing::debug!("Handshake complete");
+
+		let mut writer = BytesWriter::default();
+		ProtocolControlMessagesWriter::write_set_chunk_size(&self.chunk_encoder, &mut writer, CHUNK_SIZE as u32)?;
+		self.chunk_encoder.set_chunk_size(CHUNK_SIZE);
+		NetConnection::write_connect(&self.chunk_encoder, &mut writer, CONNECT_TRANSACTION_ID, app, tc_url)?;
+		self.write_data(writer.dispose()).await?;
+
+		loop {
+			match self.do_ready(stream_name).await {
+				Ok(Some(ended)) => return Ok(ended),
+				Ok(None) => {}
+				Err(SessionError::BytesIO(BytesIOError::ClientClosed)) => {
+					tracing::debug!("Server closed the connection");
+					return Ok(false);
+				}
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
+	async fn do_handshake(&mut self) -> Result<(), SessionError> {
+		let mut handshaker = HandshakeClient::default();
+
+		let mut writer = BytesWriter::default();
+		handshaker.handshake(&mut writer)?;
+		self.write_data(writer.dispose()).await?;
+
+		while handshaker.state() != ClientHandshakeState::Finish {
+			let buf = self.io.read().await?;
+			handshaker.extend_data(&buf[..]);
+
+			let mut writer = BytesWriter::default();
+			handshaker.handshake(&mut writer)?;
+			self.write_data(writer.dispose()).await?;
+		}
+
+		let over_read = handshaker.extract_remaining_bytes();
+		if !over_read.is_empty() {
+			self.skip_read = true;
+			self.chunk_decoder.extend_data(&over_read[..]);
+		}
+
+		Ok(())
+	}
+
+	/// Read data from the server and process it.
+	/// Returns `Some` once the stream is over.
+	async fn do_ready(&mut self, stream_name: &str) -> Result<Option<bool>, SessionError> {
+		if self.skip_read {
+			self.skip_read = false;
+		} else {
+			let data = self.io.read().await?;
+			self.chunk_decoder.extend_data(&data[..]);
+		}
+
+		while let Some(chunk) = self.chunk_decoder.read_chunk()? {
+			if let Some(ended) = self.process_chunk(chunk, stream_name).await? {
+				return Ok(Some(ended));
+			}
+		}
+
+		self.send_acknowledgement().await?;
+
+		Ok(None)
+	}
+
+	async fn process_chunk(&mut self, chunk: Chunk, stream_name: &str) -> Result<Option<bool>, SessionError> {
+		let timestamp = chunk.message_header.timestamp;
+		let msg_stream_id = chunk.message_header.msg_stream_id;
+
+		// The message parser only handles the messages a server needs, these are
+		// the extra ones a client has to handle.
+		match chunk.message_header.msg_type_id {
+			MessageTypeID::WindowAcknowledgementSize => {
+				self.window_ack_size = ProtocolControlMessageReader::read_window_acknowledgement_size(chunk.payload)? as u64;
+				return Ok(None);
+			}
+			MessageTypeID::UserControlEvent => {
+				self.on_user_control_event(chunk.payload).await?;
+				return Ok(None);
+			}
+			_ => {}
+		}
+
+		let Some(msg) = MessageParser::parse(chunk)? else {
+			return Ok(None);
+		};
+
+		match msg {
+			RtmpMessageData::Amf0Command {
+				command_name,
+				transaction_id,
+				others,
+				..
+			} => {
+				return self
+					.on_amf0_command_message(command_name, transaction_id, others, stream_name)
+					.await;
+			}
+			RtmpMessageData::SetChunkSize { chunk_size } => {
+				if !self.chunk_decoder.update_max_chunk_size(chunk_size as usize) {
+					return Err(SessionError::InvalidChunkSize(chunk_size as usize));
+				}
+			}
+			RtmpMessageData::AudioData { data } => {
+				self.on_data(msg_stream_id, ChannelData::Audio { timestamp, data }).await?;
+			}
+			RtmpMessageData::VideoData { data } => {
+				self.on_data(msg_stream_id, ChannelData::Video { timestamp, data }).await?;
+			}
+			RtmpMessageData::AmfData { data } => {
+				if !is_play_control_data(&data) {
+					self.on_data(msg_stream_id, ChannelData::Metadata { timestamp, data }).await?;
+				}
+			}
+		}
+
+		Ok(None)
+	}
+
+	async fn on_user_control_event(&mut self, payload: Bytes) -> Result<(), SessionError> {
+		let mut cursor = &payload[..];
+
+		// Other events are only informational, so malformed ones are ignored.
+		if let (Ok(RTMP_EVENT_PING_REQUEST), Ok(timestamp)) =
+			(cursor.read_u16::<BigEndian>(), cursor.read_u32::<BigEndian>())
+		{
+			let mut writer = BytesWriter::default();
+			EventMessagesWriter::write_ping_response(&self.chunk_encoder, &mut writer, timestamp)?;
+			self.write_data(writer.dispose()).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Servers stop sending data if we do not acknowledge what we have read.
+	/// RTMP Specification 1.0 - 5.4.3
+	async fn send_acknowledgement(&mut self) -> Result<(), SessionError> {
+		let bytes_read = self.io.stats().bytes_read;
+		if self.window_ack_size == 0 || bytes_read - self.last_ack < self.window_ack_size {
+			return Ok(());
+		}
+
+		self.last_ack = bytes_read;
+
+		let mut writer = BytesWriter::default();
+		// The sequence number is the number of bytes received so far, and wraps
+		// around.
+		ProtocolControlMessagesWriter::write_acknowledgement(&self.chunk_encoder, &mut writer, bytes_read as u32)?;
+		self.write_data(writer.dispose()).await?;
+
+		Ok(())
+	}
+
+	async fn on_data(&self, stream_id: u32, data: ChannelData) -> Result<(), SessionError> {
+		if stream_id != self.stream_id || !self.is_playing {
+			return Err(SessionError::UnknownStreamID(stream_id));
+		}
+
+		if matches!(
+			self.data_producer.send(data).timeout(Duration::from_secs(2)).await,
+			Err(_) | Ok(Err(_))
+		) {
+			tracing::debug!("Consumer dropped");
+			return Err(SessionError::PublisherDropped);
+		}
+
+		Ok(())
+	}
+
+	async fn on_amf0_command_message(
+		&mut self,
+		command_name: Amf0Value,
+		transaction_id: Amf0Value,
+		others: Vec<Amf0Value>,
+		stream_name: &str,
+	) -> Result<Option<bool>, SessionError> {
+		let transaction_id = match transaction_id {
+			Amf0Value::Number(number) => number,
+			_ => 0.0,
+		};
+
+		let command_name = match &command_name {
+			Amf0Value::String(s) => s.as_str(),
+			_ => "",
+		};
+
+		match (command_name, transaction_id) {
+			("_result", CONNECT_TRANSACTION_ID) => {
+				let mut writer = BytesWriter::default();
+				NetConnection::write_create_stream(&self.chunk_encoder, &mut writer, CREATE_STREAM_TRANSACTION_ID)?;
+				self.write_data(writer.dispose()).await?;
+			}
+			("_error", CONNECT_TRANSACTION_ID) => {
+				return Err(SessionError::ConnectRequestDenied);
+			}
+			("_result", CREATE_STREAM_TRANSACTION_ID) => {
+				let Some(Amf0Value::Number(stream_id)) = others.first() else {
+					return Err(SessionError::UnknownStreamID(0));
+				};
+
+				self.stream_id = *stream_id as u32;
+
+				let mut writer = BytesWriter::default();
+				EventMessagesWriter::write_set_buffer_length(&self.chunk_encoder, &mut writer, 0, BUFFER_LENGTH)?;
+				NetStreamWriter::write_play(
+					&self.chunk_encoder,
+					&mut writer,
+					PLAY_TRANSACTION_ID,
+					self.stream_id,
+					stream_name,
+				)?;
+				EventMessagesWriter::write_set_buffer_length(
+					&self.chunk_encoder,
+					&mut writer,
+					self.stream_id,
+					BUFFER_LENGTH,
+				)?;
+				self.write_data(writer.dispose()).await?;
+			}
+			("_error", CREATE_STREAM_TRANSACTION_ID) => {
+				return Err(SessionError::PlayRequestDenied("createStream failed".to_string()));
+			}
+			("onStatus", _) => {
+				let code = match others.first() {
+					Some(Amf0Value::Object(info)) => status_code(info),
+					_ => "",
+				};
+
+				tracing::debug!(code, "Stream status");
+
+				match code {
+					"NetStream.Play.Start" | "NetStream.Play.Reset" | "NetStream.Play.PublishNotify" => {
+						self.is_playing = true;
+					}
+					"NetStream.Play.Stop" | "NetStream.Play.UnpublishNotify" | "NetStream.Play.Complete" => {
+						return Ok(Some(true));
+					}
+					code if code.ends_with("Failed") || code.ends_with("NotFound") || code.ends_with("BadName") => {
+						return Err(SessionError::PlayRequestDenied(code.to_string()));
+					}
+					_ => {}
+				}
+			}
+			_ => {}
+		}
+
+		Ok(None)
+	}
+
+	/// write_data is a helper function to write data to the underlying
+	/// connection. If the data is empty, it will not write anything.
+	async fn write_data(&mut self, data: Bytes) -> Result<(), SessionError> {
+		if !data.is_empty() {
+			self.io.write(data).await?;
+		}
+
+		Ok(())
+	}
+}
+
+fn status_code(info: &HashMap<String, Amf0Value>) -> &str {
+	match info.get("code") {
+		Some(Amf0Value::String(code)) => code,
+		_ => "",
+	}
+}
+
+/// Data messages servers send to players which are not part of the stream.
+fn is_play_control_data(data: &Bytes) -> bool {
+	matches!(
+		Amf0Reader::new(data.clone()).read_with_type(amf0::Amf0Marker::String),
+		Ok(Amf0Value::String(name)) if name == "|RtmpSampleAccess" || name == "onStatus"
+	)
+}
@@ -29,6 +29,7 @@ pub enum SessionError {
 	PublishRequestDenied,
 	ConnectRequestDenied,
 	PlayNotSupported,
+	PlayRequestDenied(String),
 	PublisherDropped,
 	InvalidChunkSize(usize),
 }
@@ -63,6 +64,7 @@ impl fmt::Display for SessionError {
 			Self::ConnectRequestDenied => write!(f, "connect request denied"),
 			Self::InvalidChunkSize(size) => write!(f, "invalid chunk size: {}", size),
 			Self::PlayNotSupported => write!(f, "play not supported"),
+			Self::PlayRequestDenied(code) => write!(f, "play request denied: {}", code),
 			Self::PublisherDropped => write!(f, "publisher dropped"),
 		}
 	}
@@ -1,7 +1,9 @@
+mod client_session;
 mod define;
 mod errors;
 mod server_session;
 
+pub use self::client_session::ClientSession;
 pub use self::errors::SessionError;
 pub use self::server_session::Session;
 
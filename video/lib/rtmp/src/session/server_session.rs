@@ -1,12 +1,13 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use amf0::Amf0Value;
+use byteorder::{BigEndian, ReadBytesExt};
 use bytes::Bytes;
 use bytesio::bytes_writer::BytesWriter;
-use bytesio::bytesio::{AsyncReadWrite, BytesIO};
+use bytesio::bytesio::{AsyncReadWrite, BytesIO, BytesIOStats};
 use bytesio::bytesio_errors::BytesIOError;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 use utils::prelude::FutureTimeout;
 
 use super::define::RtmpCommand;
@@ -14,13 +15,16 @@ use super::errors::SessionError;
 use crate::channels::{ChannelData, DataProducer, PublishRequest, UniqueID};
 use crate::chunk::{ChunkDecoder, ChunkEncoder, CHUNK_SIZE};
 use crate::handshake::{HandshakeServer, ServerHandshakeState};
-use crate::messages::{MessageParser, RtmpMessageData};
+use crate::messages::{MessageParser, MessageTypeID, RtmpMessageData};
 use crate::netconnection::NetConnection;
 use crate::netstream::NetStreamWriter;
 use crate::protocol_control_messages::ProtocolControlMessagesWriter;
-use crate::user_control_messages::EventMessagesWriter;
+use crate::user_control_messages::{EventMessagesWriter, RTMP_EVENT_PING_RESPONSE};
 use crate::{handshake, PublishProducer};
 
+/// How often a publishing client is pinged to measure the round trip time.
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct Session<S: AsyncReadWrite> {
 	/// When you connect via rtmp, you specify the app name in the url
 	/// For example: rtmp://localhost:1935/live/xyz
@@ -33,6 +37,10 @@ pub struct Session<S: AsyncReadWrite> {
 	/// per RTMP connection (using different stream keys) as per the RTMP spec.
 	app_name: Option<String>,
 
+	/// The plain values of the connect command object, passed along with the
+	/// publish request so the server can use them to authorize it
+	connect_params: HashMap<String, String>,
+
 	/// This is a unique id for this session
 	/// This is issued when the client connects to the server
 	uid: Option<UniqueID>,
@@ -63,15 +71,29 @@ pub struct Session<S: AsyncReadWrite> {
 	/// when the publisher connects and tries to publish a stream, we need to
 	/// send a publish request to the server
 	publish_request_producer: PublishProducer,
+
+	/// When the session was created, ping timestamps are relative to this
+	started_at: Instant,
+
+	/// When the last ping was sent, and its timestamp if the client has not
+	/// responded to it yet
+	last_ping: Instant,
+	pending_ping: Option<u32>,
+
+	/// The round trip time measured by the last ping
+	rtt: watch::Sender<Option<Duration>>,
 }
 
 impl<S: AsyncReadWrite> Session<S> {
 	pub fn new(stream: S, data_producer: DataProducer, publish_request_producer: PublishProducer) -> Self {
-		let io = BytesIO::new(stream);
+		let io = BytesIO::new(stream)
+			.with_read_timeout(Duration::from_millis(2500))
+			.with_write_timeout(Duration::from_secs(2));
 
 		Self {
 			uid: None,
 			app_name: None,
+			connect_params: HashMap::new(),
 			io,
 			skip_read: false,
 			chunk_decoder: ChunkDecoder::default(),
@@ -80,6 +102,10 @@ impl<S: AsyncReadWrite> Session<S> {
 			stream_id: 0,
 			is_publishing: false,
 			publish_request_producer,
+			started_at: Instant::now(),
+			last_ping: Instant::now(),
+			pending_ping: None,
+			rtt: watch::Sender::new(None),
 		}
 	}
 
@@ -87,6 +113,17 @@ impl<S: AsyncReadWrite> Session<S> {
 		self.uid
 	}
 
+	/// Total bytes read from and written to the underlying connection.
+	pub fn io_stats(&self) -> BytesIOStats {
+		self.io.stats()
+	}
+
+	/// The round trip time to the client, measured with a ping every few
+	/// seconds while it is publishing.
+	pub fn rtt(&self) -> watch::Receiver<Option<Duration>> {
+		self.rtt.subscribe()
+	}
+
 	/// Run the session to completion
 	/// The result of the return value will be true if all publishers have
 	/// disconnected If any publishers are still connected, the result will be
@@ -132,7 +169,7 @@ impl<S: AsyncReadWrite> Session<S> {
 		let mut bytes_len = 0;
 
 		while bytes_len < handshake::RTMP_HANDSHAKE_SIZE {
-			let buf = self.io.read_timeout(Duration::from_millis(2500)).await?;
+			let buf = self.io.read().await?;
 			bytes_len += buf.len();
 			handshaker.extend_data(&buf[..]);
 		}
@@ -171,12 +208,14 @@ impl<S: AsyncReadWrite> Session<S> {
 		if self.skip_read {
 			self.skip_read = false;
 		} else {
-			let data = self.io.read_timeout(Duration::from_millis(2500)).await?;
+			let data = self.io.read().await?;
 			self.chunk_decoder.extend_data(&data[..]);
 		}
 
 		self.parse_chunks().await?;
 
+		self.send_ping().await?;
+
 		Ok(true)
 	}
 
@@ -186,6 +225,12 @@ impl<S: AsyncReadWrite> Session<S> {
 			let timestamp = chunk.message_header.timestamp;
 			let msg_stream_id = chunk.message_header.msg_stream_id;
 
+			// The message parser does not handle user control events.
+			if chunk.message_header.msg_type_id == MessageTypeID::UserControlEvent {
+				self.on_user_control_event(chunk.payload);
+				continue;
+			}
+
 			if let Some(msg) = MessageParser::parse(chunk)? {
 				self.process_messages(msg, msg_stream_id, timestamp).await?;
 			}
@@ -228,6 +273,40 @@ impl<S: AsyncReadWrite> Session<S> {
 		Ok(())
 	}
 
+	/// Measures the round trip time when the client responds to our ping.
+	/// Other events are only informational, so they are ignored.
+	fn on_user_control_event(&mut self, payload: Bytes) {
+		let mut cursor = &payload[..];
+
+		if let (Ok(RTMP_EVENT_PING_RESPONSE), Ok(timestamp)) =
+			(cursor.read_u16::<BigEndian>(), cursor.read_u32::<BigEndian>())
+		{
+			if self.pending_ping == Some(timestamp) {
+				self.pending_ping = None;
+				self.rtt.send_replace(Some(self.last_ping.elapsed()));
+			}
+		}
+	}
+
+	/// Pings the client while it is publishing.
+	/// A ping which is not responded to is replaced by the next one.
+	async fn send_ping(&mut self) -> Result<(), SessionError> {
+		if !self.is_publishing || self.last_ping.elapsed() < PING_INTERVAL {
+			return Ok(());
+		}
+
+		let timestamp = self.started_at.elapsed().as_millis() as u32;
+
+		self.last_ping = Instant::now();
+		self.pending_ping = Some(timestamp);
+
+		let mut writer = BytesWriter::default();
+		EventMessagesWriter::write_ping_request(&self.chunk_encoder, &mut writer, timestamp)?;
+		self.write_data(writer.dispose()).await?;
+
+		Ok(())
+	}
+
 	/// Set the server chunk size to the client
 	async fn send_set_chunk_size(&mut self) -> Result<(), SessionError> {
 		let mut writer = BytesWriter::default();
@@ -352,6 +431,20 @@ impl<S: AsyncReadWrite> Session<S> {
 
 		self.app_name = Some(app_name.to_owned());
 
+		self.connect_params = command_obj
+			.iter()
+			.filter_map(|(key, value)| {
+				let value = match value {
+					Amf0Value::String(value) | Amf0Value::LongString(value) => value.clone(),
+					Amf0Value::Number(value) => value.to_string(),
+					Amf0Value::Boolean(value) => value.to_string(),
+					_ => return None,
+				};
+
+				Some((key.clone(), value))
+			})
+			.collect();
+
 		// The only AMF encoding supported by this server is AMF0
 		// So we ignore the objectEncoding value sent by the client
 		// and always use AMF0
@@ -462,6 +555,7 @@ impl<S: AsyncReadWrite> Session<S> {
 			.send(PublishRequest {
 				app_name: app_name.clone(),
 				stream_name: stream_name.clone(),
+				connect_params: self.connect_params.clone(),
 				response,
 			})
 			.await
@@ -501,7 +595,7 @@ impl<S: AsyncReadWrite> Session<S> {
 	/// This is to avoid writing empty bytes to the underlying connection.
 	async fn write_data(&mut self, data: Bytes) -> Result<(), SessionError> {
 		if !data.is_empty() {
-			self.io.write_timeout(data, Duration::from_secs(2)).await?;
+			self.io.write(data).await?;
 		}
 
 		Ok(())
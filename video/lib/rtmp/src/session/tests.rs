@@ -69,6 +69,9 @@ fn test_error_display() {
 	let error = SessionError::PlayNotSupported;
 	assert_eq!(error.to_string(), "play not supported");
 
+	let error = SessionError::PlayRequestDenied("NetStream.Play.StreamNotFound".to_string());
+	assert_eq!(error.to_string(), "play request denied: NetStream.Play.StreamNotFound");
+
 	let error = SessionError::PublisherDropped;
 	assert_eq!(error.to_string(), "publisher dropped");
 
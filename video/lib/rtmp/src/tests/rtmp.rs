@@ -1,12 +1,23 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use amf0::{Amf0Value, Amf0Writer};
+use bytes::Bytes;
+use bytesio::bytes_writer::BytesWriter;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::process::Command;
 use tokio::sync::mpsc;
 use utils::prelude::FutureTimeout;
 
 use crate::channels::{ChannelData, UniqueID};
-use crate::Session;
+use crate::chunk::{Chunk, ChunkDecoder, ChunkEncoder};
+use crate::handshake::{HandshakeServer, ServerHandshakeState, RTMP_HANDSHAKE_SIZE};
+use crate::messages::{MessageParser, MessageTypeID, RtmpMessageData};
+use crate::netconnection::NetConnection;
+use crate::netstream::NetStreamWriter;
+use crate::protocol_control_messages::ProtocolControlMessagesWriter;
+use crate::{ClientSession, Session, SessionError};
 
 #[tokio::test]
 async fn test_basic_rtmp_clean() {
@@ -67,6 +78,7 @@ async fn test_basic_rtmp_clean() {
 
 	assert_eq!(event.app_name, "live");
 	assert_eq!(event.stream_name, "stream-key");
+	assert_eq!(event.connect_params.get("app").map(String::as_str), Some("live"));
 
 	let stream_id = UniqueID::new_v4();
 	event.response.send(stream_id).expect("failed to send response");
@@ -199,3 +211,199 @@ async fn test_basic_rtmp_unclean() {
 			.expect("failed to handle ffmpeg connection")
 	);
 }
+
+/// A server which answers a client's play request with the given status code,
+/// and if it is `NetStream.Play.Start` sends a few messages followed by
+/// `NetStream.Play.Stop`. Returns the types of the messages the client sent.
+async fn play_server(mut stream: TcpStream, play_status: &str) -> Vec<MessageTypeID> {
+	let mut handshaker = HandshakeServer::default();
+	let mut buf = vec![0; 4096];
+	let mut read = 0;
+
+	while handshaker.state() != ServerHandshakeState::Finish {
+		let n = stream.read(&mut buf).await.unwrap();
+		assert_ne!(n, 0, "client closed during handshake");
+		handshaker.extend_data(&buf[..n]);
+		read += n;
+
+		if read < 1 + RTMP_HANDSHAKE_SIZE {
+			continue;
+		}
+
+		let mut writer = BytesWriter::default();
+		handshaker.handshake(&mut writer).unwrap();
+		stream.write_all(&writer.dispose()).await.unwrap();
+	}
+
+	let encoder = ChunkEncoder::default();
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&handshaker.extract_remaining_bytes());
+
+	let mut received = Vec::new();
+
+	loop {
+		while let Some(chunk) = decoder.read_chunk().unwrap() {
+			received.push(chunk.message_header.msg_type_id);
+
+			let msg = MessageParser::parse(chunk).unwrap();
+			let mut writer = BytesWriter::default();
+
+			match msg {
+				Some(RtmpMessageData::SetChunkSize { chunk_size }) => {
+					decoder.update_max_chunk_size(chunk_size as usize);
+				}
+				Some(RtmpMessageData::Amf0Command {
+					command_name: Amf0Value::String(name),
+					transaction_id: Amf0Value::Number(transaction_id),
+					..
+				}) => match name.as_str() {
+					"connect" => {
+						// A tiny window so that the client has to acknowledge.
+						ProtocolControlMessagesWriter::write_window_acknowledgement_size(&encoder, &mut writer, 64).unwrap();
+						NetConnection::write_connect_response(
+							&encoder,
+							&mut writer,
+							transaction_id,
+							"FMS/3,0,1,123",
+							31.0,
+							"NetConnection.Connect.Success",
+							"status",
+							"Connection Succeeded.",
+							0.0,
+						)
+						.unwrap();
+					}
+					"createStream" => {
+						NetConnection::write_create_stream_response(&encoder, &mut writer, transaction_id, 1.0).unwrap();
+					}
+					"play" => {
+						NetStreamWriter::write_on_status(&encoder, &mut writer, 0.0, "status", play_status, "").unwrap();
+
+						if play_status == "NetStream.Play.Start" {
+							// A ping request
+							encoder
+								.write_chunk(
+									&mut writer,
+									Chunk::new(
+										2,
+										0,
+										MessageTypeID::UserControlEvent,
+										0,
+										Bytes::from_static(&[0, 6, 0, 0, 0, 1]),
+									),
+								)
+								.unwrap();
+
+							let mut sample_access = BytesWriter::default();
+							Amf0Writer::write_string(&mut sample_access, "|RtmpSampleAccess").unwrap();
+							Amf0Writer::write_bool(&mut sample_access, true).unwrap();
+							Amf0Writer::write_bool(&mut sample_access, true).unwrap();
+
+							let mut metadata = BytesWriter::default();
+							Amf0Writer::write_string(&mut metadata, "onMetaData").unwrap();
+							Amf0Writer::write_null(&mut metadata).unwrap();
+
+							for (ty, payload) in [
+								(MessageTypeID::DataAMF0, sample_access.dispose()),
+								(MessageTypeID::DataAMF0, metadata.dispose()),
+								(MessageTypeID::Video, Bytes::from(vec![0x17; 256])),
+								(MessageTypeID::Audio, Bytes::from(vec![0xaf; 256])),
+							] {
+								encoder.write_chunk(&mut writer, Chunk::new(4, 10, ty, 1, payload)).unwrap();
+							}
+
+							NetStreamWriter::write_on_status(
+								&encoder,
+								&mut writer,
+								0.0,
+								"status",
+								"NetStream.Play.Stop",
+								"",
+							)
+							.unwrap();
+						}
+					}
+					_ => {}
+				},
+				_ => {}
+			}
+
+			let data = writer.dispose();
+			if !data.is_empty() {
+				stream.write_all(&data).await.unwrap();
+			}
+		}
+
+		let n = stream.read(&mut buf).await.unwrap();
+		if n == 0 {
+			return received;
+		}
+
+		decoder.extend_data(&buf[..n]);
+	}
+}
+
+#[tokio::test]
+async fn test_client_play() {
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("failed to bind");
+	let addr = listener.local_addr().unwrap();
+
+	let server = tokio::spawn(async move {
+		let (stream, _) = listener.accept().await.expect("failed to accept");
+		play_server(stream, "NetStream.Play.Start").await
+	});
+
+	let stream = TcpStream::connect(addr).await.expect("failed to connect");
+	let (data_producer, mut data_reciever) = mpsc::channel(128);
+
+	let ended = ClientSession::new(stream, data_producer)
+		.play(&format!("rtmp://{addr}/live"), "live", "stream-key")
+		.timeout(Duration::from_secs(5))
+		.await
+		.expect("timedout")
+		.expect("failed to play");
+
+	// The server ended the stream
+	assert!(ended);
+
+	let mut data = Vec::new();
+	while let Ok(d) = data_reciever.try_recv() {
+		data.push(d);
+	}
+
+	// The sample access message is not part of the stream.
+	assert_eq!(data.len(), 3);
+	assert!(matches!(&data[0], ChannelData::Metadata { timestamp: 10, .. }));
+	assert!(matches!(&data[1], ChannelData::Video { timestamp: 10, data } if data.len() == 256));
+	assert!(matches!(&data[2], ChannelData::Audio { timestamp: 10, data } if data.len() == 256));
+
+	let received = server.await.unwrap();
+	assert!(received.contains(&MessageTypeID::Acknowledgement));
+	// The ping response
+	assert!(received.contains(&MessageTypeID::UserControlEvent));
+}
+
+#[tokio::test]
+async fn test_client_play_not_found() {
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("failed to bind");
+	let addr = listener.local_addr().unwrap();
+
+	tokio::spawn(async move {
+		let (stream, _) = listener.accept().await.expect("failed to accept");
+		play_server(stream, "NetStream.Play.StreamNotFound").await
+	});
+
+	let stream = TcpStream::connect(addr).await.expect("failed to connect");
+	let (data_producer, _data_reciever) = mpsc::channel(128);
+
+	let result = ClientSession::new(stream, data_producer)
+		.play(&format!("rtmp://{addr}/live"), "live", "stream-key")
+		.timeout(Duration::from_secs(5))
+		.await
+		.expect("timedout");
+
+	assert!(matches!(
+		result,
+		Err(SessionError::PlayRequestDenied(code)) if code == "NetStream.Play.StreamNotFound"
+	));
+}
@@ -1 +1,4 @@
 pub const RTMP_EVENT_STREAM_BEGIN: u16 = 0;
+pub const RTMP_EVENT_SET_BUFFER_LENGTH: u16 = 3;
+pub const RTMP_EVENT_PING_REQUEST: u16 = 6;
+pub const RTMP_EVENT_PING_RESPONSE: u16 = 7;
@@ -2,6 +2,7 @@ mod define;
 mod errors;
 mod writer;
 
+pub(crate) use self::define::{RTMP_EVENT_PING_REQUEST, RTMP_EVENT_PING_RESPONSE};
 pub use self::errors::EventMessagesError;
 pub use self::writer::EventMessagesWriter;
 
@@ -26,3 +26,57 @@ fn test_write_stream_begin() {
 	assert_eq!(chunk.message_header.msg_stream_id, 0);
 	assert_eq!(chunk.payload, Bytes::from(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x01]));
 }
+
+#[test]
+fn test_write_set_buffer_length() {
+	let mut writer = BytesWriter::default();
+	let encoder = ChunkEncoder::default();
+
+	EventMessagesWriter::write_set_buffer_length(&encoder, &mut writer, 1, 3000).unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x02);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x04);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+	assert_eq!(
+		chunk.payload,
+		Bytes::from(vec![0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x0b, 0xb8])
+	);
+}
+
+#[test]
+fn test_write_ping_request() {
+	let mut writer = BytesWriter::default();
+	let encoder = ChunkEncoder::default();
+
+	EventMessagesWriter::write_ping_request(&encoder, &mut writer, 1234).unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x02);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x04);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+	assert_eq!(chunk.payload, Bytes::from(vec![0x00, 0x06, 0x00, 0x00, 0x04, 0xd2]));
+}
+
+#[test]
+fn test_write_ping_response() {
+	let mut writer = BytesWriter::default();
+	let encoder = ChunkEncoder::default();
+
+	EventMessagesWriter::write_ping_response(&encoder, &mut writer, 1234).unwrap();
+
+	let mut decoder = ChunkDecoder::default();
+	decoder.extend_data(&writer.dispose());
+
+	let chunk = decoder.read_chunk().unwrap().unwrap();
+	assert_eq!(chunk.basic_header.chunk_stream_id, 0x02);
+	assert_eq!(chunk.message_header.msg_type_id as u8, 0x04);
+	assert_eq!(chunk.message_header.msg_stream_id, 0);
+	assert_eq!(chunk.payload, Bytes::from(vec![0x00, 0x07, 0x00, 0x00, 0x04, 0xd2]));
+}
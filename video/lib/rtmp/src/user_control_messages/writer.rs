@@ -24,4 +24,54 @@ impl EventMessagesWriter {
 
 		Ok(())
 	}
+
+	pub fn write_set_buffer_length(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		stream_id: u32,
+		buffer_length: u32, // in milliseconds
+	) -> Result<(), EventMessagesError> {
+		let mut data = Vec::new();
+
+		data.write_u16::<BigEndian>(define::RTMP_EVENT_SET_BUFFER_LENGTH)
+			.expect("write u16");
+		data.write_u32::<BigEndian>(stream_id).expect("write u32");
+		data.write_u32::<BigEndian>(buffer_length).expect("write u32");
+
+		encoder.write_chunk(writer, Chunk::new(0x02, 0, MessageTypeID::UserControlEvent, 0, data.into()))?;
+
+		Ok(())
+	}
+
+	pub fn write_ping_request(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		timestamp: u32,
+	) -> Result<(), EventMessagesError> {
+		let mut data = Vec::new();
+
+		data.write_u16::<BigEndian>(define::RTMP_EVENT_PING_REQUEST)
+			.expect("write u16");
+		data.write_u32::<BigEndian>(timestamp).expect("write u32");
+
+		encoder.write_chunk(writer, Chunk::new(0x02, 0, MessageTypeID::UserControlEvent, 0, data.into()))?;
+
+		Ok(())
+	}
+
+	pub fn write_ping_response(
+		encoder: &ChunkEncoder,
+		writer: &mut BytesWriter,
+		timestamp: u32,
+	) -> Result<(), EventMessagesError> {
+		let mut data = Vec::new();
+
+		data.write_u16::<BigEndian>(define::RTMP_EVENT_PING_RESPONSE)
+			.expect("write u16");
+		data.write_u32::<BigEndian>(timestamp).expect("write u32");
+
+		encoder.write_chunk(writer, Chunk::new(0x02, 0, MessageTypeID::UserControlEvent, 0, data.into()))?;
+
+		Ok(())
+	}
 }
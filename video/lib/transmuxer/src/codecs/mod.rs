@@ -2,3 +2,4 @@ pub mod aac;
 pub mod av1;
 pub mod avc;
 pub mod hevc;
+pub mod opus;
@@ -0,0 +1,109 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use bytes::Bytes;
+use mp4::types::dops::{ChannelMappingTable, DOps};
+use mp4::types::opus::Opus;
+use mp4::types::stsd::{AudioSampleEntry, SampleEntry};
+use mp4::types::trun::{TrunSample, TrunSampleFlag};
+use mp4::DynBox;
+
+use crate::TransmuxError;
+
+/// Opus is always decoded at 48kHz, regardless of the input sample rate.
+pub const SAMPLE_RATE: u32 = 48000;
+
+/// Build the `Opus` sample entry from an OpusHead.
+/// RFC 7845 - 5.1
+pub fn stsd_entry(data: Bytes) -> Result<(DynBox, DOps), TransmuxError> {
+	if data.len() < 19 || &data[..8] != b"OpusHead" {
+		return Err(TransmuxError::InvalidOpusHead);
+	}
+
+	let mut reader = &data[9..];
+	let output_channel_count = reader.read_u8()?;
+	let pre_skip = reader.read_u16::<LittleEndian>()?;
+	let input_sample_rate = reader.read_u32::<LittleEndian>()?;
+	let output_gain = reader.read_i16::<LittleEndian>()?;
+	let channel_mapping_family = reader.read_u8()?;
+
+	let channel_mapping_table = if channel_mapping_family != 0 {
+		if reader.len() < 2 + output_channel_count as usize {
+			return Err(TransmuxError::InvalidOpusHead);
+		}
+
+		Some(ChannelMappingTable {
+			stream_count: reader[0],
+			coupled_count: reader[1],
+			channel_mapping: reader[2..2 + output_channel_count as usize].to_vec(),
+		})
+	} else {
+		None
+	};
+
+	// The OpusHead is little endian and the dOps box is big endian, so the
+	// values are re-encoded rather than copied.
+	let dops = DOps::new(
+		output_channel_count,
+		pre_skip,
+		input_sample_rate,
+		output_gain,
+		channel_mapping_family,
+		channel_mapping_table,
+	);
+
+	Ok((
+		Opus::new(
+			SampleEntry::new(AudioSampleEntry::new(output_channel_count as u16, 16, SAMPLE_RATE)),
+			dops.clone(),
+			None,
+		)
+		.into(),
+		dops,
+	))
+}
+
+pub fn trun_sample(data: &Bytes) -> Result<(TrunSample, u32), TransmuxError> {
+	let duration = packet_duration(data).ok_or(TransmuxError::InvalidOpusPacket)?;
+
+	Ok((
+		TrunSample {
+			duration: Some(duration),
+			composition_time_offset: None,
+			flags: Some(TrunSampleFlag {
+				reserved: 0,
+				is_leading: 0,
+				sample_degradation_priority: 0,
+				sample_depends_on: 2,
+				sample_has_redundancy: 0,
+				sample_is_depended_on: 0,
+				sample_is_non_sync_sample: false,
+				sample_padding_value: 0,
+			}),
+			size: Some(data.len() as u32),
+		},
+		duration,
+	))
+}
+
+/// The number of 48kHz samples in an Opus packet, from its TOC byte.
+/// RFC 6716 - 3.1
+fn packet_duration(data: &[u8]) -> Option<u32> {
+	let toc = *data.first()?;
+	let config = toc >> 3;
+
+	let frame_size = match config {
+		// SILK: 10, 20, 40 or 60ms
+		0..=11 => [480, 960, 1920, 2880][config as usize % 4],
+		// Hybrid: 10 or 20ms
+		12..=15 => [480, 960][config as usize % 2],
+		// CELT: 2.5, 5, 10 or 20ms
+		_ => [120, 240, 480, 960][config as usize % 4],
+	};
+
+	let frames = match toc & 0x03 {
+		0 => 1,
+		1 | 2 => 2,
+		_ => (*data.get(1)? & 0x3F) as u32,
+	};
+
+	Some(frame_size * frames)
+}
@@ -19,6 +19,8 @@ pub(crate) struct AudioSequenceHeader {
 
 pub(crate) enum AudioSequenceHeaderData {
 	Aac(Bytes),
+	/// The OpusHead from the Enhanced RTMP sequence start.
+	Opus(Bytes),
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +56,8 @@ pub struct AudioSettings {
 pub enum MediaType {
 	Video,
 	Audio,
+	/// Timed metadata, the data is an `emsg` box on the video timeline.
+	Metadata,
 }
 
 #[derive(Debug, Clone)]
@@ -8,6 +8,8 @@ pub enum TransmuxError {
 	InvalidHEVCDecoderConfigurationRecord,
 	InvalidAv1DecoderConfigurationRecord,
 	InvalidAVCDecoderConfigurationRecord,
+	InvalidOpusHead,
+	InvalidOpusPacket,
 	NoSequenceHeaders,
 	IO(io::Error),
 	FlvDemuxer(flv::FlvDemuxerError),
@@ -40,6 +42,8 @@ impl std::fmt::Display for TransmuxError {
 			Self::InvalidAVCDecoderConfigurationRecord => {
 				write!(f, "invalid avc decoder configuration record")
 			}
+			Self::InvalidOpusHead => write!(f, "invalid opus head"),
+			Self::InvalidOpusPacket => write!(f, "invalid opus packet"),
 			Self::NoSequenceHeaders => write!(f, "no sequence headers"),
 			Self::IO(err) => write!(f, "io error: {}", err),
 			Self::FlvDemuxer(err) => write!(f, "flv demuxer error: {}", err),
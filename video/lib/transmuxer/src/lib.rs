@@ -9,8 +9,8 @@ use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
 use bytesio::bytes_writer::BytesWriter;
 use flv::{
-	AacPacket, Av1Packet, AvcPacket, EnhancedPacket, FlvTag, FlvTagAudioData, FlvTagData, FlvTagVideoData, FrameType,
-	HevcPacket, SoundType,
+	AacPacket, Av1Packet, AvcPacket, EnhancedAudioPacket, EnhancedPacket, FlvTag, FlvTagAudioData, FlvTagData,
+	FlvTagVideoData, FrameType, HevcPacket, OpusPacket, SoundType,
 };
 use mp4::codec::{AudioCodec, VideoCodec};
 use mp4::types::ftyp::{FourCC, Ftyp};
@@ -44,9 +44,11 @@ use mp4::BoxType;
 mod codecs;
 mod define;
 mod errors;
+mod metadata;
 
 pub use define::*;
 pub use errors::TransmuxError;
+pub use metadata::AMF0_SCHEME_ID_URI;
 
 #[derive(Debug, Clone)]
 pub struct Transmuxer {
@@ -57,6 +59,7 @@ pub struct Transmuxer {
 	video_duration: u64,
 	sequence_number: u32,
 	last_video_timestamp: u32,
+	metadata_id: u32,
 	settings: Option<(VideoSettings, AudioSettings)>,
 	tags: VecDeque<FlvTag>,
 }
@@ -75,6 +78,7 @@ impl Transmuxer {
 			audio_duration: 0,
 			video_duration: 0,
 			last_video_timestamp: 0,
+			metadata_id: 0,
 			settings: None,
 		}
 	}
@@ -132,6 +136,26 @@ impl Transmuxer {
 				return Ok(None);
 			};
 
+			if let FlvTagData::ScriptData { name, data } = &tag.data {
+				// Timed metadata is placed on the video timeline, at the start of
+				// the next video sample.
+				let Some(emsg) = metadata::emsg(name, data, video_settings.timescale, self.video_duration, self.metadata_id)
+				else {
+					continue;
+				};
+
+				self.metadata_id = self.metadata_id.wrapping_add(1);
+
+				emsg.mux(&mut writer)?;
+
+				return Ok(Some(TransmuxResult::MediaSegment(MediaSegment {
+					data: writer.dispose(),
+					ty: MediaType::Metadata,
+					keyframe: false,
+					timestamp: self.video_duration,
+				})));
+			}
+
 			let mdat_data;
 			let total_duration;
 			let trun_sample;
@@ -141,7 +165,7 @@ impl Transmuxer {
 			let duration =
 				if self.last_video_timestamp == 0 || tag.timestamp == 0 || tag.timestamp < self.last_video_timestamp {
 					1000 // the first frame is always 1000 ticks where the timescale
-					// is 1000 * fps.
+				 // is 1000 * fps.
 				} else {
 					// Since the delta is in milliseconds (ie 1/1000 of a second)
 					// Rounding errors happen. Our presision is only 1/1000 of a second.
@@ -173,6 +197,17 @@ impl Transmuxer {
 					total_duration = duration;
 					is_audio = true;
 				}
+				FlvTagData::Audio {
+					data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::Raw(data))),
+					..
+				} => {
+					let (sample, duration) = codecs::opus::trun_sample(&data)?;
+
+					trun_sample = sample;
+					mdat_data = data;
+					total_duration = duration;
+					is_audio = true;
+				}
 				FlvTagData::Video {
 					frame_type,
 					data: FlvTagVideoData::Avc(AvcPacket::Nalu { composition_time, data }),
@@ -334,6 +369,18 @@ impl Transmuxer {
 						sound_type: *sound_type,
 					});
 				}
+				FlvTagData::Audio {
+					sound_size,
+					sound_type,
+					sound_rate: _,
+					data: FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::SequenceStart(data))),
+				} => {
+					audio_sequence_header = Some(AudioSequenceHeader {
+						data: AudioSequenceHeaderData::Opus(data.clone()),
+						sound_size: *sound_size,
+						sound_type: *sound_type,
+					});
+				}
 				FlvTagData::ScriptData { data, name } => {
 					if name == "@setDataFrame" || name == "onMetaData" {
 						let meta_object = data.iter().find(|v| matches!(v, Amf0Value::Object(_)));
@@ -485,6 +532,16 @@ impl Transmuxer {
 					SoundType::Stereo => 2,
 				};
 
+				entry
+			}
+			AudioSequenceHeaderData::Opus(data) => {
+				compatiable_brands.push(FourCC::Opus);
+				let (entry, dops) = codecs::opus::stsd_entry(data)?;
+
+				audio_sample_rate = codecs::opus::SAMPLE_RATE;
+				audio_codec = AudioCodec::Opus;
+				audio_channels = dops.output_channel_count;
+
 				entry
 			}
 		};
@@ -0,0 +1,45 @@
+use amf0::{Amf0Value, Amf0Writer};
+use bytesio::bytes_writer::BytesWriter;
+use mp4::types::emsg::Emsg;
+
+/// The scheme used for timed metadata events passed through from RTMP.
+/// The emsg value is the name of the script data tag (ie. `onCuePoint`)
+/// and the message data is the AMF0 encoded values of the tag.
+pub const AMF0_SCHEME_ID_URI: &str = "urn:scuffle:video:amf0";
+
+/// Script data tags which describe the stream itself and are used to create
+/// the init segment, rather than being timed events.
+fn is_stream_metadata(name: &str) -> bool {
+	name == "onMetaData"
+}
+
+/// Create an emsg box for a script data tag.
+/// Returns `None` if the tag is not a timed event or cannot be encoded.
+pub fn emsg(name: &str, data: &[Amf0Value], timescale: u32, presentation_time: u64, id: u32) -> Option<Emsg> {
+	// `@setDataFrame` wraps another tag, the first value being the name of the
+	// wrapped tag.
+	let (name, data) = match (name, data) {
+		("@setDataFrame", [Amf0Value::String(name), data @ ..]) => (name.as_str(), data),
+		("@setDataFrame", _) => return None,
+		_ => (name, data),
+	};
+
+	if is_stream_metadata(name) {
+		return None;
+	}
+
+	let mut writer = BytesWriter::default();
+	for value in data {
+		Amf0Writer::write_any(&mut writer, value).ok()?;
+	}
+
+	Some(Emsg::new(
+		AMF0_SCHEME_ID_URI,
+		name,
+		timescale,
+		presentation_time,
+		0,
+		id,
+		writer.dispose(),
+	))
+}
@@ -5,11 +5,14 @@ use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
 use aac::AudioObjectType;
+use amf0::{Amf0Reader, Amf0Value};
+use bytes::Bytes;
 use bytesio::bytes_writer::BytesWriter;
-use flv::FlvHeader;
+use flv::{AacPacket, EnhancedAudioPacket, FlvHeader, FlvTag, FlvTagAudioData, FlvTagData, OpusPacket};
 use mp4::codec::{AudioCodec, VideoCodec};
+use mp4::DynBox;
 
-use crate::define::{AudioSettings, VideoSettings};
+use crate::define::{AudioSettings, MediaType, VideoSettings};
 use crate::{TransmuxResult, Transmuxer};
 
 #[test]
@@ -279,7 +282,7 @@ fn test_transmuxer_hevc_aac() {
 						}
 					}
 				);
-				assert_eq!(video_settings.codec.to_string(), "hev1.1.40.L99.90");
+				assert_eq!(video_settings.codec.to_string(), "hev1.1.2.L153.90");
 
 				assert_eq!(
 					audio_settings,
@@ -347,3 +350,138 @@ fn test_transmuxer_hevc_aac() {
 	assert_eq!(json["streams"][1]["sample_rate"], "48000");
 	assert_eq!(json["streams"][1]["channels"], 2);
 }
+
+#[test]
+fn test_transmuxer_timed_metadata() {
+	let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+	let data = std::fs::read(dir.join("avc_aac.flv").to_str().unwrap()).unwrap();
+
+	let mut transmuxer = Transmuxer::new();
+
+	let mut cursor = io::Cursor::new(data.into());
+	FlvHeader::demux(&mut cursor).unwrap();
+
+	let pos = cursor.position() as usize;
+	transmuxer.demux(cursor.into_inner().slice(pos..)).unwrap();
+
+	let cue_point = FlvTag {
+		timestamp: 1000,
+		stream_id: 0,
+		data: FlvTagData::ScriptData {
+			name: "onCuePoint".to_string(),
+			data: vec![Amf0Value::Object(
+				[("name".to_string(), Amf0Value::String("ad-break".to_string()))]
+					.into_iter()
+					.collect(),
+			)],
+		},
+	};
+
+	transmuxer.tags.insert(100, cue_point);
+
+	let mut events = Vec::new();
+	while let Some(result) = transmuxer.mux().unwrap() {
+		if let TransmuxResult::MediaSegment(segment) = result {
+			if segment.ty == MediaType::Metadata {
+				events.push(segment);
+			}
+		}
+	}
+
+	assert_eq!(events.len(), 1);
+
+	let emsg = match DynBox::demux(&mut io::Cursor::new(events[0].data.clone())).unwrap() {
+		DynBox::Emsg(emsg) => emsg,
+		other => panic!("expected emsg, got {:?}", other),
+	};
+
+	assert_eq!(emsg.scheme_id_uri, crate::AMF0_SCHEME_ID_URI);
+	assert_eq!(emsg.value, "onCuePoint");
+	assert_eq!(emsg.timescale, 60000);
+	assert_eq!(emsg.presentation_time, events[0].timestamp);
+
+	let values = Amf0Reader::new(emsg.message_data).read_all().unwrap();
+	assert_eq!(
+		values,
+		vec![Amf0Value::Object(
+			[("name".to_string(), Amf0Value::String("ad-break".to_string()))]
+				.into_iter()
+				.collect(),
+		)]
+	);
+}
+
+#[test]
+fn test_transmuxer_avc_opus() {
+	let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+	let data = std::fs::read(dir.join("avc_aac.flv").to_str().unwrap()).unwrap();
+
+	let mut transmuxer = Transmuxer::new();
+
+	let mut cursor = io::Cursor::new(data.into());
+	FlvHeader::demux(&mut cursor).unwrap();
+
+	let pos = cursor.position() as usize;
+	transmuxer.demux(cursor.into_inner().slice(pos..)).unwrap();
+
+	// Replace the AAC audio with Opus, a 20ms CELT frame is 960 samples.
+	let opus_head = Bytes::from_static(b"OpusHead\x01\x02\x38\x01\x80\xbb\x00\x00\x00\x00\x00");
+	for tag in transmuxer.tags.iter_mut() {
+		if let FlvTagData::Audio { data, .. } = &mut tag.data {
+			*data = match data {
+				FlvTagAudioData::Aac(AacPacket::SequenceHeader(_)) => {
+					FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::SequenceStart(opus_head.clone())))
+				}
+				_ => FlvTagAudioData::Enhanced(EnhancedAudioPacket::Opus(OpusPacket::Raw(Bytes::from_static(
+					b"\xfc\xff\xfe",
+				)))),
+			};
+		}
+	}
+
+	let mut audio_segments = 0;
+	let mut audio_timestamp = 0;
+
+	while let Some(result) = transmuxer.mux().unwrap() {
+		match result {
+			TransmuxResult::InitSegment {
+				audio_settings, data, ..
+			} => {
+				assert_eq!(
+					audio_settings,
+					AudioSettings {
+						codec: AudioCodec::Opus,
+						sample_rate: 48000,
+						channels: 2,
+						bitrate: 130127,
+						timescale: 48000,
+					}
+				);
+
+				let mut cursor = io::Cursor::new(data);
+				DynBox::demux(&mut cursor).unwrap();
+				let DynBox::Moov(moov) = DynBox::demux(&mut cursor).unwrap() else {
+					panic!("expected moov");
+				};
+
+				let stsd = &moov.traks[1].mdia.minf.stbl.stsd;
+				let Some(DynBox::Opus(opus)) = stsd.entries.first() else {
+					panic!("expected opus sample entry");
+				};
+
+				assert_eq!(opus.dops.output_channel_count, 2);
+				assert_eq!(opus.dops.pre_skip, 312);
+				assert_eq!(opus.dops.input_sample_rate, 48000);
+				assert_eq!(stsd.get_codecs().collect::<Vec<_>>(), vec!["opus"]);
+			}
+			TransmuxResult::MediaSegment(segment) if segment.ty == MediaType::Audio => {
+				assert_eq!(segment.timestamp, audio_timestamp);
+				audio_timestamp += 960;
+				audio_segments += 1;
+			}
+			_ => {}
+		}
+	}
+
+	assert!(audio_segments > 0);
+}
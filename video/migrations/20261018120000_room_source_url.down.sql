@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS rooms@idx_rooms_source_lease_expires_at;
+
+ALTER TABLE rooms DROP COLUMN IF EXISTS source_lease_expires_at;
+ALTER TABLE rooms DROP COLUMN IF EXISTS source_lease_id;
+ALTER TABLE rooms DROP COLUMN IF EXISTS source_url;
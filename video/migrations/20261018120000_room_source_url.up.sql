@@ -0,0 +1,7 @@
+-- Rooms can pull their stream from a remote source instead of waiting for a broadcaster to push to them.
+-- The lease columns are used by ingest to make sure only one puller is running for a room at a time.
+ALTER TABLE rooms ADD COLUMN source_url VARCHAR(2048);
+ALTER TABLE rooms ADD COLUMN source_lease_id UUID;
+ALTER TABLE rooms ADD COLUMN source_lease_expires_at TIMESTAMPTZ(3);
+
+CREATE INDEX idx_rooms_source_lease_expires_at ON rooms(source_lease_expires_at) WHERE source_url IS NOT NULL;
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS rooms@idx_rooms_ingest_policy_id;
+ALTER TABLE rooms DROP CONSTRAINT IF EXISTS rooms_ingest_policy_id_fkey;
+ALTER TABLE rooms DROP COLUMN IF EXISTS ingest_policy_id;
+
+DROP TABLE IF EXISTS ingest_policies CASCADE;
@@ -0,0 +1,30 @@
+-- Ingest policies limit what a broadcaster is allowed to publish to a room.
+-- Rooms use their own policy if they have one, otherwise the default policy of their organization.
+CREATE TABLE ingest_policies (
+    organization_id UUID NOT NULL,
+    id UUID NOT NULL,
+
+    max_bitrate INT8,
+    max_width INT4,
+    max_height INT4,
+    max_fps INT4,
+    allowed_video_codecs VARCHAR(16)[] NOT NULL DEFAULT ARRAY[],
+    allowed_audio_codecs VARCHAR(16)[] NOT NULL DEFAULT ARRAY[],
+    max_bytes_between_keyframes INT8,
+    max_time_between_keyframes INT8,
+
+    organization_default BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ(3) NOT NULL DEFAULT NOW(),
+
+    tags JSONB NOT NULL DEFAULT '{}'::JSONB,
+
+    PRIMARY KEY (organization_id, id)
+);
+
+ALTER TABLE ingest_policies ADD CONSTRAINT ingest_policies_organization_id_fkey FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE;
+CREATE INVERTED INDEX idx_ingest_policies_tags ON ingest_policies(organization_id, tags);
+CREATE UNIQUE INDEX idx_ingest_policies_organization_default ON ingest_policies(organization_id) WHERE organization_default;
+
+ALTER TABLE rooms ADD COLUMN ingest_policy_id UUID;
+ALTER TABLE rooms ADD CONSTRAINT rooms_ingest_policy_id_fkey FOREIGN KEY (organization_id, ingest_policy_id) REFERENCES ingest_policies(organization_id, id);
+CREATE INDEX idx_rooms_ingest_policy_id ON rooms(organization_id, ingest_policy_id);
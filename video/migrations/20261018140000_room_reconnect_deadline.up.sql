@@ -0,0 +1,2 @@
+-- When a broadcaster drops, the connection is kept around until the deadline so that a reconnect can resume it.
+ALTER TABLE rooms ADD COLUMN reconnect_deadline TIMESTAMPTZ(3);
@@ -0,0 +1,2 @@
+ALTER TABLE rooms DROP COLUMN IF EXISTS active_ingest_backup;
+ALTER TABLE rooms DROP COLUMN IF EXISTS backup_ingest_connection_id;
@@ -0,0 +1,4 @@
+-- A second publish with the backup stream key is held warm in this slot, ready to take over from the active input.
+ALTER TABLE rooms ADD COLUMN backup_ingest_connection_id UUID;
+-- If the active ingest connection is delivered by the backup input.
+ALTER TABLE rooms ADD COLUMN active_ingest_backup BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,3 @@
+ALTER TABLE organizations DROP COLUMN IF EXISTS ingest_auth_fail_open;
+ALTER TABLE organizations DROP COLUMN IF EXISTS ingest_auth_timeout;
+ALTER TABLE organizations DROP COLUMN IF EXISTS ingest_auth_url;
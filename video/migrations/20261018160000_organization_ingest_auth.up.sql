@@ -0,0 +1,6 @@
+-- An optional hook which is asked to authorize every publish to a room of the organization.
+ALTER TABLE organizations ADD COLUMN ingest_auth_url TEXT;
+-- How long to wait for the hook, in milliseconds.
+ALTER TABLE organizations ADD COLUMN ingest_auth_timeout INT8 NOT NULL DEFAULT 2000;
+-- If a publish is allowed when the hook fails or times out, the stream key is still checked.
+ALTER TABLE organizations ADD COLUMN ingest_auth_fail_open BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+ALTER TABLE rooms DROP COLUMN IF EXISTS ingest_ip_deny;
+ALTER TABLE rooms DROP COLUMN IF EXISTS ingest_ip_allow;
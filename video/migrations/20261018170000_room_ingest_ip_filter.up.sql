@@ -0,0 +1,4 @@
+-- IP addresses or CIDR ranges allowed to publish to the room, everyone is allowed if empty.
+ALTER TABLE rooms ADD COLUMN ingest_ip_allow VARCHAR(64)[] NOT NULL DEFAULT ARRAY[];
+-- IP addresses or CIDR ranges denied from publishing to the room, this takes precedence over the allow list.
+ALTER TABLE rooms ADD COLUMN ingest_ip_deny VARCHAR(64)[] NOT NULL DEFAULT ARRAY[];
@@ -0,0 +1,2 @@
+-- The health of the input of the active ingest connection, encoded as a protobuf.
+ALTER TABLE rooms ADD COLUMN ingest_health BYTES;
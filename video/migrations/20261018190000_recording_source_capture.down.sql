@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS recording_source_chunks CASCADE;
+
+ALTER TABLE recording_configs DROP COLUMN capture_source;
@@ -0,0 +1,20 @@
+ALTER TABLE recording_configs ADD COLUMN capture_source BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Recording source chunks are the archived source stream of a recording, as it was received from ingest.
+-- They are stored in the s3 bucket defined in the recording, next to an init segment for the ingest connection they were received on.
+-- A recording spans every connection of a broadcaster that reconnects, so the chunks are numbered per connection.
+CREATE TABLE recording_source_chunks (
+    organization_id UUID NOT NULL,
+    recording_id UUID NOT NULL,
+    connection_id UUID NOT NULL,
+    idx INT4 NOT NULL,
+    id UUID NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    size_bytes BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (organization_id, recording_id, connection_id, idx)
+);
+
+ALTER TABLE recording_source_chunks ADD CONSTRAINT recording_source_chunks_organization_id_fkey FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE recording_source_chunks ADD CONSTRAINT recording_source_chunks_recording_id_fkey FOREIGN KEY (organization_id, recording_id) REFERENCES recordings(organization_id, id);
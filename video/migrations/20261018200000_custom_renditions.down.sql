@@ -0,0 +1,10 @@
+ALTER TABLE transcoding_configs DROP COLUMN custom_renditions;
+
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_1';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_2';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_3';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_4';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_5';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_6';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_7';
+ALTER TYPE rendition DROP VALUE 'VIDEO_CUSTOM_8';
@@ -0,0 +1,11 @@
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_1';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_2';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_3';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_4';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_5';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_6';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_7';
+ALTER TYPE rendition ADD VALUE 'VIDEO_CUSTOM_8';
+
+-- The rungs of the custom ladder of a transcoding config, encoded as protobufs.
+ALTER TABLE transcoding_configs ADD COLUMN custom_renditions BYTES[] NOT NULL DEFAULT ARRAY[];
@@ -0,0 +1,3 @@
+ALTER TYPE rendition DROP VALUE 'AUDIO_AAC_HE';
+ALTER TYPE rendition DROP VALUE 'AUDIO_OPUS';
+ALTER TYPE rendition DROP VALUE 'AUDIO_STEREO';
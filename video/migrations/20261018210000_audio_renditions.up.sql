@@ -0,0 +1,3 @@
+ALTER TYPE rendition ADD VALUE 'AUDIO_AAC_HE';
+ALTER TYPE rendition ADD VALUE 'AUDIO_OPUS';
+ALTER TYPE rendition ADD VALUE 'AUDIO_STEREO';
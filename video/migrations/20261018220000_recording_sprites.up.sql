@@ -0,0 +1,22 @@
+-- Recording sprites are sheets of small thumbnails laid out in a grid, used by players to preview the recording while seeking.
+-- They are stored in the s3 bucket that the recording is stored in, next to the recording thumbnails.
+-- Tiles are filled row by row, the start time of every tile is stored so the sheet can be mapped back onto the timeline.
+CREATE TABLE recording_sprites (
+    organization_id UUID NOT NULL,
+    recording_id UUID NOT NULL,
+    idx INT4 NOT NULL,
+    id UUID NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    tile_times REAL[] NOT NULL,
+    tile_width INT4 NOT NULL,
+    tile_height INT4 NOT NULL,
+    columns INT4 NOT NULL,
+    rows INT4 NOT NULL,
+    size_bytes BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (organization_id, recording_id, idx)
+);
+
+ALTER TABLE recording_sprites ADD CONSTRAINT recording_sprites_organization_id_fkey FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE recording_sprites ADD CONSTRAINT recording_sprites_recording_id_fkey FOREIGN KEY (organization_id, recording_id) REFERENCES recordings(organization_id, id);
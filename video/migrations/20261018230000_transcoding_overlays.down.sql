@@ -0,0 +1 @@
+ALTER TABLE transcoding_configs DROP COLUMN overlays;
@@ -0,0 +1,2 @@
+-- The overlays drawn onto the transcoded renditions of a transcoding config, encoded as protobufs.
+ALTER TABLE transcoding_configs ADD COLUMN overlays BYTES[] NOT NULL DEFAULT ARRAY[];
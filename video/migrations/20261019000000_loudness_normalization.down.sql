@@ -0,0 +1,2 @@
+ALTER TABLE rooms DROP COLUMN IF EXISTS audio_loudness;
+ALTER TABLE transcoding_configs DROP COLUMN IF EXISTS loudness_normalization;
@@ -0,0 +1,5 @@
+-- The loudness normalization of the transcoded audio renditions, encoded as a protobuf.
+ALTER TABLE transcoding_configs ADD COLUMN loudness_normalization BYTES;
+
+-- The loudness of the audio of the active ingest connection, encoded as a protobuf.
+ALTER TABLE rooms ADD COLUMN audio_loudness BYTES;
@@ -0,0 +1,2 @@
+-- The content aware encoding of the transcoded video renditions, encoded as a protobuf.
+ALTER TABLE transcoding_configs ADD COLUMN content_aware_encoding BYTES;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS recording_caption_segments CASCADE;
+ALTER TABLE transcoding_configs DROP COLUMN IF EXISTS captions;
@@ -0,0 +1,19 @@
+-- If the CEA-608 captions (CC1) embedded in the source video are extracted into a WebVTT subtitle rendition.
+ALTER TABLE transcoding_configs ADD COLUMN captions BOOL NOT NULL DEFAULT FALSE;
+
+-- Recording caption segments are WebVTT files cut at the same times as the video segments with the same index.
+-- They are stored in the s3 bucket that the recording is stored in, next to the renditions.
+CREATE TABLE recording_caption_segments (
+    organization_id UUID NOT NULL,
+    recording_id UUID NOT NULL,
+    idx INT4 NOT NULL,
+    id UUID NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    size_bytes BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (organization_id, recording_id, idx)
+);
+
+ALTER TABLE recording_caption_segments ADD CONSTRAINT recording_caption_segments_organization_id_fkey FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE recording_caption_segments ADD CONSTRAINT recording_caption_segments_recording_id_fkey FOREIGN KEY (organization_id, recording_id) REFERENCES recordings(organization_id, id);
@@ -0,0 +1 @@
+ALTER TABLE recording_rendition_segments DROP COLUMN IF EXISTS discontinuity;
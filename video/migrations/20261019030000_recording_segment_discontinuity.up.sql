@@ -0,0 +1,2 @@
+-- If the segment is the first one after a discontinuity in the recording, such as when the broadcaster reconnected.
+ALTER TABLE recording_rendition_segments ADD COLUMN discontinuity BOOL NOT NULL DEFAULT FALSE;
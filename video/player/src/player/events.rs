@@ -2,6 +2,8 @@ use std::cell::Cell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use bytes::Bytes;
+use mp4::DynBox;
 use tsify::JsValueSerdeExt;
 use wasm_bindgen::prelude::*;
 
@@ -22,6 +24,7 @@ pub enum UserEvent {
 	Realtime,
 	Destroyed,
 	Finished,
+	Metadata(MetadataEvent),
 }
 
 #[derive(Debug, Clone, serde::Serialize, tsify::Tsify)]
@@ -37,6 +40,56 @@ pub struct VariantEvent {
 	pub previous_variant_id: i32,
 }
 
+#[derive(Debug, Clone, serde::Serialize, tsify::Tsify)]
+/// The event emitted when timed metadata is found in the stream.
+pub struct MetadataEvent {
+	/// The scheme of the event, this tells you how to interpret the data.
+	pub scheme_id_uri: String,
+
+	/// The value of the event, for RTMP metadata this is the name of the
+	/// script data tag (ie. `onCuePoint`).
+	pub value: String,
+
+	/// The time the event should be presented at, in seconds.
+	pub time: f64,
+
+	/// The duration of the event in seconds, 0 if unknown.
+	pub duration: f64,
+
+	/// The ID of the event.
+	pub id: u32,
+
+	/// The event data.
+	pub data: Vec<u8>,
+}
+
+impl MetadataEvent {
+	/// Find the events at the start of a media segment.
+	pub fn from_segment(data: &Bytes) -> Vec<Self> {
+		let mut events = Vec::new();
+		let mut cursor = std::io::Cursor::new(data.clone());
+
+		// Event message boxes are always placed before the moof.
+		while data.get(cursor.position() as usize + 4..cursor.position() as usize + 8) == Some(b"emsg") {
+			let Ok(DynBox::Emsg(emsg)) = DynBox::demux(&mut cursor) else {
+				break;
+			};
+
+			let timescale = emsg.timescale.max(1) as f64;
+			events.push(Self {
+				scheme_id_uri: emsg.scheme_id_uri,
+				value: emsg.value,
+				time: emsg.presentation_time as f64 / timescale,
+				duration: emsg.event_duration as f64 / timescale,
+				id: emsg.id,
+				data: emsg.message_data.to_vec(),
+			});
+		}
+
+		events
+	}
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EventType {
 	Error,
@@ -52,6 +105,7 @@ pub enum EventType {
 	Started,
 	Finished,
 	Visibility,
+	Metadata,
 }
 
 impl std::str::FromStr for EventType {
@@ -72,6 +126,7 @@ impl std::str::FromStr for EventType {
 			"started" => Ok(Self::Started),
 			"finished" => Ok(Self::Finished),
 			"visibility" => Ok(Self::Visibility),
+			"metadata" => Ok(Self::Metadata),
 			_ => Err(()),
 		}
 	}
@@ -93,6 +148,7 @@ impl UserEvent {
 			Self::Started => EventType::Started,
 			Self::Finished => EventType::Finished,
 			Self::Visibility => EventType::Visibility,
+			Self::Metadata(_) => EventType::Metadata,
 		}
 	}
 
@@ -111,6 +167,7 @@ impl UserEvent {
 			Self::Started => None,
 			Self::Finished => None,
 			Self::Visibility => None,
+			Self::Metadata(event) => Some(JsValue::from_serde(&event).unwrap()),
 		}
 	}
 }
@@ -90,6 +90,11 @@ type PlayerEvents = {
 
     // Finished playing the recording or room.
     finished: () => void;
+
+    // Timed metadata was found in the stream, such as an RTMP onCuePoint.
+    // The event is emitted when the media is buffered, use the time property
+    // to schedule it against the video element's currentTime.
+    metadata: (evt: MetadataEvent) => void;
 };
 
 // A Scuffle Video Player.
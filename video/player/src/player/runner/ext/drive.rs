@@ -268,6 +268,10 @@ impl Runner {
 
 					self.init_video().await;
 
+					for event in events::MetadataEvent::from_segment(&data) {
+						events::dispatch!(self.inner.borrow_mut().events.emit(events::UserEvent::Metadata(event)));
+					}
+
 					tracing::debug!(start_time, end_time, "appending video data");
 					self.source_buffers
 						.as_mut()
@@ -15,6 +15,7 @@ impl Variant {
 			audio_track: AudioTrack {
 				id: audio_track.0,
 				name: audio_track.1.name.clone(),
+				label: audio_track.1.label.clone(),
 				codec: audio_track.1.codec.clone(),
 				channels: audio_track.1.other.channels,
 				sample_rate: audio_track.1.other.sample_rate,
@@ -23,6 +24,7 @@ impl Variant {
 			video_track: video_track.map(|(id, track)| VideoTrack {
 				id,
 				name: track.name.clone(),
+				label: track.label.clone(),
 				codec: track.codec.clone(),
 				width: track.other.width,
 				height: track.other.height,
@@ -37,6 +39,7 @@ impl Variant {
 pub struct AudioTrack {
 	pub id: usize,
 	pub name: String,
+	pub label: Option<String>,
 	pub codec: String,
 	pub channels: u32,
 	pub sample_rate: u32,
@@ -47,6 +50,7 @@ pub struct AudioTrack {
 pub struct VideoTrack {
 	pub id: usize,
 	pub name: String,
+	pub label: Option<String>,
 	pub codec: String,
 	pub width: u32,
 	pub height: u32,
@@ -1,7 +1,11 @@
 mod rendition_playlist;
 mod session_playlist;
 mod session_refresh;
+mod sprite_index;
+mod subtitle_playlist;
 
 pub use rendition_playlist::*;
 pub use session_playlist::*;
 pub use session_refresh::*;
+pub use sprite_index::*;
+pub use subtitle_playlist::*;
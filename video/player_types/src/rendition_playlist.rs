@@ -27,6 +27,8 @@ pub struct RenditionPlaylist {
 	#[serde(skip)]
 	pub msn: u32,
 	#[serde(skip)]
+	pub discontinuity_sequence: u32,
+	#[serde(skip)]
 	pub skip_segments: u32,
 }
 
@@ -53,7 +55,7 @@ impl RenditionPlaylist {
 
 		m3u8.push_str(format!("#EXT-X-MEDIA-SEQUENCE:{}\n", self.msn).as_str());
 
-		m3u8.push_str("#EXT-DISCONTINUITY-SEQUENCE:0\n");
+		m3u8.push_str(format!("#EXT-X-DISCONTINUITY-SEQUENCE:{}\n", self.discontinuity_sequence).as_str());
 		if room_id.is_some() {
 			m3u8.push_str("#EXT-X-PART-INF:PART-TARGET=0.250\n");
 			if !self.finished {
@@ -90,6 +92,10 @@ impl RenditionPlaylist {
 		}
 
 		for segment in self.segments.iter() {
+			if segment.discontinuity {
+				m3u8.push_str("#EXT-X-DISCONTINUITY\n");
+			}
+
 			for part in segment.parts.iter() {
 				m3u8.push_str(
 					format!(
@@ -217,11 +223,17 @@ pub struct RenditionPlaylistSegment {
 
 	#[serde(rename = "p", default, skip_serializing_if = "Vec::is_empty")]
 	pub parts: Vec<RenditionPlaylistSegmentPart>,
+
+	/// The segment is the first one after a discontinuity, such as when the
+	/// broadcaster reconnected, so it may be encoded differently from the
+	/// segment before it.
+	#[serde(rename = "dc", default, skip_serializing_if = "is_false")]
+	pub discontinuity: bool,
 }
 
 impl RenditionPlaylistSegment {
 	pub fn duration(&self) -> f64 {
-		if self.discontinuity() {
+		if self.is_gap() {
 			return 0.0;
 		}
 
@@ -232,7 +244,8 @@ impl RenditionPlaylistSegment {
 		}
 	}
 
-	pub fn discontinuity(&self) -> bool {
+	/// The segment is a gap in a recording, it has no media.
+	pub fn is_gap(&self) -> bool {
 		self.start_time.is_none()
 	}
 }
@@ -8,6 +8,9 @@ pub struct SessionPlaylist {
 	pub audio_tracks: Vec<RoomPlaylistTrack<RoomPlaylistTrackAudio>>,
 	#[serde(rename = "s")]
 	pub session: String,
+	/// If the captions embedded in the source are served as a subtitle track.
+	#[serde(rename = "cc", default, skip_serializing_if = "std::ops::Not::not")]
+	pub captions: bool,
 }
 
 impl SessionPlaylist {
@@ -35,6 +38,22 @@ impl SessionPlaylist {
 			);
 		}
 
+		if self.captions {
+			m3u8.push_str("#EXT-X-MEDIA:TYPE=SUBTITLES,");
+			m3u8.push_str("GROUP-ID=\"captions\",");
+			m3u8.push_str("DEFAULT=NO,");
+			m3u8.push_str("AUTOSELECT=YES,");
+			m3u8.push_str("NAME=\"CC1\",");
+			m3u8.push_str(
+				format!(
+					"URI=\"/{organization_id}/{session}/captions.m3u8\"\n",
+					organization_id = organization_id,
+					session = self.session,
+				)
+				.as_str(),
+			);
+		}
+
 		for video in self.video_tracks.iter() {
 			for audio in self.audio_tracks.iter() {
 				m3u8.push_str("#EXT-X-STREAM-INF:");
@@ -42,7 +61,11 @@ impl SessionPlaylist {
 				m3u8.push_str(format!("CODECS=\"{},{}\",", video.codec, audio.codec).as_str());
 				m3u8.push_str(format!("RESOLUTION={}x{},", video.other.width, video.other.height).as_str());
 				m3u8.push_str(format!("FRAME-RATE={},", video.other.frame_rate).as_str());
-				m3u8.push_str(format!("AUDIO=\"{}\"\n", audio.name).as_str());
+				m3u8.push_str(format!("AUDIO=\"{}\"", audio.name).as_str());
+				if self.captions {
+					m3u8.push_str(",SUBTITLES=\"captions\"");
+				}
+				m3u8.push('\n');
 				m3u8.push_str(
 					format!(
 						"/{organization_id}/{session}/{name}.m3u8\n",
@@ -63,6 +86,9 @@ impl SessionPlaylist {
 pub struct RoomPlaylistTrack<T> {
 	#[serde(rename = "n")]
 	pub name: String,
+	/// A friendly name for the track, set for custom renditions.
+	#[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+	pub label: Option<String>,
 	#[serde(rename = "br")]
 	pub bitrate: u32,
 	#[serde(rename = "c")]
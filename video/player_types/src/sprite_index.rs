@@ -0,0 +1,90 @@
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SpriteIndex {
+	#[serde(rename = "t")]
+	pub tiles: Vec<SpriteTile>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpriteTile {
+	#[serde(rename = "s")]
+	pub start_time: f64,
+	#[serde(rename = "e")]
+	pub end_time: f64,
+	#[serde(rename = "u")]
+	pub url: String,
+	#[serde(rename = "x")]
+	pub x: u32,
+	#[serde(rename = "y")]
+	pub y: u32,
+	#[serde(rename = "w")]
+	pub width: u32,
+	#[serde(rename = "h")]
+	pub height: u32,
+}
+
+impl SpriteIndex {
+	/// Adds the tiles of a sheet, each tile is shown until the next one starts
+	/// and the last one until the end time of the sheet.
+	pub fn push_sheet(
+		&mut self,
+		url: &str,
+		tile_times: &[f64],
+		end_time: f64,
+		tile_width: u32,
+		tile_height: u32,
+		columns: u32,
+	) {
+		let columns = columns.max(1);
+
+		self.tiles.extend(tile_times.iter().enumerate().map(|(idx, start_time)| {
+			let idx = idx as u32;
+
+			SpriteTile {
+				start_time: *start_time,
+				end_time: tile_times.get(idx as usize + 1).copied().unwrap_or(end_time),
+				url: url.to_string(),
+				x: (idx % columns) * tile_width,
+				y: (idx / columns) * tile_height,
+				width: tile_width,
+				height: tile_height,
+			}
+		}));
+	}
+
+	pub fn to_vtt(&self) -> String {
+		let mut vtt = String::new();
+
+		vtt.push_str("WEBVTT\n");
+
+		for tile in self.tiles.iter() {
+			write!(
+				vtt,
+				"\n{} --> {}\n{}#xywh={},{},{},{}\n",
+				vtt_timestamp(tile.start_time),
+				vtt_timestamp(tile.end_time),
+				tile.url,
+				tile.x,
+				tile.y,
+				tile.width,
+				tile.height,
+			)
+			.unwrap();
+		}
+
+		vtt
+	}
+}
+
+fn vtt_timestamp(time: f64) -> String {
+	let millis = (time.max(0.0) * 1000.0).round() as u64;
+
+	format!(
+		"{:02}:{:02}:{:02}.{:03}",
+		millis / 3_600_000,
+		millis / 60_000 % 60,
+		millis / 1000 % 60,
+		millis % 1000
+	)
+}
@@ -0,0 +1,58 @@
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SubtitlePlaylist {
+	#[serde(rename = "s")]
+	pub segments: Vec<SubtitlePlaylistSegment>,
+	#[serde(rename = "f", default, skip_serializing_if = "std::ops::Not::not")]
+	pub finished: bool,
+
+	#[serde(skip)]
+	pub msn: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SubtitlePlaylistSegment {
+	#[serde(rename = "u")]
+	pub url: String,
+	#[serde(rename = "st")]
+	pub start_time: f64,
+	#[serde(rename = "et")]
+	pub end_time: f64,
+}
+
+impl SubtitlePlaylist {
+	/// The WebVTT segments are cut at the same times as the video segments, so
+	/// the playlist lines up with the renditions.
+	pub fn to_m3u8(&self) -> String {
+		let mut m3u8 = String::new();
+
+		m3u8.push_str("#EXTM3U\n");
+		m3u8.push_str("#EXT-X-VERSION:6\n");
+
+		let target_duration = self
+			.segments
+			.iter()
+			.map(|s| (s.end_time - s.start_time).ceil() as u32)
+			.max()
+			.unwrap_or_default()
+			.max(5);
+
+		m3u8.push_str(format!("#EXT-X-TARGETDURATION:{target_duration}\n").as_str());
+
+		if self.finished {
+			m3u8.push_str("#EXT-X-PLAYLIST-TYPE:VOD\n");
+		}
+
+		m3u8.push_str(format!("#EXT-X-MEDIA-SEQUENCE:{}\n", self.msn).as_str());
+
+		for segment in self.segments.iter() {
+			m3u8.push_str(format!("#EXTINF:{:.3},\n", segment.end_time - segment.start_time).as_str());
+			m3u8.push_str(format!("{}\n", segment.url).as_str());
+		}
+
+		if self.finished {
+			m3u8.push_str("#EXT-X-ENDLIST\n");
+		}
+
+		m3u8
+	}
+}
@@ -3,6 +3,81 @@ use std::time::Duration;
 
 use binary_helper::config::TlsConfig;
 
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct SpriteConfig {
+	/// The width of a single tile in pixels
+	pub tile_width: u32,
+
+	/// The height of a single tile in pixels
+	pub tile_height: u32,
+
+	/// The number of tiles in a row
+	pub columns: u32,
+
+	/// The number of rows on a sheet
+	pub rows: u32,
+}
+
+impl Default for SpriteConfig {
+	fn default() -> Self {
+		Self {
+			tile_width: 160,
+			tile_height: 90,
+			columns: 5,
+			rows: 5,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
+#[serde(default)]
+pub struct CapacityConfig {
+	/// The estimated cost of the jobs the transcoder takes on at once, in
+	/// megapixels per second, 0 for no limit
+	pub budget: f64,
+
+	/// The cost of encoding a rendition with h264, relative to decoding the
+	/// input
+	pub h264_factor: f64,
+
+	/// The cost of encoding a rendition with hevc, relative to decoding the
+	/// input
+	pub hevc_factor: f64,
+
+	/// The cost of encoding a rendition with av1, relative to decoding the
+	/// input
+	pub av1_factor: f64,
+
+	/// How long a request which does not fit in the budget is held back before
+	/// it is offered to a transcoder again, doubled every time it does not fit
+	pub retry_delay: Duration,
+
+	/// The longest a request which does not fit in the budget is held back
+	pub max_retry_delay: Duration,
+
+	/// The subject the load of the transcoder is published on
+	pub load_subject: String,
+
+	/// The interval the load of the transcoder is published at
+	pub load_report_interval: Duration,
+}
+
+impl Default for CapacityConfig {
+	fn default() -> Self {
+		Self {
+			budget: 0.0,
+			h264_factor: 1.0,
+			hevc_factor: 2.0,
+			av1_factor: 3.0,
+			retry_delay: Duration::from_secs(1),
+			max_retry_delay: Duration::from_secs(30),
+			load_subject: "scuffle-video-transcoder_load".to_string(),
+			load_report_interval: Duration::from_secs(10),
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, config::Config, serde::Deserialize)]
 #[serde(default)]
 pub struct TranscoderConfig {
@@ -12,6 +87,9 @@ pub struct TranscoderConfig {
 	/// The name of the transcoder requests queue to use
 	pub transcoder_request_subject: String,
 
+	/// The name of the queue uploaded recordings are transcoded from
+	pub recording_upload_subject: String,
+
 	/// The NATS KV bucket to use for metadata
 	pub metadata_kv_store: String,
 
@@ -27,6 +105,9 @@ pub struct TranscoderConfig {
 	/// The maximum part length
 	pub max_part_duration: Duration,
 
+	/// The jobs the transcoder takes on, by their estimated cost
+	pub capacity: CapacityConfig,
+
 	/// The TLS config to use when connecting to ingest
 	pub ingest_tls: Option<TlsConfig>,
 
@@ -36,12 +117,38 @@ pub struct TranscoderConfig {
 	/// The interval to take screenshots at
 	pub screenshot_interval: Duration,
 
+	/// The trick-play sprite sheets built from the screenshots
+	pub sprite: SpriteConfig,
+
+	/// The font used to draw text overlays, the default font is used if unset
+	pub overlay_font_file: Option<String>,
+
+	/// The interval the measured loudness of the audio is reported at
+	pub loudness_report_interval: Duration,
+
+	/// The length of the chunks the source of a recording is archived in
+	pub source_chunk_duration: Duration,
+
 	/// The encoder to use for h264
 	pub h264_encoder: Option<String>,
 
 	/// H264 encoder options
 	#[config(cli(skip), env(skip))]
 	pub h264_encoder_options: HashMap<String, String>,
+
+	/// The encoder to use for hevc
+	pub hevc_encoder: Option<String>,
+
+	/// HEVC encoder options
+	#[config(cli(skip), env(skip))]
+	pub hevc_encoder_options: HashMap<String, String>,
+
+	/// The encoder to use for av1
+	pub av1_encoder: Option<String>,
+
+	/// AV1 encoder options
+	#[config(cli(skip), env(skip))]
+	pub av1_encoder_options: HashMap<String, String>,
 }
 
 impl Default for TranscoderConfig {
@@ -49,16 +156,26 @@ impl Default for TranscoderConfig {
 		Self {
 			events_stream_name: "scuffle-video-events".to_string(),
 			transcoder_request_subject: "scuffle-video-transcoder_requests".to_string(),
+			recording_upload_subject: "scuffle-video-recording_uploads".to_string(),
 			metadata_kv_store: "scuffle-video-transcoder_metadata".to_string(),
 			media_ob_store: "scuffle-video-transcoder_media".to_string(),
 			min_segment_duration: Duration::from_secs(2),
 			target_part_duration: Duration::from_millis(250),
 			max_part_duration: Duration::from_millis(500),
 			screenshot_interval: Duration::from_secs(5),
+			sprite: SpriteConfig::default(),
+			overlay_font_file: None,
+			loudness_report_interval: Duration::from_secs(10),
+			source_chunk_duration: Duration::from_secs(60),
+			capacity: CapacityConfig::default(),
 			ingest_tls: None,
 			playlist_segments: 5,
 			h264_encoder: Some("libx264".to_string()),
 			h264_encoder_options: vec![("tune".into(), "zerolatency".into())].into_iter().collect(),
+			hevc_encoder: Some("libx265".to_string()),
+			hevc_encoder_options: vec![("tune".into(), "zerolatency".into())].into_iter().collect(),
+			av1_encoder: Some("libsvtav1".to_string()),
+			av1_encoder_options: vec![("preset".into(), "10".into())].into_iter().collect(),
 		}
 	}
 }
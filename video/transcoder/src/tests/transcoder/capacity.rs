@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use mp4::codec::VideoCodec;
+use pb::scuffle::video::v1::types::{Rendition, VideoConfig};
+
+use crate::config::CapacityConfig;
+use crate::transcoder::capacity::{estimate_cost, retry_delay, Capacity, Load};
+
+fn video_config(rendition: Rendition, codec: VideoCodec) -> VideoConfig {
+	VideoConfig {
+		rendition: rendition.into(),
+		codec: codec.to_string(),
+		bitrate: 1024 * 1024,
+		width: 1920,
+		height: 1080,
+		fps: 60,
+		name: String::new(),
+	}
+}
+
+const AVC: VideoCodec = VideoCodec::Avc {
+	profile: 100,
+	constraint_set: 0,
+	level: 51,
+};
+
+const AV1: VideoCodec = VideoCodec::Av1 {
+	profile: 0,
+	level: 9,
+	tier: false,
+	depth: 8,
+	monochrome: false,
+	sub_sampling_x: true,
+	sub_sampling_y: true,
+	color_primaries: 1,
+	transfer_characteristics: 1,
+	matrix_coefficients: 1,
+	full_range_flag: false,
+};
+
+#[test]
+fn test_estimate_cost() {
+	let config = CapacityConfig::default();
+	let input = video_config(Rendition::VideoSource, AVC);
+
+	// 1080p60 is about 124 megapixels per second
+	let pixel_rate = 1920.0 * 1080.0 * 60.0 / 1_000_000.0;
+
+	// Passing the source through only decodes it
+	let cost = estimate_cost(&config, &input, &[input.clone()]);
+	assert!((cost - pixel_rate).abs() < 1e-9, "unexpected cost: {cost}");
+
+	let cost = estimate_cost(
+		&config,
+		&input,
+		&[
+			input.clone(),
+			video_config(Rendition::VideoHd, AVC),
+			video_config(Rendition::VideoSd, AVC),
+			video_config(Rendition::VideoLd, AV1),
+		],
+	);
+	let expected = pixel_rate * (1.0 + 2.0 * config.h264_factor + config.av1_factor);
+	assert!((cost - expected).abs() < 1e-9, "unexpected cost: {cost}, expected {expected}");
+}
+
+#[test]
+fn test_capacity_budget() {
+	let capacity = Capacity::new(100.0);
+
+	// An idle transcoder takes on a job larger than the budget
+	let large = capacity.try_acquire(150.0).expect("idle transcoder rejected a job");
+	assert!(capacity.try_acquire(10.0).is_none());
+	drop(large);
+
+	let first = capacity.try_acquire(60.0).unwrap();
+	let second = capacity.try_acquire(40.0).unwrap();
+	assert!(capacity.try_acquire(1.0).is_none());
+
+	assert_eq!(
+		capacity.report(),
+		Load {
+			load: 100.0,
+			jobs: 2,
+			rejected: 2,
+		}
+	);
+	// The rejected requests are counted from the last report
+	assert_eq!(capacity.report().rejected, 0);
+
+	drop(first);
+	let third = capacity.try_acquire(50.0).unwrap();
+	assert_eq!(capacity.report().jobs, 2);
+
+	drop(second);
+	drop(third);
+	assert_eq!(capacity.report(), Load::default());
+}
+
+#[test]
+fn test_capacity_unlimited() {
+	let capacity = Capacity::new(0.0);
+
+	let guards = (0..10).map(|_| capacity.try_acquire(1000.0).unwrap()).collect::<Vec<_>>();
+	assert_eq!(capacity.report().jobs, 10);
+
+	drop(guards);
+	assert_eq!(capacity.report().jobs, 0);
+}
+
+#[test]
+fn test_capacity_retry_delay() {
+	let config = CapacityConfig {
+		retry_delay: Duration::from_secs(1),
+		max_retry_delay: Duration::from_secs(30),
+		..Default::default()
+	};
+
+	let delays = [1, 2, 3, 5, 6, 100, i64::MAX].map(|delivered| retry_delay(&config, delivered).as_secs());
+	assert_eq!(delays, [1, 2, 4, 16, 30, 30, 30]);
+
+	// Deliveries are counted from one
+	assert_eq!(retry_delay(&config, 0), Duration::from_secs(1));
+}
@@ -0,0 +1,143 @@
+use crate::transcoder::job::captions::{Caption, CaptionDecoder, CaptionTrack};
+
+/// Sets the odd parity bit CEA-608 requires on a byte.
+fn parity(b: u8) -> u8 {
+	if b.count_ones() % 2 == 0 { b | 0x80 } else { b }
+}
+
+/// The `cc_data` of a frame carrying a single pair on the first field.
+fn cc_data(b1: u8, b2: u8) -> Vec<u8> {
+	vec![0xfc, parity(b1), parity(b2)]
+}
+
+/// Control codes are sent twice, as broadcasters do.
+fn control(decoder: &mut CaptionDecoder, b1: u8, b2: u8) -> Option<String> {
+	let first = decoder.decode(&cc_data(b1, b2));
+	let second = decoder.decode(&cc_data(b1, b2));
+	assert_eq!(second, None, "the repeated control code is ignored");
+	first
+}
+
+fn text(decoder: &mut CaptionDecoder, text: &str) -> Option<String> {
+	let mut result = None;
+	for pair in text.as_bytes().chunks(2) {
+		result = result.or(decoder.decode(&cc_data(pair[0], pair.get(1).copied().unwrap_or(0))));
+	}
+
+	result
+}
+
+#[test]
+fn test_captions_pop_on() {
+	let mut decoder = CaptionDecoder::default();
+
+	// Resume caption loading, then a preamble address code for row 15
+	assert_eq!(control(&mut decoder, 0x14, 0x20), None);
+	assert_eq!(control(&mut decoder, 0x14, 0x70), None);
+	assert_eq!(text(&mut decoder, "HELLO WORLD"), None, "loaded captions are not shown");
+
+	// End of caption shows the loaded caption
+	assert_eq!(control(&mut decoder, 0x14, 0x2f), Some("HELLO WORLD".to_owned()));
+
+	// Erase displayed memory clears it
+	assert_eq!(control(&mut decoder, 0x14, 0x2c), Some(String::new()));
+}
+
+#[test]
+fn test_captions_roll_up() {
+	let mut decoder = CaptionDecoder::default();
+
+	// Roll-up with 2 rows
+	assert_eq!(control(&mut decoder, 0x14, 0x25), None, "nothing is shown yet");
+
+	assert_eq!(text(&mut decoder, "FIRST"), None);
+	assert_eq!(control(&mut decoder, 0x14, 0x2d), Some("FIRST".to_owned()));
+
+	assert_eq!(text(&mut decoder, "SECOND"), None);
+	assert_eq!(control(&mut decoder, 0x14, 0x2d), Some("FIRST\nSECOND".to_owned()));
+
+	// The first row rolls out of the window
+	assert_eq!(text(&mut decoder, "THIRD"), None);
+	assert_eq!(control(&mut decoder, 0x14, 0x2d), Some("SECOND\nTHIRD".to_owned()));
+}
+
+#[test]
+fn test_captions_characters() {
+	let mut decoder = CaptionDecoder::default();
+
+	control(&mut decoder, 0x14, 0x20);
+	control(&mut decoder, 0x14, 0x70);
+
+	// A special character, and an extended character replacing the character
+	// sent before it
+	text(&mut decoder, "A");
+	control(&mut decoder, 0x11, 0x37);
+	text(&mut decoder, "E");
+	control(&mut decoder, 0x12, 0x21);
+	// The second channel is ignored
+	control(&mut decoder, 0x1c, 0x20);
+	text(&mut decoder, "XX");
+
+	assert_eq!(control(&mut decoder, 0x14, 0x2f), Some("A♪É".to_owned()));
+}
+
+#[test]
+fn test_captions_webvtt_segments() {
+	let mut track = CaptionTrack::default();
+
+	track.push(Caption {
+		time: 1.0,
+		text: "HELLO".to_owned(),
+	});
+	track.push(Caption {
+		time: 3.0,
+		text: "<WORLD> & MORE".to_owned(),
+	});
+
+	// A cue running past the segment is clipped to it
+	let segment = String::from_utf8(track.cut(0.0, 2.0).to_vec()).unwrap();
+	assert_eq!(
+		segment,
+		"WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n\n00:00:01.000 --> 00:00:02.000\nHELLO\n"
+	);
+
+	// And continues in the next one, along with the caption still shown
+	let segment = String::from_utf8(track.cut(2.0, 4.0).to_vec()).unwrap();
+	assert_eq!(
+		segment,
+		"WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n\n00:00:02.000 --> 00:00:03.000\nHELLO\n\n00:00:03.000 --> 00:00:04.000\n&lt;WORLD&gt; &amp; MORE\n"
+	);
+
+	track.push(Caption {
+		time: 4.5,
+		text: String::new(),
+	});
+
+	let segment = String::from_utf8(track.cut(4.0, 6.0).to_vec()).unwrap();
+	assert_eq!(
+		segment,
+		"WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n\n00:00:04.000 --> 00:00:04.500\n&lt;WORLD&gt; &amp; MORE\n"
+	);
+
+	// A segment without captions only has the header
+	let segment = String::from_utf8(track.cut(6.0, 8.0).to_vec()).unwrap();
+	assert_eq!(segment, "WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n");
+}
+
+#[test]
+fn test_captions_offset() {
+	let mut track = CaptionTrack::default();
+
+	// A reconnect continues the timeline of the renditions at 100 seconds
+	track.set_offset(100.0);
+	track.push(Caption {
+		time: 1.0,
+		text: "AGAIN".to_owned(),
+	});
+
+	let segment = String::from_utf8(track.cut(100.0, 102.0).to_vec()).unwrap();
+	assert_eq!(
+		segment,
+		"WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n\n00:01:41.000 --> 00:01:42.000\nAGAIN\n"
+	);
+}
@@ -0,0 +1,116 @@
+use ffmpeg::ffi::{AVPixelFormat, AVRational};
+use ffmpeg::frame::VideoFrame;
+use pb::scuffle::video::v1::types::{content_aware_encoding, ContentAwareEncoding};
+
+use crate::transcoder::job::complexity::{scaled_bitrate, ComplexityMeter, RenditionRate};
+
+const WIDTH: i32 = 320;
+const HEIGHT: i32 = 180;
+const FPS: i64 = 30;
+
+const TIME_BASE: AVRational = AVRational { num: 1, den: 30 };
+
+fn content_aware_encoding() -> ContentAwareEncoding {
+	ContentAwareEncoding {
+		mode: content_aware_encoding::Mode::Bitrate.into(),
+		min_bitrate_ratio: 0.25,
+		max_bitrate_ratio: Some(0.75),
+		crf: None,
+		analysis_duration: Some(2),
+	}
+}
+
+/// A yuv420p frame whose luma is filled by the given function of the
+/// position.
+fn frame(idx: i64, luma: impl Fn(usize, usize) -> u8) -> VideoFrame {
+	let mut frame = VideoFrame::alloc(WIDTH, HEIGHT, AVPixelFormat::AV_PIX_FMT_YUV420P).unwrap();
+
+	let linesize = frame.linesize(0).unwrap() as usize;
+	let data = frame.data_mut(0).unwrap();
+	for y in 0..HEIGHT as usize {
+		for x in 0..WIDTH as usize {
+			data[y * linesize + x] = luma(x, y);
+		}
+	}
+
+	frame.set_pts(Some(idx));
+	frame
+}
+
+/// Noise which changes every frame, the hardest a scene gets to encode.
+fn noise(x: usize, y: usize, idx: i64) -> u8 {
+	let mut v = (x as u32) ^ ((y as u32) << 12) ^ ((idx as u32) << 22);
+	v ^= v >> 16;
+	v = v.wrapping_mul(0x7feb352d);
+	v ^= v >> 15;
+	v = v.wrapping_mul(0x846ca68b);
+	v ^= v >> 16;
+	v as u8
+}
+
+/// Sends the frames to a meter, returning the complexity of every window.
+fn measure(frames: impl Iterator<Item = VideoFrame>) -> Vec<f64> {
+	let mut meter = ComplexityMeter::new(&content_aware_encoding(), AVPixelFormat::AV_PIX_FMT_YUV420P, TIME_BASE).unwrap();
+
+	frames.filter_map(|frame| meter.send_frame(&frame)).collect()
+}
+
+#[test]
+fn test_complexity_static() {
+	// A slideshow, the same flat gray frame for 5 seconds
+	let complexity = measure((0..5 * FPS).map(|idx| frame(idx, |_, _| 128)));
+
+	assert_eq!(complexity.len(), 2, "a complexity for every full window");
+	for complexity in complexity {
+		assert_eq!(complexity, 0.0);
+		assert_eq!(
+			scaled_bitrate(&content_aware_encoding(), 4000 * 1024, complexity),
+			1000 * 1024
+		);
+	}
+}
+
+#[test]
+fn test_complexity_motion() {
+	let complexity = measure((0..5 * FPS).map(|idx| frame(idx, |x, y| noise(x, y, idx))));
+
+	assert_eq!(complexity.len(), 2, "a complexity for every full window");
+	for complexity in complexity {
+		assert!(complexity > 0.8, "complexity: {complexity}");
+
+		let bitrate = scaled_bitrate(&content_aware_encoding(), 4000 * 1024, complexity);
+		assert!(bitrate > 2600 * 1024 && bitrate <= 3000 * 1024, "bitrate: {bitrate}");
+	}
+}
+
+#[test]
+fn test_complexity_unsupported_pixel_format() {
+	assert!(ComplexityMeter::new(&content_aware_encoding(), AVPixelFormat::AV_PIX_FMT_YUV420P10LE, TIME_BASE).is_err());
+}
+
+#[test]
+fn test_rendition_rate_segment_boundary() {
+	let content_aware_encoding = content_aware_encoding();
+	let mut rate = RenditionRate::new(&content_aware_encoding, 4000 * 1024, 60);
+
+	assert_eq!(rate.bitrate(), 3000 * 1024, "starts at the max bitrate");
+
+	// The first frame starts a segment but nothing was measured yet
+	assert_eq!(rate.next_frame(), None);
+
+	rate.update(&content_aware_encoding, 0.0);
+
+	// The new bitrate waits for the next segment
+	for _ in 1..60 {
+		assert_eq!(rate.next_frame(), None);
+	}
+
+	assert_eq!(rate.next_frame(), Some(1000 * 1024));
+	assert_eq!(rate.bitrate(), 1000 * 1024);
+
+	// The same bitrate does not reconfigure the encoder
+	rate.update(&content_aware_encoding, 0.0);
+	for _ in 0..120 {
+		assert_eq!(rate.next_frame(), None);
+	}
+}
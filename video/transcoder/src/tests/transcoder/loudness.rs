@@ -0,0 +1,127 @@
+use ffmpeg::ffi::{AVSampleFormat, AV_CH_LAYOUT_STEREO};
+use ffmpeg::filter_graph::{Filter, FilterGraph};
+use ffmpeg::frame::{AudioFrame, Frame};
+use pb::scuffle::video::v1::types::LoudnessNormalization;
+
+use crate::transcoder::job::loudness::{normalization_filter, LoudnessMeter};
+
+const SAMPLE_RATE: i32 = 48000;
+const FRAME_SIZE: i32 = 1024;
+
+fn abuffer() -> String {
+	format!(
+		"time_base=1/{SAMPLE_RATE}:sample_rate={SAMPLE_RATE}:sample_fmt={}:channel_layout=0x{:x}",
+		AVSampleFormat::AV_SAMPLE_FMT_FLT as i32,
+		AV_CH_LAYOUT_STEREO,
+	)
+}
+
+/// A stereo 1kHz sine wave with the given peak amplitude, a peak of 0.1 is a
+/// loudness of -20 LUFS.
+fn sine(amplitude: f32, seconds: i32) -> Vec<AudioFrame> {
+	(0..seconds * SAMPLE_RATE / FRAME_SIZE)
+		.map(|idx| {
+			let mut frame = AudioFrame::alloc(
+				FRAME_SIZE,
+				AVSampleFormat::AV_SAMPLE_FMT_FLT,
+				AV_CH_LAYOUT_STEREO,
+				SAMPLE_RATE,
+			)
+			.unwrap();
+
+			let data = frame.data_mut(0).unwrap();
+			for n in 0..FRAME_SIZE as usize {
+				let t = (idx as usize * FRAME_SIZE as usize + n) as f32 / SAMPLE_RATE as f32;
+				let sample = (amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin()).to_le_bytes();
+
+				data[n * 8..n * 8 + 4].copy_from_slice(&sample);
+				data[n * 8 + 4..n * 8 + 8].copy_from_slice(&sample);
+			}
+
+			frame.set_pts(Some(idx as i64 * FRAME_SIZE as i64));
+			frame
+		})
+		.collect()
+}
+
+/// Runs the frames through the loudnorm filter, converting the output back to
+/// the format of the input.
+fn normalize(loudness_normalization: &LoudnessNormalization, frames: &[AudioFrame]) -> Vec<Frame> {
+	let mut graph = FilterGraph::new().unwrap();
+
+	graph.add(Filter::get("abuffer").unwrap(), "in", &abuffer()).unwrap();
+	graph.add(Filter::get("abuffersink").unwrap(), "out", "").unwrap();
+
+	graph
+		.output("in", 0)
+		.unwrap()
+		.input("out", 0)
+		.unwrap()
+		.parse(&format!(
+			"{},aresample={SAMPLE_RATE},aformat=sample_fmts=flt:channel_layouts=stereo",
+			normalization_filter(loudness_normalization),
+		))
+		.unwrap();
+
+	graph.validate().unwrap();
+
+	for frame in frames {
+		graph.get("in").unwrap().source().send_frame(frame).unwrap();
+	}
+
+	graph.get("in").unwrap().source().send_eof(None).unwrap();
+
+	let mut output = Vec::new();
+	let mut sink = graph.get("out").unwrap().sink();
+	while let Some(frame) = sink.receive_frame().unwrap() {
+		output.push(frame);
+	}
+
+	output
+}
+
+#[test]
+fn test_loudness_meter() {
+	let mut meter = LoudnessMeter::new(&abuffer()).unwrap();
+
+	for frame in sine(0.1, 10) {
+		meter.send_frame(&frame).unwrap();
+	}
+
+	let loudness = meter.loudness().expect("no measurement");
+	assert!(
+		(-21.0..-19.0).contains(&loudness.integrated_loudness),
+		"unexpected integrated loudness {}",
+		loudness.integrated_loudness
+	);
+	assert!(
+		(-21.0..-19.0).contains(&loudness.true_peak),
+		"unexpected true peak {}",
+		loudness.true_peak
+	);
+}
+
+#[test]
+fn test_loudness_normalization() {
+	let loudness_normalization = LoudnessNormalization {
+		integrated_loudness: -16.0,
+		true_peak: -1.5,
+		loudness_range: None,
+	};
+
+	assert_eq!(normalization_filter(&loudness_normalization), "loudnorm=I=-16:TP=-1.5:LRA=11");
+
+	let mut meter = LoudnessMeter::new(&abuffer()).unwrap();
+
+	for frame in normalize(&loudness_normalization, &sine(0.1, 20)) {
+		meter.send_frame(&frame).unwrap();
+	}
+
+	let loudness = meter.loudness().expect("no measurement");
+	assert!(
+		(-17.5..-14.5).contains(&loudness.integrated_loudness),
+		"unexpected integrated loudness {}",
+		loudness.integrated_loudness
+	);
+	assert!(loudness.true_peak <= -1.0, "unexpected true peak {}", loudness.true_peak);
+}
@@ -35,6 +35,13 @@ use crate::config::TranscoderConfig;
 use crate::global::TranscoderState;
 use crate::transcoder;
 
+mod capacity;
+mod captions;
+mod complexity;
+mod loudness;
+mod overlay;
+mod upload;
+
 type IngestRequest = (
 	mpsc::Sender<Result<IngestWatchResponse>>,
 	tonic::Streaming<IngestWatchRequest>,
@@ -85,6 +92,7 @@ async fn test_transcode() {
 	let (global, handler) = crate::tests::global::mock_global_state(TranscoderConfig {
 		events_stream_name: Ulid::new().to_string(),
 		transcoder_request_subject: Ulid::new().to_string(),
+		recording_upload_subject: Ulid::new().to_string(),
 		metadata_kv_store: Ulid::new().to_string(),
 		media_ob_store: Ulid::new().to_string(),
 		..Default::default()
@@ -161,6 +169,7 @@ async fn test_transcode() {
 			height: 2160,
 			width: 3840,
 			rendition: Rendition::VideoSource.into(),
+			name: String::new(),
 		}
 		.encode_to_vec(),
 	)
@@ -191,6 +200,7 @@ async fn test_transcode() {
 				request_id: Some(req_id.into()),
 				connection_id: Some(connection_id.into()),
 				grpc_endpoint: format!("localhost:{}", port),
+				discontinuity: false,
 			}
 			.encode_to_vec()
 			.into(),
@@ -258,12 +268,13 @@ async fn test_transcode() {
 								keyframe: ms.keyframe,
 								timescale: match ms.ty {
 									transmuxer::MediaType::Audio => audio.as_ref().unwrap().timescale,
-									transmuxer::MediaType::Video => video.as_ref().unwrap().timescale,
+									transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => video.as_ref().unwrap().timescale,
 								},
 								timestamp: ms.timestamp,
 								r#type: match ms.ty {
 									transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio.into(),
 									transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video.into(),
+									transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata.into(),
 								},
 							})),
 						}))
@@ -611,6 +622,7 @@ async fn test_transcode_reconnect() {
 	let (global, handler) = crate::tests::global::mock_global_state(TranscoderConfig {
 		events_stream_name: Ulid::new().to_string(),
 		transcoder_request_subject: Ulid::new().to_string(),
+		recording_upload_subject: Ulid::new().to_string(),
 		metadata_kv_store: Ulid::new().to_string(),
 		media_ob_store: Ulid::new().to_string(),
 		..Default::default()
@@ -698,6 +710,7 @@ async fn test_transcode_reconnect() {
 			height: 3840,
 			width: 2160,
 			rendition: Rendition::VideoSource.into(),
+			name: String::new(),
 		}
 		.encode_to_vec(),
 	)
@@ -748,6 +761,7 @@ async fn test_transcode_reconnect() {
 					request_id: Some(req_id.into()),
 					connection_id: Some(connection_id.into()),
 					grpc_endpoint: format!("localhost:{}", port),
+					discontinuity: false,
 				}
 				.encode_to_vec()
 				.into(),
@@ -808,12 +822,13 @@ async fn test_transcode_reconnect() {
 								keyframe: ms.keyframe,
 								timescale: match ms.ty {
 									transmuxer::MediaType::Audio => audio.as_ref().unwrap().timescale,
-									transmuxer::MediaType::Video => video.as_ref().unwrap().timescale,
+									transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => video.as_ref().unwrap().timescale,
 								},
 								timestamp: ms.timestamp,
 								r#type: match ms.ty {
 									transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio.into(),
 									transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video.into(),
+									transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata.into(),
 								},
 							})),
 						}))
@@ -915,6 +930,7 @@ async fn test_transcode_reconnect() {
 					request_id: Some(new_req_id.into()),
 					connection_id: Some(connection_id.into()),
 					grpc_endpoint: format!("localhost:{}", port),
+					discontinuity: false,
 				}
 				.encode_to_vec()
 				.into(),
@@ -975,12 +991,13 @@ async fn test_transcode_reconnect() {
 								keyframe: ms.keyframe,
 								timescale: match ms.ty {
 									transmuxer::MediaType::Audio => audio.as_ref().unwrap().timescale,
-									transmuxer::MediaType::Video => video.as_ref().unwrap().timescale,
+									transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => video.as_ref().unwrap().timescale,
 								},
 								timestamp: ms.timestamp,
 								r#type: match ms.ty {
 									transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio.into(),
 									transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video.into(),
+									transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata.into(),
 								},
 							})),
 						}))
@@ -1090,6 +1107,7 @@ async fn test_transcode_reconnect() {
 					request_id: Some(new_req_id.into()),
 					connection_id: Some(connection_id.into()),
 					grpc_endpoint: format!("localhost:{}", port),
+					discontinuity: false,
 				}
 				.encode_to_vec()
 				.into(),
@@ -1150,12 +1168,13 @@ async fn test_transcode_reconnect() {
 								keyframe: ms.keyframe,
 								timescale: match ms.ty {
 									transmuxer::MediaType::Audio => audio.as_ref().unwrap().timescale,
-									transmuxer::MediaType::Video => video.as_ref().unwrap().timescale,
+									transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => video.as_ref().unwrap().timescale,
 								},
 								timestamp: ms.timestamp,
 								r#type: match ms.ty {
 									transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio.into(),
 									transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video.into(),
+									transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata.into(),
 								},
 							})),
 						}))
@@ -1275,6 +1294,7 @@ async fn test_transcode_reconnect() {
 					request_id: Some(new_req_id.into()),
 					connection_id: Some(connection_id.into()),
 					grpc_endpoint: format!("localhost:{}", port),
+					discontinuity: false,
 				}
 				.encode_to_vec()
 				.into(),
@@ -1335,12 +1355,13 @@ async fn test_transcode_reconnect() {
 								keyframe: ms.keyframe,
 								timescale: match ms.ty {
 									transmuxer::MediaType::Audio => audio.as_ref().unwrap().timescale,
-									transmuxer::MediaType::Video => video.as_ref().unwrap().timescale,
+									transmuxer::MediaType::Video | transmuxer::MediaType::Metadata => video.as_ref().unwrap().timescale,
 								},
 								timestamp: ms.timestamp,
 								r#type: match ms.ty {
 									transmuxer::MediaType::Audio => ingest_watch_response::media::Type::Audio.into(),
 									transmuxer::MediaType::Video => ingest_watch_response::media::Type::Video.into(),
+									transmuxer::MediaType::Metadata => ingest_watch_response::media::Type::Metadata.into(),
 								},
 							})),
 						}))
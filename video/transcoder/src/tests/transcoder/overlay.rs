@@ -0,0 +1,97 @@
+use ffmpeg::ffi::{AVPixelFormat, AVRational};
+use ffmpeg::frame::{Frame, VideoFrame};
+use ffmpeg::scalar::Scalar;
+use image::{Rgba, RgbaImage};
+use pb::scuffle::video::v1::types::{overlay, Overlay};
+
+use crate::transcoder::job::overlay::{LoadedOverlay, OverlayGraph};
+
+const WIDTH: i32 = 320;
+const HEIGHT: i32 = 180;
+
+fn image_overlay(position: overlay::Position, opacity: f32) -> LoadedOverlay {
+	LoadedOverlay {
+		overlay: Overlay {
+			content: Some(overlay::Content::Image(overlay::Image {
+				s3_bucket_id: None,
+				key: "logo.png".to_string(),
+			})),
+			position: position as i32,
+			size: 0.25,
+			margin: 0.0,
+			opacity,
+			renditions: vec![],
+		},
+		image: Some(RgbaImage::from_pixel(16, 16, Rgba([255, 255, 255, 255]))),
+	}
+}
+
+/// A black yuv420p frame, converted from rgba so every plane is filled.
+fn black_frame() -> Frame {
+	let mut rgba = VideoFrame::alloc(WIDTH, HEIGHT, AVPixelFormat::AV_PIX_FMT_RGBA).unwrap();
+	for pixel in rgba.data_mut(0).unwrap().chunks_mut(4) {
+		pixel.copy_from_slice(&[0, 0, 0, 255]);
+	}
+
+	let mut scalar = Scalar::new(
+		WIDTH,
+		HEIGHT,
+		AVPixelFormat::AV_PIX_FMT_RGBA,
+		WIDTH,
+		HEIGHT,
+		AVPixelFormat::AV_PIX_FMT_YUV420P,
+	)
+	.unwrap();
+
+	let mut frame = scalar.process(&rgba).unwrap().0.clone();
+	frame.set_pts(Some(0));
+	frame
+}
+
+fn apply(overlay: &LoadedOverlay) -> VideoFrame {
+	let mut graph = OverlayGraph::new(
+		&[overlay],
+		WIDTH,
+		HEIGHT,
+		AVPixelFormat::AV_PIX_FMT_YUV420P,
+		AVRational { num: 1, den: 1000 },
+		None,
+	)
+	.unwrap();
+
+	graph.send_frame(&black_frame()).unwrap();
+	graph.send_eof().unwrap();
+
+	let frame = graph.receive_frame().unwrap().expect("no frame").video();
+	assert_eq!(frame.format(), AVPixelFormat::AV_PIX_FMT_YUV420P as i32);
+	assert_eq!(frame.width(), WIDTH as usize);
+	assert_eq!(frame.height(), HEIGHT as usize);
+
+	frame
+}
+
+fn luma(frame: &VideoFrame, x: usize, y: usize) -> u8 {
+	frame.data(0).unwrap()[y * frame.linesize(0).unwrap() as usize + x]
+}
+
+#[test]
+fn test_overlay_image() {
+	let frame = apply(&image_overlay(overlay::Position::TopLeft, 1.0));
+
+	// The image is scaled to a quarter of the height, 45x45 in the top left
+	assert!(luma(&frame, 10, 10) > 200);
+	assert!(luma(&frame, 40, 40) > 200);
+	assert!(luma(&frame, 60, 10) < 40);
+	assert!(luma(&frame, 10, 60) < 40);
+	assert!(luma(&frame, 300, 170) < 40);
+}
+
+#[test]
+fn test_overlay_image_position_opacity() {
+	let frame = apply(&image_overlay(overlay::Position::BottomRight, 0.5));
+
+	let blended = luma(&frame, 300, 170);
+	assert!((100..180).contains(&blended), "unexpected luma {blended}");
+	assert!(luma(&frame, 10, 10) < 40);
+	assert!(luma(&frame, 260, 170) < 40);
+}
@@ -0,0 +1,119 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use pb::scuffle::video::v1::types::Rendition;
+use tokio_util::sync::CancellationToken;
+
+use crate::transcoder::job::upload::probe::probe;
+use crate::transcoder::job::upload::range_reader::RangeReader;
+use crate::transcoder::job::upload::{until_shutdown, UploadError};
+
+fn asset(name: &str) -> std::fs::File {
+	std::fs::File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets").join(name)).unwrap()
+}
+
+#[test]
+fn test_probe_upload() {
+	let probe = probe(&asset("avc_aac.mp4")).unwrap();
+
+	assert_eq!(probe.video_input.rendition(), Rendition::VideoSource);
+	assert_eq!(probe.video_input.codec, "avc1.64001f");
+	assert_eq!(probe.video_input.width, 480);
+	assert_eq!(probe.video_input.height, 852);
+	assert!(probe.video_input.fps > 0, "expected a frame rate");
+
+	let audio_input = probe.audio_input.expect("expected an audio input");
+	assert_eq!(audio_input.rendition(), Rendition::AudioSource);
+	assert_eq!(audio_input.codec, "mp4a.40.2");
+	assert_eq!(audio_input.channels, 2);
+	assert_eq!(audio_input.sample_rate, 44100);
+
+	assert!(
+		(probe.duration - 4.667).abs() < 0.05,
+		"unexpected duration: {}",
+		probe.duration
+	);
+}
+
+#[test]
+fn test_probe_upload_without_audio() {
+	let probe = probe(&asset("avc.mp4")).unwrap();
+
+	assert_eq!(probe.video_input.codec, "avc1.64001f");
+	assert_eq!(probe.audio_input, None);
+}
+
+#[test]
+fn test_probe_upload_rewinds() {
+	let file = asset("avc_aac.mp4");
+
+	// The file is probed after it is downloaded, when it is read to the end
+	let first = probe(&file).unwrap();
+	let second = probe(&file).unwrap();
+
+	assert_eq!(first, second);
+}
+
+#[test]
+fn test_probe_upload_invalid() {
+	let file = tempfile::tempfile().unwrap();
+	std::io::Write::write_all(&mut &file, b"not a video file").unwrap();
+
+	assert!(probe(&file).is_err(), "expected the file to be rejected");
+}
+
+#[test]
+fn test_probe_upload_range_reader() {
+	let mut data = Vec::new();
+	asset("avc_aac.mp4").read_to_end(&mut data).unwrap();
+	let data = Bytes::from(data);
+
+	let mut requests = 0;
+	let mut reader = RangeReader::new(data.len() as u64, |range| {
+		requests += 1;
+		Ok(data.slice(*range.start() as usize..=*range.end() as usize))
+	});
+
+	assert_eq!(probe(&mut reader).unwrap(), probe(&asset("avc_aac.mp4")).unwrap());
+	assert!(reader.take_error().is_none());
+	drop(reader);
+
+	assert!(requests > 0, "expected the file to be read through range requests");
+}
+
+#[test]
+fn test_probe_upload_range_reader_error() {
+	let mut reader = RangeReader::new(1024, |_| Err(anyhow::anyhow!("bucket is unavailable")));
+
+	// The probe fails, and the reason is kept for the caller
+	assert!(probe(&mut reader).is_err());
+	assert_eq!(reader.take_error().unwrap().to_string(), "bucket is unavailable");
+}
+
+#[tokio::test]
+async fn test_upload_shutdown_retries() {
+	let shutdown_token = CancellationToken::new();
+	shutdown_token.cancel();
+
+	// An upload cut short by a shutdown is put back on the queue instead of failing
+	let result = until_shutdown(&shutdown_token, std::future::pending::<Result<(), UploadError>>()).await;
+	assert!(
+		matches!(result, Err(UploadError::Retry(_))),
+		"expected the upload to be retried"
+	);
+}
+
+#[tokio::test]
+async fn test_upload_without_shutdown() {
+	let shutdown_token = CancellationToken::new();
+
+	let result = until_shutdown(&shutdown_token, async { Ok(true) }).await;
+	assert!(matches!(result, Ok(true)));
+
+	let result = until_shutdown(&shutdown_token, async {
+		Err::<(), _>(UploadError::from(anyhow::anyhow!("invalid file")))
+	})
+	.await;
+	assert!(matches!(result, Err(UploadError::Failed(_))), "expected the upload to fail");
+}
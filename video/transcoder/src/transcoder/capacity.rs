@@ -0,0 +1,196 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_nats::jetstream::{AckKind, Message};
+use mp4::codec::VideoCodec;
+use pb::scuffle::video::internal::TranscoderLoad;
+use pb::scuffle::video::v1::types::{Rendition, VideoConfig};
+use prost::Message as _;
+use ulid::Ulid;
+
+use crate::config::CapacityConfig;
+use crate::global::TranscoderGlobal;
+
+/// Estimates the cost of transcoding the input into the video renditions, in
+/// megapixels per second.
+///
+/// Decoding the input counts once and every transcoded rendition adds the
+/// factor of its codec, both at the pixel rate of the input. Copies of the
+/// source and the audio renditions are cheap enough to leave out.
+pub fn estimate_cost(config: &CapacityConfig, video_input: &VideoConfig, video_outputs: &[VideoConfig]) -> f64 {
+	let pixel_rate =
+		video_input.width.max(0) as f64 * video_input.height.max(0) as f64 * video_input.fps.max(0) as f64 / 1_000_000.0;
+
+	let factors = video_outputs
+		.iter()
+		.filter(|output| output.rendition() != Rendition::VideoSource)
+		.map(|output| match output.codec.parse() {
+			Ok(VideoCodec::Avc { .. }) => config.h264_factor,
+			Ok(VideoCodec::Hevc { .. }) => config.hevc_factor,
+			Ok(VideoCodec::Av1 { .. }) => config.av1_factor,
+			// The job fails before it encodes anything
+			Err(_) => 0.0,
+		})
+		.sum::<f64>();
+
+	pixel_rate * (1.0 + factors)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Load {
+	/// The estimated cost of the running jobs, in megapixels per second.
+	pub load: f64,
+	pub jobs: u32,
+	/// The requests which did not fit in the budget since the last report.
+	pub rejected: u32,
+}
+
+/// The jobs a transcoder has taken on, by their estimated cost.
+#[derive(Debug)]
+pub struct Capacity {
+	budget: f64,
+	load: Mutex<Load>,
+}
+
+impl Capacity {
+	/// A budget of 0 takes on every job.
+	pub fn new(budget: f64) -> Arc<Self> {
+		Arc::new(Self {
+			budget,
+			load: Mutex::new(Load::default()),
+		})
+	}
+
+	pub fn budget(&self) -> f64 {
+		self.budget
+	}
+
+	/// Takes on a job if it fits in the budget. A transcoder without jobs takes
+	/// on any job, so a job which costs more than the budget still runs
+	/// somewhere.
+	pub fn try_acquire(self: &Arc<Self>, cost: f64) -> Option<CapacityGuard> {
+		let mut load = self.load.lock().unwrap();
+
+		if self.budget > 0.0 && load.jobs > 0 && load.load + cost > self.budget {
+			load.rejected += 1;
+			return None;
+		}
+
+		load.load += cost;
+		load.jobs += 1;
+
+		Some(CapacityGuard {
+			capacity: self.clone(),
+			cost,
+		})
+	}
+
+	/// Returns the current load and resets the count of rejected requests.
+	pub fn report(&self) -> Load {
+		let mut load = self.load.lock().unwrap();
+		let report = *load;
+		load.rejected = 0;
+		report
+	}
+}
+
+/// Releases the cost of a job when the job finishes.
+#[derive(Debug)]
+pub struct CapacityGuard {
+	capacity: Arc<Capacity>,
+	cost: f64,
+}
+
+impl CapacityGuard {
+	pub fn cost(&self) -> f64 {
+		self.cost
+	}
+}
+
+impl Drop for CapacityGuard {
+	fn drop(&mut self) {
+		let mut load = self.capacity.load.lock().unwrap();
+
+		load.jobs -= 1;
+		// Keep rounding errors from adding up while the transcoder is idle
+		load.load = if load.jobs == 0 {
+			0.0
+		} else {
+			(load.load - self.cost).max(0.0)
+		};
+	}
+}
+
+/// How long a message which was put back on the queue `delivered` times is
+/// held back, doubling with every delivery up to the maximum delay.
+pub fn retry_delay(config: &CapacityConfig, delivered: i64) -> Duration {
+	let doublings = delivered.saturating_sub(1).clamp(0, 16) as u32;
+
+	config.retry_delay.saturating_mul(1 << doublings).min(config.max_retry_delay)
+}
+
+/// Takes on the job of a message, or puts the message back on the queue for
+/// another transcoder if the job does not fit in the budget. The consumers
+/// redeliver messages without limit, so a request is only dropped once it is
+/// older than its stream allows.
+pub async fn admit(
+	global: &Arc<impl TranscoderGlobal>,
+	capacity: &Arc<Capacity>,
+	msg: &Message,
+	cost: f64,
+) -> Option<CapacityGuard> {
+	if let Some(guard) = capacity.try_acquire(cost) {
+		return Some(guard);
+	}
+
+	let delivered = msg.info().map_or(1, |info| info.delivered);
+	let delay = retry_delay(&global.config().capacity, delivered);
+
+	tracing::info!(
+		cost,
+		budget = capacity.budget(),
+		delivered,
+		?delay,
+		"transcoder is at capacity, putting message back"
+	);
+
+	msg.ack_with(AckKind::Nak(Some(delay))).await.ok();
+
+	None
+}
+
+/// Publishes the load of the transcoder for operators to watch, such as with
+/// `nats sub '<load subject>.>'`. Nothing in the cluster consumes it, every
+/// transcoder admits jobs by its own load alone.
+pub async fn report_load(global: Arc<impl TranscoderGlobal>, transcoder_id: Ulid, capacity: Arc<Capacity>) {
+	let config = &global.config().capacity;
+
+	let subject = video_common::keys::transcoder_load(&config.load_subject, transcoder_id);
+	let mut timer = tokio::time::interval(config.load_report_interval);
+
+	loop {
+		timer.tick().await;
+
+		let load = capacity.report();
+
+		tracing::debug!(
+			load = load.load,
+			budget = capacity.budget(),
+			jobs = load.jobs,
+			rejected = load.rejected,
+			"transcoder load"
+		);
+
+		let message = TranscoderLoad {
+			transcoder_id: Some(transcoder_id.into()),
+			load: load.load,
+			budget: capacity.budget(),
+			jobs: load.jobs,
+			rejected: load.rejected,
+		};
+
+		if let Err(err) = global.nats().publish(subject.clone(), message.encode_to_vec().into()).await {
+			tracing::warn!(error = %err, "failed to publish transcoder load");
+		}
+	}
+}
@@ -0,0 +1,457 @@
+use std::fmt::Write as _;
+
+use bytes::Bytes;
+
+const ROWS: usize = 15;
+const COLUMNS: usize = 32;
+
+/// The row a preamble address code moves the cursor to, indexed by the low
+/// bits of its first byte and bit 5 of its second byte.
+const PAC_ROWS: [usize; 16] = [10, 10, 0, 1, 2, 3, 11, 12, 13, 14, 4, 5, 6, 7, 8, 9];
+
+/// The characters of the basic set which differ from ASCII.
+fn basic_char(b: u8) -> char {
+	match b {
+		0x2a => 'á',
+		0x5c => 'é',
+		0x5e => 'í',
+		0x5f => 'ó',
+		0x60 => 'ú',
+		0x7b => 'ç',
+		0x7c => '÷',
+		0x7d => 'Ñ',
+		0x7e => 'ñ',
+		0x7f => '█',
+		b => b as char,
+	}
+}
+
+/// The special characters, sent as `0x11 0x30..=0x3f`.
+const SPECIAL_CHARS: [char; 16] = ['®', '°', '½', '¿', '™', '¢', '£', '♪', 'à', ' ', 'è', 'â', 'ê', 'î', 'ô', 'û'];
+
+/// The extended Spanish and French characters, sent as `0x12 0x20..=0x3f`.
+const EXTENDED_CHARS_12: [char; 32] = [
+	'Á', 'É', 'Ó', 'Ú', 'Ü', 'ü', '‘', '¡', '*', '’', '—', '©', '℠', '•', '“', '”', 'À', 'Â', 'Ç', 'È', 'Ê', 'Ë', 'ë', 'Î',
+	'Ï', 'ï', 'Ô', 'Ù', 'ù', 'Û', '«', '»',
+];
+
+/// The extended Portuguese, German and Danish characters, sent as `0x13
+/// 0x20..=0x3f`.
+const EXTENDED_CHARS_13: [char; 32] = [
+	'Ã', 'ã', 'Í', 'Ì', 'ì', 'Ò', 'ò', 'Õ', 'õ', '{', '}', '\\', '^', '_', '|', '~', 'Ä', 'ä', 'Ö', 'ö', 'ß', '¥', '¤', '│',
+	'Å', 'å', 'Ø', 'ø', '┌', '┐', '└', '┘',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+	PopOn,
+	RollUp(usize),
+	PaintOn,
+	/// Text mode is not meant to be shown with the video, so its characters
+	/// are dropped.
+	Text,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Memory([[Option<char>; COLUMNS]; ROWS]);
+
+impl Default for Memory {
+	fn default() -> Self {
+		Self([[None; COLUMNS]; ROWS])
+	}
+}
+
+impl Memory {
+	fn clear(&mut self) {
+		*self = Self::default();
+	}
+
+	fn text(&self) -> String {
+		self.0
+			.iter()
+			.map(|row| row.iter().map(|c| c.unwrap_or(' ')).collect::<String>())
+			.map(|row| row.trim().to_owned())
+			.filter(|row| !row.is_empty())
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+}
+
+/// Decodes the CEA-608 captions of the first caption channel (CC1) from the
+/// `cc_data` a decoder attaches to the frames of a video.
+///
+/// CEA-708 services are not decoded, broadcasters carry their captions in
+/// the CEA-608 compatibility bytes as well.
+pub struct CaptionDecoder {
+	mode: Mode,
+	displayed: Memory,
+	non_displayed: Memory,
+	row: usize,
+	column: usize,
+	/// The row the window of roll-up captions sits on.
+	base_row: usize,
+	/// The last control code, which broadcasters send twice.
+	last_control: Option<(u8, u8)>,
+	/// The last control code was for the second channel, so the characters
+	/// which follow it are too.
+	other_channel: bool,
+	/// The roll-up window before a carriage return rolled it, which is the
+	/// last the viewer saw of it.
+	rolled: Option<String>,
+	/// The text last returned, so unchanged captions are not returned again.
+	text: String,
+}
+
+impl Default for CaptionDecoder {
+	fn default() -> Self {
+		Self {
+			mode: Mode::PopOn,
+			displayed: Memory::default(),
+			non_displayed: Memory::default(),
+			row: ROWS - 1,
+			column: 0,
+			base_row: ROWS - 1,
+			last_control: None,
+			other_channel: false,
+			rolled: None,
+			text: String::new(),
+		}
+	}
+}
+
+impl CaptionDecoder {
+	/// Decodes the `cc_data` of a frame, returning the text shown from the
+	/// frame on if it changed. An empty string clears the captions.
+	pub fn decode(&mut self, cc_data: &[u8]) -> Option<String> {
+		let mut text = None;
+
+		for triplet in cc_data.chunks_exact(3) {
+			let cc_valid = triplet[0] & 0x04 != 0;
+			let cc_type = triplet[0] & 0x03;
+
+			// Only the first field carries CC1
+			if !cc_valid || cc_type != 0 {
+				continue;
+			}
+
+			if self.decode_pair(triplet[1] & 0x7f, triplet[2] & 0x7f) {
+				text = Some(self.rolled.take().unwrap_or_else(|| self.displayed.text()));
+			}
+		}
+
+		let text = text?;
+		if text == self.text {
+			return None;
+		}
+
+		self.text.clone_from(&text);
+		Some(text)
+	}
+
+	/// Decodes a pair of bytes with their parity bits stripped, returning true
+	/// if the shown captions should be committed.
+	fn decode_pair(&mut self, b1: u8, b2: u8) -> bool {
+		// Padding
+		if b1 == 0 && b2 == 0 {
+			return false;
+		}
+
+		if !(0x10..=0x1f).contains(&b1) {
+			self.last_control = None;
+
+			// Characters below 0x20 belong to the data services of the second field
+			if !self.other_channel && b1 >= 0x20 {
+				self.write_char(basic_char(b1));
+				if b2 >= 0x20 {
+					self.write_char(basic_char(b2));
+				}
+			}
+
+			return false;
+		}
+
+		// Control codes are sent twice in a row so they survive a lost pair
+		if self.last_control == Some((b1, b2)) {
+			self.last_control = None;
+			return false;
+		}
+
+		self.last_control = Some((b1, b2));
+
+		self.other_channel = b1 & 0x08 != 0;
+		if self.other_channel {
+			return false;
+		}
+
+		let committed = match (b1, b2) {
+			(0x14 | 0x15, 0x20..=0x2f) => self.misc_control(b2),
+			(0x17, 0x21..=0x23) => {
+				self.column = (self.column + (b2 - 0x20) as usize).min(COLUMNS - 1);
+				false
+			}
+			(0x11, 0x30..=0x3f) => {
+				self.write_char(SPECIAL_CHARS[(b2 - 0x30) as usize]);
+				false
+			}
+			(0x12 | 0x13, 0x20..=0x3f) => {
+				// An extended character replaces the standard character sent before it
+				self.backspace();
+				self.write_char(if b1 == 0x12 {
+					EXTENDED_CHARS_12[(b2 - 0x20) as usize]
+				} else {
+					EXTENDED_CHARS_13[(b2 - 0x20) as usize]
+				});
+				false
+			}
+			// Mid-row codes change the style, which shows up as a space
+			(0x11, 0x20..=0x2f) => {
+				self.write_char(' ');
+				false
+			}
+			(0x10..=0x17, 0x40..=0x7f) => {
+				self.preamble_address(b1, b2);
+				false
+			}
+			_ => false,
+		};
+
+		committed || self.mode == Mode::PaintOn
+	}
+
+	fn misc_control(&mut self, code: u8) -> bool {
+		match code {
+			// Resume caption loading
+			0x20 => {
+				self.mode = Mode::PopOn;
+				false
+			}
+			// Backspace
+			0x21 => {
+				self.backspace();
+				false
+			}
+			// Delete to end of row
+			0x24 => {
+				let (row, column) = (self.row, self.column);
+				self.memory().0[row][column..].fill(None);
+				false
+			}
+			// Roll-up captions with 2, 3 or 4 rows
+			0x25..=0x27 => {
+				let rows = (code - 0x23) as usize;
+				if !matches!(self.mode, Mode::RollUp(_)) {
+					self.displayed.clear();
+					self.non_displayed.clear();
+					self.row = self.base_row;
+				}
+
+				self.mode = Mode::RollUp(rows);
+				self.clear_outside_window(rows);
+				self.column = 0;
+				true
+			}
+			// Resume direct captioning
+			0x29 => {
+				self.mode = Mode::PaintOn;
+				false
+			}
+			// Text restart and resume text display
+			0x2a | 0x2b => {
+				self.mode = Mode::Text;
+				false
+			}
+			// Erase displayed memory
+			0x2c => {
+				self.displayed.clear();
+				true
+			}
+			// Carriage return
+			0x2d => {
+				if let Mode::RollUp(rows) = self.mode {
+					// Roll-up rows are committed once complete, so the window is shown
+					// with the row which was just finished
+					self.rolled = Some(self.displayed.text());
+
+					let top = self.base_row + 1 - rows.min(self.base_row + 1);
+					self.displayed.0.copy_within(top + 1..=self.base_row, top);
+					self.displayed.0[self.base_row] = [None; COLUMNS];
+					self.row = self.base_row;
+				} else {
+					self.row = (self.row + 1).min(ROWS - 1);
+				}
+
+				self.column = 0;
+				true
+			}
+			// Erase non-displayed memory
+			0x2e => {
+				self.non_displayed.clear();
+				false
+			}
+			// End of caption, the loaded caption is shown
+			0x2f => {
+				std::mem::swap(&mut self.displayed, &mut self.non_displayed);
+				self.mode = Mode::PopOn;
+				true
+			}
+			// Alarms and flash on change nothing which can be shown
+			_ => false,
+		}
+	}
+
+	fn preamble_address(&mut self, b1: u8, b2: u8) {
+		let row = PAC_ROWS[(((b1 & 0x07) << 1) | ((b2 >> 5) & 0x01)) as usize];
+
+		// A roll-up window moves along with its base row
+		if let Mode::RollUp(rows) = self.mode {
+			if row != self.base_row {
+				let window = self.window(rows);
+				let height = window.end() - window.start() + 1;
+				let mut moved = Memory::default();
+				for (idx, src) in window.enumerate() {
+					if let Some(dst) = (row + idx + 1).checked_sub(height) {
+						moved.0[dst] = self.displayed.0[src];
+					}
+				}
+
+				self.displayed = moved;
+				self.base_row = row;
+			}
+		}
+
+		self.row = row;
+		self.column = if b2 & 0x10 != 0 { ((b2 & 0x0e) >> 1) as usize * 4 } else { 0 };
+	}
+
+	fn window(&self, rows: usize) -> std::ops::RangeInclusive<usize> {
+		self.base_row + 1 - rows.min(self.base_row + 1)..=self.base_row
+	}
+
+	fn clear_outside_window(&mut self, rows: usize) {
+		let window = self.window(rows);
+		for (idx, row) in self.displayed.0.iter_mut().enumerate() {
+			if !window.contains(&idx) {
+				*row = [None; COLUMNS];
+			}
+		}
+	}
+
+	fn memory(&mut self) -> &mut Memory {
+		match self.mode {
+			Mode::PopOn | Mode::Text => &mut self.non_displayed,
+			Mode::RollUp(_) | Mode::PaintOn => &mut self.displayed,
+		}
+	}
+
+	fn write_char(&mut self, c: char) {
+		if self.mode == Mode::Text {
+			return;
+		}
+
+		let (row, column) = (self.row, self.column);
+		self.memory().0[row][column] = Some(c);
+		self.column = (column + 1).min(COLUMNS - 1);
+	}
+
+	fn backspace(&mut self) {
+		if self.mode == Mode::Text {
+			return;
+		}
+
+		self.column = self.column.saturating_sub(1);
+		let (row, column) = (self.row, self.column);
+		self.memory().0[row][column] = None;
+	}
+}
+
+/// A change of the captions shown, at a time in seconds from the first frame
+/// decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+	pub time: f64,
+	pub text: String,
+}
+
+struct Cue {
+	start: f64,
+	end: f64,
+	text: String,
+}
+
+/// Collects the decoded captions into cues and cuts them into WebVTT
+/// segments along the segments of the video.
+#[derive(Default)]
+pub struct CaptionTrack {
+	/// The time on the timeline of the renditions of the first frame decoded.
+	offset: f64,
+	cues: Vec<Cue>,
+	/// The caption which is still shown, it ends with the next change.
+	open: Option<(f64, String)>,
+}
+
+impl CaptionTrack {
+	/// Sets where the first frame decoded sits on the timeline of the
+	/// renditions, which continues from the previous connection after a
+	/// reconnect.
+	pub fn set_offset(&mut self, offset: f64) {
+		self.offset = offset;
+	}
+
+	pub fn push(&mut self, caption: Caption) {
+		let time = caption.time + self.offset;
+
+		if let Some((start, text)) = self.open.take() {
+			if time > start {
+				self.cues.push(Cue { start, end: time, text });
+			}
+		}
+
+		if !caption.text.is_empty() {
+			self.open = Some((time, caption.text));
+		}
+	}
+
+	/// Cuts the WebVTT segment from `start` to `end` on the timeline of the
+	/// renditions. Cues which run past the
+	/// segment are clipped to it, and continue in the next one. A segment
+	/// without any captions only has the header.
+	pub fn cut(&mut self, start: f64, end: f64) -> Bytes {
+		// The cue times are on the timeline of the renditions, which starts at 0
+		let mut vtt = String::from("WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:00.000,MPEGTS:0\n");
+
+		let open = self.open.as_ref().map(|(start, text)| (*start, f64::INFINITY, text));
+		for (cue_start, cue_end, text) in self.cues.iter().map(|c| (c.start, c.end, &c.text)).chain(open) {
+			if cue_end <= start || cue_start >= end {
+				continue;
+			}
+
+			write!(
+				vtt,
+				"\n{} --> {}\n{}\n",
+				vtt_timestamp(cue_start.max(start)),
+				vtt_timestamp(cue_end.min(end)),
+				escape(text)
+			)
+			.unwrap();
+		}
+
+		self.cues.retain(|c| c.end > end);
+
+		vtt.into()
+	}
+}
+
+fn vtt_timestamp(seconds: f64) -> String {
+	let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+	format!(
+		"{:02}:{:02}:{:02}.{:03}",
+		millis / 3_600_000,
+		millis / 60_000 % 60,
+		millis / 1000 % 60,
+		millis % 1000
+	)
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
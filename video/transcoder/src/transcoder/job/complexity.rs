@@ -0,0 +1,241 @@
+use ffmpeg::ffi::{AVPixelFormat, AVRational};
+use ffmpeg::frame::VideoFrame;
+use pb::scuffle::video::v1::types::ContentAwareEncoding;
+
+/// The share of the bitrate of a rung to encode with if the config does not
+/// set a max ratio.
+const DEFAULT_MAX_BITRATE_RATIO: f64 = 1.0;
+
+/// The constant rate factor if the config does not set one, the default of
+/// x264 and x265.
+const DEFAULT_CRF: u32 = 23;
+
+/// The seconds of source measured per window if the config does not set
+/// them.
+const DEFAULT_ANALYSIS_DURATION: u32 = 10;
+
+/// Only every nth pixel of every nth row is measured, the complexity of a
+/// scene does not need every pixel and this keeps the meter cheap on 1080p60
+/// sources.
+const SAMPLE_STEP: usize = 8;
+
+/// The mean difference between neighbouring luma samples at which a frame
+/// counts as fully detailed.
+const MAX_SPATIAL_ACTIVITY: f64 = 24.0;
+
+/// The mean difference between the luma samples of consecutive frames at
+/// which a scene counts as fully in motion.
+const MAX_TEMPORAL_ACTIVITY: f64 = 16.0;
+
+/// Motion costs an encoder far more bits than detail it can predict from the
+/// previous frame, so it makes up most of the complexity.
+const TEMPORAL_WEIGHT: f64 = 0.7;
+
+/// The pixel formats whose first plane is 8 bit luma.
+const SUPPORTED_PIXEL_FORMATS: &[AVPixelFormat] = &[
+	AVPixelFormat::AV_PIX_FMT_YUV420P,
+	AVPixelFormat::AV_PIX_FMT_YUVJ420P,
+	AVPixelFormat::AV_PIX_FMT_YUV422P,
+	AVPixelFormat::AV_PIX_FMT_YUVJ422P,
+	AVPixelFormat::AV_PIX_FMT_YUV444P,
+	AVPixelFormat::AV_PIX_FMT_YUVJ444P,
+	AVPixelFormat::AV_PIX_FMT_NV12,
+];
+
+/// The constant rate factor the rungs are encoded with in capped crf mode.
+pub fn crf(content_aware_encoding: &ContentAwareEncoding) -> u32 {
+	content_aware_encoding.crf.unwrap_or(DEFAULT_CRF)
+}
+
+/// The highest bitrate a rung with the given bitrate is encoded with.
+pub fn max_bitrate(content_aware_encoding: &ContentAwareEncoding, bitrate: i64) -> i64 {
+	(bitrate as f64 * content_aware_encoding.max_bitrate_ratio.unwrap_or(DEFAULT_MAX_BITRATE_RATIO)) as i64
+}
+
+/// The bitrate a rung with the given bitrate is encoded with for a scene of
+/// the given complexity, between 0 and 1.
+pub fn scaled_bitrate(content_aware_encoding: &ContentAwareEncoding, bitrate: i64, complexity: f64) -> i64 {
+	let min_ratio = content_aware_encoding.min_bitrate_ratio;
+	let max_ratio = content_aware_encoding.max_bitrate_ratio.unwrap_or(DEFAULT_MAX_BITRATE_RATIO);
+
+	(bitrate as f64 * (min_ratio + (max_ratio - min_ratio) * complexity.clamp(0.0, 1.0))) as i64
+}
+
+/// Measures how complex the decoded source video is to encode from the
+/// detail in every frame and the motion between them.
+pub struct ComplexityMeter {
+	window: i64,
+	window_start: Option<i64>,
+	previous: Vec<u8>,
+	current: Vec<u8>,
+	spatial: f64,
+	temporal: f64,
+	frames: u64,
+}
+
+impl ComplexityMeter {
+	/// Creates a meter for video in the given pixel format and time base,
+	/// which reports the complexity of every analysis window of the config.
+	pub fn new(
+		content_aware_encoding: &ContentAwareEncoding,
+		pixel_format: AVPixelFormat,
+		time_base: AVRational,
+	) -> anyhow::Result<Self> {
+		if !SUPPORTED_PIXEL_FORMATS.contains(&pixel_format) {
+			anyhow::bail!("unsupported pixel format: {pixel_format:?}");
+		}
+
+		let seconds = content_aware_encoding.analysis_duration.unwrap_or(DEFAULT_ANALYSIS_DURATION) as i64;
+
+		Ok(Self {
+			window: seconds * time_base.den as i64 / time_base.num.max(1) as i64,
+			window_start: None,
+			previous: Vec::new(),
+			current: Vec::new(),
+			spatial: 0.0,
+			temporal: 0.0,
+			frames: 0,
+		})
+	}
+
+	/// Measures a frame, returning the complexity of the window once the frame
+	/// is past its end. The frame then starts the next window.
+	pub fn send_frame(&mut self, frame: &VideoFrame) -> Option<f64> {
+		let pts = frame.pts()?;
+		let start = *self.window_start.get_or_insert(pts);
+
+		let complexity = if pts < start {
+			// A timestamp going backwards is a discontinuity, so the window starts over
+			self.reset();
+			None
+		} else if pts - start >= self.window {
+			let complexity = self.complexity();
+			self.reset();
+			complexity
+		} else {
+			None
+		};
+
+		self.window_start.get_or_insert(pts);
+
+		self.measure(frame);
+
+		complexity
+	}
+
+	fn measure(&mut self, frame: &VideoFrame) {
+		let Some(linesize) = frame.linesize(0).filter(|l| *l > 0) else {
+			return;
+		};
+		let Some(luma) = frame.data(0) else {
+			return;
+		};
+
+		let (width, height) = (frame.width(), frame.height());
+
+		self.current.clear();
+
+		let mut spatial = 0;
+		for y in (0..height.saturating_sub(1)).step_by(SAMPLE_STEP) {
+			let row = &luma[y * linesize as usize..];
+			let next_row = &luma[(y + 1) * linesize as usize..];
+
+			for x in (0..width.saturating_sub(1)).step_by(SAMPLE_STEP) {
+				spatial += row[x].abs_diff(row[x + 1]) as u64 + row[x].abs_diff(next_row[x]) as u64;
+				self.current.push(row[x]);
+			}
+		}
+
+		if self.current.is_empty() {
+			return;
+		}
+
+		self.spatial += spatial as f64 / (self.current.len() * 2) as f64;
+
+		// The first frame of a window has nothing to be compared to, unless the
+		// previous window measured a frame of the same size
+		if self.previous.len() == self.current.len() {
+			let temporal = self
+				.previous
+				.iter()
+				.zip(&self.current)
+				.map(|(a, b)| a.abs_diff(*b) as u64)
+				.sum::<u64>();
+
+			self.temporal += temporal as f64 / self.current.len() as f64;
+		}
+
+		std::mem::swap(&mut self.previous, &mut self.current);
+		self.frames += 1;
+	}
+
+	fn complexity(&self) -> Option<f64> {
+		if self.frames == 0 {
+			return None;
+		}
+
+		let spatial = (self.spatial / self.frames as f64 / MAX_SPATIAL_ACTIVITY).min(1.0);
+		let temporal = (self.temporal / self.frames as f64 / MAX_TEMPORAL_ACTIVITY).min(1.0);
+
+		Some(spatial * (1.0 - TEMPORAL_WEIGHT) + temporal * TEMPORAL_WEIGHT)
+	}
+
+	fn reset(&mut self) {
+		self.window_start = None;
+		self.spatial = 0.0;
+		self.temporal = 0.0;
+		self.frames = 0;
+	}
+}
+
+/// The bitrate of a transcoded video rendition, which only changes at the
+/// start of a segment so a segment is never encoded at two bitrates.
+pub struct RenditionRate {
+	bitrate: i64,
+	current: i64,
+	pending: Option<i64>,
+	gop_size: i32,
+	frames: u64,
+}
+
+impl RenditionRate {
+	/// Creates the rate of a rung with the given bitrate, encoded with
+	/// keyframes every `gop_size` frames. The rung starts at its max bitrate
+	/// until the first window is measured.
+	pub fn new(content_aware_encoding: &ContentAwareEncoding, bitrate: i64, gop_size: i32) -> Self {
+		Self {
+			bitrate,
+			current: max_bitrate(content_aware_encoding, bitrate),
+			pending: None,
+			gop_size: gop_size.max(1),
+			frames: 0,
+		}
+	}
+
+	/// Picks the bitrate for the measured complexity, applied at the next
+	/// segment boundary.
+	pub fn update(&mut self, content_aware_encoding: &ContentAwareEncoding, complexity: f64) {
+		let bitrate = scaled_bitrate(content_aware_encoding, self.bitrate, complexity);
+		self.pending = (bitrate != self.current).then_some(bitrate);
+	}
+
+	/// Counts a frame sent to the encoder, returning the bitrate to switch to
+	/// if the frame starts a segment and a new bitrate was picked.
+	pub fn next_frame(&mut self) -> Option<i64> {
+		let boundary = self.frames % self.gop_size as u64 == 0;
+		self.frames += 1;
+
+		if !boundary {
+			return None;
+		}
+
+		let bitrate = self.pending.take()?;
+		self.current = bitrate;
+		Some(bitrate)
+	}
+
+	/// The bitrate the rendition is currently encoded with.
+	pub fn bitrate(&self) -> i64 {
+		self.current
+	}
+}
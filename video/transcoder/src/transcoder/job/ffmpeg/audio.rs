@@ -1,22 +1,50 @@
+use std::time::Instant;
+
 use anyhow::Context;
 use ffmpeg::codec::EncoderCodec;
 use ffmpeg::dict::Dictionary;
 use ffmpeg::encoder::{AudioEncoderSettings, MuxerEncoder, MuxerSettings};
 use ffmpeg::error::FfmpegError;
-use ffmpeg::ffi::{AVCodecID, AVPictureType};
+use ffmpeg::ffi::{AVCodecID, AVPictureType, AVRational, AVSampleFormat, AV_CH_LAYOUT_STEREO};
+use ffmpeg::filter_graph::{Filter, FilterGraph};
 use ffmpeg::io::channel::ChannelCompatSend;
 use ffmpeg::io::OutputOptions;
 use ffmpeg::packet::Packet;
 use mp4::codec::AudioCodec;
-use pb::scuffle::video::v1::types::AudioConfig;
+use pb::scuffle::video::v1::types::{AudioConfig, LoudnessNormalization};
 use tokio::sync::mpsc;
 
-use super::{muxer_options, Transcoder};
+use super::{muxer_options, AudioDecoder, Transcoder};
+use crate::transcoder::job::loudness::normalization_filter;
+
+/// The audio an encoder expects, the decoded audio is resampled to it.
+pub struct EncoderInput {
+	pub sample_format: AVSampleFormat,
+	pub frame_size: i32,
+}
+
+impl EncoderInput {
+	fn sample_format_name(&self) -> anyhow::Result<&'static str> {
+		Ok(match self.sample_format {
+			AVSampleFormat::AV_SAMPLE_FMT_S16 => "s16",
+			AVSampleFormat::AV_SAMPLE_FMT_FLT => "flt",
+			AVSampleFormat::AV_SAMPLE_FMT_FLTP => "fltp",
+			format => anyhow::bail!("unsupported sample format: {format:?}"),
+		})
+	}
+}
 
-pub fn codec_options(codec: AudioCodec) -> anyhow::Result<(EncoderCodec, Dictionary)> {
+pub fn codec_options(codec: AudioCodec) -> anyhow::Result<(EncoderCodec, Dictionary, EncoderInput)> {
 	Ok(match codec {
 		AudioCodec::Aac { object_type } => {
-			let codec = ffmpeg::codec::EncoderCodec::by_name("libfdk_aac")
+			let fdk = ffmpeg::codec::EncoderCodec::by_name("libfdk_aac");
+
+			// HE-AAC is only supported by libfdk_aac
+			if object_type == aac::AudioObjectType::Sbr && fdk.is_none() {
+				anyhow::bail!("he-aac encoding requires libfdk_aac");
+			}
+
+			let codec = fdk
 				.or_else(|| ffmpeg::codec::EncoderCodec::new(AVCodecID::AV_CODEC_ID_AAC))
 				.ok_or(FfmpegError::NoEncoder)
 				.context("failed to find aac encoder")?;
@@ -29,25 +57,119 @@ pub fn codec_options(codec: AudioCodec) -> anyhow::Result<(EncoderCodec, Diction
 						match object_type {
 							aac::AudioObjectType::AacLowComplexity => "aac_low",
 							aac::AudioObjectType::AacMain => "aac_main",
+							aac::AudioObjectType::Sbr => "aac_he",
 							aac::AudioObjectType::Unknown(profile) => {
 								anyhow::bail!("invalid aac profile: {profile}");
 							}
 						},
 					)
 					.build(),
+				EncoderInput {
+					sample_format: if fdk.is_some() {
+						AVSampleFormat::AV_SAMPLE_FMT_S16
+					} else {
+						AVSampleFormat::AV_SAMPLE_FMT_FLTP
+					},
+					// SBR doubles the number of samples in a frame
+					frame_size: if object_type == aac::AudioObjectType::Sbr {
+						2048
+					} else {
+						1024
+					},
+				},
 			)
 		}
 		AudioCodec::Opus => {
-			let codec = ffmpeg::codec::EncoderCodec::by_name("libopus")
+			let libopus = ffmpeg::codec::EncoderCodec::by_name("libopus");
+
+			let codec = libopus
 				.or_else(|| ffmpeg::codec::EncoderCodec::new(AVCodecID::AV_CODEC_ID_OPUS))
 				.ok_or(FfmpegError::NoEncoder)
 				.context("failed to find opus encoder")?;
 
-			(codec, Dictionary::new())
+			(
+				codec,
+				Dictionary::new(),
+				EncoderInput {
+					sample_format: if libopus.is_some() {
+						AVSampleFormat::AV_SAMPLE_FMT_FLT
+					} else {
+						AVSampleFormat::AV_SAMPLE_FMT_FLTP
+					},
+					// 20ms at 48kHz
+					frame_size: 960,
+				},
+			)
 		}
 	})
 }
 
+/// The arguments of an abuffer filter which is fed the decoded audio.
+pub fn abuffer_args(decoder: &AudioDecoder) -> String {
+	let time_base = decoder.time_base();
+	let channel_layout = if decoder.channel_layout() != 0 {
+		format!("channel_layout=0x{:x}", decoder.channel_layout())
+	} else {
+		format!("channels={}", decoder.channels())
+	};
+
+	format!(
+		"time_base={}/{}:sample_rate={}:sample_fmt={}:{channel_layout}",
+		time_base.num,
+		time_base.den,
+		decoder.sample_rate(),
+		decoder.sample_format() as i32,
+	)
+}
+
+/// Builds a filter graph which converts the decoded audio to what the encoder
+/// expects, this normalizes the loudness if asked to, downmixes the audio to
+/// stereo and resamples it.
+fn resampler(
+	decoder: &AudioDecoder,
+	audio_config: &AudioConfig,
+	input: &EncoderInput,
+	loudness_normalization: Option<&LoudnessNormalization>,
+) -> anyhow::Result<FilterGraph> {
+	let mut graph = FilterGraph::new().context("failed to create filter graph")?;
+
+	graph
+		.add(
+			Filter::get("abuffer").ok_or(FfmpegError::Arguments("missing abuffer filter"))?,
+			"in",
+			&abuffer_args(decoder),
+		)
+		.context("failed to create abuffer")?;
+
+	graph
+		.add(
+			Filter::get("abuffersink").ok_or(FfmpegError::Arguments("missing abuffersink filter"))?,
+			"out",
+			"",
+		)
+		.context("failed to create abuffersink")?;
+
+	// loudnorm upsamples to 192kHz, so it runs before the audio is resampled
+	let normalization = loudness_normalization
+		.map(|l| format!("{},", normalization_filter(l)))
+		.unwrap_or_default();
+
+	graph
+		.output("in", 0)?
+		.input("out", 0)?
+		.parse(&format!(
+			"{normalization}aresample={},aformat=sample_fmts={}:channel_layouts=stereo,asetnsamples=n={}:p=0",
+			audio_config.sample_rate,
+			input.sample_format_name()?,
+			input.frame_size,
+		))
+		.context("failed to parse filter graph")?;
+
+	graph.validate().context("failed to validate filter graph")?;
+
+	Ok(graph)
+}
+
 impl Transcoder {
 	pub fn setup_audio_encoder(
 		&mut self,
@@ -55,6 +177,7 @@ impl Transcoder {
 		audio_config: &AudioConfig,
 		encoder_codec: EncoderCodec,
 		encoder_options: Dictionary,
+		encoder_input: EncoderInput,
 	) -> anyhow::Result<()> {
 		let output = ffmpeg::io::Output::new(
 			sender.into_compat(),
@@ -65,16 +188,28 @@ impl Transcoder {
 		)
 		.context("failed to create output")?;
 
+		let decoder = self.audio_decoder.as_ref().unwrap();
+
+		self.audio_resamplers.push(
+			resampler(decoder, audio_config, &encoder_input, self.loudness_normalization.as_ref()).context("resampler")?,
+		);
+
+		// The resampler outputs frames in the time base of the output sample rate
+		let time_base = AVRational {
+			num: 1,
+			den: audio_config.sample_rate,
+		};
+
 		self.audio_encoders.push(MuxerEncoder::new(
 			encoder_codec,
 			output,
-			self.audio_decoder.as_ref().unwrap().time_base(),
-			self.audio_decoder.as_ref().unwrap().time_base(),
+			time_base,
+			time_base,
 			AudioEncoderSettings::builder(
 				audio_config.sample_rate,
-				self.audio_decoder.as_ref().unwrap().channel_layout(),
-				self.audio_decoder.as_ref().unwrap().channels(),
-				self.audio_decoder.as_ref().unwrap().sample_format(),
+				AV_CH_LAYOUT_STEREO,
+				audio_config.channels,
+				encoder_input.sample_format,
 			)
 			.bitrate(audio_config.bitrate)
 			.rc_max_rate(audio_config.bitrate)
@@ -118,6 +253,17 @@ impl Transcoder {
 
 		self.handle_audio_decoder()?;
 
+		for resampler in self.audio_resamplers.iter_mut() {
+			resampler
+				.get("in")
+				.ok_or(FfmpegError::Arguments("missing abuffer"))?
+				.source()
+				.send_eof(None)
+				.context("resampler eof")?;
+		}
+
+		self.handle_audio_resamplers()?;
+
 		for encoder in self.audio_encoders.iter_mut() {
 			encoder.send_eof().context("encoder eof")?;
 		}
@@ -132,12 +278,55 @@ impl Transcoder {
 				let frame_timestamp = frame.best_effort_timestamp();
 				frame.set_pts(frame_timestamp);
 
-				for encoder in self.audio_encoders.iter_mut() {
-					encoder.send_frame(&frame).context("encoder")?;
+				for resampler in self.audio_resamplers.iter_mut() {
+					resampler
+						.get("in")
+						.ok_or(FfmpegError::Arguments("missing abuffer"))?
+						.source()
+						.send_frame(&frame)
+						.context("resampler")?;
+				}
+
+				if let Some(meter) = &mut self.loudness_meter {
+					meter.send_frame(&frame).context("loudness meter")?;
 				}
 			}
 		}
 
+		self.report_loudness();
+
+		self.handle_audio_resamplers()
+	}
+
+	fn report_loudness(&mut self) {
+		let Some(loudness) = self.loudness_meter.as_ref().and_then(|m| m.loudness()) else {
+			return;
+		};
+
+		if self.last_loudness_report.elapsed() < self.loudness_report_interval {
+			return;
+		}
+
+		self.last_loudness_report = Instant::now();
+
+		// A report is dropped rather than holding up the audio if the job is behind
+		if let Err(mpsc::error::TrySendError::Full(_)) = self.loudness_output.try_send(loudness.clone()) {
+			tracing::debug!("dropped loudness report");
+		}
+	}
+
+	fn handle_audio_resamplers(&mut self) -> anyhow::Result<()> {
+		for (resampler, encoder) in self.audio_resamplers.iter_mut().zip(self.audio_encoders.iter_mut()) {
+			let mut sink = resampler
+				.get("out")
+				.ok_or(FfmpegError::Arguments("missing abuffersink"))?
+				.sink();
+
+			while let Some(frame) = sink.receive_frame().context("resampler receive frame")? {
+				encoder.send_frame(&frame).context("encoder")?;
+			}
+		}
+
 		Ok(())
 	}
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::io::{self, Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -8,14 +9,21 @@ use ffmpeg::decoder::Decoder;
 use ffmpeg::dict::Dictionary;
 use ffmpeg::error::FfmpegError;
 use ffmpeg::ffi::{AVMediaType, AVPixelFormat};
+use ffmpeg::filter_graph::FilterGraph;
 use ffmpeg::frame::Frame;
 use ffmpeg::io::channel::{ChannelCompatRecv as _, ChannelCompatSend as _};
 use ffmpeg::io::OutputOptions;
 use ffmpeg::log::LogLevel;
-use pb::scuffle::video::v1::types::{AudioConfig, VideoConfig};
+use pb::scuffle::video::v1::types::{
+	content_aware_encoding, AudioConfig, AudioLoudness, ContentAwareEncoding, LoudnessNormalization, VideoConfig,
+};
 use tokio::sync::mpsc;
 use video_common::database::Rendition;
 
+use super::captions::{Caption, CaptionDecoder};
+use super::complexity::{ComplexityMeter, RenditionRate};
+use super::loudness::LoudnessMeter;
+use super::overlay::{LoadedOverlay, OverlayGraph};
 use crate::global::TranscoderGlobal;
 
 mod audio;
@@ -26,7 +34,7 @@ const MP4_FLAGS: &str = "frag_keyframe+frag_every_frame+empty_moov+delay_moov+de
 type ChannelCompatRecv = ffmpeg::io::channel::ChannelCompat<mpsc::Receiver<Bytes>>;
 type ChannelCompatSend = ffmpeg::io::channel::ChannelCompat<mpsc::Sender<Vec<u8>>>;
 
-type Input = ffmpeg::io::Input<ChannelCompatRecv>;
+type Input = ffmpeg::io::Input<InputReader>;
 type Output = ffmpeg::io::Output<ChannelCompatSend>;
 type VideoDecoder = ffmpeg::decoder::VideoDecoder;
 type AudioDecoder = ffmpeg::decoder::AudioDecoder;
@@ -34,6 +42,37 @@ type Encoder = ffmpeg::encoder::MuxerEncoder<ChannelCompatSend>;
 type Scalar = ffmpeg::scalar::Scalar;
 type Limiter = ffmpeg::limiter::FrameRateLimiter;
 
+/// The media the transcoder reads.
+pub enum TranscoderInput {
+	/// A stream which is sent by ingest as it is received.
+	Live(mpsc::Receiver<Bytes>),
+	/// An uploaded file, which is transcoded as fast as it can be.
+	File(std::fs::File),
+}
+
+enum InputReader {
+	Live(ChannelCompatRecv),
+	File(std::fs::File),
+}
+
+impl Read for InputReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match self {
+			Self::Live(recv) => recv.read(buf),
+			Self::File(file) => file.read(buf),
+		}
+	}
+}
+
+impl Seek for InputReader {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		match self {
+			Self::Live(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "cannot seek a live stream")),
+			Self::File(file) => file.seek(pos),
+		}
+	}
+}
+
 static SETUP_LOGGING: std::sync::Once = std::sync::Once::new();
 
 fn muxer_options() -> Dictionary {
@@ -65,7 +104,8 @@ pub fn screenshot_size(width: i32, height: i32) -> (i32, i32) {
 pub struct Transcoder {
 	input: Input,
 	video_stream_index: i32,
-	audio_stream_index: i32,
+	// Only uploaded files can be without audio.
+	audio_stream_index: Option<i32>,
 	video_decoder: VideoDecoder,
 	audio_decoder: Option<AudioDecoder>,
 	video_copies: Vec<Output>,
@@ -73,28 +113,56 @@ pub struct Transcoder {
 	video_scalars: Vec<Scalar>,
 	frame_limiters: Vec<Limiter>,
 	video_encoders: Vec<Encoder>,
+	video_overlays: Vec<Option<OverlayGraph>>,
+	overlay_font_file: Option<String>,
+	video_rates: Vec<Option<RenditionRate>>,
+	content_aware_encoding: Option<ContentAwareEncoding>,
+	complexity_meter: Option<ComplexityMeter>,
 	audio_encoders: Vec<Encoder>,
+	audio_resamplers: Vec<FilterGraph>,
+	loudness_normalization: Option<LoudnessNormalization>,
+	loudness_meter: Option<LoudnessMeter>,
+	loudness_output: mpsc::Sender<AudioLoudness>,
+	last_loudness_report: Instant,
+	loudness_report_interval: Duration,
+	realtime: bool,
 	last_screenshot: Instant,
+	last_screenshot_time: Option<f64>,
 	screenshot_interval: Duration,
 	screenshot_scalar: Scalar,
 	screenshot_output: mpsc::Sender<Frame>,
+	caption_decoder: Option<CaptionDecoder>,
+	caption_start: Option<i64>,
+	caption_output: mpsc::Sender<Caption>,
 }
 
 impl Transcoder {
 	pub fn new(
 		global: &Arc<impl TranscoderGlobal>,
-		input: mpsc::Receiver<Bytes>,
+		input: TranscoderInput,
 		screenshot_output: mpsc::Sender<Frame>,
+		loudness_output: mpsc::Sender<AudioLoudness>,
+		caption_output: mpsc::Sender<Caption>,
 		mut outputs: HashMap<Rendition, mpsc::Sender<Vec<u8>>>,
 		mut video_configs: Vec<VideoConfig>,
 		mut audio_outputs: Vec<AudioConfig>,
+		overlays: Vec<LoadedOverlay>,
+		loudness_normalization: Option<LoudnessNormalization>,
+		content_aware_encoding: Option<ContentAwareEncoding>,
+		captions: bool,
 	) -> anyhow::Result<Self> {
 		SETUP_LOGGING.call_once(|| {
 			ffmpeg::log::set_log_level(LogLevel::Trace);
 			ffmpeg::log::log_callback_tracing();
 		});
 
-		let input = ffmpeg::io::Input::new(input.into_compat()).context("failed to create input")?;
+		// Only a file can be seeked, which the demuxer needs for files which have
+		// their index at the end.
+		let (input, realtime) = match input {
+			TranscoderInput::Live(recv) => (ffmpeg::io::Input::new(InputReader::Live(recv.into_compat())), true),
+			TranscoderInput::File(file) => (ffmpeg::io::Input::seekable(InputReader::File(file)), false),
+		};
+		let input = input.context("failed to create input")?;
 
 		let video_stream = input
 			.streams()
@@ -102,11 +170,10 @@ impl Transcoder {
 			.ok_or(FfmpegError::NoStream)
 			.context("failed to find video stream")?;
 
-		let audio_stream = input
+		let audio_stream_index = input
 			.streams()
 			.best(AVMediaType::AVMEDIA_TYPE_AUDIO)
-			.ok_or(FfmpegError::NoStream)
-			.context("failed to find video stream")?;
+			.map(|stream| stream.index());
 
 		let video_decoder = match ffmpeg::decoder::Decoder::new(&video_stream).context("failed to create h264 decoder")? {
 			Decoder::Video(decoder) => decoder,
@@ -126,11 +193,13 @@ impl Transcoder {
 		.context("failed to create screenshot scalar")?;
 
 		let mut this = Self {
-			audio_stream_index: audio_stream.index(),
+			audio_stream_index,
 			video_stream_index: video_stream.index(),
 			video_decoder,
 			input,
+			realtime,
 			last_screenshot: Instant::now() - global.config().screenshot_interval,
+			last_screenshot_time: None,
 			screenshot_interval: global.config().screenshot_interval,
 			audio_decoder: None,
 			video_copies: Vec::new(),
@@ -138,9 +207,23 @@ impl Transcoder {
 			video_scalars: Vec::new(),
 			frame_limiters: Vec::new(),
 			video_encoders: Vec::new(),
+			video_overlays: Vec::new(),
+			overlay_font_file: global.config().overlay_font_file.clone(),
+			video_rates: Vec::new(),
+			content_aware_encoding,
+			complexity_meter: None,
 			audio_encoders: Vec::new(),
+			audio_resamplers: Vec::new(),
+			loudness_normalization,
+			loudness_meter: None,
+			loudness_output,
+			last_loudness_report: Instant::now(),
+			loudness_report_interval: global.config().loudness_report_interval,
 			screenshot_output,
 			screenshot_scalar,
+			caption_decoder: captions.then(CaptionDecoder::default),
+			caption_start: None,
+			caption_output,
 		};
 
 		if audio_outputs.iter().any(|c| c.rendition() == Rendition::AudioSource.into()) {
@@ -211,41 +294,62 @@ impl Transcoder {
 
 				let (encoder_codec, encoder_options) = video::codec_options(config, codec)?;
 
+				let rendition = Rendition::from(video_config.rendition());
+				let overlays = overlays.iter().filter(|o| o.applies_to(rendition)).collect::<Vec<_>>();
+
 				let sender = outputs
-					.remove(&Rendition::from(video_config.rendition()))
+					.remove(&rendition)
 					.ok_or_else(|| anyhow::anyhow!("missing video output"))?;
-				this.setup_video_encoder(sender, &video_config, encoder_codec, encoder_options)?;
+				this.setup_video_encoder(sender, &video_config, encoder_codec, encoder_options, &overlays)?;
 			}
 		}
 
-		if !audio_outputs.is_empty() {
-			let audio_stream = this
-				.input
-				.streams()
-				.best(AVMediaType::AVMEDIA_TYPE_AUDIO)
-				.ok_or(FfmpegError::NoStream)
-				.context("failed to find video stream")?;
+		// The source only needs to be measured if a rendition picks its bitrate from
+		// it, and the stream should not fail because it cannot be measured
+		if let Some(content_aware_encoding) = this
+			.content_aware_encoding
+			.as_ref()
+			.filter(|c| c.mode() == content_aware_encoding::Mode::Bitrate && this.video_rates.iter().any(Option::is_some))
+		{
+			this.complexity_meter = ComplexityMeter::new(
+				content_aware_encoding,
+				this.video_decoder.pixel_format(),
+				this.video_decoder.time_base(),
+			)
+			.map_err(|err| tracing::warn!(error = %err, "failed to create complexity meter"))
+			.ok();
+		}
 
-			this.audio_decoder = Some(
-				match ffmpeg::decoder::Decoder::new(&audio_stream).context("failed to create aac decoder")? {
-					Decoder::Audio(decoder) => decoder,
-					_ => anyhow::bail!("expected audio decoder"),
-				},
-			);
+		if let Some(audio_stream) = this.input.streams().best(AVMediaType::AVMEDIA_TYPE_AUDIO) {
+			// The audio is always decoded so its loudness can be measured, even if it is
+			// only copied
+			let audio_decoder = match ffmpeg::decoder::Decoder::new(&audio_stream).context("failed to create aac decoder")? {
+				Decoder::Audio(decoder) => decoder,
+				_ => anyhow::bail!("expected audio decoder"),
+			};
+
+			// The stream should not fail because its loudness cannot be measured
+			this.loudness_meter = LoudnessMeter::new(&audio::abuffer_args(&audio_decoder))
+				.map_err(|err| tracing::warn!(error = %err, "failed to create loudness meter"))
+				.ok();
+
+			this.audio_decoder = Some(audio_decoder);
+		} else if !audio_outputs.is_empty() {
+			return Err(FfmpegError::NoStream).context("failed to find audio stream");
+		}
 
-			for audio_config in audio_outputs {
-				let codec = audio_config
-					.codec
-					.parse()
-					.map_err(|err| anyhow::anyhow!("failed to parse audio codec: {err}"))?;
+		for audio_config in audio_outputs {
+			let codec = audio_config
+				.codec
+				.parse()
+				.map_err(|err| anyhow::anyhow!("failed to parse audio codec: {err}"))?;
 
-				let (encoder_codec, encoder_options) = audio::codec_options(codec)?;
+			let (encoder_codec, encoder_options, encoder_input) = audio::codec_options(codec)?;
 
-				let sender = outputs
-					.remove(&Rendition::from(audio_config.rendition()))
-					.ok_or_else(|| anyhow::anyhow!("missing audio output"))?;
-				this.setup_audio_encoder(sender, &audio_config, encoder_codec, encoder_options)?;
-			}
+			let sender = outputs
+				.remove(&Rendition::from(audio_config.rendition()))
+				.ok_or_else(|| anyhow::anyhow!("missing audio output"))?;
+			this.setup_audio_encoder(sender, &audio_config, encoder_codec, encoder_options, encoder_input)?;
 		}
 
 		if !outputs.is_empty() {
@@ -262,7 +366,7 @@ impl Transcoder {
 
 			if stream_idx == self.video_stream_index {
 				self.handle_video_packet(packet).context("video")?;
-			} else if stream_idx == self.audio_stream_index {
+			} else if Some(stream_idx) == self.audio_stream_index {
 				self.handle_audio_packet(packet).context("audio")?;
 			}
 		}
@@ -3,15 +3,19 @@ use ffmpeg::codec::EncoderCodec;
 use ffmpeg::dict::Dictionary;
 use ffmpeg::encoder::{MuxerEncoder, MuxerSettings, VideoEncoderSettings};
 use ffmpeg::error::FfmpegError;
-use ffmpeg::ffi::{AVCodecID, AVPictureType, AVRational};
+use ffmpeg::ffi::{AVCodecID, AVFrameSideDataType, AVPictureType, AVRational};
+use ffmpeg::frame::Frame;
 use ffmpeg::io::channel::ChannelCompatSend;
 use ffmpeg::io::OutputOptions;
 use mp4::codec::VideoCodec;
-use pb::scuffle::video::v1::types::VideoConfig;
+use pb::scuffle::video::v1::types::{content_aware_encoding, VideoConfig};
 use tokio::sync::mpsc;
 
-use super::{muxer_options, Limiter, Scalar, Transcoder};
+use super::{muxer_options, Encoder, Limiter, Scalar, Transcoder};
 use crate::config::TranscoderConfig;
+use crate::transcoder::job::captions::Caption;
+use crate::transcoder::job::complexity::{self, RenditionRate};
+use crate::transcoder::job::overlay::{LoadedOverlay, OverlayGraph};
 
 pub fn codec_options(config: &TranscoderConfig, codec: VideoCodec) -> anyhow::Result<(EncoderCodec, Dictionary)> {
 	match codec {
@@ -66,11 +70,78 @@ pub fn codec_options(config: &TranscoderConfig, codec: VideoCodec) -> anyhow::Re
 				options,
 			))
 		}
-		VideoCodec::Av1 { .. } => {
-			anyhow::bail!("av1 encoding is not supported");
+		VideoCodec::Hevc {
+			profile, level, tier, ..
+		} => {
+			let mut options = Dictionary::from(config.hevc_encoder_options.clone());
+
+			options
+				.set(
+					"profile",
+					match profile {
+						1 => "main",
+						_ => {
+							anyhow::bail!("invalid hevc profile: {profile}");
+						}
+					},
+				)
+				.context("failed to set hevc profile")?;
+
+			// The level is given as 30 times the level number, so 153 is level 5.1
+			if level == 0 || level % 3 != 0 {
+				anyhow::bail!("invalid hevc level: {level}");
+			}
+
+			let level = format!("level-idc={}.{}:high-tier={}", level / 30, level % 30 / 3, tier as u8);
+			let params = match options.get("x265-params") {
+				Some(params) => format!("{params}:{level}"),
+				None => level,
+			};
+
+			options.set("x265-params", &params).context("failed to set hevc level")?;
+
+			Ok((
+				config
+					.hevc_encoder
+					.as_ref()
+					.map(|name| ffmpeg::codec::EncoderCodec::by_name(name))
+					.unwrap_or_else(|| ffmpeg::codec::EncoderCodec::new(AVCodecID::AV_CODEC_ID_HEVC))
+					.ok_or(FfmpegError::NoEncoder)
+					.context("failed to find hevc encoder")?,
+				options,
+			))
 		}
-		VideoCodec::Hevc { .. } => {
-			anyhow::bail!("hevc encoding is not supported");
+		VideoCodec::Av1 {
+			profile, level, depth, ..
+		} => {
+			let mut options = Dictionary::from(config.av1_encoder_options.clone());
+
+			if profile != 0 || depth != 8 {
+				anyhow::bail!("invalid av1 profile: {profile}, depth: {depth}");
+			}
+
+			options.set("profile", "0").context("failed to set av1 profile")?;
+
+			// The sequence level index maps to level X.Y where X = 2 + (idx >> 2) and Y =
+			// idx & 3, the encoders take the level as 10 * X + Y.
+			if level > 23 {
+				anyhow::bail!("invalid av1 level: {level}");
+			}
+
+			options
+				.set("level", &((2 + (level >> 2)) * 10 + (level & 3)).to_string())
+				.context("failed to set av1 level")?;
+
+			Ok((
+				config
+					.av1_encoder
+					.as_ref()
+					.map(|name| ffmpeg::codec::EncoderCodec::by_name(name))
+					.unwrap_or_else(|| ffmpeg::codec::EncoderCodec::new(AVCodecID::AV_CODEC_ID_AV1))
+					.ok_or(FfmpegError::NoEncoder)
+					.context("failed to find av1 encoder")?,
+				options,
+			))
 		}
 	}
 }
@@ -81,7 +152,8 @@ impl Transcoder {
 		sender: mpsc::Sender<Vec<u8>>,
 		video_config: &VideoConfig,
 		encoder_codec: EncoderCodec,
-		encoder_options: Dictionary,
+		mut encoder_options: Dictionary,
+		overlays: &[&LoadedOverlay],
 	) -> anyhow::Result<()> {
 		let output = ffmpeg::io::Output::new(
 			sender.into_compat(),
@@ -113,6 +185,42 @@ impl Transcoder {
 			self.video_decoder.pixel_format(),
 		)?);
 
+		// A broken overlay should not stop the stream from being transcoded
+		self.video_overlays.push(if overlays.is_empty() {
+			None
+		} else {
+			OverlayGraph::new(
+				overlays,
+				video_config.width,
+				video_config.height,
+				self.video_decoder.pixel_format(),
+				self.video_decoder.time_base(),
+				self.overlay_font_file.as_deref(),
+			)
+			.map_err(|err| tracing::warn!(error = %err, "failed to create overlay graph"))
+			.ok()
+		});
+
+		let gop_size = video_config.fps * 2;
+
+		let (bitrate, rc_max_rate, rate) = match &self.content_aware_encoding {
+			Some(content_aware_encoding) if content_aware_encoding.mode() == content_aware_encoding::Mode::CappedCrf => {
+				encoder_options
+					.set("crf", &complexity::crf(content_aware_encoding).to_string())
+					.context("failed to set crf")?;
+
+				// The encoders use an average bitrate instead of the crf if a bitrate is set
+				(0, complexity::max_bitrate(content_aware_encoding, video_config.bitrate), None)
+			}
+			Some(content_aware_encoding) => {
+				let rate = RenditionRate::new(content_aware_encoding, video_config.bitrate, gop_size);
+				(rate.bitrate(), rate.bitrate(), Some(rate))
+			}
+			None => (video_config.bitrate, video_config.bitrate, None),
+		};
+
+		self.video_rates.push(rate);
+
 		self.video_encoders.push(MuxerEncoder::new(
 			encoder_codec,
 			output,
@@ -127,10 +235,10 @@ impl Transcoder {
 				video_config.fps,
 				self.video_decoder.pixel_format(),
 			)
-			.bitrate(video_config.bitrate)
-			.rc_max_rate(video_config.bitrate)
-			.rc_buffer_size(video_config.bitrate as i32 * 2)
-			.gop_size(video_config.fps * 2)
+			.bitrate(bitrate)
+			.rc_max_rate(rc_max_rate)
+			.rc_buffer_size(rc_max_rate as i32 * 2)
+			.gop_size(gop_size)
 			.max_b_frames(0)
 			.thread_count(0)
 			.codec_specific_options(encoder_options)
@@ -150,7 +258,8 @@ impl Transcoder {
 			copy.write_interleaved_packet(packet.clone()).context("copy")?;
 		}
 
-		if packet.is_key() || !self.video_encoders.is_empty() {
+		// Captions are carried by every frame, so they need every packet decoded
+		if packet.is_key() || !self.video_encoders.is_empty() || self.caption_decoder.is_some() {
 			self.video_decoder.send_packet(&packet).context("decoder send")?;
 		}
 
@@ -168,7 +277,17 @@ impl Transcoder {
 
 		self.handle_video_decoder().context("decoder")?;
 
-		for encoder in self.video_encoders.iter_mut() {
+		for ((encoder, overlay), rate) in self
+			.video_encoders
+			.iter_mut()
+			.zip(self.video_overlays.iter_mut())
+			.zip(self.video_rates.iter_mut())
+		{
+			if let Some(overlay) = overlay {
+				overlay.send_eof()?;
+				drain_overlay(overlay, encoder, rate)?;
+			}
+
 			encoder.send_eof().context("encoder eof")?;
 		}
 
@@ -182,7 +301,17 @@ impl Transcoder {
 			frame.set_pts(frame_timestamp);
 			frame.set_format(self.video_decoder.pixel_format() as i32);
 
-			if self.last_screenshot.elapsed() > self.screenshot_interval {
+			self.handle_captions(&frame);
+
+			if let Some(complexity) = self.complexity_meter.as_mut().and_then(|meter| meter.send_frame(&frame)) {
+				if let Some(content_aware_encoding) = &self.content_aware_encoding {
+					for rate in self.video_rates.iter_mut().flatten() {
+						rate.update(content_aware_encoding, complexity);
+					}
+				}
+			}
+
+			if self.screenshot_due(&frame) {
 				let mut frame = self.screenshot_scalar.process(&frame).context("screenshot")?.clone();
 				frame.set_time_base(self.video_decoder.time_base());
 				self.screenshot_output.blocking_send(frame.0).context("screenshot")?;
@@ -198,11 +327,103 @@ impl Transcoder {
 				frames.push(scalar.process(frames.last().copied().unwrap_or(&frame.0)).context("scalar")?);
 			}
 
-			for (encoder, frame) in self.video_encoders.iter_mut().zip(frames) {
-				encoder.send_frame(frame).context("encoder")?;
+			for (((encoder, overlay), rate), frame) in self
+				.video_encoders
+				.iter_mut()
+				.zip(self.video_overlays.iter_mut())
+				.zip(self.video_rates.iter_mut())
+				.zip(frames)
+			{
+				match overlay {
+					Some(overlay) => {
+						overlay.send_frame(frame)?;
+						drain_overlay(overlay, encoder, rate)?;
+					}
+					None => encode_frame(encoder, rate, frame)?,
+				}
 			}
 		}
 
 		Ok(())
 	}
+
+	/// A file is decoded faster than realtime, so its screenshots are spaced by
+	/// the time of the frames instead of the wall clock.
+	fn screenshot_due(&mut self, frame: &Frame) -> bool {
+		if self.realtime {
+			return self.last_screenshot.elapsed() > self.screenshot_interval;
+		}
+
+		let Some(pts) = frame.pts() else {
+			return false;
+		};
+
+		let time_base = self.video_decoder.time_base();
+		let time = pts as f64 * time_base.num as f64 / time_base.den as f64;
+
+		if self
+			.last_screenshot_time
+			.is_some_and(|last| time - last < self.screenshot_interval.as_secs_f64())
+		{
+			return false;
+		}
+
+		self.last_screenshot_time = Some(time);
+		true
+	}
+
+	fn handle_captions(&mut self, frame: &Frame) {
+		let (Some(decoder), Some(pts)) = (self.caption_decoder.as_mut(), frame.pts()) else {
+			return;
+		};
+
+		// The renditions start with the first decoded frame
+		let start = *self.caption_start.get_or_insert(pts);
+
+		let Some(text) = frame
+			.side_data(AVFrameSideDataType::AV_FRAME_DATA_A53_CC)
+			.and_then(|cc_data| decoder.decode(cc_data))
+		else {
+			return;
+		};
+
+		let time_base = self.video_decoder.time_base();
+		let time = (pts - start) as f64 * time_base.num as f64 / time_base.den as f64;
+
+		// The job waits on this thread while it sends the input, so blocking here
+		// could deadlock. A lost caption is better than a stalled stream. A file is
+		// read by ffmpeg itself, so there the captions can wait for the job.
+		if !self.realtime {
+			if self.caption_output.blocking_send(Caption { time, text }).is_err() {
+				tracing::warn!("caption channel is closed, dropping caption");
+			}
+		} else if self.caption_output.try_send(Caption { time, text }).is_err() {
+			tracing::warn!("caption channel is full, dropping caption");
+		}
+	}
+}
+
+fn drain_overlay(overlay: &mut OverlayGraph, encoder: &mut Encoder, rate: &mut Option<RenditionRate>) -> anyhow::Result<()> {
+	while let Some(frame) = overlay.receive_frame()? {
+		encode_frame(encoder, rate, &frame)?;
+	}
+
+	Ok(())
+}
+
+/// Sends a frame to the encoder, switching it to a newly picked bitrate if the
+/// frame starts a segment.
+fn encode_frame(encoder: &mut Encoder, rate: &mut Option<RenditionRate>, frame: &Frame) -> anyhow::Result<()> {
+	let Some(bitrate) = rate.as_mut().and_then(RenditionRate::next_frame) else {
+		return encoder.send_frame(frame).context("encoder");
+	};
+
+	encoder.set_bitrate(bitrate, bitrate, bitrate as i32 * 2);
+
+	// Forcing a keyframe makes sure the new bitrate starts a new segment, even if
+	// the encoder moved its keyframes for a scene cut
+	let mut frame = frame.clone().video();
+	frame.set_pict_type(AVPictureType::AV_PICTURE_TYPE_I);
+
+	encoder.send_frame(&frame).context("encoder")
 }
@@ -0,0 +1,111 @@
+use anyhow::Context;
+use ffmpeg::error::FfmpegError;
+use ffmpeg::filter_graph::{Filter, FilterGraph};
+use ffmpeg::frame::Frame;
+use pb::scuffle::video::v1::types::{AudioLoudness, LoudnessNormalization};
+
+/// The loudness range loudnorm aims for if the config does not set one, in LU.
+const DEFAULT_LOUDNESS_RANGE: f64 = 11.0;
+
+/// The loudnorm filter which normalizes the audio to the targets of the config.
+/// A live stream cannot be measured ahead of time, so it runs in its single
+/// pass mode and adjusts the gain as the stream goes.
+pub fn normalization_filter(loudness_normalization: &LoudnessNormalization) -> String {
+	format!(
+		"loudnorm=I={}:TP={}:LRA={}",
+		loudness_normalization.integrated_loudness,
+		loudness_normalization.true_peak,
+		loudness_normalization.loudness_range.unwrap_or(DEFAULT_LOUDNESS_RANGE),
+	)
+}
+
+/// Measures the loudness of the decoded audio with the ebur128 filter.
+pub struct LoudnessMeter {
+	graph: FilterGraph,
+	loudness: Option<AudioLoudness>,
+}
+
+impl LoudnessMeter {
+	/// Creates a meter for audio described by the arguments of an abuffer
+	/// filter.
+	pub fn new(abuffer: &str) -> anyhow::Result<Self> {
+		let mut graph = FilterGraph::new().context("failed to create filter graph")?;
+
+		graph
+			.add(
+				Filter::get("abuffer").ok_or(FfmpegError::Arguments("missing abuffer filter"))?,
+				"in",
+				abuffer,
+			)
+			.context("failed to create abuffer")?;
+
+		graph
+			.add(
+				Filter::get("abuffersink").ok_or(FfmpegError::Arguments("missing abuffersink filter"))?,
+				"out",
+				"",
+			)
+			.context("failed to create abuffersink")?;
+
+		graph
+			.output("in", 0)?
+			.input("out", 0)?
+			.parse("ebur128=metadata=1:peak=true")
+			.context("failed to parse filter graph")?;
+
+		graph.validate().context("failed to validate filter graph")?;
+
+		Ok(Self { graph, loudness: None })
+	}
+
+	/// The latest measurement, if the meter has seen enough audio to make one.
+	pub fn loudness(&self) -> Option<&AudioLoudness> {
+		self.loudness.as_ref()
+	}
+
+	pub fn send_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+		self.graph
+			.get("in")
+			.ok_or(FfmpegError::Arguments("missing abuffer"))?
+			.source()
+			.send_frame(frame)
+			.context("loudness meter send frame")?;
+
+		self.drain()
+	}
+
+	fn drain(&mut self) -> anyhow::Result<()> {
+		let mut sink = self
+			.graph
+			.get("out")
+			.ok_or(FfmpegError::Arguments("missing abuffersink"))?
+			.sink();
+
+		while let Some(frame) = sink.receive_frame().context("loudness meter receive frame")? {
+			// Only the frames which complete a 100ms block carry a measurement
+			if let Some(loudness) = measurement(&frame) {
+				self.loudness = Some(loudness);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn measurement(frame: &Frame) -> Option<AudioLoudness> {
+	let metadata = frame.metadata();
+	let value = |key: &str| {
+		metadata
+			.iter()
+			.find(|(k, _)| k.to_bytes() == key.as_bytes())
+			.and_then(|(_, v)| v.to_str().ok()?.parse::<f64>().ok())
+	};
+
+	Some(AudioLoudness {
+		integrated_loudness: value("lavfi.r128.I")?,
+		short_term_loudness: value("lavfi.r128.S")?,
+		loudness_range: value("lavfi.r128.LRA")?,
+		// The peak is reported as a linear amplitude
+		true_peak: 20.0 * value("lavfi.r128.true_peak")?.log10(),
+	})
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::io;
 use std::pin::pin;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,16 +9,20 @@ use async_nats::jetstream::Message;
 use bytes::Bytes;
 use futures::{FutureExt, StreamExt};
 use futures_util::TryFutureExt;
+use mp4::DynBox;
 use pb::ext::UlidExt;
 use pb::scuffle::video::internal::events::TranscoderRequestTask;
 use pb::scuffle::video::internal::ingest_client::IngestClient;
+use pb::scuffle::video::internal::live_manifest::{
+	CaptionSegment as ManifestCaptionSegment, Sprite as ManifestSprite, SpriteLayout,
+};
 use pb::scuffle::video::internal::live_rendition_manifest::RenditionInfo;
 use pb::scuffle::video::internal::{
 	ingest_watch_request, ingest_watch_response, IngestWatchRequest, IngestWatchResponse, LiveManifest,
 	LiveRenditionManifest,
 };
 use pb::scuffle::video::v1::events_fetch_request::Target;
-use pb::scuffle::video::v1::types::event;
+use pb::scuffle::video::v1::types::{event, AudioLoudness};
 use prost::Message as _;
 use tokio::sync::mpsc;
 use tokio::{select, try_join};
@@ -27,29 +32,62 @@ use utils::prelude::FutureTimeout;
 use utils::task::AsyncTask;
 use video_common::database::Rendition;
 
+use self::captions::{Caption, CaptionTrack};
 use self::recording::Recording;
+use self::sprite::Sprite;
 use self::task::generic::GenericTask;
 use self::track::parser::TrackOut;
 use self::track::Track;
 use crate::global::TranscoderGlobal;
-use crate::transcoder::job::ffmpeg::Transcoder;
-use crate::transcoder::job::sql_operations::perform_sql_operations;
+use crate::transcoder::capacity::{self, Capacity, CapacityGuard};
+use crate::transcoder::job::ffmpeg::{Transcoder, TranscoderInput};
+use crate::transcoder::job::sql_operations::{perform_sql_operations, query_job_config, JobConfig};
 use crate::transcoder::job::task::generic::generic_task;
 use crate::transcoder::job::task::rendition::track_task;
 use crate::transcoder::job::task::track_parser::track_parser_task;
 use crate::transcoder::job::track::parser::TrackParser;
 
 mod breakpoint;
+pub(crate) mod captions;
+pub(crate) mod complexity;
 mod ffmpeg;
+pub(crate) mod loudness;
+pub(crate) mod overlay;
 mod recording;
 mod renditions;
 mod screenshot;
+mod sprite;
 mod sql_operations;
 mod task;
 mod track;
+pub(crate) mod upload;
+
+pub use self::upload::handle_upload_message;
+
+pub async fn handle_message<G: TranscoderGlobal>(
+	global: Arc<G>,
+	msg: Message,
+	capacity: Arc<Capacity>,
+	shutdown_token: CancellationToken,
+) {
+	let (message, config) = match query_message(&global, &msg).await {
+		Ok(r) => r,
+		Err(err) => {
+			msg.ack_with(async_nats::jetstream::AckKind::Nak(Some(Duration::from_secs(15))))
+				.await
+				.ok();
+			tracing::error!(error = %err, "failed to handle message");
+			return;
+		}
+	};
+
+	let cost = capacity::estimate_cost(&global.config().capacity, &config.video_input, &config.video_output);
+
+	let Some(guard) = capacity::admit(&global, &capacity, &msg, cost).await else {
+		return;
+	};
 
-pub async fn handle_message<G: TranscoderGlobal>(global: Arc<G>, msg: Message, shutdown_token: CancellationToken) {
-	let mut job = match Job::new(&global, &msg).await {
+	let mut job = match Job::new(&global, message, config, guard).await {
 		Ok(job) => job,
 		Err(err) => {
 			msg.ack_with(async_nats::jetstream::AckKind::Nak(Some(Duration::from_secs(15))))
@@ -93,11 +131,28 @@ pub async fn handle_message<G: TranscoderGlobal>(global: Arc<G>, msg: Message, s
 	tracing::info!("stream finished");
 }
 
+async fn query_message(global: &Arc<impl TranscoderGlobal>, msg: &Message) -> Result<(TranscoderRequestTask, JobConfig)> {
+	let message = TranscoderRequestTask::decode(msg.payload.clone())?;
+
+	let config = query_job_config(
+		global,
+		message.organization_id.into_ulid(),
+		message.room_id.into_ulid(),
+		message.connection_id.into_ulid(),
+	)
+	.await?;
+
+	Ok((message, config))
+}
+
 struct Job {
 	organization_id: Ulid,
 	room_id: Ulid,
 	connection_id: Ulid,
 
+	// The broadcaster reconnected, so the stream continues after a discontinuity.
+	discontinuity: bool,
+
 	recording: Option<Recording>,
 
 	ingest_ready: bool,
@@ -110,26 +165,46 @@ struct Job {
 	ffmpeg_recv: mpsc::Receiver<(Rendition, TrackOut)>,
 
 	screenshot_recv: mpsc::Receiver<(Bytes, f64)>,
+	sprite_recv: mpsc::Receiver<Sprite>,
+	loudness_recv: mpsc::Receiver<AudioLoudness>,
+	caption_recv: mpsc::Receiver<Caption>,
 
 	tasks: Vec<AsyncTask<anyhow::Result<()>>>,
 
 	first_init_put: bool,
 	screenshot_idx: u32,
+	screenshot_interval: f64,
+
+	sprite_idx: u32,
+	sprite_layout: SpriteLayout,
+	sprites: Vec<ManifestSprite>,
+
+	// Only set if the transcoding config extracts captions.
+	captions: Option<CaptionTrack>,
+	// The captions are cut along the segments of this rendition.
+	caption_rendition: Option<Rendition>,
+	caption_segments: Vec<ManifestCaptionSegment>,
 
 	ingest_send: mpsc::Sender<IngestWatchRequest>,
 	ingest_recv: tonic::Streaming<IngestWatchResponse>,
 	ingest_shutdown: Option<ingest_watch_response::Shutdown>,
+
+	// Released once the job is dropped.
+	_capacity: CapacityGuard,
 }
 
 impl Job {
-	async fn new(global: &Arc<impl TranscoderGlobal>, msg: &Message) -> Result<Self> {
-		let message = TranscoderRequestTask::decode(msg.payload.clone())?;
-
+	async fn new(
+		global: &Arc<impl TranscoderGlobal>,
+		message: TranscoderRequestTask,
+		config: JobConfig,
+		capacity: CapacityGuard,
+	) -> Result<Self> {
 		let organization_id = message.organization_id.into_ulid();
 		let room_id = message.room_id.into_ulid();
 		let connection_id = message.connection_id.into_ulid();
 
-		let result = perform_sql_operations(global, organization_id, room_id, connection_id).await?;
+		let result = perform_sql_operations(global, organization_id, room_id, connection_id, config).await?;
 
 		tracing::info!(
 			%organization_id,
@@ -137,9 +212,12 @@ impl Job {
 			%connection_id,
 			transcoding_config_id = %result.transcoding_config.id.into_ulid(),
 			recording_id = %result.recording.as_ref().map(|r| r.id().to_string()).unwrap_or_default(),
+			cost = capacity.cost(),
 			"got new stream request",
 		);
 
+		let overlays = overlay::load_overlays(global, organization_id, &result.transcoding_config.overlays).await;
+
 		let renditions = result
 			.video_output
 			.iter()
@@ -171,7 +249,7 @@ impl Job {
 
 		tasks.extend(renditions.iter().copied().map(|rendition| {
 			let (tx, rx) = mpsc::channel(16);
-			tracks.insert(rendition, Track::new(global, rendition, tx));
+			tracks.insert(rendition, Track::new(global, rendition, Some(tx)));
 
 			AsyncTask::spawn(
 				format!("rendition({rendition})"),
@@ -180,28 +258,48 @@ impl Job {
 		}));
 
 		let (frame_send, frame_recv) = mpsc::channel(1);
+		let (loudness_send, loudness_recv) = mpsc::channel(1);
+		let (caption_send, caption_recv) = mpsc::channel(16);
+		let captions = result.transcoding_config.captions;
 		tasks.push(AsyncTask::spawn_blocking("ffmpeg", {
 			let global = global.clone();
 
 			let video_configs = result.video_output.clone();
 			let audio_configs = result.audio_output.clone();
+			let loudness_normalization = result.transcoding_config.loudness_normalization.clone();
+			let content_aware_encoding = result.transcoding_config.content_aware_encoding.clone();
 
 			move || {
 				Transcoder::new(
 					&global,
-					input_receiver,
+					TranscoderInput::Live(input_receiver),
 					frame_send,
+					loudness_send,
+					caption_send,
 					ffmpeg_outputs,
 					video_configs,
 					audio_configs,
+					overlays,
+					loudness_normalization,
+					content_aware_encoding,
+					captions,
 				)?
 				.run()
 			}
 		}));
 
+		let sprite_config = global.config().sprite.clone();
+		let sprite_layout = SpriteLayout {
+			tile_width: sprite_config.tile_width,
+			tile_height: sprite_config.tile_height,
+			columns: sprite_config.columns,
+			rows: sprite_config.rows,
+		};
+
 		let (screenshot_send, screenshot_recv) = mpsc::channel(16);
+		let (sprite_send, sprite_recv) = mpsc::channel(16);
 		tasks.push(AsyncTask::spawn_blocking("screenshot", || {
-			screenshot::screenshot_task(frame_recv, screenshot_send)
+			screenshot::screenshot_task(frame_recv, screenshot_send, sprite_config, sprite_send)
 		}));
 
 		let (generic_uploader, rx) = mpsc::channel(16);
@@ -241,8 +339,16 @@ impl Job {
 			organization_id,
 			room_id,
 			connection_id,
+			discontinuity: message.discontinuity,
 			recording,
 			screenshot_idx: 0,
+			screenshot_interval: global.config().screenshot_interval.as_secs_f64(),
+			sprite_idx: 0,
+			sprite_layout,
+			sprites: Vec::new(),
+			captions: captions.then(CaptionTrack::default),
+			caption_rendition: result.video_output.iter().map(|c| Rendition::from(c.rendition())).min(),
+			caption_segments: Vec::new(),
 			ingest_ready: false,
 			transcoder_ready: false,
 			tracks,
@@ -255,6 +361,10 @@ impl Job {
 			ffmpeg_recv: ffmpeg_output,
 			generic_uploader,
 			screenshot_recv,
+			sprite_recv,
+			loudness_recv,
+			caption_recv,
+			_capacity: capacity,
 		})
 	}
 
@@ -296,6 +406,19 @@ impl Job {
 					self.update_manifest()?;
 					self.ready()?;
 				},
+				Some(sprite) = self.sprite_recv.recv() => {
+					self.handle_sprite(sprite)?;
+				},
+				Some(caption) = self.caption_recv.recv() => {
+					if let Some(captions) = &mut self.captions {
+						captions.push(caption);
+					}
+				},
+				Some(loudness) = self.loudness_recv.recv() => {
+					self.generic_uploader
+						.try_send(GenericTask::Loudness { loudness })
+						.context("send loudness task")?;
+				},
 				msg = self.ingest_recv.next() => {
 					let Some(msg) = msg else {
 						if self.ingest_shutdown.is_none() {
@@ -340,7 +463,20 @@ impl Job {
 		let msg = msg.message.ok_or_else(|| anyhow::anyhow!("ingest sent bad message"))?;
 
 		match msg {
+			ingest_watch_response::Message::Media(media)
+				if media.r#type() == ingest_watch_response::media::Type::Metadata =>
+			{
+				if let Some(recording) = &mut self.recording {
+					recording.capture_source(&media)?;
+				}
+
+				self.handle_metadata(media.data);
+			}
 			ingest_watch_response::Message::Media(media) => {
+				if let Some(recording) = &mut self.recording {
+					recording.capture_source(&media)?;
+				}
+
 				let mut outputs = Vec::new();
 				{
 					let input = self
@@ -381,6 +517,24 @@ impl Job {
 		Ok(())
 	}
 
+	fn handle_metadata(&mut self, data: Bytes) {
+		// Timed metadata does not go through ffmpeg, it is written into the next
+		// part of every track, rescaled to the timescale of the track.
+		let event = match DynBox::demux(&mut io::Cursor::new(data)) {
+			Ok(DynBox::Emsg(event)) => event,
+			Ok(other) => {
+				tracing::warn!(box_type = other.name(), "ingest sent metadata which is not an emsg box");
+				return;
+			}
+			Err(err) => {
+				tracing::warn!(error = %err, "failed to demux metadata");
+				return;
+			}
+		};
+
+		self.tracks.values_mut().for_each(|track| track.append_event(event.clone()));
+	}
+
 	fn handle_track(&mut self, rendition: Rendition, track_out: TrackOut) -> Result<()> {
 		let track = self.tracks.get_mut(&rendition).unwrap();
 
@@ -388,6 +542,10 @@ impl Job {
 
 		self.put_init_segments()?;
 
+		if update_manifest && self.caption_rendition == Some(rendition) {
+			self.cut_captions()?;
+		}
+
 		if update_manifest && !self.first_init_put {
 			let info_map = self.track_info_map();
 			self.tracks
@@ -449,6 +607,17 @@ impl Job {
 			.values_mut()
 			.try_for_each(|track| track.finish(self.recording.as_mut()))?;
 
+		while let Ok(sprite) = self.sprite_recv.try_recv() {
+			self.handle_sprite(sprite)?;
+		}
+
+		self.cut_captions()?;
+
+		if let Some(recording) = &mut self.recording {
+			recording.finish_source()?;
+			recording.finish_sprite()?;
+		}
+
 		let info_map = self
 			.tracks
 			.iter()
@@ -488,6 +657,98 @@ impl Job {
 		Ok(())
 	}
 
+	fn handle_sprite(&mut self, sprite: Sprite) -> Result<()> {
+		// The sheet is sent again for every tile, the first tile starts a new one.
+		if sprite.tile_times.len() == 1 {
+			self.sprite_idx += 1;
+		}
+
+		let end_time = sprite.tile_times.last().copied().unwrap_or_default() + self.screenshot_interval;
+
+		if let Some(recording) = &mut self.recording {
+			recording.upload_sprite(self.sprite_idx, &sprite, end_time)?;
+		}
+
+		let manifest_sprite = ManifestSprite {
+			idx: self.sprite_idx,
+			tile_times: sprite.tile_times.iter().map(|t| *t as f32).collect(),
+			end_time: end_time as f32,
+		};
+
+		if let Some(last) = self.sprites.last_mut().filter(|s| s.idx == self.sprite_idx) {
+			*last = manifest_sprite;
+		} else {
+			if self.sprites.len() >= 10 {
+				self.sprites.remove(0);
+			}
+
+			self.sprites.push(manifest_sprite);
+		}
+
+		self.generic_uploader
+			.try_send(GenericTask::Sprite {
+				data: sprite.data,
+				idx: self.sprite_idx,
+			})
+			.context("send sprite task")?;
+
+		self.update_manifest()
+	}
+
+	/// Cuts the captions of the segments the caption rendition completed since
+	/// the last call.
+	fn cut_captions(&mut self) -> Result<()> {
+		let Some(captions) = &mut self.captions else {
+			return Ok(());
+		};
+
+		let Some(track) = self.caption_rendition.and_then(|rendition| self.tracks.get(&rendition)) else {
+			return Ok(());
+		};
+
+		// The captions of a frame are sent as soon as it is decoded, before the
+		// segment with the frame is complete.
+		while let Ok(caption) = self.caption_recv.try_recv() {
+			captions.push(caption);
+		}
+
+		let last_idx = self.caption_segments.last().map(|s| s.idx);
+		let segments = track
+			.completed_segments()
+			.filter(|(idx, _, _)| !last_idx.is_some_and(|last| *idx <= last))
+			.collect::<Vec<_>>();
+
+		if segments.is_empty() {
+			return Ok(());
+		}
+
+		for (idx, start_time, end_time) in segments {
+			let data = captions.cut(start_time, end_time);
+			let id = Ulid::new();
+
+			if let Some(recording) = &mut self.recording {
+				recording.upload_captions(idx, id, start_time, end_time, data.clone())?;
+			}
+
+			self.generic_uploader
+				.try_send(GenericTask::Captions { data, idx })
+				.context("send captions task")?;
+
+			if self.caption_segments.len() >= 5 {
+				self.caption_segments.remove(0);
+			}
+
+			self.caption_segments.push(ManifestCaptionSegment {
+				idx,
+				id: Some(id.into()),
+				start_time: start_time as f32,
+				end_time: end_time as f32,
+			});
+		}
+
+		self.update_manifest()
+	}
+
 	fn update_manifest(&mut self) -> Result<()> {
 		if !self.ingest_ready {
 			return Ok(());
@@ -495,6 +756,10 @@ impl Job {
 
 		let data = LiveManifest {
 			screenshot_idx: self.screenshot_idx,
+			sprite_layout: Some(self.sprite_layout.clone()),
+			sprite_idx: self.sprite_idx,
+			sprites: self.sprites.clone(),
+			caption_segments: self.caption_segments.clone(),
 		}
 		.encode_to_vec()
 		.into();
@@ -548,6 +813,9 @@ impl Job {
 		let manifest = LiveManifest::decode(manifest)?;
 
 		self.screenshot_idx = manifest.screenshot_idx;
+		self.sprite_idx = manifest.sprite_idx;
+		self.sprites = manifest.sprites;
+		self.caption_segments = manifest.caption_segments;
 
 		for (rendition, data) in rendition_manfiests {
 			let Some(data) = data else {
@@ -562,7 +830,19 @@ impl Job {
 				}
 			}
 
-			self.tracks.get_mut(&rendition).unwrap().apply_manifest(manifest);
+			self.tracks
+				.get_mut(&rendition)
+				.unwrap()
+				.apply_manifest(manifest, self.discontinuity);
+		}
+
+		// This connection continues the timeline of the renditions where the
+		// previous one stopped, and so do its captions
+		if let (Some(captions), Some(track)) = (
+			&mut self.captions,
+			self.caption_rendition.and_then(|rendition| self.tracks.get(&rendition)),
+		) {
+			captions.set_offset(track.duration());
 		}
 
 		Ok(())
@@ -0,0 +1,299 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use aws_config::Region;
+use aws_sdk_s3::config::Credentials;
+use ffmpeg::error::FfmpegError;
+use ffmpeg::ffi::{AVPixelFormat, AVRational};
+use ffmpeg::filter_graph::{Filter, FilterGraph};
+use ffmpeg::frame::{Frame, VideoFrame};
+use image::imageops::FilterType;
+use image::RgbaImage;
+use pb::ext::UlidExt;
+use pb::scuffle::video::v1::types::{overlay, Overlay};
+use ulid::Ulid;
+use video_common::database::{Rendition, S3Bucket};
+
+use crate::global::TranscoderGlobal;
+
+/// An overlay of the transcoding config, with its image fetched and decoded.
+pub struct LoadedOverlay {
+	pub overlay: Overlay,
+	pub image: Option<RgbaImage>,
+}
+
+impl LoadedOverlay {
+	pub fn applies_to(&self, rendition: Rendition) -> bool {
+		self.overlay.renditions.is_empty() || self.overlay.renditions().any(|r| Rendition::from(r) == rendition)
+	}
+}
+
+/// Fetches the images of the overlays, overlays which cannot be loaded are
+/// skipped so that the stream is still transcoded.
+pub async fn load_overlays(
+	global: &Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	overlays: &[Overlay],
+) -> Vec<LoadedOverlay> {
+	let mut loaded = Vec::with_capacity(overlays.len());
+
+	for overlay in overlays {
+		let image = match &overlay.content {
+			Some(overlay::Content::Image(image)) => match load_image(global, organization_id, image).await {
+				Ok(image) => Some(image),
+				Err(err) => {
+					tracing::warn!(key = %image.key, error = %err, "failed to load overlay image");
+					continue;
+				}
+			},
+			Some(_) => None,
+			None => continue,
+		};
+
+		loaded.push(LoadedOverlay {
+			overlay: overlay.clone(),
+			image,
+		});
+	}
+
+	loaded
+}
+
+async fn load_image(
+	global: &Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	image: &overlay::Image,
+) -> anyhow::Result<RgbaImage> {
+	let client = global.db().get().await.context("failed to get database connection")?;
+
+	let s3_bucket: S3Bucket = utils::database::query(
+		r#"
+		SELECT
+			*
+		FROM
+			s3_buckets
+		WHERE
+			organization_id = $1
+			AND id = $2
+		"#,
+	)
+	.bind(organization_id)
+	.bind(image.s3_bucket_id.into_ulid())
+	.build_query_as()
+	.fetch_one(&client)
+	.await
+	.context("failed to query s3 bucket")?;
+
+	let bucket = binary_helper::s3::Bucket::new(
+		s3_bucket.name.clone(),
+		Credentials::from_keys(&s3_bucket.access_key_id, &s3_bucket.secret_access_key, None),
+		Region::new(s3_bucket.region.clone()),
+		s3_bucket.endpoint.clone(),
+	);
+
+	let data = bucket
+		.get_object(&image.key)
+		.await
+		.context("failed to get image")?
+		.body
+		.collect()
+		.await
+		.context("failed to read image")?
+		.into_bytes();
+
+	Ok(image::load_from_memory(&data).context("failed to decode image")?.to_rgba8())
+}
+
+/// A filter graph which draws overlays onto the frames of a rendition.
+pub struct OverlayGraph(FilterGraph);
+
+impl OverlayGraph {
+	pub fn new(
+		overlays: &[&LoadedOverlay],
+		width: i32,
+		height: i32,
+		pixel_format: AVPixelFormat,
+		time_base: AVRational,
+		font_file: Option<&str>,
+	) -> anyhow::Result<Self> {
+		let mut graph = FilterGraph::new().context("failed to create filter graph")?;
+
+		let buffer = Filter::get("buffer").ok_or(FfmpegError::Arguments("missing buffer filter"))?;
+
+		graph
+			.add(
+				buffer,
+				"in",
+				&format!(
+					"video_size={width}x{height}:pix_fmt={}:time_base={}/{}:pixel_aspect=1/1",
+					pixel_format as i32, time_base.num, time_base.den,
+				),
+			)
+			.context("failed to create buffer")?;
+
+		graph
+			.add(
+				Filter::get("buffersink").ok_or(FfmpegError::Arguments("missing buffersink filter"))?,
+				"out",
+				"",
+			)
+			.context("failed to create buffersink")?;
+
+		let font = font_file.map(|f| format!("fontfile='{f}':")).unwrap_or_default();
+
+		let mut images = Vec::new();
+		let mut spec = String::new();
+		let mut label = "in".to_string();
+
+		for (idx, loaded) in overlays.iter().enumerate() {
+			let overlay = &loaded.overlay;
+			let next = format!("v{idx}");
+			let size = ((overlay.size * height as f32).round() as i32).max(1);
+			let margin = (overlay.margin * height as f32).round() as i32;
+			let opacity = overlay.opacity.clamp(0.0, 1.0);
+
+			match (&overlay.content, &loaded.image) {
+				(Some(overlay::Content::Image(_)), Some(image)) => {
+					let image = prepare_image(image, size as u32, opacity);
+					let name = format!("img{idx}");
+
+					graph
+						.add(
+							buffer,
+							&name,
+							&format!(
+								"video_size={}x{}:pix_fmt={}:time_base={}/{}:pixel_aspect=1/1",
+								image.width(),
+								image.height(),
+								AVPixelFormat::AV_PIX_FMT_RGBA as i32,
+								time_base.num,
+								time_base.den,
+							),
+						)
+						.context("failed to create image buffer")?;
+
+					let (x, y) = position(overlay.position(), margin, ("W", "H"), ("w", "h"));
+					spec.push_str(&format!("[{label}][{name}]overlay=x={x}:y={y}[{next}];"));
+
+					images.push((name, image));
+				}
+				(Some(overlay::Content::LiveBadge(_)), _) => {
+					let border = (size / 4).max(1);
+					let (x, y) = position(overlay.position(), margin + border, ("w", "h"), ("tw", "th"));
+					spec.push_str(&format!(
+						"[{label}]drawtext={font}text='LIVE':fontsize={size}:fontcolor=white@{opacity:.3}:box=1:boxcolor=red@{opacity:.3}:boxborderw={border}:x={x}:y={y}[{next}];"
+					));
+				}
+				(Some(overlay::Content::Timestamp(_)), _) => {
+					let (x, y) = position(overlay.position(), margin, ("w", "h"), ("tw", "th"));
+					spec.push_str(&format!(
+						"[{label}]drawtext={font}text='%{{gmtime}}':fontsize={size}:fontcolor=white@{opacity:.3}:shadowcolor=black@{opacity:.3}:shadowx=1:shadowy=1:x={x}:y={y}[{next}];"
+					));
+				}
+				_ => continue,
+			}
+
+			label = next;
+		}
+
+		// The encoders expect the pixel format of the decoder
+		spec.push_str(&format!("[{label}]format=pix_fmts={}[out]", pixel_format as i32));
+
+		let mut parser = graph.output("in", 0)?;
+		for (name, _) in &images {
+			parser = parser.output(name, 0)?;
+		}
+
+		parser.input("out", 0)?.parse(&spec).context("failed to parse filter graph")?;
+
+		graph.validate().context("failed to validate filter graph")?;
+
+		// Each image is a single frame, the overlay filter keeps drawing the last frame
+		// once the image input has ended.
+		for (name, image) in images {
+			let mut frame = VideoFrame::alloc(image.width() as i32, image.height() as i32, AVPixelFormat::AV_PIX_FMT_RGBA)
+				.context("failed to allocate image frame")?;
+
+			let stride = frame.linesize(0).context("missing image linesize")? as usize;
+			let row = image.width() as usize * 4;
+			let data = frame.data_mut(0).context("missing image data")?;
+			for (dst, src) in data.chunks_mut(stride).zip(image.as_raw().chunks(row)) {
+				dst[..row].copy_from_slice(src);
+			}
+
+			frame.set_pts(Some(0));
+
+			let mut source = graph
+				.get(&name)
+				.ok_or(FfmpegError::Arguments("missing image buffer"))?
+				.source();
+			source.send_frame(&frame).context("failed to send image")?;
+			source.send_eof(None).context("failed to send image eof")?;
+		}
+
+		Ok(Self(graph))
+	}
+
+	pub fn send_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+		self.0
+			.get("in")
+			.ok_or(FfmpegError::Arguments("missing buffer"))?
+			.source()
+			.send_frame(frame)
+			.context("overlay send frame")
+	}
+
+	pub fn send_eof(&mut self) -> anyhow::Result<()> {
+		self.0
+			.get("in")
+			.ok_or(FfmpegError::Arguments("missing buffer"))?
+			.source()
+			.send_eof(None)
+			.context("overlay eof")
+	}
+
+	pub fn receive_frame(&mut self) -> anyhow::Result<Option<Frame>> {
+		self.0
+			.get("out")
+			.ok_or(FfmpegError::Arguments("missing buffersink"))?
+			.sink()
+			.receive_frame()
+			.context("overlay receive frame")
+	}
+}
+
+/// Scales the image to the given height and applies the opacity to its alpha
+/// channel.
+fn prepare_image(image: &RgbaImage, height: u32, opacity: f32) -> RgbaImage {
+	let width = ((image.width() as f64 * height as f64 / image.height().max(1) as f64).round() as u32).max(1);
+
+	let mut image = image::imageops::resize(image, width, height, FilterType::Triangle);
+
+	for pixel in image.pixels_mut() {
+		pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u8;
+	}
+
+	image
+}
+
+/// The x and y expressions which place the overlay, in terms of the size of
+/// the video and the size of the overlay.
+fn position(
+	position: overlay::Position,
+	margin: i32,
+	(video_width, video_height): (&str, &str),
+	(width, height): (&str, &str),
+) -> (String, String) {
+	let left = margin.to_string();
+	let top = margin.to_string();
+	let right = format!("{video_width}-{width}-{margin}");
+	let bottom = format!("{video_height}-{height}-{margin}");
+
+	match position {
+		overlay::Position::TopLeft => (left, top),
+		overlay::Position::TopRight => (right, top),
+		overlay::Position::BottomLeft => (left, bottom),
+		overlay::Position::BottomRight => (right, bottom),
+		overlay::Position::Center => (format!("({video_width}-{width})/2"), format!("({video_height}-{height})/2")),
+	}
+}
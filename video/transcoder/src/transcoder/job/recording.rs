@@ -6,6 +6,7 @@ use aws_config::Region;
 use aws_sdk_s3::config::Credentials;
 use bytes::Bytes;
 use pb::ext::UlidExt;
+use pb::scuffle::video::internal::ingest_watch_response::{media, Media};
 use pb::scuffle::video::internal::live_rendition_manifest::recording_data::RecordingThumbnail;
 use pb::scuffle::video::v1::types::{AudioConfig, RecordingConfig, Rendition as PbRendition, VideoConfig};
 use prost::Message;
@@ -15,7 +16,11 @@ use utils::database::tokio_postgres::Transaction;
 use utils::task::AsyncTask;
 use video_common::database::{Rendition, S3Bucket, Visibility};
 
-use super::task::recording::{recording_task, recording_thumbnail_task, RecordingTask, RecordingThumbnailTask};
+use super::sprite::Sprite;
+use super::task::recording::{
+	recording_caption_task, recording_source_task, recording_sprite_task, recording_task, recording_thumbnail_task,
+	RecordingCaptionTask, RecordingSourceTask, RecordingSpriteTask, RecordingTask, RecordingThumbnailTask,
+};
 use crate::global::TranscoderGlobal;
 
 pub struct PartialUpload {
@@ -23,18 +28,42 @@ pub struct PartialUpload {
 	segment_idx: u32,
 	duration: f64,
 	start_time: f64,
+	discontinuity: bool,
 	parts: Vec<Bytes>,
 }
 
+pub struct PartialSourceChunk {
+	chunk_id: Ulid,
+	chunk_idx: u32,
+	start_time: f64,
+	end_time: f64,
+	parts: Vec<Bytes>,
+}
+
+pub struct PartialSprite {
+	sprite_id: Ulid,
+	sprite_idx: u32,
+	tile_times: Vec<f64>,
+	end_time: f64,
+	data: Bytes,
+}
+
 pub struct Recording {
 	id: Ulid,
 	allow_dvr: bool,
 	partial_uploads: HashMap<Rendition, PartialUpload>,
 	uploaders: HashMap<Rendition, mpsc::Sender<RecordingTask>>,
 	thumbnail_uploader: mpsc::Sender<RecordingThumbnailTask>,
+	sprite_uploader: mpsc::Sender<RecordingSpriteTask>,
+	sprite: Option<PartialSprite>,
+	caption_uploader: mpsc::Sender<RecordingCaptionTask>,
 	tasks: Vec<AsyncTask<anyhow::Result<()>>>,
 	renditions: HashSet<Rendition>,
 	previous_thumbnails: Vec<RecordingThumbnail>,
+	source_uploader: Option<mpsc::Sender<RecordingSourceTask>>,
+	source_chunk: Option<PartialSourceChunk>,
+	source_chunk_idx: u32,
+	source_chunk_duration: f64,
 }
 
 impl Recording {
@@ -44,7 +73,8 @@ impl Recording {
 		tx: &Transaction<'_>,
 		id: Ulid,
 		organization_id: Ulid,
-		room_id: Ulid,
+		room_id: Option<Ulid>,
+		connection_id: Ulid,
 		visibility: Visibility,
 		audio_outputs: &[AudioConfig],
 		video_outputs: &[VideoConfig],
@@ -127,9 +157,39 @@ impl Recording {
 		let (tx, rx) = mpsc::channel(16);
 		tasks.push(AsyncTask::new(
 			"recording(thumbnail)",
-			recording_thumbnail_task(global.clone(), organization_id, room_id, bucket.clone(), rx),
+			recording_thumbnail_task(global.clone(), organization_id, id, bucket.clone(), rx),
 		));
 
+		let (sprite_uploader, rx) = mpsc::channel(16);
+		tasks.push(AsyncTask::new(
+			"recording(sprite)",
+			recording_sprite_task(
+				global.clone(),
+				organization_id,
+				id,
+				global.config().sprite.clone(),
+				bucket.clone(),
+				rx,
+			),
+		));
+
+		let (caption_uploader, rx) = mpsc::channel(16);
+		tasks.push(AsyncTask::new(
+			"recording(captions)",
+			recording_caption_task(global.clone(), organization_id, id, bucket.clone(), rx),
+		));
+
+		let source_uploader = if recording_config.capture_source {
+			let (tx, rx) = mpsc::channel(16);
+			tasks.push(AsyncTask::new(
+				"recording(source)",
+				recording_source_task(global.clone(), organization_id, id, connection_id, bucket.clone(), rx),
+			));
+			Some(tx)
+		} else {
+			None
+		};
+
 		Ok(Self {
 			id,
 			allow_dvr,
@@ -139,6 +199,13 @@ impl Recording {
 			tasks,
 			previous_thumbnails: Vec::new(),
 			thumbnail_uploader: tx,
+			sprite_uploader,
+			sprite: None,
+			caption_uploader,
+			source_uploader,
+			source_chunk: None,
+			source_chunk_idx: 0,
+			source_chunk_duration: global.config().source_chunk_duration.as_secs_f64(),
 		})
 	}
 
@@ -171,6 +238,7 @@ impl Recording {
 		data: Bytes,
 		start_time: f64,
 		duration: f64,
+		discontinuity: bool,
 		finished: bool,
 	) -> anyhow::Result<()> {
 		if !self.renditions.contains(&rendition) {
@@ -182,10 +250,11 @@ impl Recording {
 			segment_idx: idx,
 			duration,
 			start_time,
+			discontinuity,
 			parts: Vec::new(),
 		});
 
-		if partial_upload.segment_id != id || finished {
+		if partial_upload.segment_id != id {
 			let partial_upload = std::mem::replace(
 				partial_upload,
 				PartialUpload {
@@ -193,26 +262,57 @@ impl Recording {
 					segment_idx: idx,
 					duration,
 					start_time,
+					discontinuity,
 					parts: vec![data],
 				},
 			);
 
-			self.uploaders
-				.get_mut(&rendition)
-				.unwrap()
-				.try_send(RecordingTask::Segment {
-					segment_id: partial_upload.segment_id,
-					segment_idx: partial_upload.segment_idx,
-					duration: partial_upload.duration,
-					start_time: partial_upload.start_time,
-					parts: partial_upload.parts,
-				})
-				.context("send upload task")?;
+			self.send_segment(rendition, partial_upload)?;
 		} else {
 			partial_upload.parts.push(data);
 			partial_upload.duration = duration;
 		}
 
+		// The last part of the track completes its segment, there is no next segment
+		// to start
+		if finished {
+			if let Some(partial_upload) = self.partial_uploads.remove(&rendition) {
+				self.send_segment(rendition, partial_upload)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn send_segment(&mut self, rendition: Rendition, partial_upload: PartialUpload) -> anyhow::Result<()> {
+		self.uploaders
+			.get_mut(&rendition)
+			.unwrap()
+			.try_send(RecordingTask::Segment {
+				segment_id: partial_upload.segment_id,
+				segment_idx: partial_upload.segment_idx,
+				duration: partial_upload.duration,
+				start_time: partial_upload.start_time,
+				discontinuity: partial_upload.discontinuity,
+				parts: partial_upload.parts,
+			})
+			.context("send upload task")?;
+
+		Ok(())
+	}
+
+	/// Waits until every uploader has room for another task. The live media
+	/// arrives in realtime, but an upload is transcoded faster than the
+	/// segments can be uploaded.
+	pub async fn ready_for_upload(&self) -> anyhow::Result<()> {
+		for uploader in self.uploaders.values() {
+			uploader.reserve().await.context("recording uploader closed")?;
+		}
+
+		self.thumbnail_uploader.reserve().await.context("thumbnail uploader closed")?;
+		self.sprite_uploader.reserve().await.context("sprite uploader closed")?;
+		self.caption_uploader.reserve().await.context("caption uploader closed")?;
+
 		Ok(())
 	}
 
@@ -254,4 +354,144 @@ impl Recording {
 
 		Ok(())
 	}
+
+	/// Sheets are uploaded once they are complete, until then the latest version
+	/// of the sheet is kept so it can be uploaded when the recording finishes.
+	pub fn upload_sprite(&mut self, idx: u32, sprite: &Sprite, end_time: f64) -> anyhow::Result<()> {
+		if self.sprite.as_ref().is_some_and(|partial| partial.sprite_idx != idx) {
+			self.finish_sprite()?;
+		}
+
+		let partial = self.sprite.get_or_insert_with(|| PartialSprite {
+			sprite_id: Ulid::new(),
+			sprite_idx: idx,
+			tile_times: Vec::new(),
+			end_time,
+			data: Bytes::new(),
+		});
+
+		partial.tile_times = sprite.tile_times.clone();
+		partial.end_time = end_time;
+		partial.data = sprite.data.clone();
+
+		if sprite.complete {
+			self.finish_sprite()?;
+		}
+
+		Ok(())
+	}
+
+	/// Uploads the sheet which is being filled, if there is one.
+	pub fn finish_sprite(&mut self) -> anyhow::Result<()> {
+		let Some(sprite) = self.sprite.take() else {
+			return Ok(());
+		};
+
+		self.sprite_uploader
+			.try_send(RecordingSpriteTask {
+				idx: sprite.sprite_idx,
+				id: sprite.sprite_id,
+				tile_times: sprite.tile_times,
+				end_time: sprite.end_time,
+				data: sprite.data,
+			})
+			.context("send sprite task")?;
+
+		Ok(())
+	}
+
+	pub fn upload_captions(
+		&mut self,
+		idx: u32,
+		id: Ulid,
+		start_time: f64,
+		end_time: f64,
+		data: Bytes,
+	) -> anyhow::Result<()> {
+		self.caption_uploader
+			.try_send(RecordingCaptionTask {
+				idx,
+				id,
+				start_time,
+				end_time,
+				data,
+			})
+			.context("send caption task")?;
+
+		Ok(())
+	}
+
+	/// Archives the media as it was received from ingest. Chunks are cut at
+	/// the first keyframe after the chunk duration is reached.
+	pub fn capture_source(&mut self, media: &Media) -> anyhow::Result<()> {
+		let Some(uploader) = &self.source_uploader else {
+			return Ok(());
+		};
+
+		if media.r#type() == media::Type::Init {
+			return uploader
+				.try_send(RecordingSourceTask::Init {
+					data: media.data.clone(),
+				})
+				.context("send source init task");
+		}
+
+		let time = media.timestamp as f64 / media.timescale.max(1) as f64;
+
+		if media.r#type() == media::Type::Video && media.keyframe {
+			if self
+				.source_chunk
+				.as_ref()
+				.is_some_and(|chunk| time - chunk.start_time >= self.source_chunk_duration)
+			{
+				self.finish_source_chunk(time)?;
+			}
+
+			if self.source_chunk.is_none() {
+				self.source_chunk = Some(PartialSourceChunk {
+					chunk_id: Ulid::new(),
+					chunk_idx: self.source_chunk_idx,
+					start_time: time,
+					end_time: time,
+					parts: Vec::new(),
+				});
+				self.source_chunk_idx += 1;
+			}
+		}
+
+		// Chunks start on a keyframe, so anything before the first one is dropped.
+		if let Some(chunk) = &mut self.source_chunk {
+			if media.r#type() != media::Type::Metadata {
+				chunk.end_time = chunk.end_time.max(time);
+			}
+
+			chunk.parts.push(media.data.clone());
+		}
+
+		Ok(())
+	}
+
+	/// Uploads the chunk which is being captured, if there is one.
+	pub fn finish_source(&mut self) -> anyhow::Result<()> {
+		let end_time = self.source_chunk.as_ref().map(|chunk| chunk.end_time).unwrap_or_default();
+		self.finish_source_chunk(end_time)
+	}
+
+	fn finish_source_chunk(&mut self, end_time: f64) -> anyhow::Result<()> {
+		let (Some(uploader), Some(chunk)) = (&self.source_uploader, self.source_chunk.take()) else {
+			return Ok(());
+		};
+
+		uploader
+			.try_send(RecordingSourceTask::Chunk {
+				chunk_id: chunk.chunk_id,
+				chunk_idx: chunk.chunk_idx,
+				start_time: chunk.start_time,
+				end_time,
+				parts: chunk.parts,
+			})
+			.context("send source chunk task")?;
+
+		Ok(())
+	}
 }
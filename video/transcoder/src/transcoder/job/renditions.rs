@@ -1,5 +1,43 @@
-use mp4::codec::VideoCodec;
-use pb::scuffle::video::v1::types::{AudioConfig, Rendition, TranscodingConfig, VideoConfig};
+use mp4::codec::{AudioCodec, VideoCodec};
+use pb::scuffle::video::v1::types::{custom_rendition, AudioConfig, Rendition, TranscodingConfig, VideoConfig};
+
+/// The H.264 High 5.1 codec the built-in renditions are encoded with.
+const H264_HIGH: VideoCodec = VideoCodec::Avc {
+	profile: 100, // High
+	level: 51,    // 5.1
+	constraint_set: 0,
+};
+
+/// The HEVC Main 4.1 codec custom renditions can be encoded with.
+const HEVC_MAIN: VideoCodec = VideoCodec::Hevc {
+	general_profile_space: 0,
+	profile: 1,                  // Main
+	profile_compatibility: 0x60, // Main, Main 10
+	level: 123,                  // 4.1
+	tier: false,
+	constraint_indicator: 0x90, // Progressive, frame only
+};
+
+/// The AV1 Main 4.1 codec custom renditions can be encoded with.
+const AV1_MAIN: VideoCodec = VideoCodec::Av1 {
+	profile: 0, // Main
+	level: 9,   // 4.1
+	tier: false,
+	depth: 8,
+	monochrome: false,
+	sub_sampling_x: true,
+	sub_sampling_y: true,
+	color_primaries: 1,
+	transfer_characteristics: 1,
+	matrix_coefficients: 1,
+	full_range_flag: false,
+};
+
+/// The highest sample rate the transcoded aac renditions are encoded with.
+const AAC_MAX_SAMPLE_RATE: i32 = 48000;
+
+/// Opus is always encoded at 48kHz.
+const OPUS_SAMPLE_RATE: i32 = 48000;
 
 pub fn determine_output_renditions(
 	video_input: &VideoConfig,
@@ -19,6 +57,48 @@ pub fn determine_output_renditions(
 		});
 	}
 
+	// The transcoded audio renditions are all stereo, sources with more channels
+	// are downmixed.
+	if transcoding_config.renditions.contains(&Rendition::AudioAacHe.into()) {
+		audio_configs.push(AudioConfig {
+			rendition: Rendition::AudioAacHe as i32,
+			codec: AudioCodec::Aac {
+				object_type: aac::AudioObjectType::Sbr,
+			}
+			.to_string(),
+			bitrate: 48 * 1024,
+			channels: 2,
+			sample_rate: audio_input.sample_rate.min(AAC_MAX_SAMPLE_RATE),
+		});
+	}
+
+	if transcoding_config.renditions.contains(&Rendition::AudioOpus.into()) {
+		audio_configs.push(AudioConfig {
+			rendition: Rendition::AudioOpus as i32,
+			codec: AudioCodec::Opus.to_string(),
+			bitrate: 96 * 1024,
+			channels: 2,
+			sample_rate: OPUS_SAMPLE_RATE,
+		});
+	}
+
+	// A stereo downmix is only useful when the source is not already stereo,
+	// unless it would be the only audio rendition.
+	if transcoding_config.renditions.contains(&Rendition::AudioStereo.into())
+		&& (audio_input.channels > 2 || audio_configs.is_empty())
+	{
+		audio_configs.push(AudioConfig {
+			rendition: Rendition::AudioStereo as i32,
+			codec: AudioCodec::Aac {
+				object_type: aac::AudioObjectType::AacLowComplexity,
+			}
+			.to_string(),
+			bitrate: 128 * 1024,
+			channels: 2,
+			sample_rate: audio_input.sample_rate.min(AAC_MAX_SAMPLE_RATE),
+		});
+	}
+
 	if transcoding_config.renditions.contains(&Rendition::VideoSource.into()) {
 		video_configs.push(VideoConfig {
 			rendition: Rendition::VideoSource as i32,
@@ -27,6 +107,7 @@ pub fn determine_output_renditions(
 			fps: video_input.fps,
 			height: video_input.height,
 			width: video_input.width,
+			name: String::new(),
 		});
 	}
 
@@ -34,9 +115,12 @@ pub fn determine_output_renditions(
 
 	struct Resolution {
 		rendition: Rendition,
+		name: String,
 		side: u32,
 		framerate: u32,
-		bitrate: u32,
+		bitrate: i64,
+		codec: VideoCodec,
+		max_area: u32,
 	}
 
 	let mut resolutions = vec![];
@@ -44,30 +128,73 @@ pub fn determine_output_renditions(
 	if transcoding_config.renditions.contains(&Rendition::VideoHd.into()) {
 		resolutions.push(Resolution {
 			rendition: Rendition::VideoHd,
+			name: String::new(),
 			bitrate: 4000 * 1024,
 			framerate: video_input.fps.min(60) as u32,
 			side: 720,
+			codec: H264_HIGH,
+			max_area: 720 * 1280,
 		});
 	}
 
 	if transcoding_config.renditions.contains(&Rendition::VideoSd.into()) {
 		resolutions.push(Resolution {
 			rendition: Rendition::VideoSd,
+			name: String::new(),
 			bitrate: 2000 * 1024,
 			framerate: video_input.fps.min(30) as u32,
 			side: 480,
+			codec: H264_HIGH,
+			max_area: 720 * 1280,
 		});
 	}
 
 	if transcoding_config.renditions.contains(&Rendition::VideoLd.into()) {
 		resolutions.push(Resolution {
 			rendition: Rendition::VideoLd,
+			name: String::new(),
 			bitrate: 1000 * 1024,
 			framerate: video_input.fps.min(30) as u32,
 			side: 360,
+			codec: H264_HIGH,
+			max_area: 720 * 1280,
 		})
 	}
 
+	// Custom renditions are only transcoded when the config selects them, a
+	// rendition without a definition is skipped.
+	for custom in &transcoding_config.custom_renditions {
+		if !transcoding_config.renditions.contains(&custom.rendition) {
+			continue;
+		}
+
+		let framerate = if custom.max_fps > 0 {
+			video_input.fps.min(custom.max_fps)
+		} else {
+			video_input.fps
+		};
+
+		let bitrate = if video_input.bitrate > 0 {
+			video_input.bitrate.min(custom.max_bitrate)
+		} else {
+			custom.max_bitrate
+		};
+
+		resolutions.push(Resolution {
+			rendition: custom.rendition(),
+			name: custom.name.clone(),
+			bitrate,
+			framerate: framerate as u32,
+			side: custom.side as u32,
+			codec: match custom.codec() {
+				custom_rendition::Codec::H264 => H264_HIGH,
+				custom_rendition::Codec::Hevc => HEVC_MAIN,
+				custom_rendition::Codec::Av1 => AV1_MAIN,
+			},
+			max_area: 1080 * 1920,
+		});
+	}
+
 	for res in resolutions {
 		// This prevents us from upscaling the video
 		// We only want to downscale the video
@@ -80,7 +207,7 @@ pub fn determine_output_renditions(
 		};
 
 		// we need even numbers for the width and height
-		// this is a requirement of the h264 codec
+		// this is a requirement of the h264, hevc and av1 encoders
 		if width % 2 != 0 {
 			width += 1;
 		} else if height % 2 != 0 {
@@ -95,23 +222,20 @@ pub fn determine_output_renditions(
 		// expensive than a normal 720p video. 1080 * 1920 = 2073600
 		// 720 * 2880 = 2073600
 		// So a 720p video with an aspect ratio of 4:1 is just as expensive as a 1080p
-		// video with a 16:9 aspect ratio.
-		if width < 100 || height < 100 || width * height > 720 * 1280 {
+		// video with a 16:9 aspect ratio. Custom renditions go up to 1080p, so they
+		// are held to the cost of a 1080p video instead.
+		if width < 100 || height < 100 || width * height > res.max_area {
 			continue;
 		}
 
 		video_configs.push(VideoConfig {
 			rendition: res.rendition as i32,
-			codec: VideoCodec::Avc {
-				profile: 100, // High
-				level: 51,    // 5.1
-				constraint_set: 0,
-			}
-			.to_string(),
-			bitrate: res.bitrate as i64,
+			codec: res.codec.to_string(),
+			bitrate: res.bitrate,
 			fps: res.framerate as i32,
 			height: height as i32,
 			width: width as i32,
+			name: res.name,
 		})
 	}
 
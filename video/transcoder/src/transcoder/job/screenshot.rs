@@ -5,7 +5,17 @@ use ffmpeg::frame::Frame;
 use image::codecs::jpeg::JpegEncoder;
 use tokio::sync::mpsc;
 
-pub fn screenshot_task(mut recv: mpsc::Receiver<Frame>, send: mpsc::Sender<(Bytes, f64)>) -> anyhow::Result<()> {
+use super::sprite::{Sprite, SpriteSheet};
+use crate::config::SpriteConfig;
+
+pub fn screenshot_task(
+	mut recv: mpsc::Receiver<Frame>,
+	send: mpsc::Sender<(Bytes, f64)>,
+	sprite_config: SpriteConfig,
+	sprite_send: mpsc::Sender<Sprite>,
+) -> anyhow::Result<()> {
+	let mut sheet = SpriteSheet::new(&sprite_config);
+
 	while let Some(frame) = recv.blocking_recv() {
 		let _guard = utils::task::AbortGuard::new();
 
@@ -15,14 +25,14 @@ pub fn screenshot_task(mut recv: mpsc::Receiver<Frame>, send: mpsc::Sender<(Byte
 		let mut encoder = JpegEncoder::new_with_quality(&mut writer, 95);
 		let width = frame.width() as u32;
 		let height = frame.height() as u32;
-		let data = frame.data(0).ok_or_else(|| anyhow::anyhow!("no frame data"))?;
+		let pixels = frame.data(0).ok_or_else(|| anyhow::anyhow!("no frame data"))?;
 
 		if frame.format() != AVPixelFormat::AV_PIX_FMT_RGBA as i32 {
 			anyhow::bail!("expected rgb frame");
 		}
 
 		encoder
-			.encode(data, width, height, image::ColorType::Rgba8)
+			.encode(pixels, width, height, image::ColorType::Rgba8)
 			.context("failed to encode jpeg")?;
 
 		let data = Bytes::from(writer);
@@ -35,6 +45,17 @@ pub fn screenshot_task(mut recv: mpsc::Receiver<Frame>, send: mpsc::Sender<(Byte
 		let time = timestamp as f64 * frame.time_base().num as f64 / frame.time_base().den as f64;
 
 		send.blocking_send((data, time)).context("failed to send screenshot")?;
+
+		if sheet.is_full() {
+			sheet = SpriteSheet::new(&sprite_config);
+		}
+
+		let stride = frame.linesize(0).ok_or_else(|| anyhow::anyhow!("no frame linesize"))? as usize;
+		sheet.add(pixels, stride, width, height, time)?;
+
+		// The sheet is sent every time a tile is added, so a live stream can show
+		// the tiles before the sheet is complete.
+		sprite_send.blocking_send(sheet.encode()?).context("failed to send sprite")?;
 	}
 
 	Ok(())
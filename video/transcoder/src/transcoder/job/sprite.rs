@@ -0,0 +1,92 @@
+use anyhow::Context;
+use bytes::Bytes;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+
+use crate::config::SpriteConfig;
+
+pub struct Sprite {
+	pub data: Bytes,
+	pub tile_times: Vec<f64>,
+	/// If every tile on the sheet has been filled, the next tile starts a new
+	/// sheet.
+	pub complete: bool,
+}
+
+/// A sheet of screenshots scaled down to tiles, filled row by row.
+pub struct SpriteSheet {
+	canvas: RgbaImage,
+	config: SpriteConfig,
+	tile_times: Vec<f64>,
+}
+
+impl SpriteSheet {
+	pub fn new(config: &SpriteConfig) -> Self {
+		Self {
+			canvas: RgbaImage::from_pixel(
+				config.tile_width * config.columns,
+				config.tile_height * config.rows,
+				Rgba([0, 0, 0, 255]),
+			),
+			config: config.clone(),
+			tile_times: Vec::new(),
+		}
+	}
+
+	pub fn is_full(&self) -> bool {
+		self.tile_times.len() as u32 >= self.config.columns * self.config.rows
+	}
+
+	/// Adds the RGBA screenshot as the next tile, keeping its aspect ratio and
+	/// centering it on the tile.
+	pub fn add(&mut self, data: &[u8], stride: usize, width: u32, height: u32, time: f64) -> anyhow::Result<()> {
+		if self.is_full() {
+			anyhow::bail!("sprite sheet is full");
+		}
+
+		let row = width as usize * 4;
+		let pixels = data
+			.chunks(stride)
+			.take(height as usize)
+			.flat_map(|line| &line[..row.min(line.len())])
+			.copied()
+			.collect();
+
+		let image = RgbaImage::from_raw(width, height, pixels).context("invalid screenshot buffer")?;
+
+		let scale = (self.config.tile_width as f64 / width as f64).min(self.config.tile_height as f64 / height as f64);
+		let scaled_width = ((width as f64 * scale).round() as u32).clamp(1, self.config.tile_width);
+		let scaled_height = ((height as f64 * scale).round() as u32).clamp(1, self.config.tile_height);
+
+		let tile = image::imageops::resize(&image, scaled_width, scaled_height, FilterType::Triangle);
+
+		let idx = self.tile_times.len() as u32;
+		let x = (idx % self.config.columns) * self.config.tile_width + (self.config.tile_width - scaled_width) / 2;
+		let y = (idx / self.config.columns) * self.config.tile_height + (self.config.tile_height - scaled_height) / 2;
+
+		image::imageops::replace(&mut self.canvas, &tile, x as i64, y as i64);
+
+		self.tile_times.push(time);
+
+		Ok(())
+	}
+
+	pub fn encode(&self) -> anyhow::Result<Sprite> {
+		let mut writer = Vec::new();
+		JpegEncoder::new_with_quality(&mut writer, 80)
+			.encode(
+				self.canvas.as_raw(),
+				self.canvas.width(),
+				self.canvas.height(),
+				image::ColorType::Rgba8,
+			)
+			.context("failed to encode sprite")?;
+
+		Ok(Sprite {
+			data: Bytes::from(writer),
+			tile_times: self.tile_times.clone(),
+			complete: self.is_full(),
+		})
+	}
+}
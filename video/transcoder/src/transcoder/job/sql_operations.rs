@@ -2,10 +2,10 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use pb::ext::UlidExt;
-use pb::scuffle::video::v1::types::{AudioConfig, Rendition, TranscodingConfig, VideoConfig};
+use pb::scuffle::video::v1::types::{AudioConfig, RecordingConfig, Rendition, TranscodingConfig, VideoConfig};
 use prost::Message;
 use ulid::Ulid;
-use video_common::database::Room;
+use video_common::database::{Room, S3Bucket, Visibility};
 
 use super::recording::Recording;
 use crate::global::TranscoderGlobal;
@@ -21,13 +21,33 @@ pub struct SqlOperations {
 	pub audio_output: Vec<AudioConfig>,
 }
 
-pub async fn perform_sql_operations(
+/// The configs of a room, read before the job is taken on.
+pub struct JobConfig {
+	active_recording_id: Option<Ulid>,
+	visibility: Visibility,
+	recording_config: Option<(RecordingConfig, S3Bucket)>,
+	pub transcoding_config: TranscodingConfig,
+	pub video_input: VideoConfig,
+	pub audio_input: AudioConfig,
+	pub video_output: Vec<VideoConfig>,
+	pub audio_output: Vec<AudioConfig>,
+}
+
+/// Without a transcoding config only the source is passed through.
+pub fn default_transcoding_config() -> TranscodingConfig {
+	TranscodingConfig {
+		renditions: vec![Rendition::AudioSource.into(), Rendition::VideoSource.into()],
+		..Default::default()
+	}
+}
+
+pub async fn query_job_config(
 	global: &Arc<impl TranscoderGlobal>,
 	organization_id: Ulid,
 	room_id: Ulid,
 	connection_id: Ulid,
-) -> anyhow::Result<SqlOperations> {
-	let mut client = global.db().get().await.context("failed to get database connection")?;
+) -> anyhow::Result<JobConfig> {
+	let client = global.db().get().await.context("failed to get database connection")?;
 
 	let room: Option<Room> = match utils::database::query(
 		r#"
@@ -154,14 +174,43 @@ pub async fn perform_sql_operations(
 			}
 		}
 	} else {
-		TranscodingConfig {
-			renditions: vec![Rendition::AudioSource.into(), Rendition::VideoSource.into()],
-			..Default::default()
-		}
+		default_transcoding_config()
 	};
 
 	let (video_output, audio_output) = determine_output_renditions(&video_input, &audio_input, &transcoding_config);
 
+	Ok(JobConfig {
+		active_recording_id: room.active_recording_id.map(Ulid::from),
+		visibility: room.visibility,
+		recording_config,
+		transcoding_config,
+		video_input,
+		audio_input,
+		video_output,
+		audio_output,
+	})
+}
+
+pub async fn perform_sql_operations(
+	global: &Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	room_id: Ulid,
+	connection_id: Ulid,
+	config: JobConfig,
+) -> anyhow::Result<SqlOperations> {
+	let JobConfig {
+		active_recording_id,
+		visibility,
+		recording_config,
+		transcoding_config,
+		video_input,
+		audio_input,
+		video_output,
+		audio_output,
+	} = config;
+
+	let mut client = global.db().get().await.context("failed to get database connection")?;
+
 	let tx = client.transaction().await.context("failed to start transaction")?;
 
 	utils::database::query(
@@ -195,10 +244,11 @@ pub async fn perform_sql_operations(
 			Recording::new(
 				global,
 				&tx,
-				room.active_recording_id.map(Ulid::from).unwrap_or_else(Ulid::new),
+				active_recording_id.unwrap_or_else(Ulid::new),
 				organization_id,
-				room_id,
-				room.visibility,
+				Some(room_id),
+				connection_id,
+				visibility,
 				&audio_output,
 				&video_output,
 				s3_bucket,
@@ -3,7 +3,8 @@ use std::sync::Arc;
 use anyhow::Context;
 use bytes::Bytes;
 use pb::scuffle::video::v1::events_fetch_request::Target;
-use pb::scuffle::video::v1::types::event;
+use pb::scuffle::video::v1::types::{event, AudioLoudness};
+use prost::Message as _;
 use tokio::sync::mpsc;
 use ulid::Ulid;
 use video_common::database::RoomStatus;
@@ -13,8 +14,11 @@ use crate::global::TranscoderGlobal;
 
 pub enum GenericTask {
 	Screenshot { data: Bytes, idx: u32 },
+	Sprite { data: Bytes, idx: u32 },
+	Captions { data: Bytes, idx: u32 },
 	Manifest { data: Bytes },
 	RoomReady,
+	Loudness { loudness: AudioLoudness },
 }
 
 pub async fn generic_task(
@@ -36,6 +40,22 @@ pub async fn generic_task(
 							.await
 							.context("upload screenshot")?;
 					}
+					GenericTask::Sprite { data, idx } => {
+						let key = video_common::keys::sprite(organization_id, room_id, connection_id, *idx);
+						global
+							.media_store()
+							.put(key.as_str(), &mut std::io::Cursor::new(&data))
+							.await
+							.context("upload sprite")?;
+					}
+					GenericTask::Captions { data, idx } => {
+						let key = video_common::keys::captions(organization_id, room_id, connection_id, *idx);
+						global
+							.media_store()
+							.put(key.as_str(), &mut std::io::Cursor::new(&data))
+							.await
+							.context("upload captions")?;
+					}
 					GenericTask::Manifest { data } => {
 						let key = video_common::keys::manifest(organization_id, room_id, connection_id);
 						global
@@ -84,6 +104,29 @@ pub async fn generic_task(
 						)
 						.await;
 					}
+					GenericTask::Loudness { loudness } => {
+						// The room may have moved on to another connection, which is not an error
+						utils::database::query(
+							r#"
+						UPDATE rooms
+						SET
+							updated_at = NOW(),
+							audio_loudness = $1
+						WHERE
+							organization_id = $2 AND
+							id = $3 AND
+							active_ingest_connection_id = $4
+						"#,
+						)
+						.bind(loudness.encode_to_vec())
+						.bind(organization_id)
+						.bind(room_id)
+						.bind(connection_id)
+						.build()
+						.execute(global.db())
+						.await
+						.context("update room loudness")?;
+					}
 				}
 				Ok(())
 			},
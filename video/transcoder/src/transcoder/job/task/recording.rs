@@ -10,6 +10,7 @@ use ulid::Ulid;
 use video_common::database::Rendition;
 
 use super::retry_task;
+use crate::config::SpriteConfig;
 use crate::global::TranscoderGlobal;
 
 pub enum RecordingTask {
@@ -18,6 +19,7 @@ pub enum RecordingTask {
 		segment_idx: u32,
 		duration: f64,
 		start_time: f64,
+		discontinuity: bool,
 		parts: Vec<Bytes>,
 	},
 	Init {
@@ -47,6 +49,7 @@ pub async fn recording_task(
 						segment_idx,
 						duration,
 						start_time,
+						discontinuity,
 						parts,
 					} => {
 						let size = parts.iter().map(|p| p.len()).sum::<usize>();
@@ -83,7 +86,8 @@ pub async fn recording_task(
                             id,
                             start_time,
                             end_time,
-                            size_bytes
+                            size_bytes,
+                            discontinuity
                         ) VALUES (
                             $1,
                             $2,
@@ -92,7 +96,8 @@ pub async fn recording_task(
                             $5,
                             $6,
                             $7,
-                            $8
+                            $8,
+                            $9
                         )"#,
 						)
 						.bind(organization_id)
@@ -103,10 +108,12 @@ pub async fn recording_task(
 						.bind(normalize_float(*start_time))
 						.bind(normalize_float(start_time + duration))
 						.bind(size as i64)
+						.bind(*discontinuity)
 						.build()
 						.execute(global.db())
 						.await
-						.context("insert segment")? != 1
+						.context("insert segment")?
+							!= 1
 						{
 							anyhow::bail!("no rows affected");
 						}
@@ -182,7 +189,8 @@ pub async fn recording_thumbnail_task(
                     $2,
                     $3,
                     $4,
-                    $5
+                    $5,
+                    $6
                 )"#,
 				)
 				.bind(organization_id)
@@ -210,3 +218,297 @@ pub async fn recording_thumbnail_task(
 
 	Ok(())
 }
+
+pub struct RecordingSpriteTask {
+	pub idx: u32,
+	pub id: Ulid,
+	pub tile_times: Vec<f64>,
+	pub end_time: f64,
+	pub data: Bytes,
+}
+
+pub async fn recording_sprite_task(
+	global: Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	sprite_config: SpriteConfig,
+	bucket: binary_helper::s3::Bucket,
+	mut rx: mpsc::Receiver<RecordingSpriteTask>,
+) -> anyhow::Result<()> {
+	while let Some(task) = rx.recv().await {
+		retry_task(
+			|| async {
+				let size = task.data.len();
+
+				bucket
+					.put_object(
+						video_common::keys::s3_sprite(organization_id, recording_id, task.idx, task.id),
+						task.data.clone(),
+						Some(PutObjectOptions {
+							content_type: Some("image/jpeg".to_owned()),
+							acl: Some(ObjectCannedAcl::PublicRead),
+						}),
+					)
+					.await
+					.context("upload sprite")?;
+
+				if utils::database::query(
+					r#"
+                INSERT INTO recording_sprites (
+                    organization_id,
+                    recording_id,
+                    idx,
+                    id,
+                    start_time,
+                    end_time,
+                    tile_times,
+                    tile_width,
+                    tile_height,
+                    columns,
+                    rows,
+                    size_bytes
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9,
+                    $10,
+                    $11,
+                    $12
+                )"#,
+				)
+				.bind(organization_id)
+				.bind(recording_id)
+				.bind(task.idx as i32)
+				.bind(task.id)
+				.bind(normalize_float(task.tile_times.first().copied().unwrap_or_default()))
+				.bind(normalize_float(task.end_time))
+				.bind(task.tile_times.iter().map(|t| normalize_float(*t) as f32).collect::<Vec<_>>())
+				.bind(sprite_config.tile_width as i32)
+				.bind(sprite_config.tile_height as i32)
+				.bind(sprite_config.columns as i32)
+				.bind(sprite_config.rows as i32)
+				.bind(size as i64)
+				.build()
+				.execute(global.db())
+				.await
+				.context("insert sprite")?
+					!= 1
+				{
+					anyhow::bail!("no rows affected");
+				}
+
+				Ok(())
+			},
+			5,
+		)
+		.await
+		.context("s3_sprite_task")?;
+	}
+
+	Ok(())
+}
+
+pub struct RecordingCaptionTask {
+	pub idx: u32,
+	pub id: Ulid,
+	pub start_time: f64,
+	pub end_time: f64,
+	pub data: Bytes,
+}
+
+pub async fn recording_caption_task(
+	global: Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	bucket: binary_helper::s3::Bucket,
+	mut rx: mpsc::Receiver<RecordingCaptionTask>,
+) -> anyhow::Result<()> {
+	while let Some(task) = rx.recv().await {
+		retry_task(
+			|| async {
+				let size = task.data.len();
+
+				bucket
+					.put_object(
+						video_common::keys::s3_captions(organization_id, recording_id, task.idx, task.id),
+						task.data.clone(),
+						Some(PutObjectOptions {
+							content_type: Some("text/vtt".to_owned()),
+							acl: Some(ObjectCannedAcl::PublicRead),
+						}),
+					)
+					.await
+					.context("upload captions")?;
+
+				if utils::database::query(
+					r#"
+                INSERT INTO recording_caption_segments (
+                    organization_id,
+                    recording_id,
+                    idx,
+                    id,
+                    start_time,
+                    end_time,
+                    size_bytes
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7
+                )"#,
+				)
+				.bind(organization_id)
+				.bind(recording_id)
+				.bind(task.idx as i32)
+				.bind(task.id)
+				.bind(normalize_float(task.start_time))
+				.bind(normalize_float(task.end_time))
+				.bind(size as i64)
+				.build()
+				.execute(global.db())
+				.await
+				.context("insert captions")?
+					!= 1
+				{
+					anyhow::bail!("no rows affected");
+				}
+
+				Ok(())
+			},
+			5,
+		)
+		.await
+		.context("s3_caption_task")?;
+	}
+
+	Ok(())
+}
+
+pub enum RecordingSourceTask {
+	Chunk {
+		chunk_id: Ulid,
+		chunk_idx: u32,
+		start_time: f64,
+		end_time: f64,
+		parts: Vec<Bytes>,
+	},
+	Init {
+		data: Bytes,
+	},
+}
+
+pub async fn recording_source_task(
+	global: Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	connection_id: Ulid,
+	bucket: binary_helper::s3::Bucket,
+	mut rx: mpsc::Receiver<RecordingSourceTask>,
+) -> anyhow::Result<()> {
+	while let Some(task) = rx.recv().await {
+		retry_task(
+			|| async {
+				match &task {
+					RecordingSourceTask::Chunk {
+						chunk_id,
+						chunk_idx,
+						start_time,
+						end_time,
+						parts,
+					} => {
+						let size = parts.iter().map(|p| p.len()).sum::<usize>();
+
+						let stream = futures_util::stream::iter(parts.clone()).map(std::io::Result::Ok);
+
+						// The source is not played back, so it is not made public.
+						bucket
+							.put_object(
+								video_common::keys::s3_source_chunk(
+									organization_id,
+									recording_id,
+									connection_id,
+									*chunk_idx,
+									*chunk_id,
+								),
+								AsyncStreamBody(stream),
+								Some(PutObjectOptions {
+									content_type: Some("video/mp4".to_owned()),
+									acl: None,
+								}),
+							)
+							.await
+							.context("upload source chunk")?;
+
+						if utils::database::query(
+							r#"
+                        INSERT INTO recording_source_chunks (
+                            organization_id,
+                            recording_id,
+                            idx,
+                            id,
+                            connection_id,
+                            start_time,
+                            end_time,
+                            size_bytes
+                        ) VALUES (
+                            $1,
+                            $2,
+                            $3,
+                            $4,
+                            $5,
+                            $6,
+                            $7,
+                            $8
+                        )"#,
+						)
+						.bind(organization_id)
+						.bind(recording_id)
+						.bind(*chunk_idx as i32)
+						.bind(*chunk_id)
+						.bind(connection_id)
+						.bind(normalize_float(*start_time))
+						.bind(normalize_float(*end_time))
+						.bind(size as i64)
+						.build()
+						.execute(global.db())
+						.await
+						.context("insert source chunk")?
+							!= 1
+						{
+							anyhow::bail!("no rows affected");
+						}
+					}
+					RecordingSourceTask::Init { data } => {
+						bucket
+							.put_object(
+								video_common::keys::s3_source_init(organization_id, recording_id, connection_id),
+								data.clone(),
+								Some(PutObjectOptions {
+									content_type: Some("video/mp4".to_owned()),
+									acl: None,
+								}),
+							)
+							.await
+							.context("upload source init")?;
+					}
+				}
+
+				Ok(())
+			},
+			5,
+		)
+		.await
+		.context("s3_recording_source_task")?;
+	}
+
+	Ok(())
+}
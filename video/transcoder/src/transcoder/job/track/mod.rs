@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use bytes::Bytes;
+use mp4::types::emsg::Emsg;
 use pb::scuffle::video::internal::live_rendition_manifest::{self, RecordingData, RenditionInfo, Segment};
 use pb::scuffle::video::internal::LiveRenditionManifest;
 use prost::Message;
@@ -19,7 +20,8 @@ pub mod state;
 pub struct Track {
 	rendition: Rendition,
 	state: state::TrackState,
-	uploader: mpsc::Sender<TrackTask>,
+	// Not set for uploads, which are only written to the recording.
+	uploader: Option<mpsc::Sender<TrackTask>>,
 	target_part_duration: f64,
 	max_part_duration: f64,
 	min_segment_duration: f64,
@@ -28,7 +30,11 @@ pub struct Track {
 }
 
 impl Track {
-	pub fn new(global: &Arc<impl TranscoderGlobal>, rendition: Rendition, uploader: mpsc::Sender<TrackTask>) -> Self {
+	pub fn new(
+		global: &Arc<impl TranscoderGlobal>,
+		rendition: Rendition,
+		uploader: Option<mpsc::Sender<TrackTask>>,
+	) -> Self {
 		Self {
 			rendition,
 			state: state::TrackState::default(),
@@ -50,11 +56,11 @@ impl Track {
 	}
 
 	pub fn upload_init(&mut self) -> anyhow::Result<()> {
-		if !self.ready {
+		let (true, Some(uploader)) = (self.ready, &self.uploader) else {
 			return Ok(());
-		}
+		};
 
-		self.uploader
+		uploader
 			.try_send(TrackTask::Init {
 				data: self.state.init_segment().unwrap().clone(),
 			})
@@ -88,13 +94,17 @@ impl Track {
 		}
 	}
 
+	pub fn append_event(&mut self, event: Emsg) {
+		self.state.append_event(event);
+	}
+
 	pub fn finish(&mut self, mut recording: Option<&mut Recording>) -> anyhow::Result<()> {
 		if !self.ready {
 			return Ok(());
 		}
 
 		if let Some((segment_idx, part_idx)) = self.state.finish() {
-			self.handle_addtion(&mut recording, segment_idx, part_idx)?;
+			self.handle_addtion(&mut recording, segment_idx, part_idx, true)?;
 		}
 
 		Ok(())
@@ -104,6 +114,34 @@ impl Track {
 		self.state.init_segment()
 	}
 
+	/// The duration of the track in seconds, including the segments of
+	/// previous connections.
+	pub fn duration(&self) -> f64 {
+		self.state.total_duration() as f64 / self.state.timescale().max(1) as f64
+	}
+
+	/// The index, start and end time in seconds of the segments this
+	/// connection has completed. Segments restored from a previous connection
+	/// are skipped, the last segment is only complete once the track is.
+	pub fn completed_segments(&self) -> impl Iterator<Item = (u32, f64, f64)> + '_ {
+		let timescale = self.state.timescale().max(1) as f64;
+		let count = self.state.segments().count();
+		let complete = self.state.complete();
+
+		self.state
+			.segments()
+			.enumerate()
+			.filter(move |(idx, segment)| {
+				!segment.parts.is_empty()
+					&& segment.parts.iter().all(|p| !p.data.is_empty())
+					&& (complete || idx + 1 < count)
+			})
+			.map(move |(_, segment)| {
+				let start = segment.parts[0].start_ts as f64 / timescale;
+				(segment.idx, start, start + segment.duration() as f64 / timescale)
+			})
+	}
+
 	fn handle_samples(&mut self, mut recording: Option<&mut Recording>) -> anyhow::Result<bool> {
 		if !self.ready {
 			return Ok(false);
@@ -117,7 +155,7 @@ impl Track {
 
 		for (segment_idx, parts) in additions {
 			for part_idx in parts {
-				self.handle_addtion(&mut recording, segment_idx, part_idx)?;
+				self.handle_addtion(&mut recording, segment_idx, part_idx, false)?;
 				has_additions = true;
 			}
 		}
@@ -132,6 +170,7 @@ impl Track {
 		recording: &mut Option<&mut Recording>,
 		segment_idx: u32,
 		part_idx: u32,
+		finished: bool,
 	) -> anyhow::Result<f64> {
 		let segment = self.state.segment(segment_idx).unwrap();
 
@@ -146,23 +185,26 @@ impl Track {
 					part.data.clone(),
 					segment.parts.first().map(|p| p.start_ts).unwrap_or_default() as f64 / self.state.timescale() as f64,
 					segment.duration() as f64 / self.state.timescale() as f64,
-					false,
+					segment.discontinuity,
+					finished,
 				)
 				.context("recording")?;
 		}
 
-		self.uploader
-			.try_send(TrackTask::Media {
-				part_idx,
-				data: part.data.clone(),
-			})
-			.context("send media task")?;
+		if let Some(uploader) = &self.uploader {
+			uploader
+				.try_send(TrackTask::Media {
+					part_idx,
+					data: part.data.clone(),
+				})
+				.context("send media task")?;
+		}
 
 		Ok(part.duration as f64 / self.state.timescale() as f64)
 	}
 
-	pub fn apply_manifest(&mut self, manifest: LiveRenditionManifest) {
-		self.state.apply_manifest(&manifest);
+	pub fn apply_manifest(&mut self, manifest: LiveRenditionManifest, discontinuity: bool) {
+		self.state.apply_manifest(&manifest, discontinuity);
 		self.previous_segments = manifest.segments;
 	}
 
@@ -172,9 +214,9 @@ impl Track {
 		info_map: &HashMap<String, RenditionInfo>,
 		shutdown: bool,
 	) -> anyhow::Result<()> {
-		if !self.ready {
+		let (true, Some(uploader)) = (self.ready, &self.uploader) else {
 			return Ok(());
-		}
+		};
 
 		let completed = self.state.complete() && shutdown;
 
@@ -184,6 +226,7 @@ impl Track {
 			completed,
 			timescale: self.state.timescale(),
 			total_duration: self.state.total_duration(),
+			discontinuity_sequence: self.state.discontinuity_sequence(),
 			recording_data: if let Some(recording) = &recording {
 				if recording.allow_dvr() {
 					Some(RecordingData {
@@ -202,6 +245,7 @@ impl Track {
 				.map(|s| live_rendition_manifest::Segment {
 					idx: s.idx,
 					id: Some(s.id.into()),
+					discontinuity: s.discontinuity,
 					parts: s
 						.parts
 						.iter()
@@ -225,7 +269,7 @@ impl Track {
 
 		let data = Bytes::from(manifest.encode_to_vec());
 
-		self.uploader
+		uploader
 			.try_send(TrackTask::Manifest { data })
 			.context("send manifest task")?;
 
@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use bytes::Bytes;
 use bytesio::bytes_writer::BytesWriter;
+use mp4::types::emsg::Emsg;
 use mp4::types::ftyp::{FourCC, Ftyp};
 use mp4::types::mdat::Mdat;
 use mp4::types::mfhd::Mfhd;
@@ -46,6 +47,7 @@ pub struct Segment {
 	pub parts: Vec<Part>,
 	pub idx: u32,
 	pub id: Ulid,
+	pub discontinuity: bool,
 }
 
 impl Segment {
@@ -62,9 +64,14 @@ impl Segment {
 pub struct TrackState {
 	samples: VecDeque<TrackSample>,
 
+	// Timed metadata waiting to be written with the next part.
+	events: Vec<Emsg>,
+
 	timescale: u32,
 
 	segments: VecDeque<Segment>,
+	// The number of discontinuities which were removed from the segment list.
+	discontinuity_sequence: u32,
 
 	init_segment: Option<Bytes>,
 
@@ -83,6 +90,7 @@ impl std::fmt::Debug for TrackState {
 		f.debug_struct("TrackState")
 			.field("timescale", &self.timescale)
 			.field("segments", &self.segments)
+			.field("discontinuity_sequence", &self.discontinuity_sequence)
 			.field("total_duration", &self.total_duration)
 			.field("next_part_idx", &self.next_part_idx)
 			.field("next_segment_idx", &self.next_segment_idx)
@@ -106,6 +114,10 @@ impl TrackState {
 		self.segments.iter()
 	}
 
+	pub fn discontinuity_sequence(&self) -> u32 {
+		self.discontinuity_sequence
+	}
+
 	pub fn next_part_idx(&self) -> u32 {
 		self.next_part_idx
 	}
@@ -122,7 +134,9 @@ impl TrackState {
 		self.last_independent_part_idx
 	}
 
-	pub fn apply_manifest(&mut self, manifest: &LiveRenditionManifest) {
+	/// Continues the track from the manifest of a previous transcoder, if
+	/// `discontinuity` is set the next segment starts after a discontinuity.
+	pub fn apply_manifest(&mut self, manifest: &LiveRenditionManifest, discontinuity: bool) {
 		let Some(info) = manifest.info.as_ref() else {
 			return;
 		};
@@ -133,6 +147,7 @@ impl TrackState {
 		self.timescale = manifest.timescale;
 		self.next_segment_part_idx = info.next_segment_part_idx;
 		self.last_independent_part_idx = info.last_independent_part_idx;
+		self.discontinuity_sequence = manifest.discontinuity_sequence;
 
 		let mut segments = manifest
 			.segments
@@ -151,6 +166,7 @@ impl TrackState {
 					})
 					.collect(),
 				id: s.id.into_ulid(),
+				discontinuity: s.discontinuity,
 			})
 			.collect::<Vec<_>>();
 
@@ -162,6 +178,7 @@ impl TrackState {
 			idx: self.next_segment_idx,
 			parts: vec![],
 			id: Ulid::new(),
+			discontinuity,
 		});
 
 		self.next_segment_idx += 1;
@@ -181,9 +198,13 @@ impl TrackState {
 	}
 
 	pub fn retain_segments(&mut self, count: usize) -> Vec<Segment> {
-		(0..self.segments.len().saturating_sub(count))
+		let removed = (0..self.segments.len().saturating_sub(count))
 			.filter_map(|_| self.segments.pop_front())
-			.collect()
+			.collect::<Vec<_>>();
+
+		self.discontinuity_sequence += removed.iter().filter(|s| s.discontinuity).count() as u32;
+
+		removed
 	}
 
 	pub fn last_segment_duration(&self) -> u32 {
@@ -254,6 +275,7 @@ impl TrackState {
 				parts: vec![part],
 				idx: self.next_segment_idx,
 				id: Ulid::new(),
+				discontinuity: false,
 			});
 			self.next_segment_idx += 1;
 			self.next_segment_idx - 1
@@ -289,6 +311,12 @@ impl TrackState {
 		let mdat = Mdat::new(samples.into_iter().map(|s| s.data).collect::<Vec<_>>());
 
 		let mut writer = BytesWriter::default();
+		// Event message boxes must come before the moof they apply to, and are
+		// stamped in the timescale of the track they are written into.
+		for mut event in self.events.drain(..) {
+			event.rescale(self.timescale);
+			event.mux(&mut writer).unwrap();
+		}
 		moof.mux(&mut writer).unwrap();
 		mdat.mux(&mut writer).unwrap();
 
@@ -315,6 +343,10 @@ impl TrackState {
 		self.samples.extend(samples);
 	}
 
+	pub fn append_event(&mut self, event: Emsg) {
+		self.events.push(event);
+	}
+
 	pub fn init_segment(&self) -> Option<&Bytes> {
 		self.init_segment.as_ref()
 	}
@@ -374,6 +406,7 @@ impl TrackState {
 				parts: vec![],
 				idx: self.next_segment_idx,
 				id: Ulid::new(),
+				discontinuity: false,
 			});
 			self.next_segment_idx += 1;
 			self.next_segment_part_idx = 0;
@@ -401,6 +434,7 @@ impl TrackState {
 						parts: vec![],
 						idx: self.next_segment_idx,
 						id: Ulid::new(),
+						discontinuity: false,
 					});
 					self.next_segment_idx += 1;
 					self.next_segment_part_idx = 0;
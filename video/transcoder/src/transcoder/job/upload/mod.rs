@@ -0,0 +1,814 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::io::Seek;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use async_nats::jetstream::{AckKind, Message};
+use aws_config::Region;
+use aws_sdk_s3::config::Credentials;
+use bytes::Bytes;
+use pb::ext::UlidExt;
+use pb::scuffle::video::internal::events::RecordingUploadTask;
+use pb::scuffle::video::v1::events_fetch_request::Target;
+use pb::scuffle::video::v1::types::{event, AudioConfig, Rendition as PbRendition, TranscodingConfig, VideoConfig};
+use prost::Message as _;
+use tokio::io::AsyncWriteExt;
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
+use utils::task::AsyncTask;
+use video_common::database::{Rendition, S3Bucket, Visibility};
+
+use self::probe::Probe;
+use self::range_reader::RangeReader;
+use super::captions::{Caption, CaptionTrack};
+use super::ffmpeg::{Transcoder, TranscoderInput};
+use super::overlay::LoadedOverlay;
+use super::recording::Recording;
+use super::renditions::determine_output_renditions;
+use super::sprite::Sprite;
+use super::sql_operations::default_transcoding_config;
+use super::task::track_parser::track_parser_task;
+use super::track::parser::{TrackOut, TrackParser};
+use super::track::Track;
+use super::{overlay, screenshot};
+use crate::global::TranscoderGlobal;
+use crate::transcoder::capacity::{self, Capacity};
+
+pub(crate) mod probe;
+pub(crate) mod range_reader;
+
+/// The message is redelivered to another transcoder if it is not acked within
+/// the ack wait of the consumer, which is shorter than most uploads take.
+const ACK_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long an upload which failed to start waits before it is tried again.
+const RETRY_DELAY: Duration = Duration::from_secs(15);
+
+/// How long after it was queued an upload which failed to start is still tried
+/// again. The deliveries which did not fit in the budget of a transcoder are
+/// not attempts, so the age of the message is counted instead.
+const MAX_RETRY_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Why an upload did not finish.
+pub(crate) enum UploadError {
+	/// The upload failed before it was transcoded, such as when S3, the
+	/// database or nats are unavailable, or the transcoder shut down while
+	/// transcoding it, and is tried again.
+	Retry(anyhow::Error),
+	/// The upload is not tried again, the file would fail the same way.
+	Failed(anyhow::Error),
+}
+
+impl UploadError {
+	fn into_inner(self) -> anyhow::Error {
+		match self {
+			Self::Retry(err) | Self::Failed(err) => err,
+		}
+	}
+}
+
+impl From<anyhow::Error> for UploadError {
+	fn from(err: anyhow::Error) -> Self {
+		Self::Failed(err)
+	}
+}
+
+pub async fn handle_upload_message<G: TranscoderGlobal>(
+	global: Arc<G>,
+	msg: Message,
+	capacity: Arc<Capacity>,
+	shutdown_token: CancellationToken,
+) {
+	let task = match RecordingUploadTask::decode(msg.payload.clone()) {
+		Ok(task) => task,
+		Err(err) => {
+			tracing::error!(error = %err, "failed to decode upload task");
+			msg.ack_with(AckKind::Term).await.ok();
+			return;
+		}
+	};
+
+	let organization_id = task.organization_id.into_ulid();
+	let recording_id = task.recording_id.into_ulid();
+
+	tracing::info!(%organization_id, %recording_id, "got new upload");
+
+	let result = in_progress(&msg, transcode_upload(&global, &msg, &capacity, &task, &shutdown_token)).await;
+
+	let event = match result {
+		// The upload did not fit in the budget and is left to another transcoder
+		Ok(false) => return,
+		Ok(true) => {
+			if let Err(err) = msg.double_ack().await {
+				tracing::error!(error = %err, "failed to ACK message");
+			}
+
+			// The uploaded file is kept until the recording is finished, so that a
+			// failed upload can be retried with the same file
+			if task.delete_source {
+				if let Err(err) = delete_source(&global, &task).await {
+					tracing::error!(error = %err, %recording_id, "failed to delete uploaded file");
+				}
+			}
+
+			tracing::info!(%organization_id, %recording_id, "upload finished");
+
+			event::recording::Event::Finished(event::recording::Finished {})
+		}
+		// An upload cut short by a shutdown is tried again however old it is
+		Err(UploadError::Retry(err)) if shutdown_token.is_cancelled() || can_retry(&msg) => {
+			tracing::warn!(error = %err, %recording_id, "upload did not finish, retrying");
+
+			msg.ack_with(AckKind::Nak(Some(RETRY_DELAY))).await.ok();
+
+			return;
+		}
+		Err(err) => {
+			let err = err.into_inner();
+
+			// A file which failed to transcode will fail again, so it is not redelivered
+			msg.ack_with(AckKind::Term).await.ok();
+
+			tracing::error!(error = %err, %recording_id, "failed to transcode upload");
+
+			event::recording::Event::Failed(event::recording::Failed { error: err.to_string() })
+		}
+	};
+
+	emit(&global, organization_id, recording_id, event).await;
+}
+
+fn can_retry(msg: &Message) -> bool {
+	msg.info().is_ok_and(|info| {
+		SystemTime::from(info.published)
+			.elapsed()
+			.map_or(true, |age| age < MAX_RETRY_AGE)
+	})
+}
+
+/// Runs the future until the transcoder shuts down. The upload is then put
+/// back on the queue, so that another transcoder picks it up.
+pub(crate) async fn until_shutdown<T>(
+	shutdown_token: &CancellationToken,
+	fut: impl Future<Output = Result<T, UploadError>>,
+) -> Result<T, UploadError> {
+	select! {
+		r = fut => r,
+		_ = shutdown_token.cancelled() => Err(UploadError::Retry(anyhow::anyhow!("transcoder shut down"))),
+	}
+}
+
+/// Keeps the message from being redelivered while the future runs.
+async fn in_progress<T>(msg: &Message, fut: impl Future<Output = T>) -> T {
+	let mut fut = pin!(fut);
+	let mut ack_timer = tokio::time::interval(ACK_PROGRESS_INTERVAL);
+
+	loop {
+		select! {
+			r = &mut fut => return r,
+			_ = ack_timer.tick() => {
+				if let Err(err) = msg.ack_with(AckKind::Progress).await {
+					tracing::warn!(error = %err, "failed to mark upload in progress");
+				}
+			}
+		}
+	}
+}
+
+async fn emit(
+	global: &Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	recording_id: Ulid,
+	event: event::recording::Event,
+) {
+	video_common::events::emit(
+		global.nats(),
+		&global.config().events_stream_name,
+		organization_id,
+		Target::Recording,
+		event::Event::Recording(event::Recording {
+			recording_id: Some(recording_id.into()),
+			event: Some(event),
+		}),
+	)
+	.await;
+}
+
+/// Returns false if the upload was put back on the queue.
+async fn transcode_upload(
+	global: &Arc<impl TranscoderGlobal>,
+	msg: &Message,
+	capacity: &Arc<Capacity>,
+	task: &RecordingUploadTask,
+	shutdown_token: &CancellationToken,
+) -> Result<bool, UploadError> {
+	let organization_id = task.organization_id.into_ulid();
+	let recording_id = task.recording_id.into_ulid();
+
+	let mut recording_config = task.recording_config.clone().context("missing recording config")?;
+
+	// The api creates the recording before it queues the upload, but the
+	// transaction may not be visible to us yet
+	let visibility: Visibility =
+		utils::database::query("SELECT visibility FROM recordings WHERE organization_id = $1 AND id = $2")
+			.bind(organization_id)
+			.bind(recording_id)
+			.build_query_single_scalar()
+			.fetch_optional(global.db())
+			.await
+			.context("failed to query recording")
+			.and_then(|visibility| visibility.context("recording not found"))
+			.map_err(UploadError::Retry)?;
+
+	let source_bucket = query_s3_bucket(global, organization_id, task.s3_bucket_id.into_ulid()).await?;
+	let probe = probe_source(&source_bucket, &task.key).await?;
+
+	let mut transcoding_config = task.transcoding_config.clone().unwrap_or_else(default_transcoding_config);
+
+	// Renditions the recording does not keep would only be thrown away
+	transcoding_config
+		.renditions
+		.retain(|r| recording_config.renditions.contains(r));
+
+	// The source renditions are copies of the file, which players can only play if
+	// it is h264 and aac
+	if probe.video_input.codec.is_empty() {
+		transcoding_config
+			.renditions
+			.retain(|r| *r != PbRendition::VideoSource as i32);
+	}
+
+	if probe.audio_input.as_ref().map_or(true, |audio| audio.codec.is_empty()) {
+		transcoding_config
+			.renditions
+			.retain(|r| *r != PbRendition::AudioSource as i32);
+	}
+
+	let (video_output, mut audio_output) = determine_output_renditions(
+		&probe.video_input,
+		probe.audio_input.as_ref().unwrap_or(&AudioConfig::default()),
+		&transcoding_config,
+	);
+
+	// A silent file only has video renditions
+	if probe.audio_input.is_none() {
+		audio_output.clear();
+	}
+
+	if video_output.is_empty() {
+		return Err(anyhow::anyhow!("no video renditions can be transcoded from the file").into());
+	}
+
+	// Files are transcoded as fast as the transcoder allows, the cost is that of
+	// transcoding them in real time like a room
+	let cost = capacity::estimate_cost(&global.config().capacity, &probe.video_input, &video_output);
+
+	let Some(_guard) = capacity::admit(global, capacity, msg, cost).await else {
+		return Ok(false);
+	};
+
+	let mut file = download(&source_bucket, &task.key).await.map_err(UploadError::Retry)?;
+
+	tracing::info!(
+		%organization_id,
+		%recording_id,
+		duration = probe.duration,
+		renditions = video_output.len() + audio_output.len(),
+		cost,
+		"transcoding upload",
+	);
+
+	let recording_bucket = query_s3_bucket(global, organization_id, recording_config.s3_bucket_id.into_ulid()).await?;
+
+	// The uploaded file is the source, it is not captured again
+	recording_config.capture_source = false;
+
+	// The renditions are inserted if they do not exist yet, so this can be retried
+	let recording = async {
+		let mut client = global.db().get().await.context("failed to get database connection")?;
+		let tx = client.transaction().await.context("failed to start transaction")?;
+
+		// An upload cut short by a shutdown starts over, without what it had already
+		// transcoded
+		for table in [
+			"recording_rendition_segments",
+			"recording_thumbnails",
+			"recording_sprites",
+			"recording_caption_segments",
+		] {
+			utils::database::query(format!(
+				"DELETE FROM {table} WHERE organization_id = $1 AND recording_id = $2"
+			))
+			.bind(organization_id)
+			.bind(recording_id)
+			.build()
+			.execute(&tx)
+			.await
+			.with_context(|| format!("failed to clear {table}"))?;
+		}
+
+		let recording = Recording::new(
+			global,
+			&tx,
+			recording_id,
+			organization_id,
+			None,
+			Ulid::nil(),
+			visibility,
+			&audio_output,
+			&video_output,
+			&recording_bucket,
+			&recording_config,
+		)
+		.await?;
+
+		tx.commit().await.context("failed to commit transaction")?;
+
+		anyhow::Ok(recording)
+	}
+	.await
+	.map_err(UploadError::Retry)?;
+
+	emit(
+		global,
+		organization_id,
+		recording_id,
+		event::recording::Event::Started(event::recording::Started {
+			room_id: None,
+			recording_config_id: recording_config.id,
+		}),
+	)
+	.await;
+
+	let overlays = overlay::load_overlays(global, organization_id, &transcoding_config.overlays).await;
+
+	file.rewind().context("failed to rewind file")?;
+
+	let mut upload = Upload::new(
+		global,
+		file,
+		recording,
+		video_output,
+		audio_output,
+		overlays,
+		transcoding_config,
+		probe.duration,
+	);
+
+	until_shutdown(shutdown_token, async {
+		upload
+			.run(global, organization_id, recording_id)
+			.await
+			.map_err(UploadError::from)
+	})
+	.await?;
+	upload.finish().await?;
+
+	utils::database::query(
+		"UPDATE recordings SET ended_at = NOW(), updated_at = NOW() WHERE organization_id = $1 AND id = $2",
+	)
+	.bind(organization_id)
+	.bind(recording_id)
+	.build()
+	.execute(global.db())
+	.await
+	.context("failed to end recording")?;
+
+	Ok(true)
+}
+
+async fn query_s3_bucket(
+	global: &Arc<impl TranscoderGlobal>,
+	organization_id: Ulid,
+	id: Ulid,
+) -> Result<S3Bucket, UploadError> {
+	let s3_bucket: Option<S3Bucket> =
+		utils::database::query("SELECT * FROM s3_buckets WHERE organization_id = $1 AND id = $2")
+			.bind(organization_id)
+			.bind(id)
+			.build_query_as()
+			.fetch_optional(global.db())
+			.await
+			.context("failed to query s3 bucket")
+			.map_err(UploadError::Retry)?;
+
+	Ok(s3_bucket.context("s3 bucket not found")?)
+}
+
+fn bucket(s3_bucket: &S3Bucket) -> binary_helper::s3::Bucket {
+	binary_helper::s3::Bucket::new(
+		s3_bucket.name.clone(),
+		Credentials::from_keys(&s3_bucket.access_key_id, &s3_bucket.secret_access_key, None),
+		Region::new(s3_bucket.region.clone()),
+		s3_bucket.endpoint.clone(),
+	)
+}
+
+/// Probes the uploaded file through range requests, so that the upload can be
+/// admitted before the file is downloaded.
+async fn probe_source(s3_bucket: &S3Bucket, key: &str) -> Result<Probe, UploadError> {
+	let bucket = bucket(s3_bucket);
+
+	let head = bucket
+		.head_object(key)
+		.await
+		.context("failed to get uploaded file")
+		.map_err(UploadError::Retry)?;
+
+	let size = head.content_length().unwrap_or_default().max(0) as u64;
+	let runtime = tokio::runtime::Handle::current();
+	let key = key.to_string();
+
+	let (probe, error) = tokio::task::spawn_blocking(move || {
+		let mut reader = RangeReader::new(size, |range| {
+			runtime.block_on(async {
+				let object = bucket
+					.get_object_range(&key, range)
+					.await
+					.context("failed to get uploaded file")?;
+
+				let body = object.body.collect().await.context("failed to read uploaded file")?;
+
+				anyhow::Ok(body.into_bytes())
+			})
+		});
+
+		let probe = probe::probe(&mut reader);
+		(probe, reader.take_error())
+	})
+	.await
+	.context("probe panicked")?;
+
+	// The file is not at fault if it could not be read
+	if let Some(err) = error {
+		return Err(UploadError::Retry(err));
+	}
+
+	Ok(probe?)
+}
+
+/// The file is downloaded before it is transcoded, since most containers need
+/// to be seeked to be demuxed.
+async fn download(s3_bucket: &S3Bucket, key: &str) -> anyhow::Result<File> {
+	let object = bucket(s3_bucket)
+		.get_object(key)
+		.await
+		.context("failed to get uploaded file")?;
+
+	let file = tempfile::tempfile().context("failed to create temporary file")?;
+	let mut writer = tokio::fs::File::from_std(file.try_clone().context("failed to clone file")?);
+
+	let mut body = object.body;
+	while let Some(chunk) = body.try_next().await.context("failed to download uploaded file")? {
+		writer.write_all(&chunk).await.context("failed to write file")?;
+	}
+
+	writer.flush().await.context("failed to write file")?;
+
+	Ok(file)
+}
+
+async fn delete_source(global: &Arc<impl TranscoderGlobal>, task: &RecordingUploadTask) -> anyhow::Result<()> {
+	let s3_bucket = query_s3_bucket(global, task.organization_id.into_ulid(), task.s3_bucket_id.into_ulid())
+		.await
+		.map_err(UploadError::into_inner)?;
+
+	bucket(&s3_bucket)
+		.delete_object(&task.key)
+		.await
+		.context("failed to delete object")?;
+
+	Ok(())
+}
+
+struct Upload {
+	recording: Recording,
+
+	tracks: HashMap<Rendition, Track>,
+	ready: bool,
+
+	ffmpeg_recv: mpsc::Receiver<(Rendition, TrackOut)>,
+	screenshot_recv: mpsc::Receiver<(Bytes, f64)>,
+	sprite_recv: mpsc::Receiver<Sprite>,
+	caption_recv: mpsc::Receiver<Caption>,
+
+	// The recording uploaders, which run until the recording is finished.
+	tasks: Vec<AsyncTask<anyhow::Result<()>>>,
+	// ffmpeg and the tasks fed by it, which finish with the file.
+	transcode_tasks: Vec<AsyncTask<anyhow::Result<()>>>,
+
+	screenshot_idx: u32,
+	screenshot_interval: f64,
+	sprite_idx: u32,
+
+	// Only set if the transcoding config extracts captions.
+	captions: Option<CaptionTrack>,
+	// The captions are cut along the segments of this rendition.
+	caption_rendition: Option<Rendition>,
+	last_caption_idx: Option<u32>,
+
+	// The duration of the file in seconds.
+	duration: f64,
+	progress_step: u32,
+}
+
+impl Upload {
+	#[allow(clippy::too_many_arguments)]
+	fn new(
+		global: &Arc<impl TranscoderGlobal>,
+		file: File,
+		mut recording: Recording,
+		video_output: Vec<VideoConfig>,
+		audio_output: Vec<AudioConfig>,
+		overlays: Vec<LoadedOverlay>,
+		transcoding_config: TranscodingConfig,
+		duration: f64,
+	) -> Self {
+		let renditions = video_output
+			.iter()
+			.map(|r| Rendition::from(r.rendition()))
+			.chain(audio_output.iter().map(|r| Rendition::from(r.rendition())))
+			.collect::<Vec<_>>();
+
+		let tasks = recording.tasks();
+		let mut transcode_tasks = Vec::new();
+
+		let (track_parser, ffmpeg_recv) = mpsc::channel(renditions.len());
+		let mut ffmpeg_outputs = HashMap::new();
+
+		transcode_tasks.extend(renditions.iter().copied().map(|rendition| {
+			let (tx, rx) = mpsc::channel(1);
+			ffmpeg_outputs.insert(rendition, tx);
+
+			let tp = TrackParser::new(rx);
+			AsyncTask::spawn(
+				format!("track_parser({rendition})"),
+				track_parser_task(tp, rendition, track_parser.clone()),
+			)
+		}));
+
+		// The segments of an upload are only written to the recording
+		let tracks = renditions
+			.iter()
+			.map(|rendition| (*rendition, Track::new(global, *rendition, None)))
+			.collect();
+
+		let (frame_send, frame_recv) = mpsc::channel(1);
+		// The loudness of an upload is not reported on a room
+		let (loudness_send, _) = mpsc::channel(1);
+		let (caption_send, caption_recv) = mpsc::channel(16);
+		let captions = transcoding_config.captions;
+		transcode_tasks.push(AsyncTask::spawn_blocking("ffmpeg", {
+			let global = global.clone();
+
+			let video_configs = video_output.clone();
+			let audio_configs = audio_output;
+			let loudness_normalization = transcoding_config.loudness_normalization;
+			let content_aware_encoding = transcoding_config.content_aware_encoding;
+
+			move || {
+				Transcoder::new(
+					&global,
+					TranscoderInput::File(file),
+					frame_send,
+					loudness_send,
+					caption_send,
+					ffmpeg_outputs,
+					video_configs,
+					audio_configs,
+					overlays,
+					loudness_normalization,
+					content_aware_encoding,
+					captions,
+				)?
+				.run()
+			}
+		}));
+
+		let sprite_config = global.config().sprite.clone();
+
+		let (screenshot_send, screenshot_recv) = mpsc::channel(16);
+		let (sprite_send, sprite_recv) = mpsc::channel(16);
+		transcode_tasks.push(AsyncTask::spawn_blocking("screenshot", || {
+			screenshot::screenshot_task(frame_recv, screenshot_send, sprite_config, sprite_send)
+		}));
+
+		Self {
+			recording,
+			tracks,
+			ready: false,
+			ffmpeg_recv,
+			screenshot_recv,
+			sprite_recv,
+			caption_recv,
+			tasks,
+			transcode_tasks,
+			screenshot_idx: 0,
+			screenshot_interval: global.config().screenshot_interval.as_secs_f64(),
+			sprite_idx: 0,
+			captions: captions.then(CaptionTrack::default),
+			caption_rendition: video_output.iter().map(|c| Rendition::from(c.rendition())).min(),
+			last_caption_idx: None,
+			duration,
+			progress_step: 0,
+		}
+	}
+
+	async fn run(
+		&mut self,
+		global: &Arc<impl TranscoderGlobal>,
+		organization_id: Ulid,
+		recording_id: Ulid,
+	) -> anyhow::Result<()> {
+		let mut ffmpeg_done = false;
+		let mut screenshots_done = false;
+		let mut sprites_done = false;
+
+		// The screenshots of the last frames come in after ffmpeg is done
+		while !ffmpeg_done || !screenshots_done || !sprites_done {
+			// The file is transcoded faster than realtime, so ffmpeg has to wait for the
+			// uploads to keep up
+			self.recording.ready_for_upload().await?;
+
+			select! {
+				r = self.ffmpeg_recv.recv(), if !ffmpeg_done => {
+					let Some((rendition, track_out)) = r else {
+						ffmpeg_done = true;
+						continue;
+					};
+
+					self.handle_track(rendition, track_out)?;
+
+					if let Some(progress) = self.progress() {
+						emit(
+							global,
+							organization_id,
+							recording_id,
+							event::recording::Event::Progress(event::recording::Progress { progress }),
+						)
+						.await;
+					}
+				},
+				r = self.screenshot_recv.recv(), if !screenshots_done => {
+					let Some((data, time)) = r else {
+						screenshots_done = true;
+						continue;
+					};
+
+					self.screenshot_idx += 1;
+					self.recording.upload_thumbnail(self.screenshot_idx, time, data)?;
+				},
+				r = self.sprite_recv.recv(), if !sprites_done => {
+					let Some(sprite) = r else {
+						sprites_done = true;
+						continue;
+					};
+
+					self.handle_sprite(sprite)?;
+				},
+				Some(caption) = self.caption_recv.recv() => {
+					if let Some(captions) = &mut self.captions {
+						captions.push(caption);
+					}
+				},
+			}
+
+			self.check_tasks()?;
+		}
+
+		Ok(())
+	}
+
+	fn check_tasks(&mut self) -> anyhow::Result<()> {
+		if let Some(task) = self.tasks.iter_mut().find(|task| task.is_finished()) {
+			anyhow::bail!("task exited early: {}", task.tag());
+		}
+
+		Ok(())
+	}
+
+	fn handle_track(&mut self, rendition: Rendition, track_out: TrackOut) -> anyhow::Result<()> {
+		let track = self.tracks.get_mut(&rendition).unwrap();
+
+		let has_additions = track.handle_track_out(Some(&mut self.recording), track_out)?;
+
+		self.put_init_segments()?;
+
+		if has_additions && self.caption_rendition == Some(rendition) {
+			self.cut_captions()?;
+		}
+
+		Ok(())
+	}
+
+	fn put_init_segments(&mut self) -> anyhow::Result<()> {
+		if self.ready || self.tracks.values().any(|track| track.init_segment().is_none()) {
+			return Ok(());
+		}
+
+		self.ready = true;
+
+		for (rendition, track) in self.tracks.iter_mut() {
+			self.recording
+				.upload_init(*rendition, track.init_segment().unwrap().clone())?;
+			track.ready(Some(&mut self.recording))?;
+		}
+
+		Ok(())
+	}
+
+	fn handle_sprite(&mut self, sprite: Sprite) -> anyhow::Result<()> {
+		// The sheet is sent again for every tile, the first tile starts a new one.
+		if sprite.tile_times.len() == 1 {
+			self.sprite_idx += 1;
+		}
+
+		let end_time = sprite.tile_times.last().copied().unwrap_or_default() + self.screenshot_interval;
+
+		self.recording.upload_sprite(self.sprite_idx, &sprite, end_time)
+	}
+
+	/// Cuts the captions of the segments the caption rendition completed since
+	/// the last call.
+	fn cut_captions(&mut self) -> anyhow::Result<()> {
+		let (Some(captions), Some(track)) = (
+			&mut self.captions,
+			self.caption_rendition.and_then(|rendition| self.tracks.get(&rendition)),
+		) else {
+			return Ok(());
+		};
+
+		while let Ok(caption) = self.caption_recv.try_recv() {
+			captions.push(caption);
+		}
+
+		let segments = track
+			.completed_segments()
+			.filter(|(idx, _, _)| !self.last_caption_idx.is_some_and(|last| *idx <= last))
+			.collect::<Vec<_>>();
+
+		for (idx, start_time, end_time) in segments {
+			let data = captions.cut(start_time, end_time);
+			self.recording.upload_captions(idx, Ulid::new(), start_time, end_time, data)?;
+			self.last_caption_idx = Some(idx);
+		}
+
+		Ok(())
+	}
+
+	/// The progress if it passed the next tenth of the file, the renditions are
+	/// only as far as the slowest of them.
+	fn progress(&mut self) -> Option<f32> {
+		if self.duration <= 0.0 {
+			return None;
+		}
+
+		let transcoded = self.tracks.values().map(Track::duration).fold(f64::INFINITY, f64::min);
+		let step = ((transcoded / self.duration).clamp(0.0, 1.0) * 10.0) as u32;
+
+		// The end of the file is reported by the finished event
+		if step <= self.progress_step || step >= 10 {
+			return None;
+		}
+
+		self.progress_step = step;
+
+		Some(step as f32 / 10.0)
+	}
+
+	async fn finish(mut self) -> anyhow::Result<()> {
+		for mut task in self.transcode_tasks.drain(..) {
+			task.join()
+				.await
+				.with_context(|| format!("{}: panic'd", task.tag()))?
+				.with_context(|| format!("{}: ", task.tag()))?;
+		}
+
+		self.recording.ready_for_upload().await?;
+
+		self.tracks
+			.values_mut()
+			.try_for_each(|track| track.finish(Some(&mut self.recording)))?;
+
+		self.cut_captions()?;
+		self.recording.finish_sprite()?;
+
+		// Close the uploaders so that they can finish their tasks
+		drop(self.recording);
+
+		for mut task in self.tasks.drain(..) {
+			task.join()
+				.await
+				.with_context(|| format!("{}: panic'd", task.tag()))?
+				.with_context(|| format!("{}: ", task.tag()))?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,130 @@
+use std::io::{Read, Seek};
+
+use anyhow::Context;
+use bytes::Bytes;
+use ffmpeg::error::FfmpegError;
+use ffmpeg::ffi::{AVCodecID, AVCodecParameters, AVMediaType, AVRational};
+use mp4::codec::{AudioCodec, VideoCodec};
+use pb::scuffle::video::v1::types::{AudioConfig, Rendition, VideoConfig};
+
+/// The input of an uploaded file, as ingest describes the input of a room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+	/// The codec is empty if the source cannot be passed through.
+	pub video_input: VideoConfig,
+	/// The codec is empty if the source cannot be passed through, and there is
+	/// no audio input if the file is silent.
+	pub audio_input: Option<AudioConfig>,
+	/// The duration of the file in seconds.
+	pub duration: f64,
+}
+
+pub fn probe(mut input: impl Read + Seek + Send + Sync) -> anyhow::Result<Probe> {
+	input.rewind().context("failed to rewind file")?;
+
+	let input = ffmpeg::io::Input::seekable(input).context("failed to open file")?;
+
+	let video_stream = input
+		.streams()
+		.best(AVMediaType::AVMEDIA_TYPE_VIDEO)
+		.ok_or(FfmpegError::NoStream)
+		.context("file has no video stream")?;
+
+	let video_params = video_stream
+		.codec_parameters()
+		.ok_or(FfmpegError::NoStream)
+		.context("video stream has no codec parameters")?;
+
+	let fps = [video_stream.avg_frame_rate(), video_stream.r_frame_rate()]
+		.into_iter()
+		.find(|rate| rate.num > 0 && rate.den > 0)
+		.map(|rate| (rate.num as f64 / rate.den as f64).round() as i32)
+		.unwrap_or(30);
+
+	let video_input = VideoConfig {
+		rendition: Rendition::VideoSource.into(),
+		codec: video_codec(video_params).map(|c| c.to_string()).unwrap_or_default(),
+		bitrate: video_params.bit_rate,
+		width: video_params.width,
+		height: video_params.height,
+		fps,
+		name: String::new(),
+	};
+
+	let audio_input = match input.streams().best(AVMediaType::AVMEDIA_TYPE_AUDIO) {
+		Some(audio_stream) => {
+			let audio_params = audio_stream
+				.codec_parameters()
+				.ok_or(FfmpegError::NoStream)
+				.context("audio stream has no codec parameters")?;
+
+			Some(AudioConfig {
+				rendition: Rendition::AudioSource.into(),
+				codec: audio_codec(audio_params).map(|c| c.to_string()).unwrap_or_default(),
+				bitrate: audio_params.bit_rate,
+				channels: audio_params.ch_layout.nb_channels,
+				sample_rate: audio_params.sample_rate,
+			})
+		}
+		None => None,
+	};
+
+	let duration = video_stream
+		.duration()
+		.map(|duration| seconds(duration, video_stream.time_base()))
+		.or_else(|| {
+			// The container duration is in microseconds
+			let duration = video_stream.format_context().duration;
+			(duration > 0).then(|| duration as f64 / 1_000_000.0)
+		})
+		.unwrap_or_default();
+
+	Ok(Probe {
+		video_input,
+		audio_input,
+		duration,
+	})
+}
+
+fn seconds(ts: i64, time_base: AVRational) -> f64 {
+	ts as f64 * time_base.num as f64 / time_base.den.max(1) as f64
+}
+
+fn extradata(params: &AVCodecParameters) -> &[u8] {
+	if params.extradata.is_null() || params.extradata_size <= 0 {
+		return &[];
+	}
+
+	// Safety: the extradata is owned by the codec parameters and is
+	// extradata_size bytes long.
+	unsafe { std::slice::from_raw_parts(params.extradata, params.extradata_size as usize) }
+}
+
+/// Only h264 in an avcC record can be passed through, which is what the mp4
+/// muxer of the renditions expects.
+fn video_codec(params: &AVCodecParameters) -> Option<VideoCodec> {
+	if params.codec_id != AVCodecID::AV_CODEC_ID_H264 {
+		return None;
+	}
+
+	match extradata(params) {
+		[1, profile, constraint_set, level, ..] => Some(VideoCodec::Avc {
+			profile: *profile,
+			constraint_set: *constraint_set,
+			level: *level,
+		}),
+		_ => None,
+	}
+}
+
+fn audio_codec(params: &AVCodecParameters) -> Option<AudioCodec> {
+	if params.codec_id != AVCodecID::AV_CODEC_ID_AAC {
+		return None;
+	}
+
+	let config = aac::AudioSpecificConfig::parse(Bytes::copy_from_slice(extradata(params))).ok()?;
+
+	Some(AudioCodec::Aac {
+		object_type: config.audio_object_type,
+	})
+}
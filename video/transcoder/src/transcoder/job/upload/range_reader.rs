@@ -0,0 +1,86 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::RangeInclusive;
+
+use bytes::Bytes;
+
+/// The size of the range requests, ffmpeg reads in much smaller pieces.
+const CHUNK_SIZE: u64 = 256 * 1024;
+
+/// Reads a file through range requests, so that it can be probed without
+/// downloading it. Only the chunks ffmpeg reads are fetched, which for most
+/// containers is little more than their header.
+pub struct RangeReader<F> {
+	fetch: F,
+	size: u64,
+	pos: u64,
+
+	chunk: Bytes,
+	chunk_offset: u64,
+
+	// ffmpeg only sees that a read failed, not why.
+	error: Option<anyhow::Error>,
+}
+
+impl<F: FnMut(RangeInclusive<u64>) -> anyhow::Result<Bytes>> RangeReader<F> {
+	pub fn new(size: u64, fetch: F) -> Self {
+		Self {
+			fetch,
+			size,
+			pos: 0,
+			chunk: Bytes::new(),
+			chunk_offset: 0,
+			error: None,
+		}
+	}
+
+	/// Returns the error of the last range request which failed.
+	pub fn take_error(&mut self) -> Option<anyhow::Error> {
+		self.error.take()
+	}
+}
+
+impl<F: FnMut(RangeInclusive<u64>) -> anyhow::Result<Bytes>> Read for RangeReader<F> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if buf.is_empty() || self.pos >= self.size {
+			return Ok(0);
+		}
+
+		if self.pos < self.chunk_offset || self.pos >= self.chunk_offset + self.chunk.len() as u64 {
+			let end = self.pos.saturating_add(CHUNK_SIZE).min(self.size) - 1;
+
+			match (self.fetch)(self.pos..=end) {
+				Ok(chunk) if !chunk.is_empty() => {
+					self.chunk = chunk;
+					self.chunk_offset = self.pos;
+				}
+				Ok(_) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "range request returned no data")),
+				Err(err) => {
+					self.error = Some(err);
+					return Err(io::Error::other("range request failed"));
+				}
+			}
+		}
+
+		let start = (self.pos - self.chunk_offset) as usize;
+		let len = buf.len().min(self.chunk.len() - start);
+
+		buf[..len].copy_from_slice(&self.chunk[start..start + len]);
+		self.pos += len as u64;
+
+		Ok(len)
+	}
+}
+
+impl<F> Seek for RangeReader<F> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let pos = match pos {
+			SeekFrom::Start(pos) => Some(pos),
+			SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+			SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+		};
+
+		self.pos = pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative position"))?;
+
+		Ok(self.pos)
+	}
+}
@@ -5,16 +5,26 @@ use anyhow::{bail, Result};
 use async_nats::jetstream::consumer::pull::Config;
 use async_nats::jetstream::consumer::DeliverPolicy;
 use async_nats::jetstream::stream::RetentionPolicy;
+use async_nats::jetstream::Message;
 use futures::StreamExt;
 use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
 use utils::context::ContextExt;
+use utils::task::AsyncTask;
 
 use crate::config::TranscoderConfig;
 use crate::global::TranscoderGlobal;
-use crate::transcoder::job::handle_message;
+use crate::transcoder::capacity::Capacity;
+use crate::transcoder::job::{handle_message, handle_upload_message};
 
+pub(crate) mod capacity;
 pub(crate) mod job;
 
+enum Task {
+	Transcode(Message),
+	Upload(Message),
+}
+
 pub async fn run<G: TranscoderGlobal>(global: Arc<G>) -> Result<()> {
 	let config = global.config::<TranscoderConfig>();
 
@@ -30,20 +40,63 @@ pub async fn run<G: TranscoderGlobal>(global: Arc<G>) -> Result<()> {
 		})
 		.await?;
 
+	// Messages are put back on the queue for as long as they do not fit in the
+	// budget of any transcoder, so they are redelivered until they are too old for
+	// their stream.
 	let consumer = stream
 		.get_or_create_consumer(
 			"transcoder",
 			Config {
 				name: Some("transcoder".to_string()),
 				filter_subject: config.transcoder_request_subject.clone(),
-				max_deliver: 3,
+				max_deliver: -1,
 				deliver_policy: DeliverPolicy::All,
 				..Default::default()
 			},
 		)
 		.await?;
 
-	let mut messages = consumer.messages().await?;
+	// Uploads take as long to transcode as their files are long, so they are kept
+	// until a transcoder is free to take them.
+	let upload_stream = global
+		.jetstream()
+		.get_or_create_stream(async_nats::jetstream::stream::Config {
+			name: config.recording_upload_subject.clone(),
+			max_age: Duration::from_secs(60 * 60 * 24), // 1 day max age
+			retention: RetentionPolicy::WorkQueue,
+			subjects: vec![config.recording_upload_subject.clone()],
+			storage: async_nats::jetstream::stream::StorageType::File,
+			..Default::default()
+		})
+		.await?;
+
+	let upload_consumer = upload_stream
+		.get_or_create_consumer(
+			"transcoder_uploads",
+			Config {
+				name: Some("transcoder_uploads".to_string()),
+				filter_subject: config.recording_upload_subject.clone(),
+				max_deliver: -1,
+				deliver_policy: DeliverPolicy::All,
+				..Default::default()
+			},
+		)
+		.await?;
+
+	let mut messages = futures::stream::select(
+		consumer.messages().await?.map(|m| m.map(Task::Transcode)),
+		upload_consumer.messages().await?.map(|m| m.map(Task::Upload)),
+	);
+
+	let transcoder_id = Ulid::new();
+	let capacity = Capacity::new(config.capacity.budget);
+
+	tracing::info!(%transcoder_id, budget = config.capacity.budget, "starting transcoder");
+
+	let _load_reporter = AsyncTask::spawn(
+		"load_reporter",
+		capacity::report_load(global.clone(), transcoder_id, capacity.clone()),
+	);
 
 	let shutdown_token = CancellationToken::new();
 	let child_token = shutdown_token.child_token();
@@ -61,11 +114,20 @@ pub async fn run<G: TranscoderGlobal>(global: Arc<G>) -> Result<()> {
 			}
 		};
 
-		tokio::spawn(handle_message(global.clone(), m, child_token.clone()));
+		match m {
+			Task::Transcode(m) => tokio::spawn(handle_message(global.clone(), m, capacity.clone(), child_token.clone())),
+			Task::Upload(m) => tokio::spawn(handle_upload_message(
+				global.clone(),
+				m,
+				capacity.clone(),
+				child_token.clone(),
+			)),
+		};
 	}
 
 	drop(messages);
 	drop(consumer);
+	drop(upload_consumer);
 
 	tokio::time::sleep(Duration::from_millis(100)).await;
 